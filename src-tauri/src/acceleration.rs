@@ -0,0 +1,167 @@
+use crate::app_config::load_config;
+use crate::defaults::DEFAULT_ACCELERATION_PROVIDER;
+use once_cell::sync::Lazy;
+use ort::ep::{DirectML, ExecutionProvider, ExecutionProviderDispatch, CUDA};
+use serde::Serialize;
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Accelerator {
+    Cpu,
+    Cuda,
+    #[serde(rename = "directml")]
+    DirectMl,
+}
+
+const GPU_ACCELERATORS: [Accelerator; 2] = [Accelerator::Cuda, Accelerator::DirectMl];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccelerationStatus {
+    pub model: &'static str,
+    pub requested: String,
+    pub active: Accelerator,
+    pub available: Vec<Accelerator>,
+    pub fallback_reason: Option<String>,
+}
+
+static STATUS: Lazy<Mutex<Vec<AccelerationStatus>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+// `auto` tries CUDA, then DirectML; ort falls back to the CPU provider when none registers.
+pub fn requested_accelerators(provider: &str) -> Result<Vec<Accelerator>, String> {
+    match provider.trim().to_lowercase().as_str() {
+        "" | "auto" => Ok(GPU_ACCELERATORS.to_vec()),
+        "cpu" => Ok(Vec::new()),
+        "cuda" => Ok(vec![Accelerator::Cuda]),
+        "directml" | "dml" => Ok(vec![Accelerator::DirectMl]),
+        other => Err(format!(
+            "unknown execution provider: {other} (expected auto, cpu, cuda or directml)"
+        )),
+    }
+}
+
+fn resolve_active(requested: &[Accelerator], available: &[Accelerator]) -> Accelerator {
+    requested
+        .iter()
+        .copied()
+        .find(|accelerator| available.contains(accelerator))
+        .unwrap_or(Accelerator::Cpu)
+}
+
+// Providers for one ONNX session, in priority order; an empty list means CPU only.
+pub struct ProviderSelection {
+    model: &'static str,
+    requested: String,
+    available: Vec<Accelerator>,
+    active: Accelerator,
+    pub providers: Vec<ExecutionProviderDispatch>,
+}
+
+impl ProviderSelection {
+    pub fn for_model(model: &'static str) -> Self {
+        let config = load_config()
+            .ok()
+            .and_then(|cfg| cfg.acceleration)
+            .unwrap_or_default();
+        let requested = config
+            .provider
+            .unwrap_or_else(|| DEFAULT_ACCELERATION_PROVIDER.to_string());
+        let wanted = requested_accelerators(&requested).unwrap_or_else(|err| {
+            warn!("{err}; using cpu");
+            Vec::new()
+        });
+        let device_id = config.device_id.unwrap_or(0);
+        let available = GPU_ACCELERATORS
+            .into_iter()
+            .filter(|accelerator| is_available(*accelerator, device_id))
+            .collect::<Vec<_>>();
+        let active = resolve_active(&wanted, &available);
+        let providers = wanted
+            .iter()
+            .filter(|accelerator| available.contains(accelerator))
+            .filter_map(|accelerator| dispatch(*accelerator, device_id))
+            .collect();
+        Self {
+            model,
+            requested,
+            available,
+            active,
+            providers,
+        }
+    }
+
+    pub fn uses_gpu(&self) -> bool {
+        !self.providers.is_empty()
+    }
+
+    // Call once the session is built; `cpu_fallback` carries the GPU error when the caller had to
+    // retry on CPU.
+    pub fn record(self, cpu_fallback: Option<String>) {
+        let active = if cpu_fallback.is_some() {
+            Accelerator::Cpu
+        } else {
+            self.active
+        };
+        info!(
+            "[acceleration] model={} requested={} active={:?}",
+            self.model, self.requested, active
+        );
+        let status = AccelerationStatus {
+            model: self.model,
+            requested: self.requested,
+            active,
+            available: self.available,
+            fallback_reason: cpu_fallback,
+        };
+        if let Ok(mut guard) = STATUS.lock() {
+            guard.retain(|item| item.model != status.model);
+            guard.push(status);
+        }
+    }
+}
+
+// The CPU provider is always appended by ort itself.
+fn dispatch(accelerator: Accelerator, device_id: i32) -> Option<ExecutionProviderDispatch> {
+    match accelerator {
+        Accelerator::Cuda => Some(CUDA::default().with_device_id(device_id).build()),
+        Accelerator::DirectMl => Some(DirectML::default().with_device_id(device_id).build()),
+        Accelerator::Cpu => None,
+    }
+}
+
+fn is_available(accelerator: Accelerator, device_id: i32) -> bool {
+    let result = match accelerator {
+        Accelerator::Cuda => CUDA::default().with_device_id(device_id).is_available(),
+        Accelerator::DirectMl => DirectML::default().with_device_id(device_id).is_available(),
+        Accelerator::Cpu => Ok(true),
+    };
+    result.unwrap_or(false)
+}
+
+#[tauri::command]
+pub fn get_acceleration_status() -> Vec<AccelerationStatus> {
+    STATUS.lock().map(|guard| guard.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{requested_accelerators, resolve_active, Accelerator};
+
+    #[test]
+    fn falls_back_to_cpu_when_nothing_requested_is_available() {
+        let auto = requested_accelerators("Auto").unwrap();
+        assert_eq!(resolve_active(&auto, &[]), Accelerator::Cpu);
+        assert_eq!(
+            resolve_active(&auto, &[Accelerator::DirectMl]),
+            Accelerator::DirectMl
+        );
+        let cuda = requested_accelerators("cuda").unwrap();
+        assert_eq!(
+            resolve_active(&cuda, &[Accelerator::DirectMl]),
+            Accelerator::Cpu
+        );
+        assert!(requested_accelerators("cpu").unwrap().is_empty());
+        assert!(requested_accelerators("tpu").is_err());
+    }
+}
@@ -1,10 +1,29 @@
-use serde::Deserialize;
+use crate::acceleration::requested_accelerators;
+use crate::audio::queue::OverflowPolicy;
+use crate::config_migration::{migrate_config, MigrationOutcome, CURRENT_CONFIG_VERSION};
+use crate::defaults::{
+    apply_env_overrides, DEFAULT_ASR_LIVE_PROVIDER, DEFAULT_ASR_TRANSLATE_TO_ENGLISH,
+    DEFAULT_DEEPGRAM_DIARIZE, DEFAULT_DEEPGRAM_MODEL, DEFAULT_LOCAL_GPT_BASE_URL,
+    DEFAULT_LOCAL_GPT_TIMEOUT, DEFAULT_OFFLINE_FALLBACK_ENABLED, DEFAULT_OFFLINE_FALLBACK_MODEL,
+    DEFAULT_OLLAMA_BASE_URL, DEFAULT_OLLAMA_MODEL, DEFAULT_OLLAMA_TIMEOUT,
+    DEFAULT_REALTIME_TRANSCRIBE_MODEL, DEFAULT_TRANSLATE_PROVIDER,
+    DEFAULT_TRANSLATE_TARGET_LANGUAGE, DEFAULT_TWO_PASS_ENABLED, DEFAULT_TWO_PASS_MODEL,
+    DEFAULT_TWO_PASS_RETRANSLATE, DEFAULT_VAD_MODEL_PATH, DEFAULT_VAD_THRESHOLD,
+    DEFAULT_WHISPER_CONTEXT_BOUNDARY_GAP_MS, DEFAULT_WHISPER_CONTEXT_ENABLED,
+    DEFAULT_WHISPER_CONTEXT_MAX_CHARS, DEFAULT_WHISPER_CONTEXT_RESET_SILENCE_MS,
+    DEFAULT_WHISPER_CONTEXT_SHORT_SEGMENT_MS,
+};
+use crate::logging::normalize_level;
+use crate::redact::{self, is_valid_pattern};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 const CONFIG_FILE: &str = "ai-interview.config";
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenAiConfig {
     pub api_key: String,
@@ -19,7 +38,7 @@ pub struct OpenAiConfig {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OllamaConfig {
     pub enabled: Option<bool>,
@@ -29,7 +48,7 @@ pub struct OllamaConfig {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalGptConfig {
     pub enabled: Option<bool>,
@@ -39,32 +58,101 @@ pub struct LocalGptConfig {
     pub project_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeConfig {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    // Full messages endpoint, like `openai.chatBaseUrl`.
+    pub base_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiConfig {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    // API root; the model and method are appended.
+    pub base_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    #[allow(dead_code)]
+    #[serde(default, alias = "configVersion")]
+    pub config_version: u64,
     pub openai: OpenAiConfig,
     #[allow(dead_code)]
     pub ollama: Option<OllamaConfig>,
     #[allow(dead_code)]
     #[serde(alias = "localGpt", alias = "local-gpt")]
     pub local_gpt: Option<LocalGptConfig>,
+    pub claude: Option<ClaudeConfig>,
+    pub gemini: Option<GeminiConfig>,
     pub translate: Option<TranslateConfig>,
     pub speaker: Option<SpeakerConfig>,
     pub asr: Option<AsrConfig>,
+    pub remote: Option<RemoteConfig>,
+    pub webhooks: Option<Vec<WebhookConfig>>,
+    pub calendar: Option<CalendarConfig>,
+    pub schedule: Option<ScheduleConfig>,
+    pub mqtt: Option<MqttConfig>,
+    pub share: Option<ShareConfig>,
+    pub logging: Option<LoggingConfig>,
+    pub network: Option<NetworkConfig>,
+    pub queues: Option<QueuesConfig>,
+    pub acceleration: Option<AccelerationConfig>,
+    pub rag: Option<RagConfig>,
+    pub notifications: Option<NotificationsConfig>,
+    pub updates: Option<UpdatesConfig>,
+    pub time: Option<TimeConfig>,
+    pub pii: Option<PiiConfig>,
+    #[serde(alias = "wordFilter", alias = "word-filter")]
+    pub word_filter: Option<WordFilterConfig>,
+    pub consent: Option<ConsentConfig>,
+    pub templates: Option<Vec<MeetingTemplateConfig>>,
+    #[serde(alias = "autoStop", alias = "auto-stop")]
+    pub auto_stop: Option<AutoStopConfig>,
+    pub interpreter: Option<InterpreterConfig>,
+    pub punctuation: Option<PunctuationConfig>,
+    pub usage: Option<UsageConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslateConfig {
     pub enabled: Option<bool>,
     pub provider: Option<String>,
     pub target_language: Option<String>,
     pub segment_batch_size: Option<usize>,
+    // Streams segment translations with the single prompt, trading transcript cleanup for
+    // progressive display.
+    pub stream_segments: Option<bool>,
     pub segment_single_prompt: Option<String>,
     pub segment_batch_prompt: Option<String>,
     pub live_prompt: Option<String>,
+    // Persistent cache of finished translations, see `translation_cache`.
+    pub cache_enabled: Option<bool>,
+    pub cache_size: Option<usize>,
+    // Tried in order when the provider errors or times out on a segment batch.
+    pub fallback_providers: Option<Vec<String>>,
+    // Per diarized speaker; unlisted speakers use the settings above.
+    pub speakers: Option<Vec<SpeakerTranslationConfig>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerTranslationConfig {
+    pub speaker_id: u32,
+    // `false` leaves the speaker's segments in their source language.
+    pub translate: Option<bool>,
+    pub target_language: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SpeakerConfig {
     pub enabled: Option<bool>,
@@ -79,7 +167,7 @@ pub struct SpeakerConfig {
     pub min_rms_db: Option<f32>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AsrConfig {
     pub provider: Option<String>,
@@ -90,11 +178,24 @@ pub struct AsrConfig {
     pub whisper_server_device: Option<String>,
     pub whisper_server_url: Option<String>,
     pub whisper_server_timeout_secs: Option<u64>,
+    // `-t` for whisper-server; unset uses a table by physical core count.
+    pub whisper_server_threads: Option<usize>,
+    // When the first-run benchmark wrote `whisperServerDevice` and `whisperServerThreads`.
+    pub whisper_server_benchmarked_at: Option<String>,
+    pub whisper_pipe_path: Option<String>,
+    pub whisper_pipe_workers: Option<usize>,
     pub language: Option<String>,
+    // Speech in `language` comes out as English text: OpenAI's translations endpoint, or
+    // whisper.cpp's translate flag. whisper-pipe and Deepgram still transcribe.
+    pub translate_to_english: Option<bool>,
     pub fallback_to_openai: Option<bool>,
+    // Used when whisper-server cannot start and OpenAI is not a fallback: `offlineFallbackModel`
+    // (a model manager name or ggml path) runs in process on the CPU.
+    pub offline_fallback: Option<bool>,
+    pub offline_fallback_model: Option<String>,
     pub use_whisper_vad: Option<bool>,
-    pub whisper_cpp_vad_path: Option<String>,
-    pub whisper_cpp_vad_model_path: Option<String>,
+    pub vad_model_path: Option<String>,
+    pub vad_threshold: Option<f32>,
     pub use_whisper_stream: Option<bool>,
     pub whisper_cpp_stream_path: Option<String>,
     pub whisper_cpp_stream_step_ms: Option<u64>,
@@ -103,6 +204,288 @@ pub struct AsrConfig {
     pub whisper_context_short_segment_ms: Option<u64>,
     pub whisper_context_boundary_gap_ms: Option<u64>,
     pub whisper_context_reset_silence_ms: Option<u64>,
+    // Where `download_whisper_model` fetches `ggml-<name>.bin`, e.g. a Hugging Face mirror.
+    pub whisper_model_base_url: Option<String>,
+    pub deepgram_api_key: Option<String>,
+    pub deepgram_model: Option<String>,
+    pub deepgram_base_url: Option<String>,
+    // Labels each word with a speaker; the local speaker model still wins where it runs.
+    pub deepgram_diarize: Option<bool>,
+    // `window` uploads the rolling window on every step; `openai-realtime` streams capture audio
+    // to the OpenAI realtime transcription API instead.
+    pub live_provider: Option<String>,
+    pub realtime_model: Option<String>,
+    pub realtime_url: Option<String>,
+    // Re-runs every transcribed segment on `twoPassModel` once the draft is shown.
+    pub two_pass_enabled: Option<bool>,
+    // Empty or `local` for a whisper-pipe worker kept loaded; `openai` or `deepgram` for a cloud
+    // pass.
+    pub two_pass_provider: Option<String>,
+    // A model name from the model manager, a ggml path, or the cloud model name.
+    pub two_pass_model: Option<String>,
+    // Translates the segment again when the accurate transcript replaces the draft.
+    pub two_pass_retranslate: Option<bool>,
+    // Spoken numbers to numerals, per language: `{"en": true, "ja": true}`.
+    pub itn: Option<HashMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteConfig {
+    pub enabled: Option<bool>,
+    pub bind_address: Option<String>,
+    pub port: Option<u16>,
+    pub token: Option<String>,
+    pub api_enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarConfig {
+    pub enabled: Option<bool>,
+    pub ics_url: Option<String>,
+    pub ics_path: Option<String>,
+    pub early_minutes: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConfig {
+    pub enabled: Option<bool>,
+    // Starts capture when a scheduled meeting begins; otherwise only the event is sent.
+    pub auto_start: Option<bool>,
+    // Stops a capture the scheduler started once its meeting ends.
+    pub auto_stop: Option<bool>,
+    // Events of the `calendar` ICS count as scheduled meetings too.
+    pub use_calendar: Option<bool>,
+    pub entries: Option<Vec<ScheduleEntry>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    pub title: Option<String>,
+    // `mon` .. `sun`; empty or missing means every day.
+    pub days: Option<Vec<String>>,
+    // Local `HH:MM`.
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttConfig {
+    pub enabled: Option<bool>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub client_id: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub topic_prefix: Option<String>,
+    pub topics: Option<HashMap<String, String>>,
+    pub qos: Option<u8>,
+    pub retain: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareConfig {
+    pub slack_webhook_url: Option<String>,
+    pub discord_webhook_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggingConfig {
+    pub level: Option<String>,
+    pub modules: Option<HashMap<String, String>>,
+    pub max_files: Option<usize>,
+    // Extra regexes masked in logs and UI events, on top of the built-in key and token rules.
+    pub redact_patterns: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConfig {
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+// Finalized segments are never dropped, so `transcribe` and `vad` always block and their
+// `overflow` is ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuesConfig {
+    pub transcribe: Option<QueueConfig>,
+    pub vad: Option<QueueConfig>,
+    pub translate: Option<QueueConfig>,
+    pub window: Option<QueueConfig>,
+    // Second-pass transcriptions waiting for the accurate model.
+    pub refine: Option<QueueConfig>,
+    // A worker busy with one item for longer than this is restarted and the item re-queued.
+    pub stuck_worker_secs: Option<u64>,
+    // Live windows are skipped while this many finalized segments wait for transcription.
+    pub window_shed_depth: Option<usize>,
+}
+
+// `overflow` is one of dropOldest, coalesce or block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueConfig {
+    pub capacity: Option<usize>,
+    pub overflow: Option<String>,
+}
+
+// ONNX execution provider for the embedding and speaker models: auto, cpu, cuda or directml.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccelerationConfig {
+    pub provider: Option<String>,
+    pub device_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RagConfig {
+    // Load the embedding model in the background at startup instead of on the first search.
+    pub warm_up: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsConfig {
+    pub enabled: Option<bool>,
+    // Skip the toast while the main window has focus; the UI already shows the result there.
+    pub only_when_unfocused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatesConfig {
+    pub enabled: Option<bool>,
+    // A JSON release document: `{ "version", "notes", "url" }` or a GitHub releases response.
+    pub feed_url: Option<String>,
+    pub check_interval_hours: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeConfig {
+    // `local`, `utc`, `original` (the offset the time was recorded with) or a fixed `+09:00`.
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    // Show recent times as "2 分钟前" in the UI; exports always use absolute dates.
+    pub relative: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiConfig {
+    pub enabled: Option<bool>,
+    pub emails: Option<bool>,
+    pub phones: Option<bool>,
+    // Always masked, e.g. participants and customer names.
+    pub names: Option<Vec<String>>,
+    pub patterns: Option<Vec<String>>,
+    // Also ask the selected LLM provider for names in each transcript.
+    pub llm: Option<bool>,
+    // Replaces the `[email]` / `[phone]` / `[name]` labels when set.
+    pub mask: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordFilterConfig {
+    pub enabled: Option<bool>,
+    // `mask` stars each character; `replace` swaps in `replacements[word]` or `replacement`.
+    pub mode: Option<String>,
+    pub replacement: Option<String>,
+    // Keyed by language (`en`, `zh`, `ja`, ...); `*` applies to every language.
+    pub words: Option<HashMap<String, Vec<String>>>,
+    pub replacements: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunctuationConfig {
+    pub enabled: Option<bool>,
+    // `off`, `rules` or `llm`, for languages without an entry in `languages`.
+    pub mode: Option<String>,
+    // Per-language mode, keyed like `asr.language` (`ja`, `zh`, `en`, ...).
+    pub languages: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageConfig {
+    // Keyed `provider:model` or just `provider`; adds to and overrides `DEFAULT_TOKEN_PRICES`.
+    pub prices: Option<HashMap<String, TokenPrice>>,
+}
+
+// US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentConfig {
+    // `none`, `beep` or `speech`, played when capture starts.
+    pub announcement: Option<String>,
+    pub speech_text: Option<String>,
+    // Shows a recording badge in the output window while capturing.
+    pub indicator: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStopConfig {
+    pub enabled: Option<bool>,
+    // Minutes without speech before capture stops on its own.
+    pub silence_minutes: Option<u64>,
+    // Summarizes the session after an automatic stop.
+    pub summarize: Option<bool>,
+}
+
+// Low-latency preset switched on at runtime with `set_interpreter_mode`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterpreterConfig {
+    // Segments are cut at this length even while the speaker keeps talking.
+    pub max_segment_ms: Option<u64>,
+    // Used only when the file exists; otherwise the regular model stays loaded.
+    pub whisper_model_path: Option<String>,
+    // Replaces a selected provider that cannot stream live translations.
+    pub translate_provider: Option<String>,
+}
+
+// A meeting template; an entry whose `id` matches a built-in one replaces it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingTemplateConfig {
+    pub id: String,
+    pub name: Option<String>,
+    // Layered over `translate` while the template's session runs.
+    pub target_language: Option<String>,
+    pub live_prompt: Option<String>,
+    pub segment_batch_prompt: Option<String>,
+    // Summary structure, added to the summary prompt.
+    pub summary_prompt: Option<String>,
+    pub action_items: Option<bool>,
+    // Summarizes the session as soon as it ends.
+    pub auto_summarize: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub enabled: Option<bool>,
+    pub url: String,
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
 }
 
 impl Default for AsrConfig {
@@ -116,29 +499,604 @@ impl Default for AsrConfig {
             whisper_server_device: Some("auto".to_string()),
             whisper_server_url: None,
             whisper_server_timeout_secs: None,
+            whisper_server_threads: None,
+            whisper_server_benchmarked_at: None,
+            whisper_pipe_path: None,
+            whisper_pipe_workers: None,
             language: Some("ja".to_string()),
+            translate_to_english: Some(DEFAULT_ASR_TRANSLATE_TO_ENGLISH),
             fallback_to_openai: Some(true),
+            offline_fallback: Some(DEFAULT_OFFLINE_FALLBACK_ENABLED),
+            offline_fallback_model: Some(DEFAULT_OFFLINE_FALLBACK_MODEL.to_string()),
             use_whisper_vad: Some(false),
-            whisper_cpp_vad_path: Some("whisper-vad-speech-segments.exe".to_string()),
-            whisper_cpp_vad_model_path: None,
+            vad_model_path: Some(DEFAULT_VAD_MODEL_PATH.to_string()),
+            vad_threshold: Some(DEFAULT_VAD_THRESHOLD),
             use_whisper_stream: Some(false),
             whisper_cpp_stream_path: Some("whisper-stream.exe".to_string()),
             whisper_cpp_stream_step_ms: Some(1000),
-            whisper_context_enabled: Some(true),
-            whisper_context_max_chars: Some(100),
-            whisper_context_short_segment_ms: Some(2500),
-            whisper_context_boundary_gap_ms: Some(1200),
-            whisper_context_reset_silence_ms: Some(4000),
+            whisper_context_enabled: Some(DEFAULT_WHISPER_CONTEXT_ENABLED),
+            whisper_context_max_chars: Some(DEFAULT_WHISPER_CONTEXT_MAX_CHARS),
+            whisper_context_short_segment_ms: Some(DEFAULT_WHISPER_CONTEXT_SHORT_SEGMENT_MS),
+            whisper_context_boundary_gap_ms: Some(DEFAULT_WHISPER_CONTEXT_BOUNDARY_GAP_MS),
+            whisper_context_reset_silence_ms: Some(DEFAULT_WHISPER_CONTEXT_RESET_SILENCE_MS),
+            whisper_model_base_url: None,
+            deepgram_api_key: None,
+            deepgram_model: Some(DEFAULT_DEEPGRAM_MODEL.to_string()),
+            deepgram_base_url: None,
+            deepgram_diarize: Some(DEFAULT_DEEPGRAM_DIARIZE),
+            live_provider: Some(DEFAULT_ASR_LIVE_PROVIDER.to_string()),
+            realtime_model: Some(DEFAULT_REALTIME_TRANSCRIBE_MODEL.to_string()),
+            realtime_url: None,
+            two_pass_enabled: Some(DEFAULT_TWO_PASS_ENABLED),
+            two_pass_provider: None,
+            two_pass_model: Some(DEFAULT_TWO_PASS_MODEL.to_string()),
+            two_pass_retranslate: Some(DEFAULT_TWO_PASS_RETRANSLATE),
+            itn: None,
+        }
+    }
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Some(true),
+            model: Some(DEFAULT_OLLAMA_MODEL.to_string()),
+            base_url: Some(DEFAULT_OLLAMA_BASE_URL.to_string()),
+            timeout_secs: Some(DEFAULT_OLLAMA_TIMEOUT),
+        }
+    }
+}
+
+impl Default for LocalGptConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Some(true),
+            base_url: Some(DEFAULT_LOCAL_GPT_BASE_URL.to_string()),
+            timeout_secs: Some(DEFAULT_LOCAL_GPT_TIMEOUT),
+            project_id: None,
+        }
+    }
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Some(true),
+            provider: Some(DEFAULT_TRANSLATE_PROVIDER.to_string()),
+            target_language: Some(DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string()),
+            segment_batch_size: None,
+            stream_segments: None,
+            segment_single_prompt: None,
+            segment_batch_prompt: None,
+            live_prompt: None,
+            cache_enabled: None,
+            cache_size: None,
+            fallback_providers: None,
+            speakers: None,
         }
     }
 }
 
 pub fn load_config() -> Result<AppConfig, String> {
+    let (path, mut value) = load_config_value()?;
+    apply_env_overrides(&mut value);
+    let mut config = serde_json::from_value(value)
+        .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+    crate::templates::apply_active(&mut config);
+    crate::interpreter::apply(&mut config);
+    Ok(config)
+}
+
+pub fn load_file_config() -> Result<AppConfig, String> {
+    let (path, value) = load_config_value()?;
+    serde_json::from_value(value).map_err(|err| format!("invalid config {}: {err}", path.display()))
+}
+
+pub fn load_config_value() -> Result<(PathBuf, serde_json::Value), String> {
     let path = find_config_path()?;
     let content = fs::read_to_string(&path)
         .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
-    serde_json::from_str(&content)
-        .map_err(|err| format!("invalid config {}: {err}", path.display()))
+    let mut value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+    let outcome = migrate_config(&mut value)
+        .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+    if let Some(outcome) = outcome {
+        if let Err(err) = write_migrated_config(&path, &content, &value, &outcome) {
+            warn!("config migration not saved: {err}");
+        }
+    }
+    Ok((path, value))
+}
+
+pub fn save_config_value(value: &serde_json::Value) -> Result<PathBuf, String> {
+    let config = serde_json::from_value::<AppConfig>(value.clone())
+        .map_err(|err| format!("invalid config: {err}"))?;
+    validate_config(&config)?;
+    let mut value = value.clone();
+    if let Some(root) = value.as_object_mut() {
+        root.insert(
+            "config_version".to_string(),
+            serde_json::Value::from(CURRENT_CONFIG_VERSION),
+        );
+    }
+    let path = find_config_path().or_else(|_| {
+        config_candidates()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "no config location available".to_string())
+    })?;
+    let content = serde_json::to_string_pretty(&value).map_err(|err| err.to_string())?;
+    fs::write(&path, content)
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    redact::configure(&config);
+    Ok(path)
+}
+
+pub fn save_config(config: &AppConfig) -> Result<PathBuf, String> {
+    let serde_json::Value::Object(sections) =
+        serde_json::to_value(config).map_err(|err| err.to_string())?
+    else {
+        return Err("config must serialize to an object".to_string());
+    };
+    // Known sections are replaced wholesale; unknown top-level keys are kept as-is.
+    let mut value = load_config_value()
+        .map(|(_, value)| value)
+        .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| "config root must be a JSON object".to_string())?;
+    for (key, mut section) in sections {
+        if section.is_null() {
+            root.remove(&key);
+        } else {
+            strip_nulls(&mut section);
+            root.insert(key, section);
+        }
+    }
+    save_config_value(&value)
+}
+
+fn is_translate_provider(provider: &str) -> bool {
+    matches!(
+        provider.trim().to_lowercase().as_str(),
+        "openai"
+            | "chatgpt"
+            | "ollama"
+            | "local-gpt"
+            | "local_gpt"
+            | "localgpt"
+            | "claude"
+            | "anthropic"
+            | "gemini"
+            | "google"
+    )
+}
+
+pub fn validate_config(config: &AppConfig) -> Result<(), String> {
+    if let Some(translate) = &config.translate {
+        if let Some(provider) = &translate.provider {
+            if !is_translate_provider(provider) {
+                return Err(format!(
+                    "translate.provider: unsupported provider {provider}"
+                ));
+            }
+        }
+        for provider in translate.fallback_providers.iter().flatten() {
+            if !is_translate_provider(provider) {
+                return Err(format!(
+                    "translate.fallbackProviders: unsupported provider {provider}"
+                ));
+            }
+        }
+        if translate.segment_batch_size == Some(0) {
+            return Err("translate.segmentBatchSize must be greater than 0".to_string());
+        }
+        if translate.cache_size == Some(0) {
+            return Err("translate.cacheSize must be greater than 0".to_string());
+        }
+    }
+    if let Some(speaker) = &config.speaker {
+        for (name, value) in [
+            ("similarityThreshold", speaker.similarity_threshold),
+            ("updateThreshold", speaker.update_threshold),
+        ] {
+            if let Some(value) = value {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(format!("speaker.{name} must be between 0 and 1"));
+                }
+            }
+        }
+        if speaker.max_speakers == Some(0) {
+            return Err("speaker.maxSpeakers must be greater than 0".to_string());
+        }
+    }
+    if let Some(asr) = &config.asr {
+        if let Some(provider) = &asr.provider {
+            if !matches!(
+                provider.trim().to_lowercase().as_str(),
+                "whisperserver"
+                    | "whisper-server"
+                    | "whisper_server"
+                    | "server"
+                    | "whisperpipe"
+                    | "whisper-pipe"
+                    | "whisper_pipe"
+                    | "pipe"
+                    | "whisperinproc"
+                    | "whisper-inproc"
+                    | "whisper_inproc"
+                    | "inproc"
+                    | "deepgram"
+                    | "openai"
+            ) {
+                return Err(format!("asr.provider: unsupported provider {provider}"));
+            }
+        }
+        if let Some(device) = &asr.whisper_server_device {
+            if !matches!(
+                device.trim().to_lowercase().as_str(),
+                "auto" | "gpu" | "cpu"
+            ) {
+                return Err(format!(
+                    "asr.whisperServerDevice: unsupported device {device}"
+                ));
+            }
+        }
+        if let Some(live_provider) = &asr.live_provider {
+            if !matches!(
+                live_provider.trim().to_lowercase().as_str(),
+                "window" | "openai-realtime"
+            ) {
+                return Err(format!(
+                    "asr.liveProvider: unsupported provider {live_provider}"
+                ));
+            }
+        }
+        if let Some(provider) = &asr.two_pass_provider {
+            if !matches!(
+                provider.trim().to_lowercase().as_str(),
+                "" | "local" | "openai" | "deepgram"
+            ) {
+                return Err(format!(
+                    "asr.twoPassProvider: unsupported provider {provider}"
+                ));
+            }
+        }
+        if asr.whisper_server_threads == Some(0) {
+            return Err("asr.whisperServerThreads must be greater than 0".to_string());
+        }
+        if asr.whisper_pipe_workers == Some(0) {
+            return Err("asr.whisperPipeWorkers must be greater than 0".to_string());
+        }
+        if let Some(threshold) = asr.vad_threshold {
+            if !(threshold > 0.0 && threshold < 1.0) {
+                return Err("asr.vadThreshold must be between 0 and 1".to_string());
+            }
+        }
+    }
+    if let Some(remote) = &config.remote {
+        if remote.port == Some(0) {
+            return Err("remote.port must be greater than 0".to_string());
+        }
+        if let Some(address) = &remote.bind_address {
+            address
+                .parse::<std::net::IpAddr>()
+                .map_err(|_| format!("remote.bindAddress: invalid address {address}"))?;
+        }
+    }
+    for (index, hook) in config.webhooks.iter().flatten().enumerate() {
+        let url = url::Url::parse(hook.url.trim())
+            .map_err(|err| format!("webhooks[{index}].url: {err}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("webhooks[{index}].url must be http or https"));
+        }
+        for event in hook.events.iter().flatten() {
+            if !crate::webhooks::is_webhook_event(event) {
+                return Err(format!("webhooks[{index}].events: unknown event {event}"));
+            }
+        }
+    }
+    if let Some(url) = config
+        .calendar
+        .as_ref()
+        .and_then(|calendar| calendar.ics_url.as_deref())
+        .filter(|value| !value.trim().is_empty())
+    {
+        let url = url::Url::parse(url.trim()).map_err(|err| format!("calendar.icsUrl: {err}"))?;
+        if !matches!(url.scheme(), "http" | "https" | "webcal") {
+            return Err("calendar.icsUrl must be http, https or webcal".to_string());
+        }
+    }
+    for (index, entry) in config
+        .schedule
+        .iter()
+        .flat_map(|schedule| schedule.entries.iter().flatten())
+        .enumerate()
+    {
+        crate::scheduler::validate_entry(entry)
+            .map_err(|err| format!("schedule.entries[{index}]: {err}"))?;
+    }
+    if let Some(updates) = &config.updates {
+        if let Some(url) = updates
+            .feed_url
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+        {
+            let url =
+                url::Url::parse(url.trim()).map_err(|err| format!("updates.feedUrl: {err}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err("updates.feedUrl must be http or https".to_string());
+            }
+        }
+        if updates.check_interval_hours == Some(0) {
+            return Err("updates.checkIntervalHours must be greater than 0".to_string());
+        }
+    }
+    if let Some(timezone) = config
+        .time
+        .as_ref()
+        .and_then(|time| time.timezone.as_deref())
+    {
+        if !crate::timestamps::is_valid_timezone(timezone) {
+            return Err(format!("time.timezone: unsupported timezone {timezone}"));
+        }
+    }
+    for pattern in config
+        .pii
+        .as_ref()
+        .and_then(|pii| pii.patterns.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        if !is_valid_pattern(pattern) {
+            return Err(format!("pii.patterns has an invalid pattern: {pattern}"));
+        }
+    }
+    if let Some(mode) = config
+        .word_filter
+        .as_ref()
+        .and_then(|word_filter| word_filter.mode.as_deref())
+    {
+        if !crate::word_filter::is_valid_mode(mode) {
+            return Err(format!(
+                "wordFilter.mode must be mask or replace, got {mode}"
+            ));
+        }
+    }
+    if let Some(punctuation) = config.punctuation.as_ref() {
+        for (key, mode) in punctuation
+            .mode
+            .iter()
+            .map(|mode| ("mode".to_string(), mode))
+            .chain(
+                punctuation
+                    .languages
+                    .iter()
+                    .flatten()
+                    .map(|(language, mode)| (format!("languages.{language}"), mode)),
+            )
+        {
+            if !crate::punctuation::is_valid_mode(mode) {
+                return Err(format!(
+                    "punctuation.{key} must be off, rules or llm, got {mode}"
+                ));
+            }
+        }
+    }
+    if let Some(announcement) = config
+        .consent
+        .as_ref()
+        .and_then(|consent| consent.announcement.as_deref())
+    {
+        if !crate::consent::is_valid_announcement(announcement) {
+            return Err(format!(
+                "consent.announcement must be none, beep or speech, got {announcement}"
+            ));
+        }
+    }
+    if config
+        .auto_stop
+        .as_ref()
+        .and_then(|auto_stop| auto_stop.silence_minutes)
+        == Some(0)
+    {
+        return Err("autoStop.silenceMinutes must be at least 1".to_string());
+    }
+    if config
+        .interpreter
+        .as_ref()
+        .and_then(|interpreter| interpreter.max_segment_ms)
+        .is_some_and(|ms| ms < 500)
+    {
+        return Err("interpreter.maxSegmentMs must be at least 500".to_string());
+    }
+    if let Some(templates) = &config.templates {
+        if templates
+            .iter()
+            .any(|template| template.id.trim().is_empty())
+        {
+            return Err("templates[].id must not be empty".to_string());
+        }
+    }
+    if let Some(share) = &config.share {
+        for (name, value) in [
+            ("share.slackWebhookUrl", &share.slack_webhook_url),
+            ("share.discordWebhookUrl", &share.discord_webhook_url),
+        ] {
+            let Some(value) = value.as_deref().filter(|value| !value.trim().is_empty()) else {
+                continue;
+            };
+            let url = url::Url::parse(value.trim()).map_err(|err| format!("{name}: {err}"))?;
+            if url.scheme() != "https" {
+                return Err(format!("{name} must be an https URL"));
+            }
+        }
+    }
+    if let Some(mqtt) = &config.mqtt {
+        if mqtt.enabled == Some(true)
+            && mqtt
+                .host
+                .as_deref()
+                .is_none_or(|host| host.trim().is_empty())
+        {
+            return Err("mqtt.host is required when mqtt is enabled".to_string());
+        }
+        if mqtt.qos.is_some_and(|qos| qos > 2) {
+            return Err("mqtt.qos must be 0, 1 or 2".to_string());
+        }
+        if mqtt.port == Some(0) {
+            return Err("mqtt.port must be greater than 0".to_string());
+        }
+    }
+    if let Some(logging) = &config.logging {
+        if let Some(level) = &logging.level {
+            if normalize_level(Some(level)).is_none() {
+                return Err(format!("logging.level is not a valid level: {level}"));
+            }
+        }
+        for (module, level) in logging.modules.iter().flatten() {
+            if normalize_level(Some(level)).is_none() {
+                return Err(format!(
+                    "logging.modules.{module} is not a valid level: {level}"
+                ));
+            }
+        }
+        if logging.max_files == Some(0) {
+            return Err("logging.maxFiles must be greater than 0".to_string());
+        }
+        for pattern in logging.redact_patterns.iter().flatten() {
+            if !is_valid_pattern(pattern) {
+                return Err(format!(
+                    "logging.redactPatterns has an invalid pattern: {pattern}"
+                ));
+            }
+        }
+    }
+    if let Some(proxy) = config
+        .network
+        .as_ref()
+        .and_then(|network| network.proxy.as_deref())
+        .filter(|value| !value.trim().is_empty())
+    {
+        let url = url::Url::parse(proxy.trim()).map_err(|err| format!("network.proxy: {err}"))?;
+        if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+            return Err("network.proxy must be an http, https or socks5 URL".to_string());
+        }
+    }
+    if let Some(queues) = &config.queues {
+        for (name, queue) in [
+            ("transcribe", &queues.transcribe),
+            ("vad", &queues.vad),
+            ("translate", &queues.translate),
+            ("window", &queues.window),
+            ("refine", &queues.refine),
+        ] {
+            let Some(queue) = queue else {
+                continue;
+            };
+            if queue.capacity == Some(0) {
+                return Err(format!("queues.{name}.capacity must be greater than 0"));
+            }
+            if let Some(overflow) = &queue.overflow {
+                if OverflowPolicy::parse(overflow).is_none() {
+                    return Err(format!(
+                        "queues.{name}.overflow must be dropOldest, coalesce or block"
+                    ));
+                }
+            }
+        }
+        if queues.stuck_worker_secs == Some(0) {
+            return Err("queues.stuckWorkerSecs must be greater than 0".to_string());
+        }
+        if queues.window_shed_depth == Some(0) {
+            return Err("queues.windowShedDepth must be greater than 0".to_string());
+        }
+    }
+    if let Some(acceleration) = &config.acceleration {
+        if let Some(provider) = &acceleration.provider {
+            requested_accelerators(provider)
+                .map_err(|err| format!("acceleration.provider: {err}"))?;
+        }
+        if acceleration.device_id.is_some_and(|id| id < 0) {
+            return Err("acceleration.deviceId must not be negative".to_string());
+        }
+    }
+    for (name, value) in [
+        ("openai.timeoutSecs", config.openai.timeout_secs),
+        ("openai.chatTimeoutSecs", config.openai.chat_timeout_secs),
+        (
+            "ollama.timeoutSecs",
+            config.ollama.as_ref().and_then(|cfg| cfg.timeout_secs),
+        ),
+        (
+            "localGpt.timeoutSecs",
+            config.local_gpt.as_ref().and_then(|cfg| cfg.timeout_secs),
+        ),
+        (
+            "claude.timeoutSecs",
+            config.claude.as_ref().and_then(|cfg| cfg.timeout_secs),
+        ),
+        (
+            "gemini.timeoutSecs",
+            config.gemini.as_ref().and_then(|cfg| cfg.timeout_secs),
+        ),
+    ] {
+        if value == Some(0) {
+            return Err(format!("{name} must be greater than 0"));
+        }
+    }
+    if config.claude.as_ref().and_then(|cfg| cfg.max_tokens) == Some(0) {
+        return Err("claude.maxTokens must be greater than 0".to_string());
+    }
+    for (key, price) in config
+        .usage
+        .as_ref()
+        .and_then(|usage| usage.prices.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        if !(price.input_per_million >= 0.0 && price.output_per_million >= 0.0) {
+            return Err(format!("usage.prices.{key} must not be negative"));
+        }
+    }
+    Ok(())
+}
+
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, child| !child.is_null());
+            for child in map.values_mut() {
+                strip_nulls(child);
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn write_migrated_config(
+    path: &Path,
+    original: &str,
+    value: &serde_json::Value,
+    outcome: &MigrationOutcome,
+) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| CONFIG_FILE.to_string());
+    let backup = path.with_file_name(format!("{file_name}.v{}.bak", outcome.from_version));
+    fs::write(&backup, original)
+        .map_err(|err| format!("failed to write {}: {err}", backup.display()))?;
+    let content = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+    fs::write(path, content).map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    info!(
+        "config migrated from v{} to v{} (backup: {})",
+        outcome.from_version,
+        outcome.to_version,
+        backup.display()
+    );
+    Ok(())
 }
 
 fn find_config_path() -> Result<PathBuf, String> {
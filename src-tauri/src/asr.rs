@@ -5,6 +5,8 @@ pub struct AsrState {
     provider: Mutex<String>,
     fallback_to_openai: Mutex<bool>,
     language: Mutex<String>,
+    // Whisper model picked while a capture was running; loaded when the next one starts.
+    next_model: Mutex<Option<String>>,
 }
 
 impl AsrState {
@@ -29,6 +31,7 @@ impl AsrState {
             provider: Mutex::new(normalize_provider(&provider)),
             fallback_to_openai: Mutex::new(fallback),
             language: Mutex::new(normalize_language(&language)),
+            next_model: Mutex::new(None),
         }
     }
 
@@ -76,16 +79,38 @@ impl AsrState {
         }
         normalized
     }
+
+    pub fn next_model(&self) -> Option<String> {
+        self.next_model.lock().ok().and_then(|guard| guard.clone())
+    }
+
+    pub fn set_next_model(&self, model: Option<String>) {
+        if let Ok(mut guard) = self.next_model.lock() {
+            *guard = model;
+        }
+    }
+
+    pub fn take_next_model(&self) -> Option<String> {
+        self.next_model
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
 }
 
-fn normalize_provider(raw: &str) -> String {
+pub(crate) fn normalize_provider(raw: &str) -> String {
     let trimmed = raw.trim().to_lowercase();
     match trimmed.as_str() {
         "openai" => "openai".to_string(),
+        "deepgram" => "deepgram".to_string(),
         "whispercpp" | "whisper.cpp" | "whisper" => "whisperserver".to_string(),
         "whisperserver" | "whisper-server" | "whisper_server" | "server" => {
             "whisperserver".to_string()
         }
+        "whisperpipe" | "whisper-pipe" | "whisper_pipe" | "pipe" => "whisperpipe".to_string(),
+        "whisperinproc" | "whisper-inproc" | "whisper_inproc" | "inproc" => {
+            "whisperinproc".to_string()
+        }
         _ => "whisperserver".to_string(),
     }
 }
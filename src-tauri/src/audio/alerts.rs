@@ -0,0 +1,180 @@
+use crate::audio::config::AudioConfig;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+// Samples this close to full scale count as clipped.
+const CLIP_LEVEL: f32 = 0.999;
+const CLIP_WINDOW: Duration = Duration::from_secs(1);
+// Share of clipped samples that makes a window count as clipped.
+const CLIP_RATIO: f64 = 0.001;
+// Consecutive clipped windows before the alert is raised, and clean ones before it clears.
+const CLIP_RAISE_WINDOWS: u32 = 3;
+const CLIP_CLEAR_WINDOWS: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioAlert {
+    // `clipping` or `dead_air`.
+    pub kind: &'static str,
+    // Raised, or `false` once the condition is gone.
+    pub active: bool,
+    // How long the condition had lasted when the alert changed.
+    pub seconds: u64,
+}
+
+// Watches the running capture for input that keeps hitting full scale and for a source that went
+// quiet, e.g. a muted microphone or a loopback on the wrong device.
+pub struct SignalMonitor {
+    clipping_enabled: bool,
+    dead_air_after: Option<Duration>,
+    window_started: Instant,
+    window_samples: u64,
+    window_clipped: u64,
+    clipped_windows: u32,
+    clean_windows: u32,
+    clipping_since: Option<Instant>,
+    last_sound: Instant,
+    dead_air: bool,
+}
+
+impl SignalMonitor {
+    pub fn new(config: &AudioConfig, now: Instant) -> Self {
+        Self {
+            clipping_enabled: config.clipping_alert,
+            dead_air_after: (config.dead_air_alert_minutes > 0)
+                .then(|| Duration::from_secs(config.dead_air_alert_minutes * 60)),
+            window_started: now,
+            window_samples: 0,
+            window_clipped: 0,
+            clipped_windows: 0,
+            clean_windows: 0,
+            clipping_since: None,
+            last_sound: now,
+            dead_air: false,
+        }
+    }
+
+    // Raw captured samples, before denoising.
+    pub fn add(&mut self, pcm: &[f32]) {
+        self.window_samples += pcm.len() as u64;
+        self.window_clipped += pcm
+            .iter()
+            .filter(|sample| sample.abs() >= CLIP_LEVEL)
+            .count() as u64;
+    }
+
+    // Audio above the silence threshold arrived.
+    pub fn heard(&mut self, now: Instant) {
+        self.last_sound = now;
+    }
+
+    // After a pause nothing has been missing; the clip history restarts too.
+    pub fn reset(&mut self, now: Instant) {
+        self.last_sound = now;
+        self.window_started = now;
+        self.window_samples = 0;
+        self.window_clipped = 0;
+        self.clipped_windows = 0;
+    }
+
+    // Alerts that were raised or cleared since the last call; called on every loop pass, also
+    // when no audio arrived.
+    pub fn poll(&mut self, now: Instant) -> Vec<AudioAlert> {
+        let mut alerts = Vec::new();
+        if self.clipping_enabled && now.duration_since(self.window_started) >= CLIP_WINDOW {
+            let clipped = self.window_samples > 0
+                && self.window_clipped as f64 / self.window_samples as f64 >= CLIP_RATIO;
+            if clipped {
+                self.clipped_windows += 1;
+                self.clean_windows = 0;
+            } else {
+                self.clipped_windows = 0;
+                self.clean_windows += 1;
+            }
+            match self.clipping_since {
+                None if self.clipped_windows >= CLIP_RAISE_WINDOWS => {
+                    let since = self
+                        .window_started
+                        .checked_sub(CLIP_WINDOW * (CLIP_RAISE_WINDOWS - 1))
+                        .unwrap_or(self.window_started);
+                    self.clipping_since = Some(since);
+                    alerts.push(alert("clipping", true, now.duration_since(since)));
+                }
+                Some(since) if self.clean_windows >= CLIP_CLEAR_WINDOWS => {
+                    self.clipping_since = None;
+                    alerts.push(alert("clipping", false, now.duration_since(since)));
+                }
+                _ => {}
+            }
+            self.window_started = now;
+            self.window_samples = 0;
+            self.window_clipped = 0;
+        }
+        if let Some(after) = self.dead_air_after {
+            let quiet = now.duration_since(self.last_sound);
+            if !self.dead_air && quiet >= after {
+                self.dead_air = true;
+                alerts.push(alert("dead_air", true, quiet));
+            } else if self.dead_air && quiet < after {
+                self.dead_air = false;
+                alerts.push(alert("dead_air", false, quiet));
+            }
+        }
+        alerts
+    }
+}
+
+fn alert(kind: &'static str, active: bool, lasted: Duration) -> AudioAlert {
+    AudioAlert {
+        kind,
+        active,
+        seconds: lasted.as_secs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignalMonitor;
+    use crate::audio::config::AudioConfig;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn raises_and_clears_clipping_and_dead_air() {
+        let config = AudioConfig {
+            dead_air_alert_minutes: 1,
+            ..AudioConfig::default()
+        };
+        let start = Instant::now();
+        let second = |n: u64| start + Duration::from_secs(n);
+        let mut monitor = SignalMonitor::new(&config, start);
+
+        // One clipped second is a peak, three in a row are persistent.
+        let mut raised = Vec::new();
+        for n in 1..=3 {
+            monitor.add(&[1.0, 0.2, -1.0, 0.1]);
+            monitor.heard(second(n));
+            raised.extend(monitor.poll(second(n)));
+        }
+        assert_eq!(raised.len(), 1);
+        assert_eq!((raised[0].kind, raised[0].active), ("clipping", true));
+        for n in 4..=12 {
+            monitor.add(&[0.1; 4]);
+            monitor.heard(second(n));
+            assert!(monitor.poll(second(n)).is_empty());
+        }
+        monitor.add(&[0.1; 4]);
+        let cleared = monitor.poll(second(13));
+        assert_eq!((cleared[0].kind, cleared[0].active), ("clipping", false));
+
+        // Nothing arrives at all, as with a silent loopback.
+        let quiet = monitor.poll(second(72));
+        assert_eq!(quiet.len(), 1);
+        assert_eq!(
+            (quiet[0].kind, quiet[0].active, quiet[0].seconds),
+            ("dead_air", true, 60)
+        );
+        assert!(monitor.poll(second(80)).is_empty());
+        monitor.heard(second(81));
+        let back = monitor.poll(second(81));
+        assert_eq!((back[0].kind, back[0].active), ("dead_air", false));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::audio::config::{AudioConfig, BackendKind, CaptureSource};
+use crate::audio::cpal_capture::{self, CpalCapture};
+use crate::audio::devices::{ActiveDevice, AudioDevice, Notification};
+use crate::audio::meeting_apps;
+use crate::audio::mixer::TrackMixer;
+#[cfg(windows)]
+use crate::audio::wasapi::{self, WasapiCapture};
+use serde::Serialize;
+use std::time::Duration;
+
+// A running capture as `run_capture` reads it, whichever API delivers the audio. Opened and read
+// on the capture thread only.
+pub trait CaptureBackend {
+    // The format of the first device, kept for the whole capture.
+    fn sample_rate(&self) -> u32;
+    fn channels(&self) -> u16;
+    // Blocks until audio arrives or `timeout` passes, so the capture thread does not spin.
+    fn wait(&mut self, timeout: Duration);
+    // Interleaved samples that arrived since the last call; empty when there are none yet.
+    fn read(&mut self) -> Result<Vec<f32>, String>;
+    // Devices the capture moved to since the last call. Only a capture opened on the system
+    // default moves.
+    fn follow_default(&mut self) -> Result<Vec<DeviceSwitch>, String>;
+    fn active_devices(&self) -> Vec<ActiveDevice>;
+    // Packets lost since the capture opened, as far as the API can tell.
+    fn dropped_buffers(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSwitch {
+    pub source: CaptureSource,
+    pub previous_device_id: String,
+    pub device_id: String,
+    pub name: String,
+    // `default_changed`, or `device_lost` when the old device stopped delivering audio.
+    pub reason: &'static str,
+    // The new device's own format; its audio is converted to the format capture started with.
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+// `auto`, and `wasapi` outside Windows, resolve to the backend this platform can run.
+pub fn resolve(kind: BackendKind) -> BackendKind {
+    match kind {
+        BackendKind::Cpal => BackendKind::Cpal,
+        _ if cfg!(windows) => BackendKind::Wasapi,
+        _ => BackendKind::Cpal,
+    }
+}
+
+pub fn open(config: &AudioConfig) -> Result<Box<dyn CaptureBackend>, String> {
+    let kind = resolve(config.backend);
+    let open = |source| open_source(kind, source, config.device_id(source));
+    Ok(match config.source {
+        CaptureSource::Dual => Box::new(DualCapture::new(
+            open(CaptureSource::Microphone)?,
+            open(CaptureSource::Loopback)?,
+        )),
+        source => open(source)?,
+    })
+}
+
+fn open_source(
+    kind: BackendKind,
+    source: CaptureSource,
+    device_id: Option<&str>,
+) -> Result<Box<dyn CaptureBackend>, String> {
+    Ok(match kind {
+        #[cfg(windows)]
+        BackendKind::Wasapi => Box::new(WasapiCapture::open(source, device_id)?),
+        _ => Box::new(CpalCapture::open(source, device_id)?),
+    })
+}
+
+// Devices `source` can be captured from; blocking.
+pub fn endpoints(
+    kind: BackendKind,
+    source: CaptureSource,
+    selected: Option<&str>,
+) -> Result<Vec<AudioDevice>, String> {
+    match resolve(kind) {
+        #[cfg(windows)]
+        BackendKind::Wasapi => wasapi::endpoints(source, selected),
+        _ => cpal_capture::endpoints(source, selected),
+    }
+}
+
+// Calls `on_change` for device changes until the app exits; blocks the calling thread.
+pub fn watch_devices(kind: BackendKind, on_change: impl FnMut(Notification)) -> Result<(), String> {
+    match resolve(kind) {
+        #[cfg(windows)]
+        BackendKind::Wasapi => wasapi::watch(on_change),
+        _ => cpal_capture::watch(on_change),
+    }
+}
+
+// Processes that may be in a call, for `audio::meeting_apps`: those with an active audio session
+// on WASAPI, every running process elsewhere.
+pub fn audio_processes(kind: BackendKind) -> Result<Vec<String>, String> {
+    match resolve(kind) {
+        #[cfg(windows)]
+        BackendKind::Wasapi => wasapi::session_processes(),
+        _ => meeting_apps::running_processes(),
+    }
+}
+
+// The microphone and loopback read side by side and mixed into one two-channel stream at the
+// loopback rate, see `audio::mixer`.
+pub struct DualCapture {
+    me: Box<dyn CaptureBackend>,
+    them: Box<dyn CaptureBackend>,
+    mixer: TrackMixer,
+}
+
+impl DualCapture {
+    pub fn new(me: Box<dyn CaptureBackend>, them: Box<dyn CaptureBackend>) -> Self {
+        let mixer = TrackMixer::new(
+            me.sample_rate(),
+            me.channels(),
+            them.sample_rate(),
+            them.channels(),
+        );
+        Self { me, them, mixer }
+    }
+}
+
+impl CaptureBackend for DualCapture {
+    fn sample_rate(&self) -> u32 {
+        self.them.sample_rate()
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    // The microphone delivers audio steadily; loopback goes quiet while nothing plays.
+    fn wait(&mut self, timeout: Duration) {
+        self.me.wait(timeout);
+    }
+
+    fn read(&mut self) -> Result<Vec<f32>, String> {
+        let me = self.me.read()?;
+        let them = self.them.read()?;
+        Ok(self.mixer.mix(&me, &them))
+    }
+
+    fn follow_default(&mut self) -> Result<Vec<DeviceSwitch>, String> {
+        let mut switches = self.me.follow_default()?;
+        switches.extend(self.them.follow_default()?);
+        Ok(switches)
+    }
+
+    fn active_devices(&self) -> Vec<ActiveDevice> {
+        let mut devices = self.me.active_devices();
+        devices.extend(self.them.active_devices());
+        devices
+    }
+
+    fn dropped_buffers(&self) -> u64 {
+        self.me.dropped_buffers() + self.them.dropped_buffers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, CaptureBackend, DeviceSwitch, DualCapture};
+    use crate::audio::config::{BackendKind, CaptureSource};
+    use crate::audio::devices::ActiveDevice;
+    use std::time::Duration;
+
+    struct Fixed {
+        source: CaptureSource,
+        sample_rate: u32,
+        channels: u16,
+        packets: Vec<Vec<f32>>,
+    }
+
+    impl CaptureBackend for Fixed {
+        fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+
+        fn channels(&self) -> u16 {
+            self.channels
+        }
+
+        fn wait(&mut self, _timeout: Duration) {}
+
+        fn read(&mut self) -> Result<Vec<f32>, String> {
+            Ok(self.packets.pop().unwrap_or_default())
+        }
+
+        fn follow_default(&mut self) -> Result<Vec<DeviceSwitch>, String> {
+            Ok(Vec::new())
+        }
+
+        fn active_devices(&self) -> Vec<ActiveDevice> {
+            vec![ActiveDevice {
+                id: format!("{:?}", self.source),
+                source: self.source,
+                follows_default: true,
+            }]
+        }
+
+        fn dropped_buffers(&self) -> u64 {
+            0
+        }
+    }
+
+    #[test]
+    fn mixes_two_backends_into_one_stream() {
+        assert_eq!(resolve(BackendKind::Cpal), BackendKind::Cpal);
+        let platform = if cfg!(windows) {
+            BackendKind::Wasapi
+        } else {
+            BackendKind::Cpal
+        };
+        assert_eq!(resolve(BackendKind::Auto), platform);
+        assert_eq!(resolve(BackendKind::Wasapi), platform);
+
+        let me = Fixed {
+            source: CaptureSource::Microphone,
+            sample_rate: 16_000,
+            channels: 1,
+            packets: vec![vec![0.5, 0.5]],
+        };
+        let them = Fixed {
+            source: CaptureSource::Loopback,
+            sample_rate: 16_000,
+            channels: 2,
+            packets: vec![vec![0.2, 0.4, 0.6, 0.8]],
+        };
+        let mut dual = DualCapture::new(Box::new(me), Box::new(them));
+        assert_eq!((dual.sample_rate(), dual.channels()), (16_000, 2));
+        let pcm = dual.read().unwrap();
+        assert_eq!(pcm.len(), 4);
+        assert_eq!((pcm[0], pcm[2]), (0.5, 0.5));
+        assert!((pcm[1] - 0.3).abs() < 1e-6 && (pcm[3] - 0.7).abs() < 1e-6);
+        let sources = dual
+            .active_devices()
+            .into_iter()
+            .map(|device| device.source)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            [CaptureSource::Microphone, CaptureSource::Loopback]
+        );
+    }
+}
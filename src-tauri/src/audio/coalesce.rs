@@ -0,0 +1,233 @@
+use crate::audio::config::AudioConfig;
+use crate::audio::manager::SegmentInfo;
+use crate::audio::writer;
+use hound::{WavReader, WavWriter};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+// Short segments split off by brief pauses are joined into one file before transcription, since
+// whisper does poorly on sub-second clips. A segment is held back until the next one shows
+// whether it can be merged, which delays it by at most `merge_gap_ms` unless speech resumed.
+pub struct Coalescer {
+    max_gap: Duration,
+    short_ms: u64,
+    max_ms: u64,
+    held: Option<Held>,
+    // Start of the segment the capture loop is writing now.
+    opened_at: Option<Instant>,
+}
+
+struct Held {
+    info: SegmentInfo,
+    closed_at: Instant,
+}
+
+impl Coalescer {
+    pub fn new(config: &AudioConfig) -> Self {
+        Self {
+            max_gap: Duration::from_millis(config.merge_gap_ms),
+            short_ms: config.merge_short_ms,
+            max_ms: config.max_segment_ms,
+            held: None,
+            opened_at: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.max_gap.is_zero() && self.short_ms > 0
+    }
+
+    // The capture loop started writing a new segment.
+    pub fn opened(&mut self, at: Instant) {
+        self.opened_at = Some(at);
+    }
+
+    // How long to wait for the next segment before the held one is given up on; `None` to wait
+    // indefinitely, when nothing is held or the next segment already started in time.
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        let held = self.held.as_ref()?;
+        if self.opened_at.is_some_and(|opened| {
+            opened >= held.closed_at && opened <= held.closed_at + self.max_gap
+        }) {
+            return None;
+        }
+        Some((held.closed_at + self.max_gap).saturating_duration_since(now))
+    }
+
+    // Takes a finalized segment and returns those ready for transcription, in order.
+    pub fn push(
+        &mut self,
+        dir: &Path,
+        info: SegmentInfo,
+        opened_at: Instant,
+        closed_at: Instant,
+    ) -> Vec<SegmentInfo> {
+        let mut ready = Vec::new();
+        let next = match self.held.take() {
+            Some(mut held) => {
+                let gap = opened_at.saturating_duration_since(held.closed_at);
+                if self.mergeable(&held.info, gap, &info) {
+                    match append(dir, &mut held.info, &info, gap) {
+                        Ok(()) => held.info,
+                        Err(err) => {
+                            warn!("[coalesce] merging {} failed: {err}", info.name);
+                            ready.push(held.info);
+                            info
+                        }
+                    }
+                } else {
+                    ready.push(held.info);
+                    info
+                }
+            }
+            None => info,
+        };
+        if self.is_enabled() && next.duration_ms < self.short_ms {
+            self.held = Some(Held {
+                info: next,
+                closed_at,
+            });
+        } else {
+            ready.push(next);
+        }
+        ready
+    }
+
+    // The held segment, once no merge can follow.
+    pub fn take(&mut self) -> Option<SegmentInfo> {
+        self.held.take().map(|held| held.info)
+    }
+
+    fn mergeable(&self, first: &SegmentInfo, gap: Duration, second: &SegmentInfo) -> bool {
+        let total_ms = first.duration_ms + gap.as_millis() as u64 + second.duration_ms;
+        self.is_enabled()
+            && gap <= self.max_gap
+            && first.sample_rate == second.sample_rate
+            && first.channels == second.channels
+            && (first.duration_ms < self.short_ms || second.duration_ms < self.short_ms)
+            && (self.max_ms == 0 || total_ms <= self.max_ms)
+    }
+}
+
+// Appends `second` to `first`'s file with the pause between them as silence, and removes
+// `second`'s file.
+fn append(
+    dir: &Path,
+    first: &mut SegmentInfo,
+    second: &SegmentInfo,
+    gap: Duration,
+) -> Result<(), String> {
+    let first_path = dir.join(&first.name);
+    let second_path = dir.join(&second.name);
+    let mut reader = WavReader::open(&first_path).map_err(|err| err.to_string())?;
+    let spec = reader.spec();
+    let mut samples = writer::f32_samples(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    let gap_frames = gap.as_millis() as u64 * spec.sample_rate as u64 / 1000;
+    samples.resize(
+        samples.len() + (gap_frames * spec.channels as u64) as usize,
+        0.0,
+    );
+    let mut reader = WavReader::open(&second_path).map_err(|err| err.to_string())?;
+    for sample in writer::f32_samples(&mut reader) {
+        samples.push(sample?);
+    }
+
+    let merged_path = first_path.with_extension("merging");
+    let mut wav = WavWriter::create(&merged_path, spec).map_err(|err| err.to_string())?;
+    for sample in &samples {
+        writer::write_sample(&mut wav, *sample)?;
+    }
+    wav.finalize().map_err(|err| err.to_string())?;
+    fs::rename(&merged_path, &first_path).map_err(|err| err.to_string())?;
+    let _ = fs::remove_file(&second_path);
+
+    let frames = samples.len() as u64 / spec.channels.max(1) as u64;
+    first.duration_ms = frames * 1000 / spec.sample_rate.max(1) as u64;
+    first.end_offset_ms = second
+        .end_offset_ms
+        .or_else(|| first.start_offset_ms.map(|start| start + first.duration_ms));
+    if first.track != second.track {
+        first.track = None;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Coalescer;
+    use crate::audio::config::AudioConfig;
+    use crate::audio::manager::SegmentInfo;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+    use std::time::{Duration, Instant};
+
+    fn segment(dir: &std::path::Path, name: &str, frames: usize) -> SegmentInfo {
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 1_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(dir.join(name), spec).unwrap();
+        for _ in 0..frames {
+            writer.write_sample(0.5f32).unwrap();
+        }
+        writer.finalize().unwrap();
+        SegmentInfo {
+            name: name.to_string(),
+            duration_ms: frames as u64,
+            sample_rate: 1_000,
+            channels: 1,
+            ..SegmentInfo::default()
+        }
+    }
+
+    #[test]
+    fn merges_short_segments_across_brief_pauses() {
+        let dir = std::env::temp_dir().join(format!("ai_shepherd_coalesce_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = AudioConfig {
+            merge_gap_ms: 500,
+            merge_short_ms: 1_500,
+            max_segment_ms: 10_000,
+            ..AudioConfig::default()
+        };
+        let mut coalescer = Coalescer::new(&config);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // A short segment is held until the next one arrives.
+        let first = segment(&dir, "a.wav", 700);
+        assert!(coalescer.push(&dir, first, at(0), at(700)).is_empty());
+        assert_eq!(coalescer.wait(at(900)), Some(Duration::from_millis(300)));
+        coalescer.opened(at(1_000));
+        assert_eq!(coalescer.wait(at(1_300)), None);
+
+        // 300 ms later the next one starts: one 2 s file, released since it is no longer short.
+        let second = segment(&dir, "b.wav", 1_000);
+        let ready = coalescer.push(&dir, second, at(1_000), at(2_000));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].name, "a.wav");
+        assert_eq!(ready[0].duration_ms, 2_000);
+        assert!(!dir.join("b.wav").exists());
+        let samples = hound::WavReader::open(dir.join("a.wav"))
+            .unwrap()
+            .samples::<f32>()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(samples.len(), 2_000);
+        assert_eq!(samples[800], 0.0);
+
+        // Too far apart: both go on their own.
+        let third = segment(&dir, "c.wav", 600);
+        assert!(coalescer.push(&dir, third, at(3_000), at(3_600)).is_empty());
+        let fourth = segment(&dir, "d.wav", 3_000);
+        let ready = coalescer.push(&dir, fourth, at(5_000), at(8_000));
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].duration_ms, 600);
+        assert!(coalescer.take().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,31 @@
+use crate::audio::index::DEFAULT_INDEX_FLUSH_MS;
+use crate::audio::store::DEFAULT_MAX_IN_MEMORY_SEGMENTS;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureSource {
+    // What the output device plays: WASAPI loopback, or a monitor/loopback input with cpal.
+    #[default]
+    Loopback,
+    Microphone,
+    // Microphone and loopback together as a two-channel recording, see `audio::mixer`.
+    Dual,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    // WASAPI on Windows, cpal elsewhere.
+    #[default]
+    Auto,
+    Wasapi,
+    Cpal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AudioConfig {
@@ -19,6 +42,40 @@ pub struct AudioConfig {
     pub rolling_window_ms: u64,
     pub rolling_step_ms: u64,
     pub rolling_min_ms: u64,
+    // Older segments are evicted to the on-disk index past this; 0 keeps everything in memory.
+    pub max_in_memory_segments: usize,
+    // Segment index updates are batched for this long; 0 writes each one immediately.
+    pub index_flush_ms: u64,
+    pub source: CaptureSource,
+    // Endpoint ids from `list_audio_devices`; `None` follows the system default.
+    pub output_device_id: Option<String>,
+    pub input_device_id: Option<String>,
+    // Capture API, see `audio::backend`; device ids are only valid for the backend listing them.
+    pub backend: BackendKind,
+    // RNNoise pass before silence detection and segment writing, see `audio::denoise`.
+    pub denoise: bool,
+    // One continuous WAV per session next to the segments, see `audio::recording`.
+    pub record_session: bool,
+    // Oldest audio is pruned past these, see `audio::retention`; 0 for no limit.
+    pub max_storage_mb: u64,
+    pub max_storage_age_days: u64,
+    // Audio kept in memory while capture is armed, see `audio::retro`.
+    pub retro_buffer_secs: u64,
+    // Segments shorter than `merge_short_ms` are joined with a neighbour at most `merge_gap_ms`
+    // away before transcription, see `audio::coalesce`; 0 turns it off.
+    pub merge_gap_ms: u64,
+    pub merge_short_ms: u64,
+    // Store segments as 16 kHz mono 16-bit instead of the capture format, see `audio::resample`.
+    pub resample_16k_mono: bool,
+    // Watch for call apps, see `audio::meeting_apps`: a prompt by default, capture starts by
+    // itself with `meeting_app_auto_start`. `meeting_apps` adds process names to look for.
+    pub meeting_app_detection: bool,
+    pub meeting_app_auto_start: bool,
+    pub meeting_apps: Vec<String>,
+    // `audio_alert` when input keeps hitting full scale, or when nothing above
+    // `silence_threshold_db` arrived for this many minutes (0 turns it off), see `audio::alerts`.
+    pub clipping_alert: bool,
+    pub dead_air_alert_minutes: u64,
 }
 
 impl Default for AudioConfig {
@@ -37,6 +94,25 @@ impl Default for AudioConfig {
             rolling_window_ms: 8000,
             rolling_step_ms: 500,
             rolling_min_ms: 1500,
+            max_in_memory_segments: DEFAULT_MAX_IN_MEMORY_SEGMENTS,
+            index_flush_ms: DEFAULT_INDEX_FLUSH_MS,
+            source: CaptureSource::Loopback,
+            output_device_id: None,
+            input_device_id: None,
+            backend: BackendKind::Auto,
+            denoise: false,
+            record_session: false,
+            max_storage_mb: 0,
+            max_storage_age_days: 0,
+            retro_buffer_secs: 120,
+            merge_gap_ms: 0,
+            merge_short_ms: 1500,
+            resample_16k_mono: false,
+            meeting_app_detection: false,
+            meeting_app_auto_start: false,
+            meeting_apps: Vec::new(),
+            clipping_alert: true,
+            dead_air_alert_minutes: 3,
         }
     }
 }
@@ -72,6 +148,17 @@ fn write_default(path: &Path, config: &AudioConfig) {
     }
 }
 
+impl AudioConfig {
+    // The endpoint selected for a single source.
+    pub fn device_id(&self, source: CaptureSource) -> Option<&str> {
+        match source {
+            CaptureSource::Microphone => self.input_device_id.as_deref(),
+            CaptureSource::Loopback | CaptureSource::Dual => self.output_device_id.as_deref(),
+        }
+        .filter(|id| !id.is_empty())
+    }
+}
+
 pub fn load_config(app: &AppHandle) -> AudioConfig {
     if let Some(path) = app_config_path(app) {
         if let Some(config) = read_config(&path) {
@@ -96,3 +183,14 @@ pub fn ensure_config_file(app: &AppHandle, config: &AudioConfig) {
         write_default(&path, config);
     }
 }
+
+pub fn save_config(app: &AppHandle, config: &AudioConfig) -> Result<PathBuf, String> {
+    let path = app_config_path(app).ok_or_else(|| "app data dir unavailable".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|err| err.to_string())?;
+    fs::write(&path, content)
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
@@ -0,0 +1,115 @@
+// Converts interleaved packets from a reopened device to the format the capture started with, so
+// segment writers and VAD thresholds never see a format change in the middle of a session.
+pub struct FormatAdapter {
+    from_rate: u32,
+    from_channels: u16,
+    to_rate: u32,
+    to_channels: u16,
+    // Last converted frame of the previous packet; interpolation continues from it.
+    last: Vec<f32>,
+    // Position of the next output frame, in input frames counted from `last`.
+    position: f64,
+}
+
+impl FormatAdapter {
+    pub fn new(from_rate: u32, from_channels: u16, to_rate: u32, to_channels: u16) -> Self {
+        Self {
+            from_rate: from_rate.max(1),
+            from_channels: from_channels.max(1),
+            to_rate: to_rate.max(1),
+            to_channels: to_channels.max(1),
+            last: Vec::new(),
+            position: 0.0,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.from_rate == self.to_rate && self.from_channels == self.to_channels
+    }
+
+    pub fn convert(&mut self, input: &[f32]) -> Vec<f32> {
+        let mapped = self.map_channels(input);
+        if self.from_rate == self.to_rate {
+            return mapped;
+        }
+        self.resample(mapped)
+    }
+
+    // Mono output averages every channel; mono input is copied to every channel; otherwise
+    // channels are matched by position and missing ones repeat the last input channel.
+    fn map_channels(&self, input: &[f32]) -> Vec<f32> {
+        let from = self.from_channels as usize;
+        let to = self.to_channels as usize;
+        if from == to {
+            return input.to_vec();
+        }
+        let mut output = Vec::with_capacity(input.len() / from * to);
+        for frame in input.chunks_exact(from) {
+            if to == 1 {
+                output.push(frame.iter().sum::<f32>() / from as f32);
+            } else {
+                output.extend((0..to).map(|channel| frame[channel.min(from - 1)]));
+            }
+        }
+        output
+    }
+
+    // Linear interpolation, carrying the last frame over so packet boundaries do not click.
+    fn resample(&mut self, mapped: Vec<f32>) -> Vec<f32> {
+        let channels = self.to_channels as usize;
+        let mut frames = std::mem::take(&mut self.last);
+        frames.extend(mapped);
+        let count = frames.len() / channels;
+        if count == 0 {
+            return Vec::new();
+        }
+        let step = self.from_rate as f64 / self.to_rate as f64;
+        let mut output = Vec::new();
+        while self.position + 1.0 < count as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            for channel in 0..channels {
+                let current = frames[index * channels + channel];
+                let next = frames[(index + 1) * channels + channel];
+                output.push(current + (next - current) * fraction);
+            }
+            self.position += step;
+        }
+        self.position -= (count - 1) as f64;
+        self.last = frames[(count - 1) * channels..count * channels].to_vec();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FormatAdapter;
+
+    #[test]
+    fn converts_channels_and_rate_across_packets() {
+        let mut same = FormatAdapter::new(48_000, 2, 48_000, 2);
+        assert!(same.is_identity());
+        assert_eq!(same.convert(&[0.1, 0.2]), [0.1, 0.2]);
+
+        let mut mono = FormatAdapter::new(48_000, 2, 48_000, 1);
+        assert_eq!(mono.convert(&[0.2, 0.4, 1.0, 0.0]), [0.3, 0.5]);
+        let mut stereo = FormatAdapter::new(48_000, 1, 48_000, 2);
+        assert_eq!(stereo.convert(&[0.5]), [0.5, 0.5]);
+
+        // 48 kHz to 16 kHz keeps every third frame of a ramp, whatever the packet sizes.
+        let mut down = FormatAdapter::new(48_000, 1, 16_000, 1);
+        let ramp = (0..30).map(|value| value as f32).collect::<Vec<_>>();
+        let mut output = down.convert(&ramp[..7]);
+        output.extend(down.convert(&ramp[7..8]));
+        output.extend(down.convert(&ramp[8..]));
+        assert_eq!(
+            output,
+            [0.0, 3.0, 6.0, 9.0, 12.0, 15.0, 18.0, 21.0, 24.0, 27.0]
+        );
+
+        let mut up = FormatAdapter::new(16_000, 1, 32_000, 1);
+        let mut output = up.convert(&[0.0, 1.0]);
+        output.extend(up.convert(&[2.0]));
+        assert_eq!(output, [0.0, 0.5, 1.0, 1.5]);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::audio::backend::{CaptureBackend, DeviceSwitch};
+use crate::audio::config::CaptureSource;
+use crate::audio::convert::FormatAdapter;
+use crate::audio::devices::{ActiveDevice, AudioDevice, Flow, Notification};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{
+    Device, FromSample, Host, InputCallbackInfo, Sample, SampleFormat, SizedSample, Stream,
+    StreamConfig, StreamError, StreamInstant,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// cpal has no device notifications, so defaults and device lists are polled this often.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// A device that fails again this soon after being reopened is treated as gone for good.
+const REOPEN_RETRY_AFTER: Duration = Duration::from_secs(2);
+// A packet captured this much later than the previous one ended counts as dropped audio.
+const GAP_TOLERANCE: Duration = Duration::from_millis(50);
+
+// Inputs that record what an output plays: PulseAudio/PipeWire monitor sources, or loopback
+// drivers such as BlackHole on macOS.
+const LOOPBACK_HINTS: [&str; 4] = ["monitor", "loopback", "blackhole", "soundflower"];
+
+fn is_loopback_input(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+// cpal's WASAPI host records an output device directly; other hosts only record inputs.
+fn records_output(source: CaptureSource) -> bool {
+    cfg!(windows) && source != CaptureSource::Microphone
+}
+
+// cpal has no endpoint ids, so devices are identified by name.
+fn device_name(device: &Device) -> String {
+    device.name().unwrap_or_default()
+}
+
+fn devices(host: &Host, source: CaptureSource) -> Result<Vec<Device>, String> {
+    if records_output(source) {
+        return Ok(host
+            .output_devices()
+            .map_err(|err| err.to_string())?
+            .collect());
+    }
+    let inputs = host.input_devices().map_err(|err| err.to_string())?;
+    let loopback = source != CaptureSource::Microphone;
+    Ok(inputs
+        .filter(|device| cfg!(windows) || is_loopback_input(&device_name(device)) == loopback)
+        .collect())
+}
+
+// Outside Windows loopback uses the default input when it is a monitor (e.g. chosen in
+// pavucontrol), otherwise the first monitor or loopback input.
+fn default_device(host: &Host, source: CaptureSource) -> Option<Device> {
+    if records_output(source) {
+        return host.default_output_device();
+    }
+    if source == CaptureSource::Microphone {
+        return host.default_input_device();
+    }
+    host.default_input_device()
+        .filter(|device| is_loopback_input(&device_name(device)))
+        .or_else(|| devices(host, source).ok()?.into_iter().next())
+}
+
+fn no_device(source: CaptureSource) -> String {
+    match source {
+        CaptureSource::Microphone => "No microphone found".to_string(),
+        _ => "No loopback device found: select a PulseAudio/PipeWire monitor source, or install \
+              a loopback driver such as BlackHole on macOS"
+            .to_string(),
+    }
+}
+
+// An open cpal stream; its callback forwards packets to `samples` from cpal's own thread.
+struct Input {
+    device_id: String,
+    sample_rate: u32,
+    channels: u16,
+    samples: Receiver<Vec<f32>>,
+    // A packet `wait` took off `samples`, for the next `read`.
+    waited: Vec<f32>,
+    errors: Receiver<String>,
+    _stream: Stream,
+}
+
+impl Input {
+    fn open(
+        device: &Device,
+        source: CaptureSource,
+        dropped: Arc<AtomicU64>,
+    ) -> Result<Self, String> {
+        let supported = if records_output(source) {
+            device.default_output_config()
+        } else {
+            device.default_input_config()
+        }
+        .map_err(|err| err.to_string())?;
+        let sample_format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+        let (sender, samples) = mpsc::channel();
+        let (error_sender, errors) = mpsc::channel();
+        let on_error = move |err: StreamError| {
+            let _ = error_sender.send(err.to_string());
+        };
+        let stream = match sample_format {
+            SampleFormat::F32 => build::<f32>(device, &config, sender, dropped, on_error),
+            SampleFormat::I16 => build::<i16>(device, &config, sender, dropped, on_error),
+            SampleFormat::I32 => build::<i32>(device, &config, sender, dropped, on_error),
+            SampleFormat::U16 => build::<u16>(device, &config, sender, dropped, on_error),
+            format => Err(format!("Unsupported cpal sample format: {format}")),
+        }?;
+        stream.play().map_err(|err| err.to_string())?;
+        Ok(Self {
+            device_id: device_name(device),
+            sample_rate: config.sample_rate.0,
+            channels: config.channels,
+            samples,
+            waited: Vec::new(),
+            errors,
+            _stream: stream,
+        })
+    }
+
+    // Blocks on the callback's channel until it sends a packet.
+    fn wait(&mut self, timeout: Duration) {
+        if !self.waited.is_empty() {
+            return;
+        }
+        match self.samples.recv_timeout(timeout) {
+            Ok(packet) => self.waited = packet,
+            Err(RecvTimeoutError::Timeout) => {}
+            // The stream is gone; `read` reports its error.
+            Err(RecvTimeoutError::Disconnected) => thread::sleep(timeout),
+        }
+    }
+
+    fn read(&mut self) -> Result<Vec<f32>, String> {
+        if let Ok(err) = self.errors.try_recv() {
+            return Err(err);
+        }
+        let mut pcm = std::mem::take(&mut self.waited);
+        pcm.extend(self.samples.try_iter().flatten());
+        Ok(pcm)
+    }
+}
+
+fn build<T>(
+    device: &Device,
+    config: &StreamConfig,
+    sender: Sender<Vec<f32>>,
+    dropped: Arc<AtomicU64>,
+    on_error: impl FnMut(StreamError) + Send + 'static,
+) -> Result<Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels.max(1) as u64;
+    let sample_rate = config.sample_rate.0.max(1) as u64;
+    // When the previous packet's audio ends, by the stream's own capture clock.
+    let mut expected: Option<StreamInstant> = None;
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], info: &InputCallbackInfo| {
+                let capture = info.timestamp().capture;
+                if expected
+                    .and_then(|expected| capture.duration_since(&expected))
+                    .is_some_and(|gap| gap > GAP_TOLERANCE)
+                {
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                let frames = data.len() as u64 / channels;
+                expected = capture.add(Duration::from_nanos(frames * 1_000_000_000 / sample_rate));
+                let _ = sender.send(data.iter().map(|&value| f32::from_sample(value)).collect());
+            },
+            on_error,
+            None,
+        )
+        .map_err(|err| err.to_string())
+}
+
+pub struct CpalCapture {
+    host: Host,
+    input: Input,
+    source: CaptureSource,
+    // Opened as the default, so it moves along when the default changes.
+    follows_default: bool,
+    // The format of the first device, kept for the whole capture.
+    sample_rate: u32,
+    channels: u16,
+    // Set while the current device's format differs from the first one.
+    adapter: Option<FormatAdapter>,
+    switched: Option<DeviceSwitch>,
+    last_reopen: Option<Instant>,
+    last_poll: Instant,
+    // Shared with every stream this capture opens, so reopening keeps the count.
+    dropped: Arc<AtomicU64>,
+}
+
+impl CpalCapture {
+    // `device_id` is a name from `endpoints`; `None`, or a device that is gone, opens the default.
+    pub fn open(source: CaptureSource, device_id: Option<&str>) -> Result<Self, String> {
+        let host = cpal::default_host();
+        let selected = match device_id {
+            Some(id) => {
+                let device = devices(&host, source)?
+                    .into_iter()
+                    .find(|device| device_name(device) == id);
+                if device.is_none() {
+                    warn!("[cpal] device {id} unavailable, using the default");
+                }
+                device
+            }
+            None => None,
+        };
+        let follows_default = selected.is_none();
+        let device = match selected {
+            Some(device) => device,
+            None => default_device(&host, source).ok_or_else(|| no_device(source))?,
+        };
+        let dropped = Arc::new(AtomicU64::new(0));
+        let input = Input::open(&device, source, Arc::clone(&dropped))?;
+        info!(
+            "[cpal] {:?} host={:?} device={} ({} Hz, {} ch)",
+            source,
+            host.id(),
+            input.device_id,
+            input.sample_rate,
+            input.channels
+        );
+        Ok(Self {
+            sample_rate: input.sample_rate,
+            channels: input.channels,
+            host,
+            input,
+            source,
+            follows_default,
+            adapter: None,
+            switched: None,
+            last_reopen: None,
+            last_poll: Instant::now(),
+            dropped,
+        })
+    }
+
+    fn reopen_default(&mut self, reason: &'static str) -> Result<(), String> {
+        self.last_reopen = Some(Instant::now());
+        let device =
+            default_device(&self.host, self.source).ok_or_else(|| no_device(self.source))?;
+        let input = Input::open(&device, self.source, Arc::clone(&self.dropped))?;
+        let previous = std::mem::replace(&mut self.input, input);
+        let adapter = FormatAdapter::new(
+            self.input.sample_rate,
+            self.input.channels,
+            self.sample_rate,
+            self.channels,
+        );
+        self.adapter = (!adapter.is_identity()).then_some(adapter);
+        info!(
+            "[cpal] {reason}: {} -> {} ({} Hz, {} ch)",
+            previous.device_id, self.input.device_id, self.input.sample_rate, self.input.channels
+        );
+        self.switched = Some(DeviceSwitch {
+            source: self.source,
+            previous_device_id: previous.device_id.clone(),
+            device_id: self.input.device_id.clone(),
+            name: self.input.device_id.clone(),
+            reason,
+            sample_rate: self.input.sample_rate,
+            channels: self.input.channels,
+        });
+        Ok(())
+    }
+}
+
+impl CaptureBackend for CpalCapture {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn wait(&mut self, timeout: Duration) {
+        self.input.wait(timeout);
+    }
+
+    fn read(&mut self) -> Result<Vec<f32>, String> {
+        let pcm = match self.input.read() {
+            Ok(pcm) => pcm,
+            // Gives up when the device that was just reopened fails as well.
+            Err(err)
+                if self.follows_default
+                    && self
+                        .last_reopen
+                        .is_none_or(|at| at.elapsed() >= REOPEN_RETRY_AFTER) =>
+            {
+                warn!("[cpal] stream failed, reopening the default device: {err}");
+                self.reopen_default("device_lost")?;
+                return Ok(Vec::new());
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(match self.adapter.as_mut() {
+            Some(adapter) => adapter.convert(&pcm),
+            None => pcm,
+        })
+    }
+
+    fn follow_default(&mut self) -> Result<Vec<DeviceSwitch>, String> {
+        if self.follows_default && self.last_poll.elapsed() >= POLL_INTERVAL {
+            self.last_poll = Instant::now();
+            let changed = default_device(&self.host, self.source)
+                .is_some_and(|device| device_name(&device) != self.input.device_id);
+            if changed {
+                self.reopen_default("default_changed")?;
+            }
+        }
+        Ok(self.switched.take().into_iter().collect())
+    }
+
+    fn active_devices(&self) -> Vec<ActiveDevice> {
+        vec![ActiveDevice {
+            id: self.input.device_id.clone(),
+            source: self.source,
+            follows_default: self.follows_default,
+        }]
+    }
+
+    fn dropped_buffers(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) fn endpoints(
+    source: CaptureSource,
+    selected: Option<&str>,
+) -> Result<Vec<AudioDevice>, String> {
+    let host = cpal::default_host();
+    let default_id = default_device(&host, source).map(|device| device_name(&device));
+    Ok(devices(&host, source)?
+        .iter()
+        .map(|device| {
+            let id = device_name(device);
+            let is_default = default_id.as_deref() == Some(id.as_str());
+            AudioDevice {
+                name: id.clone(),
+                selected: selected.map_or(is_default, |selected| selected == id),
+                is_default,
+                id,
+            }
+        })
+        .collect())
+}
+
+#[derive(Default)]
+struct Snapshot {
+    default_id: Option<String>,
+    ids: Vec<String>,
+}
+
+fn snapshot(host: &Host, flow: Flow) -> Snapshot {
+    let source = match flow {
+        Flow::Render => CaptureSource::Loopback,
+        Flow::Capture => CaptureSource::Microphone,
+    };
+    Snapshot {
+        default_id: default_device(host, source).map(|device| device_name(&device)),
+        ids: devices(host, source)
+            .map(|devices| devices.iter().map(device_name).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn changes(flow: Flow, before: &Snapshot, after: &Snapshot) -> Vec<Notification> {
+    let mut notifications = before
+        .ids
+        .iter()
+        .filter(|id| !after.ids.contains(id))
+        .map(|id| Notification::Unavailable {
+            device_id: id.clone(),
+        })
+        .collect::<Vec<_>>();
+    if after.default_id != before.default_id {
+        notifications.push(Notification::DefaultChanged {
+            flow,
+            device_id: after.default_id.clone().unwrap_or_default(),
+        });
+    }
+    notifications
+}
+
+// Compares the device lists on every poll, for the same notifications WASAPI sends.
+pub(crate) fn watch(mut on_change: impl FnMut(Notification)) -> Result<(), String> {
+    let host = cpal::default_host();
+    let flows = [Flow::Render, Flow::Capture];
+    let mut last = flows.map(|flow| snapshot(&host, flow));
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        for (flow, previous) in flows.into_iter().zip(last.iter_mut()) {
+            let current = snapshot(&host, flow);
+            for notification in changes(flow, previous, &current) {
+                on_change(notification);
+            }
+            *previous = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changes, is_loopback_input, Snapshot};
+    use crate::audio::devices::{Flow, Notification};
+
+    #[test]
+    fn detects_loopback_inputs_and_device_changes() {
+        assert!(is_loopback_input("Monitor of Built-in Audio Analog Stereo"));
+        assert!(is_loopback_input("BlackHole 2ch"));
+        assert!(!is_loopback_input("MacBook Pro Microphone"));
+
+        let before = Snapshot {
+            default_id: Some("Headset".to_string()),
+            ids: vec!["Headset".to_string(), "Speakers".to_string()],
+        };
+        let after = Snapshot {
+            default_id: Some("Speakers".to_string()),
+            ids: vec!["Speakers".to_string()],
+        };
+        let notifications = changes(Flow::Render, &before, &after);
+        assert_eq!(
+            notifications,
+            [
+                Notification::Unavailable {
+                    device_id: "Headset".to_string()
+                },
+                Notification::DefaultChanged {
+                    flow: Flow::Render,
+                    device_id: "Speakers".to_string()
+                },
+            ]
+        );
+        assert!(changes(Flow::Capture, &after, &after).is_empty());
+        assert_eq!(
+            changes(Flow::Capture, &Snapshot::default(), &after).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::audio::convert::FormatAdapter;
+use nnnoiseless::DenoiseState;
+
+// RNNoise takes 10 ms frames of 48 kHz mono audio in the i16 range.
+const MODEL_RATE: u32 = 48_000;
+const FRAME_SIZE: usize = DenoiseState::FRAME_SIZE;
+const I16_SCALE: f32 = 32_768.0;
+
+// Suppresses background noise in interleaved PCM, each channel on its own, keeping the input
+// format. Output trails the input by up to one frame, so a packet may come back shorter.
+pub struct Denoiser {
+    channels: Vec<ChannelDenoiser>,
+}
+
+struct ChannelDenoiser {
+    state: Box<DenoiseState<'static>>,
+    to_model: FormatAdapter,
+    from_model: FormatAdapter,
+    pending: Vec<f32>,
+}
+
+impl Denoiser {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            channels: (0..channels.max(1))
+                .map(|_| ChannelDenoiser {
+                    state: DenoiseState::new(),
+                    to_model: FormatAdapter::new(sample_rate, 1, MODEL_RATE, 1),
+                    from_model: FormatAdapter::new(MODEL_RATE, 1, sample_rate, 1),
+                    pending: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn process(&mut self, pcm: &[f32]) -> Vec<f32> {
+        let count = self.channels.len();
+        let outputs = self
+            .channels
+            .iter_mut()
+            .enumerate()
+            .map(|(channel, denoiser)| {
+                let samples = pcm
+                    .iter()
+                    .skip(channel)
+                    .step_by(count)
+                    .copied()
+                    .collect::<Vec<_>>();
+                denoiser.process(&samples)
+            })
+            .collect::<Vec<_>>();
+        // Every channel sees the same sample counts, so they come back the same length.
+        let frames = outputs.iter().map(Vec::len).min().unwrap_or(0);
+        let mut interleaved = Vec::with_capacity(frames * count);
+        for frame in 0..frames {
+            interleaved.extend(outputs.iter().map(|output| output[frame]));
+        }
+        interleaved
+    }
+}
+
+impl ChannelDenoiser {
+    fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.pending.extend(
+            self.to_model
+                .convert(samples)
+                .into_iter()
+                .map(|sample| sample * I16_SCALE),
+        );
+        let ready = self.pending.len() / FRAME_SIZE * FRAME_SIZE;
+        let mut denoised = vec![0.0f32; ready];
+        for (input, output) in self.pending[..ready]
+            .chunks_exact(FRAME_SIZE)
+            .zip(denoised.chunks_exact_mut(FRAME_SIZE))
+        {
+            self.state.process_frame(output, input);
+        }
+        self.pending.drain(..ready);
+        for sample in denoised.iter_mut() {
+            *sample /= I16_SCALE;
+        }
+        self.from_model.convert(&denoised)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Denoiser, FRAME_SIZE};
+
+    #[test]
+    fn keeps_format_and_channel_alignment() {
+        let mut denoiser = Denoiser::new(48_000, 2);
+        // Half a frame is held back until the rest arrives.
+        assert!(denoiser.process(&vec![0.0; FRAME_SIZE]).is_empty());
+        let output = denoiser.process(&vec![0.0; FRAME_SIZE * 3]);
+        assert_eq!(output.len(), FRAME_SIZE * 4);
+        assert!(output.iter().all(|sample| sample.abs() < 1e-3));
+
+        // 16 kHz audio goes through the model at 48 kHz and comes back at 16 kHz.
+        let mut denoiser = Denoiser::new(16_000, 1);
+        let output = denoiser.process(&vec![0.0; 1_600]);
+        assert!(output.len() > 1_400 && output.len() <= 1_600);
+    }
+}
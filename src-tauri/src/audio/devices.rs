@@ -0,0 +1,242 @@
+use crate::audio::backend;
+use crate::audio::config::{load_config, BackendKind, CaptureSource};
+use crate::audio::manager::CaptureManager;
+use crate::events::{emit_ui_event, AUDIO_DEVICE_CHANGED};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+
+// The endpoints the running capture reads from, two for a dual-source capture.
+static ACTIVE_DEVICES: Lazy<Mutex<Vec<ActiveDevice>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Clone)]
+pub struct ActiveDevice {
+    pub id: String,
+    pub source: CaptureSource,
+    // Opened as the system default rather than picked with `set_capture_device`.
+    pub follows_default: bool,
+}
+
+pub fn set_active_devices(devices: Vec<ActiveDevice>) {
+    if let Ok(mut guard) = ACTIVE_DEVICES.lock() {
+        *guard = devices;
+    }
+}
+
+fn active_devices() -> Vec<ActiveDevice> {
+    ACTIVE_DEVICES
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+    // Chosen in audio.json, or the default when nothing is chosen.
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevices {
+    pub source: CaptureSource,
+    // The backend capture runs on; `auto` resolved for this platform.
+    pub backend: BackendKind,
+    // The saved selection; `None` follows the system default.
+    pub output_device_id: Option<String>,
+    pub input_device_id: Option<String>,
+    // What loopback can record: output endpoints, or monitor inputs for cpal outside Windows.
+    pub outputs: Vec<AudioDevice>,
+    pub inputs: Vec<AudioDevice>,
+    pub capturing: bool,
+    // The endpoints the running capture reads from.
+    pub active_device_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaptureDeviceSelection {
+    pub source: CaptureSource,
+    // `None` follows the system default.
+    pub output_device_id: Option<String>,
+    pub input_device_id: Option<String>,
+}
+
+// Endpoint direction: output devices render, microphones capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Flow {
+    Render,
+    Capture,
+}
+
+impl Flow {
+    pub(crate) fn of(source: CaptureSource) -> Self {
+        match source {
+            CaptureSource::Microphone => Self::Capture,
+            CaptureSource::Loopback | CaptureSource::Dual => Self::Render,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Notification {
+    DefaultChanged { flow: Flow, device_id: String },
+    Unavailable { device_id: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceChange {
+    // `default_changed` or `unavailable` (unplugged, disabled or removed).
+    pub kind: &'static str,
+    // `render` or `capture`; unknown for `unavailable`.
+    pub flow: Option<&'static str>,
+    // Empty when the last device of that flow went away.
+    pub device_id: String,
+    // The capture is running on this device.
+    pub active: bool,
+    pub capturing: bool,
+    // Capture lost a device it picked by id; one on the default device reopens the new default.
+    pub restart_suggested: bool,
+}
+
+// Emits `audio_device_changed` for the lifetime of the app.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    let kind = load_config(&app).backend;
+    thread::spawn(move || {
+        let watched = backend::watch_devices(kind, |notification| {
+            let capturing = app
+                .try_state::<CaptureManager>()
+                .is_some_and(|capture| capture.is_capturing());
+            let change = describe(notification, capturing);
+            info!(
+                "[devices] {} flow={:?} active={} capturing={}",
+                change.kind, change.flow, change.active, capturing
+            );
+            emit_ui_event(&app, AUDIO_DEVICE_CHANGED, change);
+        });
+        if let Err(err) = watched {
+            warn!("[devices] notifications unavailable: {err}");
+        }
+    });
+}
+
+fn describe(notification: Notification, capturing: bool) -> DeviceChange {
+    let active_devices = active_devices();
+    let active_device = |device_id: &str| {
+        active_devices
+            .iter()
+            .find(|device| device.id == device_id)
+            .cloned()
+    };
+    match notification {
+        Notification::DefaultChanged { flow, device_id } => {
+            let active = active_device(&device_id).is_some();
+            let flow = match flow {
+                Flow::Render => "render",
+                Flow::Capture => "capture",
+            };
+            DeviceChange {
+                kind: "default_changed",
+                flow: Some(flow),
+                // A capture on the default device switches by itself; one on a device picked by
+                // id keeps it.
+                restart_suggested: false,
+                device_id,
+                active,
+                capturing,
+            }
+        }
+        Notification::Unavailable { device_id } => {
+            let device = active_device(&device_id);
+            let active = device.is_some();
+            let follows_default = device.is_some_and(|device| device.follows_default);
+            DeviceChange {
+                kind: "unavailable",
+                flow: None,
+                restart_suggested: capturing && active && !follows_default,
+                device_id,
+                active,
+                capturing,
+            }
+        }
+    }
+}
+
+// Enumerated on a blocking thread: command threads may already be initialized for a different
+// COM apartment.
+async fn describe_devices(app: &AppHandle) -> Result<AudioDevices, String> {
+    let config = load_config(app);
+    let source = config.source;
+    let output_device_id = config.output_device_id.clone();
+    let input_device_id = config.input_device_id.clone();
+    let capturing = app
+        .try_state::<CaptureManager>()
+        .is_some_and(|capture| capture.is_capturing());
+    let kind = backend::resolve(config.backend);
+    let (outputs, inputs) = tauri::async_runtime::spawn_blocking(move || {
+        let outputs = backend::endpoints(
+            kind,
+            CaptureSource::Loopback,
+            config.output_device_id.as_deref(),
+        )?;
+        let inputs = backend::endpoints(
+            kind,
+            CaptureSource::Microphone,
+            config.input_device_id.as_deref(),
+        )?;
+        Ok::<_, String>((outputs, inputs))
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(AudioDevices {
+        source,
+        backend: kind,
+        output_device_id,
+        input_device_id,
+        outputs,
+        inputs,
+        capturing,
+        active_device_ids: active_devices()
+            .into_iter()
+            .filter(|_| capturing)
+            .map(|device| device.id)
+            .collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn list_audio_devices(app: AppHandle) -> Result<AudioDevices, String> {
+    describe_devices(&app).await
+}
+
+// Takes effect on the next capture start; a running capture keeps its device.
+#[tauri::command]
+pub async fn set_capture_device(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    selection: CaptureDeviceSelection,
+) -> Result<AudioDevices, String> {
+    let devices = describe_devices(&app).await?;
+    let known = |list: &[AudioDevice], id: &Option<String>| {
+        id.as_deref()
+            .is_none_or(|id| list.iter().any(|device| device.id == id))
+    };
+    if !known(&devices.outputs, &selection.output_device_id) {
+        return Err("output device not found".to_string());
+    }
+    if !known(&devices.inputs, &selection.input_device_id) {
+        return Err("input device not found".to_string());
+    }
+    state.select_devices(
+        &app,
+        selection.source,
+        selection.output_device_id,
+        selection.input_device_id,
+    )?;
+    describe_devices(&app).await
+}
@@ -0,0 +1,266 @@
+use crate::audio::manager::SegmentInfo;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+
+pub const DEFAULT_INDEX_FLUSH_MS: u64 = 500;
+
+const INDEX_FILE: &str = "index.json";
+const INDEX_LOG_FILE: &str = "index.log";
+// Folding the log into index.json is the only full rewrite left, so it only happens once the log
+// has grown past this or when a capture stops.
+const COMPACT_AFTER_BYTES: u64 = 1024 * 1024;
+
+// Serializes appends against compaction so no record lands in a log that is about to be truncated.
+// Taken before `PENDING` whenever both are needed.
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// Records waiting for the next flush, coalesced per segment so a burst of updates to one segment
+// becomes a single line.
+#[derive(Default)]
+struct PendingWrites {
+    by_dir: HashMap<PathBuf, Vec<SegmentInfo>>,
+    flush_scheduled: bool,
+}
+
+static PENDING: Lazy<Mutex<PendingWrites>> = Lazy::new(|| Mutex::new(PendingWrites::default()));
+static FLUSH_INTERVAL_MS: AtomicU64 = AtomicU64::new(DEFAULT_INDEX_FLUSH_MS);
+
+// 0 writes every record straight through.
+pub fn set_flush_interval(ms: u64) {
+    FLUSH_INTERVAL_MS.store(ms, Ordering::SeqCst);
+}
+
+fn index_path(dir: &Path) -> PathBuf {
+    dir.join(INDEX_FILE)
+}
+
+fn log_path(dir: &Path) -> PathBuf {
+    dir.join(INDEX_LOG_FILE)
+}
+
+// index.json is the last compacted snapshot; each line of index.log is a full segment that
+// replaces the entry with the same name, or is appended when the name is new. Records that have
+// not been flushed yet are applied last.
+pub fn load(dir: &Path) -> Vec<SegmentInfo> {
+    let _guard = lock_index();
+    let mut segments = load_from_disk(dir);
+    if let Some(pending) = lock_pending().by_dir.get(dir) {
+        for segment in pending {
+            upsert(&mut segments, segment.clone());
+        }
+    }
+    segments
+}
+
+fn load_from_disk(dir: &Path) -> Vec<SegmentInfo> {
+    let mut segments = fs::read_to_string(index_path(dir))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<SegmentInfo>>(&content).ok())
+        .unwrap_or_default();
+    if let Ok(content) = fs::read_to_string(log_path(dir)) {
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            // A torn last line from a crash is skipped; earlier records are still applied.
+            let Ok(segment) = serde_json::from_str::<SegmentInfo>(line) else {
+                continue;
+            };
+            upsert(&mut segments, segment);
+        }
+    }
+    segments
+}
+
+fn upsert(segments: &mut Vec<SegmentInfo>, segment: SegmentInfo) {
+    match segments.iter_mut().find(|item| item.name == segment.name) {
+        Some(existing) => *existing = segment,
+        None => segments.push(segment),
+    }
+}
+
+pub fn record(dir: &Path, segment: &SegmentInfo) {
+    let interval_ms = FLUSH_INTERVAL_MS.load(Ordering::SeqCst);
+    if interval_ms == 0 {
+        let _guard = lock_index();
+        write_records(dir, std::slice::from_ref(segment));
+        return;
+    }
+    let schedule = {
+        let mut pending = lock_pending();
+        upsert(
+            pending.by_dir.entry(dir.to_path_buf()).or_default(),
+            segment.clone(),
+        );
+        !std::mem::replace(&mut pending.flush_scheduled, true)
+    };
+    if schedule {
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(interval_ms));
+            flush_all();
+        });
+    }
+}
+
+// Writes every pending record now; called by the debounce timer and on app exit.
+pub fn flush_all() {
+    let _guard = lock_index();
+    let by_dir = {
+        let mut pending = lock_pending();
+        pending.flush_scheduled = false;
+        std::mem::take(&mut pending.by_dir)
+    };
+    for (dir, segments) in by_dir {
+        write_records(&dir, &segments);
+    }
+}
+
+// Drops records that have not been written, for a directory that is being wiped.
+pub fn discard_pending(dir: &Path) {
+    let _guard = lock_index();
+    lock_pending().by_dir.remove(dir);
+}
+
+// Flushes pending records for `dir` before folding the log into index.json.
+pub fn compact(dir: &Path) {
+    let _guard = lock_index();
+    flush_locked(dir);
+    compact_locked(dir);
+}
+
+// Applies `change` to the whole index and writes it back as a fresh snapshot, for edits that drop
+// or reorder entries, which the append log cannot express.
+pub fn rewrite(dir: &Path, change: impl FnOnce(&mut Vec<SegmentInfo>)) -> Result<(), String> {
+    let _guard = lock_index();
+    flush_locked(dir);
+    let mut segments = load_from_disk(dir);
+    change(&mut segments);
+    write_snapshot(dir, &segments)?;
+    let _ = fs::remove_file(log_path(dir));
+    Ok(())
+}
+
+fn flush_locked(dir: &Path) {
+    let segments = lock_pending().by_dir.remove(dir);
+    if let Some(segments) = segments {
+        write_records(dir, &segments);
+    }
+}
+
+fn write_records(dir: &Path, segments: &[SegmentInfo]) {
+    if segments.is_empty() {
+        return;
+    }
+    if let Err(err) = append(dir, segments) {
+        warn!("segment index append failed: {err}");
+        return;
+    }
+    let log_len = fs::metadata(log_path(dir))
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    if log_len >= COMPACT_AFTER_BYTES {
+        compact_locked(dir);
+    }
+}
+
+// Rebuilt from disk rather than from memory, which only holds the most recent segments.
+fn compact_locked(dir: &Path) {
+    // No log means nothing changed since the last snapshot.
+    if !log_path(dir).exists() {
+        return;
+    }
+    let snapshot = load_from_disk(dir);
+    if let Err(err) = write_snapshot(dir, &snapshot) {
+        warn!("segment index compaction failed: {err}");
+        return;
+    }
+    let _ = fs::remove_file(log_path(dir));
+}
+
+// One write per flush, however many records it carries.
+fn append(dir: &Path, segments: &[SegmentInfo]) -> Result<(), String> {
+    let mut lines = String::new();
+    for segment in segments {
+        lines.push_str(&serde_json::to_string(segment).map_err(|err| err.to_string())?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(dir))
+        .map_err(|err| err.to_string())?;
+    file.write_all(lines.as_bytes())
+        .map_err(|err| err.to_string())
+}
+
+// Written to a temp file and renamed so a crash never leaves a half-written index.json.
+fn write_snapshot(dir: &Path, segments: &[SegmentInfo]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(segments).map_err(|err| err.to_string())?;
+    let tmp = dir.join(format!("{INDEX_FILE}.tmp"));
+    fs::write(&tmp, content).map_err(|err| err.to_string())?;
+    fs::rename(&tmp, index_path(dir)).map_err(|err| err.to_string())
+}
+
+fn lock_index() -> std::sync::MutexGuard<'static, ()> {
+    match INDEX_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn lock_pending() -> std::sync::MutexGuard<'static, PendingWrites> {
+    match PENDING.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compact, flush_all, load, log_path, record};
+    use crate::audio::manager::SegmentInfo;
+
+    fn segment(name: &str, transcript: Option<&str>) -> SegmentInfo {
+        SegmentInfo {
+            name: name.to_string(),
+            transcript: transcript.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replays_log_over_snapshot_and_compacts() {
+        let dir = std::env::temp_dir().join(format!("ai_shepherd_index_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        record(&dir, &segment("a.wav", None));
+        compact(&dir);
+        assert!(!log_path(&dir).exists());
+        record(&dir, &segment("b.wav", None));
+        record(&dir, &segment("a.wav", Some("hello")));
+        // Readers see records that are still waiting for the debounced flush.
+        assert_eq!(load(&dir)[0].transcript.as_deref(), Some("hello"));
+        flush_all();
+        std::fs::write(
+            log_path(&dir),
+            std::fs::read_to_string(log_path(&dir)).unwrap() + "{\"name\":\"c.w",
+        )
+        .unwrap();
+
+        let loaded = load(&dir);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].transcript.as_deref(), Some("hello"));
+        assert_eq!(loaded[1].name, "b.wav");
+
+        compact(&dir);
+        assert!(!log_path(&dir).exists());
+        assert_eq!(load(&dir).len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::audio::config::CaptureSource;
+use crate::audio::mixer::THEM_CHANNEL;
+use serde::Serialize;
+use std::time::Duration;
+
+pub const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+// Reported for digital silence, and for a loopback that delivered no packets at all.
+pub const FLOOR_DB: f32 = -100.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevel {
+    pub levels: Vec<SourceLevel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceLevel {
+    pub source: CaptureSource,
+    pub rms_db: f32,
+    pub peak_db: f32,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    squares: f64,
+    samples: u64,
+    peak: f32,
+}
+
+// RMS and peak of everything captured since the last report, per source: each channel of a
+// dual-source recording separately, every channel together otherwise.
+pub struct LevelMeter {
+    channels: usize,
+    sources: Vec<(CaptureSource, Accumulator)>,
+}
+
+impl LevelMeter {
+    pub fn new(source: CaptureSource, channels: u16) -> Self {
+        let sources = match source {
+            CaptureSource::Dual => vec![CaptureSource::Microphone, CaptureSource::Loopback],
+            source => vec![source],
+        };
+        Self {
+            channels: channels.max(1) as usize,
+            sources: sources
+                .into_iter()
+                .map(|source| (source, Accumulator::default()))
+                .collect(),
+        }
+    }
+
+    pub fn add(&mut self, pcm: &[f32]) {
+        let dual = self.sources.len() == 2 && self.channels == 2;
+        for (index, sample) in pcm.iter().copied().enumerate() {
+            let slot = usize::from(dual && index % self.channels == THEM_CHANNEL);
+            let accumulator = &mut self.sources[slot].1;
+            accumulator.squares += (sample as f64) * (sample as f64);
+            accumulator.samples += 1;
+            accumulator.peak = accumulator.peak.max(sample.abs());
+        }
+    }
+
+    pub fn take(&mut self) -> AudioLevel {
+        let levels = self
+            .sources
+            .iter_mut()
+            .map(|(source, accumulator)| {
+                let accumulator = std::mem::take(accumulator);
+                let rms = if accumulator.samples == 0 {
+                    0.0
+                } else {
+                    (accumulator.squares / accumulator.samples as f64).sqrt() as f32
+                };
+                SourceLevel {
+                    source: *source,
+                    rms_db: to_dbfs(rms),
+                    peak_db: to_dbfs(accumulator.peak),
+                }
+            })
+            .collect();
+        AudioLevel { levels }
+    }
+}
+
+fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return FLOOR_DB;
+    }
+    (20.0 * amplitude.log10()).max(FLOOR_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LevelMeter, FLOOR_DB};
+    use crate::audio::config::CaptureSource;
+
+    #[test]
+    fn reports_each_source_and_resets() {
+        let mut meter = LevelMeter::new(CaptureSource::Dual, 2);
+        // Microphone at half scale, loopback silent.
+        meter.add(&[0.5, 0.0, -0.5, 0.0]);
+        let level = meter.take();
+        assert_eq!(level.levels.len(), 2);
+        let me = &level.levels[0];
+        assert_eq!(me.source, CaptureSource::Microphone);
+        assert!((me.rms_db + 6.0206).abs() < 1e-3 && (me.peak_db + 6.0206).abs() < 1e-3);
+        assert_eq!(level.levels[1].source, CaptureSource::Loopback);
+        assert_eq!(level.levels[1].rms_db, FLOOR_DB);
+
+        // Nothing captured since the last report reads as silence.
+        assert_eq!(meter.take().levels[0].peak_db, FLOOR_DB);
+
+        let mut single = LevelMeter::new(CaptureSource::Loopback, 2);
+        single.add(&[1.0, 0.0]);
+        let level = single.take();
+        assert_eq!(level.levels.len(), 1);
+        assert_eq!(level.levels[0].peak_db, 0.0);
+        assert!((level.levels[0].rms_db + 3.0103).abs() < 1e-3);
+    }
+}
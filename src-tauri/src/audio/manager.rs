@@ -1,16 +1,73 @@
-use crate::app_config::{load_config as load_app_config, AsrConfig};
+use crate::app_config::{load_config as load_app_config, AsrConfig, QueueConfig};
 use crate::asr::AsrState;
-use crate::audio::config::{ensure_config_file, load_config};
-use crate::audio::speaker::SpeakerDiarizer;
-use crate::audio::wasapi::LoopbackCapture;
-use crate::audio::writer::SegmentWriter;
-use crate::transcribe::{transcribe_file, transcribe_with_whisper_server};
+use crate::audio::alerts::SignalMonitor;
+use crate::audio::backend;
+use crate::audio::coalesce::Coalescer;
+use crate::audio::config::{
+    ensure_config_file, load_config, save_config, AudioConfig, CaptureSource,
+};
+use crate::audio::denoise::Denoiser;
+use crate::audio::devices;
+use crate::audio::index;
+use crate::audio::level::{LevelMeter, LEVEL_INTERVAL};
+use crate::audio::playback::{PlaybackChunk, PlaybackState, SegmentPlayer};
+use crate::audio::queue::{BoundedQueue, OverflowPolicy, QueueStats};
+use crate::audio::recording;
+use crate::audio::recovery;
+use crate::audio::retention::{self, RetentionPolicy, StorageStats};
+use crate::audio::retro::RetroBuffer;
+use crate::audio::ring::{self, RingConsumer};
+use crate::audio::speaker::{SessionDiarizer, SpeakerDiarizer, SwitchDetector};
+use crate::audio::stats::{CaptureCounters, CaptureStats};
+use crate::audio::store::SegmentStore;
+use crate::audio::timing::{self, Stage, StageTimings};
+use crate::audio::vad::{SileroVad, SpeechSpan};
+use crate::audio::watchdog::{InFlight, Pulse, Supervised, WorkerSlot};
+use crate::audio::waveform::{self, Waveform};
+use crate::audio::writer::{self, SegmentFormat, SegmentWriter};
+use crate::cancel::{cancellable, CancelScope};
+use crate::consent::{self, RecordingConsent};
+use crate::defaults::{
+    DEFAULT_AUTO_STOP_ENABLED, DEFAULT_AUTO_STOP_SILENCE_MINUTES, DEFAULT_REFINE_QUEUE_CAPACITY,
+    DEFAULT_REFINE_QUEUE_OVERFLOW, DEFAULT_SEGMENT_TRANSLATE_BATCH_SIZE,
+    DEFAULT_SEGMENT_TRANSLATE_STREAM, DEFAULT_STUCK_WORKER_SECS, DEFAULT_TRANSCRIBE_QUEUE_CAPACITY,
+    DEFAULT_TRANSLATE_QUEUE_CAPACITY, DEFAULT_TRANSLATE_QUEUE_OVERFLOW, DEFAULT_VAD_QUEUE_CAPACITY,
+    DEFAULT_WHISPER_CONTEXT_BOUNDARY_GAP_MS, DEFAULT_WHISPER_CONTEXT_ENABLED,
+    DEFAULT_WHISPER_CONTEXT_MAX_CHARS, DEFAULT_WHISPER_CONTEXT_RESET_SILENCE_MS,
+    DEFAULT_WHISPER_CONTEXT_SHORT_SEGMENT_MS, DEFAULT_WINDOW_QUEUE_CAPACITY,
+    DEFAULT_WINDOW_QUEUE_OVERFLOW, DEFAULT_WINDOW_SHED_DEPTH,
+};
+use crate::events::{
+    emit_output_event, emit_ui_event, AUDIO_ALERT, AUDIO_LEVEL, CAPTURE_ARMED_CHANGED,
+    CAPTURE_DEVICE_CHANGED, CAPTURE_FAILED, CAPTURE_PAUSED_CHANGED, QUEUE_METRICS, SEGMENT_DELETED,
+    SEGMENT_TRANSLATION_CHUNK, SESSION_RETRANSCRIBED, SPEAKERS_REDIARIZED, TRANSCRIPTION_BACKLOG,
+    WORKER_RESTARTED,
+};
+use crate::itn::normalize_transcript;
+use crate::live_draft;
+use crate::pii::{mask_text, mask_transcript, PiiFilter};
+use crate::punctuation::restore_punctuation;
+use crate::realtime_asr::RealtimeStream;
+use crate::runtime;
+use crate::session_archive::chronological_position;
+use crate::templates::active_template;
+use crate::timestamps::{now_rfc3339, rfc3339};
+use crate::transcribe::{
+    transcribe_detailed, transcribe_with_whisper_server, TranscriptWord, Transcription,
+};
 use crate::translate::{
+    fallback_providers, is_english, speaker_translation,
+    target_language as current_target_language, translate_text_batch_streaming,
     translate_text_batch_with_options, BatchTranslationItem, BatchTranslationOptions,
-    TranslateSource,
+    SpeakerTranslation, TranslateSource,
 };
+use crate::translation_cache;
+use crate::two_pass::{Refiner, TwoPassSettings};
+use crate::usage::UsageState;
+use crate::whisper_models::set_whisper_model;
+use crate::word_filter::{apply_to, filter_transcript, filter_translation, WordFilter};
 use chrono::{DateTime, Duration as ChronoDuration, FixedOffset, Local};
-use hound::{SampleFormat, WavSpec, WavWriter};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
@@ -18,21 +75,19 @@ use std::io::BufRead;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, info_span, warn};
 
-const DEFAULT_SEGMENT_TRANSLATE_BATCH_SIZE: usize = 1;
 const TRANSLATION_BATCH_POLL_MS: u64 = 10;
-const DEFAULT_WHISPER_CONTEXT_ENABLED: bool = true;
-const DEFAULT_WHISPER_CONTEXT_MAX_CHARS: usize = 100;
-const DEFAULT_WHISPER_CONTEXT_SHORT_SEGMENT_MS: u64 = 2500;
-const DEFAULT_WHISPER_CONTEXT_BOUNDARY_GAP_MS: u64 = 1200;
-const DEFAULT_WHISPER_CONTEXT_RESET_SILENCE_MS: u64 = 4000;
+const QUEUE_METRICS_INTERVAL_MS: u64 = 1_000;
+const WATCHDOG_INTERVAL_MS: u64 = 5_000;
 const WHISPER_CONTEXT_HISTORY_MULTIPLIER: usize = 3;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SegmentInfo {
     pub name: String,
     pub duration_ms: u64,
@@ -45,10 +100,107 @@ pub struct SegmentInfo {
     pub translation_at: Option<String>,
     pub transcript_ms: Option<u64>,
     pub translation_ms: Option<u64>,
+    // Target language the translation was made in; switching mid-session only affects later ones.
+    #[serde(default)]
+    pub translation_language: Option<String>,
+    // Provider that produced the translation, which differs from the selected one after a fallback.
+    #[serde(default)]
+    pub translation_provider: Option<String>,
     pub speaker_id: Option<u32>,
     pub speaker_changed: Option<bool>,
     pub speaker_similarity: Option<f32>,
     pub speaker_switches_ms: Option<Vec<u64>>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub timings: StageTimings,
+    // ASR language setting at transcription time; `auto` when the model detected it.
+    #[serde(default)]
+    pub transcript_language: Option<String>,
+    #[serde(default)]
+    pub failures: Vec<ProviderFailure>,
+    // `me` or `them` for a dual-source recording: whichever channel was louder.
+    #[serde(default)]
+    pub track: Option<String>,
+    // Speech the VAD found, relative to the start of the file; `None` when it did not run.
+    #[serde(default)]
+    pub speech_spans: Option<Vec<SpeechSpan>>,
+    // Where the segment sits on the meeting timeline, from the moment capture started; `None` for
+    // segments written before offsets were recorded.
+    #[serde(default)]
+    pub start_offset_ms: Option<u64>,
+    #[serde(default)]
+    pub end_offset_ms: Option<u64>,
+    // Timed words from a provider that returns them, e.g. Deepgram; `None` for the others.
+    #[serde(default)]
+    pub words: Option<Vec<TranscriptWord>>,
+    // Set while the transcript is the fast first pass and the accurate one is still to come.
+    #[serde(default)]
+    pub draft: bool,
+}
+
+// A provider call that failed for this segment, kept for the session statistics.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderFailure {
+    // `transcribe` or `translate`.
+    pub stage: String,
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub participants: Vec<String>,
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    pub consent: Option<RecordingConsent>,
+    // Meeting template the session was started with.
+    #[serde(default)]
+    pub template: Option<String>,
+    // `silence` when auto-stop ended the session, `retroactive` for one flushed from the armed
+    // buffer; empty for a manual stop.
+    #[serde(default)]
+    pub end_reason: Option<String>,
+    // ASR language and whisper model fixed at start; segments queued for the session keep them
+    // even if the settings change mid-meeting.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub asr_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub created_at: String,
+    pub offset_ms: u64,
+    pub note: Option<String>,
+}
+
+// Capture ended on its own, e.g. the device was invalidated, rather than through `stop`.
+#[derive(Debug, Clone, Serialize)]
+struct CaptureFailed {
+    error: String,
+}
+
+enum CaptureExit {
+    Stopped,
+    // Nothing above the silence threshold for `autoStop.silenceMinutes`.
+    Silence,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    #[serde(flatten)]
+    pub session: SessionInfo,
+    pub active: bool,
+    pub segment_count: usize,
+    pub duration_ms: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +210,35 @@ struct WindowTask {
     channels: u16,
     window_ms: u64,
     created_at: String,
+    language: Option<String>,
+    cancel: CancellationToken,
+}
+
+#[derive(Debug, Clone)]
+struct TranscribeTask {
+    name: String,
+    queued_at: Instant,
+    // The capture session's; `None` uses the current ASR settings.
+    language: Option<String>,
+    model: Option<String>,
+}
+
+impl InFlight for TranscribeTask {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RefineTask {
+    name: String,
+    // Provider the draft was queued for translation with; `None` when it was not translated.
+    translation: Option<Option<String>>,
+    // Translation generation at the time; a cancel since then means no re-translation.
+    generation: u64,
+    language: Option<String>,
+    // The draft is the provider's English translation (`asr.translateToEnglish`).
+    english: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +246,14 @@ struct VadTask {
     info: SegmentInfo,
     min_transcribe_ms: u64,
     asr_config: AsrConfig,
+    cancel: CancellationToken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptionBacklog {
+    // While `true`, live windows are skipped.
+    behind: bool,
+    depth: usize,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -78,6 +267,17 @@ struct WindowTranscript {
     speaker_mixed: bool,
 }
 
+// A segment translation so far while it streams in, masked and filtered as a whole; each event
+// replaces the text of the previous one, and `segment_translated` follows with the final text.
+// `reset` clears what a failed provider streamed before a fallback provider starts over.
+#[derive(Debug, Clone, Serialize)]
+struct SegmentTranslationChunk {
+    name: String,
+    text: String,
+    target_language: String,
+    reset: bool,
+}
+
 #[derive(Debug, Clone)]
 struct SegmentContextMeta {
     duration_ms: u64,
@@ -217,18 +417,81 @@ impl SpeakerState {
 
 pub struct CaptureManager {
     handle: Mutex<Option<CaptureHandle>>,
-    segments: Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: Arc<SegmentStore>,
+    sessions: Mutex<Vec<SessionInfo>>,
     queues: Mutex<Option<TaskQueues>>,
     translation_pending: Arc<Mutex<HashMap<String, Option<String>>>>,
     speaker_state: Arc<Mutex<SpeakerState>>,
     translation_generation: Arc<AtomicU64>,
     drop_segment_translation: Arc<AtomicBool>,
+    // Runtime switch that skips all translation while capture and transcription keep running.
+    transcription_only: AtomicBool,
+    // `clear` aborts in-flight VAD, transcription and window work; dropping translations aborts
+    // the running LLM request.
+    pipeline_cancel: Arc<CancelScope>,
+    translation_cancel: Arc<CancelScope>,
+    // Buffers audio while capture is stopped, see `arm`.
+    armed: Mutex<Option<ArmedCapture>>,
+    // The segment the output window is playing, one at a time.
+    playback: Mutex<Option<SegmentPlayer>>,
+    // Set once the first capture of this launch has looked for segments a crash left behind.
+    recovered: AtomicBool,
+    capture_counters: Arc<CaptureCounters>,
+    // One batch re-transcription at a time, see `retranscribe_session`.
+    retranscribing: AtomicBool,
 }
 
 struct CaptureHandle {
-    stop: Arc<AtomicBool>,
+    stop: CancellationToken,
+    // Set while paused: audio is read and discarded, everything else keeps running.
+    paused: Arc<AtomicBool>,
     handle: JoinHandle<()>,
     stream: Option<StreamHandle>,
+    // Buffer length to re-arm with once this capture stops, when it was armed before.
+    rearm_seconds: Option<u64>,
+}
+
+struct ArmedCapture {
+    seconds: u64,
+    stop: CancellationToken,
+    // Created by the capture thread once the device format is known.
+    buffer: Arc<Mutex<Option<RetroBuffer>>>,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RediarizeReport {
+    pub session_id: String,
+    pub segment_count: usize,
+    // Segments with enough speech for an embedding; the rest lose their speaker.
+    pub labeled: usize,
+    pub speakers: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetranscribeReport {
+    // `None` when every segment in the index was re-run.
+    pub session_id: Option<String>,
+    pub segment_count: usize,
+    pub transcribed: usize,
+    pub failed: usize,
+    // Segments whose WAV retention already removed; they keep their transcript.
+    pub missing: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RetranscribeProgress {
+    session_id: Option<String>,
+    name: String,
+    done: usize,
+    total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArmedStatus {
+    pub armed: bool,
+    pub seconds: u64,
+    pub buffered_ms: u64,
 }
 
 struct StreamHandle {
@@ -238,26 +501,56 @@ struct StreamHandle {
 
 #[derive(Clone)]
 struct TaskQueues {
-    transcribe_tx: mpsc::Sender<String>,
-    vad_tx: mpsc::Sender<VadTask>,
+    transcribe_queue: Arc<BoundedQueue<TranscribeTask>>,
+    vad_queue: Arc<BoundedQueue<VadTask>>,
     translation_queue: Arc<TranslationQueue>,
     translation_in_flight: Arc<AtomicBool>,
-    window_tx: mpsc::Sender<WindowTask>,
+    window_queue: Arc<BoundedQueue<WindowTask>>,
     window_in_flight: Arc<AtomicBool>,
+    refine_queue: Arc<BoundedQueue<RefineTask>>,
     speaker_state: Arc<Mutex<SpeakerState>>,
+    pipeline_cancel: Arc<CancelScope>,
+    // Transcription backlog at which live windows are shed in favour of finalized segments.
+    window_shed_depth: usize,
+}
+
+impl TaskQueues {
+    fn transcription_behind(&self) -> bool {
+        self.transcribe_queue.len() >= self.window_shed_depth
+    }
+
+    fn stats(&self) -> Vec<QueueStats> {
+        vec![
+            self.vad_queue.stats(),
+            self.transcribe_queue.stats(),
+            self.translation_queue.stats(),
+            self.window_queue.stats(),
+            self.refine_queue.stats(),
+        ]
+    }
 }
 
 #[derive(Debug, Clone)]
 struct TranslationRequest {
     name: String,
     provider: Option<String>,
+    // Resolved per speaker when the batch is picked up.
+    target_language: Option<String>,
     order: usize,
     generation: u64,
+    queued_at: Instant,
+}
+
+impl InFlight for TranslationRequest {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 struct SegmentTranslationBatchConfig {
     size: usize,
+    stream: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -270,104 +563,126 @@ struct CleanedBatchItem {
 struct SegmentTranslationHistory {
     generation: u64,
     provider: Option<String>,
+    target_language: String,
     previous_batch: Vec<CleanedBatchItem>,
 }
 
 struct TranslationQueue {
-    state: Mutex<TranslationQueueState>,
-    cvar: Condvar,
-}
-
-struct TranslationQueueState {
-    items: Vec<TranslationRequest>,
-    pending: HashSet<String>,
+    inner: BoundedQueue<TranslationRequest>,
 }
 
 impl TranslationQueue {
-    fn new() -> Self {
+    fn new(config: Option<&QueueConfig>) -> Self {
         Self {
-            state: Mutex::new(TranslationQueueState {
-                items: Vec::new(),
-                pending: HashSet::new(),
-            }),
-            cvar: Condvar::new(),
+            inner: bounded_queue(
+                "translate",
+                config,
+                DEFAULT_TRANSLATE_QUEUE_CAPACITY,
+                DEFAULT_TRANSLATE_QUEUE_OVERFLOW,
+            ),
         }
     }
 
-    fn push(&self, request: TranslationRequest) {
-        let mut guard = match self.state.lock() {
-            Ok(guard) => guard,
-            Err(_) => return,
-        };
-        if guard.pending.contains(&request.name) {
-            return;
-        }
-        let insert_at = guard
-            .items
-            .iter()
-            .position(|item| request.order < item.order)
-            .unwrap_or(guard.items.len());
-        guard.items.insert(insert_at, request.clone());
-        guard.pending.insert(request.name);
-        self.cvar.notify_one();
+    // Keeps segment order and ignores segments that are already queued.
+    fn push(&self, request: TranslationRequest) -> Option<TranslationRequest> {
+        let name = request.name.clone();
+        let order = request.order;
+        self.inner.insert_with(request, |items| {
+            if items.iter().any(|item| item.name == name) {
+                return None;
+            }
+            Some(
+                items
+                    .iter()
+                    .position(|item| order < item.order)
+                    .unwrap_or(items.len()),
+            )
+        })
     }
 
     fn pop(&self) -> TranslationRequest {
-        let mut guard = match self.state.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        loop {
-            if !guard.items.is_empty() {
-                let request = guard.items.remove(0);
-                guard.pending.remove(&request.name);
-                return request;
-            }
-            guard = match self.cvar.wait(guard) {
-                Ok(guard) => guard,
-                Err(poisoned) => poisoned.into_inner(),
-            };
-        }
+        self.inner.pop()
     }
 
     fn try_pop(&self) -> Option<TranslationRequest> {
-        let mut guard = match self.state.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => poisoned.into_inner(),
-        };
-        if guard.items.is_empty() {
-            return None;
-        }
-        let request = guard.items.remove(0);
-        guard.pending.remove(&request.name);
-        Some(request)
+        self.inner.try_pop()
     }
 
     fn clear(&self) {
-        if let Ok(mut guard) = self.state.lock() {
-            guard.items.clear();
-            guard.pending.clear();
-        }
+        self.inner.clear();
+    }
+
+    fn retain(&self, keep: impl Fn(&TranslationRequest) -> bool) -> usize {
+        self.inner.retain(keep)
     }
 
     fn len(&self) -> usize {
-        match self.state.lock() {
-            Ok(guard) => guard.items.len(),
-            Err(_) => 0,
-        }
+        self.inner.len()
+    }
+
+    fn stats(&self) -> QueueStats {
+        self.inner.stats()
     }
 }
 
+fn bounded_queue<T>(
+    name: &'static str,
+    config: Option<&QueueConfig>,
+    default_capacity: usize,
+    default_overflow: &str,
+) -> BoundedQueue<T> {
+    let (capacity, policy) = queue_settings(config, default_capacity, default_overflow);
+    BoundedQueue::new(name, capacity, policy)
+}
+
+// Holds finalized segments, which are never dropped: a full queue makes the producer wait,
+// whatever `overflow` says.
+fn lossless_queue<T>(
+    name: &'static str,
+    config: Option<&QueueConfig>,
+    default_capacity: usize,
+) -> BoundedQueue<T> {
+    let capacity = config
+        .and_then(|config| config.capacity)
+        .unwrap_or(default_capacity);
+    BoundedQueue::new(name, capacity, OverflowPolicy::Block)
+}
+
+fn queue_settings(
+    config: Option<&QueueConfig>,
+    default_capacity: usize,
+    default_overflow: &str,
+) -> (usize, OverflowPolicy) {
+    let capacity = config
+        .and_then(|config| config.capacity)
+        .unwrap_or(default_capacity);
+    let policy = config
+        .and_then(|config| config.overflow.as_deref())
+        .and_then(OverflowPolicy::parse)
+        .or_else(|| OverflowPolicy::parse(default_overflow))
+        .unwrap_or(OverflowPolicy::DropOldest);
+    (capacity, policy)
+}
+
 impl CaptureManager {
     pub fn new() -> Self {
         Self {
             handle: Mutex::new(None),
-            segments: Arc::new(Mutex::new(Vec::new())),
+            segments: Arc::new(SegmentStore::new()),
+            sessions: Mutex::new(Vec::new()),
             queues: Mutex::new(None),
             translation_pending: Arc::new(Mutex::new(HashMap::new())),
             speaker_state: Arc::new(Mutex::new(SpeakerState::default())),
             translation_generation: Arc::new(AtomicU64::new(0)),
             drop_segment_translation: Arc::new(AtomicBool::new(false)),
+            transcription_only: AtomicBool::new(false),
+            pipeline_cancel: Arc::new(CancelScope::new()),
+            translation_cancel: Arc::new(CancelScope::new()),
+            armed: Mutex::new(None),
+            playback: Mutex::new(None),
+            recovered: AtomicBool::new(false),
+            capture_counters: Arc::new(CaptureCounters::default()),
+            retranscribing: AtomicBool::new(false),
         }
     }
 
@@ -380,9 +695,33 @@ impl CaptureManager {
             return existing.clone();
         }
 
-        let (tx, rx) = mpsc::channel();
-        let (vad_tx, vad_rx) = mpsc::channel();
-        let translation_queue = Arc::new(TranslationQueue::new());
+        let queue_config = load_app_config()
+            .ok()
+            .and_then(|cfg| cfg.queues)
+            .unwrap_or_default();
+        let transcribe_queue = Arc::new(lossless_queue(
+            "transcribe",
+            queue_config.transcribe.as_ref(),
+            DEFAULT_TRANSCRIBE_QUEUE_CAPACITY,
+        ));
+        let vad_queue = Arc::new(lossless_queue(
+            "vad",
+            queue_config.vad.as_ref(),
+            DEFAULT_VAD_QUEUE_CAPACITY,
+        ));
+        let window_queue = Arc::new(bounded_queue(
+            "window",
+            queue_config.window.as_ref(),
+            DEFAULT_WINDOW_QUEUE_CAPACITY,
+            DEFAULT_WINDOW_QUEUE_OVERFLOW,
+        ));
+        let refine_queue = Arc::new(bounded_queue(
+            "refine",
+            queue_config.refine.as_ref(),
+            DEFAULT_REFINE_QUEUE_CAPACITY,
+            DEFAULT_REFINE_QUEUE_OVERFLOW,
+        ));
+        let translation_queue = Arc::new(TranslationQueue::new(queue_config.translate.as_ref()));
         let translation_in_flight = Arc::new(AtomicBool::new(false));
         let segments = Arc::clone(&self.segments);
         let pending = Arc::clone(&self.translation_pending);
@@ -391,31 +730,59 @@ impl CaptureManager {
         let app_handle = app.clone();
         let dir_buf = dir.to_path_buf();
         let translation_queue_clone = Arc::clone(&translation_queue);
-        thread::spawn(move || {
-            run_transcription_worker(
-                app_handle,
-                dir_buf,
-                segments,
-                rx,
-                translation_queue_clone,
-                pending,
-                generation,
-                drop_segment_translation,
-            );
-        });
+        let queue = Arc::clone(&transcribe_queue);
+        let refine = Arc::clone(&refine_queue);
+        let cancel = Arc::clone(&self.pipeline_cancel);
+        let requeue = Arc::clone(&transcribe_queue);
+        // No cancel scope: `pipeline_cancel` also covers queued VAD tasks.
+        let transcription_worker = WorkerSlot::start(
+            "transcribe",
+            None,
+            move |pulse| {
+                let app_handle = app_handle.clone();
+                let dir_buf = dir_buf.clone();
+                let segments = Arc::clone(&segments);
+                let queue = Arc::clone(&queue);
+                let refine = Arc::clone(&refine);
+                let translation_queue = Arc::clone(&translation_queue_clone);
+                let pending = Arc::clone(&pending);
+                let generation = Arc::clone(&generation);
+                let drop_segment_translation = Arc::clone(&drop_segment_translation);
+                let cancel = Arc::clone(&cancel);
+                thread::spawn(move || {
+                    run_transcription_worker(
+                        app_handle,
+                        dir_buf,
+                        segments,
+                        queue,
+                        refine,
+                        translation_queue,
+                        pending,
+                        generation,
+                        drop_segment_translation,
+                        cancel,
+                        pulse,
+                    );
+                })
+            },
+            move |task| {
+                requeue.requeue(task);
+            },
+        );
 
         let app_handle = app.clone();
         let dir_buf = dir.to_path_buf();
         let segments = Arc::clone(&self.segments);
-        let transcribe_tx = tx.clone();
+        let queue = Arc::clone(&vad_queue);
+        let transcribe = Arc::clone(&transcribe_queue);
         let speaker_state = Arc::clone(&self.speaker_state);
         thread::spawn(move || {
             run_vad_worker(
                 app_handle,
                 dir_buf,
                 segments,
-                vad_rx,
-                transcribe_tx,
+                queue,
+                transcribe,
                 speaker_state,
             );
         });
@@ -426,35 +793,95 @@ impl CaptureManager {
         let translation_queue_clone = Arc::clone(&translation_queue);
         let translation_in_flight_clone = Arc::clone(&translation_in_flight);
         let generation = Arc::clone(&self.translation_generation);
+        let cancel = Arc::clone(&self.translation_cancel);
+        let requeue = Arc::clone(&translation_queue);
+        let translation_worker = WorkerSlot::start(
+            "translate",
+            Some(Arc::clone(&self.translation_cancel)),
+            move |pulse| {
+                // A replaced worker may have been stopped mid-batch with the flag still set.
+                translation_in_flight_clone.store(false, Ordering::SeqCst);
+                let app_handle = app_handle.clone();
+                let dir_buf = dir_buf.clone();
+                let segments = Arc::clone(&segments);
+                let queue = Arc::clone(&translation_queue_clone);
+                let in_flight = Arc::clone(&translation_in_flight_clone);
+                let generation = Arc::clone(&generation);
+                let cancel = Arc::clone(&cancel);
+                thread::spawn(move || {
+                    run_translation_worker(
+                        app_handle, dir_buf, segments, queue, in_flight, generation, cancel, pulse,
+                    );
+                })
+            },
+            move |request| {
+                requeue.push(request);
+            },
+        );
+
+        let app_handle = app.clone();
+        let dir_buf = dir.to_path_buf();
+        let segments = Arc::clone(&self.segments);
+        let queue = Arc::clone(&refine_queue);
+        let transcribe = Arc::clone(&transcribe_queue);
+        let translation = Arc::clone(&translation_queue);
+        let generation = Arc::clone(&self.translation_generation);
+        let cancel = Arc::clone(&self.pipeline_cancel);
         thread::spawn(move || {
-            run_translation_worker(
+            run_refine_worker(
                 app_handle,
                 dir_buf,
                 segments,
-                translation_queue_clone,
-                translation_in_flight_clone,
+                queue,
+                transcribe,
+                translation,
                 generation,
+                cancel,
             );
         });
 
-        let (window_tx, window_rx) = mpsc::channel();
         let window_in_flight = Arc::new(AtomicBool::new(false));
         let app_handle = app.clone();
+        let queue = Arc::clone(&window_queue);
         let in_flight = Arc::clone(&window_in_flight);
         let speaker_state = Arc::clone(&self.speaker_state);
         thread::spawn(move || {
-            run_window_worker(app_handle, window_rx, in_flight, speaker_state);
+            run_window_worker(app_handle, queue, in_flight, speaker_state);
         });
 
         let queues = TaskQueues {
-            transcribe_tx: tx,
-            vad_tx,
+            transcribe_queue,
+            vad_queue,
             translation_queue,
             translation_in_flight,
-            window_tx,
+            window_queue,
             window_in_flight,
+            refine_queue,
             speaker_state: Arc::clone(&self.speaker_state),
+            pipeline_cancel: Arc::clone(&self.pipeline_cancel),
+            window_shed_depth: queue_config
+                .window_shed_depth
+                .unwrap_or(DEFAULT_WINDOW_SHED_DEPTH)
+                .max(1),
         };
+        let app_handle = app.clone();
+        let monitored = queues.clone();
+        thread::spawn(move || {
+            run_queue_metrics(app_handle, monitored);
+        });
+        let stuck_after = Duration::from_secs(
+            queue_config
+                .stuck_worker_secs
+                .unwrap_or(DEFAULT_STUCK_WORKER_SECS),
+        );
+        let app_handle = app.clone();
+        thread::spawn(move || {
+            run_watchdog(
+                app_handle,
+                vec![Box::new(transcription_worker), Box::new(translation_worker)],
+                stuck_after,
+            );
+        });
         *guard = Some(queues.clone());
         queues
     }
@@ -475,50 +902,119 @@ impl CaptureManager {
 
         let segments_dir = ensure_segments_dir(&app)?;
         self.drop_segment_translation.store(false, Ordering::SeqCst);
+        // The buffer would compete with the capture for the device.
+        let rearm_seconds = self.disarm(&app).map(|status| status.seconds);
         let config = load_config(&app);
-        let mut asr_config = load_app_config()
-            .ok()
-            .and_then(|cfg| cfg.asr)
-            .unwrap_or_default();
-        if let Some(state) = app.try_state::<AsrState>() {
-            let language = state.language();
-            if !language.trim().is_empty() {
-                asr_config.language = Some(language);
+        // A model picked during the last meeting is loaded before this one starts.
+        if let Some(model) = app
+            .try_state::<AsrState>()
+            .and_then(|state| state.take_next_model())
+        {
+            if let Err(err) = set_whisper_model(app.clone(), model) {
+                warn!("next session model not applied: {err}");
             }
         }
+        let asr_config = session_asr_config(&app);
         ensure_config_file(&app, &config);
 
         let segments = Arc::clone(&self.segments);
-        load_index_if_needed(&segments_dir, &segments);
+        segments.set_cap(config.max_in_memory_segments);
+        index::set_flush_interval(config.index_flush_ms);
+        segments.load_if_needed(&segments_dir);
         let queues = self.ensure_queues(&app, &segments_dir);
-
-        let stop = Arc::new(AtomicBool::new(false));
-        let stop_flag = Arc::clone(&stop);
+        if !self.recovered.swap(true, Ordering::SeqCst) {
+            self.recover_segments(&app, &segments_dir, &queues, &asr_config, &config);
+        }
+        prune_storage(
+            &segments_dir,
+            &segments,
+            RetentionPolicy::from_config(&config),
+            None,
+        );
+        let session = self.begin_session(&segments_dir, Local::now(), &asr_config);
+        let session_id = session.id.clone();
+        live_draft::reset();
+
+        let stop = CancellationToken::new();
+        let stop_flag = stop.clone();
+        let paused = Arc::new(AtomicBool::new(false));
+        let paused_flag = Arc::clone(&paused);
+        let counters = Arc::clone(&self.capture_counters);
         let app_handle = app.clone();
+        // The whisper stream below is started with the same settings.
+        let capture_asr_config = asr_config.clone();
 
         let handle = std::thread::spawn(move || {
-            if let Err(err) = run_capture(
-                app_handle,
+            let result = run_capture(
+                app_handle.clone(),
                 segments_dir,
                 segments,
                 config,
+                capture_asr_config,
                 stop_flag,
+                paused_flag,
                 queues,
-            ) {
-                eprintln!("loopback capture stopped: {err}");
+                session_id,
+                Arc::clone(&counters),
+            );
+            counters.end();
+            devices::set_active_devices(Vec::new());
+            match result {
+                Ok(CaptureExit::Stopped) => {}
+                // `stop` joins this thread, so it has to run on another one.
+                Ok(CaptureExit::Silence) => {
+                    thread::spawn(move || {
+                        let manager = app_handle.state::<CaptureManager>();
+                        if let Err(err) = manager.stop_with_reason(&app_handle, false, "silence") {
+                            warn!("[auto-stop] stop failed: {err}");
+                        }
+                    });
+                }
+                Err(err) => {
+                    error!("loopback capture stopped: {err}");
+                    emit_ui_event(&app_handle, CAPTURE_FAILED, CaptureFailed { error: err });
+                }
             }
         });
 
-        let stream = start_whisper_stream(&app, &asr_config);
+        let stream = start_whisper_stream(&app, &asr_config, Arc::clone(&paused));
         *guard = Some(CaptureHandle {
             stop,
+            paused,
             handle,
             stream,
+            rearm_seconds,
         });
+        if let Some(consent) = &session.consent {
+            consent::announce(consent);
+        }
+        // The usage stats ("this session's cost") start over with each session.
+        if let Some(usage) = UsageState::of(&app) {
+            usage.reset();
+        }
+        emit_ui_event(&app, "session_started", session);
         Ok(())
     }
 
     pub fn stop(&self, app: &AppHandle, drop_translations: bool) -> Result<(), String> {
+        self.stop_session(app, drop_translations, None)
+    }
+
+    pub fn stop_with_reason(
+        &self,
+        app: &AppHandle,
+        drop_translations: bool,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.stop_session(app, drop_translations, Some(reason))
+    }
+
+    fn stop_session(
+        &self,
+        app: &AppHandle,
+        drop_translations: bool,
+        reason: Option<&str>,
+    ) -> Result<(), String> {
         if drop_translations {
             self.drop_pending_translations(app);
         }
@@ -529,7 +1025,7 @@ impl CaptureManager {
         let Some(handle) = guard.take() else {
             return Ok(());
         };
-        handle.stop.store(true, Ordering::SeqCst);
+        handle.stop.cancel();
         let _ = handle.handle.join();
         if let Some(stream) = handle.stream {
             if let Ok(mut child) = stream.child.lock() {
@@ -537,125 +1033,887 @@ impl CaptureManager {
             }
             let _ = stream.reader.join();
         }
+        if let Ok(dir) = ensure_segments_dir(app) {
+            index::compact(&dir);
+            let policy = RetentionPolicy::from_config(&load_config(app));
+            prune_storage(&dir, &self.segments, policy, None);
+        }
+        if let Some(session) = self.end_session(app, reason) {
+            emit_ui_event(app, "session_ended", session);
+        }
+        drop(guard);
+        if let Some(seconds) = handle.rearm_seconds {
+            if let Err(err) = self.arm(app, Some(seconds)) {
+                warn!("[armed] re-arming failed: {err}");
+            }
+        }
         Ok(())
     }
 
-    pub fn is_translation_busy(&self) -> bool {
-        let pending_busy = self
-            .translation_pending
-            .lock()
-            .map(|guard| !guard.is_empty())
-            .unwrap_or(false);
-        if pending_busy {
-            return true;
+    // Keeps the last `seconds` of audio (`retro_buffer_secs` when `None`) in memory while capture
+    // is stopped, for `flush_armed`. Stopped while a capture runs and re-armed after it.
+    pub fn arm(&self, app: &AppHandle, seconds: Option<u64>) -> Result<ArmedStatus, String> {
+        if self.is_capturing() {
+            return Err("capture already running".to_string());
         }
-        let guard = match self.queues.lock() {
-            Ok(guard) => guard,
-            Err(_) => return false,
+        self.disarm(app);
+        let config = load_config(app);
+        let seconds = seconds.unwrap_or(config.retro_buffer_secs).max(1);
+        let stop = CancellationToken::new();
+        let buffer = Arc::new(Mutex::new(None));
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let handle = {
+            let stop = stop.clone();
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || run_armed(config, seconds, stop, buffer, ready_tx))
         };
-        let Some(queues) = guard.as_ref() else {
-            return false;
+        ready_rx
+            .recv()
+            .map_err(|_| "armed capture did not start".to_string())??;
+        info!("[armed] buffering the last {seconds}s");
+        let status = ArmedStatus {
+            armed: true,
+            seconds,
+            buffered_ms: 0,
         };
-        if queues.translation_in_flight.load(Ordering::SeqCst) {
-            return true;
-        }
-        queues.translation_queue.len() > 0
+        *self
+            .armed
+            .lock()
+            .map_err(|_| "armed capture poisoned".to_string())? = Some(ArmedCapture {
+            seconds,
+            stop,
+            buffer,
+            handle,
+        });
+        emit_ui_event(app, CAPTURE_ARMED_CHANGED, status.clone());
+        Ok(status)
     }
 
-    pub fn list(&self, app: AppHandle) -> Result<Vec<SegmentInfo>, String> {
-        let segments_dir = ensure_segments_dir(&app)?;
-        load_index_if_needed(&segments_dir, &self.segments);
-        let guard = self
-            .segments
-            .lock()
-            .map_err(|_| "segment list poisoned".to_string())?;
-        Ok(guard.clone())
+    // Drops the buffered audio; returns the status it had when it was armed.
+    pub fn disarm(&self, app: &AppHandle) -> Option<ArmedStatus> {
+        let armed = self.armed.lock().ok()?.take()?;
+        armed.stop.cancel();
+        let _ = armed.handle.join();
+        info!("[armed] disarmed");
+        let status = ArmedStatus {
+            armed: false,
+            seconds: armed.seconds,
+            buffered_ms: 0,
+        };
+        emit_ui_event(app, CAPTURE_ARMED_CHANGED, status.clone());
+        Some(status)
     }
 
-    pub fn read_segment_bytes(&self, app: AppHandle, name: String) -> Result<Vec<u8>, String> {
-        let segments_dir = ensure_segments_dir(&app)?;
-        let safe_name = Path::new(&name)
-            .file_name()
-            .and_then(|value| value.to_str())
-            .ok_or_else(|| "invalid segment name".to_string())?;
-        if safe_name != name {
-            return Err("invalid segment name".to_string());
+    pub fn armed_status(&self) -> ArmedStatus {
+        let guard = self.armed.lock().ok();
+        let Some(armed) = guard.as_deref().and_then(Option::as_ref) else {
+            return ArmedStatus {
+                armed: false,
+                seconds: 0,
+                buffered_ms: 0,
+            };
+        };
+        let buffered_ms = armed
+            .buffer
+            .lock()
+            .ok()
+            .and_then(|buffer| buffer.as_ref().map(RetroBuffer::duration_ms))
+            .unwrap_or(0);
+        ArmedStatus {
+            armed: true,
+            seconds: armed.seconds,
+            buffered_ms,
         }
-        let path = segments_dir.join(safe_name);
-        fs::read(&path).map_err(|err| err.to_string())
     }
 
-    pub fn clear(&self, app: AppHandle) -> Result<(), String> {
-        self.stop(&app, true)?;
-        let segments_dir = ensure_segments_dir(&app)?;
-        if let Ok(entries) = fs::read_dir(&segments_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    let _ = fs::remove_file(path);
-                }
-            }
-        }
-        if let Ok(mut guard) = self.segments.lock() {
-            guard.clear();
-        }
-        if let Ok(mut guard) = self.translation_pending.lock() {
-            guard.clear();
-        }
-        if let Ok(mut guard) = self.speaker_state.lock() {
-            *guard = SpeakerState::default();
+    // Turns the last `seconds` buffered while armed (all of it when `None`) into a session of its
+    // own, cut every `max_segment_ms` and skipping silent pieces. The buffer keeps running but
+    // starts over. Returns how many segments went to transcription.
+    pub fn flush_armed(&self, app: &AppHandle, seconds: Option<u64>) -> Result<usize, String> {
+        let (started_at, pcm, sample_rate, channels) = {
+            let guard = self
+                .armed
+                .lock()
+                .map_err(|_| "armed capture poisoned".to_string())?;
+            let armed = guard
+                .as_ref()
+                .ok_or_else(|| "capture is not armed".to_string())?;
+            let mut buffer = armed
+                .buffer
+                .lock()
+                .map_err(|_| "armed capture poisoned".to_string())?;
+            let buffer = buffer
+                .as_mut()
+                .ok_or_else(|| "capture is not armed".to_string())?;
+            let (started_at, pcm) = buffer
+                .take(seconds)
+                .ok_or_else(|| "nothing buffered yet".to_string())?;
+            (started_at, pcm, buffer.sample_rate(), buffer.channels())
+        };
+
+        let dir = ensure_segments_dir(app)?;
+        let config = load_config(app);
+        let asr_config = session_asr_config(app);
+        self.segments.load_if_needed(&dir);
+        let queues = self.ensure_queues(app, &dir);
+        let session = self.begin_session(&dir, started_at, &asr_config);
+        if let Some(usage) = UsageState::of(app) {
+            usage.reset();
         }
-        if let Ok(guard) = self.queues.lock() {
-            if let Some(queues) = guard.as_ref() {
-                queues.translation_queue.clear();
+        emit_ui_event(app, "session_started", session.clone());
+
+        let segment_frames =
+            (config.max_segment_ms.saturating_mul(sample_rate as u64) / 1000).max(1);
+        let segment_samples = (segment_frames * channels as u64) as usize;
+        let mut writers = Vec::new();
+        for (index, chunk) in pcm.chunks(segment_samples).enumerate() {
+            if is_silence(chunk, config.silence_threshold_db) {
+                continue;
             }
+            let offset_ms = index as u64 * segment_frames * 1000 / sample_rate.max(1) as u64;
+            let mut writer = SegmentWriter::start_at(
+                &dir,
+                sample_rate,
+                channels,
+                SegmentFormat::from_config(&config),
+                &session.id,
+                started_at + ChronoDuration::milliseconds(offset_ms as i64),
+            )?
+            .with_start_offset(offset_ms);
+            if config.source == CaptureSource::Dual {
+                writer = writer.with_track_energy();
+            }
+            writer.write(chunk)?;
+            writer.close();
+            writers.push(writer);
         }
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("segment_list_cleared", true);
+        let count = writers.len();
+        for writer in writers {
+            finalize_segment(
+                app,
+                &dir,
+                &self.segments,
+                &queues,
+                &asr_config,
+                writer,
+                config.min_transcribe_ms,
+            );
         }
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("live_translation_cleared", true);
+        info!("[armed] flushed {count} segments into {}", session.id);
+        if let Some(session) = self.end_session(app, Some("retroactive")) {
+            emit_ui_event(app, "session_ended", session);
         }
-        Ok(())
+        Ok(count)
     }
 
-    pub fn translate_segment(
+    // Saved to audio.json and used from the next capture start; `None` follows the system default.
+    pub fn select_devices(
         &self,
-        app: AppHandle,
-        name: String,
-        provider: Option<String>,
+        app: &AppHandle,
+        source: CaptureSource,
+        output_device_id: Option<String>,
+        input_device_id: Option<String>,
     ) -> Result<(), String> {
-        let segments_dir = ensure_segments_dir(&app)?;
-        let safe_name = Path::new(&name)
-            .file_name()
-            .and_then(|value| value.to_str())
-            .ok_or_else(|| "invalid segment name".to_string())?;
-        if safe_name != name {
-            return Err("invalid segment name".to_string());
-        }
-        let queues = self.ensure_queues(&app, &segments_dir);
-        let provider = provider.filter(|value| !value.trim().is_empty());
-        if self.drop_segment_translation.load(Ordering::SeqCst) {
-            return Ok(());
-        }
+        let mut config = load_config(app);
+        config.source = source;
+        config.output_device_id = output_device_id;
+        config.input_device_id = input_device_id;
+        save_config(app, &config)?;
+        info!(
+            "[devices] selected source={source:?} output={:?} input={:?} capturing={}",
+            config.output_device_id,
+            config.input_device_id,
+            self.is_capturing()
+        );
+        Ok(())
+    }
 
-        let transcript_ready = {
-            let guard = self.segments.lock().ok();
-            guard
-                .as_ref()
-                .and_then(|segments| {
-                    segments
-                        .iter()
-                        .find(|segment| segment.name == name)
-                        .and_then(|segment| segment.transcript.as_ref())
-                })
-                .is_some()
-        };
+    // Audio captured while paused is discarded and the open segment ends; the session, workers,
+    // whisper-server and queued segments carry on.
+    pub fn pause(&self, app: &AppHandle) -> Result<(), String> {
+        self.set_paused(app, true)
+    }
 
-        if transcript_ready {
-            enqueue_translation(
-                &queues.translation_queue,
-                &self.segments,
+    pub fn resume(&self, app: &AppHandle) -> Result<(), String> {
+        self.set_paused(app, false)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.handle
+            .lock()
+            .map(|guard| {
+                guard
+                    .as_ref()
+                    .is_some_and(|handle| handle.paused.load(Ordering::SeqCst))
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn capture_stats(&self) -> CaptureStats {
+        let queues = self
+            .queues
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(TaskQueues::stats))
+            .unwrap_or_default();
+        self.capture_counters.snapshot(self.is_paused(), queues)
+    }
+
+    fn set_paused(&self, app: &AppHandle, paused: bool) -> Result<(), String> {
+        let guard = self
+            .handle
+            .lock()
+            .map_err(|_| "capture state poisoned".to_string())?;
+        let Some(handle) = guard.as_ref().filter(|handle| !handle.handle.is_finished()) else {
+            return Err("capture is not running".to_string());
+        };
+        if handle.paused.swap(paused, Ordering::SeqCst) == paused {
+            return Ok(());
+        }
+        info!("[capture] {}", if paused { "paused" } else { "resumed" });
+        emit_ui_event(app, CAPTURE_PAUSED_CHANGED, paused);
+        Ok(())
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.handle
+            .lock()
+            .map(|guard| {
+                guard
+                    .as_ref()
+                    .is_some_and(|handle| !handle.handle.is_finished())
+            })
+            .unwrap_or(false)
+    }
+
+    // Segments a crash left unfinished or unindexed get their headers fixed and go through VAD and
+    // transcription like new ones, under the session they were recorded in.
+    fn recover_segments(
+        &self,
+        app: &AppHandle,
+        dir: &Path,
+        queues: &TaskQueues,
+        asr_config: &AsrConfig,
+        config: &AudioConfig,
+    ) {
+        let indexed = index::load(dir)
+            .into_iter()
+            .map(|segment| segment.name)
+            .collect::<HashSet<_>>();
+        let report = recovery::recover(dir, &indexed);
+        if report.segments.is_empty() && report.recordings == 0 {
+            return;
+        }
+        info!(
+            "[recovery] salvaged {} segments, repaired {} recordings",
+            report.segments.len(),
+            report.recordings
+        );
+        self.load_sessions_if_needed(dir);
+        let sessions = self
+            .sessions
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default();
+        // A long backlog fills the transcription queue, and waiting for room would hold up the
+        // new capture.
+        let app = app.clone();
+        let dir = dir.to_path_buf();
+        let segments = Arc::clone(&self.segments);
+        let queues = queues.clone();
+        let asr_config = asr_config.clone();
+        let min_transcribe_ms = config.min_transcribe_ms;
+        thread::spawn(move || {
+            for mut info in report.segments {
+                info.session_id = session_at(&sessions, &info.created_at);
+                dispatch_segment(
+                    &app,
+                    &dir,
+                    &segments,
+                    &queues,
+                    &asr_config,
+                    info,
+                    min_transcribe_ms,
+                );
+            }
+        });
+    }
+
+    pub fn storage_stats(&self, app: &AppHandle) -> Result<StorageStats, String> {
+        let dir = ensure_segments_dir(app)?;
+        Ok(retention::stats(&dir, &load_config(app)))
+    }
+
+    pub fn list_sessions(&self, app: AppHandle) -> Result<Vec<SessionSummary>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        self.load_sessions_if_needed(&segments_dir);
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| "session list poisoned".to_string())?
+            .clone();
+        let segments = self.segments.list_all(&segments_dir);
+        Ok(sessions
+            .into_iter()
+            .map(|session| {
+                let (segment_count, duration_ms) = segments
+                    .iter()
+                    .filter(|segment| segment.session_id.as_deref() == Some(session.id.as_str()))
+                    .fold((0, 0), |(count, total), segment| {
+                        (count + 1, total + segment.duration_ms)
+                    });
+                SessionSummary {
+                    active: session.ended_at.is_none(),
+                    session,
+                    segment_count,
+                    duration_ms,
+                }
+            })
+            .collect())
+    }
+
+    pub fn list_session_segments(
+        &self,
+        app: AppHandle,
+        session_id: &str,
+    ) -> Result<Vec<SegmentInfo>, String> {
+        Ok(self
+            .list(app)?
+            .into_iter()
+            .filter(|segment| segment.session_id.as_deref() == Some(session_id))
+            .collect())
+    }
+
+    // Re-assigns `speaker_id` on every segment of an ended session from embeddings clustered over
+    // the whole meeting, which is steadier than the live per-window guess.
+    pub fn rediarize_session(
+        &self,
+        app: &AppHandle,
+        session_id: &str,
+    ) -> Result<RediarizeReport, String> {
+        let dir = ensure_segments_dir(app)?;
+        self.ensure_session_ended(&dir, session_id)?;
+        let mut diarizer = SessionDiarizer::new(app)
+            .ok_or_else(|| "speaker diarization is off or its model is missing".to_string())?;
+
+        let segments = self.list_session_segments(app.clone(), session_id)?;
+        let embeddings = segments
+            .iter()
+            .map(|segment| {
+                read_segment(&dir.join(&segment.name))
+                    .and_then(|(samples, spec)| {
+                        diarizer.embed(&samples, spec.sample_rate, spec.channels)
+                    })
+                    .map_err(|err| warn!("[rediarize] {} skipped: {err}", segment.name))
+                    .ok()
+                    .flatten()
+            })
+            .collect::<Vec<_>>();
+
+        let mut previous = None;
+        let mut labeled = 0;
+        let mut speakers = 0;
+        for (segment, assigned) in segments.iter().zip(diarizer.assign(&embeddings)) {
+            let speaker_id = assigned.map(|(id, _)| id);
+            let changed = speaker_id.is_some() && previous.is_some() && speaker_id != previous;
+            self.segments.update(&dir, &segment.name, |info| {
+                info.speaker_id = speaker_id;
+                info.speaker_similarity = assigned.map(|(_, similarity)| similarity);
+                info.speaker_changed = Some(changed);
+            });
+            if let Some(id) = speaker_id {
+                labeled += 1;
+                speakers = speakers.max(id);
+                previous = speaker_id;
+            }
+        }
+        let report = RediarizeReport {
+            session_id: session_id.to_string(),
+            segment_count: segments.len(),
+            labeled,
+            speakers,
+        };
+        info!(
+            "[rediarize] {session_id}: {labeled}/{} segments, {speakers} speakers",
+            segments.len()
+        );
+        emit_ui_event(app, SPEAKERS_REDIARIZED, report.clone());
+        Ok(report)
+    }
+
+    fn ensure_session_ended(&self, dir: &Path, session_id: &str) -> Result<(), String> {
+        self.load_sessions_if_needed(dir);
+        let ended = self
+            .sessions
+            .lock()
+            .map_err(|_| "session list poisoned".to_string())?
+            .iter()
+            .find(|item| item.id == session_id)
+            .map(|item| item.ended_at.is_some())
+            .ok_or_else(|| format!("session not found: {session_id}"))?;
+        if !ended {
+            return Err(format!("session is still recording: {session_id}"));
+        }
+        Ok(())
+    }
+
+    pub fn label_session(
+        &self,
+        app: &AppHandle,
+        session_id: &str,
+        title: String,
+        participants: Vec<String>,
+    ) -> Result<SessionInfo, String> {
+        let dir = ensure_segments_dir(app)?;
+        self.load_sessions_if_needed(&dir);
+        let updated = {
+            let mut guard = self
+                .sessions
+                .lock()
+                .map_err(|_| "session list poisoned".to_string())?;
+            let session = guard
+                .iter_mut()
+                .find(|item| item.id == session_id)
+                .ok_or_else(|| format!("session not found: {session_id}"))?;
+            session.title = Some(title);
+            session.participants = participants;
+            let updated = session.clone();
+            save_sessions(&dir, &guard)?;
+            updated
+        };
+        emit_ui_event(app, "session_updated", updated.clone());
+        Ok(updated)
+    }
+
+    // Drops an ended session with its segments and audio files, e.g. once it has been archived.
+    // Returns how many segments were removed.
+    pub fn remove_session(&self, app: &AppHandle, session_id: &str) -> Result<usize, String> {
+        let dir = ensure_segments_dir(app)?;
+        self.load_sessions_if_needed(&dir);
+        {
+            let mut guard = self
+                .sessions
+                .lock()
+                .map_err(|_| "session list poisoned".to_string())?;
+            let position = guard
+                .iter()
+                .position(|item| item.id == session_id)
+                .ok_or_else(|| format!("session not found: {session_id}"))?;
+            if guard[position].ended_at.is_none() {
+                return Err(format!("session is still recording: {session_id}"));
+            }
+            guard.remove(position);
+            save_sessions(&dir, &guard)?;
+        }
+        let mut removed = Vec::new();
+        let mut transcripts = Vec::new();
+        index::rewrite(&dir, |segments| {
+            segments.retain(|segment| {
+                let keep = segment.session_id.as_deref() != Some(session_id);
+                if !keep {
+                    removed.push(segment.name.clone());
+                    transcripts.extend(segment.transcript.clone());
+                }
+                keep
+            });
+        })?;
+        self.segments.reload(&dir);
+        translation_cache::forget(transcripts.iter().map(String::as_str));
+        for name in &removed {
+            let _ = fs::remove_file(dir.join(name));
+        }
+        let _ = fs::remove_file(recording::recording_path(&dir, session_id));
+        emit_ui_event(
+            app,
+            "session_removed",
+            serde_json::json!({ "session_id": session_id, "segments": removed.len() }),
+        );
+        Ok(removed.len())
+    }
+
+    // Puts a session back in time order together with its segments; their audio files have to be
+    // in the segments directory already.
+    pub fn restore_session(
+        &self,
+        app: &AppHandle,
+        session: SessionInfo,
+        segments: Vec<SegmentInfo>,
+    ) -> Result<(), String> {
+        let dir = ensure_segments_dir(app)?;
+        self.load_sessions_if_needed(&dir);
+        {
+            let mut guard = self
+                .sessions
+                .lock()
+                .map_err(|_| "session list poisoned".to_string())?;
+            if guard.iter().any(|item| item.id == session.id) {
+                return Err(format!("session already exists: {}", session.id));
+            }
+            let started = guard
+                .iter()
+                .map(|item| item.started_at.as_str())
+                .collect::<Vec<_>>();
+            let position = chronological_position(&started, &session.started_at);
+            guard.insert(position, session.clone());
+            save_sessions(&dir, &guard)?;
+        }
+        index::rewrite(&dir, |existing| {
+            existing.retain(|segment| !segments.iter().any(|item| item.name == segment.name));
+            let created = existing
+                .iter()
+                .map(|segment| segment.created_at.as_str())
+                .collect::<Vec<_>>();
+            let first = segments
+                .first()
+                .map(|segment| segment.created_at.as_str())
+                .unwrap_or(&session.started_at);
+            let position = chronological_position(&created, first);
+            existing.splice(position..position, segments);
+        })?;
+        self.segments.reload(&dir);
+        emit_ui_event(app, "session_restored", session);
+        Ok(())
+    }
+
+    // Marks the current moment of the running session, e.g. from a Stream Deck button.
+    pub fn add_bookmark(&self, app: &AppHandle, note: Option<String>) -> Result<Bookmark, String> {
+        let dir = ensure_segments_dir(app)?;
+        self.load_sessions_if_needed(&dir);
+        let (session_id, bookmark) = {
+            let mut guard = self
+                .sessions
+                .lock()
+                .map_err(|_| "session list poisoned".to_string())?;
+            let session = guard
+                .iter_mut()
+                .rev()
+                .find(|item| item.ended_at.is_none())
+                .ok_or_else(|| "no active session".to_string())?;
+            let now = Local::now();
+            let offset_ms = DateTime::parse_from_rfc3339(&session.started_at)
+                .map(|started| (now.fixed_offset() - started).num_milliseconds().max(0) as u64)
+                .unwrap_or(0);
+            let bookmark = Bookmark {
+                created_at: rfc3339(&now),
+                offset_ms,
+                note: note.filter(|value| !value.trim().is_empty()),
+            };
+            session.bookmarks.push(bookmark.clone());
+            let session_id = session.id.clone();
+            save_sessions(&dir, &guard)?;
+            (session_id, bookmark)
+        };
+        emit_ui_event(
+            app,
+            "bookmark_added",
+            serde_json::json!({ "session_id": session_id, "bookmark": bookmark }),
+        );
+        Ok(bookmark)
+    }
+
+    fn load_sessions_if_needed(&self, dir: &Path) {
+        let Ok(mut guard) = self.sessions.lock() else {
+            return;
+        };
+        if guard.is_empty() {
+            *guard = load_sessions(dir);
+        }
+    }
+
+    fn begin_session(&self, dir: &Path, now: DateTime<Local>, asr: &AsrConfig) -> SessionInfo {
+        self.load_sessions_if_needed(dir);
+        let started_at = rfc3339(&now);
+        let template = active_template();
+        let session = SessionInfo {
+            id: format!("session_{}", now.format("%Y%m%d_%H%M%S_%3f")),
+            consent: consent::for_new_session(&started_at),
+            started_at,
+            ended_at: None,
+            title: template.as_ref().and_then(|template| template.name.clone()),
+            participants: Vec::new(),
+            bookmarks: Vec::new(),
+            template: template.map(|template| template.id),
+            end_reason: None,
+            language: asr.language.clone(),
+            asr_model: asr.whisper_cpp_model_path.clone(),
+        };
+        if let Ok(mut guard) = self.sessions.lock() {
+            // A crash can leave a session open; close it at its own start time.
+            for stale in guard.iter_mut().filter(|item| item.ended_at.is_none()) {
+                stale.ended_at = Some(stale.started_at.clone());
+            }
+            guard.push(session.clone());
+            let _ = save_sessions(dir, &guard);
+        }
+        session
+    }
+
+    fn end_session(&self, app: &AppHandle, reason: Option<&str>) -> Option<SessionInfo> {
+        let dir = ensure_segments_dir(app).ok()?;
+        let mut guard = self.sessions.lock().ok()?;
+        let session = guard
+            .iter_mut()
+            .rev()
+            .find(|item| item.ended_at.is_none())?;
+        session.ended_at = Some(now_rfc3339());
+        session.end_reason = reason.map(str::to_string);
+        let ended = session.clone();
+        let _ = save_sessions(&dir, &guard);
+        Some(ended)
+    }
+
+    pub fn is_translation_busy(&self) -> bool {
+        let pending_busy = self
+            .translation_pending
+            .lock()
+            .map(|guard| !guard.is_empty())
+            .unwrap_or(false);
+        if pending_busy {
+            return true;
+        }
+        let guard = match self.queues.lock() {
+            Ok(guard) => guard,
+            Err(_) => return false,
+        };
+        let Some(queues) = guard.as_ref() else {
+            return false;
+        };
+        if queues.translation_in_flight.load(Ordering::SeqCst) {
+            return true;
+        }
+        queues.translation_queue.len() > 0
+    }
+
+    pub fn list(&self, app: AppHandle) -> Result<Vec<SegmentInfo>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.list_all(&segments_dir))
+    }
+
+    pub fn segment_count(&self, app: AppHandle) -> Result<usize, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.count())
+    }
+
+    pub fn find_segment(
+        &self,
+        app: AppHandle,
+        name: &str,
+    ) -> Result<Option<Arc<SegmentInfo>>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.find(&segments_dir, name))
+    }
+
+    pub fn recent_segments(
+        &self,
+        app: AppHandle,
+        limit: usize,
+    ) -> Result<Vec<Arc<SegmentInfo>>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.recent(&segments_dir, limit))
+    }
+
+    pub fn latest_segment(
+        &self,
+        app: AppHandle,
+        predicate: impl Fn(&SegmentInfo) -> bool,
+    ) -> Result<Option<Arc<SegmentInfo>>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.latest(&segments_dir, predicate))
+    }
+
+    // Pages back through history without keeping it in memory; `before` is the oldest segment the
+    // caller already has.
+    pub fn list_page(
+        &self,
+        app: AppHandle,
+        before: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<SegmentInfo>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        self.segments.load_if_needed(&segments_dir);
+        Ok(self.segments.page(&segments_dir, before.as_deref(), limit))
+    }
+
+    pub fn read_segment_bytes(&self, app: AppHandle, name: String) -> Result<Vec<u8>, String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        fs::read(segment_path(&segments_dir, &name)?).map_err(|err| err.to_string())
+    }
+
+    pub fn segment_waveform(
+        &self,
+        app: &AppHandle,
+        name: &str,
+        buckets: usize,
+    ) -> Result<Waveform, String> {
+        let segments_dir = ensure_segments_dir(app)?;
+        waveform::load(&segment_path(&segments_dir, name)?, name, buckets)
+    }
+
+    // Replaces whatever was playing.
+    pub fn open_playback(&self, app: &AppHandle, name: &str) -> Result<PlaybackState, String> {
+        let segments_dir = ensure_segments_dir(app)?;
+        let player = SegmentPlayer::open(&segment_path(&segments_dir, name)?, name)?;
+        let state = player.state();
+        *self
+            .playback
+            .lock()
+            .map_err(|_| "playback poisoned".to_string())? = Some(player);
+        Ok(state)
+    }
+
+    pub fn read_playback(&self, max_ms: u64) -> Result<PlaybackChunk, String> {
+        self.with_player(|player| player.read(max_ms))
+    }
+
+    pub fn seek_playback(&self, position_ms: u64) -> Result<PlaybackState, String> {
+        self.with_player(|player| player.seek(position_ms))
+    }
+
+    pub fn playback_state(&self) -> Option<PlaybackState> {
+        let guard = self.playback.lock().ok()?;
+        guard.as_ref().map(SegmentPlayer::state)
+    }
+
+    pub fn close_playback(&self) {
+        if let Ok(mut guard) = self.playback.lock() {
+            *guard = None;
+        }
+    }
+
+    fn with_player<T>(
+        &self,
+        action: impl FnOnce(&mut SegmentPlayer) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut guard = self
+            .playback
+            .lock()
+            .map_err(|_| "playback poisoned".to_string())?;
+        let player = guard
+            .as_mut()
+            .ok_or_else(|| "no segment is open for playback".to_string())?;
+        action(player)
+    }
+
+    // Removes one segment: its audio, its index entry and any transcription or translation still
+    // queued for it. Work already in flight finds the segment gone and is dropped.
+    pub fn delete_segment(&self, app: &AppHandle, name: &str) -> Result<(), String> {
+        let segments_dir = ensure_segments_dir(app)?;
+        let path = segment_path(&segments_dir, name)?;
+        let Some(segment) = self.segments.find(&segments_dir, name) else {
+            return Err(format!("segment not found: {name}"));
+        };
+        if let Ok(guard) = self.queues.lock() {
+            if let Some(queues) = guard.as_ref() {
+                queues.transcribe_queue.retain(|task| task.name != name);
+                queues.refine_queue.retain(|task| task.name != name);
+                queues
+                    .translation_queue
+                    .retain(|request| request.name != name);
+            }
+        }
+        if let Ok(mut guard) = self.translation_pending.lock() {
+            guard.remove(name);
+        }
+        if let Ok(mut guard) = self.playback.lock() {
+            if guard.as_ref().is_some_and(|player| player.name() == name) {
+                *guard = None;
+            }
+        }
+        index::rewrite(&segments_dir, |segments| {
+            segments.retain(|segment| segment.name != name);
+        })?;
+        self.segments.reload(&segments_dir);
+        translation_cache::forget(segment.transcript.as_deref());
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => warn!("failed to remove {name}: {err}"),
+        }
+        emit_ui_event(app, SEGMENT_DELETED, serde_json::json!({ "name": name }));
+        Ok(())
+    }
+
+    pub fn clear(&self, app: AppHandle) -> Result<(), String> {
+        self.stop(&app, true)?;
+        self.close_playback();
+        self.pipeline_cancel.cancel();
+        if let Ok(guard) = self.queues.lock() {
+            if let Some(queues) = guard.as_ref() {
+                queues.vad_queue.clear();
+                queues.transcribe_queue.clear();
+                queues.refine_queue.clear();
+                queues.window_queue.clear();
+                queues.window_in_flight.store(false, Ordering::SeqCst);
+            }
+        }
+        let segments_dir = ensure_segments_dir(&app)?;
+        index::discard_pending(&segments_dir);
+        if let Ok(entries) = fs::read_dir(&segments_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+        self.segments.clear();
+        translation_cache::clear_translation_cache();
+        if let Ok(mut guard) = self.sessions.lock() {
+            guard.clear();
+        }
+        if let Ok(mut guard) = self.translation_pending.lock() {
+            guard.clear();
+        }
+        if let Ok(mut guard) = self.speaker_state.lock() {
+            *guard = SpeakerState::default();
+        }
+        if let Ok(guard) = self.queues.lock() {
+            if let Some(queues) = guard.as_ref() {
+                queues.translation_queue.clear();
+            }
+        }
+        emit_ui_event(&app, "segment_list_cleared", true);
+        emit_ui_event(&app, "live_translation_cleared", true);
+        Ok(())
+    }
+
+    pub fn translate_segment(
+        &self,
+        app: AppHandle,
+        name: String,
+        provider: Option<String>,
+    ) -> Result<(), String> {
+        let segments_dir = ensure_segments_dir(&app)?;
+        let safe_name = Path::new(&name)
+            .file_name()
+            .and_then(|value| value.to_str())
+            .ok_or_else(|| "invalid segment name".to_string())?;
+        if safe_name != name {
+            return Err("invalid segment name".to_string());
+        }
+        let queues = self.ensure_queues(&app, &segments_dir);
+        let provider = provider.filter(|value| !value.trim().is_empty());
+        if self.drop_segment_translation.load(Ordering::SeqCst) || self.is_transcription_only() {
+            return Ok(());
+        }
+
+        let transcript_ready = self
+            .segments
+            .find(&segments_dir, &name)
+            .is_some_and(|segment| segment.transcript.is_some());
+
+        if transcript_ready {
+            enqueue_translation(
+                &app,
+                &segments_dir,
+                &queues.translation_queue,
+                &self.segments,
                 &self.translation_generation,
                 name,
                 provider,
@@ -666,9 +1924,162 @@ impl CaptureManager {
         Ok(())
     }
 
+    // Runs ASR again on a kept segment, optionally with another provider or model. Only the
+    // transcript changes; the old translation stays until the segment is translated again.
+    pub async fn retranscribe_segment(
+        &self,
+        app: &AppHandle,
+        name: &str,
+        provider: Option<String>,
+        model: Option<String>,
+    ) -> Result<SegmentInfo, String> {
+        let segments_dir = ensure_segments_dir(app)?;
+        let path = segment_path(&segments_dir, name)?;
+        if self.segments.find(&segments_dir, name).is_none() {
+            return Err("segment not found".to_string());
+        }
+        let provider = provider.filter(|value| !value.trim().is_empty());
+        let started_at = Instant::now();
+        let result = transcribe_detailed(
+            app,
+            &path,
+            None,
+            provider.as_deref(),
+            model.as_deref(),
+            None,
+        )
+        .await;
+        let text = match result {
+            Ok(transcription) => {
+                apply_words(app, &segments_dir, &self.segments, name, &transcription);
+                transcription.text
+            }
+            Err(err) => {
+                warn!("re-transcription failed for {name}: {err}");
+                let provider = provider.unwrap_or_else(|| {
+                    app.try_state::<AsrState>()
+                        .map(|state| state.provider())
+                        .unwrap_or_default()
+                });
+                record_failure(&segments_dir, &self.segments, name, "transcribe", provider);
+                return Err(err);
+            }
+        };
+        let text = restore_punctuation(app, text, None).await;
+        let text = normalize_transcript(app, &text, None);
+        let text = mask_transcript(app, text).await;
+        let transcript = filter_transcript(app, &text);
+        let elapsed_ms = started_at.elapsed().as_millis() as u64;
+        apply_transcript(
+            app,
+            &segments_dir,
+            &self.segments,
+            name,
+            Some(transcript),
+            elapsed_ms,
+            None,
+            None,
+        );
+        self.segments
+            .find(&segments_dir, name)
+            .map(|info| (*info).clone())
+            .ok_or_else(|| "segment not found".to_string())
+    }
+
+    // Re-runs ASR on every kept segment of an ended session, or of the whole index without a
+    // session, with the current provider, model and language, e.g. after fixing the language or
+    // upgrading the model mid-project. Segments go one at a time like `retranscribe_segment`.
+    pub async fn retranscribe_session(
+        &self,
+        app: &AppHandle,
+        session_id: Option<&str>,
+    ) -> Result<RetranscribeReport, String> {
+        let dir = ensure_segments_dir(app)?;
+        let segments = match session_id {
+            Some(session_id) => {
+                self.ensure_session_ended(&dir, session_id)?;
+                self.list_session_segments(app.clone(), session_id)?
+            }
+            None if self.is_capturing() => {
+                return Err("stop capture before re-transcribing every segment".to_string());
+            }
+            None => self.list(app.clone())?,
+        };
+        if self.retranscribing.swap(true, Ordering::SeqCst) {
+            return Err("a re-transcription is already running".to_string());
+        }
+        let segment_count = segments.len();
+        let kept = segments
+            .into_iter()
+            .filter(|segment| dir.join(&segment.name).is_file())
+            .collect::<Vec<_>>();
+        let total = kept.len();
+        let mut transcribed = 0;
+        for (index, segment) in kept.iter().enumerate() {
+            // Failures are logged and recorded on the segment.
+            if self
+                .retranscribe_segment(app, &segment.name, None, None)
+                .await
+                .is_ok()
+            {
+                transcribed += 1;
+            }
+            emit_output_event(
+                app,
+                "session_retranscribe_progress",
+                RetranscribeProgress {
+                    session_id: session_id.map(str::to_string),
+                    name: segment.name.clone(),
+                    done: index + 1,
+                    total,
+                },
+            );
+        }
+        self.retranscribing.store(false, Ordering::SeqCst);
+
+        let report = RetranscribeReport {
+            session_id: session_id.map(str::to_string),
+            segment_count,
+            transcribed,
+            failed: total - transcribed,
+            missing: segment_count - total,
+        };
+        info!(
+            "[retranscribe] {}: {transcribed}/{total} segments, {} missing",
+            session_id.unwrap_or("all"),
+            report.missing
+        );
+        emit_ui_event(app, SESSION_RETRANSCRIBED, report.clone());
+        Ok(report)
+    }
+
+    pub fn is_transcription_only(&self) -> bool {
+        self.transcription_only.load(Ordering::SeqCst)
+    }
+
+    pub fn set_transcription_only(&self, app: &AppHandle, enabled: bool) {
+        let previous = self.transcription_only.swap(enabled, Ordering::SeqCst);
+        if previous == enabled {
+            return;
+        }
+        if enabled {
+            self.cancel_translations(app);
+        }
+        info!(
+            "[translate] transcription-only mode {}",
+            if enabled { "on" } else { "off" }
+        );
+        emit_ui_event(app, "transcription_only_changed", enabled);
+    }
+
     fn drop_pending_translations(&self, app: &AppHandle) {
         self.drop_segment_translation.store(true, Ordering::SeqCst);
+        self.cancel_translations(app);
+    }
+
+    fn cancel_translations(&self, app: &AppHandle) {
         self.translation_generation.fetch_add(1, Ordering::SeqCst);
+        self.translation_cancel.cancel();
         if let Ok(mut guard) = self.translation_pending.lock() {
             guard.clear();
         }
@@ -677,60 +2088,133 @@ impl CaptureManager {
                 queues.translation_queue.clear();
             }
         }
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("segment_translation_canceled", true);
-        }
+        emit_ui_event(app, "segment_translation_canceled", true);
     }
 }
 
-fn ensure_segments_dir(app: &AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn ensure_segments_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let base = app.path().app_data_dir().map_err(|err| err.to_string())?;
     let segments_dir = base.join("segments");
     fs::create_dir_all(&segments_dir).map_err(|err| err.to_string())?;
     Ok(segments_dir)
 }
 
-fn index_path(dir: &Path) -> PathBuf {
-    dir.join("index.json")
+fn sessions_path(dir: &Path) -> PathBuf {
+    dir.join("sessions.json")
 }
 
-fn load_index_if_needed(dir: &Path, segments: &Arc<Mutex<Vec<SegmentInfo>>>) {
-    let mut guard = match segments.lock() {
-        Ok(guard) => guard,
-        Err(_) => return,
+fn load_sessions(dir: &Path) -> Vec<SessionInfo> {
+    fs::read_to_string(sessions_path(dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_sessions(dir: &Path, sessions: &[SessionInfo]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(sessions).map_err(|err| err.to_string())?;
+    fs::write(sessions_path(dir), content).map_err(|err| err.to_string())
+}
+
+// Fills the retro buffer until `stop`; `ready` reports whether the device could be opened.
+fn run_armed(
+    config: crate::audio::config::AudioConfig,
+    seconds: u64,
+    stop: CancellationToken,
+    buffer: Arc<Mutex<Option<RetroBuffer>>>,
+    ready: mpsc::Sender<Result<(), String>>,
+) {
+    let mut capture = match backend::open(&config) {
+        Ok(capture) => capture,
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            return;
+        }
     };
-    if !guard.is_empty() {
-        return;
+    if let Ok(mut guard) = buffer.lock() {
+        *guard = Some(RetroBuffer::new(
+            capture.sample_rate(),
+            capture.channels(),
+            seconds,
+        ));
     }
-    let path = index_path(dir);
-    if let Ok(content) = fs::read_to_string(&path) {
-        if let Ok(list) = serde_json::from_str::<Vec<SegmentInfo>>(&content) {
-            *guard = list;
+    let _ = ready.send(Ok(()));
+    while !stop.is_cancelled() {
+        let pcm = match capture.follow_default().and_then(|_| capture.read()) {
+            Ok(pcm) => pcm,
+            Err(err) => {
+                warn!("[armed] capture stopped: {err}");
+                break;
+            }
+        };
+        if pcm.is_empty() {
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        if let Ok(mut guard) = buffer.lock() {
+            if let Some(buffer) = guard.as_mut() {
+                buffer.push(&pcm);
+            }
         }
     }
 }
 
-pub(crate) fn save_index(dir: &Path, segments: &[SegmentInfo]) -> Result<(), String> {
-    let path = index_path(dir);
-    let content = serde_json::to_string_pretty(segments).map_err(|err| err.to_string())?;
-    fs::write(path, content).map_err(|err| err.to_string())
+enum Finalize {
+    // A new segment started, for `Coalescer` to wait for it.
+    Opened(Instant),
+    Closed(SegmentWriter),
+}
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// How much audio the ring holds while processing catches up.
+const CAPTURE_RING_MS: u64 = 4_000;
+// The longest the capture thread waits for audio before it checks for a stop or a device change.
+const CAPTURE_WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+const PROCESS_WAKE_INTERVAL: Duration = Duration::from_millis(100);
+
+fn prune_storage(
+    dir: &Path,
+    segments: &SegmentStore,
+    policy: RetentionPolicy,
+    protected_session: Option<&str>,
+) {
+    match retention::enforce(dir, policy, protected_session) {
+        Ok(report) if report.files > 0 => {
+            info!(
+                "[retention] removed {} files ({} bytes, {} segments)",
+                report.files, report.bytes, report.segments
+            );
+            if report.segments > 0 {
+                segments.reload(dir);
+            }
+        }
+        Ok(_) => {}
+        Err(err) => warn!("[retention] pruning failed: {err}"),
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_capture(
     app: AppHandle,
     segments_dir: PathBuf,
-    segments: Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: Arc<SegmentStore>,
     config: crate::audio::config::AudioConfig,
-    stop: Arc<AtomicBool>,
+    asr_config: AsrConfig,
+    stop: CancellationToken,
+    paused: Arc<AtomicBool>,
     queues: TaskQueues,
-) -> Result<(), String> {
-    let asr_config = load_app_config()
-        .ok()
-        .and_then(|cfg| cfg.asr)
-        .unwrap_or_default();
-    let mut capture = LoopbackCapture::new()?;
-    let sample_rate = capture.sample_rate();
-    let channels = capture.channels().max(1);
+    session_id: String,
+    counters: Arc<CaptureCounters>,
+) -> Result<CaptureExit, String> {
+    // Cancelled on every way out of this function, so the capture thread never outlives it.
+    let capture_stop = stop.child_token();
+    let capture_guard = capture_stop.clone().drop_guard();
+    let CaptureThread {
+        sample_rate,
+        channels,
+        mut samples,
+        handle: capture_thread,
+    } = spawn_capture_thread(&app, &config, capture_stop, Arc::clone(&counters))?;
+    let capture_started = Instant::now();
 
     let min_segment_frames = config.min_segment_ms.saturating_mul(sample_rate as u64) / 1000;
     let min_silence_frames = config.min_silence_ms.saturating_mul(sample_rate as u64) / 1000;
@@ -744,6 +2228,74 @@ fn run_capture(
     let rolling_min_frames = config.rolling_min_ms.saturating_mul(sample_rate as u64) / 1000;
     let rolling_window_samples = rolling_window_frames.saturating_mul(channels as u64) as usize;
     let rolling_min_samples = rolling_min_frames.saturating_mul(channels as u64) as usize;
+    let segment_format = SegmentFormat::from_config(&config);
+
+    // Finished segments are flushed and handed to the pipeline in order on their own thread, so
+    // waiting for the WAV writer never stalls capture reads.
+    let (finalize_tx, finalize_rx) = mpsc::channel::<Finalize>();
+    let finalizer = {
+        let app = app.clone();
+        let segments_dir = segments_dir.clone();
+        let segments = Arc::clone(&segments);
+        let queues = queues.clone();
+        let asr_config = asr_config.clone();
+        let min_transcribe_ms = config.min_transcribe_ms;
+        let policy = RetentionPolicy::from_config(&config);
+        let session_id = session_id.clone();
+        let mut coalescer = Coalescer::new(&config);
+        thread::spawn(move || {
+            let mut last_prune = Instant::now();
+            let dispatch = |info| {
+                dispatch_segment(
+                    &app,
+                    &segments_dir,
+                    &segments,
+                    &queues,
+                    &asr_config,
+                    info,
+                    min_transcribe_ms,
+                )
+            };
+            loop {
+                let message = match coalescer.wait(Instant::now()) {
+                    Some(wait) => match finalize_rx.recv_timeout(wait) {
+                        Ok(message) => message,
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            coalescer.take().into_iter().for_each(dispatch);
+                            continue;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match finalize_rx.recv() {
+                        Ok(message) => message,
+                        Err(_) => break,
+                    },
+                };
+                let writer = match message {
+                    Finalize::Opened(at) => {
+                        coalescer.opened(at);
+                        continue;
+                    }
+                    Finalize::Closed(writer) => writer,
+                };
+                let opened_at = writer.opened_at();
+                let closed_at = writer.closed_at().unwrap_or_else(Instant::now);
+                match writer.finalize() {
+                    Ok(info) => coalescer
+                        .push(&segments_dir, info, opened_at, closed_at)
+                        .into_iter()
+                        .for_each(dispatch),
+                    Err(err) => warn!("segment finalize failed: {err}"),
+                }
+                // Long captures are kept within the limits as well, sparing the running session.
+                if last_prune.elapsed() >= PRUNE_INTERVAL {
+                    last_prune = Instant::now();
+                    prune_storage(&segments_dir, &segments, policy, Some(&session_id));
+                }
+            }
+            coalescer.take().into_iter().for_each(dispatch);
+        })
+    };
 
     let mut pre_roll: VecDeque<f32> = VecDeque::with_capacity(pre_roll_samples.max(1));
     let mut current_writer: Option<SegmentWriter> = None;
@@ -751,24 +2303,109 @@ fn run_capture(
     let mut silence_frames: u64 = 0;
     let mut rolling_buffer: VecDeque<f32> = VecDeque::with_capacity(rolling_window_samples.max(1));
     let mut rolling_since_emit: u64 = 0;
+    // Streams the live view instead of window uploads while the connection lasts.
+    let mut realtime = if rolling_enabled && window_transcribe_enabled {
+        RealtimeStream::start(&app, sample_rate, channels, stop.clone())
+    } else {
+        None
+    };
 
-    println!(
+    info!(
         "[rolling] enabled={} window_transcribe_enabled={}",
         rolling_enabled, window_transcribe_enabled
     );
 
-    while !stop.load(Ordering::SeqCst) {
-        let pcm = capture.read()?;
+    let auto_stop_after = auto_stop_after();
+    let mut last_speech = Instant::now();
+    let mut exit = CaptureExit::Stopped;
+    let mut meter = LevelMeter::new(config.source, channels);
+    let mut monitor = SignalMonitor::new(&config, Instant::now());
+    let mut denoiser = config.denoise.then(|| Denoiser::new(sample_rate, channels));
+    // Losing the continuous recording must not stop the segments.
+    let mut recording = if config.record_session {
+        recording::start(&segments_dir, &session_id, sample_rate, channels)
+            .map_err(|err| warn!("session recording unavailable: {err}"))
+            .ok()
+    } else {
+        None
+    };
+    let mut last_level = Instant::now();
+
+    while !stop.is_cancelled() {
+        // Checked before reading: loopback delivers no packets at all while nothing plays.
+        if auto_stop_after.is_some_and(|after| last_speech.elapsed() >= after) {
+            info!(
+                "[auto-stop] no speech for {}s, stopping capture",
+                last_speech.elapsed().as_secs()
+            );
+            exit = CaptureExit::Silence;
+            break;
+        }
+        for alert in monitor.poll(Instant::now()) {
+            warn!(
+                "[alert] {} active={} after {}s",
+                alert.kind, alert.active, alert.seconds
+            );
+            emit_ui_event(&app, AUDIO_ALERT, alert);
+        }
+        // Woken by the capture thread as audio arrives; the timeout keeps the checks above going
+        // while loopback is quiet.
+        samples.wait(PROCESS_WAKE_INTERVAL);
+        let pcm = samples.pop();
+        if pcm.is_empty() && samples.is_finished() {
+            break;
+        }
+        counters.set_segment_frames(segment_frames);
+        if paused.load(Ordering::SeqCst) {
+            // The device keeps being drained so resuming picks up live audio, not a backlog.
+            if let Some(mut writer) = current_writer.take() {
+                writer.close();
+                let _ = finalize_tx.send(Finalize::Closed(writer));
+            }
+            segment_frames = 0;
+            silence_frames = 0;
+            pre_roll.clear();
+            rolling_buffer.clear();
+            rolling_since_emit = 0;
+            last_speech = Instant::now();
+            monitor.reset(last_speech);
+            continue;
+        }
+        if !pcm.is_empty() {
+            if let Some(Err(err)) = recording.as_ref().map(|sink| sink.write(&pcm)) {
+                warn!("session recording stopped: {err}");
+                recording = None;
+            }
+        }
+        meter.add(&pcm);
+        monitor.add(&pcm);
+        if last_level.elapsed() >= LEVEL_INTERVAL {
+            last_level = Instant::now();
+            emit_output_event(&app, AUDIO_LEVEL, meter.take());
+        }
+        // The meter shows the raw input; silence detection and segments see the denoised audio.
+        let pcm = match denoiser.as_mut() {
+            Some(denoiser) => denoiser.process(&pcm),
+            None => pcm,
+        };
         if pcm.is_empty() {
-            std::thread::sleep(Duration::from_millis(10));
             continue;
         }
 
         let frame_count = (pcm.len() / channels as usize) as u64;
         let is_silence = is_silence(&pcm, config.silence_threshold_db);
+        if !is_silence {
+            last_speech = Instant::now();
+            monitor.heard(last_speech);
+        }
 
-        if rolling_enabled
-            && window_transcribe_enabled
+        if realtime.as_mut().is_some_and(|stream| !stream.push(&pcm)) {
+            warn!("[realtime] stream ended, falling back to window transcription");
+            realtime = None;
+        }
+        // Interpreter mode streams window drafts even when rolling transcription is off.
+        let window_drafts = rolling_enabled && window_transcribe_enabled && realtime.is_none();
+        if (window_drafts || crate::interpreter::is_enabled())
             && rolling_window_frames > 0
             && rolling_step_frames > 0
         {
@@ -781,7 +2418,10 @@ fn run_capture(
             rolling_since_emit = rolling_since_emit.saturating_add(frame_count);
             if rolling_since_emit >= rolling_step_frames {
                 rolling_since_emit = 0;
-                if rolling_buffer.len() >= rolling_min_samples {
+                // Finalized segments come first; a live window would only compete with them.
+                if queues.transcription_behind() {
+                    queues.window_queue.record_dropped();
+                } else if rolling_buffer.len() >= rolling_min_samples {
                     let already_running = queues.window_in_flight.swap(true, Ordering::SeqCst);
                     if !already_running {
                         let samples: Vec<f32> = rolling_buffer.iter().copied().collect();
@@ -796,11 +2436,11 @@ fn run_capture(
                             sample_rate,
                             channels,
                             window_ms,
-                            created_at: Local::now().to_rfc3339(),
+                            created_at: now_rfc3339(),
+                            language: asr_config.language.clone(),
+                            cancel: stop.clone(),
                         };
-                        if queues.window_tx.send(task).is_err() {
-                            queues.window_in_flight.store(false, Ordering::SeqCst);
-                        }
+                        queues.window_queue.push(task);
                     }
                 }
             }
@@ -824,18 +2464,22 @@ fn run_capture(
 
             let reached_min = segment_frames >= min_segment_frames;
             let reached_silence = silence_frames >= min_silence_frames;
+            let max_segment_frames = match crate::interpreter::max_segment_ms() {
+                Some(ms) => {
+                    let frames = ms.saturating_mul(sample_rate as u64) / 1000;
+                    if max_segment_frames > 0 {
+                        frames.min(max_segment_frames)
+                    } else {
+                        frames
+                    }
+                }
+                None => max_segment_frames,
+            };
             let reached_max = max_segment_frames > 0 && segment_frames >= max_segment_frames;
             if (reached_min && reached_silence) || reached_max {
-                let writer = current_writer.take().unwrap();
-                finalize_segment(
-                    &app,
-                    &segments_dir,
-                    &segments,
-                    &queues,
-                    &asr_config,
-                    writer,
-                    config.min_transcribe_ms,
-                );
+                let mut writer = current_writer.take().unwrap();
+                writer.close();
+                let _ = finalize_tx.send(Finalize::Closed(writer));
                 segment_frames = 0;
                 silence_frames = 0;
             }
@@ -843,7 +2487,22 @@ fn run_capture(
         }
 
         if !is_silence {
-            let mut writer = SegmentWriter::start_new(&segments_dir, sample_rate, channels)?;
+            let mut writer = SegmentWriter::start_new(
+                &segments_dir,
+                sample_rate,
+                channels,
+                segment_format,
+                &session_id,
+            )?;
+            if config.source == CaptureSource::Dual {
+                writer = writer.with_track_energy();
+            }
+            // The pre-roll was captured before the segment opened.
+            let pre_roll_ms =
+                (pre_roll.len() / channels as usize) as u64 * 1000 / sample_rate.max(1) as u64;
+            let start_offset_ms = timing::elapsed_ms(capture_started).saturating_sub(pre_roll_ms);
+            writer = writer.with_start_offset(start_offset_ms);
+            let _ = finalize_tx.send(Finalize::Opened(writer.opened_at()));
             if !pre_roll.is_empty() {
                 let pre_roll_vec: Vec<f32> = pre_roll.iter().copied().collect();
                 if !pre_roll_vec.is_empty() {
@@ -859,69 +2518,175 @@ fn run_capture(
         }
     }
 
-    if let Some(writer) = current_writer.take() {
-        finalize_segment(
-            &app,
-            &segments_dir,
-            &segments,
-            &queues,
-            &asr_config,
-            writer,
-            config.min_transcribe_ms,
-        );
+    if let Some(mut writer) = current_writer.take() {
+        writer.close();
+        let _ = finalize_tx.send(Finalize::Closed(writer));
+    }
+    drop(finalize_tx);
+    let _ = finalizer.join();
+    if let Some(mut sink) = recording {
+        if let Err(err) = sink.finish() {
+            warn!("session recording incomplete: {err}");
+        }
     }
+    drop(capture_guard);
+    capture_thread
+        .join()
+        .map_err(|_| "capture thread panicked".to_string())??;
 
-    Ok(())
+    Ok(exit)
 }
 
+struct CaptureThread {
+    sample_rate: u32,
+    channels: u16,
+    samples: RingConsumer,
+    handle: JoinHandle<Result<(), String>>,
+}
+
+// Opens the backend on a thread that does nothing but read the device into a ring, so slow
+// processing (denoising, WAV writes, rolling windows) never delays a read. Audio that does not fit
+// because processing fell that far behind counts as dropped.
+fn spawn_capture_thread(
+    app: &AppHandle,
+    config: &crate::audio::config::AudioConfig,
+    stop: CancellationToken,
+    counters: Arc<CaptureCounters>,
+) -> Result<CaptureThread, String> {
+    let app = app.clone();
+    let config = config.clone();
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    let handle = thread::Builder::new()
+        .name("audio-capture".to_string())
+        .spawn(move || {
+            let mut capture = match backend::open(&config) {
+                Ok(capture) => capture,
+                // Reported through `ready_tx` instead of the join handle.
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return Ok(());
+                }
+            };
+            devices::set_active_devices(capture.active_devices());
+            let sample_rate = capture.sample_rate();
+            let channels = capture.channels().max(1);
+            counters.begin(sample_rate, channels);
+            let capacity =
+                CAPTURE_RING_MS.saturating_mul(sample_rate as u64) / 1000 * channels as u64;
+            let (mut producer, consumer) = ring::channel(capacity as usize);
+            let _ = ready_tx.send(Ok((sample_rate, channels, consumer)));
+
+            let mut overflowed = 0u64;
+            while !stop.is_cancelled() {
+                let switches = capture.follow_default()?;
+                if !switches.is_empty() {
+                    devices::set_active_devices(capture.active_devices());
+                }
+                for switch in switches {
+                    emit_ui_event(&app, CAPTURE_DEVICE_CHANGED, switch);
+                }
+                let pcm = capture.read()?;
+                counters.add_frames((pcm.len() / channels as usize) as u64);
+                if !pcm.is_empty() && !producer.push(&pcm) {
+                    overflowed += 1;
+                }
+                counters.set_dropped_buffers(capture.dropped_buffers() + overflowed);
+                if pcm.is_empty() {
+                    capture.wait(CAPTURE_WAIT_TIMEOUT);
+                }
+            }
+            Ok(())
+        })
+        .map_err(|err| err.to_string())?;
+    match ready_rx.recv() {
+        Ok(Ok((sample_rate, channels, samples))) => Ok(CaptureThread {
+            sample_rate,
+            channels,
+            samples,
+            handle,
+        }),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err("capture thread exited before opening the device".to_string()),
+    }
+}
+
+fn auto_stop_after() -> Option<Duration> {
+    let config = load_app_config().ok()?.auto_stop.unwrap_or_default();
+    if !config.enabled.unwrap_or(DEFAULT_AUTO_STOP_ENABLED) {
+        return None;
+    }
+    let minutes = config
+        .silence_minutes
+        .unwrap_or(DEFAULT_AUTO_STOP_SILENCE_MINUTES)
+        .max(1);
+    Some(Duration::from_secs(minutes * 60))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn finalize_segment_with_vad(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
-    transcribe_tx: &mpsc::Sender<String>,
+    segments: &Arc<SegmentStore>,
+    transcribe_queue: &BoundedQueue<TranscribeTask>,
     speaker_state: &Arc<Mutex<SpeakerState>>,
-    min_transcribe_ms: u64,
     asr_config: &AsrConfig,
-    info: SegmentInfo,
+    min_transcribe_ms: u64,
+    vad: Option<&mut SileroVad>,
+    mut info: SegmentInfo,
 ) {
     let path = dir.join(&info.name);
-    if min_transcribe_ms > 0 && info.duration_ms < min_transcribe_ms {
-        let _ = fs::remove_file(&path);
-        return;
-    }
-    let should_keep = match should_keep_segment(&path, asr_config) {
-        Ok(result) => result,
-        Err(err) => {
-            eprintln!("vad check failed: {err}");
-            true
-        }
-    };
-
-    if should_keep {
-        push_segment(app, dir, segments, speaker_state, info.clone());
-        let _ = transcribe_tx.send(info.name);
-    } else {
+    if min_transcribe_ms > 0 && info.duration_ms < min_transcribe_ms {
         let _ = fs::remove_file(&path);
+        return;
+    }
+    if let Some(vad) = vad {
+        match detect_speech(vad, &path) {
+            Ok(spans) if spans.is_empty() => {
+                let _ = fs::remove_file(&path);
+                return;
+            }
+            Ok(spans) => info.speech_spans = Some(spans),
+            Err(err) => warn!("vad check failed: {err}"),
+        }
     }
+
+    push_segment(app, dir, segments, speaker_state, info.clone());
+    enqueue_transcription(transcribe_queue, asr_config, info.name);
 }
 
 fn finalize_segment(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     queues: &TaskQueues,
     asr_config: &AsrConfig,
     writer: SegmentWriter,
     min_transcribe_ms: u64,
 ) {
-    let info = match writer.finalize() {
-        Ok(info) => info,
-        Err(err) => {
-            eprintln!("segment finalize failed: {err}");
-            return;
-        }
-    };
+    match writer.finalize() {
+        Ok(info) => dispatch_segment(
+            app,
+            dir,
+            segments,
+            queues,
+            asr_config,
+            info,
+            min_transcribe_ms,
+        ),
+        Err(err) => warn!("segment finalize failed: {err}"),
+    }
+}
 
+// Hands a complete segment file to VAD, or straight to transcription.
+fn dispatch_segment(
+    app: &AppHandle,
+    dir: &Path,
+    segments: &Arc<SegmentStore>,
+    queues: &TaskQueues,
+    asr_config: &AsrConfig,
+    info: SegmentInfo,
+    min_transcribe_ms: u64,
+) {
     if min_transcribe_ms > 0 && info.duration_ms < min_transcribe_ms {
         let path = dir.join(&info.name);
         let _ = fs::remove_file(&path);
@@ -933,70 +2698,141 @@ fn finalize_segment(
             info,
             min_transcribe_ms,
             asr_config: asr_config.clone(),
+            cancel: queues.pipeline_cancel.token(),
         };
-        if let Err(err) = queues.vad_tx.send(task) {
-            eprintln!("vad worker unavailable, fallback to inline processing");
-            let task = err.0;
-            finalize_segment_with_vad(
-                app,
-                dir,
-                segments,
-                &queues.transcribe_tx,
-                &queues.speaker_state,
-                task.min_transcribe_ms,
-                &task.asr_config,
-                task.info,
-            );
-        }
+        queues.vad_queue.push(task);
         return;
     }
 
     let name = info.name.clone();
     push_segment(app, dir, segments, &queues.speaker_state, info);
-    enqueue_transcription(queues, name);
+    enqueue_transcription(&queues.transcribe_queue, asr_config, name);
+}
+
+// Waits while the queue is full; finalized segments are never dropped.
+fn enqueue_transcription(
+    queue: &BoundedQueue<TranscribeTask>,
+    asr_config: &AsrConfig,
+    name: String,
+) {
+    let task = TranscribeTask {
+        name,
+        queued_at: Instant::now(),
+        language: asr_config.language.clone(),
+        model: asr_config.whisper_cpp_model_path.clone(),
+    };
+    queue.push(task);
 }
 
-fn enqueue_transcription(queues: &TaskQueues, name: String) {
-    let _ = queues.transcribe_tx.send(name);
+// Stored before the transcript, so the transcript event carries them. Each word goes through the
+// regex PII pass and the word filter; a speaker from the local model is kept.
+fn apply_words(
+    app: &AppHandle,
+    dir: &Path,
+    segments: &Arc<SegmentStore>,
+    name: &str,
+    transcription: &Transcription,
+) {
+    let Some(words) = transcription.words.as_ref() else {
+        return;
+    };
+    let pii = PiiFilter::from_config();
+    let word_filter = WordFilter::from_config();
+    let language = app.try_state::<AsrState>().map(|state| state.language());
+    let words = words
+        .iter()
+        .map(|word| {
+            let text = pii
+                .as_ref()
+                .map(|pii| pii.mask(&word.word))
+                .unwrap_or_else(|| word.word.clone());
+            TranscriptWord {
+                word: apply_to(word_filter.as_ref(), &text, language.as_deref()),
+                ..word.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    let speaker = transcription.speaker();
+    let switches = transcription.speaker_switches_ms();
+    segments.update(dir, name, |segment| {
+        if segment.speaker_id.is_none() {
+            segment.speaker_id = speaker;
+        }
+        if segment.speaker_switches_ms.is_none() && speaker.is_some() {
+            segment.speaker_switches_ms = Some(switches);
+        }
+        segment.words = Some(words);
+    });
 }
 
+// `language` is the session's; `None` records the current ASR language.
+#[allow(clippy::too_many_arguments)]
 fn apply_transcript(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     name: &str,
     transcript: Option<String>,
     elapsed_ms: u64,
+    queue_wait_ms: Option<u64>,
+    language: Option<&str>,
 ) {
     let transcript_text = transcript
         .as_ref()
         .map(|value| value.trim())
         .filter(|value| !value.is_empty())
         .map(|value| value.to_string());
-    let mut updated: Option<SegmentInfo> = None;
-    let mut snapshot: Option<Vec<SegmentInfo>> = None;
-    if let Ok(mut guard) = segments.lock() {
-        if let Some(segment) = guard.iter_mut().find(|segment| segment.name == name) {
-            segment.transcript = transcript;
-            segment.transcript_at = Some(Local::now().to_rfc3339());
-            segment.transcript_ms = Some(elapsed_ms);
-            updated = Some(segment.clone());
-            snapshot = Some(guard.clone());
+    let language = language
+        .map(str::to_string)
+        .or_else(|| app.try_state::<AsrState>().map(|state| state.language()));
+    let updated = segments.update(dir, name, |segment| {
+        segment.transcript = transcript;
+        segment.transcript_language = language;
+        segment.transcript_at = Some(now_rfc3339());
+        segment.transcript_ms = Some(elapsed_ms);
+        if let Some(wait_ms) = queue_wait_ms {
+            segment.timings.record(name, Stage::TranscribeWait, wait_ms);
         }
-    }
-    if let Some(snapshot) = snapshot {
-        let _ = save_index(dir, &snapshot);
-    }
+        segment.timings.record(name, Stage::Transcribe, elapsed_ms);
+    });
 
     if let Some(info) = updated {
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("segment_transcribed", info.clone());
-        }
+        emit_ui_event(app, "segment_transcribed", &*info);
     }
 
     let _ = transcript_text;
 }
 
+// The ASR settings a new capture session is bound to, with the language picked in the UI.
+fn session_asr_config(app: &AppHandle) -> AsrConfig {
+    let mut asr_config = load_app_config()
+        .ok()
+        .and_then(|cfg| cfg.asr)
+        .unwrap_or_default();
+    if let Some(state) = app.try_state::<AsrState>() {
+        let language = state.language();
+        if !language.trim().is_empty() {
+            asr_config.language = Some(language);
+        }
+    }
+    asr_config
+}
+
+// The session's whisper model, when it is no longer the configured one; other providers keep
+// their own model settings.
+fn session_model_override(app: &AppHandle, model: Option<&str>) -> Option<String> {
+    let model = model.map(str::trim).filter(|value| !value.is_empty())?;
+    let provider = app.try_state::<AsrState>()?.provider();
+    if !provider.starts_with("whisper") {
+        return None;
+    }
+    let configured = load_app_config()
+        .ok()
+        .and_then(|cfg| cfg.asr)
+        .and_then(|asr| asr.whisper_cpp_model_path);
+    (configured.as_deref().map(str::trim) != Some(model)).then(|| model.to_string())
+}
+
 fn load_whisper_context_policy() -> WhisperContextPolicy {
     let asr_config = load_app_config()
         .ok()
@@ -1006,11 +2842,10 @@ fn load_whisper_context_policy() -> WhisperContextPolicy {
 }
 
 fn load_segment_context_meta(
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     name: &str,
 ) -> Option<SegmentContextMeta> {
-    let guard = segments.lock().ok()?;
-    let segment = guard.iter().find(|segment| segment.name == name)?;
+    let segment = segments.get(name)?;
     Some(SegmentContextMeta {
         duration_ms: segment.duration_ms,
         created_at: DateTime::parse_from_rfc3339(&segment.created_at).ok(),
@@ -1037,68 +2872,323 @@ fn take_tail_chars(text: &str, max_chars: usize) -> String {
 fn run_transcription_worker(
     app: AppHandle,
     dir: PathBuf,
-    segments: Arc<Mutex<Vec<SegmentInfo>>>,
-    rx: mpsc::Receiver<String>,
+    segments: Arc<SegmentStore>,
+    queue: Arc<BoundedQueue<TranscribeTask>>,
+    refine_queue: Arc<BoundedQueue<RefineTask>>,
     translation_queue: Arc<TranslationQueue>,
     pending: Arc<Mutex<HashMap<String, Option<String>>>>,
     translation_generation: Arc<AtomicU64>,
     drop_segment_translation: Arc<AtomicBool>,
+    pipeline_cancel: Arc<CancelScope>,
+    pulse: Pulse<TranscribeTask>,
 ) {
     let mut context_state = WhisperContextState::new(load_whisper_context_policy());
-    while let Ok(name) = rx.recv() {
+    // Loaded with the first segment; `None` while speaker diarization is off.
+    let mut switch_detector: Option<Option<SwitchDetector>> = None;
+    loop {
+        let task = queue.pop();
+        if !pulse.begin(std::slice::from_ref(&task)) {
+            queue.requeue(task);
+            return;
+        }
+        let TranscribeTask {
+            name,
+            queued_at,
+            language,
+            model,
+        } = task;
+        let queue_wait_ms = timing::elapsed_ms(queued_at);
+        let _span = info_span!("transcribe", segment = %name).entered();
+        let cancel = pipeline_cancel.token();
         let path = dir.join(&name);
         let meta = load_segment_context_meta(&segments, &name);
         let prompt_hint = meta
             .as_ref()
             .and_then(|segment_meta| context_state.prompt_for(segment_meta));
+        if let Some(detector) = switch_detector
+            .get_or_insert_with(|| SwitchDetector::new(&app))
+            .as_mut()
+        {
+            detect_speaker_switches(detector, &dir, &segments, &name);
+        }
         let thread_id = std::thread::current().id();
-        println!("[transcribe] thread={thread_id:?} name={name}");
+        debug!("[transcribe] thread={thread_id:?} name={name}");
         let started_at = Instant::now();
-        let transcript = match tauri::async_runtime::block_on(async {
-            transcribe_file(&app, &path, prompt_hint.as_deref()).await
-        }) {
-            Ok(text) => Some(text),
-            Err(err) => {
-                eprintln!("transcription failed for {name}: {err}");
+        let model = session_model_override(&app, model.as_deref());
+        let mut english = false;
+        let result = runtime::block_on(cancellable(
+            &cancel,
+            transcribe_detailed(
+                &app,
+                &path,
+                prompt_hint.as_deref(),
+                None,
+                model.as_deref(),
+                language.as_deref(),
+            ),
+        ));
+        if !pulse.is_current() {
+            debug!("[transcribe] worker replaced, dropping result name={name}");
+            return;
+        }
+        let transcript = match result {
+            None => {
+                debug!("[transcribe] canceled name={name}");
+                pulse.finish();
+                continue;
+            }
+            Some(Ok(transcription)) => {
+                apply_words(&app, &dir, &segments, &name, &transcription);
+                english = transcription.english;
+                let text_language = if english {
+                    Some("en")
+                } else {
+                    language.as_deref()
+                };
+                let text = runtime::block_on(async {
+                    let text = restore_punctuation(&app, transcription.text, text_language).await;
+                    let text = normalize_transcript(&app, &text, text_language);
+                    mask_transcript(&app, text).await
+                });
+                Some(filter_transcript(&app, &text))
+            }
+            Some(Err(err)) => {
+                warn!("transcription failed for {name}: {err}");
+                let provider = app
+                    .try_state::<AsrState>()
+                    .map(|state| state.provider())
+                    .unwrap_or_default();
+                record_failure(&dir, &segments, &name, "transcribe", provider);
                 Some(String::new())
             }
         };
         context_state.observe_result(meta.as_ref(), transcript.as_deref());
+        // With two-pass transcription this is the draft; the accurate pass replaces it later.
+        let draft = transcript
+            .as_deref()
+            .is_some_and(|text| !text.trim().is_empty())
+            && TwoPassSettings::load().is_some();
+        if draft {
+            segments.update(&dir, &name, |segment| segment.draft = true);
+        }
         let elapsed_ms = started_at.elapsed().as_millis() as u64;
-        apply_transcript(&app, &dir, &segments, &name, transcript, elapsed_ms);
+        // Speech the provider already translated needs no translation into English.
+        let english_translation = transcript
+            .clone()
+            .filter(|_| english && is_english(&current_target_language()));
+        apply_transcript(
+            &app,
+            &dir,
+            &segments,
+            &name,
+            transcript,
+            elapsed_ms,
+            Some(queue_wait_ms),
+            if english {
+                Some("en")
+            } else {
+                language.as_deref()
+            },
+        );
+        pulse.finish();
+
+        let translation = if drop_segment_translation.load(Ordering::SeqCst) {
+            None
+        } else {
+            take_pending_translation(&pending, &name)
+        };
+        if let Some(provider) = translation.clone() {
+            match english_translation {
+                Some(text) => apply_translation(
+                    &app,
+                    &dir,
+                    &segments,
+                    &name,
+                    Some(text),
+                    Some("en"),
+                    None,
+                    0,
+                    None,
+                ),
+                None => enqueue_translation(
+                    &app,
+                    &dir,
+                    &translation_queue,
+                    &segments,
+                    &translation_generation,
+                    name.clone(),
+                    provider,
+                ),
+            }
+        }
+        if draft {
+            let dropped = refine_queue.push(RefineTask {
+                name,
+                translation,
+                generation: translation_generation.load(Ordering::SeqCst),
+                language,
+                english,
+            });
+            if let Some(dropped) = dropped {
+                warn!("refine queue full, keeping the draft of {}", dropped.name);
+                clear_draft(&app, &dir, &segments, &dropped.name);
+            }
+        }
+    }
+}
 
-        if drop_segment_translation.load(Ordering::SeqCst) {
+// Second pass of two-pass transcription. It waits while drafts are queued, so the accurate model
+// never delays a segment's first transcript.
+fn run_refine_worker(
+    app: AppHandle,
+    dir: PathBuf,
+    segments: Arc<SegmentStore>,
+    queue: Arc<BoundedQueue<RefineTask>>,
+    transcribe_queue: Arc<BoundedQueue<TranscribeTask>>,
+    translation_queue: Arc<TranslationQueue>,
+    translation_generation: Arc<AtomicU64>,
+    pipeline_cancel: Arc<CancelScope>,
+) {
+    let mut refiner = Refiner::new();
+    loop {
+        let RefineTask {
+            name,
+            translation,
+            generation,
+            language,
+            english,
+        } = queue.pop();
+        transcribe_queue.wait_until_empty();
+        if segments.get(&name).is_none() {
+            continue;
+        }
+        let Some(settings) = TwoPassSettings::load() else {
+            clear_draft(&app, &dir, &segments, &name);
+            continue;
+        };
+        // whisper-pipe workers only transcribe, so they would swap the English draft for
+        // source-language text.
+        if english && settings.provider.is_none() {
+            clear_draft(&app, &dir, &segments, &name);
+            continue;
+        }
+        let _span = info_span!("refine", segment = %name).entered();
+        let cancel = pipeline_cancel.token();
+        let started_at = Instant::now();
+        let result = refiner.transcribe(&app, &settings, &dir.join(&name), language.as_deref());
+        if cancel.is_cancelled() {
+            continue;
+        }
+        let transcription = match result {
+            Ok(transcription) => transcription,
+            Err(err) => {
+                warn!("second pass failed for {name}, keeping the draft: {err}");
+                clear_draft(&app, &dir, &segments, &name);
+                continue;
+            }
+        };
+        if transcription.english != english {
+            warn!("second pass of {name} came back in another language, keeping the draft");
+            clear_draft(&app, &dir, &segments, &name);
             continue;
         }
-        if let Some(provider) = take_pending_translation(&pending, &name) {
+        apply_words(&app, &dir, &segments, &name, &transcription);
+        let text_language = if english {
+            Some("en")
+        } else {
+            language.as_deref()
+        };
+        let text = runtime::block_on(async {
+            let text = restore_punctuation(&app, transcription.text, text_language).await;
+            let text = normalize_transcript(&app, &text, text_language);
+            mask_transcript(&app, text).await
+        });
+        let transcript = filter_transcript(&app, &text);
+        let updated = segments.update(&dir, &name, |segment| {
+            if !transcript.trim().is_empty() {
+                segment.transcript = Some(transcript);
+            }
+            segment.draft = false;
+        });
+        let Some(info) = updated else {
+            continue;
+        };
+        debug!(
+            "[refine] name={name} elapsed_ms={}",
+            started_at.elapsed().as_millis()
+        );
+        emit_ui_event(&app, "segment_transcribed", &*info);
+
+        let canceled = generation != translation_generation.load(Ordering::SeqCst);
+        let Some(provider) = translation.filter(|_| settings.retranslate && !canceled) else {
+            continue;
+        };
+        // Like the first pass, English speech needs no translation into English.
+        if english && is_english(&current_target_language()) {
+            apply_translation(
+                &app,
+                &dir,
+                &segments,
+                &name,
+                info.transcript.clone(),
+                Some("en"),
+                None,
+                0,
+                None,
+            );
+        } else {
             enqueue_translation(
+                &app,
+                &dir,
                 &translation_queue,
                 &segments,
                 &translation_generation,
-                name.clone(),
+                name,
                 provider,
             );
         }
     }
 }
 
+// The draft stays as the final transcript, e.g. when the second pass failed.
+fn clear_draft(app: &AppHandle, dir: &Path, segments: &Arc<SegmentStore>, name: &str) {
+    if let Some(info) = segments.update(dir, name, |segment| segment.draft = false) {
+        emit_ui_event(app, "segment_transcribed", &*info);
+    }
+}
+
 fn run_vad_worker(
     app: AppHandle,
     dir: PathBuf,
-    segments: Arc<Mutex<Vec<SegmentInfo>>>,
-    rx: mpsc::Receiver<VadTask>,
-    transcribe_tx: mpsc::Sender<String>,
+    segments: Arc<SegmentStore>,
+    queue: Arc<BoundedQueue<VadTask>>,
+    transcribe_queue: Arc<BoundedQueue<TranscribeTask>>,
     speaker_state: Arc<Mutex<SpeakerState>>,
 ) {
-    while let Ok(task) = rx.recv() {
+    // Loaded with the first segment; without a model every segment is kept unchecked.
+    let mut vad: Option<Option<SileroVad>> = None;
+    loop {
+        let task = queue.pop();
+        if task.cancel.is_cancelled() {
+            let _ = fs::remove_file(dir.join(&task.info.name));
+            continue;
+        }
+        let vad = vad.get_or_insert_with(|| match SileroVad::load(&app, &task.asr_config) {
+            Ok(vad) => Some(vad),
+            Err(err) => {
+                warn!("silero vad unavailable, keeping every segment: {err}");
+                None
+            }
+        });
         finalize_segment_with_vad(
             &app,
             &dir,
             &segments,
-            &transcribe_tx,
+            &transcribe_queue,
             &speaker_state,
-            task.min_transcribe_ms,
             &task.asr_config,
+            task.min_transcribe_ms,
+            vad.as_mut(),
             task.info,
         );
     }
@@ -1106,7 +3196,14 @@ fn run_vad_worker(
 
 fn load_segment_translation_batch_config() -> SegmentTranslationBatchConfig {
     // Force per-segment translation dispatch: do not batch multiple segments.
-    SegmentTranslationBatchConfig { size: 1 }
+    SegmentTranslationBatchConfig {
+        size: DEFAULT_SEGMENT_TRANSLATE_BATCH_SIZE,
+        stream: load_app_config()
+            .ok()
+            .and_then(|config| config.translate)
+            .and_then(|translate| translate.stream_segments)
+            .unwrap_or(DEFAULT_SEGMENT_TRANSLATE_STREAM),
+    }
 }
 
 fn collect_translation_batch(
@@ -1145,25 +3242,40 @@ fn collect_translation_batch(
 fn translate_segment_batch_now(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     requests: Vec<TranslationRequest>,
     batch_config: SegmentTranslationBatchConfig,
     translation_generation: Arc<AtomicU64>,
+    cancel: &CancellationToken,
     history: &mut SegmentTranslationHistory,
 ) {
     if requests.is_empty() {
         return;
     }
 
+    let config = load_app_config().ok();
     let mut group: Vec<TranslationRequest> = Vec::new();
-    let mut current_provider: Option<String> = None;
-    for request in requests {
+    let mut current_key: (Option<String>, Option<String>) = (None, None);
+    for mut request in requests {
+        if let Some(config) = &config {
+            let speaker_id = segments
+                .find(dir, &request.name)
+                .and_then(|segment| segment.speaker_id);
+            match speaker_translation(config, speaker_id) {
+                SpeakerTranslation::Skip => {
+                    leave_untranslated(app, dir, segments, &request.name);
+                    continue;
+                }
+                SpeakerTranslation::Target(language) => request.target_language = Some(language),
+            }
+        }
+        let key = (request.provider.clone(), request.target_language.clone());
         if group.is_empty() {
-            current_provider = request.provider.clone();
+            current_key = key;
             group.push(request);
             continue;
         }
-        if request.provider == current_provider {
+        if key == current_key {
             group.push(request);
             continue;
         }
@@ -1175,9 +3287,10 @@ fn translate_segment_batch_now(
             std::mem::take(&mut group),
             batch_config,
             Arc::clone(&translation_generation),
+            cancel,
             history,
         );
-        current_provider = request.provider.clone();
+        current_key = key;
         group.push(request);
     }
 
@@ -1189,6 +3302,7 @@ fn translate_segment_batch_now(
             group,
             batch_config,
             translation_generation,
+            cancel,
             history,
         );
     }
@@ -1197,10 +3311,11 @@ fn translate_segment_batch_now(
 fn translate_segment_provider_group(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     requests: Vec<TranslationRequest>,
     batch_config: SegmentTranslationBatchConfig,
     translation_generation: Arc<AtomicU64>,
+    cancel: &CancellationToken,
     history: &mut SegmentTranslationHistory,
 ) {
     if requests.is_empty() {
@@ -1211,9 +3326,19 @@ fn translate_segment_provider_group(
     let provider = requests
         .first()
         .and_then(|request| request.provider.clone());
-    if history.generation != active_generation || history.provider != provider {
+    // Read once per batch; a switch mid-session applies from the next batch onwards. Context items
+    // are re-applied with the batch, so they are dropped too rather than switching language.
+    let target_language = requests
+        .first()
+        .and_then(|request| request.target_language.clone())
+        .unwrap_or_else(current_target_language);
+    if history.generation != active_generation
+        || history.provider != provider
+        || history.target_language != target_language
+    {
         history.generation = active_generation;
         history.provider = provider.clone();
+        history.target_language = target_language.clone();
         history.previous_batch.clear();
     }
 
@@ -1222,15 +3347,9 @@ fn translate_segment_provider_group(
         if request.generation != active_generation {
             continue;
         }
-        let transcript = {
-            let guard = segments.lock().ok();
-            guard.as_ref().and_then(|segments| {
-                segments
-                    .iter()
-                    .find(|segment| segment.name == request.name)
-                    .and_then(|segment| segment.transcript.clone())
-            })
-        };
+        let transcript = segments
+            .find(dir, &request.name)
+            .and_then(|segment| segment.transcript.clone());
         let Some(transcript) = transcript else {
             continue;
         };
@@ -1245,7 +3364,13 @@ fn translate_segment_provider_group(
     }
 
     let max_batch_size = batch_config.size.max(1);
-    let prev_start = history.previous_batch.len().saturating_sub(max_batch_size);
+    // Interpreter mode and streaming translate only the new items, without context or cleanup.
+    let skip_cleanup = crate::interpreter::is_enabled() || batch_config.stream;
+    let prev_start = if skip_cleanup {
+        history.previous_batch.len()
+    } else {
+        history.previous_batch.len().saturating_sub(max_batch_size)
+    };
     let context_items: Vec<BatchTranslationItem> = history.previous_batch[prev_start..]
         .iter()
         .map(|item| BatchTranslationItem {
@@ -1263,24 +3388,118 @@ fn translate_segment_provider_group(
     }
 
     let all_names: Vec<String> = all_items.iter().map(|item| item.id.clone()).collect();
+    // Context items from the previous batch were not waiting in the queue, so they have no entry.
+    let queue_waits: HashMap<String, u64> = requests
+        .iter()
+        .map(|request| (request.name.clone(), timing::elapsed_ms(request.queued_at)))
+        .collect();
+    let _span = info_span!("translate_batch", items = all_names.len()).entered();
     let started_at = Instant::now();
-    let batch_result = tauri::async_runtime::block_on(async {
-        translate_text_batch_with_options(
-            &all_items,
-            provider.clone(),
-            TranslateSource::Segment,
-            BatchTranslationOptions {
-                context_items: context_items.clone(),
-            },
-        )
-        .await
-    });
+    let primary = provider
+        .clone()
+        .unwrap_or_else(|| crate::selected_translate_provider(app));
+    // `None` until the first fallback, so the primary call goes out exactly as requested.
+    let mut fallback: Option<String> = None;
+    let mut fallbacks: Option<std::vec::IntoIter<String>> = None;
+    let batch_result = loop {
+        let result = if batch_config.stream {
+            // A number or a banned word can be split across chunks, so the whole text is masked
+            // and filtered before every event.
+            let mut streamed: HashMap<String, String> = HashMap::new();
+            let mut on_chunk = |name: &str, chunk: &str| {
+                let text = streamed.entry(name.to_string()).or_default();
+                text.push_str(chunk);
+                emit_output_event(
+                    app,
+                    SEGMENT_TRANSLATION_CHUNK,
+                    SegmentTranslationChunk {
+                        name: name.to_string(),
+                        text: filter_translation(&mask_text(text), Some(&target_language)),
+                        target_language: target_language.clone(),
+                        reset: false,
+                    },
+                );
+            };
+            runtime::block_on(cancellable(
+                cancel,
+                translate_text_batch_streaming(
+                    app,
+                    &all_items,
+                    fallback.clone().or_else(|| provider.clone()),
+                    TranslateSource::Segment,
+                    Some(target_language.clone()),
+                    &mut on_chunk,
+                ),
+            ))
+        } else {
+            runtime::block_on(cancellable(
+                cancel,
+                translate_text_batch_with_options(
+                    app,
+                    &all_items,
+                    fallback.clone().or_else(|| provider.clone()),
+                    TranslateSource::Segment,
+                    BatchTranslationOptions {
+                        context_items: context_items.clone(),
+                        target_language: Some(target_language.clone()),
+                        skip_cleanup,
+                    },
+                ),
+            ))
+        };
+        let Some(result) = result else {
+            debug!("[translate-worker] batch canceled");
+            return;
+        };
+        let attempt = fallback.clone().unwrap_or_else(|| primary.clone());
+        let err = match result {
+            Ok(translations) => break Ok(translations),
+            Err(err) => err,
+        };
+        if translation_generation.load(Ordering::SeqCst) != active_generation {
+            return;
+        }
+        for item in &current_batch_items {
+            record_failure(dir, segments, &item.id, "translate", attempt.clone());
+        }
+        // `translate.fallbackProviders` is read on the first failure of each batch.
+        let next = fallbacks
+            .get_or_insert_with(|| {
+                load_app_config()
+                    .map(|config| fallback_providers(&config, &primary))
+                    .unwrap_or_default()
+                    .into_iter()
+            })
+            .next();
+        match next {
+            Some(next) => {
+                warn!("batch translation failed on {attempt}, retrying on {next}: {err}");
+                if batch_config.stream {
+                    for item in &all_items {
+                        emit_output_event(
+                            app,
+                            SEGMENT_TRANSLATION_CHUNK,
+                            SegmentTranslationChunk {
+                                name: item.id.clone(),
+                                text: String::new(),
+                                target_language: target_language.clone(),
+                                reset: true,
+                            },
+                        );
+                    }
+                }
+                fallback = Some(next);
+            }
+            None => break Err(err),
+        }
+    };
 
     match batch_result {
         Ok(translations) => {
             if translation_generation.load(Ordering::SeqCst) != active_generation {
                 return;
             }
+            let translated_by = fallback.unwrap_or(primary);
             let elapsed_ms = started_at.elapsed().as_millis() as u64;
             let mut missing_count = 0usize;
             for name in &all_names {
@@ -1291,10 +3510,20 @@ fn translate_segment_provider_group(
                         missing_count += 1;
                         String::new()
                     });
-                apply_translation(app, dir, segments, name, Some(translation), elapsed_ms);
+                apply_translation(
+                    app,
+                    dir,
+                    segments,
+                    name,
+                    Some(translation),
+                    Some(&target_language),
+                    Some(&translated_by),
+                    elapsed_ms,
+                    queue_waits.get(name).copied(),
+                );
             }
             if missing_count > 0 {
-                eprintln!(
+                warn!(
           "batch translation missing {} item(s), marked as failed without single fallback",
           missing_count
         );
@@ -1322,13 +3551,20 @@ fn translate_segment_provider_group(
             }
         }
         Err(err) => {
-            if translation_generation.load(Ordering::SeqCst) != active_generation {
-                return;
-            }
             let elapsed_ms = started_at.elapsed().as_millis() as u64;
-            eprintln!("batch translation failed: {err}");
+            warn!("batch translation failed: {err}");
             for name in all_names {
-                apply_translation(app, dir, segments, &name, Some(String::new()), elapsed_ms);
+                apply_translation(
+                    app,
+                    dir,
+                    segments,
+                    &name,
+                    Some(String::new()),
+                    None,
+                    None,
+                    elapsed_ms,
+                    queue_waits.get(&name).copied(),
+                );
             }
             history.generation = active_generation;
             history.provider = provider;
@@ -1340,14 +3576,18 @@ fn translate_segment_provider_group(
 fn run_translation_worker(
     app: AppHandle,
     dir: PathBuf,
-    segments: Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: Arc<SegmentStore>,
     queue: Arc<TranslationQueue>,
     in_flight: Arc<AtomicBool>,
     translation_generation: Arc<AtomicU64>,
+    translation_cancel: Arc<CancelScope>,
+    pulse: Pulse<TranslationRequest>,
 ) {
     let mut history = SegmentTranslationHistory::default();
     loop {
         let first = queue.pop();
+        // Taken before the generation check so a drop that races with this batch still aborts it.
+        let cancel = translation_cancel.token();
         if first.generation != translation_generation.load(Ordering::SeqCst) {
             continue;
         }
@@ -1357,11 +3597,17 @@ fn run_translation_worker(
         if batch_requests.is_empty() {
             continue;
         }
-        eprintln!(
+        debug!(
             "[translate-worker] batch_size={} picked={}",
             batch_config.size,
             batch_requests.len()
         );
+        if !pulse.begin(&batch_requests) {
+            for request in batch_requests {
+                queue.push(request);
+            }
+            return;
+        }
         in_flight.store(true, Ordering::SeqCst);
         translate_segment_batch_now(
             &app,
@@ -1370,20 +3616,30 @@ fn run_translation_worker(
             batch_requests,
             batch_config,
             Arc::clone(&translation_generation),
+            &cancel,
             &mut history,
         );
+        if !pulse.is_current() {
+            return;
+        }
         in_flight.store(false, Ordering::SeqCst);
+        pulse.finish();
     }
 }
 
 fn run_window_worker(
     app: AppHandle,
-    rx: mpsc::Receiver<WindowTask>,
+    queue: Arc<BoundedQueue<WindowTask>>,
     in_flight: Arc<AtomicBool>,
     speaker_state: Arc<Mutex<SpeakerState>>,
 ) {
     let mut diarizer = SpeakerDiarizer::new(&app);
-    while let Ok(task) = rx.recv() {
+    loop {
+        let task = queue.pop();
+        if task.cancel.is_cancelled() {
+            in_flight.store(false, Ordering::SeqCst);
+            continue;
+        }
         let started_at = Instant::now();
         let mut speaker_decision = None;
         if let Some(diarizer) = diarizer.as_mut() {
@@ -1399,41 +3655,42 @@ fn run_window_worker(
         let path = match window_wav_path(&app) {
             Ok(path) => path,
             Err(err) => {
-                eprintln!("window wav path error: {err}");
+                warn!("window wav path error: {err}");
                 in_flight.store(false, Ordering::SeqCst);
                 continue;
             }
         };
 
         if let Err(err) = write_window_wav(&path, &task.samples, task.sample_rate, task.channels) {
-            eprintln!("window wav write failed: {err}");
+            warn!("window wav write failed: {err}");
             in_flight.store(false, Ordering::SeqCst);
             continue;
         }
 
-        let mut asr_config = load_app_config()
-            .ok()
-            .and_then(|cfg| cfg.asr)
-            .unwrap_or_default();
-        if let Some(state) = app.try_state::<AsrState>() {
-            let language = state.language();
-            if !language.trim().is_empty() {
-                asr_config.language = Some(language);
-            }
+        let mut asr_config = session_asr_config(&app);
+        if let Some(language) = task.language.clone() {
+            asr_config.language = Some(language);
         }
-        let transcript = match tauri::async_runtime::block_on(async {
-            transcribe_with_whisper_server(&app, &path, &asr_config, None).await
-        }) {
-            Ok(text) => text,
-            Err(err) => {
-                eprintln!("window transcription failed: {err}");
+        let result = runtime::block_on(cancellable(
+            &task.cancel,
+            transcribe_with_whisper_server(&app, &path, &asr_config, None),
+        ));
+        let transcript = match result {
+            Some(Ok(text)) => text,
+            Some(Err(err)) => {
+                warn!("window transcription failed: {err}");
+                in_flight.store(false, Ordering::SeqCst);
+                continue;
+            }
+            None => {
                 in_flight.store(false, Ordering::SeqCst);
                 continue;
             }
         };
 
         let elapsed_ms = started_at.elapsed().as_millis() as u64;
-        let text = transcript.trim().to_string();
+        // Live drafts get the regex pass only; the LLM name pass would lag the caption.
+        let text = filter_transcript(&app, &mask_text(transcript.trim()));
         let (speaker_id, speaker_similarity, speaker_mixed) = speaker_decision
             .map(|decision| (decision.speaker_id, decision.similarity, decision.mixed))
             .unwrap_or((None, None, false));
@@ -1446,81 +3703,124 @@ fn run_window_worker(
             speaker_similarity,
             speaker_mixed,
         };
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("window_transcribed", payload.clone());
-        }
+        emit_ui_event(&app, "window_transcribed", payload.clone());
+        live_draft::publish(&app, &payload.text);
 
         in_flight.store(false, Ordering::SeqCst);
     }
 }
 
+// Speakers with `translate: false`; the transcript stands in so the UI does not request it again.
+fn leave_untranslated(app: &AppHandle, dir: &Path, segments: &Arc<SegmentStore>, name: &str) {
+    let updated = segments.update(dir, name, |segment| {
+        segment.translation = segment.transcript.clone();
+        segment.translation_language = None;
+        segment.translation_at = Some(now_rfc3339());
+    });
+    if let Some(info) = updated {
+        emit_ui_event(app, "segment_translated", &*info);
+    }
+}
+
+fn record_failure(
+    dir: &Path,
+    segments: &Arc<SegmentStore>,
+    name: &str,
+    stage: &str,
+    provider: String,
+) {
+    segments.update(dir, name, |segment| {
+        segment.failures.push(ProviderFailure {
+            stage: stage.to_string(),
+            provider,
+        });
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 fn apply_translation(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     name: &str,
     translation: Option<String>,
+    language: Option<&str>,
+    provider: Option<&str>,
     elapsed_ms: u64,
+    queue_wait_ms: Option<u64>,
 ) {
-    let mut updated: Option<SegmentInfo> = None;
-    let mut snapshot: Option<Vec<SegmentInfo>> = None;
-    if let Ok(mut guard) = segments.lock() {
-        if let Some(segment) = guard.iter_mut().find(|segment| segment.name == name) {
-            segment.translation = translation;
-            segment.translation_at = Some(Local::now().to_rfc3339());
-            segment.translation_ms = Some(elapsed_ms);
-            updated = Some(segment.clone());
-            snapshot = Some(guard.clone());
+    let translation = translation.map(|text| filter_translation(&mask_text(&text), language));
+    let updated = segments.update(dir, name, |segment| {
+        segment.translation = translation;
+        segment.translation_language = language.map(str::to_string);
+        segment.translation_provider = provider.map(str::to_string);
+        segment.translation_at = Some(now_rfc3339());
+        segment.translation_ms = Some(elapsed_ms);
+        if let Some(wait_ms) = queue_wait_ms {
+            segment.timings.record(name, Stage::TranslateWait, wait_ms);
         }
-    }
-    if let Some(snapshot) = snapshot {
-        let _ = save_index(dir, &snapshot);
-    }
+        segment.timings.record(name, Stage::Translate, elapsed_ms);
+    });
 
     if let Some(info) = updated {
-        if let Some(webview) = app.get_webview("output") {
-            let _ = webview.emit("segment_translated", info.clone());
-        }
+        emit_ui_event(app, "segment_translated", &*info);
     }
 }
 
-fn should_keep_segment(path: &Path, asr_config: &AsrConfig) -> Result<bool, String> {
-    if asr_config.use_whisper_vad != Some(true) {
-        return Ok(true);
-    }
-
-    let vad_exe = asr_config
-        .whisper_cpp_vad_path
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .ok_or_else(|| "whisper VAD path is required".to_string())?;
-    let vad_exe =
-        resolve_local_path(&vad_exe).ok_or_else(|| format!("whisper VAD not found: {vad_exe}"))?;
+// The latest session started at or before `created_at`.
+fn session_at(sessions: &[SessionInfo], created_at: &str) -> Option<String> {
+    let at = DateTime::parse_from_rfc3339(created_at).ok()?;
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let started = DateTime::parse_from_rfc3339(&session.started_at).ok()?;
+            (started <= at).then_some((started, session))
+        })
+        .max_by_key(|(started, _)| *started)
+        .map(|(_, session)| session.id.clone())
+}
 
-    let model_path = asr_config
-        .whisper_cpp_vad_model_path
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .and_then(|value| resolve_local_path(&value));
-    let Some(model_path) = model_path else {
-        eprintln!("whisper VAD model path missing, skip VAD check");
-        return Ok(true);
-    };
+// Rejects names that would reach outside the segments directory.
+fn segment_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let safe_name = Path::new(name)
+        .file_name()
+        .and_then(|value| value.to_str())
+        .ok_or_else(|| "invalid segment name".to_string())?;
+    if safe_name != name {
+        return Err("invalid segment name".to_string());
+    }
+    Ok(dir.join(safe_name))
+}
 
-    let mut cmd = Command::new(vad_exe);
-    cmd.arg("-f").arg(path).arg("-np");
+fn read_segment(path: &Path) -> Result<(Vec<f32>, WavSpec), String> {
+    let mut reader = WavReader::open(path).map_err(|err| err.to_string())?;
+    let spec = reader.spec();
+    let samples = writer::f32_samples(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    Ok((samples, spec))
+}
 
-    cmd.arg("--vad-model").arg(model_path);
+fn detect_speech(vad: &mut SileroVad, path: &Path) -> Result<Vec<SpeechSpan>, String> {
+    let (samples, spec) = read_segment(path)?;
+    vad.detect(&samples, spec.sample_rate, spec.channels)
+}
 
-    let output = cmd.output().map_err(|err| err.to_string())?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        return Err(format!("whisper VAD failed: {stderr} {stdout}"));
+// Stored on the segment before its transcript, so the transcript event carries them.
+fn detect_speaker_switches(
+    detector: &mut SwitchDetector,
+    dir: &Path,
+    segments: &Arc<SegmentStore>,
+    name: &str,
+) {
+    let switches = read_segment(&dir.join(name))
+        .and_then(|(samples, spec)| detector.detect(&samples, spec.sample_rate, spec.channels));
+    match switches {
+        Ok(switches) => {
+            segments.update(dir, name, |segment| {
+                segment.speaker_switches_ms = Some(switches);
+            });
+        }
+        Err(err) => warn!("speaker switch detection failed for {name}: {err}"),
     }
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(!stdout.trim().is_empty())
 }
 
 fn is_silence(pcm: &[f32], threshold_db: f32) -> bool {
@@ -1611,7 +3911,11 @@ fn resolve_in_same_dir(base: &str, target: &str) -> Option<PathBuf> {
     candidate.exists().then_some(candidate)
 }
 
-fn start_whisper_stream(app: &AppHandle, asr_config: &AsrConfig) -> Option<StreamHandle> {
+fn start_whisper_stream(
+    app: &AppHandle,
+    asr_config: &AsrConfig,
+    paused: Arc<AtomicBool>,
+) -> Option<StreamHandle> {
     if asr_config.use_whisper_stream != Some(true) {
         return None;
     }
@@ -1664,7 +3968,8 @@ fn start_whisper_stream(app: &AppHandle, asr_config: &AsrConfig) -> Option<Strea
                 break;
             }
             let text = line.trim();
-            if text.is_empty() {
+            // whisper-stream records on its own, so its output is what has to be dropped.
+            if text.is_empty() || paused.load(Ordering::SeqCst) {
                 continue;
             }
             let _ = app_handle.emit("stream_transcript", text.to_string());
@@ -1679,7 +3984,7 @@ fn start_whisper_stream(app: &AppHandle, asr_config: &AsrConfig) -> Option<Strea
             }
             let err = err_line.trim();
             if !err.is_empty() {
-                eprintln!("whisper-stream: {err}");
+                warn!("whisper-stream: {err}");
             }
         }
     });
@@ -1699,33 +4004,86 @@ fn take_pending_translation(
 }
 
 fn enqueue_translation(
+    app: &AppHandle,
+    dir: &Path,
     queue: &TranslationQueue,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     translation_generation: &Arc<AtomicU64>,
     name: String,
     provider: Option<String>,
 ) {
-    let order = segment_order(segments, &name);
-    queue.push(TranslationRequest {
+    let order = segments.order(&name);
+    let dropped = queue.push(TranslationRequest {
         name,
         provider,
+        target_language: None,
         order,
         generation: translation_generation.load(Ordering::SeqCst),
+        queued_at: Instant::now(),
     });
+    if let Some(dropped) = dropped {
+        warn!("translation queue full, skipped {}", dropped.name);
+        apply_translation(
+            app,
+            dir,
+            segments,
+            &dropped.name,
+            Some(String::new()),
+            None,
+            None,
+            0,
+            None,
+        );
+    }
 }
 
-fn segment_order(segments: &Arc<Mutex<Vec<SegmentInfo>>>, name: &str) -> usize {
-    let guard = segments.lock().ok();
-    guard
-        .as_ref()
-        .and_then(|segments| segments.iter().position(|segment| segment.name == name))
-        .unwrap_or(usize::MAX)
+// Emits `queue_metrics` whenever depth or overflow counters change, and `transcription_backlog`
+// when transcription falls behind or catches up.
+fn run_queue_metrics(app: AppHandle, queues: TaskQueues) {
+    let mut last = Vec::new();
+    let mut was_behind = false;
+    loop {
+        thread::sleep(Duration::from_millis(QUEUE_METRICS_INTERVAL_MS));
+        let stats = queues.stats();
+        if stats != last {
+            emit_ui_event(&app, QUEUE_METRICS, stats.clone());
+            last = stats;
+        }
+        let behind = queues.transcription_behind();
+        if behind != was_behind {
+            emit_ui_event(
+                &app,
+                TRANSCRIPTION_BACKLOG,
+                TranscriptionBacklog {
+                    behind,
+                    depth: queues.transcribe_queue.len(),
+                },
+            );
+            was_behind = behind;
+        }
+    }
+}
+
+// Workers only end by panicking; a stuck one is left to finish on its own and exits afterwards.
+fn run_watchdog(app: AppHandle, mut workers: Vec<Box<dyn Supervised>>, stuck_after: Duration) {
+    loop {
+        thread::sleep(Duration::from_millis(WATCHDOG_INTERVAL_MS));
+        for worker in workers.iter_mut() {
+            if let Some(restart) = worker.supervise(stuck_after) {
+                warn!(
+                    "[watchdog] restarted {} worker ({:?}), requeued {:?}",
+                    restart.worker, restart.reason, restart.requeued
+                );
+                emit_ui_event(&app, WORKER_RESTARTED, restart);
+            }
+        }
+    }
 }
 
 fn push_segment(
     app: &AppHandle,
     dir: &Path,
-    segments: &Arc<Mutex<Vec<SegmentInfo>>>,
+    segments: &Arc<SegmentStore>,
     speaker_state: &Arc<Mutex<SpeakerState>>,
     mut info: SegmentInfo,
 ) {
@@ -1734,15 +4092,7 @@ fn push_segment(
         info.speaker_similarity = guard.current_similarity;
         info.speaker_changed = guard.last_changed;
     }
-    let mut snapshot: Option<Vec<SegmentInfo>> = None;
-    if let Ok(mut guard) = segments.lock() {
-        guard.push(info.clone());
-        snapshot = Some(guard.clone());
-    }
-    if let Some(snapshot) = snapshot {
-        let _ = save_index(dir, &snapshot);
-    }
-    if let Some(webview) = app.get_webview("output") {
-        let _ = webview.emit("segment_created", info.clone());
-    }
+    let info = segments.push(info);
+    index::record(dir, &info);
+    emit_ui_event(app, "segment_created", &*info);
 }
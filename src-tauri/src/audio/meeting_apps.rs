@@ -0,0 +1,228 @@
+use crate::audio::backend;
+use crate::audio::config::load_config;
+use crate::audio::manager::CaptureManager;
+use crate::events::{emit_ui_event, MEETING_APP_DETECTED, MEETING_APP_ENDED};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+// A call app that drops its audio session briefly, e.g. while switching devices, stays detected.
+const GONE_AFTER: Duration = Duration::from_secs(30);
+
+// Executable names without `.exe`, lowercase. On Windows they are matched against processes with
+// an active audio session, elsewhere against running processes.
+const KNOWN_APPS: &[(&str, &str)] = &[
+    ("zoom", "Zoom"),
+    ("zoom.us", "Zoom"),
+    ("cpthost", "Zoom"),
+    ("ms-teams", "Microsoft Teams"),
+    ("teams", "Microsoft Teams"),
+    ("microsoft teams", "Microsoft Teams"),
+    ("webex", "Webex"),
+    ("ciscocollabhost", "Webex"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingAppDetected {
+    // Display name, e.g. `Zoom`.
+    pub app: String,
+    pub process: String,
+    // Capture was already running; nothing was started.
+    pub capturing: bool,
+    pub auto_started: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingAppEnded {
+    pub app: String,
+}
+
+// Emits `meeting_app_detected` while `meeting_app_detection` is on, and starts capture for it
+// with `meeting_app_auto_start`.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut tracker = Tracker::default();
+        let mut failing = false;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let config = load_config(&app);
+            if !config.meeting_app_detection {
+                tracker = Tracker::default();
+                continue;
+            }
+            let processes = match backend::audio_processes(config.backend) {
+                Ok(processes) => processes,
+                Err(err) => {
+                    if !failing {
+                        warn!("[meeting-apps] process list unavailable: {err}");
+                    }
+                    failing = true;
+                    continue;
+                }
+            };
+            failing = false;
+            let detected = detect(&processes, &config.meeting_apps);
+            let (appeared, gone) = tracker.update(
+                detected.iter().map(|(name, _)| name.as_str()),
+                Instant::now(),
+            );
+            for name in appeared {
+                let process = detected
+                    .iter()
+                    .find(|(detected, _)| *detected == name)
+                    .map(|(_, process)| process.clone())
+                    .unwrap_or_default();
+                announce(&app, name, process, config.meeting_app_auto_start);
+            }
+            for name in gone {
+                info!("[meeting-apps] {name} ended");
+                emit_ui_event(&app, MEETING_APP_ENDED, MeetingAppEnded { app: name });
+            }
+        }
+    });
+}
+
+fn announce(app: &AppHandle, name: String, process: String, auto_start: bool) {
+    let Some(manager) = app.try_state::<CaptureManager>() else {
+        return;
+    };
+    let capturing = manager.is_capturing();
+    let mut auto_started = false;
+    if auto_start && !capturing {
+        match manager.start(app.clone()) {
+            Ok(()) => auto_started = true,
+            Err(err) => warn!("[meeting-apps] capture start failed: {err}"),
+        }
+    }
+    info!("[meeting-apps] {name} detected ({process}) auto_started={auto_started}");
+    emit_ui_event(
+        app,
+        MEETING_APP_DETECTED,
+        MeetingAppDetected {
+            app: name,
+            process,
+            capturing,
+            auto_started,
+        },
+    );
+}
+
+// Process names elsewhere than Windows, where audio sessions cannot be attributed to a process.
+pub(crate) fn running_processes() -> Result<Vec<String>, String> {
+    let output = Command::new("ps")
+        .args(["-A", "-o", "comm="])
+        .output()
+        .map_err(|err| err.to_string())?;
+    if !output.status.success() {
+        return Err(format!("ps exited with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // macOS prints the full path.
+        .filter_map(|line| line.trim().rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+// (display name, process) per meeting app found, once per app; `extra` names show as themselves.
+fn detect(processes: &[String], extra: &[String]) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    for process in processes {
+        let lower = process.to_lowercase();
+        let key = lower.strip_suffix(".exe").unwrap_or(&lower);
+        let name = KNOWN_APPS
+            .iter()
+            .find(|(known, _)| *known == key)
+            .map(|(_, name)| name.to_string())
+            .or_else(|| {
+                extra
+                    .iter()
+                    .find(|extra| {
+                        let extra = extra.trim().to_lowercase();
+                        extra.strip_suffix(".exe").unwrap_or(&extra) == key
+                    })
+                    .map(|extra| extra.trim().to_string())
+            });
+        if let Some(name) = name {
+            if !found.iter().any(|(existing, _)| *existing == name) {
+                found.push((name, process.clone()));
+            }
+        }
+    }
+    found
+}
+
+#[derive(Default)]
+struct Tracker {
+    last_seen: HashMap<String, Instant>,
+}
+
+impl Tracker {
+    // Apps seen for the first time, and apps not seen for `GONE_AFTER`.
+    fn update<'a>(
+        &mut self,
+        detected: impl Iterator<Item = &'a str>,
+        now: Instant,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut appeared = Vec::new();
+        for name in detected {
+            if self.last_seen.insert(name.to_string(), now).is_none() {
+                appeared.push(name.to_string());
+            }
+        }
+        let gone = self
+            .last_seen
+            .iter()
+            .filter(|(_, seen)| now.duration_since(**seen) >= GONE_AFTER)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        for name in &gone {
+            self.last_seen.remove(name);
+        }
+        (appeared, gone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect, Tracker, GONE_AFTER};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn detects_call_apps_and_debounces_their_end() {
+        let processes = ["explorer.exe", "Zoom.exe", "CptHost.exe", "chrome.exe"]
+            .map(str::to_string)
+            .to_vec();
+        assert_eq!(
+            detect(&processes, &[]),
+            [("Zoom".to_string(), "Zoom.exe".to_string())]
+        );
+        let found = detect(&processes, &["Chrome.exe".to_string()]);
+        assert_eq!(
+            found[1],
+            ("Chrome.exe".to_string(), "chrome.exe".to_string())
+        );
+
+        let start = Instant::now();
+        let mut tracker = Tracker::default();
+        let (appeared, gone) = tracker.update(["Zoom"].into_iter(), start);
+        assert_eq!((appeared, gone.len()), (vec!["Zoom".to_string()], 0));
+        // Still there, then briefly missing: neither is news.
+        let (appeared, gone) = tracker.update(["Zoom"].into_iter(), start + Duration::from_secs(3));
+        assert!(appeared.is_empty() && gone.is_empty());
+        let (_, gone) = tracker.update(std::iter::empty(), start + Duration::from_secs(10));
+        assert!(gone.is_empty());
+        let (_, gone) = tracker.update(
+            std::iter::empty(),
+            start + Duration::from_secs(3) + GONE_AFTER,
+        );
+        assert_eq!(gone, ["Zoom"]);
+    }
+}
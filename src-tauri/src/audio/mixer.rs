@@ -0,0 +1,89 @@
+use crate::audio::convert::FormatAdapter;
+use std::collections::VecDeque;
+
+// Channel of each source in a dual-source recording.
+pub const ME_CHANNEL: usize = 0;
+pub const THEM_CHANNEL: usize = 1;
+
+// Loopback delivers no packets while nothing plays, so a source that falls this far behind the
+// other one is padded with silence instead of holding the mix back.
+const MAX_LAG_MS: u64 = 100;
+
+// Interleaves the microphone ("me") and loopback ("them") into one two-channel stream at the
+// loopback rate, each source downmixed to mono.
+pub struct TrackMixer {
+    me: FormatAdapter,
+    them: FormatAdapter,
+    me_pending: VecDeque<f32>,
+    them_pending: VecDeque<f32>,
+    max_lag: usize,
+}
+
+impl TrackMixer {
+    pub fn new(me_rate: u32, me_channels: u16, them_rate: u32, them_channels: u16) -> Self {
+        Self {
+            me: FormatAdapter::new(me_rate, me_channels, them_rate, 1),
+            them: FormatAdapter::new(them_rate, them_channels, them_rate, 1),
+            me_pending: VecDeque::new(),
+            them_pending: VecDeque::new(),
+            max_lag: (them_rate as u64 * MAX_LAG_MS / 1000) as usize,
+        }
+    }
+
+    pub fn mix(&mut self, me: &[f32], them: &[f32]) -> Vec<f32> {
+        self.me_pending.extend(self.me.convert(me));
+        self.them_pending.extend(self.them.convert(them));
+        let (me_len, them_len) = (self.me_pending.len(), self.them_pending.len());
+        if me_len > them_len + self.max_lag {
+            self.them_pending.resize(me_len, 0.0);
+        } else if them_len > me_len + self.max_lag {
+            self.me_pending.resize(them_len, 0.0);
+        }
+        let frames = self.me_pending.len().min(self.them_pending.len());
+        let mut output = Vec::with_capacity(frames * 2);
+        for (me, them) in self
+            .me_pending
+            .drain(..frames)
+            .zip(self.them_pending.drain(..frames))
+        {
+            output.push(me);
+            output.push(them);
+        }
+        output
+    }
+}
+
+// The source that was louder over a segment, from the summed squares of each channel; `None`
+// for a silent one.
+pub fn dominant_track(energy: [f64; 2]) -> Option<&'static str> {
+    if energy[ME_CHANNEL] <= 0.0 && energy[THEM_CHANNEL] <= 0.0 {
+        return None;
+    }
+    if energy[ME_CHANNEL] >= energy[THEM_CHANNEL] {
+        Some("me")
+    } else {
+        Some("them")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dominant_track, TrackMixer};
+
+    #[test]
+    fn interleaves_sources_and_pads_a_silent_one() {
+        // 20 Hz loopback keeps the lag limit at two frames.
+        let mut mixer = TrackMixer::new(40, 1, 20, 2);
+        assert_eq!(mixer.mix(&[0.2, 0.2, 0.4, 0.4], &[0.5, 0.7]), [0.2, 0.6]);
+        // The microphone may run two frames ahead; past that loopback is padded with silence.
+        assert_eq!(mixer.mix(&[0.4, 0.4], &[]), Vec::<f32>::new());
+        assert_eq!(
+            mixer.mix(&[0.6, 0.6, 0.8, 0.8], &[]),
+            [0.4, 0.0, 0.4, 0.0, 0.6, 0.0, 0.8, 0.0]
+        );
+
+        assert_eq!(dominant_track([0.0, 0.0]), None);
+        assert_eq!(dominant_track([2.0, 0.5]), Some("me"));
+        assert_eq!(dominant_track([0.1, 0.5]), Some("them"));
+    }
+}
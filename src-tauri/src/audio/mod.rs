@@ -1,7 +1,36 @@
+pub mod alerts;
+pub mod backend;
+pub mod coalesce;
 pub mod config;
+pub mod convert;
+pub mod cpal_capture;
+pub mod denoise;
+pub mod devices;
+pub mod index;
+pub mod level;
 pub mod manager;
+pub mod meeting_apps;
+pub mod mixer;
+pub mod playback;
+pub mod queue;
+pub mod recording;
+pub mod recovery;
+pub mod resample;
+pub mod retention;
+pub mod retro;
+pub mod ring;
 pub mod speaker;
+pub mod stats;
+pub mod store;
+pub mod timing;
+pub mod vad;
+#[cfg(windows)]
 pub mod wasapi;
+pub mod watchdog;
+pub mod waveform;
 pub mod writer;
 
-pub use manager::{CaptureManager, SegmentInfo};
+pub use manager::{
+    ArmedStatus, Bookmark, CaptureManager, RediarizeReport, RetranscribeReport, SegmentInfo,
+    SessionSummary,
+};
@@ -0,0 +1,147 @@
+use crate::audio::writer;
+use hound::WavReader;
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+pub const DEFAULT_CHUNK_MS: u64 = 1_000;
+const MAX_CHUNK_MS: u64 = 10_000;
+
+// Reads one segment in chunks for the output window to play, so an audit of what ASR heard never
+// ships the whole file to the frontend at once.
+pub struct SegmentPlayer {
+    name: String,
+    reader: WavReader<BufReader<File>>,
+    sample_rate: u32,
+    channels: u16,
+    frames: u32,
+    // Next frame to read.
+    position: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackState {
+    pub name: String,
+    pub duration_ms: u64,
+    pub position_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackChunk {
+    pub name: String,
+    // Where `samples` starts in the segment.
+    pub position_ms: u64,
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    // Interleaved.
+    pub samples: Vec<f32>,
+    // No audio left after this chunk.
+    pub done: bool,
+}
+
+impl SegmentPlayer {
+    pub fn open(path: &Path, name: &str) -> Result<Self, String> {
+        let reader = WavReader::open(path).map_err(|err| err.to_string())?;
+        let spec = reader.spec();
+        Ok(Self {
+            name: name.to_string(),
+            frames: reader.duration(),
+            sample_rate: spec.sample_rate,
+            channels: spec.channels.max(1),
+            reader,
+            position: 0,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn state(&self) -> PlaybackState {
+        PlaybackState {
+            name: self.name.clone(),
+            duration_ms: self.frames_to_ms(self.frames),
+            position_ms: self.frames_to_ms(self.position),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+        }
+    }
+
+    // Positions past the end clamp to it.
+    pub fn seek(&mut self, position_ms: u64) -> Result<PlaybackState, String> {
+        let frame = (position_ms.saturating_mul(self.sample_rate as u64) / 1000)
+            .min(self.frames as u64) as u32;
+        self.reader.seek(frame).map_err(|err| err.to_string())?;
+        self.position = frame;
+        Ok(self.state())
+    }
+
+    pub fn read(&mut self, max_ms: u64) -> Result<PlaybackChunk, String> {
+        let wanted = (max_ms.clamp(1, MAX_CHUNK_MS) * self.sample_rate as u64 / 1000).max(1);
+        let frames = wanted.min((self.frames - self.position) as u64) as u32;
+        let start = self.position;
+        let samples = writer::f32_samples(&mut self.reader)
+            .take(frames as usize * self.channels as usize)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.position += frames;
+        Ok(PlaybackChunk {
+            name: self.name.clone(),
+            position_ms: self.frames_to_ms(start),
+            duration_ms: self.frames_to_ms(frames),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            samples,
+            done: self.position >= self.frames,
+        })
+    }
+
+    fn frames_to_ms(&self, frames: u32) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        frames as u64 * 1000 / self.sample_rate as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentPlayer;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+
+    #[test]
+    fn reads_in_chunks_from_any_position() {
+        let path =
+            std::env::temp_dir().join(format!("ai_shepherd_playback_{}.wav", std::process::id()));
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 1_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for frame in 0..2_500 {
+            writer.write_sample(frame as f32).unwrap();
+            writer.write_sample(-(frame as f32)).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut player = SegmentPlayer::open(&path, "a.wav").unwrap();
+        assert_eq!(player.state().duration_ms, 2_500);
+        let chunk = player.read(1_000).unwrap();
+        assert_eq!((chunk.position_ms, chunk.samples.len()), (0, 2_000));
+        assert_eq!(player.state().position_ms, 1_000);
+
+        let state = player.seek(2_000).unwrap();
+        assert_eq!(state.position_ms, 2_000);
+        let chunk = player.read(1_000).unwrap();
+        assert_eq!(chunk.duration_ms, 500);
+        assert_eq!(&chunk.samples[..2], [2_000.0, -2_000.0]);
+        assert!(chunk.done);
+        assert!(player.read(1_000).unwrap().samples.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,311 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverflowPolicy {
+    // Evict the item that has waited longest.
+    DropOldest,
+    // Replace the newest queued item, so only the latest of a burst survives.
+    Coalesce,
+    // Make the producer wait until a worker frees a slot.
+    Block,
+}
+
+impl OverflowPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace(['-', '_'], "").as_str() {
+            "dropoldest" => Some(Self::DropOldest),
+            "coalesce" => Some(Self::Coalesce),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueueStats {
+    pub name: &'static str,
+    pub depth: usize,
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+    pub pushed: u64,
+    pub dropped: u64,
+    pub coalesced: u64,
+    pub high_water: usize,
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    pushed: u64,
+    dropped: u64,
+    coalesced: u64,
+    high_water: usize,
+}
+
+pub struct BoundedQueue<T> {
+    name: &'static str,
+    capacity: usize,
+    policy: OverflowPolicy,
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    drained: Condvar,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(name: &'static str, capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            name,
+            capacity: capacity.max(1),
+            policy,
+            state: Mutex::new(QueueState {
+                items: VecDeque::new(),
+                pushed: 0,
+                dropped: 0,
+                coalesced: 0,
+                high_water: 0,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            drained: Condvar::new(),
+        }
+    }
+
+    // Returns the item evicted to make room, if any, so the caller can settle it.
+    pub fn push(&self, item: T) -> Option<T> {
+        self.insert_with(item, |items| Some(items.len()))
+    }
+
+    // `position` picks the insertion index, or `None` to discard the item as already queued.
+    pub fn insert_with(
+        &self,
+        item: T,
+        position: impl Fn(&VecDeque<T>) -> Option<usize>,
+    ) -> Option<T> {
+        let mut guard = self.lock();
+        position(&guard.items)?;
+        let mut evicted = None;
+        if guard.items.len() >= self.capacity {
+            match self.policy {
+                OverflowPolicy::DropOldest => {
+                    evicted = guard.items.pop_front();
+                    guard.dropped += 1;
+                }
+                OverflowPolicy::Coalesce => {
+                    evicted = guard.items.pop_back();
+                    guard.coalesced += 1;
+                }
+                OverflowPolicy::Block => {
+                    while guard.items.len() >= self.capacity {
+                        guard = match self.not_full.wait(guard) {
+                            Ok(guard) => guard,
+                            Err(poisoned) => poisoned.into_inner(),
+                        };
+                    }
+                }
+            }
+        }
+        let Some(index) = position(&guard.items) else {
+            return evicted;
+        };
+        let index = index.min(guard.items.len());
+        guard.items.insert(index, item);
+        guard.pushed += 1;
+        guard.high_water = guard.high_water.max(guard.items.len());
+        self.not_empty.notify_one();
+        evicted
+    }
+
+    // Puts an item back in front whatever the capacity: it was admitted once already, e.g. by a
+    // worker that was restarted while holding it, and waiting for room could stall the watchdog.
+    pub fn requeue(&self, item: T) {
+        let mut guard = self.lock();
+        guard.items.push_front(item);
+        guard.high_water = guard.high_water.max(guard.items.len());
+        self.not_empty.notify_one();
+    }
+
+    // Counts an item the producer shed instead of queueing.
+    pub fn record_dropped(&self) {
+        self.lock().dropped += 1;
+    }
+
+    pub fn pop(&self) -> T {
+        let mut guard = self.lock();
+        loop {
+            if let Some(item) = guard.items.pop_front() {
+                self.notify_removed(&guard);
+                return item;
+            }
+            guard = match self.not_empty.wait(guard) {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        let mut guard = self.lock();
+        let item = guard.items.pop_front();
+        if item.is_some() {
+            self.notify_removed(&guard);
+        }
+        item
+    }
+
+    // Blocks until nothing is queued, for lower-priority work that yields to this queue.
+    pub fn wait_until_empty(&self) {
+        let mut guard = self.lock();
+        while !guard.items.is_empty() {
+            guard = match self.drained.wait(guard) {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+    }
+
+    pub fn clear(&self) {
+        self.lock().items.clear();
+        self.not_full.notify_all();
+        self.drained.notify_all();
+    }
+
+    // Drops queued items `keep` rejects; returns how many went.
+    pub fn retain(&self, keep: impl Fn(&T) -> bool) -> usize {
+        let mut guard = self.lock();
+        let before = guard.items.len();
+        guard.items.retain(|item| keep(item));
+        let removed = before - guard.items.len();
+        if removed > 0 {
+            self.not_full.notify_all();
+            if guard.items.is_empty() {
+                self.drained.notify_all();
+            }
+        }
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        let guard = self.lock();
+        QueueStats {
+            name: self.name,
+            depth: guard.items.len(),
+            capacity: self.capacity,
+            overflow: self.policy,
+            pushed: guard.pushed,
+            dropped: guard.dropped,
+            coalesced: guard.coalesced,
+            high_water: guard.high_water,
+        }
+    }
+
+    fn notify_removed(&self, guard: &QueueState<T>) {
+        self.not_full.notify_one();
+        if guard.items.is_empty() {
+            self.drained.notify_all();
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState<T>> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedQueue, OverflowPolicy};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn overflow_policies_evict_the_expected_item() {
+        let queue = BoundedQueue::new("test", 2, OverflowPolicy::DropOldest);
+        assert_eq!(queue.push(1), None);
+        assert_eq!(queue.push(2), None);
+        assert_eq!(queue.push(3), Some(1));
+        assert_eq!((queue.pop(), queue.pop()), (2, 3));
+
+        let queue = BoundedQueue::new("test", 2, OverflowPolicy::Coalesce);
+        queue.push(1);
+        queue.push(2);
+        assert_eq!(queue.push(3), Some(2));
+        let stats = queue.stats();
+        assert_eq!((stats.depth, stats.coalesced, stats.high_water), (2, 1, 2));
+        assert_eq!((queue.pop(), queue.pop()), (1, 3));
+    }
+
+    #[test]
+    fn retain_removes_only_rejected_items() {
+        let queue = BoundedQueue::new("test", 4, OverflowPolicy::Block);
+        for value in [1, 2, 3, 2] {
+            queue.push(value);
+        }
+        assert_eq!(queue.retain(|value| *value != 2), 2);
+        assert_eq!(queue.retain(|value| *value != 2), 0);
+        assert_eq!((queue.pop(), queue.pop()), (1, 3));
+        assert_eq!(queue.stats().pushed, 4);
+    }
+
+    #[test]
+    fn wait_until_empty_returns_once_the_last_item_is_taken() {
+        let queue = Arc::new(BoundedQueue::new("test", 4, OverflowPolicy::Block));
+        queue.wait_until_empty();
+        queue.push(1);
+        queue.push(2);
+        let waiter = {
+            let queue = queue.clone();
+            thread::spawn(move || queue.wait_until_empty())
+        };
+        assert_eq!(queue.pop(), 1);
+        assert_eq!(queue.try_pop(), Some(2));
+        waiter.join().unwrap();
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn requeue_goes_first_even_when_full() {
+        let queue = BoundedQueue::new("test", 1, OverflowPolicy::Block);
+        queue.push(1);
+        queue.requeue(0);
+        queue.record_dropped();
+        let stats = queue.stats();
+        assert_eq!((stats.depth, stats.high_water, stats.dropped), (2, 2, 1));
+        assert_eq!((queue.pop(), queue.pop()), (0, 1));
+    }
+
+    #[test]
+    fn ordered_insert_skips_duplicates() {
+        let queue = BoundedQueue::new("test", 4, OverflowPolicy::Block);
+        let ordered = |value: u32| {
+            move |items: &std::collections::VecDeque<u32>| {
+                if items.contains(&value) {
+                    return None;
+                }
+                Some(
+                    items
+                        .iter()
+                        .position(|item| value < *item)
+                        .unwrap_or(items.len()),
+                )
+            }
+        };
+        queue.insert_with(5, ordered(5));
+        queue.insert_with(2, ordered(2));
+        queue.insert_with(5, ordered(5));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), 2);
+        assert_eq!(
+            OverflowPolicy::parse("drop-oldest"),
+            Some(OverflowPolicy::DropOldest)
+        );
+    }
+}
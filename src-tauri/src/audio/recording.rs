@@ -0,0 +1,61 @@
+use crate::audio::writer::WavSink;
+use hound::{SampleFormat, WavSpec};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Continuous recordings live next to the segments, one per session.
+const RECORDINGS_DIR: &str = "recordings";
+
+pub fn recordings_dir(segments_dir: &Path) -> PathBuf {
+    segments_dir.join(RECORDINGS_DIR)
+}
+
+pub fn recording_path(segments_dir: &Path, session_id: &str) -> PathBuf {
+    recordings_dir(segments_dir).join(format!("{session_id}.wav"))
+}
+
+// Everything a session captured, in one 16-bit file: float samples would double the size of a
+// multi-hour meeting for no audible gain.
+pub fn start(
+    segments_dir: &Path,
+    session_id: &str,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<WavSink, String> {
+    let path = recording_path(segments_dir, session_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let spec = WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    WavSink::create(&path, spec, "session-recorder")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recording_path, start};
+
+    #[test]
+    fn records_a_session_as_16_bit_wav() {
+        let dir =
+            std::env::temp_dir().join(format!("ai_shepherd_recording_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut sink = start(&dir, "session_1", 16_000, 2).unwrap();
+        sink.write(&[0.5, -0.5, 1.5, 0.0]).unwrap();
+        sink.finish().unwrap();
+
+        let mut reader = hound::WavReader::open(recording_path(&dir, "session_1")).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        let samples = reader
+            .samples::<i16>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(samples, [16_383, -16_383, i16::MAX, 0]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
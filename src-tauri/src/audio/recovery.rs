@@ -0,0 +1,219 @@
+use crate::audio::manager::SegmentInfo;
+use crate::audio::recording;
+use crate::timestamps::rfc3339;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use hound::WavReader;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const SEGMENT_PREFIX: &str = "segment_";
+const SEGMENT_NAME_FORMAT: &str = "segment_%Y%m%d_%H%M%S_%3f";
+// Left by a merge that never finished; the first segment is still intact.
+const MERGE_SCRATCH_EXTENSION: &str = "merging";
+
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    // Segment files missing from the index, headers fixed, not yet assigned to a session.
+    pub segments: Vec<SegmentInfo>,
+    pub recordings: usize,
+}
+
+// Fixes what a crash leaves behind in the segments directory: segments the index never heard of,
+// WAV headers that still describe the last flush, and merge scratch files. Only safe while nothing
+// is being written.
+pub fn recover(dir: &Path, indexed: &HashSet<String>) -> RecoveryReport {
+    let mut report = RecoveryReport::default();
+    for path in files(dir) {
+        let Some(name) = path.file_name().and_then(|value| value.to_str()) else {
+            continue;
+        };
+        if path.extension().and_then(|value| value.to_str()) == Some(MERGE_SCRATCH_EXTENSION) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+        if !name.starts_with(SEGMENT_PREFIX) || !name.ends_with(".wav") || indexed.contains(name) {
+            continue;
+        }
+        match salvage(&path, name) {
+            Ok(info) => report.segments.push(info),
+            Err(err) => warn!("[recovery] {name} not recovered: {err}"),
+        }
+    }
+    for path in files(&recording::recordings_dir(dir)) {
+        match repair_header(&path) {
+            Ok(true) => report.recordings += 1,
+            Ok(false) => {}
+            Err(err) => warn!("[recovery] {} not repaired: {err}", path.display()),
+        }
+    }
+    report
+        .segments
+        .sort_by(|left, right| left.name.cmp(&right.name));
+    report
+}
+
+fn salvage(path: &Path, name: &str) -> Result<SegmentInfo, String> {
+    repair_header(path)?;
+    let reader = WavReader::open(path).map_err(|err| err.to_string())?;
+    let spec = reader.spec();
+    let duration_ms = reader.duration() as u64 * 1000 / spec.sample_rate.max(1) as u64;
+    Ok(SegmentInfo {
+        name: name.to_string(),
+        duration_ms,
+        created_at: created_at(path, name),
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        ..SegmentInfo::default()
+    })
+}
+
+// Segment names carry their start time; the file time is the fallback.
+fn created_at(path: &Path, name: &str) -> String {
+    let stem = name.trim_end_matches(".wav");
+    let parsed = NaiveDateTime::parse_from_str(stem, SEGMENT_NAME_FORMAT)
+        .ok()
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest());
+    let at = parsed.unwrap_or_else(|| {
+        fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .map(DateTime::<Local>::from)
+            .unwrap_or_else(|_| Local::now())
+    });
+    rfc3339(&at)
+}
+
+// Rewrites the RIFF and data sizes to cover every whole frame in the file, dropping a torn last
+// frame. Returns whether anything changed.
+pub fn repair_header(path: &Path) -> Result<bool, String> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|err| err.to_string())?;
+    let file_len = file.metadata().map_err(|err| err.to_string())?.len();
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff).map_err(|err| err.to_string())?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err("not a WAV file".to_string());
+    }
+
+    let mut block_align = None;
+    let mut offset = 12u64;
+    loop {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut header))
+            .map_err(|_| "no data chunk".to_string())?;
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        if &header[0..4] == b"fmt " {
+            block_align = Some(read_block_align(&mut file, offset)?);
+        }
+        if &header[0..4] == b"data" {
+            break;
+        }
+        offset += 8 + size + size % 2;
+    }
+
+    let block_align = block_align.ok_or_else(|| "no fmt chunk before data".to_string())?;
+    let data_start = offset + 8;
+    let data_len = file_len.saturating_sub(data_start) / block_align * block_align;
+    let (Ok(data_size), Ok(riff_size)) = (
+        u32::try_from(data_len),
+        u32::try_from(data_start + data_len - 8),
+    ) else {
+        return Err("file too large for a WAV header".to_string());
+    };
+    let mut declared = [0u8; 4];
+    file.seek(SeekFrom::Start(offset + 4))
+        .and_then(|_| file.read_exact(&mut declared))
+        .map_err(|err| err.to_string())?;
+    if u32::from_le_bytes(declared) == data_size
+        && u32::from_le_bytes([riff[4], riff[5], riff[6], riff[7]]) == riff_size
+        && file_len == data_start + data_len
+    {
+        return Ok(false);
+    }
+
+    file.set_len(data_start + data_len)
+        .map_err(|err| err.to_string())?;
+    file.seek(SeekFrom::Start(4))
+        .and_then(|_| file.write_all(&riff_size.to_le_bytes()))
+        .and_then(|_| file.seek(SeekFrom::Start(offset + 4)))
+        .and_then(|_| file.write_all(&data_size.to_le_bytes()))
+        .and_then(|_| file.sync_all())
+        .map_err(|err| err.to_string())?;
+    Ok(true)
+}
+
+fn read_block_align(file: &mut File, fmt_offset: u64) -> Result<u64, String> {
+    let mut value = [0u8; 2];
+    file.seek(SeekFrom::Start(fmt_offset + 8 + 12))
+        .and_then(|_| file.read_exact(&mut value))
+        .map_err(|err| err.to_string())?;
+    match u16::from_le_bytes(value) {
+        0 => Err("invalid block align".to_string()),
+        align => Ok(align as u64),
+    }
+}
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recover;
+    use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+    use std::collections::HashSet;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    #[test]
+    fn salvages_segments_left_by_a_crash() {
+        let dir = std::env::temp_dir().join(format!("ai_shepherd_recovery_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 1_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let path = dir.join("segment_20240102_030405_678.wav");
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for _ in 0..250 * 2 {
+            writer.write_sample(0.25f32).unwrap();
+        }
+        writer.flush().unwrap();
+        // The process dies after more audio reached the file but before the next header update:
+        // 250 more frames and half of one.
+        std::mem::forget(writer);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0u8; 250 * 8 + 4]).unwrap();
+        drop(file);
+        std::fs::write(dir.join("segment_20240102_030400_000.merging"), b"").unwrap();
+
+        let indexed = HashSet::from(["segment_20240102_030000_000.wav".to_string()]);
+        let report = recover(&dir, &indexed);
+        assert_eq!(report.segments.len(), 1);
+        let info = &report.segments[0];
+        assert_eq!(info.duration_ms, 500);
+        assert!(info.created_at.starts_with("2024-01-02T03:04:05.678"));
+        assert_eq!(WavReader::open(&path).unwrap().duration(), 500);
+        assert!(!dir.join("segment_20240102_030400_000.merging").exists());
+
+        // Once repaired, a second pass leaves the file alone.
+        assert!(!super::repair_header(&path).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
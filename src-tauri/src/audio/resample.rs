@@ -0,0 +1,158 @@
+use rubato::{FftFixedIn, Resampler};
+
+// What whisper works at; storing segments like this saves the server resampling every upload.
+pub const ASR_SAMPLE_RATE: u32 = 16_000;
+// Input frames per FFT pass.
+const CHUNK_FRAMES: usize = 1_024;
+const SUB_CHUNKS: usize = 2;
+
+// Downmixes interleaved audio to mono and resamples it to `ASR_SAMPLE_RATE` (or another target),
+// streaming: input of any length goes in, the converted audio comes out as whole chunks complete.
+pub struct MonoResampler {
+    channels: usize,
+    // `None` when the input already runs at the target rate.
+    inner: Option<FftFixedIn<f32>>,
+    pending: Vec<f32>,
+    // Leading output frames that are only the filter's delay.
+    skip: usize,
+    input_rate: u32,
+    output_rate: u32,
+    frames_in: u64,
+    frames_out: u64,
+}
+
+impl MonoResampler {
+    pub fn new(sample_rate: u32, channels: u16) -> Result<Self, String> {
+        Self::with_target(sample_rate, channels, ASR_SAMPLE_RATE)
+    }
+
+    pub fn with_target(sample_rate: u32, channels: u16, output_rate: u32) -> Result<Self, String> {
+        let inner = if sample_rate == output_rate {
+            None
+        } else {
+            Some(
+                FftFixedIn::new(
+                    sample_rate as usize,
+                    output_rate as usize,
+                    CHUNK_FRAMES,
+                    SUB_CHUNKS,
+                    1,
+                )
+                .map_err(|err| err.to_string())?,
+            )
+        };
+        Ok(Self {
+            channels: channels.max(1) as usize,
+            skip: inner.as_ref().map_or(0, |inner| inner.output_delay()),
+            inner,
+            pending: Vec::new(),
+            input_rate: sample_rate.max(1),
+            output_rate,
+            frames_in: 0,
+            frames_out: 0,
+        })
+    }
+
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>, String> {
+        let channels = self.channels;
+        let mono = samples
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32);
+        self.frames_in += (samples.len() / channels) as u64;
+        let Some(inner) = self.inner.as_mut() else {
+            let out = mono.collect::<Vec<_>>();
+            self.frames_out += out.len() as u64;
+            return Ok(out);
+        };
+        self.pending.extend(mono);
+        let mut out = Vec::new();
+        loop {
+            let needed = inner.input_frames_next();
+            if self.pending.len() < needed {
+                break;
+            }
+            let chunk = self.pending.drain(..needed).collect::<Vec<_>>();
+            let mut converted = inner
+                .process(&[chunk], None)
+                .map_err(|err| err.to_string())?;
+            out.append(&mut converted[0]);
+        }
+        Ok(self.emit(out))
+    }
+
+    // Converts what is still buffered and flushes the filter; the total output matches the input
+    // duration.
+    pub fn finish(&mut self) -> Result<Vec<f32>, String> {
+        let expected = self.frames_in * self.output_rate as u64 / self.input_rate as u64;
+        let Some(inner) = self.inner.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let mut out = Vec::new();
+        let pending = std::mem::take(&mut self.pending);
+        let mut converted = inner
+            .process_partial(Some(&[pending]), None)
+            .map_err(|err| err.to_string())?;
+        out.append(&mut converted[0]);
+        // The delay line still holds audio that only comes out with more input.
+        while (self.frames_out + out.len().saturating_sub(self.skip) as u64) < expected {
+            let mut converted = inner
+                .process_partial::<Vec<f32>>(None, None)
+                .map_err(|err| err.to_string())?;
+            if converted[0].is_empty() {
+                break;
+            }
+            out.append(&mut converted[0]);
+        }
+        let before = self.frames_out;
+        let mut out = self.emit(out);
+        out.truncate(expected.saturating_sub(before) as usize);
+        self.frames_out = before + out.len() as u64;
+        Ok(out)
+    }
+
+    fn emit(&mut self, mut out: Vec<f32>) -> Vec<f32> {
+        let skipped = self.skip.min(out.len());
+        out.drain(..skipped);
+        self.skip -= skipped;
+        self.frames_out += out.len() as u64;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MonoResampler, ASR_SAMPLE_RATE};
+
+    #[test]
+    fn downmixes_and_keeps_the_duration() {
+        // One second of a 440 Hz tone, 48 kHz stereo, fed in uneven packets.
+        let input = (0..48_000)
+            .flat_map(|frame| {
+                let value = (frame as f32 * 440.0 * std::f32::consts::TAU / 48_000.0).sin() * 0.5;
+                [value, value]
+            })
+            .collect::<Vec<_>>();
+        let mut resampler = MonoResampler::new(48_000, 2).unwrap();
+        let mut out = Vec::new();
+        for packet in input.chunks(960 * 2 + 6) {
+            out.extend(resampler.process(packet).unwrap());
+        }
+        out.extend(resampler.finish().unwrap());
+        assert_eq!(out.len(), ASR_SAMPLE_RATE as usize);
+        // The tone survives at its level, with the filter delay trimmed off the front.
+        let peak = out[4_000..12_000]
+            .iter()
+            .fold(0.0f32, |max, value| max.max(value.abs()));
+        assert!((peak - 0.5).abs() < 0.02, "peak {peak}");
+        let expected = (8_000.0 * 440.0 * std::f32::consts::TAU / 16_000.0).sin() * 0.5;
+        assert!((out[8_000] - expected).abs() < 0.05);
+
+        // Already at 16 kHz only downmixes.
+        let mut resampler = MonoResampler::new(ASR_SAMPLE_RATE, 2).unwrap();
+        assert_eq!(
+            resampler.process(&[0.2, 0.4, -1.0, 0.0]).unwrap(),
+            [0.3, -0.5]
+        );
+        assert!(resampler.finish().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::audio::config::AudioConfig;
+use crate::audio::index;
+use crate::translation_cache;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+const RECORDINGS_DIR: &str = "recordings";
+// Scratch file of window transcription, rewritten all the time.
+const WINDOW_FILE: &str = "window_live.wav";
+// Files this new may still be waiting for VAD or transcription, before they are indexed.
+const MIN_AGE: Duration = Duration::from_secs(5 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetentionPolicy {
+    // 0 for no limit.
+    pub max_bytes: u64,
+    pub max_age: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &AudioConfig) -> Self {
+        Self {
+            max_bytes: config.max_storage_mb.saturating_mul(1024 * 1024),
+            max_age: (config.max_storage_age_days > 0)
+                .then(|| DAY * config.max_storage_age_days.min(u32::MAX as u64) as u32),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes == 0 && self.max_age.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageStats {
+    pub dir: String,
+    pub total_bytes: u64,
+    pub segment_files: usize,
+    pub segment_bytes: u64,
+    pub recording_files: usize,
+    pub recording_bytes: u64,
+    // Index, session list and anything else that is never pruned.
+    pub other_bytes: u64,
+    pub indexed_segments: usize,
+    pub oldest_segment_at: Option<String>,
+    pub max_bytes: u64,
+    pub max_age_days: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub files: usize,
+    pub bytes: u64,
+    // Index entries dropped along with their audio.
+    pub segments: usize,
+}
+
+#[derive(Debug, Clone)]
+struct StoredFile {
+    path: PathBuf,
+    // Segment file name, for its index entry; `None` for a continuous recording.
+    segment: Option<String>,
+    session_id: Option<String>,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+pub fn stats(dir: &Path, config: &AudioConfig) -> StorageStats {
+    let files = audio_files(dir, &HashMap::new());
+    let segments = index::load(dir);
+    let mut stats = StorageStats {
+        dir: dir.to_string_lossy().to_string(),
+        total_bytes: dir_size(dir),
+        indexed_segments: segments.len(),
+        oldest_segment_at: segments.first().map(|segment| segment.created_at.clone()),
+        max_bytes: config.max_storage_mb.saturating_mul(1024 * 1024),
+        max_age_days: config.max_storage_age_days,
+        ..StorageStats::default()
+    };
+    for file in &files {
+        if file.segment.is_some() {
+            stats.segment_files += 1;
+            stats.segment_bytes += file.bytes;
+        } else {
+            stats.recording_files += 1;
+            stats.recording_bytes += file.bytes;
+        }
+    }
+    stats.other_bytes = stats
+        .total_bytes
+        .saturating_sub(stats.segment_bytes + stats.recording_bytes);
+    stats
+}
+
+// Deletes the oldest audio until the directory fits the policy and drops the index entries of
+// pruned segments. Files of `protected_session` and very recent files are always kept.
+pub fn enforce(
+    dir: &Path,
+    policy: RetentionPolicy,
+    protected_session: Option<&str>,
+) -> Result<PruneReport, String> {
+    if policy.is_unlimited() {
+        return Ok(PruneReport::default());
+    }
+    let sessions = index::load(dir)
+        .into_iter()
+        .filter_map(|segment| Some((segment.name, segment.session_id?)))
+        .collect::<HashMap<_, _>>();
+    let files = audio_files(dir, &sessions);
+    let selected = select_expired(
+        &files,
+        policy,
+        dir_size(dir),
+        SystemTime::now(),
+        protected_session,
+    );
+
+    let mut report = PruneReport::default();
+    let mut pruned = HashSet::new();
+    for file in selected.into_iter().map(|index| &files[index]) {
+        if let Err(err) = fs::remove_file(&file.path) {
+            warn!("[retention] {} not removed: {err}", file.path.display());
+            continue;
+        }
+        report.files += 1;
+        report.bytes += file.bytes;
+        if let Some(name) = &file.segment {
+            pruned.insert(name.clone());
+        }
+    }
+    if !pruned.is_empty() {
+        let mut transcripts = Vec::new();
+        index::rewrite(dir, |segments| {
+            segments.retain(|segment| {
+                let keep = !pruned.contains(&segment.name);
+                if !keep {
+                    transcripts.extend(segment.transcript.clone());
+                }
+                keep
+            });
+        })?;
+        translation_cache::forget(transcripts.iter().map(String::as_str));
+        report.segments = pruned.len();
+    }
+    Ok(report)
+}
+
+// Indexes into `files`: everything past `max_age`, then the oldest of the rest until `total_bytes`
+// is within `max_bytes`.
+fn select_expired(
+    files: &[StoredFile],
+    policy: RetentionPolicy,
+    total_bytes: u64,
+    now: SystemTime,
+    protected_session: Option<&str>,
+) -> Vec<usize> {
+    let age = |file: &StoredFile| now.duration_since(file.modified).unwrap_or_default();
+    let mut candidates = (0..files.len())
+        .filter(|&index| {
+            let file = &files[index];
+            age(file) >= MIN_AGE
+                && (protected_session.is_none() || file.session_id.as_deref() != protected_session)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|&index| files[index].modified);
+
+    let mut remaining = total_bytes;
+    let mut selected = Vec::new();
+    for index in candidates {
+        let file = &files[index];
+        let expired = policy.max_age.is_some_and(|max_age| age(file) > max_age);
+        let over_quota = policy.max_bytes > 0 && remaining > policy.max_bytes;
+        if !expired && !over_quota {
+            continue;
+        }
+        remaining = remaining.saturating_sub(file.bytes);
+        selected.push(index);
+    }
+    selected
+}
+
+// Segment WAVs and continuous recordings; `sessions` maps segment names to their session.
+fn audio_files(dir: &Path, sessions: &HashMap<String, String>) -> Vec<StoredFile> {
+    let mut files = Vec::new();
+    for (path, recording) in wav_files(dir, false)
+        .into_iter()
+        .chain(wav_files(&dir.join(RECORDINGS_DIR), true))
+    {
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        let name = path
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let (segment, session_id) = if recording {
+            let stem = path.file_stem().and_then(|value| value.to_str());
+            (None, stem.map(str::to_string))
+        } else {
+            let session_id = sessions.get(&name).cloned();
+            (Some(name), session_id)
+        };
+        files.push(StoredFile {
+            path,
+            segment,
+            session_id,
+            bytes: meta.len(),
+            modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        });
+    }
+    files
+}
+
+fn wav_files(dir: &Path, recording: bool) -> Vec<(PathBuf, bool)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().and_then(|value| value.to_str()) == Some("wav"))
+        .filter(|path| path.file_name().and_then(|value| value.to_str()) != Some(WINDOW_FILE))
+        .map(|path| (path, recording))
+        .collect()
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_expired, RetentionPolicy, StoredFile, DAY};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn file(name: &str, session: &str, days_old: u32, bytes: u64, now: SystemTime) -> StoredFile {
+        StoredFile {
+            path: PathBuf::from(name),
+            segment: Some(name.to_string()),
+            session_id: Some(session.to_string()),
+            bytes,
+            modified: now - DAY * days_old - Duration::from_secs(600),
+        }
+    }
+
+    #[test]
+    fn prunes_expired_then_oldest_over_quota() {
+        let now = SystemTime::now();
+        let files = [
+            file("c.wav", "s2", 1, 100, now),
+            file("a.wav", "s1", 10, 100, now),
+            file("b.wav", "s1", 5, 100, now),
+            file("d.wav", "s3", 0, 100, now),
+        ];
+        let policy = RetentionPolicy {
+            max_bytes: 0,
+            max_age: Some(DAY * 7),
+        };
+        assert_eq!(select_expired(&files, policy, 400, now, None), [1]);
+
+        // Oldest first until 450 bytes (with 50 of index) fit in 250.
+        let policy = RetentionPolicy {
+            max_bytes: 250,
+            max_age: None,
+        };
+        assert_eq!(select_expired(&files, policy, 450, now, None), [1, 2]);
+        // The running session is never touched, whatever the quota.
+        assert_eq!(select_expired(&files, policy, 450, now, Some("s1")), [0, 3]);
+
+        let mut fresh = file("e.wav", "s4", 0, 1_000, now);
+        fresh.modified = now;
+        assert!(select_expired(&[fresh], policy, 1_000, now, None).is_empty());
+    }
+}
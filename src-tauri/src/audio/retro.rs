@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use std::collections::VecDeque;
+
+// The last few minutes of interleaved PCM, kept while capture is armed but not recording so
+// `flush_armed_capture` can turn what was just said into segments after the fact.
+pub struct RetroBuffer {
+    sample_rate: u32,
+    channels: u16,
+    capacity: usize,
+    samples: VecDeque<f32>,
+    // Wall-clock time of the newest sample, to date the flushed audio.
+    last_at: Option<DateTime<Local>>,
+}
+
+impl RetroBuffer {
+    pub fn new(sample_rate: u32, channels: u16, seconds: u64) -> Self {
+        let channels = channels.max(1);
+        Self {
+            sample_rate,
+            channels,
+            capacity: samples_for(sample_rate, channels, seconds),
+            samples: VecDeque::new(),
+            last_at: None,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.frames_to_ms(self.samples.len())
+    }
+
+    pub fn push(&mut self, pcm: &[f32]) {
+        self.samples.extend(pcm.iter().copied());
+        // Whole frames are dropped so channels stay aligned.
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        let excess = excess.div_ceil(self.channels as usize) * self.channels as usize;
+        self.samples.drain(..excess.min(self.samples.len()));
+        self.last_at = Some(Local::now());
+    }
+
+    // Removes and returns up to the last `seconds` (everything when `None`) with the time the
+    // first returned sample was captured.
+    pub fn take(&mut self, seconds: Option<u64>) -> Option<(DateTime<Local>, Vec<f32>)> {
+        let last_at = self.last_at?;
+        let wanted = seconds
+            .map(|seconds| samples_for(self.sample_rate, self.channels, seconds))
+            .unwrap_or(usize::MAX)
+            .min(self.samples.len());
+        if wanted == 0 {
+            return None;
+        }
+        let pcm = self
+            .samples
+            .drain(self.samples.len() - wanted..)
+            .collect::<Vec<_>>();
+        self.samples.clear();
+        let started_at = last_at - ChronoDuration::milliseconds(self.frames_to_ms(wanted) as i64);
+        Some((started_at, pcm))
+    }
+
+    fn frames_to_ms(&self, samples: usize) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        (samples / self.channels as usize) as u64 * 1000 / self.sample_rate as u64
+    }
+}
+
+fn samples_for(sample_rate: u32, channels: u16, seconds: u64) -> usize {
+    (sample_rate as u64)
+        .saturating_mul(seconds)
+        .saturating_mul(channels.max(1) as u64)
+        .min(usize::MAX as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetroBuffer;
+
+    #[test]
+    fn keeps_only_the_latest_whole_frames() {
+        let mut buffer = RetroBuffer::new(4, 2, 2);
+        assert!(buffer.take(None).is_none());
+
+        // Capacity is 16 samples; the oldest frames go first.
+        let pcm = (0..20).map(|value| value as f32).collect::<Vec<_>>();
+        buffer.push(&pcm);
+        assert_eq!(buffer.duration_ms(), 2_000);
+        buffer.push(&[20.0, 21.0]);
+        assert_eq!(buffer.duration_ms(), 2_000);
+
+        let (_, last_second) = buffer.take(Some(1)).unwrap();
+        assert_eq!(
+            last_second,
+            [14.0, 15.0, 16.0, 17.0, 18.0, 19.0, 20.0, 21.0]
+        );
+        // A flush empties the buffer, so the same audio is never turned into segments twice.
+        assert_eq!(buffer.duration_ms(), 0);
+        assert!(buffer.take(None).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+// Single-producer single-consumer ring of interleaved samples between the capture thread and the
+// processing thread. Neither side takes a lock: the producer only moves `head`, the consumer only
+// moves `tail`, and the consumer parks until the producer unparks it.
+struct Shared {
+    buffer: Box<[UnsafeCell<f32>]>,
+    // Total samples written and read; their difference is the fill level.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    closed: AtomicBool,
+    consumer: OnceLock<Thread>,
+}
+
+// Each index range is only touched by one side at a time, as handed over through `head`/`tail`.
+unsafe impl Sync for Shared {}
+
+pub struct RingProducer {
+    shared: Arc<Shared>,
+}
+
+pub struct RingConsumer {
+    shared: Arc<Shared>,
+}
+
+pub fn channel(capacity: usize) -> (RingProducer, RingConsumer) {
+    let buffer = (0..capacity.max(1))
+        .map(|_| UnsafeCell::new(0.0))
+        .collect::<Vec<_>>()
+        .into_boxed_slice();
+    let shared = Arc::new(Shared {
+        buffer,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        closed: AtomicBool::new(false),
+        consumer: OnceLock::new(),
+    });
+    (
+        RingProducer {
+            shared: Arc::clone(&shared),
+        },
+        RingConsumer { shared },
+    )
+}
+
+impl RingProducer {
+    // All or nothing, so a full ring never splits a frame; returns false when `samples` was
+    // dropped.
+    pub fn push(&mut self, samples: &[f32]) -> bool {
+        let shared = &self.shared;
+        let capacity = shared.buffer.len();
+        let head = shared.head.load(Ordering::Relaxed);
+        let tail = shared.tail.load(Ordering::Acquire);
+        if capacity - head.wrapping_sub(tail) < samples.len() {
+            return false;
+        }
+        for (offset, sample) in samples.iter().enumerate() {
+            let slot = &shared.buffer[head.wrapping_add(offset) % capacity];
+            unsafe { *slot.get() = *sample };
+        }
+        shared
+            .head
+            .store(head.wrapping_add(samples.len()), Ordering::Release);
+        if !samples.is_empty() {
+            self.wake();
+        }
+        true
+    }
+
+    fn wake(&self) {
+        if let Some(consumer) = self.shared.consumer.get() {
+            consumer.unpark();
+        }
+    }
+}
+
+impl Drop for RingProducer {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        self.wake();
+    }
+}
+
+impl RingConsumer {
+    // Everything written so far, oldest first.
+    pub fn pop(&mut self) -> Vec<f32> {
+        let shared = &self.shared;
+        let capacity = shared.buffer.len();
+        let tail = shared.tail.load(Ordering::Relaxed);
+        let head = shared.head.load(Ordering::Acquire);
+        let len = head.wrapping_sub(tail);
+        let samples = (0..len)
+            .map(|offset| unsafe { *shared.buffer[tail.wrapping_add(offset) % capacity].get() })
+            .collect();
+        shared.tail.store(head, Ordering::Release);
+        samples
+    }
+
+    // Returns once samples are waiting, the producer is gone, or `timeout` passed.
+    pub fn wait(&self, timeout: Duration) {
+        let shared = &self.shared;
+        shared.consumer.get_or_init(thread::current);
+        if shared.head.load(Ordering::Acquire) != shared.tail.load(Ordering::Relaxed)
+            || shared.closed.load(Ordering::Acquire)
+        {
+            return;
+        }
+        thread::park_timeout(timeout);
+    }
+
+    // The producer is gone and everything it wrote has been read.
+    pub fn is_finished(&self) -> bool {
+        let shared = &self.shared;
+        shared.closed.load(Ordering::Acquire)
+            && shared.head.load(Ordering::Acquire) == shared.tail.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::channel;
+    use std::time::Duration;
+
+    #[test]
+    fn hands_samples_across_threads_in_order() {
+        let (mut producer, mut consumer) = channel(6);
+        assert!(producer.push(&[1.0, 2.0, 3.0, 4.0]));
+        // Whole packets only: two free slots are not enough for four samples.
+        assert!(!producer.push(&[5.0, 6.0, 7.0, 8.0]));
+        assert_eq!(consumer.pop(), [1.0, 2.0, 3.0, 4.0]);
+        // Wraps around the end of the buffer.
+        assert!(producer.push(&[5.0, 6.0, 7.0, 8.0]));
+        assert_eq!(consumer.pop(), [5.0, 6.0, 7.0, 8.0]);
+        assert!(consumer.pop().is_empty());
+
+        let writer = std::thread::spawn(move || {
+            for value in 0..1_000 {
+                while !producer.push(&[value as f32]) {
+                    std::thread::yield_now();
+                }
+            }
+        });
+        let mut received = Vec::new();
+        while !consumer.is_finished() {
+            consumer.wait(Duration::from_millis(50));
+            received.extend(consumer.pop());
+        }
+        writer.join().unwrap();
+        received.extend(consumer.pop());
+        let expected = (0..1_000).map(|value| value as f32).collect::<Vec<_>>();
+        assert_eq!(received, expected);
+    }
+}
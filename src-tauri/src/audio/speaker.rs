@@ -1,21 +1,20 @@
+use crate::acceleration::ProviderSelection;
 use crate::app_config::load_config;
+use crate::defaults::{
+    DEFAULT_SPEAKER_CONSECUTIVE_HITS, DEFAULT_SPEAKER_MAX_SPEAKERS, DEFAULT_SPEAKER_MIN_GAP_MS,
+    DEFAULT_SPEAKER_MIN_RMS_DB, DEFAULT_SPEAKER_MODEL_PATH, DEFAULT_SPEAKER_NEW_THRESHOLD,
+    DEFAULT_SPEAKER_STEP_MS, DEFAULT_SPEAKER_UPDATE_THRESHOLD, DEFAULT_SPEAKER_WINDOW_MS,
+};
 use ndarray::Array3;
 use ort::session::Session;
 use ort::value::TensorRef;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
+use tracing::warn;
 
 const TARGET_SAMPLE_RATE: u32 = 16_000;
 const TARGET_WINDOW_SAMPLES: usize = 16_000;
-const DEFAULT_NEW_SPEAKER_THRESHOLD: f32 = 0.75;
-const DEFAULT_UPDATE_THRESHOLD: f32 = 0.80;
-const DEFAULT_MAX_SPEAKERS: u32 = 8;
-const DEFAULT_WINDOW_MS: u64 = 2_000;
-const DEFAULT_STEP_MS: u64 = 1_000;
-const DEFAULT_MIN_RMS_DB: f32 = -45.0;
-const DEFAULT_CONSECUTIVE_HITS: u32 = 3;
-const DEFAULT_MIN_GAP_MS: u64 = 3_000;
 const DEFAULT_UPDATE_ALPHA: f32 = 0.8;
 
 #[derive(Debug, Clone)]
@@ -34,91 +33,12 @@ pub struct SpeakerDiarizer {
 
 impl SpeakerDiarizer {
     pub fn new(app: &AppHandle) -> Option<Self> {
-        let config = match load_config() {
-            Ok(config) => config,
-            Err(err) => {
-                eprintln!("speaker config unavailable: {err}");
-                return None;
-            }
-        };
-
-        let speaker = match config.speaker {
-            Some(config) => config,
-            None => return None,
-        };
-
-        if speaker.enabled == Some(false) {
-            return None;
-        }
-
-        let resource_dir = app.path().resource_dir().ok();
-        let model_path = resolve_model_path(
-            speaker
-                .model_path
-                .as_deref()
-                .or(Some("resources/models/pyannote_embedding.onnx")),
-            resource_dir,
-        );
-        let model_path = match model_path {
-            Some(path) => path,
-            None => {
-                eprintln!("speaker model path not set");
-                return None;
-            }
-        };
-        if !model_path.exists() {
-            eprintln!("speaker model not found: {}", model_path.display());
-            return None;
-        }
-
-        let new_threshold = speaker
-            .similarity_threshold
-            .unwrap_or(DEFAULT_NEW_SPEAKER_THRESHOLD);
-        let update_threshold = speaker
-            .update_threshold
-            .unwrap_or(DEFAULT_UPDATE_THRESHOLD)
-            .max(new_threshold);
-        let max_speakers = speaker.max_speakers.or(Some(DEFAULT_MAX_SPEAKERS));
-        let window_ms = speaker.window_ms.unwrap_or(DEFAULT_WINDOW_MS);
-        let step_ms = speaker.hop_ms.unwrap_or(DEFAULT_STEP_MS).max(200);
-        let min_rms_db = speaker.min_rms_db.unwrap_or(DEFAULT_MIN_RMS_DB);
-
-        let switch_window_ms = window_ms.min(1_000).max(500);
-        let switch_hop_ms = (step_ms.min(switch_window_ms)).max(200);
-
-        let switch_params = SwitchParams {
-            threshold: new_threshold,
-            window_ms: switch_window_ms,
-            hop_ms: switch_hop_ms,
-            min_gap_ms: speaker.min_gap_ms.unwrap_or(DEFAULT_MIN_GAP_MS),
-            consecutive_hits: speaker
-                .consecutive_hits
-                .unwrap_or(DEFAULT_CONSECUTIVE_HITS)
-                .max(1),
-            min_rms_db,
-        };
-
-        let embedder = match SpeakerEmbedder::new(&model_path) {
-            Ok(embedder) => embedder,
-            Err(err) => {
-                eprintln!("speaker embedder init failed: {err}");
-                return None;
-            }
-        };
-
+        let (model_path, config) = load_settings(app)?;
+        let embedder = SpeakerEmbedder::load(&model_path)?;
         Some(Self {
             embedder,
             clusterer: SpeakerClusterer::new(),
-            config: DiarizerConfig {
-                new_threshold,
-                update_threshold,
-                max_speakers,
-                window_ms,
-                step_ms,
-                min_rms_db,
-                update_alpha: DEFAULT_UPDATE_ALPHA,
-                switch_params,
-            },
+            config,
             last_processed: None,
         })
     }
@@ -172,7 +92,7 @@ impl SpeakerDiarizer {
         let embedding = match self.embedder.embedding_from_window(&embed_window) {
             Ok(embedding) => embedding,
             Err(err) => {
-                eprintln!("speaker embedding failed: {err}");
+                warn!("speaker embedding failed: {err}");
                 return None;
             }
         };
@@ -182,6 +102,281 @@ impl SpeakerDiarizer {
     }
 }
 
+// Model path and thresholds from the `speaker` config; `None` when diarization is off.
+fn load_settings(app: &AppHandle) -> Option<(PathBuf, DiarizerConfig)> {
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(err) => {
+            warn!("speaker config unavailable: {err}");
+            return None;
+        }
+    };
+
+    let speaker = match config.speaker {
+        Some(config) => config,
+        None => return None,
+    };
+
+    if speaker.enabled == Some(false) {
+        return None;
+    }
+
+    let resource_dir = app.path().resource_dir().ok();
+    let model_path = resolve_model_path(
+        speaker
+            .model_path
+            .as_deref()
+            .or(Some(DEFAULT_SPEAKER_MODEL_PATH)),
+        resource_dir,
+    );
+    let model_path = match model_path {
+        Some(path) => path,
+        None => {
+            warn!("speaker model path not set");
+            return None;
+        }
+    };
+    if !model_path.exists() {
+        warn!("speaker model not found: {}", model_path.display());
+        return None;
+    }
+
+    let new_threshold = speaker
+        .similarity_threshold
+        .unwrap_or(DEFAULT_SPEAKER_NEW_THRESHOLD);
+    let update_threshold = speaker
+        .update_threshold
+        .unwrap_or(DEFAULT_SPEAKER_UPDATE_THRESHOLD)
+        .max(new_threshold);
+    let max_speakers = speaker.max_speakers.or(Some(DEFAULT_SPEAKER_MAX_SPEAKERS));
+    let window_ms = speaker.window_ms.unwrap_or(DEFAULT_SPEAKER_WINDOW_MS);
+    let step_ms = speaker.hop_ms.unwrap_or(DEFAULT_SPEAKER_STEP_MS).max(200);
+    let min_rms_db = speaker.min_rms_db.unwrap_or(DEFAULT_SPEAKER_MIN_RMS_DB);
+
+    let switch_window_ms = window_ms.min(1_000).max(500);
+    let switch_hop_ms = (step_ms.min(switch_window_ms)).max(200);
+
+    let switch_params = SwitchParams {
+        threshold: new_threshold,
+        window_ms: switch_window_ms,
+        hop_ms: switch_hop_ms,
+        min_gap_ms: speaker.min_gap_ms.unwrap_or(DEFAULT_SPEAKER_MIN_GAP_MS),
+        consecutive_hits: speaker
+            .consecutive_hits
+            .unwrap_or(DEFAULT_SPEAKER_CONSECUTIVE_HITS)
+            .max(1),
+        min_rms_db,
+    };
+
+    Some((
+        model_path,
+        DiarizerConfig {
+            new_threshold,
+            update_threshold,
+            max_speakers,
+            window_ms,
+            step_ms,
+            min_rms_db,
+            update_alpha: DEFAULT_UPDATE_ALPHA,
+            switch_params,
+        },
+    ))
+}
+
+// Speaker changes within a whole segment, with the live diarizer's model and switch settings.
+pub struct SwitchDetector {
+    embedder: SpeakerEmbedder,
+    params: SwitchParams,
+}
+
+impl SwitchDetector {
+    pub fn new(app: &AppHandle) -> Option<Self> {
+        let (model_path, config) = load_settings(app)?;
+        Some(Self {
+            embedder: SpeakerEmbedder::load(&model_path)?,
+            params: config.switch_params,
+        })
+    }
+
+    // Offsets in milliseconds from the start of the audio.
+    pub fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<Vec<u64>, String> {
+        let mono = mix_to_mono(samples, channels);
+        let resampled = resample_to_16k(&mono, sample_rate);
+        self.embedder.detect_switches(&resampled, &self.params)
+    }
+}
+
+// Post-meeting diarization: one embedding per segment, clustered over the whole meeting at once
+// instead of window by window as the meeting goes.
+pub struct SessionDiarizer {
+    embedder: SpeakerEmbedder,
+    threshold: f32,
+    max_speakers: Option<u32>,
+    min_rms_db: f32,
+}
+
+impl SessionDiarizer {
+    pub fn new(app: &AppHandle) -> Option<Self> {
+        let (model_path, config) = load_settings(app)?;
+        Some(Self {
+            embedder: SpeakerEmbedder::load(&model_path)?,
+            threshold: config.new_threshold,
+            max_speakers: config.max_speakers,
+            min_rms_db: config.min_rms_db,
+        })
+    }
+
+    // Mean embedding of the voiced one-second windows of a segment, half overlapping; `None` when
+    // no window is loud enough.
+    pub fn embed(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<Option<Vec<f32>>, String> {
+        let resampled = resample_to_16k(&mix_to_mono(samples, channels), sample_rate);
+        let hop = TARGET_WINDOW_SAMPLES / 2;
+        let mut sum: Option<Vec<f32>> = None;
+        let mut start = 0usize;
+        loop {
+            let window = &resampled[start..(start + TARGET_WINDOW_SAMPLES).min(resampled.len())];
+            if !window.is_empty() && rms_db(window) >= self.min_rms_db {
+                let embedding = self.embedder.embedding_from_samples(window)?;
+                match sum.as_mut() {
+                    Some(sum) => sum
+                        .iter_mut()
+                        .zip(&embedding)
+                        .for_each(|(total, value)| *total += value),
+                    None => sum = Some(embedding),
+                }
+            }
+            if start + TARGET_WINDOW_SAMPLES >= resampled.len() {
+                break;
+            }
+            start += hop;
+        }
+        Ok(sum.map(|mut embedding| {
+            normalize_embedding(&mut embedding);
+            embedding
+        }))
+    }
+
+    // Speaker ids from 1 in order of first appearance, with each segment's similarity to its
+    // speaker; `None` for segments without an embedding.
+    pub fn assign(&self, embeddings: &[Option<Vec<f32>>]) -> Vec<Option<(u32, f32)>> {
+        let voiced = embeddings.iter().flatten().cloned().collect::<Vec<_>>();
+        let clusters = cluster_embeddings(&voiced, self.threshold, self.max_speakers);
+        let centroids = centroids(&voiced, &clusters);
+        let mut labels = clusters.into_iter();
+        embeddings
+            .iter()
+            .map(|embedding| {
+                let embedding = embedding.as_ref()?;
+                let cluster = labels.next()?;
+                Some((
+                    cluster as u32 + 1,
+                    cosine_similarity(&centroids[cluster], embedding),
+                ))
+            })
+            .collect()
+    }
+}
+
+// Agglomerative clustering of normalized embeddings: the two most similar clusters are merged
+// while their centroids are at least `threshold` alike, then further until at most
+// `max_clusters` remain. Returns cluster indexes numbered in order of first appearance.
+pub fn cluster_embeddings(
+    embeddings: &[Vec<f32>],
+    threshold: f32,
+    max_clusters: Option<u32>,
+) -> Vec<usize> {
+    let count = embeddings.len();
+    let max_clusters = max_clusters.map_or(usize::MAX, |max| (max as usize).max(1));
+    let mut sums = embeddings.to_vec();
+    let mut alive = vec![true; count];
+    let mut owner = (0..count).collect::<Vec<_>>();
+    // Upper triangle only: `similarity[low][high]`.
+    let mut similarity = vec![vec![f32::NEG_INFINITY; count]; count];
+    for left in 0..count {
+        for right in left + 1..count {
+            similarity[left][right] = cosine_similarity(&embeddings[left], &embeddings[right]);
+        }
+    }
+
+    let mut clusters = count;
+    while clusters > 1 {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for left in (0..count).filter(|&index| alive[index]) {
+            for right in (left + 1..count).filter(|&index| alive[index]) {
+                if best.is_none_or(|(_, _, value)| similarity[left][right] > value) {
+                    best = Some((left, right, similarity[left][right]));
+                }
+            }
+        }
+        let Some((left, right, value)) = best else {
+            break;
+        };
+        if value < threshold && clusters <= max_clusters {
+            break;
+        }
+        let merged = std::mem::take(&mut sums[right]);
+        sums[left]
+            .iter_mut()
+            .zip(&merged)
+            .for_each(|(total, value)| *total += value);
+        alive[right] = false;
+        owner
+            .iter_mut()
+            .filter(|cluster| **cluster == right)
+            .for_each(|cluster| *cluster = left);
+        clusters -= 1;
+
+        let mut centroid = sums[left].clone();
+        normalize_embedding(&mut centroid);
+        for other in (0..count).filter(|&index| alive[index] && index != left) {
+            let mut other_centroid = sums[other].clone();
+            normalize_embedding(&mut other_centroid);
+            similarity[left.min(other)][left.max(other)] =
+                cosine_similarity(&centroid, &other_centroid);
+        }
+    }
+
+    let mut order = Vec::new();
+    owner
+        .into_iter()
+        .map(
+            |cluster| match order.iter().position(|seen| *seen == cluster) {
+                Some(position) => position,
+                None => {
+                    order.push(cluster);
+                    order.len() - 1
+                }
+            },
+        )
+        .collect()
+}
+
+fn centroids(embeddings: &[Vec<f32>], clusters: &[usize]) -> Vec<Vec<f32>> {
+    let count = clusters.iter().max().map_or(0, |max| max + 1);
+    let dim = embeddings.first().map_or(0, Vec::len);
+    let mut centroids = vec![vec![0.0f32; dim]; count];
+    for (embedding, cluster) in embeddings.iter().zip(clusters) {
+        centroids[*cluster]
+            .iter_mut()
+            .zip(embedding)
+            .for_each(|(total, value)| *total += value);
+    }
+    centroids
+        .iter_mut()
+        .for_each(|centroid| normalize_embedding(centroid));
+    centroids
+}
+
 struct DiarizerConfig {
     new_threshold: f32,
     update_threshold: f32,
@@ -289,13 +484,34 @@ struct SwitchParams {
 
 impl SpeakerEmbedder {
     fn new(model_path: &Path) -> Result<Self, String> {
+        let selection = ProviderSelection::for_model("speaker");
         let session = Session::builder()
-            .map_err(|err| err.to_string())?
-            .commit_from_file(model_path)
-            .map_err(|err| err.to_string())?;
+            .and_then(|builder| builder.with_execution_providers(&selection.providers))
+            .and_then(|builder| builder.commit_from_file(model_path));
+        let session = match session {
+            Ok(session) => {
+                selection.record(None);
+                session
+            }
+            Err(err) if selection.uses_gpu() => {
+                warn!("speaker model failed on gpu, retrying on cpu: {err}");
+                let session = Session::builder()
+                    .and_then(|builder| builder.commit_from_file(model_path))
+                    .map_err(|err| err.to_string())?;
+                selection.record(Some(err.to_string()));
+                session
+            }
+            Err(err) => return Err(err.to_string()),
+        };
         Ok(Self { session })
     }
 
+    fn load(model_path: &Path) -> Option<Self> {
+        Self::new(model_path)
+            .map_err(|err| warn!("speaker embedder init failed: {err}"))
+            .ok()
+    }
+
     fn embedding_from_window(&mut self, window: &[f32]) -> Result<Vec<f32>, String> {
         let input = Array3::<f32>::from_shape_vec((1, 1, TARGET_WINDOW_SAMPLES), window.to_vec())
             .map_err(|err| err.to_string())?;
@@ -473,7 +689,10 @@ fn ms_to_samples(ms: u64, sample_rate: u32) -> usize {
     (ms.saturating_mul(sample_rate as u64) / 1000) as usize
 }
 
-fn resolve_model_path(path: Option<&str>, resource_dir: Option<PathBuf>) -> Option<PathBuf> {
+pub(crate) fn resolve_model_path(
+    path: Option<&str>,
+    resource_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
     let raw = path?.trim();
     if raw.is_empty() {
         return None;
@@ -508,3 +727,26 @@ fn resolve_model_path(path: Option<&str>, resource_dir: Option<PathBuf>) -> Opti
         .find(|path| path.exists())
         .or(Some(candidate))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::cluster_embeddings;
+
+    #[test]
+    fn clusters_embeddings_over_the_whole_meeting() {
+        let a = vec![1.0, 0.0, 0.0];
+        let a2 = vec![0.96, 0.28, 0.0];
+        let b = vec![0.0, 1.0, 0.0];
+        let c = vec![0.0, 0.0, 1.0];
+        let embeddings = [a.clone(), b.clone(), a2, c, b, a];
+        assert_eq!(
+            cluster_embeddings(&embeddings, 0.8, None),
+            [0, 1, 0, 2, 1, 0]
+        );
+        // Capped at two speakers, the least distinct ones are merged as well.
+        let capped = cluster_embeddings(&embeddings, 0.8, Some(2));
+        assert_eq!(capped.iter().max(), Some(&1));
+        assert_eq!(capped[0], capped[2]);
+        assert!(cluster_embeddings(&[], 0.8, Some(2)).is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::audio::queue::QueueStats;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+// Written by the capture thread as it goes, read by `get_capture_stats` from anywhere.
+#[derive(Debug, Default)]
+pub struct CaptureCounters {
+    running: AtomicBool,
+    sample_rate: AtomicU32,
+    channels: AtomicU32,
+    frames: AtomicU64,
+    dropped_buffers: AtomicU64,
+    segment_frames: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureStats {
+    pub capturing: bool,
+    pub paused: bool,
+    // The format capture runs at, from the first device.
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub frames_captured: u64,
+    pub captured_ms: u64,
+    // Packets the device reported lost or that arrived after a gap.
+    pub dropped_buffers: u64,
+    // Length of the segment being written; 0 between segments.
+    pub current_segment_ms: u64,
+    pub queues: Vec<QueueStats>,
+}
+
+impl CaptureCounters {
+    pub fn begin(&self, sample_rate: u32, channels: u16) {
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+        self.channels.store(channels as u32, Ordering::Relaxed);
+        self.frames.store(0, Ordering::Relaxed);
+        self.dropped_buffers.store(0, Ordering::Relaxed);
+        self.segment_frames.store(0, Ordering::Relaxed);
+        self.running.store(true, Ordering::Relaxed);
+    }
+
+    pub fn end(&self) {
+        self.running.store(false, Ordering::Relaxed);
+        self.segment_frames.store(0, Ordering::Relaxed);
+    }
+
+    pub fn add_frames(&self, frames: u64) {
+        self.frames.fetch_add(frames, Ordering::Relaxed);
+    }
+
+    pub fn set_dropped_buffers(&self, dropped: u64) {
+        self.dropped_buffers.store(dropped, Ordering::Relaxed);
+    }
+
+    pub fn set_segment_frames(&self, frames: u64) {
+        self.segment_frames.store(frames, Ordering::Relaxed);
+    }
+
+    // The last capture's totals stay readable after it stopped.
+    pub fn snapshot(&self, paused: bool, queues: Vec<QueueStats>) -> CaptureStats {
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed);
+        let to_ms = |frames: u64| {
+            if sample_rate == 0 {
+                0
+            } else {
+                frames.saturating_mul(1000) / sample_rate as u64
+            }
+        };
+        let frames = self.frames.load(Ordering::Relaxed);
+        let capturing = self.running.load(Ordering::Relaxed);
+        CaptureStats {
+            capturing,
+            paused: capturing && paused,
+            sample_rate,
+            channels: self.channels.load(Ordering::Relaxed) as u16,
+            frames_captured: frames,
+            captured_ms: to_ms(frames),
+            dropped_buffers: self.dropped_buffers.load(Ordering::Relaxed),
+            current_segment_ms: to_ms(self.segment_frames.load(Ordering::Relaxed)),
+            queues,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaptureCounters;
+
+    #[test]
+    fn reports_the_running_capture_in_milliseconds() {
+        let counters = CaptureCounters::default();
+        assert!(!counters.snapshot(false, Vec::new()).capturing);
+
+        counters.begin(48_000, 2);
+        counters.add_frames(96_000);
+        counters.add_frames(24_000);
+        counters.set_segment_frames(12_000);
+        counters.set_dropped_buffers(3);
+        let stats = counters.snapshot(true, Vec::new());
+        assert!(stats.capturing && stats.paused);
+        assert_eq!((stats.sample_rate, stats.channels), (48_000, 2));
+        assert_eq!(stats.captured_ms, 2_500);
+        assert_eq!(stats.current_segment_ms, 250);
+        assert_eq!(stats.dropped_buffers, 3);
+
+        // Totals of a stopped capture are kept until the next one begins.
+        counters.end();
+        let stats = counters.snapshot(true, Vec::new());
+        assert!(!stats.capturing && !stats.paused);
+        assert_eq!(
+            (stats.frames_captured, stats.current_segment_ms),
+            (120_000, 0)
+        );
+        counters.begin(16_000, 1);
+        assert_eq!(counters.snapshot(false, Vec::new()).frames_captured, 0);
+    }
+}
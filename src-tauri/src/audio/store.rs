@@ -0,0 +1,283 @@
+use crate::audio::index;
+use crate::audio::manager::SegmentInfo;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub const DEFAULT_MAX_IN_MEMORY_SEGMENTS: usize = 500;
+pub const DEFAULT_SEGMENT_PAGE_SIZE: usize = 100;
+
+struct StoreState {
+    items: Vec<Arc<SegmentInfo>>,
+    // Segments dropped from the front; they stay in the on-disk index and keep their order slot.
+    evicted: usize,
+    loaded: bool,
+}
+
+// Recent segments for the live pipeline. Entries are shared as `Arc`s so snapshots are cheap, and
+// only the newest `cap` are kept in memory; older ones are read back from the index on demand.
+pub struct SegmentStore {
+    state: Mutex<StoreState>,
+    cap: AtomicUsize,
+}
+
+impl SegmentStore {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(StoreState {
+                items: Vec::new(),
+                evicted: 0,
+                loaded: false,
+            }),
+            cap: AtomicUsize::new(DEFAULT_MAX_IN_MEMORY_SEGMENTS),
+        }
+    }
+
+    // 0 keeps every segment in memory.
+    pub fn set_cap(&self, cap: usize) {
+        self.cap.store(cap, Ordering::SeqCst);
+        let mut guard = self.lock();
+        self.evict(&mut guard);
+    }
+
+    pub fn load_if_needed(&self, dir: &Path) {
+        let mut guard = self.lock();
+        if guard.loaded || !guard.items.is_empty() {
+            return;
+        }
+        guard.items = index::load(dir).into_iter().map(Arc::new).collect();
+        guard.loaded = true;
+        self.evict(&mut guard);
+    }
+
+    // Re-reads the index after entries were dropped or inserted out of order.
+    pub fn reload(&self, dir: &Path) {
+        let mut guard = self.lock();
+        guard.items = index::load(dir).into_iter().map(Arc::new).collect();
+        guard.evicted = 0;
+        guard.loaded = true;
+        self.evict(&mut guard);
+    }
+
+    pub fn push(&self, info: SegmentInfo) -> Arc<SegmentInfo> {
+        let info = Arc::new(info);
+        let mut guard = self.lock();
+        guard.items.push(Arc::clone(&info));
+        self.evict(&mut guard);
+        info
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<SegmentInfo>> {
+        self.lock()
+            .items
+            .iter()
+            .find(|segment| segment.name == name)
+            .cloned()
+    }
+
+    // Falls back to the on-disk index for segments that were evicted from memory.
+    pub fn find(&self, dir: &Path, name: &str) -> Option<Arc<SegmentInfo>> {
+        if let Some(segment) = self.get(name) {
+            return Some(segment);
+        }
+        if !self.has_evicted() {
+            return None;
+        }
+        index::load(dir)
+            .into_iter()
+            .find(|segment| segment.name == name)
+            .map(Arc::new)
+    }
+
+    // Applies `change` in memory (or to the stored copy of an evicted segment) and appends the
+    // result to the index log.
+    pub fn update(
+        &self,
+        dir: &Path,
+        name: &str,
+        change: impl FnOnce(&mut SegmentInfo),
+    ) -> Option<Arc<SegmentInfo>> {
+        let mut guard = self.lock();
+        let evicted = guard.evicted > 0;
+        let updated = match guard.items.iter_mut().find(|segment| segment.name == name) {
+            Some(segment) => {
+                change(Arc::make_mut(segment));
+                Arc::clone(segment)
+            }
+            None if evicted => {
+                drop(guard);
+                let mut segment = index::load(dir)
+                    .into_iter()
+                    .find(|segment| segment.name == name)?;
+                change(&mut segment);
+                Arc::new(segment)
+            }
+            None => return None,
+        };
+        index::record(dir, &updated);
+        Some(updated)
+    }
+
+    // Position across the whole history, stable when older entries are evicted.
+    pub fn order(&self, name: &str) -> usize {
+        let guard = self.lock();
+        guard
+            .items
+            .iter()
+            .position(|segment| segment.name == name)
+            .map(|position| guard.evicted + position)
+            .unwrap_or(usize::MAX)
+    }
+
+    // Total number of segments, including the ones only kept on disk.
+    pub fn count(&self) -> usize {
+        let guard = self.lock();
+        guard.evicted + guard.items.len()
+    }
+
+    // The newest `limit` segments, oldest first; served from memory when it holds enough of them.
+    pub fn recent(&self, dir: &Path, limit: usize) -> Vec<Arc<SegmentInfo>> {
+        {
+            let guard = self.lock();
+            if guard.evicted == 0 || guard.items.len() >= limit {
+                let start = guard.items.len().saturating_sub(limit);
+                return guard.items[start..].to_vec();
+            }
+        }
+        let all = index::load(dir);
+        let start = all.len().saturating_sub(limit);
+        all.into_iter().skip(start).map(Arc::new).collect()
+    }
+
+    // Newest segment matching `predicate`, searching memory before the on-disk index.
+    pub fn latest(
+        &self,
+        dir: &Path,
+        predicate: impl Fn(&SegmentInfo) -> bool,
+    ) -> Option<Arc<SegmentInfo>> {
+        let (found, evicted) = {
+            let guard = self.lock();
+            let found = guard
+                .items
+                .iter()
+                .rev()
+                .find(|segment| predicate(segment))
+                .cloned();
+            (found, guard.evicted > 0)
+        };
+        if found.is_some() || !evicted {
+            return found;
+        }
+        index::load(dir)
+            .into_iter()
+            .rev()
+            .find(|segment| predicate(segment))
+            .map(Arc::new)
+    }
+
+    pub fn snapshot(&self) -> Vec<Arc<SegmentInfo>> {
+        self.lock().items.clone()
+    }
+
+    // Every segment, reading the on-disk index when part of the history was evicted.
+    pub fn list_all(&self, dir: &Path) -> Vec<SegmentInfo> {
+        if self.has_evicted() {
+            return index::load(dir);
+        }
+        self.lock()
+            .items
+            .iter()
+            .map(|segment| segment.as_ref().clone())
+            .collect()
+    }
+
+    // Up to `limit` segments older than `before` (or the newest when `before` is `None`), oldest
+    // first.
+    pub fn page(&self, dir: &Path, before: Option<&str>, limit: usize) -> Vec<SegmentInfo> {
+        let all = self.list_all(dir);
+        let end = before
+            .and_then(|name| all.iter().position(|segment| segment.name == name))
+            .unwrap_or(all.len());
+        let start = end.saturating_sub(limit);
+        all[start..end].to_vec()
+    }
+
+    pub fn clear(&self) {
+        let mut guard = self.lock();
+        guard.items.clear();
+        guard.evicted = 0;
+        guard.loaded = true;
+    }
+
+    fn has_evicted(&self) -> bool {
+        self.lock().evicted > 0
+    }
+
+    fn evict(&self, state: &mut StoreState) {
+        let cap = self.cap.load(Ordering::SeqCst);
+        if cap == 0 || state.items.len() <= cap {
+            return;
+        }
+        let excess = state.items.len() - cap;
+        state.items.drain(..excess);
+        state.evicted += excess;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StoreState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentStore;
+    use crate::audio::manager::SegmentInfo;
+
+    fn segment(name: &str) -> SegmentInfo {
+        SegmentInfo {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn evicts_oldest_and_reads_them_back_from_the_index() {
+        let dir = std::env::temp_dir().join(format!("ai_shepherd_store_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let store = SegmentStore::new();
+        store.set_cap(2);
+        for name in ["a.wav", "b.wav", "c.wav"] {
+            let info = store.push(segment(name));
+            crate::audio::index::record(&dir, &info);
+        }
+        assert_eq!(store.snapshot().len(), 2);
+        assert!(store.get("a.wav").is_none());
+        assert_eq!(store.order("c.wav"), 2);
+
+        let updated = store.update(&dir, "a.wav", |segment| {
+            segment.transcript = Some("late".to_string());
+        });
+        assert_eq!(updated.unwrap().transcript.as_deref(), Some("late"));
+        assert_eq!(
+            store.find(&dir, "a.wav").unwrap().transcript.as_deref(),
+            Some("late")
+        );
+        let names = store
+            .page(&dir, Some("c.wav"), 5)
+            .into_iter()
+            .map(|segment| segment.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a.wav", "b.wav"]);
+        assert_eq!(store.count(), 3);
+        let recent = store.recent(&dir, 3);
+        assert_eq!(recent.first().unwrap().name, "a.wav");
+        let latest = store.latest(&dir, |segment| segment.transcript.is_some());
+        assert_eq!(latest.unwrap().name, "a.wav");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
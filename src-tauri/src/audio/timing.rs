@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // From the capture thread closing the segment until its WAV file is flushed.
+    Finalize,
+    TranscribeWait,
+    Transcribe,
+    TranslateWait,
+    Translate,
+}
+
+impl Stage {
+    fn label(self) -> &'static str {
+        match self {
+            Stage::Finalize => "finalize",
+            Stage::TranscribeWait => "transcribe_wait",
+            Stage::Transcribe => "transcribe",
+            Stage::TranslateWait => "translate_wait",
+            Stage::Translate => "translate",
+        }
+    }
+}
+
+// Milliseconds one segment spent in each pipeline stage; a stage that has not run yet is unset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageTimings {
+    pub finalize_ms: Option<u64>,
+    pub transcribe_wait_ms: Option<u64>,
+    pub transcribe_ms: Option<u64>,
+    pub translate_wait_ms: Option<u64>,
+    pub translate_ms: Option<u64>,
+}
+
+impl StageTimings {
+    pub fn record(&mut self, segment: &str, stage: Stage, elapsed_ms: u64) {
+        debug!(
+            segment,
+            stage = stage.label(),
+            elapsed_ms,
+            "[timing] stage finished"
+        );
+        let slot = match stage {
+            Stage::Finalize => &mut self.finalize_ms,
+            Stage::TranscribeWait => &mut self.transcribe_wait_ms,
+            Stage::Transcribe => &mut self.transcribe_ms,
+            Stage::TranslateWait => &mut self.translate_wait_ms,
+            Stage::Translate => &mut self.translate_ms,
+        };
+        *slot = Some(elapsed_ms);
+    }
+}
+
+pub fn elapsed_ms(since: Instant) -> u64 {
+    since.elapsed().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Stage, StageTimings};
+
+    #[test]
+    fn records_each_stage_in_its_own_slot() {
+        let mut timings = StageTimings::default();
+        timings.record("a.wav", Stage::Finalize, 12);
+        timings.record("a.wav", Stage::Transcribe, 300);
+        timings.record("a.wav", Stage::Transcribe, 250);
+        assert_eq!(timings.transcribe_ms, Some(250));
+        assert_eq!(timings.translate_ms, None);
+        assert_eq!(timings.finalize_ms, Some(12));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::app_config::AsrConfig;
+use crate::audio::convert::FormatAdapter;
+use crate::audio::speaker::resolve_model_path;
+use crate::defaults::{DEFAULT_VAD_MODEL_PATH, DEFAULT_VAD_THRESHOLD};
+use ndarray::{arr0, Array2, ArrayD, IxDyn};
+use ort::session::Session;
+use ort::value::TensorRef;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+pub const VAD_SAMPLE_RATE: u32 = 16_000;
+// Silero v5 scores 512-sample chunks at 16 kHz, each preceded by the last 64 samples of the
+// previous one.
+const CHUNK_SAMPLES: usize = 512;
+const CONTEXT_SAMPLES: usize = 64;
+const CHUNK_MS: u64 = CHUNK_SAMPLES as u64 * 1000 / VAD_SAMPLE_RATE as u64;
+const STATE_SHAPE: [usize; 3] = [2, 1, 128];
+
+const MIN_SPEECH_MS: u64 = 250;
+const MIN_SILENCE_MS: u64 = 100;
+const SPEECH_PAD_MS: u64 = 30;
+// Speech ends only once the probability drops this far below the threshold.
+const HYSTERESIS: f32 = 0.15;
+
+// Speech within a segment, in milliseconds from the start of its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeechSpan {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+pub struct SileroVad {
+    session: Session,
+    threshold: f32,
+}
+
+impl SileroVad {
+    pub fn load(app: &AppHandle, config: &AsrConfig) -> Result<Self, String> {
+        let model_path = resolve_model_path(
+            config
+                .vad_model_path
+                .as_deref()
+                .or(Some(DEFAULT_VAD_MODEL_PATH)),
+            app.path().resource_dir().ok(),
+        )
+        .ok_or_else(|| "vad model not found".to_string())?;
+        Self::new(
+            &model_path,
+            config.vad_threshold.unwrap_or(DEFAULT_VAD_THRESHOLD),
+        )
+    }
+
+    // The model is small enough that the GPU would only add transfer overhead.
+    pub fn new(model_path: &Path, threshold: f32) -> Result<Self, String> {
+        let session = Session::builder()
+            .and_then(|builder| builder.commit_from_file(model_path))
+            .map_err(|err| err.to_string())?;
+        Ok(Self { session, threshold })
+    }
+
+    // Speech spans of interleaved audio in any format.
+    pub fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+    ) -> Result<Vec<SpeechSpan>, String> {
+        let mono = FormatAdapter::new(sample_rate, channels, VAD_SAMPLE_RATE, 1).convert(samples);
+        let probabilities = self.probabilities(&mono)?;
+        Ok(speech_spans(
+            &probabilities,
+            self.threshold,
+            mono.len() as u64 * 1000 / VAD_SAMPLE_RATE as u64,
+        ))
+    }
+
+    // Speech probability of every chunk of 16 kHz mono audio, the last one padded with silence.
+    // The recurrent state starts fresh for every call.
+    fn probabilities(&mut self, samples: &[f32]) -> Result<Vec<f32>, String> {
+        let mut state = ArrayD::<f32>::zeros(IxDyn(&STATE_SHAPE));
+        let sample_rate = arr0(VAD_SAMPLE_RATE as i64);
+        let mut context = vec![0.0f32; CONTEXT_SAMPLES];
+        let mut probabilities = Vec::with_capacity(samples.len().div_ceil(CHUNK_SAMPLES));
+        for chunk in samples.chunks(CHUNK_SAMPLES) {
+            let mut frame = Vec::with_capacity(CONTEXT_SAMPLES + CHUNK_SAMPLES);
+            frame.extend_from_slice(&context);
+            frame.extend_from_slice(chunk);
+            frame.resize(CONTEXT_SAMPLES + CHUNK_SAMPLES, 0.0);
+            context.copy_from_slice(&frame[CHUNK_SAMPLES..]);
+            let input = Array2::from_shape_vec((1, CONTEXT_SAMPLES + CHUNK_SAMPLES), frame)
+                .map_err(|err| err.to_string())?;
+
+            let outputs = self
+                .session
+                .run(ort::inputs![
+                    "input" => TensorRef::from_array_view(&input).map_err(|err| err.to_string())?,
+                    "state" => TensorRef::from_array_view(&state).map_err(|err| err.to_string())?,
+                    "sr" => TensorRef::from_array_view(&sample_rate).map_err(|err| err.to_string())?,
+                ])
+                .map_err(|err| err.to_string())?;
+            let probability = outputs["output"]
+                .try_extract_array::<f32>()
+                .map_err(|err| err.to_string())?
+                .iter()
+                .next()
+                .copied()
+                .unwrap_or(0.0);
+            let next_state = outputs["stateN"]
+                .try_extract_array::<f32>()
+                .map_err(|err| err.to_string())?
+                .to_owned();
+            drop(outputs);
+            state = next_state;
+            probabilities.push(probability);
+        }
+        Ok(probabilities)
+    }
+}
+
+// Turns per-chunk probabilities into speech spans the way Silero's reference
+// `get_speech_timestamps` does: speech starts at the threshold, ends after `MIN_SILENCE_MS` below
+// the lower one, spans shorter than `MIN_SPEECH_MS` are dropped and the rest padded.
+pub fn speech_spans(probabilities: &[f32], threshold: f32, duration_ms: u64) -> Vec<SpeechSpan> {
+    let negative = (threshold - HYSTERESIS).max(0.01);
+    let mut raw = Vec::new();
+    let mut start = None;
+    let mut silence_start = None;
+    for (index, probability) in probabilities.iter().copied().enumerate() {
+        let at = index as u64 * CHUNK_MS;
+        if probability >= threshold {
+            silence_start = None;
+            start.get_or_insert(at);
+            continue;
+        }
+        let Some(speech_start) = start else {
+            continue;
+        };
+        if probability >= negative {
+            continue;
+        }
+        let silence = *silence_start.get_or_insert(at);
+        if at + CHUNK_MS - silence >= MIN_SILENCE_MS {
+            raw.push((speech_start, silence));
+            start = None;
+            silence_start = None;
+        }
+    }
+    if let Some(speech_start) = start {
+        raw.push((speech_start, duration_ms));
+    }
+
+    let mut spans: Vec<SpeechSpan> = Vec::new();
+    for (start_ms, end_ms) in raw {
+        if end_ms.saturating_sub(start_ms) < MIN_SPEECH_MS {
+            continue;
+        }
+        let span = SpeechSpan {
+            start_ms: start_ms.saturating_sub(SPEECH_PAD_MS),
+            end_ms: (end_ms + SPEECH_PAD_MS).min(duration_ms),
+        };
+        match spans.last_mut() {
+            Some(last) if last.end_ms >= span.start_ms => last.end_ms = span.end_ms,
+            _ => spans.push(span),
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{speech_spans, SpeechSpan};
+
+    #[test]
+    fn finds_padded_speech_and_drops_blips() {
+        // 32 ms chunks: 10 of speech, a 2-chunk dip, 10 more, then a lone loud chunk.
+        let mut probabilities = vec![0.1; 3];
+        probabilities.extend([0.9; 10]);
+        probabilities.extend([0.2; 2]);
+        probabilities.extend([0.8; 10]);
+        probabilities.extend([0.05; 10]);
+        probabilities.push(0.95);
+        probabilities.extend([0.05; 5]);
+        let duration_ms = probabilities.len() as u64 * 32;
+
+        assert_eq!(
+            speech_spans(&probabilities, 0.5, duration_ms),
+            [SpeechSpan {
+                start_ms: 96 - 30,
+                end_ms: 800 + 30,
+            }]
+        );
+        assert!(speech_spans(&[0.4; 20], 0.5, 640).is_empty());
+        // Speech running to the end is closed at the end of the audio.
+        assert_eq!(
+            speech_spans(&[0.1, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.9], 0.5, 288),
+            [SpeechSpan {
+                start_ms: 2,
+                end_ms: 288,
+            }]
+        );
+    }
+}
@@ -1,16 +1,36 @@
 use std::ptr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
 
+use crate::audio::backend::{CaptureBackend, DeviceSwitch};
+use crate::audio::config::CaptureSource;
+use crate::audio::convert::FormatAdapter;
+use crate::audio::devices::{ActiveDevice, AudioDevice, Flow, Notification};
+use tracing::{info, warn};
+use windows::core::{implement, Interface, HSTRING, PCWSTR, PWSTR};
+use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+use windows::Win32::Foundation::{CloseHandle, HANDLE, PROPERTYKEY};
 use windows::Win32::Media::Audio::{
-    eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator, MMDeviceEnumerator,
-    AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_LOOPBACK,
-    WAVEFORMATEX, WAVEFORMATEXTENSIBLE, WAVE_FORMAT_PCM,
+    eCapture, eConsole, eRender, AudioSessionStateActive, EDataFlow, ERole, IAudioCaptureClient,
+    IAudioClient, IAudioSessionControl2, IAudioSessionManager2, IMMDevice, IMMDeviceEnumerator,
+    IMMEndpoint, IMMNotificationClient, IMMNotificationClient_Impl, MMDeviceEnumerator,
+    AUDCLNT_BUFFERFLAGS_DATA_DISCONTINUITY, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED,
+    AUDCLNT_STREAMFLAGS_EVENTCALLBACK, AUDCLNT_STREAMFLAGS_LOOPBACK, DEVICE_STATE,
+    DEVICE_STATE_ACTIVE, WAVEFORMATEX, WAVEFORMATEXTENSIBLE, WAVE_FORMAT_PCM,
 };
 use windows::Win32::Media::KernelStreaming::{KSDATAFORMAT_SUBTYPE_PCM, WAVE_FORMAT_EXTENSIBLE};
 use windows::Win32::Media::Multimedia::{KSDATAFORMAT_SUBTYPE_IEEE_FLOAT, WAVE_FORMAT_IEEE_FLOAT};
 use windows::Win32::System::Com::{
     CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
-    COINIT_MULTITHREADED,
+    COINIT_MULTITHREADED, STGM_READ,
 };
+use windows::Win32::System::Threading::{
+    CreateEventW, OpenProcess, QueryFullProcessImageNameW, WaitForSingleObject, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+// A device that fails again this soon after being reopened is treated as gone for good.
+const REOPEN_RETRY_AFTER: Duration = Duration::from_secs(2);
 
 struct ComGuard;
 
@@ -30,25 +50,88 @@ impl Drop for ComGuard {
     }
 }
 
-pub struct LoopbackCapture {
-    _com: ComGuard,
+fn data_flow(source: CaptureSource) -> EDataFlow {
+    match source {
+        CaptureSource::Loopback | CaptureSource::Dual => eRender,
+        CaptureSource::Microphone => eCapture,
+    }
+}
+
+fn create_enumerator() -> Result<IMMDeviceEnumerator, String> {
+    unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+        .map_err(|err| err.to_string())
+}
+
+fn endpoint_id(device: &IMMDevice) -> Result<String, String> {
+    unsafe {
+        let raw = device.GetId().map_err(|err| err.to_string())?;
+        let id = raw.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(raw.0 as _));
+        Ok(id)
+    }
+}
+
+fn is_usable(device: &IMMDevice, flow: EDataFlow) -> bool {
+    let active = unsafe { device.GetState() }.is_ok_and(|state| state == DEVICE_STATE_ACTIVE);
+    active
+        && device
+            .cast::<IMMEndpoint>()
+            .and_then(|endpoint| unsafe { endpoint.GetDataFlow() })
+            .is_ok_and(|value| value == flow)
+}
+
+// A selected endpoint that was unplugged or disabled falls back to the default one, so a stale
+// selection never keeps capture from starting.
+fn open_endpoint(
+    enumerator: &IMMDeviceEnumerator,
+    flow: EDataFlow,
+    device_id: Option<&str>,
+) -> Result<IMMDevice, String> {
+    if let Some(id) = device_id {
+        match unsafe { enumerator.GetDevice(&HSTRING::from(id)) } {
+            Ok(device) if is_usable(&device, flow) => return Ok(device),
+            _ => warn!("[wasapi] device {id} unavailable, using the default"),
+        }
+    }
+    unsafe { enumerator.GetDefaultAudioEndpoint(flow, eConsole) }.map_err(|err| err.to_string())
+}
+
+// The auto-reset event WASAPI signals each time a buffer of audio is ready.
+struct BufferEvent(HANDLE);
+
+impl BufferEvent {
+    fn new() -> Result<Self, String> {
+        unsafe { CreateEventW(None, false, false, None) }
+            .map(Self)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Drop for BufferEvent {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+struct Stream {
+    device_id: String,
     audio_client: IAudioClient,
     capture_client: IAudioCaptureClient,
     channels: u16,
     sample_rate: u32,
     bits_per_sample: u16,
     is_float: bool,
+    // Packets WASAPI flagged as following a glitch, where audio was lost.
+    discontinuities: u64,
+    // Declared after the clients, so it is closed once they are released.
+    ready: BufferEvent,
 }
 
-impl LoopbackCapture {
-    pub fn new() -> Result<Self, String> {
-        let com = ComGuard::new()?;
-
-        let enumerator: IMMDeviceEnumerator =
-            unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
-                .map_err(|err| err.to_string())?;
-        let device = unsafe { enumerator.GetDefaultAudioEndpoint(eRender, eConsole) }
-            .map_err(|err| err.to_string())?;
+impl Stream {
+    fn open(device: &IMMDevice, source: CaptureSource) -> Result<Self, String> {
+        let device_id = endpoint_id(device)?;
         let audio_client: IAudioClient =
             unsafe { device.Activate(CLSCTX_ALL, None) }.map_err(|err| err.to_string())?;
 
@@ -87,11 +170,18 @@ impl LoopbackCapture {
         let sample_rate = mix.nSamplesPerSec;
         let channels = mix.nChannels;
 
+        // Event-driven, so the capture thread sleeps until a buffer is ready. Loopback streams are
+        // only signaled while something plays, see `WasapiCapture::wait`.
+        let stream_flags = AUDCLNT_STREAMFLAGS_EVENTCALLBACK
+            | match source {
+                CaptureSource::Loopback | CaptureSource::Dual => AUDCLNT_STREAMFLAGS_LOOPBACK,
+                CaptureSource::Microphone => 0,
+            };
         unsafe {
             audio_client
                 .Initialize(
                     AUDCLNT_SHAREMODE_SHARED,
-                    AUDCLNT_STREAMFLAGS_LOOPBACK,
+                    stream_flags,
                     10_000_000,
                     0,
                     mix_ptr as *const WAVEFORMATEX,
@@ -104,6 +194,9 @@ impl LoopbackCapture {
             CoTaskMemFree(Some(mix_ptr as _));
         }
 
+        let ready = BufferEvent::new()?;
+        unsafe { audio_client.SetEventHandle(ready.0) }.map_err(|err| err.to_string())?;
+
         let capture_client: IAudioCaptureClient =
             unsafe { audio_client.GetService() }.map_err(|err| err.to_string())?;
 
@@ -116,25 +209,26 @@ impl LoopbackCapture {
         }
 
         Ok(Self {
-            _com: com,
+            device_id,
             audio_client,
             capture_client,
             channels,
             sample_rate,
             bits_per_sample,
             is_float,
+            discontinuities: 0,
+            ready,
         })
     }
 
-    pub fn channels(&self) -> u16 {
-        self.channels
-    }
-
-    pub fn sample_rate(&self) -> u32 {
-        self.sample_rate
+    fn wait(&self, timeout: Duration) {
+        let millis = timeout.as_millis().min(u32::MAX as u128) as u32;
+        unsafe {
+            let _ = WaitForSingleObject(self.ready.0, millis);
+        }
     }
 
-    pub fn read(&mut self) -> Result<Vec<f32>, String> {
+    fn read(&mut self) -> Result<Vec<f32>, String> {
         let mut packet_size =
             unsafe { self.capture_client.GetNextPacketSize() }.map_err(|err| err.to_string())?;
         if packet_size == 0 {
@@ -165,6 +259,9 @@ impl LoopbackCapture {
                 continue;
             }
 
+            if (flags & AUDCLNT_BUFFERFLAGS_DATA_DISCONTINUITY.0 as u32) != 0 {
+                self.discontinuities += 1;
+            }
             let is_silent = (flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32) != 0;
             if is_silent || data_ptr.is_null() {
                 out.extend(std::iter::repeat(0.0).take(samples));
@@ -196,10 +293,357 @@ impl LoopbackCapture {
     }
 }
 
-impl Drop for LoopbackCapture {
+pub struct WasapiCapture {
+    stream: Stream,
+    source: CaptureSource,
+    // Opened as the system default, so it moves along when the default changes.
+    follows_default: bool,
+    // The format of the first device, kept for the whole capture.
+    sample_rate: u32,
+    channels: u16,
+    // Set while the current device's format differs from the first one.
+    adapter: Option<FormatAdapter>,
+    enumerator: IMMDeviceEnumerator,
+    notifications: Receiver<Notification>,
+    switched: Option<DeviceSwitch>,
+    last_reopen: Option<Instant>,
+    // Discontinuities of the streams replaced by reopening.
+    dropped_before: u64,
+    _subscription: Subscription,
+    // Declared last so COM stays initialized while the interfaces above are released.
+    _com: ComGuard,
+}
+
+impl WasapiCapture {
+    // Loopback reads an output (render) endpoint, a microphone a capture endpoint. `device_id`
+    // `None` opens the system default.
+    pub fn open(source: CaptureSource, device_id: Option<&str>) -> Result<Self, String> {
+        let com = ComGuard::new()?;
+        let enumerator = create_enumerator()?;
+        let device = open_endpoint(&enumerator, data_flow(source), device_id)?;
+        let stream = Stream::open(&device, source)?;
+        let (subscription, notifications) = subscribe(&enumerator)?;
+        Ok(Self {
+            follows_default: device_id != Some(stream.device_id.as_str()),
+            sample_rate: stream.sample_rate,
+            channels: stream.channels,
+            stream,
+            source,
+            adapter: None,
+            enumerator,
+            notifications,
+            switched: None,
+            last_reopen: None,
+            dropped_before: 0,
+            _subscription: subscription,
+            _com: com,
+        })
+    }
+
+    fn reopen_default(&mut self, reason: &'static str) -> Result<(), String> {
+        self.last_reopen = Some(Instant::now());
+        let device = open_endpoint(&self.enumerator, data_flow(self.source), None)?;
+        let stream = Stream::open(&device, self.source)?;
+        let previous = std::mem::replace(&mut self.stream, stream);
+        self.dropped_before += previous.discontinuities;
+        let adapter = FormatAdapter::new(
+            self.stream.sample_rate,
+            self.stream.channels,
+            self.sample_rate,
+            self.channels,
+        );
+        self.adapter = (!adapter.is_identity()).then_some(adapter);
+        info!(
+            "[wasapi] {reason}: {} -> {} ({} Hz, {} ch)",
+            previous.device_id,
+            self.stream.device_id,
+            self.stream.sample_rate,
+            self.stream.channels
+        );
+        self.switched = Some(DeviceSwitch {
+            source: self.source,
+            previous_device_id: previous.device_id.clone(),
+            device_id: self.stream.device_id.clone(),
+            name: friendly_name(&device),
+            reason,
+            sample_rate: self.stream.sample_rate,
+            channels: self.stream.channels,
+        });
+        Ok(())
+    }
+}
+
+impl CaptureBackend for WasapiCapture {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    // Reopens the new default device when it changed, or reports the switch `read` made after
+    // losing the device.
+    fn follow_default(&mut self) -> Result<Vec<DeviceSwitch>, String> {
+        let flow = Flow::of(self.source);
+        let mut changed = false;
+        for notification in self.notifications.try_iter() {
+            if let Notification::DefaultChanged {
+                flow: changed_flow,
+                device_id,
+            } = notification
+            {
+                changed |= changed_flow == flow
+                    && !device_id.is_empty()
+                    && device_id != self.stream.device_id;
+            }
+        }
+        if changed && self.follows_default {
+            self.reopen_default("default_changed")?;
+        }
+        Ok(self.switched.take().into_iter().collect())
+    }
+
+    // Silence on a loopback endpoint signals nothing, so the wait ends at `timeout` then.
+    fn wait(&mut self, timeout: Duration) {
+        self.stream.wait(timeout);
+    }
+
+    fn read(&mut self) -> Result<Vec<f32>, String> {
+        let pcm = match self.stream.read() {
+            Ok(pcm) => pcm,
+            // Gives up when the device that was just reopened fails as well.
+            Err(err)
+                if self.follows_default
+                    && self
+                        .last_reopen
+                        .is_none_or(|at| at.elapsed() >= REOPEN_RETRY_AFTER) =>
+            {
+                warn!("[wasapi] read failed, reopening the default device: {err}");
+                self.reopen_default("device_lost")?;
+                return Ok(Vec::new());
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(match self.adapter.as_mut() {
+            Some(adapter) => adapter.convert(&pcm),
+            None => pcm,
+        })
+    }
+
+    fn active_devices(&self) -> Vec<ActiveDevice> {
+        vec![ActiveDevice {
+            id: self.stream.device_id.clone(),
+            source: self.source,
+            follows_default: self.follows_default,
+        }]
+    }
+
+    fn dropped_buffers(&self) -> u64 {
+        self.dropped_before + self.stream.discontinuities
+    }
+}
+
+impl Drop for Stream {
     fn drop(&mut self) {
         unsafe {
             let _ = self.audio_client.Stop();
         }
     }
 }
+
+#[implement(IMMNotificationClient)]
+struct Notifier {
+    sender: Sender<Notification>,
+}
+
+// Callbacks run on a COM worker thread and must return quickly, so they only forward.
+impl IMMNotificationClient_Impl for Notifier_Impl {
+    fn OnDeviceStateChanged(
+        &self,
+        pwstrdeviceid: &PCWSTR,
+        dwnewstate: DEVICE_STATE,
+    ) -> windows::core::Result<()> {
+        if dwnewstate != DEVICE_STATE_ACTIVE {
+            let device_id = device_id(pwstrdeviceid);
+            let _ = self.sender.send(Notification::Unavailable { device_id });
+        }
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, _pwstrdeviceid: &PCWSTR) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, pwstrdeviceid: &PCWSTR) -> windows::core::Result<()> {
+        let device_id = device_id(pwstrdeviceid);
+        let _ = self.sender.send(Notification::Unavailable { device_id });
+        Ok(())
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        flow: EDataFlow,
+        role: ERole,
+        pwstrdefaultdeviceid: &PCWSTR,
+    ) -> windows::core::Result<()> {
+        let flow = match flow {
+            flow if flow == eRender => Flow::Render,
+            flow if flow == eCapture => Flow::Capture,
+            _ => return Ok(()),
+        };
+        // Fired once per role; capture follows the console default.
+        if role == eConsole {
+            let device_id = device_id(pwstrdefaultdeviceid);
+            let _ = self
+                .sender
+                .send(Notification::DefaultChanged { flow, device_id });
+        }
+        Ok(())
+    }
+
+    fn OnPropertyValueChanged(
+        &self,
+        _pwstrdeviceid: &PCWSTR,
+        _key: &PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+fn device_id(raw: &PCWSTR) -> String {
+    if raw.is_null() {
+        return String::new();
+    }
+    unsafe { raw.to_string() }.unwrap_or_default()
+}
+
+// Keeps an endpoint notification callback registered until dropped.
+struct Subscription {
+    enumerator: IMMDeviceEnumerator,
+    client: IMMNotificationClient,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let _ = unsafe {
+            self.enumerator
+                .UnregisterEndpointNotificationCallback(&self.client)
+        };
+    }
+}
+
+fn subscribe(
+    enumerator: &IMMDeviceEnumerator,
+) -> Result<(Subscription, Receiver<Notification>), String> {
+    let (sender, receiver) = mpsc::channel();
+    let client: IMMNotificationClient = Notifier { sender }.into();
+    unsafe { enumerator.RegisterEndpointNotificationCallback(&client) }
+        .map_err(|err| err.to_string())?;
+    let subscription = Subscription {
+        enumerator: enumerator.clone(),
+        client,
+    };
+    Ok((subscription, receiver))
+}
+
+// Forwards endpoint notifications until the callback is unregistered, which never happens.
+pub(crate) fn watch(mut on_change: impl FnMut(Notification)) -> Result<(), String> {
+    let _com = ComGuard::new()?;
+    let enumerator = create_enumerator()?;
+    let (_subscription, receiver) = subscribe(&enumerator)?;
+    for notification in receiver {
+        on_change(notification);
+    }
+    Ok(())
+}
+
+fn friendly_name(device: &IMMDevice) -> String {
+    unsafe { device.OpenPropertyStore(STGM_READ) }
+        .and_then(|store| unsafe { store.GetValue(&PKEY_Device_FriendlyName) })
+        .map(|value| value.to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn endpoints(
+    source: CaptureSource,
+    selected: Option<&str>,
+) -> Result<Vec<AudioDevice>, String> {
+    let _com = ComGuard::new()?;
+    let enumerator = create_enumerator()?;
+    let flow = data_flow(source);
+    let default_id = unsafe { enumerator.GetDefaultAudioEndpoint(flow, eConsole) }
+        .ok()
+        .and_then(|device| endpoint_id(&device).ok());
+    let collection = unsafe { enumerator.EnumAudioEndpoints(flow, DEVICE_STATE_ACTIVE) }
+        .map_err(|err| err.to_string())?;
+    let count = unsafe { collection.GetCount() }.map_err(|err| err.to_string())?;
+    let mut devices = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let device = unsafe { collection.Item(index) }.map_err(|err| err.to_string())?;
+        let id = endpoint_id(&device)?;
+        let is_default = default_id.as_deref() == Some(id.as_str());
+        devices.push(AudioDevice {
+            name: friendly_name(&device),
+            selected: selected.map_or(is_default, |selected| selected == id),
+            is_default,
+            id,
+        });
+    }
+    Ok(devices)
+}
+
+// Executable names of processes with an active audio session on the default output or
+// microphone; a call app keeps one open while the call is on.
+pub(crate) fn session_processes() -> Result<Vec<String>, String> {
+    let _com = ComGuard::new()?;
+    let enumerator = create_enumerator()?;
+    let mut names = Vec::new();
+    for flow in [eRender, eCapture] {
+        let Ok(device) = (unsafe { enumerator.GetDefaultAudioEndpoint(flow, eConsole) }) else {
+            continue;
+        };
+        let manager: IAudioSessionManager2 =
+            unsafe { device.Activate(CLSCTX_ALL, None) }.map_err(|err| err.to_string())?;
+        let sessions = unsafe { manager.GetSessionEnumerator() }.map_err(|err| err.to_string())?;
+        let count = unsafe { sessions.GetCount() }.map_err(|err| err.to_string())?;
+        for index in 0..count {
+            let Ok(session) = (unsafe { sessions.GetSession(index) }) else {
+                continue;
+            };
+            let active =
+                unsafe { session.GetState() }.is_ok_and(|state| state == AudioSessionStateActive);
+            let pid = session
+                .cast::<IAudioSessionControl2>()
+                .and_then(|session| unsafe { session.GetProcessId() });
+            if let (true, Ok(pid)) = (active, pid) {
+                names.extend(process_name(pid));
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    // The system sounds session belongs to the idle process.
+    if pid == 0 {
+        return None;
+    }
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(handle);
+        queried.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        path.rsplit(['\\', '/']).next().map(str::to_string)
+    }
+}
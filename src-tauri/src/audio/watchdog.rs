@@ -0,0 +1,270 @@
+use crate::cancel::CancelScope;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// A queued item a worker can hand back to its queue when it is restarted.
+pub trait InFlight: Clone + Send + 'static {
+    fn label(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StallReason {
+    // The worker thread ended, which only happens when it panics.
+    Panicked,
+    // One item took longer than the stuck threshold, e.g. a provider that ignores its timeout.
+    Stuck,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerRestart {
+    pub worker: &'static str,
+    pub reason: StallReason,
+    pub busy_ms: Option<u64>,
+    // Labels of the in-flight items put back on the queue.
+    pub requeued: Vec<String>,
+}
+
+struct BeatState<T> {
+    // Bumped on every restart; a worker holding an older epoch has been replaced.
+    epoch: u64,
+    in_flight: Vec<T>,
+    busy_since: Option<Instant>,
+}
+
+struct Stall<T> {
+    reason: StallReason,
+    epoch: u64,
+    busy_ms: Option<u64>,
+    items: Vec<T>,
+}
+
+// Shared between a worker and the supervisor. Workers only report while handling an item, so an
+// idle worker blocked on its queue never looks stuck.
+pub struct Heartbeat<T> {
+    state: Mutex<BeatState<T>>,
+}
+
+impl<T: InFlight> Heartbeat<T> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(BeatState {
+                epoch: 0,
+                in_flight: Vec::new(),
+                busy_since: None,
+            }),
+        }
+    }
+
+    fn begin(&self, epoch: u64, items: &[T]) -> bool {
+        let mut state = self.lock();
+        if state.epoch != epoch {
+            return false;
+        }
+        state.in_flight = items.to_vec();
+        state.busy_since = Some(Instant::now());
+        true
+    }
+
+    fn is_current(&self, epoch: u64) -> bool {
+        self.lock().epoch == epoch
+    }
+
+    fn finish(&self, epoch: u64) {
+        let mut state = self.lock();
+        if state.epoch == epoch {
+            state.in_flight.clear();
+            state.busy_since = None;
+        }
+    }
+
+    fn check(&self, finished: bool, stuck_after: Duration, now: Instant) -> Option<Stall<T>> {
+        let mut state = self.lock();
+        let busy = state
+            .busy_since
+            .map(|since| now.saturating_duration_since(since));
+        let reason = if finished {
+            StallReason::Panicked
+        } else if busy.is_some_and(|busy| busy >= stuck_after) {
+            StallReason::Stuck
+        } else {
+            return None;
+        };
+        state.epoch += 1;
+        state.busy_since = None;
+        Some(Stall {
+            reason,
+            epoch: state.epoch,
+            busy_ms: busy.map(|busy| busy.as_millis() as u64),
+            items: std::mem::take(&mut state.in_flight),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BeatState<T>> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+// A worker's side of its heartbeat, tied to the epoch it was started in.
+pub struct Pulse<T> {
+    heartbeat: Arc<Heartbeat<T>>,
+    epoch: u64,
+}
+
+impl<T: InFlight> Pulse<T> {
+    // Returns `false` when the worker was replaced and has to hand `items` back and exit.
+    pub fn begin(&self, items: &[T]) -> bool {
+        self.heartbeat.begin(self.epoch, items)
+    }
+
+    // A replaced worker drops its result: the item went back to the queue for the new one.
+    pub fn is_current(&self) -> bool {
+        self.heartbeat.is_current(self.epoch)
+    }
+
+    pub fn finish(&self) {
+        self.heartbeat.finish(self.epoch);
+    }
+}
+
+type SpawnWorker<T> = Box<dyn Fn(Pulse<T>) -> JoinHandle<()> + Send>;
+
+pub struct WorkerSlot<T> {
+    name: &'static str,
+    heartbeat: Arc<Heartbeat<T>>,
+    handle: JoinHandle<()>,
+    spawn: SpawnWorker<T>,
+    requeue: Box<dyn Fn(T) + Send>,
+    // Aborts the stuck item's request, so a replaced worker that is merely slow gives up too.
+    // Only for a scope nothing but this worker uses.
+    cancel: Option<Arc<CancelScope>>,
+}
+
+impl<T: InFlight> WorkerSlot<T> {
+    // `spawn` starts the worker thread; it is called again with a fresh pulse on every restart.
+    pub fn start(
+        name: &'static str,
+        cancel: Option<Arc<CancelScope>>,
+        spawn: impl Fn(Pulse<T>) -> JoinHandle<()> + Send + 'static,
+        requeue: impl Fn(T) + Send + 'static,
+    ) -> Self {
+        let heartbeat = Arc::new(Heartbeat::new());
+        let handle = spawn(Pulse {
+            heartbeat: Arc::clone(&heartbeat),
+            epoch: 0,
+        });
+        Self {
+            name,
+            heartbeat,
+            handle,
+            spawn: Box::new(spawn),
+            requeue: Box::new(requeue),
+            cancel,
+        }
+    }
+
+    fn restart_if_stalled(&mut self, stuck_after: Duration) -> Option<WorkerRestart> {
+        let stall = self
+            .heartbeat
+            .check(self.handle.is_finished(), stuck_after, Instant::now())?;
+        if let Some(cancel) = self
+            .cancel
+            .as_ref()
+            .filter(|_| stall.reason == StallReason::Stuck)
+        {
+            cancel.cancel();
+        }
+        let requeued = stall.items.iter().map(InFlight::label).collect();
+        for item in stall.items {
+            (self.requeue)(item);
+        }
+        self.handle = (self.spawn)(Pulse {
+            heartbeat: Arc::clone(&self.heartbeat),
+            epoch: stall.epoch,
+        });
+        Some(WorkerRestart {
+            worker: self.name,
+            reason: stall.reason,
+            busy_ms: stall.busy_ms,
+            requeued,
+        })
+    }
+}
+
+// Lets the supervisor hold workers with different item types.
+pub trait Supervised: Send {
+    fn supervise(&mut self, stuck_after: Duration) -> Option<WorkerRestart>;
+}
+
+impl<T: InFlight> Supervised for WorkerSlot<T> {
+    fn supervise(&mut self, stuck_after: Duration) -> Option<WorkerRestart> {
+        self.restart_if_stalled(stuck_after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Heartbeat, InFlight, Pulse, StallReason, Supervised, WorkerSlot};
+    use crate::cancel::CancelScope;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    impl InFlight for String {
+        fn label(&self) -> String {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn restarts_stuck_and_panicked_workers_and_requeues_their_items() {
+        let heartbeat = Heartbeat::<String>::new();
+        let now = Instant::now();
+        assert!(heartbeat.check(false, Duration::ZERO, now).is_none());
+        assert!(heartbeat.begin(0, &["seg_1.wav".to_string()]));
+        let later = now + Duration::from_secs(10);
+        assert!(heartbeat
+            .check(false, Duration::from_secs(60), later)
+            .is_none());
+        let stall = heartbeat
+            .check(false, Duration::from_secs(5), later)
+            .unwrap();
+        assert_eq!(stall.reason, StallReason::Stuck);
+        assert_eq!(stall.items, ["seg_1.wav"]);
+        // The replaced worker finds out once its item returns and must not take new ones.
+        assert!(!heartbeat.is_current(0));
+        assert!(!heartbeat.begin(0, &[]));
+        assert!(heartbeat.begin(stall.epoch, &[]));
+
+        let requeued = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&requeued);
+        let spawns = Arc::new(Mutex::new(Vec::new()));
+        let spawned = Arc::clone(&spawns);
+        let mut slot = WorkerSlot::start(
+            "test",
+            Some(Arc::new(CancelScope::new())),
+            move |pulse: Pulse<String>| {
+                spawned.lock().unwrap().push(pulse.epoch);
+                std::thread::spawn(move || {
+                    if pulse.epoch == 0 {
+                        pulse.begin(&["seg_2.wav".to_string()]);
+                        panic!("worker panic");
+                    }
+                })
+            },
+            move |item| sink.lock().unwrap().push(item),
+        );
+        while !slot.handle.is_finished() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let restart = slot.supervise(Duration::from_secs(60)).unwrap();
+        assert_eq!(restart.reason, StallReason::Panicked);
+        assert_eq!(restart.requeued, ["seg_2.wav"]);
+        assert_eq!(*requeued.lock().unwrap(), ["seg_2.wav"]);
+        assert_eq!(*spawns.lock().unwrap(), [0, 1]);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::audio::writer;
+use hound::WavReader;
+use serde::Serialize;
+use std::path::Path;
+
+pub const DEFAULT_BUCKETS: usize = 200;
+const MAX_BUCKETS: usize = 2_000;
+
+// Per-bucket peak and RMS of a segment, all channels together, for drawing a small waveform
+// without sending the PCM to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct Waveform {
+    pub name: String,
+    pub duration_ms: u64,
+    pub peaks: Vec<f32>,
+    pub rms: Vec<f32>,
+}
+
+pub fn load(path: &Path, name: &str, buckets: usize) -> Result<Waveform, String> {
+    let mut reader = WavReader::open(path).map_err(|err| err.to_string())?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let frames = reader.duration() as usize;
+    let samples = writer::f32_samples(&mut reader).map(|sample| sample.unwrap_or(0.0));
+    let (peaks, rms) = envelope(samples, channels, frames, buckets);
+    Ok(Waveform {
+        name: name.to_string(),
+        duration_ms: frames as u64 * 1000 / spec.sample_rate.max(1) as u64,
+        peaks,
+        rms,
+    })
+}
+
+// Splits `frames` into `buckets` nearly equal runs; fewer buckets when there are fewer frames.
+fn envelope(
+    samples: impl Iterator<Item = f32>,
+    channels: usize,
+    frames: usize,
+    buckets: usize,
+) -> (Vec<f32>, Vec<f32>) {
+    let buckets = buckets.clamp(1, MAX_BUCKETS).min(frames);
+    let mut peaks = vec![0.0f32; buckets];
+    let mut squares = vec![0.0f64; buckets];
+    let mut counts = vec![0usize; buckets];
+    for (index, sample) in samples.take(frames * channels).enumerate() {
+        let bucket = index / channels * buckets / frames;
+        peaks[bucket] = peaks[bucket].max(sample.abs());
+        squares[bucket] += (sample as f64) * (sample as f64);
+        counts[bucket] += 1;
+    }
+    let rms = squares
+        .iter()
+        .zip(&counts)
+        .map(|(sum, count)| (sum / (*count).max(1) as f64).sqrt() as f32)
+        .collect();
+    (peaks, rms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::envelope;
+
+    #[test]
+    fn buckets_peak_and_rms_across_channels() {
+        // Two channels, 8 frames: a loud first half and a quiet second half.
+        let samples = [
+            0.5, -1.0, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.1, 0.1, -0.1, 0.1, 0.1, 0.1, 0.1, -0.1,
+        ];
+        let (peaks, rms) = envelope(samples.into_iter(), 2, 8, 2);
+        assert_eq!(peaks, [1.0, 0.1]);
+        assert!((rms[0] - (2.75f32 / 8.0).sqrt()).abs() < 1e-6);
+        assert!((rms[1] - 0.1).abs() < 1e-6);
+
+        // Never more buckets than frames.
+        let (peaks, _) = envelope(samples.into_iter(), 2, 8, 200);
+        assert_eq!(peaks.len(), 8);
+        assert!(envelope(std::iter::empty(), 1, 0, 200).0.is_empty());
+    }
+}
@@ -1,54 +1,275 @@
+use crate::audio::config::AudioConfig;
 use crate::audio::manager::SegmentInfo;
-use chrono::Local;
-use hound::{SampleFormat, WavSpec, WavWriter};
-use std::fs::File;
-use std::io::BufWriter;
+use crate::audio::mixer::dominant_track;
+use crate::audio::resample::{MonoResampler, ASR_SAMPLE_RATE};
+use crate::audio::timing::{self, Stage, StageTimings};
+use crate::timestamps::rfc3339;
+use chrono::{DateTime, Local};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::io::{Read, Seek, Write};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+// Chunks of captured PCM waiting for the writer thread; a full queue makes the capture loop wait
+// rather than silently dropping audio.
+const WRITE_QUEUE_CHUNKS: usize = 256;
+// How often the header is rewritten to cover what was written so far, so a crash loses at most
+// this much audio even before `audio::recovery` repairs the file.
+const HEADER_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentFormat {
+    // 32-bit float at the capture rate and channel count.
+    Capture,
+    // 16 kHz mono 16-bit, what ASR works at, converted by `audio::resample`.
+    AsrMono16k,
+}
+
+impl SegmentFormat {
+    pub fn from_config(config: &AudioConfig) -> Self {
+        if config.resample_16k_mono {
+            Self::AsrMono16k
+        } else {
+            Self::Capture
+        }
+    }
+}
+
+// Writes one sample in the writer's own format; integer formats get it scaled from [-1, 1].
+pub fn write_sample<W: Write + Seek>(writer: &mut WavWriter<W>, sample: f32) -> Result<(), String> {
+    match writer.spec().sample_format {
+        SampleFormat::Float => writer.write_sample(sample),
+        SampleFormat::Int => {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        }
+    }
+    .map_err(|err| err.to_string())
+}
+
+// Samples in [-1, 1] whichever format the file stores: float, or 16-bit after resampling.
+pub fn f32_samples<R: Read>(
+    reader: &mut WavReader<R>,
+) -> Box<dyn Iterator<Item = Result<f32, String>> + '_> {
+    let spec = reader.spec();
+    match spec.sample_format {
+        SampleFormat::Float => Box::new(
+            reader
+                .samples::<f32>()
+                .map(|sample| sample.map_err(|err| err.to_string())),
+        ),
+        SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            Box::new(reader.samples::<i32>().map(move |sample| {
+                sample
+                    .map(|value| value as f32 * scale)
+                    .map_err(|err| err.to_string())
+            }))
+        }
+    }
+}
+
+// Samples are handed to a thread that owns the WAV file, so the capture loop only copies buffers
+// and never waits on the disk. Integer formats get samples scaled from [-1, 1].
+pub struct WavSink {
+    sender: Option<SyncSender<Vec<f32>>>,
+    worker: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl WavSink {
+    pub fn create(path: &Path, spec: WavSpec, thread_name: &str) -> Result<Self, String> {
+        Self::create_with(path, spec, None, thread_name)
+    }
+
+    // With a resampler, samples are converted on the writer thread before they reach `spec`.
+    fn create_with(
+        path: &Path,
+        spec: WavSpec,
+        mut resampler: Option<MonoResampler>,
+        thread_name: &str,
+    ) -> Result<Self, String> {
+        let mut writer = WavWriter::create(path, spec).map_err(|err| err.to_string())?;
+        let (sender, receiver) = mpsc::sync_channel::<Vec<f32>>(WRITE_QUEUE_CHUNKS);
+        let worker = thread::Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                let mut flushed_at = Instant::now();
+                for chunk in receiver {
+                    let chunk = match resampler.as_mut() {
+                        Some(resampler) => resampler.process(&chunk)?,
+                        None => chunk,
+                    };
+                    for sample in chunk {
+                        write_sample(&mut writer, sample)?;
+                    }
+                    if flushed_at.elapsed() >= HEADER_FLUSH_INTERVAL {
+                        writer.flush().map_err(|err| err.to_string())?;
+                        flushed_at = Instant::now();
+                    }
+                }
+                if let Some(resampler) = resampler.as_mut() {
+                    for sample in resampler.finish()? {
+                        write_sample(&mut writer, sample)?;
+                    }
+                }
+                writer.flush().map_err(|err| err.to_string())?;
+                writer.finalize().map_err(|err| err.to_string())
+            })
+            .map_err(|err| err.to_string())?;
+        Ok(Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    pub fn write(&self, samples: &[f32]) -> Result<(), String> {
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| "audio writer closed".to_string())?;
+        // The receiver only goes away when the worker hit a write error; `finish` reports it.
+        sender
+            .send(samples.to_vec())
+            .map_err(|_| "audio writer stopped".to_string())
+    }
+
+    pub fn close(&mut self) {
+        drop(self.sender.take());
+    }
+
+    // Waits for queued samples to reach the file.
+    pub fn finish(&mut self) -> Result<(), String> {
+        self.close();
+        match self.worker.take() {
+            Some(worker) => worker
+                .join()
+                .map_err(|_| "audio writer thread panicked".to_string())?,
+            None => Ok(()),
+        }
+    }
+}
+
+// One VAD-split segment, plus what its `SegmentInfo` needs once the file is complete.
 pub struct SegmentWriter {
-    writer: WavWriter<BufWriter<File>>,
+    sink: WavSink,
     path: PathBuf,
     created_at: String,
+    // The capture format `write` takes; the file may store another, see `SegmentFormat`.
     sample_rate: u32,
     channels: u16,
+    stored: WavSpec,
     samples_written: u64,
+    session_id: String,
+    opened_at: Instant,
+    closed_at: Option<Instant>,
+    // Summed squares per channel of a dual-source recording, to label the segment's track.
+    track_energy: Option<[f64; 2]>,
+    start_offset_ms: Option<u64>,
 }
 
 impl SegmentWriter {
-    pub fn start_new(dir: &Path, sample_rate: u32, channels: u16) -> Result<Self, String> {
-        let now = Local::now();
+    pub fn start_new(
+        dir: &Path,
+        sample_rate: u32,
+        channels: u16,
+        format: SegmentFormat,
+        session_id: &str,
+    ) -> Result<Self, String> {
+        Self::start_at(dir, sample_rate, channels, format, session_id, Local::now())
+    }
+
+    // For audio captured earlier, named and dated by when it was captured.
+    pub fn start_at(
+        dir: &Path,
+        sample_rate: u32,
+        channels: u16,
+        format: SegmentFormat,
+        session_id: &str,
+        now: DateTime<Local>,
+    ) -> Result<Self, String> {
         let name = format!("segment_{}.wav", now.format("%Y%m%d_%H%M%S_%3f"));
         let path = dir.join(&name);
-        let spec = WavSpec {
-            channels,
-            sample_rate,
-            bits_per_sample: 32,
-            sample_format: SampleFormat::Float,
+        let (stored, resampler) = match format {
+            SegmentFormat::Capture => (
+                WavSpec {
+                    channels,
+                    sample_rate,
+                    bits_per_sample: 32,
+                    sample_format: SampleFormat::Float,
+                },
+                None,
+            ),
+            SegmentFormat::AsrMono16k => (
+                WavSpec {
+                    channels: 1,
+                    sample_rate: ASR_SAMPLE_RATE,
+                    bits_per_sample: 16,
+                    sample_format: SampleFormat::Int,
+                },
+                Some(MonoResampler::new(sample_rate, channels)?),
+            ),
         };
-        let writer = WavWriter::create(&path, spec).map_err(|err| err.to_string())?;
         Ok(Self {
-            writer,
+            sink: WavSink::create_with(&path, stored, resampler, "segment-writer")?,
             path,
-            created_at: now.to_rfc3339(),
+            created_at: rfc3339(&now),
             sample_rate,
             channels,
+            stored,
             samples_written: 0,
+            session_id: session_id.to_string(),
+            opened_at: Instant::now(),
+            closed_at: None,
+            track_energy: None,
+            start_offset_ms: None,
         })
     }
 
+    // For two-channel recordings from `audio::mixer`.
+    pub fn with_track_energy(mut self) -> Self {
+        if self.channels == 2 {
+            self.track_energy = Some([0.0; 2]);
+        }
+        self
+    }
+
+    // Milliseconds from the start of capture to the first sample.
+    pub fn with_start_offset(mut self, ms: u64) -> Self {
+        self.start_offset_ms = Some(ms);
+        self
+    }
+
     pub fn write(&mut self, samples: &[f32]) -> Result<(), String> {
-        for sample in samples {
-            self.writer
-                .write_sample(*sample)
-                .map_err(|err| err.to_string())?;
+        self.sink.write(samples)?;
+        if let Some(energy) = self.track_energy.as_mut() {
+            for frame in samples.chunks_exact(2) {
+                energy[0] += (frame[0] as f64).powi(2);
+                energy[1] += (frame[1] as f64).powi(2);
+            }
         }
         self.samples_written += samples.len() as u64;
         Ok(())
     }
 
+    pub fn opened_at(&self) -> Instant {
+        self.opened_at
+    }
+
+    pub fn closed_at(&self) -> Option<Instant> {
+        self.closed_at
+    }
+
+    // Called by the capture thread when the segment ends; the file is flushed in the background.
+    pub fn close(&mut self) {
+        self.sink.close();
+        self.closed_at.get_or_insert_with(Instant::now);
+    }
+
+    // Waits for queued samples to reach the file; call off the capture thread.
     pub fn finalize(mut self) -> Result<SegmentInfo, String> {
-        self.writer.flush().map_err(|err| err.to_string())?;
-        self.writer.finalize().map_err(|err| err.to_string())?;
+        self.close();
+        self.sink.finish()?;
 
         let frames = self.samples_written / self.channels as u64;
         let duration_ms = if self.sample_rate == 0 {
@@ -63,23 +284,42 @@ impl SegmentWriter {
             .and_then(|value| value.to_str())
             .unwrap_or("segment.wav")
             .to_string();
+        let mut timings = StageTimings::default();
+        if let Some(closed_at) = self.closed_at {
+            timings.record(&name, Stage::Finalize, timing::elapsed_ms(closed_at));
+        }
 
         Ok(SegmentInfo {
             name,
             duration_ms,
             created_at: self.created_at,
-            sample_rate: self.sample_rate,
-            channels: self.channels,
+            sample_rate: self.stored.sample_rate,
+            channels: self.stored.channels,
             transcript: None,
             translation: None,
             transcript_at: None,
             translation_at: None,
             transcript_ms: None,
             translation_ms: None,
+            translation_language: None,
+            translation_provider: None,
             speaker_id: None,
             speaker_changed: None,
             speaker_similarity: None,
             speaker_switches_ms: None,
+            session_id: Some(self.session_id),
+            timings,
+            transcript_language: None,
+            failures: Vec::new(),
+            track: self
+                .track_energy
+                .and_then(dominant_track)
+                .map(str::to_string),
+            speech_spans: None,
+            start_offset_ms: self.start_offset_ms,
+            end_offset_ms: self.start_offset_ms.map(|start| start + duration_ms),
+            words: None,
+            draft: false,
         })
     }
 }
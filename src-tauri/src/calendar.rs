@@ -0,0 +1,527 @@
+use crate::app_config::{load_config, CalendarConfig};
+use crate::audio::CaptureManager;
+use crate::defaults::DEFAULT_CALENDAR_EARLY_MINUTES;
+use crate::events::EventHub;
+use crate::http;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+const CALENDAR_FETCH_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_EVENT_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub attendees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: i64,
+    until: Option<NaiveDateTime>,
+    count: Option<usize>,
+    by_day: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct VEvent {
+    uid: String,
+    summary: String,
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+    duration: Option<Duration>,
+    attendees: Vec<String>,
+    recurrence: Option<Recurrence>,
+    exdates: Vec<NaiveDateTime>,
+    recurrence_id: Option<NaiveDateTime>,
+    cancelled: bool,
+}
+
+// Labels each new session with the calendar event that is happening when capture starts.
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed || event.event != "session_started" {
+                continue;
+            }
+            let Some(session_id) = event.payload.get("id").and_then(|value| value.as_str()) else {
+                continue;
+            };
+            let config = load_config()
+                .ok()
+                .and_then(|cfg| cfg.calendar)
+                .unwrap_or_default();
+            if config.enabled != Some(true) {
+                continue;
+            }
+            let found = match current_event(&config).await {
+                Ok(Some(found)) if !found.title.is_empty() => found,
+                Ok(_) => continue,
+                Err(err) => {
+                    warn!("calendar lookup failed: {err}");
+                    continue;
+                }
+            };
+            let Some(manager) = app.try_state::<CaptureManager>() else {
+                continue;
+            };
+            if let Err(err) = manager.label_session(&app, session_id, found.title, found.attendees)
+            {
+                warn!("calendar label failed: {err}");
+            }
+        }
+    });
+}
+
+pub async fn current_event(config: &CalendarConfig) -> Result<Option<CalendarEvent>, String> {
+    let content = read_calendar(config).await?;
+    let early = Duration::minutes(
+        config
+            .early_minutes
+            .unwrap_or(DEFAULT_CALENDAR_EARLY_MINUTES) as i64,
+    );
+    Ok(find_current_event(
+        &content,
+        Local::now().naive_local(),
+        early,
+    ))
+}
+
+pub async fn read_calendar(config: &CalendarConfig) -> Result<String, String> {
+    if let Some(url) = config
+        .ics_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        // Google Calendar and Outlook publish `webcal://` links that are plain HTTPS underneath.
+        let url = match url.strip_prefix("webcal://") {
+            Some(rest) => format!("https://{rest}"),
+            None => url.to_string(),
+        };
+        let client = http::client(Some(CALENDAR_FETCH_TIMEOUT_SECS))?;
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        let status = response.status();
+        let text = response.text().await.map_err(|err| err.to_string())?;
+        if !status.is_success() {
+            return Err(format!("calendar fetch failed: HTTP {status}"));
+        }
+        return Ok(text);
+    }
+    if let Some(path) = config
+        .ics_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        return std::fs::read_to_string(path).map_err(|err| format!("{path}: {err}"));
+    }
+    Err("calendar.icsUrl or calendar.icsPath is required".to_string())
+}
+
+// Picks the event whose start is closest to `now`, so back-to-back meetings resolve to the upcoming one.
+pub fn find_current_event(ics: &str, now: NaiveDateTime, early: Duration) -> Option<CalendarEvent> {
+    let events = parse_events(ics);
+    let mut overridden: HashMap<&str, Vec<NaiveDateTime>> = HashMap::new();
+    for event in &events {
+        if let Some(recurrence_id) = event.recurrence_id {
+            overridden
+                .entry(event.uid.as_str())
+                .or_default()
+                .push(recurrence_id);
+        }
+    }
+
+    let mut best: Option<CalendarEvent> = None;
+    for event in events.iter().filter(|event| !event.cancelled) {
+        let Some(start) = event.start else {
+            continue;
+        };
+        let length = event_length(event, start);
+        let skipped = overridden.get(event.uid.as_str());
+        let candidates = match (&event.recurrence, event.recurrence_id) {
+            (Some(rule), None) => occurrences_near(rule, start, length, now, early),
+            _ => vec![start],
+        };
+        for occurrence in candidates {
+            if event.exdates.contains(&occurrence)
+                || (event.recurrence_id.is_none()
+                    && skipped.is_some_and(|items| items.contains(&occurrence)))
+            {
+                continue;
+            }
+            let end = occurrence + length;
+            if now < occurrence - early || now >= end {
+                continue;
+            }
+            let closer = best.as_ref().is_none_or(|current| {
+                (occurrence - now).num_seconds().abs() < (current.start - now).num_seconds().abs()
+            });
+            if closer {
+                best = Some(CalendarEvent {
+                    title: event.summary.clone(),
+                    start: occurrence,
+                    end,
+                    attendees: event.attendees.clone(),
+                });
+            }
+        }
+    }
+    best
+}
+
+fn event_length(event: &VEvent, start: NaiveDateTime) -> Duration {
+    event
+        .end
+        .map(|end| end - start)
+        .or(event.duration)
+        .filter(|length| *length > Duration::zero())
+        .unwrap_or_else(|| Duration::minutes(DEFAULT_EVENT_MINUTES))
+}
+
+// Only the days around `now` can hold a matching occurrence, so the rule is tested day by day there.
+fn occurrences_near(
+    rule: &Recurrence,
+    start: NaiveDateTime,
+    length: Duration,
+    now: NaiveDateTime,
+    early: Duration,
+) -> Vec<NaiveDateTime> {
+    let first = (now - length).date() - Duration::days(1);
+    let last = (now + early).date();
+    let mut output = Vec::new();
+    let mut day = first.max(start.date());
+    while day <= last {
+        let occurrence = day.and_time(start.time());
+        if rule_matches(rule, start, day)
+            && rule.until.is_none_or(|until| occurrence <= until)
+            && rule
+                .count
+                .is_none_or(|count| occurrence_index(rule, start, day) < count)
+        {
+            output.push(occurrence);
+        }
+        day += Duration::days(1);
+    }
+    output
+}
+
+fn rule_matches(rule: &Recurrence, start: NaiveDateTime, day: NaiveDate) -> bool {
+    let first = start.date();
+    if day < first {
+        return false;
+    }
+    match rule.frequency {
+        Frequency::Daily => (day - first).num_days() % rule.interval == 0,
+        Frequency::Weekly => {
+            let weekday_ok = if rule.by_day.is_empty() {
+                day.weekday() == first.weekday()
+            } else {
+                rule.by_day.contains(&day.weekday())
+            };
+            let week_of = |date: NaiveDate| {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            };
+            let weeks = (week_of(day) - week_of(first)).num_days() / 7;
+            weekday_ok && weeks % rule.interval == 0
+        }
+    }
+}
+
+fn occurrence_index(rule: &Recurrence, start: NaiveDateTime, day: NaiveDate) -> usize {
+    let mut index = 0;
+    let mut current = start.date();
+    while current < day {
+        if rule_matches(rule, start, current) {
+            index += 1;
+        }
+        current += Duration::days(1);
+    }
+    index
+}
+
+fn parse_events(ics: &str) -> Vec<VEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<VEvent> = None;
+    for line in unfold_lines(ics) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match (name.as_str(), value.as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(VEvent::default()),
+            ("END", "VEVENT") => events.extend(current.take()),
+            _ => {
+                let Some(event) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "UID" => event.uid = value,
+                    "SUMMARY" => event.summary = unescape_text(&value),
+                    "DTSTART" => event.start = parse_timed(&params, &value),
+                    "DTEND" => event.end = parse_timed(&params, &value),
+                    "DURATION" => event.duration = parse_duration(&value),
+                    "RRULE" => event.recurrence = parse_rule(&value),
+                    "EXDATE" => event.exdates.extend(
+                        value
+                            .split(',')
+                            .filter_map(|item| parse_timed(&params, item)),
+                    ),
+                    "RECURRENCE-ID" => event.recurrence_id = parse_timed(&params, &value),
+                    "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+                    "ATTENDEE" => event.attendees.extend(parse_attendee(&params, &value)),
+                    _ => {}
+                }
+            }
+        }
+    }
+    events
+}
+
+fn unfold_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(rest) = raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        lines.push(raw.to_string());
+    }
+    lines
+}
+
+// `NAME;PARAM=a;PARAM="b:c":value` -> (NAME, params, value); colons inside quotes stay in params.
+fn split_property(line: &str) -> Option<(String, HashMap<String, String>, String)> {
+    let mut in_quotes = false;
+    let split = line.char_indices().find_map(|(index, ch)| {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(index),
+            _ => {}
+        }
+        None
+    })?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    let params = parts
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some((name, params, value.trim().to_string()))
+}
+
+// All-day events (`VALUE=DATE`) are skipped: they would match every capture that day.
+// TZID times are read as local time, which holds for the usual single-timezone calendar.
+fn parse_timed(params: &HashMap<String, String>, value: &str) -> Option<NaiveDateTime> {
+    if params.get("VALUE").is_some_and(|kind| kind == "DATE") {
+        return None;
+    }
+    let value = value.trim();
+    let (raw, utc) = match value.strip_suffix('Z') {
+        Some(raw) => (raw, true),
+        None => (value, false),
+    };
+    let naive = NaiveDateTime::parse_from_str(raw, "%Y%m%dT%H%M%S").ok()?;
+    if utc {
+        Some(
+            Utc.from_utc_datetime(&naive)
+                .with_timezone(&Local)
+                .naive_local(),
+        )
+    } else {
+        Some(naive)
+    }
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    let rest = value
+        .trim()
+        .strip_prefix("PT")
+        .or_else(|| value.trim().strip_prefix('P'))?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for ch in rest.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let amount = number.parse::<i64>().ok()?;
+        number.clear();
+        total += match ch {
+            'W' => Duration::weeks(amount),
+            'D' => Duration::days(amount),
+            'H' => Duration::hours(amount),
+            'M' => Duration::minutes(amount),
+            'S' => Duration::seconds(amount),
+            'T' => continue,
+            _ => return None,
+        };
+    }
+    Some(total)
+}
+
+// Daily and weekly rules cover the recurring meetings this is meant for; other rules fall back to DTSTART.
+fn parse_rule(value: &str) -> Option<Recurrence> {
+    let fields = value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value.to_string()))
+        .collect::<HashMap<_, _>>();
+    let frequency = match fields.get("FREQ")?.as_str() {
+        "DAILY" => Frequency::Daily,
+        "WEEKLY" => Frequency::Weekly,
+        _ => return None,
+    };
+    let until = fields.get("UNTIL").and_then(|raw| {
+        parse_timed(&HashMap::new(), raw).or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y%m%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(23, 59, 59))
+        })
+    });
+    let by_day = fields
+        .get("BYDAY")
+        .map(|raw| {
+            raw.split(',')
+                .filter_map(|day| {
+                    // Drop ordinal prefixes such as `1MO`; they only matter for monthly rules.
+                    let code = day.trim_start_matches(|ch: char| {
+                        ch == '-' || ch == '+' || ch.is_ascii_digit()
+                    });
+                    match code {
+                        "MO" => Some(Weekday::Mon),
+                        "TU" => Some(Weekday::Tue),
+                        "WE" => Some(Weekday::Wed),
+                        "TH" => Some(Weekday::Thu),
+                        "FR" => Some(Weekday::Fri),
+                        "SA" => Some(Weekday::Sat),
+                        "SU" => Some(Weekday::Sun),
+                        _ => None,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(Recurrence {
+        frequency,
+        interval: fields
+            .get("INTERVAL")
+            .and_then(|raw| raw.parse::<i64>().ok())
+            .filter(|interval| *interval > 0)
+            .unwrap_or(1),
+        until,
+        count: fields.get("COUNT").and_then(|raw| raw.parse().ok()),
+        by_day,
+    })
+}
+
+fn parse_attendee(params: &HashMap<String, String>, value: &str) -> Option<String> {
+    let name = params
+        .get("CN")
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let email = value
+        .strip_prefix("mailto:")
+        .or_else(|| value.strip_prefix("MAILTO:"))
+        .unwrap_or(value)
+        .trim()
+        .to_string();
+    name.or_else(|| (!email.is_empty()).then_some(email))
+}
+
+fn unescape_text(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_current_event;
+    use chrono::{Duration, NaiveDateTime};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:sync\r\n\
+SUMMARY:Weekly Platform Sync\r\n\
+DTSTART;TZID=Europe/Berlin:20250106T100000\r\n\
+DTEND;TZID=Europe/Berlin:20250106T110000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO\r\n\
+EXDATE;TZID=Europe/Berlin:20250113T100000\r\n\
+ATTENDEE;CN=\"Doe, Jane\":mailto:jane@example.com\r\n\
+ATTENDEE:mailto:bob@example.com\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:one-off\r\n\
+SUMMARY:Design review\\, round 2\r\n\
+DTSTART:20250107T140000\r\n\
+DURATION:PT30M\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn finds_recurring_occurrence_with_attendees() {
+        let event =
+            find_current_event(CALENDAR, at("2025-01-20 09:55"), Duration::minutes(10)).unwrap();
+        assert_eq!(event.title, "Weekly Platform Sync");
+        assert_eq!(event.start, at("2025-01-20 10:00"));
+        assert_eq!(event.attendees, vec!["Doe, Jane", "bob@example.com"]);
+    }
+
+    #[test]
+    fn honours_exdates_and_durations() {
+        assert!(
+            find_current_event(CALENDAR, at("2025-01-13 10:15"), Duration::minutes(10)).is_none()
+        );
+        let event =
+            find_current_event(CALENDAR, at("2025-01-07 14:20"), Duration::minutes(10)).unwrap();
+        assert_eq!(event.title, "Design review, round 2");
+        assert!(
+            find_current_event(CALENDAR, at("2025-01-07 14:31"), Duration::minutes(10)).is_none()
+        );
+    }
+}
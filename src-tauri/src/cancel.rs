@@ -0,0 +1,66 @@
+use std::future::Future;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+// Hands out the token for the current run; `cancel` aborts everything holding it and starts a
+// fresh run, so work queued afterwards is unaffected.
+pub struct CancelScope {
+    token: Mutex<CancellationToken>,
+}
+
+impl CancelScope {
+    pub fn new() -> Self {
+        Self {
+            token: Mutex::new(CancellationToken::new()),
+        }
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        match self.token.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    pub fn cancel(&self) {
+        let mut guard = match self.token.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        guard.cancel();
+        *guard = CancellationToken::new();
+    }
+}
+
+// Drops `future` as soon as `token` fires, which aborts any in-flight HTTP request inside it.
+// Returns `None` when cancelled.
+pub async fn cancellable<F: Future>(token: &CancellationToken, future: F) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        output = future => Some(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cancellable, CancelScope};
+
+    #[test]
+    fn cancel_aborts_only_the_current_run() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let scope = CancelScope::new();
+        let old = scope.token();
+        scope.cancel();
+        let fresh = scope.token();
+        assert!(old.is_cancelled());
+        assert!(!fresh.is_cancelled());
+
+        let pending = runtime.block_on(cancellable(&old, std::future::pending::<()>()));
+        assert_eq!(pending, None);
+        assert_eq!(runtime.block_on(cancellable(&fresh, async { 7 })), Some(7));
+    }
+}
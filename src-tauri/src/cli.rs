@@ -0,0 +1,350 @@
+use crate::pii::{mask_text, mask_transcript};
+use crate::rag::{rag_project_list, RagState};
+use crate::rag_batch::answer_markdown;
+use crate::timestamps::{now_rfc3339, TimeDisplay};
+use crate::transcribe::transcribe_file;
+use crate::translate::{translate_text, TranslateSource};
+use crate::whisper_pipe::WhisperPipeManager;
+use crate::whisper_server::WhisperServerManager;
+use crate::{answer_rag_question, normalize_translate_provider, RagAskRequest};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Builder, Context, Manager, Wry};
+
+pub const USAGE: &str = "Usage:
+  ai-shepherd --transcribe <file.wav> [--translate <lang>] [--provider <name>] [--out <notes.md>]
+  ai-shepherd --rag-ask <question> --project <id|name> [--project ...] [--top-k <n>] [--provider <name>] [--out <answer.md>]
+
+Without these flags the desktop app starts as usual.";
+
+const TRANSLATE_CHUNK_CHARS: usize = 1_500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Transcribe {
+        input: PathBuf,
+        translate: Option<String>,
+        provider: Option<String>,
+        out: Option<PathBuf>,
+    },
+    RagAsk {
+        query: String,
+        projects: Vec<String>,
+        top_k: Option<usize>,
+        provider: Option<String>,
+        out: Option<PathBuf>,
+    },
+    Help,
+}
+
+// Returns `Ok(None)` when no headless flag is present so the GUI starts normally.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<CliCommand>, String> {
+    let args = args.into_iter().collect::<Vec<_>>();
+    let is_headless = args
+        .iter()
+        .any(|arg| matches!(arg.as_str(), "--transcribe" | "--rag-ask" | "--help" | "-h"));
+    if !is_headless {
+        return Ok(None);
+    }
+
+    let mut transcribe = None;
+    let mut rag_ask = None;
+    let mut translate = None;
+    let mut provider = None;
+    let mut out = None;
+    let mut projects = Vec::new();
+    let mut top_k = None;
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(Some(CliCommand::Help)),
+            "--transcribe" => transcribe = Some(PathBuf::from(value(&arg)?)),
+            "--rag-ask" => rag_ask = Some(value(&arg)?),
+            "--translate" => translate = Some(value(&arg)?),
+            "--provider" => provider = Some(value(&arg)?),
+            "--out" => out = Some(PathBuf::from(value(&arg)?)),
+            "--project" => projects.push(value(&arg)?),
+            "--top-k" => {
+                let raw = value(&arg)?;
+                let parsed = raw
+                    .parse::<usize>()
+                    .map_err(|_| format!("--top-k must be a number, got {raw}"))?;
+                top_k = Some(parsed);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+    }
+
+    match (transcribe, rag_ask) {
+        (Some(_), Some(_)) => Err("--transcribe and --rag-ask cannot be combined".to_string()),
+        (Some(input), None) => {
+            if !projects.is_empty() || top_k.is_some() {
+                return Err("--project and --top-k only apply to --rag-ask".to_string());
+            }
+            Ok(Some(CliCommand::Transcribe {
+                input,
+                translate,
+                provider,
+                out,
+            }))
+        }
+        (None, Some(query)) => {
+            if translate.is_some() {
+                return Err("--translate only applies to --transcribe".to_string());
+            }
+            if projects.is_empty() {
+                return Err("--rag-ask requires at least one --project".to_string());
+            }
+            Ok(Some(CliCommand::RagAsk {
+                query,
+                projects,
+                top_k,
+                provider,
+                out,
+            }))
+        }
+        (None, None) => Err("expected --transcribe or --rag-ask".to_string()),
+    }
+}
+
+// Builds the app without its configured windows and runs one pipeline to completion.
+pub fn run(builder: Builder<Wry>, mut context: Context<Wry>, command: CliCommand) -> i32 {
+    if command == CliCommand::Help {
+        println!("{USAGE}");
+        return 0;
+    }
+    context.config_mut().app.windows.clear();
+    let app = match builder.build(context) {
+        Ok(app) => app,
+        Err(err) => {
+            eprintln!("failed to start headless runtime: {err}");
+            return 1;
+        }
+    };
+    let handle = app.handle().clone();
+    crate::logging::init(&handle);
+    let result = tauri::async_runtime::block_on(execute(&handle, command));
+    if let Some(manager) = handle.try_state::<WhisperServerManager>() {
+        manager.stop();
+    }
+    if let Some(manager) = handle.try_state::<WhisperPipeManager>() {
+        manager.stop();
+    }
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error: {err}");
+            1
+        }
+    }
+}
+
+async fn execute(app: &AppHandle, command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Transcribe {
+            input,
+            translate,
+            provider,
+            out,
+        } => {
+            let notes = transcribe_notes(app, &input, translate.as_deref(), provider).await?;
+            write_output(out.as_deref(), &notes)
+        }
+        CliCommand::RagAsk {
+            query,
+            projects,
+            top_k,
+            provider,
+            out,
+        } => {
+            let answer = rag_answer(app, query, &projects, top_k, provider).await?;
+            write_output(out.as_deref(), &answer)
+        }
+        CliCommand::Help => Ok(()),
+    }
+}
+
+async fn transcribe_notes(
+    app: &AppHandle,
+    input: &Path,
+    translate: Option<&str>,
+    provider: Option<String>,
+) -> Result<String, String> {
+    if !input.is_file() {
+        return Err(format!("input not found: {}", input.display()));
+    }
+    eprintln!("[cli] transcribing {}", input.display());
+    let transcript = mask_transcript(app, transcribe_file(app, input, None).await?).await;
+
+    let title = input
+        .file_stem()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Meeting".to_string());
+    let mut notes = format!(
+        "# {title}\n\n_Transcribed {} from `{}`_\n\n## Transcript\n\n{}\n",
+        TimeDisplay::from_config().absolute().format(&now_rfc3339()),
+        input.display(),
+        transcript.trim()
+    );
+
+    if let Some(language) = translate {
+        let provider = provider.map(|value| normalize_translate_provider(&value));
+        let chunks = split_chunks(&transcript, TRANSLATE_CHUNK_CHARS);
+        let mut translated = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            eprintln!("[cli] translating chunk {}/{}", index + 1, chunks.len());
+            let text = translate_text(
+                app,
+                chunk,
+                provider.clone(),
+                TranslateSource::Segment,
+                Some(language),
+            )
+            .await?;
+            translated.push(mask_text(&text));
+        }
+        notes.push_str(&format!(
+            "\n## Translation ({language})\n\n{}\n",
+            translated.join("\n\n").trim()
+        ));
+    }
+    Ok(notes)
+}
+
+async fn rag_answer(
+    app: &AppHandle,
+    query: String,
+    projects: &[String],
+    top_k: Option<usize>,
+    provider: Option<String>,
+) -> Result<String, String> {
+    let known = rag_project_list(app.clone())?.projects;
+    let project_ids = projects
+        .iter()
+        .map(|wanted| {
+            known
+                .iter()
+                .find(|project| {
+                    project.project_id == *wanted
+                        || project.project_name.eq_ignore_ascii_case(wanted)
+                })
+                .map(|project| project.project_id.clone())
+                .ok_or_else(|| format!("unknown RAG project: {wanted}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let provider = provider
+        .map(|value| normalize_translate_provider(&value))
+        .unwrap_or_else(|| crate::selected_translate_provider(app));
+    let state = app.state::<Arc<RagState>>().inner().clone();
+    let response = answer_rag_question(
+        app,
+        state,
+        provider,
+        RagAskRequest {
+            query: query.clone(),
+            project_ids,
+            top_k,
+            allow_out_of_context: None,
+            translate_answer: None,
+        },
+    )
+    .await?;
+
+    Ok(answer_markdown(&query, &response, 1))
+}
+
+// Release builds use the GUI subsystem, so Windows gives the process no console of its own;
+// borrow the one of the shell that started it. Fails harmlessly when there is none.
+#[cfg(windows)]
+pub fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+fn write_output(out: Option<&Path>, content: &str) -> Result<(), String> {
+    let Some(path) = out else {
+        print!("{content}");
+        return Ok(());
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, content).map_err(|err| err.to_string())?;
+    eprintln!("[cli] wrote {}", path.display());
+    Ok(())
+}
+
+// Splits on line boundaries so each translation request stays a manageable size.
+fn split_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if !current.is_empty() && current.chars().count() + line.chars().count() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, split_chunks, CliCommand};
+    use std::path::PathBuf;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_transcribe_command() {
+        let command = parse_args(args(&[
+            "--transcribe",
+            "meeting.wav",
+            "--translate",
+            "zh",
+            "--out",
+            "notes.md",
+        ]))
+        .unwrap();
+        assert_eq!(
+            command,
+            Some(CliCommand::Transcribe {
+                input: PathBuf::from("meeting.wav"),
+                translate: Some("zh".to_string()),
+                provider: None,
+                out: Some(PathBuf::from("notes.md")),
+            })
+        );
+    }
+
+    #[test]
+    fn gui_args_are_ignored_and_bad_flags_rejected() {
+        assert_eq!(parse_args(args(&[])).unwrap(), None);
+        assert!(parse_args(args(&["--rag-ask", "why?"])).is_err());
+        assert!(parse_args(args(&["--transcribe", "a.wav", "--bogus"])).is_err());
+    }
+
+    #[test]
+    fn splits_on_line_boundaries() {
+        let chunks = split_chunks("aaa\nbbb\n\nccc", 7);
+        assert_eq!(chunks, vec!["aaa\nbbb".to_string(), "ccc".to_string()]);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::app_config::{
+    load_config, load_config_value, load_file_config, save_config, AccelerationConfig, AppConfig,
+    AsrConfig, AutoStopConfig, CalendarConfig, ClaudeConfig, ConsentConfig, GeminiConfig,
+    InterpreterConfig, LocalGptConfig, LoggingConfig, MeetingTemplateConfig, MqttConfig,
+    NetworkConfig, NotificationsConfig, OllamaConfig, OpenAiConfig, PiiConfig, PunctuationConfig,
+    QueuesConfig, RagConfig, RemoteConfig, ScheduleConfig, ShareConfig, SpeakerConfig, TimeConfig,
+    TranslateConfig, UpdatesConfig, UsageConfig, WebhookConfig, WordFilterConfig,
+};
+use crate::defaults::{default_config_value, env_overrides};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[tauri::command]
+pub fn get_config() -> Result<AppConfig, String> {
+    load_config()
+}
+
+#[tauri::command]
+pub fn set_config(config: AppConfig) -> Result<AppConfig, String> {
+    // `get_config` includes env overrides (API keys too), templates and interpreter settings, so
+    // only the values the caller changed are merged into the file.
+    let effective = to_value(&load_config()?)?;
+    let mut merged = to_value(&load_file_config()?)?;
+    merge_changes(&mut merged, &effective, &to_value(&config)?);
+    save_config(&parse_section::<AppConfig>("app", merged)?)?;
+    load_config()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: Value,
+    pub default: Value,
+    pub source: String,
+}
+
+#[tauri::command]
+pub fn describe_config() -> Result<Vec<ConfigEntry>, String> {
+    let (_, file) = load_config_value()?;
+    let defaults = default_config_value();
+    let env = env_overrides()
+        .into_iter()
+        .map(|(section, key, value)| (format!("{section}.{key}"), value))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut keys = BTreeSet::new();
+    collect_keys(&defaults, &mut keys);
+    collect_keys(&file, &mut keys);
+    keys.extend(env.keys().cloned());
+
+    let entries = keys
+        .into_iter()
+        .map(|key| {
+            let default = lookup(&defaults, &key).cloned().unwrap_or(Value::Null);
+            let (value, source) = if let Some(value) = env.get(&key) {
+                (value.clone(), "env")
+            } else if let Some(value) = lookup(&file, &key) {
+                (value.clone(), "file")
+            } else {
+                (default.clone(), "default")
+            };
+            ConfigEntry {
+                key,
+                value,
+                default,
+                source: source.to_string(),
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn get_config_section(section: String) -> Result<Value, String> {
+    let config = load_config()?;
+    match normalize_section(&section)? {
+        "openai" => to_value(&config.openai),
+        "ollama" => to_value(&config.ollama),
+        "local_gpt" => to_value(&config.local_gpt),
+        "claude" => to_value(&config.claude.unwrap_or_default()),
+        "gemini" => to_value(&config.gemini.unwrap_or_default()),
+        "translate" => to_value(&config.translate),
+        "speaker" => to_value(&config.speaker),
+        "remote" => to_value(&config.remote),
+        "webhooks" => to_value(&config.webhooks.unwrap_or_default()),
+        "calendar" => to_value(&config.calendar.unwrap_or_default()),
+        "schedule" => to_value(&config.schedule.unwrap_or_default()),
+        "mqtt" => to_value(&config.mqtt.unwrap_or_default()),
+        "share" => to_value(&config.share.unwrap_or_default()),
+        "logging" => to_value(&config.logging.unwrap_or_default()),
+        "network" => to_value(&config.network.unwrap_or_default()),
+        "queues" => to_value(&config.queues.unwrap_or_default()),
+        "acceleration" => to_value(&config.acceleration.unwrap_or_default()),
+        "rag" => to_value(&config.rag.unwrap_or_default()),
+        "notifications" => to_value(&config.notifications.unwrap_or_default()),
+        "updates" => to_value(&config.updates.unwrap_or_default()),
+        "time" => to_value(&config.time.unwrap_or_default()),
+        "pii" => to_value(&config.pii.unwrap_or_default()),
+        "word_filter" => to_value(&config.word_filter.unwrap_or_default()),
+        "consent" => to_value(&config.consent.unwrap_or_default()),
+        "templates" => to_value(&config.templates.unwrap_or_default()),
+        "auto_stop" => to_value(&config.auto_stop.unwrap_or_default()),
+        "interpreter" => to_value(&config.interpreter.unwrap_or_default()),
+        "punctuation" => to_value(&config.punctuation.unwrap_or_default()),
+        "usage" => to_value(&config.usage.unwrap_or_default()),
+        _ => to_value(&config.asr.unwrap_or_default()),
+    }
+}
+
+#[tauri::command]
+pub fn set_config_section(section: String, value: Value) -> Result<Value, String> {
+    // Start from the file alone so env overrides are not persisted by accident.
+    let mut config = load_file_config()?;
+    let section = normalize_section(&section)?;
+    match section {
+        "openai" => config.openai = parse_section::<OpenAiConfig>(section, value)?,
+        "ollama" => config.ollama = Some(parse_section::<OllamaConfig>(section, value)?),
+        "local_gpt" => config.local_gpt = Some(parse_section::<LocalGptConfig>(section, value)?),
+        "claude" => config.claude = Some(parse_section::<ClaudeConfig>(section, value)?),
+        "gemini" => config.gemini = Some(parse_section::<GeminiConfig>(section, value)?),
+        "translate" => config.translate = Some(parse_section::<TranslateConfig>(section, value)?),
+        "speaker" => config.speaker = Some(parse_section::<SpeakerConfig>(section, value)?),
+        "remote" => config.remote = Some(parse_section::<RemoteConfig>(section, value)?),
+        "webhooks" => config.webhooks = Some(parse_section::<Vec<WebhookConfig>>(section, value)?),
+        "calendar" => config.calendar = Some(parse_section::<CalendarConfig>(section, value)?),
+        "schedule" => config.schedule = Some(parse_section::<ScheduleConfig>(section, value)?),
+        "mqtt" => config.mqtt = Some(parse_section::<MqttConfig>(section, value)?),
+        "share" => config.share = Some(parse_section::<ShareConfig>(section, value)?),
+        "logging" => config.logging = Some(parse_section::<LoggingConfig>(section, value)?),
+        "network" => config.network = Some(parse_section::<NetworkConfig>(section, value)?),
+        "queues" => config.queues = Some(parse_section::<QueuesConfig>(section, value)?),
+        "acceleration" => {
+            config.acceleration = Some(parse_section::<AccelerationConfig>(section, value)?)
+        }
+        "rag" => config.rag = Some(parse_section::<RagConfig>(section, value)?),
+        "notifications" => {
+            config.notifications = Some(parse_section::<NotificationsConfig>(section, value)?)
+        }
+        "updates" => config.updates = Some(parse_section::<UpdatesConfig>(section, value)?),
+        "time" => config.time = Some(parse_section::<TimeConfig>(section, value)?),
+        "pii" => config.pii = Some(parse_section::<PiiConfig>(section, value)?),
+        "word_filter" => {
+            config.word_filter = Some(parse_section::<WordFilterConfig>(section, value)?)
+        }
+        "consent" => config.consent = Some(parse_section::<ConsentConfig>(section, value)?),
+        "templates" => {
+            config.templates = Some(parse_section::<Vec<MeetingTemplateConfig>>(section, value)?)
+        }
+        "auto_stop" => config.auto_stop = Some(parse_section::<AutoStopConfig>(section, value)?),
+        "interpreter" => {
+            config.interpreter = Some(parse_section::<InterpreterConfig>(section, value)?)
+        }
+        "punctuation" => {
+            config.punctuation = Some(parse_section::<PunctuationConfig>(section, value)?)
+        }
+        "usage" => config.usage = Some(parse_section::<UsageConfig>(section, value)?),
+        _ => config.asr = Some(parse_section::<AsrConfig>(section, value)?),
+    }
+    save_config(&config)?;
+    get_config_section(section.to_string())
+}
+
+// Applies to `file` every field where `incoming` differs from `effective`, field by field inside
+// objects; fields dropped from `incoming` are dropped from `file`.
+fn merge_changes(file: &mut Value, effective: &Value, incoming: &Value) {
+    let (Some(effective), Some(incoming)) = (effective.as_object(), incoming.as_object()) else {
+        *file = incoming.clone();
+        return;
+    };
+    if !file.is_object() {
+        *file = Value::Object(serde_json::Map::new());
+    }
+    let Some(fields) = file.as_object_mut() else {
+        return;
+    };
+    for (name, value) in incoming {
+        match effective.get(name) {
+            Some(current) if current == value => {}
+            Some(current) if current.is_object() && value.is_object() => {
+                let field = fields.entry(name.clone()).or_insert(Value::Null);
+                merge_changes(field, current, value);
+            }
+            _ => {
+                fields.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    for name in effective.keys() {
+        if !incoming.contains_key(name) {
+            fields.remove(name);
+        }
+    }
+}
+
+fn collect_keys(value: &Value, keys: &mut BTreeSet<String>) {
+    let Some(root) = value.as_object() else {
+        return;
+    };
+    for (name, child) in root {
+        match child {
+            Value::Object(fields) => {
+                keys.extend(fields.keys().map(|field| format!("{name}.{field}")));
+            }
+            _ => {
+                keys.insert(name.clone());
+            }
+        }
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match key.split_once('.') {
+        Some((section, field)) => value.get(section)?.get(field),
+        None => value.get(key),
+    }
+}
+
+fn normalize_section(section: &str) -> Result<&'static str, String> {
+    match section.trim().to_lowercase().as_str() {
+        "openai" => Ok("openai"),
+        "ollama" => Ok("ollama"),
+        "localgpt" | "local_gpt" | "local-gpt" => Ok("local_gpt"),
+        "claude" | "anthropic" => Ok("claude"),
+        "gemini" => Ok("gemini"),
+        "translate" => Ok("translate"),
+        "speaker" => Ok("speaker"),
+        "asr" => Ok("asr"),
+        "remote" => Ok("remote"),
+        "webhooks" => Ok("webhooks"),
+        "calendar" => Ok("calendar"),
+        "schedule" => Ok("schedule"),
+        "mqtt" => Ok("mqtt"),
+        "share" => Ok("share"),
+        "logging" => Ok("logging"),
+        "network" => Ok("network"),
+        "queues" => Ok("queues"),
+        "acceleration" => Ok("acceleration"),
+        "rag" => Ok("rag"),
+        "notifications" => Ok("notifications"),
+        "updates" => Ok("updates"),
+        "time" => Ok("time"),
+        "pii" => Ok("pii"),
+        "wordfilter" | "word_filter" | "word-filter" => Ok("word_filter"),
+        "consent" => Ok("consent"),
+        "templates" => Ok("templates"),
+        "autostop" | "auto_stop" | "auto-stop" => Ok("auto_stop"),
+        "interpreter" => Ok("interpreter"),
+        "punctuation" => Ok("punctuation"),
+        "usage" => Ok("usage"),
+        other => Err(format!("unknown config section: {other}")),
+    }
+}
+
+fn parse_section<T: DeserializeOwned>(section: &str, value: Value) -> Result<T, String> {
+    serde_json::from_value(value).map_err(|err| format!("invalid {section} config: {err}"))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_changes;
+    use serde_json::json;
+
+    #[test]
+    fn merges_only_changed_fields_into_the_file() {
+        let mut file = json!({ "openai": { "chat_model": "gpt-4o" }, "asr": { "language": "ja" } });
+        let effective = json!({
+            "openai": { "api_key": "sk-from-env", "chat_model": "gpt-4o" },
+            "asr": { "language": "ja" },
+            "translate": { "provider": "ollama" }
+        });
+        let incoming = json!({
+            "openai": { "api_key": "sk-from-env", "chat_model": "gpt-4.1-mini" },
+            "asr": { "language": "ja" },
+            "claude": { "model": "claude-3-5-haiku-latest" }
+        });
+        merge_changes(&mut file, &effective, &incoming);
+        assert_eq!(
+            file,
+            json!({
+                "openai": { "chat_model": "gpt-4.1-mini" },
+                "asr": { "language": "ja" },
+                "claude": { "model": "claude-3-5-haiku-latest" }
+            })
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use serde_json::{Map, Value};
+
+pub const CURRENT_CONFIG_VERSION: u64 = 1;
+const VERSION_KEY: &str = "config_version";
+const CAMEL_CASE_SECTIONS: [&str; 6] = [
+    "openai",
+    "ollama",
+    "local_gpt",
+    "translate",
+    "speaker",
+    "asr",
+];
+
+type Migration = fn(&mut Map<String, Value>);
+
+// Each entry upgrades a config from `version - 1` to `version`.
+const MIGRATIONS: [(u64, Migration); 1] = [(1, migrate_to_v1)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOutcome {
+    pub from_version: u64,
+    pub to_version: u64,
+}
+
+pub fn config_version(value: &Value) -> u64 {
+    value
+        .get(VERSION_KEY)
+        .or_else(|| value.get("configVersion"))
+        .and_then(|field| field.as_u64())
+        .unwrap_or(0)
+}
+
+pub fn migrate_config(value: &mut Value) -> Result<Option<MigrationOutcome>, String> {
+    let from_version = config_version(value);
+    if from_version > CURRENT_CONFIG_VERSION {
+        return Err(format!(
+            "config version {from_version} is newer than supported version {CURRENT_CONFIG_VERSION}"
+        ));
+    }
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| "config root must be a JSON object".to_string())?;
+    if from_version == CURRENT_CONFIG_VERSION && !root.contains_key("configVersion") {
+        return Ok(None);
+    }
+
+    for (version, migration) in MIGRATIONS.iter() {
+        if *version > from_version {
+            migration(root);
+        }
+    }
+    root.remove("configVersion");
+    root.insert(VERSION_KEY.to_string(), Value::from(CURRENT_CONFIG_VERSION));
+
+    Ok(Some(MigrationOutcome {
+        from_version,
+        to_version: CURRENT_CONFIG_VERSION,
+    }))
+}
+
+fn migrate_to_v1(root: &mut Map<String, Value>) {
+    for legacy in ["localGpt", "local-gpt"] {
+        rename_key(root, legacy, "local_gpt");
+    }
+
+    for section in CAMEL_CASE_SECTIONS {
+        let Some(Value::Object(fields)) = root.get_mut(section) else {
+            continue;
+        };
+        let legacy_keys = fields
+            .keys()
+            .filter(|key| key.contains('_') || key.contains('-'))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in legacy_keys {
+            let camel = to_camel_case(&key);
+            rename_key(fields, &key, &camel);
+        }
+    }
+
+    if !root.contains_key("translate") {
+        root.insert(
+            "translate".to_string(),
+            serde_json::json!({
+                "enabled": true,
+                "provider": "ollama",
+                "targetLanguage": "zh"
+            }),
+        );
+    }
+}
+
+fn rename_key(map: &mut Map<String, Value>, from: &str, to: &str) {
+    if from == to {
+        return;
+    }
+    let Some(value) = map.remove(from) else {
+        return;
+    };
+    // An explicit value under the new name always wins over the legacy spelling.
+    map.entry(to.to_string()).or_insert(value);
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut output = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for ch in key.chars() {
+        if ch == '_' || ch == '-' {
+            upper_next = !output.is_empty();
+            continue;
+        }
+        if upper_next {
+            output.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{config_version, migrate_config, CURRENT_CONFIG_VERSION};
+    use serde_json::json;
+
+    #[test]
+    fn migrates_legacy_keys() {
+        let mut value = json!({
+            "openai": { "api_key": "sk-test", "chatModel": "gpt-4.1-mini" },
+            "local-gpt": { "project-id": "p1", "base_url": "http://127.0.0.1:8787" }
+        });
+        let outcome = migrate_config(&mut value).unwrap().unwrap();
+        assert_eq!(outcome.from_version, 0);
+        assert_eq!(config_version(&value), CURRENT_CONFIG_VERSION);
+        assert_eq!(value["openai"]["apiKey"], "sk-test");
+        assert_eq!(value["local_gpt"]["projectId"], "p1");
+        assert_eq!(value["local_gpt"]["baseUrl"], "http://127.0.0.1:8787");
+        assert_eq!(value["translate"]["provider"], "ollama");
+    }
+
+    #[test]
+    fn current_version_is_untouched() {
+        let mut value = json!({
+            "config_version": CURRENT_CONFIG_VERSION,
+            "openai": { "apiKey": "sk-test" }
+        });
+        assert!(migrate_config(&mut value).unwrap().is_none());
+        assert!(value.get("translate").is_none());
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let mut value = json!({ "config_version": CURRENT_CONFIG_VERSION + 1 });
+        assert!(migrate_config(&mut value).is_err());
+    }
+}
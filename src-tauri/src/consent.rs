@@ -0,0 +1,184 @@
+use crate::app_config::{load_config, ConsentConfig};
+use crate::defaults::{
+    DEFAULT_CONSENT_ANNOUNCEMENT, DEFAULT_CONSENT_INDICATOR, DEFAULT_CONSENT_SPEECH_TEXT,
+};
+use serde::{Deserialize, Serialize};
+use std::process::{Command, Stdio};
+use std::thread;
+use tracing::{info, warn};
+
+#[cfg(windows)]
+const BEEP_HZ: u32 = 880;
+#[cfg(windows)]
+const BEEP_MS: u32 = 250;
+
+pub fn is_valid_announcement(raw: &str) -> bool {
+    matches!(
+        raw.trim().to_lowercase().as_str(),
+        "none" | "beep" | "speech"
+    )
+}
+
+// Stored with the session, so an exported session shows how participants were told.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingConsent {
+    // `beep`, `speech` or `none`.
+    pub announcement: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speech_text: Option<String>,
+    pub indicator: bool,
+    pub notified_at: String,
+}
+
+// `None` when neither an announcement nor the indicator is configured.
+pub fn for_new_session(now: &str) -> Option<RecordingConsent> {
+    let config = load_config()
+        .ok()
+        .and_then(|config| config.consent)
+        .unwrap_or_default();
+    from_settings(&config, now)
+}
+
+pub(crate) fn from_settings(config: &ConsentConfig, now: &str) -> Option<RecordingConsent> {
+    let announcement = config
+        .announcement
+        .as_deref()
+        .unwrap_or(DEFAULT_CONSENT_ANNOUNCEMENT)
+        .trim()
+        .to_lowercase();
+    let announcement = if is_valid_announcement(&announcement) {
+        announcement
+    } else {
+        "none".to_string()
+    };
+    let indicator = config.indicator.unwrap_or(DEFAULT_CONSENT_INDICATOR);
+    if announcement == "none" && !indicator {
+        return None;
+    }
+    let speech_text = (announcement == "speech").then(|| {
+        config
+            .speech_text
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CONSENT_SPEECH_TEXT.to_string())
+    });
+    Some(RecordingConsent {
+        announcement,
+        speech_text,
+        indicator,
+        notified_at: now.to_string(),
+    })
+}
+
+// Plays on the default output device, so the loopback capture records it as well.
+pub fn announce(consent: &RecordingConsent) {
+    let consent = consent.clone();
+    thread::spawn(move || {
+        let result = match consent.announcement.as_str() {
+            "beep" => beep(),
+            "speech" => speak(consent.speech_text.as_deref().unwrap_or_default()),
+            _ => return,
+        };
+        match result {
+            Ok(()) => info!("[consent] {} announcement played", consent.announcement),
+            Err(err) => warn!("[consent] announcement failed: {err}"),
+        }
+    });
+}
+
+#[cfg(windows)]
+fn beep() -> Result<(), String> {
+    for _ in 0..2 {
+        unsafe { windows::Win32::System::Diagnostics::Debug::Beep(BEEP_HZ, BEEP_MS) }
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn beep() -> Result<(), String> {
+    Err("beep announcements are only available on Windows".to_string())
+}
+
+#[cfg(windows)]
+fn speak(text: &str) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // The text goes through the environment so it never has to be quoted into the script.
+    let status = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:AI_CONSENT_TEXT)",
+        ])
+        .env("AI_CONSENT_TEXT", text)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()
+        .map_err(|err| err.to_string())?;
+    if !status.success() {
+        return Err(format!("speech synthesis exited with {status}"));
+    }
+    Ok(())
+}
+
+// `say` on macOS, speech-dispatcher elsewhere.
+#[cfg(not(windows))]
+fn speak(text: &str) -> Result<(), String> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("say")
+    } else {
+        let mut command = Command::new("spd-say");
+        command.arg("--wait");
+        command
+    };
+    let status = command
+        .arg(text)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| err.to_string())?;
+    if !status.success() {
+        return Err(format!("speech synthesis exited with {status}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_settings;
+    use crate::app_config::ConsentConfig;
+
+    #[test]
+    fn records_the_configured_notice() {
+        let now = "2025-01-20T10:00:00.000+09:00";
+        let consent = from_settings(&ConsentConfig::default(), now).unwrap();
+        assert_eq!(consent.announcement, "none");
+        assert!(consent.indicator);
+
+        let consent = from_settings(
+            &ConsentConfig {
+                announcement: Some("Speech".to_string()),
+                speech_text: None,
+                indicator: Some(false),
+            },
+            now,
+        )
+        .unwrap();
+        assert_eq!(consent.announcement, "speech");
+        assert!(consent.speech_text.is_some_and(|text| !text.is_empty()));
+
+        let silent = ConsentConfig {
+            announcement: Some("none".to_string()),
+            speech_text: None,
+            indicator: Some(false),
+        };
+        assert!(from_settings(&silent, now).is_none());
+    }
+}
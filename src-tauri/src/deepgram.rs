@@ -0,0 +1,170 @@
+// Deepgram pre-recorded transcription. The segment WAV is uploaded as is; with diarization every
+// word comes back timed and labelled with a speaker, so speakers are known without the local
+// ONNX model.
+
+use crate::app_config::AsrConfig;
+use crate::defaults::{
+    DEFAULT_DEEPGRAM_BASE_URL, DEFAULT_DEEPGRAM_DIARIZE, DEFAULT_DEEPGRAM_MODEL,
+    DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT,
+};
+use crate::http;
+use crate::redact::redact;
+use crate::transcribe::{TranscriptWord, Transcription};
+use serde_json::Value;
+use std::path::Path;
+use url::Url;
+
+pub async fn transcribe_with_deepgram(
+    path: &Path,
+    config: &AsrConfig,
+) -> Result<Transcription, String> {
+    let api_key = config
+        .deepgram_api_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "asr.deepgramApiKey is required".to_string())?;
+    let url = listen_url(config)?;
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+
+    let client = http::client(Some(DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT))?;
+    let response = client
+        .post(url)
+        .header("Authorization", format!("Token {api_key}"))
+        .header("Content-Type", "audio/wav")
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|err| err.to_string())?;
+    if !status.is_success() {
+        return Err(redact(&text).into_owned());
+    }
+    let value: Value = serde_json::from_str(&text).map_err(|err| err.to_string())?;
+    parse_response(&value)
+}
+
+fn listen_url(config: &AsrConfig) -> Result<Url, String> {
+    let base_url = config
+        .deepgram_base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_DEEPGRAM_BASE_URL);
+    let model = config
+        .deepgram_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_DEEPGRAM_MODEL);
+    let diarize = config.deepgram_diarize.unwrap_or(DEFAULT_DEEPGRAM_DIARIZE);
+    let mut url = Url::parse(base_url).map_err(|err| err.to_string())?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("model", model)
+            .append_pair("smart_format", "true")
+            .append_pair("diarize", if diarize { "true" } else { "false" });
+        match config
+            .language
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && *value != "auto")
+        {
+            Some(language) => query.append_pair("language", language),
+            None => query.append_pair("detect_language", "true"),
+        };
+    }
+    Ok(url)
+}
+
+// Deepgram numbers speakers from 0 within one request; they are shifted to start at 1 like the
+// local speaker ids.
+fn parse_response(value: &Value) -> Result<Transcription, String> {
+    let alternative = value
+        .pointer("/results/channels/0/alternatives/0")
+        .ok_or_else(|| "deepgram returned no transcript".to_string())?;
+    let text = alternative
+        .get("transcript")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if text.is_empty() {
+        return Err("deepgram returned empty text".to_string());
+    }
+    let seconds_to_ms = |word: &Value, key: &str| {
+        word.get(key)
+            .and_then(Value::as_f64)
+            .map(|seconds| (seconds.max(0.0) * 1000.0).round() as u64)
+            .unwrap_or(0)
+    };
+    let words = alternative
+        .get("words")
+        .and_then(Value::as_array)
+        .map(|words| {
+            words
+                .iter()
+                .filter_map(|word| {
+                    let text = word
+                        .get("punctuated_word")
+                        .or_else(|| word.get("word"))
+                        .and_then(Value::as_str)?;
+                    Some(TranscriptWord {
+                        word: text.to_string(),
+                        start_ms: seconds_to_ms(word, "start"),
+                        end_ms: seconds_to_ms(word, "end"),
+                        speaker: word
+                            .get("speaker")
+                            .and_then(Value::as_u64)
+                            .map(|speaker| speaker as u32 + 1),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty());
+    Ok(Transcription {
+        text,
+        words,
+        english: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_response;
+
+    #[test]
+    fn maps_diarized_words_to_speakers_and_switches() {
+        let value = serde_json::json!({
+            "results": { "channels": [{ "alternatives": [{
+                "transcript": "Shall we start? Yes, go ahead.",
+                "words": [
+                    { "word": "shall", "punctuated_word": "Shall", "start": 0.08, "end": 0.3, "speaker": 0 },
+                    { "word": "we", "punctuated_word": "we", "start": 0.3, "end": 0.42, "speaker": 0 },
+                    { "word": "start", "punctuated_word": "start?", "start": 0.42, "end": 0.9, "speaker": 0 },
+                    { "word": "yes", "punctuated_word": "Yes,", "start": 1.4, "end": 1.6, "speaker": 1 },
+                    { "word": "go", "punctuated_word": "go", "start": 1.6, "end": 1.75, "speaker": 1 },
+                    { "word": "ahead", "punctuated_word": "ahead.", "start": 1.75, "end": 2.1, "speaker": 1 }
+                ]
+            }]}]}
+        });
+        let transcription = parse_response(&value).unwrap();
+        assert_eq!(transcription.text, "Shall we start? Yes, go ahead.");
+        let words = transcription.words.as_ref().unwrap();
+        assert_eq!(
+            (words[2].word.as_str(), words[2].start_ms, words[2].end_ms),
+            ("start?", 420, 900)
+        );
+        // The first speaker talks for 820ms against 700ms.
+        assert_eq!(transcription.speaker(), Some(1));
+        assert_eq!(transcription.speaker_switches_ms(), [1_400]);
+
+        let empty = serde_json::json!({
+            "results": { "channels": [{ "alternatives": [{ "transcript": " " }] }] }
+        });
+        assert!(parse_response(&empty).is_err());
+    }
+}
@@ -0,0 +1,407 @@
+use serde_json::{json, Map, Value};
+
+pub const ENV_OVERRIDE_PREFIX: &str = "AI_INTERVIEW__";
+
+pub const DEFAULT_OPENAI_TRANSCRIBE_MODEL: &str = "whisper-1";
+// `/audio/translations` only accepts whisper-1.
+pub const DEFAULT_OPENAI_TRANSLATION_MODEL: &str = "whisper-1";
+pub const DEFAULT_OPENAI_TRANSCRIBE_BASE_URL: &str =
+    "https://api.openai.com/v1/audio/transcriptions";
+pub const DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT: u64 = 300;
+pub const DEFAULT_OPENAI_RESPONSE_FORMAT: &str = "json";
+pub const DEFAULT_OPENAI_CHAT_MODEL: &str = "gpt-4.1-mini";
+pub const DEFAULT_OPENAI_CHAT_BASE_URL: &str = "https://api.openai.com/v1/responses";
+pub const DEFAULT_OPENAI_CHAT_TIMEOUT: u64 = 120;
+
+pub const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OLLAMA_TIMEOUT: u64 = 600;
+pub const DEFAULT_OLLAMA_MODEL: &str = "gpt-oss:20b";
+
+pub const DEFAULT_CLAUDE_BASE_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const DEFAULT_CLAUDE_MODEL: &str = "claude-3-5-haiku-latest";
+pub const DEFAULT_CLAUDE_TIMEOUT: u64 = 120;
+// The messages API requires a reply cap.
+pub const DEFAULT_CLAUDE_MAX_TOKENS: u32 = 4096;
+pub const DEFAULT_CLAUDE_API_VERSION: &str = "2023-06-01";
+
+pub const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+pub const DEFAULT_GEMINI_MODEL: &str = "gemini-2.0-flash";
+pub const DEFAULT_GEMINI_TIMEOUT: u64 = 120;
+
+// US dollars per million input and output tokens for the default cloud models; Ollama and Local
+// GPT run locally and cost nothing. `usage.prices` overrides these and prices other models.
+pub const DEFAULT_TOKEN_PRICES: &[(&str, f64, f64)] = &[
+    ("openai:gpt-4.1-mini", 0.40, 1.60),
+    ("claude:claude-3-5-haiku-latest", 0.80, 4.00),
+    ("gemini:gemini-2.0-flash", 0.10, 0.40),
+];
+
+pub const DEFAULT_LOCAL_GPT_BASE_URL: &str = "http://127.0.0.1:8787";
+pub const DEFAULT_LOCAL_GPT_TIMEOUT: u64 = 240;
+pub const DEFAULT_LOCAL_GPT_DIRECT_PATH: &str = "/local-gpt-sse/direct";
+pub const DEFAULT_LOCAL_GPT_PROJECT_ID: &str = "g-p-698c11cf2bc08191b07e28128883fcbb-testapi";
+
+pub const DEFAULT_TRANSLATE_PROVIDER: &str = "ollama";
+pub const DEFAULT_TRANSLATE_TARGET_LANGUAGE: &str = "zh";
+pub const DEFAULT_SEGMENT_TRANSLATE_BATCH_SIZE: usize = 1;
+pub const DEFAULT_SEGMENT_TRANSLATE_STREAM: bool = false;
+pub const DEFAULT_TRANSLATION_CACHE_ENABLED: bool = true;
+pub const DEFAULT_TRANSLATION_CACHE_SIZE: usize = 2000;
+pub const DEFAULT_LIVE_PROMPT: &str =
+    "Translate the following text to {target_language}. Output only the translated text.";
+pub const DEFAULT_SEGMENT_SINGLE_PROMPT: &str =
+    "Translate the following text to {target_language}. Output only the translated text.";
+pub const DEFAULT_SEGMENT_BATCH_PROMPT: &str = "You rewrite noisy ASR text and translate it.\n\
+For each item in `items`:\n\
+1) rewrite `text` into readable text in the same language as input and return as `cleaned_source`;\n\
+2) translate `cleaned_source` to {target_language} and return as `translation`.\n\
+Use `context` only as previous conversation context.\n\
+Return ONLY JSON array.\n\
+Each element must be {\"id\": string, \"cleaned_source\": string, \"translation\": string}.\n\
+Return exactly one element for every id in `items`.";
+
+pub const DEFAULT_WHISPER_SERVER_URL: &str = "http://127.0.0.1:8080/inference";
+pub const DEFAULT_WHISPER_SERVER_RESPONSE_FORMAT: &str = "text";
+pub const DEFAULT_WHISPER_SERVER_TEMPERATURE: &str = "0";
+pub const DEFAULT_WHISPER_PIPE_WORKERS: usize = 2;
+pub const DEFAULT_WHISPER_CONTEXT_ENABLED: bool = true;
+pub const DEFAULT_WHISPER_CONTEXT_MAX_CHARS: usize = 100;
+pub const DEFAULT_WHISPER_CONTEXT_SHORT_SEGMENT_MS: u64 = 2500;
+pub const DEFAULT_WHISPER_CONTEXT_BOUNDARY_GAP_MS: u64 = 1200;
+pub const DEFAULT_WHISPER_CONTEXT_RESET_SILENCE_MS: u64 = 4000;
+pub const DEFAULT_WHISPER_MODEL_BASE_URL: &str =
+    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+pub const DEFAULT_DEEPGRAM_BASE_URL: &str = "https://api.deepgram.com/v1/listen";
+pub const DEFAULT_DEEPGRAM_MODEL: &str = "nova-2";
+pub const DEFAULT_DEEPGRAM_DIARIZE: bool = true;
+pub const DEFAULT_TWO_PASS_ENABLED: bool = false;
+pub const DEFAULT_TWO_PASS_MODEL: &str = "large-v3-turbo";
+pub const DEFAULT_TWO_PASS_RETRANSLATE: bool = true;
+pub const DEFAULT_OFFLINE_FALLBACK_ENABLED: bool = true;
+pub const DEFAULT_OFFLINE_FALLBACK_MODEL: &str = "tiny";
+pub const DEFAULT_ASR_TRANSLATE_TO_ENGLISH: bool = false;
+pub const DEFAULT_ASR_LIVE_PROVIDER: &str = "window";
+pub const DEFAULT_REALTIME_TRANSCRIBE_MODEL: &str = "gpt-4o-transcribe";
+pub const DEFAULT_OPENAI_REALTIME_URL: &str =
+    "wss://api.openai.com/v1/realtime?intent=transcription";
+pub const DEFAULT_VAD_MODEL_PATH: &str = "resources/models/silero_vad.onnx";
+pub const DEFAULT_VAD_THRESHOLD: f32 = 0.5;
+
+pub const DEFAULT_SPEAKER_MODEL_PATH: &str = "resources/models/pyannote_embedding.onnx";
+pub const DEFAULT_SPEAKER_NEW_THRESHOLD: f32 = 0.75;
+pub const DEFAULT_SPEAKER_UPDATE_THRESHOLD: f32 = 0.80;
+pub const DEFAULT_SPEAKER_MAX_SPEAKERS: u32 = 8;
+pub const DEFAULT_SPEAKER_WINDOW_MS: u64 = 2_000;
+pub const DEFAULT_SPEAKER_STEP_MS: u64 = 1_000;
+pub const DEFAULT_SPEAKER_MIN_RMS_DB: f32 = -45.0;
+pub const DEFAULT_SPEAKER_CONSECUTIVE_HITS: u32 = 3;
+pub const DEFAULT_SPEAKER_MIN_GAP_MS: u64 = 3_000;
+
+pub const DEFAULT_REMOTE_BIND_ADDRESS: &str = "127.0.0.1";
+pub const DEFAULT_REMOTE_PORT: u16 = 8765;
+
+pub const DEFAULT_CALENDAR_EARLY_MINUTES: u32 = 10;
+
+pub const DEFAULT_SCHEDULE_ENABLED: bool = false;
+pub const DEFAULT_SCHEDULE_AUTO_START: bool = true;
+pub const DEFAULT_SCHEDULE_AUTO_STOP: bool = true;
+pub const DEFAULT_SCHEDULE_USE_CALENDAR: bool = false;
+
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+pub const DEFAULT_MQTT_CLIENT_ID: &str = "ai-shepherd";
+pub const DEFAULT_MQTT_TOPIC_PREFIX: &str = "ai-shepherd";
+
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+pub const DEFAULT_LOG_MAX_FILES: usize = 7;
+pub const DEFAULT_RECENT_LOG_LIMIT: usize = 200;
+pub const RECENT_LOG_CAPACITY: usize = 2_000;
+
+pub const DEFAULT_NO_PROXY: &str = "localhost,127.0.0.1,::1";
+
+pub const DEFAULT_TRANSCRIBE_QUEUE_CAPACITY: usize = 64;
+pub const DEFAULT_VAD_QUEUE_CAPACITY: usize = 64;
+pub const DEFAULT_TRANSLATE_QUEUE_CAPACITY: usize = 128;
+pub const DEFAULT_TRANSLATE_QUEUE_OVERFLOW: &str = "dropOldest";
+// Window tasks are speaker-diarization snapshots; only the newest one matters.
+pub const DEFAULT_WINDOW_QUEUE_CAPACITY: usize = 2;
+pub const DEFAULT_WINDOW_QUEUE_OVERFLOW: &str = "coalesce";
+pub const DEFAULT_REFINE_QUEUE_CAPACITY: usize = 256;
+pub const DEFAULT_REFINE_QUEUE_OVERFLOW: &str = "dropOldest";
+// Longer than the slowest provider timeout, so only a request that ignores it counts as stuck.
+pub const DEFAULT_STUCK_WORKER_SECS: u64 = 900;
+// Finalized segments waiting for transcription before live windows are skipped.
+pub const DEFAULT_WINDOW_SHED_DEPTH: usize = 2;
+
+pub const DEFAULT_ACCELERATION_PROVIDER: &str = "auto";
+
+pub const DEFAULT_RAG_WARM_UP: bool = true;
+
+pub const DEFAULT_NOTIFICATIONS_ENABLED: bool = true;
+pub const DEFAULT_NOTIFICATIONS_ONLY_WHEN_UNFOCUSED: bool = true;
+
+pub const DEFAULT_UPDATES_ENABLED: bool = false;
+pub const DEFAULT_UPDATE_CHECK_INTERVAL_HOURS: u64 = 24;
+
+pub const DEFAULT_TIME_ZONE: &str = "local";
+pub const DEFAULT_TIME_LOCALE: &str = "zh";
+pub const DEFAULT_TIME_RELATIVE: bool = false;
+
+pub const DEFAULT_PII_ENABLED: bool = false;
+pub const DEFAULT_PII_EMAILS: bool = true;
+pub const DEFAULT_PII_PHONES: bool = true;
+pub const DEFAULT_PII_LLM: bool = false;
+
+pub const DEFAULT_WORD_FILTER_ENABLED: bool = false;
+pub const DEFAULT_WORD_FILTER_MODE: &str = "mask";
+pub const DEFAULT_WORD_FILTER_REPLACEMENT: &str = "***";
+
+pub const DEFAULT_PUNCTUATION_ENABLED: bool = false;
+pub const DEFAULT_PUNCTUATION_MODE: &str = "rules";
+
+pub const DEFAULT_CONSENT_ANNOUNCEMENT: &str = "none";
+pub const DEFAULT_CONSENT_SPEECH_TEXT: &str = "本次会议正在录音并转写。";
+pub const DEFAULT_CONSENT_INDICATOR: bool = true;
+
+pub const DEFAULT_AUTO_STOP_ENABLED: bool = false;
+pub const DEFAULT_AUTO_STOP_SILENCE_MINUTES: u64 = 10;
+pub const DEFAULT_AUTO_STOP_SUMMARIZE: bool = true;
+
+pub const DEFAULT_INTERPRETER_MAX_SEGMENT_MS: u64 = 3_000;
+pub const DEFAULT_INTERPRETER_WHISPER_MODEL_PATH: &str = "resources/models/ggml-tiny.bin";
+pub const DEFAULT_INTERPRETER_TRANSLATE_PROVIDER: &str = "ollama";
+
+// Mirrors the on-disk layout so callers can diff a config file against it key by key.
+pub fn default_config_value() -> Value {
+    let mut asr = serde_json::to_value(crate::app_config::AsrConfig::default())
+        .unwrap_or_else(|_| Value::Object(Map::new()));
+    if let Value::Object(fields) = &mut asr {
+        fields.retain(|_, value| !value.is_null());
+        fields.insert(
+            "whisperServerUrl".to_string(),
+            Value::from(DEFAULT_WHISPER_SERVER_URL),
+        );
+    }
+    json!({
+        "openai": {
+            "model": DEFAULT_OPENAI_TRANSCRIBE_MODEL,
+            "baseUrl": DEFAULT_OPENAI_TRANSCRIBE_BASE_URL,
+            "timeoutSecs": DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT,
+            "responseFormat": DEFAULT_OPENAI_RESPONSE_FORMAT,
+            "chatModel": DEFAULT_OPENAI_CHAT_MODEL,
+            "chatBaseUrl": DEFAULT_OPENAI_CHAT_BASE_URL,
+            "chatTimeoutSecs": DEFAULT_OPENAI_CHAT_TIMEOUT,
+        },
+        "ollama": {
+            "enabled": true,
+            "model": DEFAULT_OLLAMA_MODEL,
+            "baseUrl": DEFAULT_OLLAMA_BASE_URL,
+            "timeoutSecs": DEFAULT_OLLAMA_TIMEOUT,
+        },
+        "claude": {
+            "model": DEFAULT_CLAUDE_MODEL,
+            "baseUrl": DEFAULT_CLAUDE_BASE_URL,
+            "timeoutSecs": DEFAULT_CLAUDE_TIMEOUT,
+            "maxTokens": DEFAULT_CLAUDE_MAX_TOKENS,
+        },
+        "gemini": {
+            "model": DEFAULT_GEMINI_MODEL,
+            "baseUrl": DEFAULT_GEMINI_BASE_URL,
+            "timeoutSecs": DEFAULT_GEMINI_TIMEOUT,
+        },
+        "local_gpt": {
+            "enabled": true,
+            "baseUrl": DEFAULT_LOCAL_GPT_BASE_URL,
+            "timeoutSecs": DEFAULT_LOCAL_GPT_TIMEOUT,
+            "projectId": DEFAULT_LOCAL_GPT_PROJECT_ID,
+        },
+        "translate": {
+            "enabled": true,
+            "provider": DEFAULT_TRANSLATE_PROVIDER,
+            "targetLanguage": DEFAULT_TRANSLATE_TARGET_LANGUAGE,
+            "segmentBatchSize": DEFAULT_SEGMENT_TRANSLATE_BATCH_SIZE,
+            "streamSegments": DEFAULT_SEGMENT_TRANSLATE_STREAM,
+            "segmentSinglePrompt": DEFAULT_SEGMENT_SINGLE_PROMPT,
+            "segmentBatchPrompt": DEFAULT_SEGMENT_BATCH_PROMPT,
+            "livePrompt": DEFAULT_LIVE_PROMPT,
+            "cacheEnabled": DEFAULT_TRANSLATION_CACHE_ENABLED,
+            "cacheSize": DEFAULT_TRANSLATION_CACHE_SIZE,
+        },
+        "speaker": {
+            "modelPath": DEFAULT_SPEAKER_MODEL_PATH,
+            "similarityThreshold": DEFAULT_SPEAKER_NEW_THRESHOLD,
+            "updateThreshold": DEFAULT_SPEAKER_UPDATE_THRESHOLD,
+            "maxSpeakers": DEFAULT_SPEAKER_MAX_SPEAKERS,
+            "windowMs": DEFAULT_SPEAKER_WINDOW_MS,
+            "hopMs": DEFAULT_SPEAKER_STEP_MS,
+            "minGapMs": DEFAULT_SPEAKER_MIN_GAP_MS,
+            "consecutiveHits": DEFAULT_SPEAKER_CONSECUTIVE_HITS,
+            "minRmsDb": DEFAULT_SPEAKER_MIN_RMS_DB,
+        },
+        "asr": asr,
+        "remote": {
+            "enabled": false,
+            "bindAddress": DEFAULT_REMOTE_BIND_ADDRESS,
+            "port": DEFAULT_REMOTE_PORT,
+            "apiEnabled": false,
+        },
+        "calendar": {
+            "enabled": false,
+            "earlyMinutes": DEFAULT_CALENDAR_EARLY_MINUTES,
+        },
+        "schedule": {
+            "enabled": DEFAULT_SCHEDULE_ENABLED,
+            "autoStart": DEFAULT_SCHEDULE_AUTO_START,
+            "autoStop": DEFAULT_SCHEDULE_AUTO_STOP,
+            "useCalendar": DEFAULT_SCHEDULE_USE_CALENDAR,
+        },
+        "mqtt": {
+            "enabled": false,
+            "port": DEFAULT_MQTT_PORT,
+            "clientId": DEFAULT_MQTT_CLIENT_ID,
+            "topicPrefix": DEFAULT_MQTT_TOPIC_PREFIX,
+            "qos": 0,
+            "retain": false,
+        },
+        "logging": {
+            "level": DEFAULT_LOG_LEVEL,
+            "maxFiles": DEFAULT_LOG_MAX_FILES,
+        },
+        "network": {
+            "noProxy": DEFAULT_NO_PROXY,
+        },
+        "queues": {
+            "transcribe": {
+                "capacity": DEFAULT_TRANSCRIBE_QUEUE_CAPACITY,
+            },
+            "vad": {
+                "capacity": DEFAULT_VAD_QUEUE_CAPACITY,
+            },
+            "translate": {
+                "capacity": DEFAULT_TRANSLATE_QUEUE_CAPACITY,
+                "overflow": DEFAULT_TRANSLATE_QUEUE_OVERFLOW,
+            },
+            "window": {
+                "capacity": DEFAULT_WINDOW_QUEUE_CAPACITY,
+                "overflow": DEFAULT_WINDOW_QUEUE_OVERFLOW,
+            },
+            "refine": {
+                "capacity": DEFAULT_REFINE_QUEUE_CAPACITY,
+                "overflow": DEFAULT_REFINE_QUEUE_OVERFLOW,
+            },
+            "stuckWorkerSecs": DEFAULT_STUCK_WORKER_SECS,
+            "windowShedDepth": DEFAULT_WINDOW_SHED_DEPTH,
+        },
+        "acceleration": {
+            "provider": DEFAULT_ACCELERATION_PROVIDER,
+            "deviceId": 0,
+        },
+        "rag": {
+            "warmUp": DEFAULT_RAG_WARM_UP,
+        },
+        "notifications": {
+            "enabled": DEFAULT_NOTIFICATIONS_ENABLED,
+            "onlyWhenUnfocused": DEFAULT_NOTIFICATIONS_ONLY_WHEN_UNFOCUSED,
+        },
+        "updates": {
+            "enabled": DEFAULT_UPDATES_ENABLED,
+            "checkIntervalHours": DEFAULT_UPDATE_CHECK_INTERVAL_HOURS,
+        },
+        "time": {
+            "timezone": DEFAULT_TIME_ZONE,
+            "locale": DEFAULT_TIME_LOCALE,
+            "relative": DEFAULT_TIME_RELATIVE,
+        },
+        "pii": {
+            "enabled": DEFAULT_PII_ENABLED,
+            "emails": DEFAULT_PII_EMAILS,
+            "phones": DEFAULT_PII_PHONES,
+            "llm": DEFAULT_PII_LLM,
+        },
+        "word_filter": {
+            "enabled": DEFAULT_WORD_FILTER_ENABLED,
+            "mode": DEFAULT_WORD_FILTER_MODE,
+            "replacement": DEFAULT_WORD_FILTER_REPLACEMENT,
+        },
+        "punctuation": {
+            "enabled": DEFAULT_PUNCTUATION_ENABLED,
+            "mode": DEFAULT_PUNCTUATION_MODE,
+        },
+        "consent": {
+            "announcement": DEFAULT_CONSENT_ANNOUNCEMENT,
+            "speechText": DEFAULT_CONSENT_SPEECH_TEXT,
+            "indicator": DEFAULT_CONSENT_INDICATOR,
+        },
+        "auto_stop": {
+            "enabled": DEFAULT_AUTO_STOP_ENABLED,
+            "silenceMinutes": DEFAULT_AUTO_STOP_SILENCE_MINUTES,
+            "summarize": DEFAULT_AUTO_STOP_SUMMARIZE,
+        },
+        "interpreter": {
+            "maxSegmentMs": DEFAULT_INTERPRETER_MAX_SEGMENT_MS,
+            "whisperModelPath": DEFAULT_INTERPRETER_WHISPER_MODEL_PATH,
+            "translateProvider": DEFAULT_INTERPRETER_TRANSLATE_PROVIDER,
+        },
+    })
+}
+
+// `AI_INTERVIEW__OPENAI__CHAT_MODEL=gpt-4o` overrides `openai.chatModel`.
+pub fn env_overrides() -> Vec<(String, String, Value)> {
+    let defaults = default_config_value();
+    let mut overrides = std::env::vars()
+        .filter_map(|(name, raw)| {
+            let rest = name.strip_prefix(ENV_OVERRIDE_PREFIX)?;
+            let (section, key) = rest.split_once("__")?;
+            let section = section.to_lowercase();
+            let key = to_camel_case(key);
+            if section.is_empty() || key.is_empty() {
+                return None;
+            }
+            let value = parse_env_value(&raw, defaults.get(&section).and_then(|s| s.get(&key)));
+            Some((section, key, value))
+        })
+        .collect::<Vec<_>>();
+    overrides.sort_by(|left, right| (&left.0, &left.1).cmp(&(&right.0, &right.1)));
+    overrides
+}
+
+pub fn apply_env_overrides(value: &mut Value) {
+    let Some(root) = value.as_object_mut() else {
+        return;
+    };
+    for (section, key, override_value) in env_overrides() {
+        let entry = root
+            .entry(section)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(fields) = entry {
+            fields.insert(key, override_value);
+        }
+    }
+}
+
+fn parse_env_value(raw: &str, default: Option<&Value>) -> Value {
+    if matches!(default, Some(Value::String(_))) {
+        return Value::String(raw.to_string());
+    }
+    match serde_json::from_str::<Value>(raw.trim()) {
+        Ok(value @ (Value::Bool(_) | Value::Number(_))) => value,
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut output = String::with_capacity(key.len());
+    let mut upper_next = false;
+    for ch in key.chars() {
+        if ch == '_' {
+            upper_next = !output.is_empty();
+            continue;
+        }
+        if upper_next {
+            output.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            output.extend(ch.to_lowercase());
+        }
+    }
+    output
+}
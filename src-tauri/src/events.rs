@@ -0,0 +1,112 @@
+use crate::redact::redact_value;
+use crate::timestamps::now_rfc3339;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+const OUTPUT_LABEL: &str = "output";
+const EVENT_CHANNEL_CAPACITY: usize = 512;
+
+pub const SESSION_ENDED: &str = "session_ended";
+pub const SUMMARY_READY: &str = "summary_ready";
+pub const ACTION_ITEMS_EXTRACTED: &str = "action_items_extracted";
+pub const RAG_SYNC_FINISHED: &str = "rag_sync_finished";
+pub const UPDATE_AVAILABLE: &str = "update_available";
+pub const AUDIO_DEVICE_CHANGED: &str = "audio_device_changed";
+pub const CAPTURE_FAILED: &str = "capture_failed";
+pub const CAPTURE_DEVICE_CHANGED: &str = "capture_device_changed";
+pub const CAPTURE_PAUSED_CHANGED: &str = "capture_paused_changed";
+pub const CAPTURE_ARMED_CHANGED: &str = "capture_armed_changed";
+pub const SPEAKERS_REDIARIZED: &str = "speakers_rediarized";
+pub const SEGMENT_DELETED: &str = "segment_deleted";
+pub const WORKER_RESTARTED: &str = "worker_restarted";
+pub const AUDIO_LEVEL: &str = "audio_level";
+pub const SCHEDULED_MEETING_STARTED: &str = "scheduled_meeting_started";
+pub const SCHEDULED_MEETING_ENDED: &str = "scheduled_meeting_ended";
+pub const MEETING_APP_DETECTED: &str = "meeting_app_detected";
+pub const MEETING_APP_ENDED: &str = "meeting_app_ended";
+pub const AUDIO_ALERT: &str = "audio_alert";
+pub const REALTIME_TRANSCRIPT: &str = "realtime_transcript";
+pub const ASR_BACKEND_RESTARTED: &str = "asr_backend_restarted";
+pub const WHISPER_MODEL_DOWNLOADED: &str = "whisper_model_downloaded";
+pub const SESSION_RETRANSCRIBED: &str = "session_retranscribed";
+pub const ASR_OFFLINE_FALLBACK: &str = "asr_offline_fallback";
+pub const QUEUE_METRICS: &str = "queue_metrics";
+pub const TRANSCRIPTION_BACKLOG: &str = "transcription_backlog";
+pub const WHISPER_BENCHMARK_FINISHED: &str = "whisper_benchmark_finished";
+pub const SEGMENT_TRANSLATION_CHUNK: &str = "segment_translation_chunk";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiEvent {
+    pub event: String,
+    pub payload: Value,
+    pub timestamp: String,
+    // Set on events re-sent from a journal so integrations with side effects can skip them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub replayed: bool,
+}
+
+pub struct EventHub {
+    sender: broadcast::Sender<UiEvent>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<UiEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, event: &str, payload: Value) {
+        // No subscribers is the normal case; the send error only means nobody is listening.
+        let _ = self.sender.send(UiEvent {
+            event: event.to_string(),
+            payload,
+            timestamp: now_rfc3339(),
+            replayed: false,
+        });
+    }
+
+    fn replay(&self, event: UiEvent) {
+        let _ = self.sender.send(UiEvent {
+            replayed: true,
+            ..event
+        });
+    }
+}
+
+// Payloads are redacted first: error strings often embed raw HTTP bodies.
+pub fn emit_ui_event<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T) {
+    let Ok(mut value) = serde_json::to_value(&payload) else {
+        return;
+    };
+    redact_value(&mut value);
+    if let Some(hub) = app.try_state::<Arc<EventHub>>() {
+        hub.publish(event, value.clone());
+    }
+    if let Some(webview) = app.get_webview(OUTPUT_LABEL) {
+        let _ = webview.emit(event, value);
+    }
+}
+
+// High-rate UI-only updates such as the level meter skip the hub, so integrations and the session
+// journal never see them.
+pub fn emit_output_event<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T) {
+    if let Some(webview) = app.get_webview(OUTPUT_LABEL) {
+        let _ = webview.emit(event, payload);
+    }
+}
+
+pub fn replay_ui_event(app: &AppHandle, event: UiEvent) {
+    if let Some(webview) = app.get_webview(OUTPUT_LABEL) {
+        let _ = webview.emit(&event.event, event.payload.clone());
+    }
+    if let Some(hub) = app.try_state::<Arc<EventHub>>() {
+        hub.replay(event);
+    }
+}
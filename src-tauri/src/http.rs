@@ -0,0 +1,76 @@
+use crate::app_config::load_config;
+use crate::defaults::DEFAULT_NO_PROXY;
+use once_cell::sync::Lazy;
+use reqwest::{Client, NoProxy, Proxy};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    timeout_secs: Option<u64>,
+    proxy: Option<String>,
+    no_proxy: String,
+}
+
+// A reqwest Client owns its connection pool and TLS roots, so one per settings combination is kept
+// and cloned out instead of building a fresh client per request.
+static CLIENTS: Lazy<Mutex<HashMap<ClientKey, Client>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Proxy settings are re-read on every call so config edits apply to the next request.
+pub fn client(timeout_secs: Option<u64>) -> Result<Client, String> {
+    let network = load_config()
+        .ok()
+        .and_then(|cfg| cfg.network)
+        .unwrap_or_default();
+    let key = ClientKey {
+        timeout_secs,
+        proxy: network
+            .proxy
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()),
+        no_proxy: network
+            .no_proxy
+            .unwrap_or_else(|| DEFAULT_NO_PROXY.to_string()),
+    };
+    let mut clients = CLIENTS
+        .lock()
+        .map_err(|_| "http client registry poisoned".to_string())?;
+    if let Some(client) = clients.get(&key) {
+        return Ok(client.clone());
+    }
+    let client = build_client(&key)?;
+    clients.insert(key, client.clone());
+    Ok(client)
+}
+
+fn build_client(key: &ClientKey) -> Result<Client, String> {
+    let mut builder = Client::builder();
+    if let Some(timeout_secs) = key.timeout_secs {
+        builder = builder.timeout(Duration::from_secs(timeout_secs));
+    }
+    // Without an explicit proxy reqwest still honours HTTP_PROXY / HTTPS_PROXY from the environment.
+    if let Some(proxy) = &key.proxy {
+        let proxy = Proxy::all(proxy)
+            .map_err(|err| format!("invalid network.proxy: {err}"))?
+            .no_proxy(NoProxy::from_string(&key.no_proxy));
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_client, ClientKey};
+
+    #[test]
+    fn builds_proxied_clients_and_rejects_bad_proxies() {
+        let key = |proxy: &str| ClientKey {
+            timeout_secs: Some(5),
+            proxy: Some(proxy.to_string()),
+            no_proxy: "localhost,127.0.0.1".to_string(),
+        };
+        assert!(build_client(&key("http://proxy.local:3128")).is_ok());
+        assert!(build_client(&key("not a proxy url")).is_err());
+    }
+}
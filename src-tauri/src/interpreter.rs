@@ -0,0 +1,194 @@
+use crate::app_config::{load_file_config, AppConfig};
+use crate::defaults::{
+    DEFAULT_INTERPRETER_MAX_SEGMENT_MS, DEFAULT_INTERPRETER_TRANSLATE_PROVIDER,
+    DEFAULT_INTERPRETER_WHISPER_MODEL_PATH,
+};
+use crate::whisper_pipe::WhisperPipeManager;
+use crate::whisper_server::{resolve_path_with_context, WhisperServerManager};
+use crate::{normalize_translate_provider, selected_translate_provider, TranslateProviderState};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+// The running preset; `None` while interpreter mode is off.
+static ACTIVE: Lazy<RwLock<Option<Preset>>> = Lazy::new(|| RwLock::new(None));
+
+// Only these providers stream live translations token by token.
+const STREAMING_PROVIDERS: [&str; 4] = ["ollama", "openai", "claude", "gemini"];
+
+#[derive(Debug, Clone)]
+struct Preset {
+    max_segment_ms: u64,
+    // `None` when the small model is not installed; the regular model then stays loaded.
+    whisper_model_path: Option<String>,
+    // The selected provider the preset replaced, restored when the mode is switched off.
+    replaced_provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterpreterStatus {
+    pub enabled: bool,
+    pub max_segment_ms: Option<u64>,
+    pub whisper_model_path: Option<String>,
+    pub translate_provider: String,
+}
+
+pub fn is_enabled() -> bool {
+    active().is_some()
+}
+
+pub fn max_segment_ms() -> Option<u64> {
+    active().map(|preset| preset.max_segment_ms)
+}
+
+fn active() -> Option<Preset> {
+    ACTIVE.read().ok().and_then(|guard| guard.clone())
+}
+
+fn set_active(preset: Option<Preset>) {
+    if let Ok(mut guard) = ACTIVE.write() {
+        *guard = preset;
+    }
+}
+
+// Called from `load_config`, so ASR workers started while the mode is on load the small model.
+pub fn apply(config: &mut AppConfig) {
+    let Some(path) = active().and_then(|preset| preset.whisper_model_path) else {
+        return;
+    };
+    let mut asr = config.asr.clone().unwrap_or_default();
+    asr.whisper_cpp_model_path = Some(path);
+    config.asr = Some(asr);
+}
+
+// The provider to switch to, or `None` when the selected one already streams. A configured
+// provider that cannot stream either falls back to the default one.
+fn streaming_provider(selected: &str, configured: Option<&str>) -> Option<String> {
+    if STREAMING_PROVIDERS.contains(&selected) {
+        return None;
+    }
+    let provider = configured
+        .filter(|value| !value.trim().is_empty())
+        .map(normalize_translate_provider)
+        .filter(|provider| STREAMING_PROVIDERS.contains(&provider.as_str()))
+        .unwrap_or_else(|| DEFAULT_INTERPRETER_TRANSLATE_PROVIDER.to_string());
+    Some(provider)
+}
+
+fn status(app: &AppHandle) -> InterpreterStatus {
+    let preset = active();
+    InterpreterStatus {
+        enabled: preset.is_some(),
+        max_segment_ms: preset.as_ref().map(|preset| preset.max_segment_ms),
+        whisper_model_path: preset.and_then(|preset| preset.whisper_model_path),
+        translate_provider: selected_translate_provider(app),
+    }
+}
+
+fn set_provider(app: &AppHandle, provider: &str) {
+    if let Some(state) = app.try_state::<TranslateProviderState>() {
+        if let Ok(mut guard) = state.provider.lock() {
+            *guard = normalize_translate_provider(provider);
+        }
+    }
+}
+
+// Running whisper processes keep their model; they restart with the new one on the next segment.
+fn restart_asr_workers(app: &AppHandle) {
+    if let Some(manager) = app.try_state::<WhisperServerManager>() {
+        manager.stop();
+    }
+    if let Some(manager) = app.try_state::<WhisperPipeManager>() {
+        manager.stop();
+    }
+}
+
+fn enable(app: &AppHandle) -> Result<Preset, String> {
+    let interpreter = load_file_config()?.interpreter.unwrap_or_default();
+    let raw_model = interpreter
+        .whisper_model_path
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_INTERPRETER_WHISPER_MODEL_PATH.to_string());
+    let whisper_model_path =
+        resolve_path_with_context(app, &raw_model).map(|path| path.to_string_lossy().to_string());
+    if whisper_model_path.is_none() {
+        warn!("[interpreter] model not found: {raw_model}, keeping the current model");
+    }
+    let selected = selected_translate_provider(app);
+    let replaced_provider =
+        match streaming_provider(&selected, interpreter.translate_provider.as_deref()) {
+            Some(provider) => {
+                set_provider(app, &provider);
+                Some(selected)
+            }
+            None => None,
+        };
+    Ok(Preset {
+        max_segment_ms: interpreter
+            .max_segment_ms
+            .unwrap_or(DEFAULT_INTERPRETER_MAX_SEGMENT_MS),
+        whisper_model_path,
+        replaced_provider,
+    })
+}
+
+#[tauri::command]
+pub fn get_interpreter_mode(app: AppHandle) -> InterpreterStatus {
+    status(&app)
+}
+
+// Takes effect mid-session: the next segment is cut shorter, window drafts start right away and
+// the next batch is translated without cleanup or context.
+#[tauri::command]
+pub fn set_interpreter_mode(app: AppHandle, enabled: bool) -> Result<InterpreterStatus, String> {
+    let previous = active();
+    let changed_preset = match (enabled, previous) {
+        (true, None) => {
+            let preset = enable(&app)?;
+            info!(
+                "[interpreter] enabled max_segment_ms={} model={:?}",
+                preset.max_segment_ms, preset.whisper_model_path
+            );
+            set_active(Some(preset.clone()));
+            Some(preset)
+        }
+        (false, Some(preset)) => {
+            if let Some(provider) = preset.replaced_provider.as_deref() {
+                set_provider(&app, provider);
+            }
+            info!("[interpreter] disabled");
+            set_active(None);
+            Some(preset)
+        }
+        _ => None,
+    };
+    if changed_preset.is_some_and(|preset| preset.whisper_model_path.is_some()) {
+        restart_asr_workers(&app);
+    }
+    Ok(status(&app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::streaming_provider;
+
+    #[test]
+    fn replaces_only_providers_that_cannot_stream() {
+        assert_eq!(streaming_provider("openai", Some("ollama")), None);
+        assert_eq!(streaming_provider("ollama", None), None);
+        assert_eq!(
+            streaming_provider("local-gpt", Some("ChatGPT")).as_deref(),
+            Some("openai")
+        );
+        assert_eq!(
+            streaming_provider("local-gpt", Some("localgpt")).as_deref(),
+            Some("ollama")
+        );
+        assert_eq!(
+            streaming_provider("local-gpt", Some(" ")).as_deref(),
+            Some("ollama")
+        );
+    }
+}
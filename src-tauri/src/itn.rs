@@ -0,0 +1,580 @@
+// Inverse text normalization: spoken numbers in transcripts become numerals ("twenty five
+// percent" → "25%", "三万五千円" → "35,000円"), so translations and summaries see clean figures.
+
+use crate::app_config::load_config;
+use crate::asr::AsrState;
+use crate::punctuation::script_language;
+use tauri::{AppHandle, Manager};
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+// Counters that make a single kanji digit a number rather than part of a word (一緒, 統一).
+const CJK_UNITS: [&str; 17] = [
+    "年",
+    "月",
+    "日",
+    "時",
+    "円",
+    "元",
+    "ドル",
+    "%",
+    "％",
+    "パーセント",
+    "歳",
+    "岁",
+    "個",
+    "个",
+    "件",
+    "回",
+    "号",
+];
+
+// Post-ASR stage; a no-op unless `asr.itn` turns it on for the language, which is the session's or
+// the current ASR language when `None`.
+pub fn normalize_transcript(app: &AppHandle, text: &str, language: Option<&str>) -> String {
+    let Some(languages) = load_config()
+        .ok()
+        .and_then(|config| config.asr)
+        .and_then(|asr| asr.itn)
+    else {
+        return text.to_string();
+    };
+    let language = language
+        .map(str::to_string)
+        .or_else(|| app.try_state::<AsrState>().map(|state| state.language()))
+        .filter(|language| !language.is_empty() && language != "auto")
+        .unwrap_or_else(|| script_language(text).to_string());
+    let enabled = languages
+        .iter()
+        .any(|(key, enabled)| *enabled && key.trim().eq_ignore_ascii_case(&language));
+    if enabled {
+        normalize(text, &language)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn normalize(text: &str, language: &str) -> String {
+    match language {
+        "en" => normalize_english(text),
+        "ja" | "zh" => normalize_cjk(text),
+        _ => text.to_string(),
+    }
+}
+
+// Thousands separators from 10,000 up, so years stay `2025`.
+fn format_number(value: u64) -> String {
+    let digits = value.to_string();
+    if value < 10_000 {
+        return digits;
+    }
+    let mut output = String::new();
+    for (index, ch) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            output.push(',');
+        }
+        output.push(ch);
+    }
+    output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberWord {
+    // zero..nineteen
+    Unit(u64),
+    // twenty..ninety
+    Ten(u64),
+    Hundred,
+    // thousand, million, billion
+    Scale(u64),
+    // first..nineteenth, twentieth, thirtieth; always ends the number.
+    Ordinal(u64),
+}
+
+fn number_word(word: &str) -> Option<NumberWord> {
+    const UNITS: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    const ORDINALS: [&str; 19] = [
+        "first",
+        "second",
+        "third",
+        "fourth",
+        "fifth",
+        "sixth",
+        "seventh",
+        "eighth",
+        "ninth",
+        "tenth",
+        "eleventh",
+        "twelfth",
+        "thirteenth",
+        "fourteenth",
+        "fifteenth",
+        "sixteenth",
+        "seventeenth",
+        "eighteenth",
+        "nineteenth",
+    ];
+    if let Some(index) = UNITS.iter().position(|unit| *unit == word) {
+        return Some(NumberWord::Unit(index as u64));
+    }
+    if let Some(index) = TENS.iter().position(|ten| *ten == word) {
+        return Some(NumberWord::Ten(index as u64 * 10 + 20));
+    }
+    if let Some(index) = ORDINALS.iter().position(|ordinal| *ordinal == word) {
+        return Some(NumberWord::Ordinal(index as u64 + 1));
+    }
+    match word {
+        "twentieth" => Some(NumberWord::Ordinal(20)),
+        "thirtieth" => Some(NumberWord::Ordinal(30)),
+        "hundred" => Some(NumberWord::Hundred),
+        "thousand" => Some(NumberWord::Scale(1_000)),
+        "million" => Some(NumberWord::Scale(1_000_000)),
+        "billion" => Some(NumberWord::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+// A word split from the punctuation around it, lower-cased for matching.
+struct Token<'a> {
+    lead: &'a str,
+    core: String,
+    original: &'a str,
+    trail: &'a str,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let start = word
+            .find(|ch: char| ch.is_alphanumeric())
+            .unwrap_or(word.len());
+        let end = word
+            .rfind(|ch: char| ch.is_alphanumeric())
+            .map(|index| index + word[index..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(start)
+            .max(start);
+        // `twenty-five` is read as two words.
+        let parts = word[start..end].split('-').collect::<Vec<_>>();
+        let hyphenated = matches!(
+            parts
+                .iter()
+                .map(|part| number_word(&part.to_lowercase()))
+                .collect::<Vec<_>>()
+                .as_slice(),
+            [
+                Some(NumberWord::Ten(_)),
+                Some(NumberWord::Unit(1..=9) | NumberWord::Ordinal(1..=9))
+            ]
+        );
+        if hyphenated {
+            for (index, part) in parts.iter().enumerate() {
+                tokens.push(Token {
+                    lead: if index == 0 { &word[..start] } else { "" },
+                    core: part.to_lowercase(),
+                    original: part,
+                    trail: if index + 1 == parts.len() {
+                        &word[end..]
+                    } else {
+                        ""
+                    },
+                });
+            }
+        } else {
+            tokens.push(Token {
+                lead: &word[..start],
+                core: word[start..end].to_lowercase(),
+                original: &word[start..end],
+                trail: &word[end..],
+            });
+        }
+    }
+    tokens
+}
+
+struct SpokenNumber {
+    value: u64,
+    // Digits after `point`.
+    decimals: String,
+    ordinal: bool,
+    // Tokens consumed, including `and` and `point`.
+    len: usize,
+}
+
+// Reads the number words starting at `start`; stops at punctuation, so "five, six" stays two
+// numbers.
+fn read_number(tokens: &[Token], start: usize) -> Option<SpokenNumber> {
+    let mut total = 0u64;
+    let mut current = 0u64;
+    let mut last: Option<NumberWord> = None;
+    let mut index = start;
+    let mut ordinal = false;
+    let mut last_scale = u64::MAX;
+    while let Some(token) = tokens.get(index) {
+        if index > start && !token.lead.is_empty() {
+            break;
+        }
+        let word = token.core.as_str();
+        if word == "and"
+            && last == Some(NumberWord::Hundred)
+            && tokens[index - 1].trail.is_empty()
+            && tokens.get(index + 1).is_some_and(|next| {
+                matches!(
+                    number_word(&next.core),
+                    Some(NumberWord::Unit(_) | NumberWord::Ten(_))
+                )
+            })
+        {
+            index += 1;
+            continue;
+        }
+        let Some(kind) = number_word(word) else {
+            break;
+        };
+        // "hundred" and "million" never start a number, and scales only go down.
+        let fits = match (last, kind) {
+            (None, NumberWord::Hundred | NumberWord::Scale(_)) => false,
+            (None, _) => true,
+            (Some(NumberWord::Ten(_)), NumberWord::Unit(unit) | NumberWord::Ordinal(unit)) => {
+                (1..10).contains(&unit)
+            }
+            (Some(NumberWord::Unit(_) | NumberWord::Ten(_)), NumberWord::Hundred) => current < 100,
+            (
+                Some(NumberWord::Hundred | NumberWord::Scale(_)),
+                NumberWord::Unit(_) | NumberWord::Ten(_) | NumberWord::Ordinal(_),
+            ) => true,
+            (
+                Some(NumberWord::Unit(_) | NumberWord::Ten(_) | NumberWord::Hundred),
+                NumberWord::Scale(scale),
+            ) => scale < last_scale,
+            _ => false,
+        };
+        if !fits {
+            break;
+        }
+        match kind {
+            NumberWord::Unit(value) | NumberWord::Ten(value) => current += value,
+            NumberWord::Ordinal(value) => {
+                current += value;
+                ordinal = true;
+            }
+            NumberWord::Hundred => current = current.max(1) * 100,
+            NumberWord::Scale(scale) => {
+                total += current * scale;
+                current = 0;
+                last_scale = scale;
+            }
+        }
+        last = Some(kind);
+        index += 1;
+        if ordinal || !token.trail.is_empty() {
+            break;
+        }
+    }
+    last?;
+    let mut number = SpokenNumber {
+        value: total + current,
+        decimals: String::new(),
+        ordinal,
+        len: index - start,
+    };
+    // "three point five"
+    if !ordinal
+        && tokens[index - 1].trail.is_empty()
+        && tokens
+            .get(index)
+            .is_some_and(|token| token.core == "point" && token.trail.is_empty())
+    {
+        let mut decimals = String::new();
+        let mut next = index + 1;
+        while let Some(Token { core, .. }) = tokens.get(next) {
+            let Some(NumberWord::Unit(digit @ 0..=9)) = number_word(core) else {
+                break;
+            };
+            decimals.push_str(&digit.to_string());
+            next += 1;
+            if !tokens[next - 1].trail.is_empty() {
+                break;
+            }
+        }
+        if !decimals.is_empty() {
+            number.decimals = decimals;
+            number.len = next - start;
+        }
+    }
+    Some(number)
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn normalize_english(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut output: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let Some(mut number) = read_number(&tokens, index) else {
+            let token = &tokens[index];
+            output.push(format!("{}{}{}", token.lead, token.original, token.trail));
+            index += 1;
+            continue;
+        };
+        // Years are spoken in pairs: "nineteen ninety", "twenty twenty five".
+        if matches!(number.value, 19 | 20) && number.len == 1 && tokens[index].trail.is_empty() {
+            let second = read_number(&tokens, index + 1).filter(|second| {
+                (10..100).contains(&second.value) && second.decimals.is_empty() && !second.ordinal
+            });
+            if let Some(second) = second {
+                number.value = number.value * 100 + second.value;
+                number.len += second.len;
+            }
+        }
+        let first = &tokens[index];
+        let mut last = &tokens[index + number.len - 1];
+        let after_month = index > 0
+            && tokens[index - 1].trail.is_empty()
+            && MONTHS.contains(&tokens[index - 1].core.as_str());
+        let mut digits = format_number(number.value);
+        if !number.decimals.is_empty() {
+            digits = format!("{digits}.{}", number.decimals);
+        }
+        let mut consumed = number.len;
+        let unit = tokens
+            .get(index + number.len)
+            .filter(|_| last.trail.is_empty() && !number.ordinal);
+        let rendered = match unit.map(|token| token.core.as_str()) {
+            Some("percent") => Some(format!("{digits}%")),
+            Some("dollars" | "dollar" | "bucks") => Some(format!("${digits}")),
+            Some("euros" | "euro") => Some(format!("€{digits}")),
+            Some("pounds") => Some(format!("£{digits}")),
+            Some("yen") => Some(format!("¥{digits}")),
+            _ => None,
+        };
+        let rendered = if let Some(rendered) = rendered {
+            consumed += 1;
+            last = &tokens[index + consumed - 1];
+            Some(rendered)
+        } else if after_month && (1..=31).contains(&number.value) && number.decimals.is_empty() {
+            Some(digits)
+        } else if number.ordinal {
+            (number.len > 1 || number.value >= 10)
+                .then(|| format!("{digits}{}", ordinal_suffix(number.value)))
+        } else {
+            // "one of them" and "zero" read better as words.
+            (number.len > 1 || number.value >= 10).then_some(digits)
+        };
+        match rendered {
+            Some(rendered) => {
+                output.push(format!("{}{rendered}{}", first.lead, last.trail));
+                index += consumed;
+            }
+            None => {
+                output.push(format!("{}{}{}", first.lead, first.original, first.trail));
+                index += 1;
+            }
+        }
+    }
+    output.join(" ")
+}
+
+fn kanji_digit(ch: char) -> Option<u64> {
+    match ch {
+        '〇' | '零' => Some(0),
+        '一' => Some(1),
+        '二' | '两' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+fn kanji_multiplier(ch: char) -> Option<u64> {
+    match ch {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1_000),
+        '万' | '萬' => Some(10_000),
+        '億' | '亿' => Some(100_000_000),
+        _ => None,
+    }
+}
+
+fn is_kanji_number(ch: char) -> bool {
+    kanji_digit(ch).is_some() || kanji_multiplier(ch).is_some()
+}
+
+// `三万五千` → 35000; a run without multipliers (`二〇二五`) is read digit by digit.
+fn kanji_value(run: &[char]) -> Option<u64> {
+    if run.iter().all(|ch| kanji_digit(*ch).is_some()) {
+        return run.iter().try_fold(0u64, |value, ch| {
+            value.checked_mul(10)?.checked_add(kanji_digit(*ch)?)
+        });
+    }
+    let mut total = 0u64;
+    let mut section = 0u64;
+    let mut digit: Option<u64> = None;
+    for ch in run {
+        if let Some(value) = kanji_digit(*ch) {
+            if digit.is_some() {
+                return None;
+            }
+            digit = Some(value);
+        } else if let Some(multiplier) = kanji_multiplier(*ch) {
+            if multiplier >= 10_000 {
+                total += (section + digit.unwrap_or(0)).max(1) * multiplier;
+                section = 0;
+            } else {
+                section += digit.unwrap_or(1) * multiplier;
+            }
+            digit = None;
+        }
+    }
+    Some(total + section + digit.unwrap_or(0))
+}
+
+fn normalize_cjk(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        // 百分之二十五 (zh) is read as 25%.
+        let percent_prefix = chars[index..].starts_with(&['百', '分', '之']);
+        let start = if percent_prefix { index + 3 } else { index };
+        let end = start
+            + chars[start..]
+                .iter()
+                .take_while(|ch| is_kanji_number(**ch))
+                .count();
+        if end == start {
+            output.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let rest = chars[end..].iter().collect::<String>();
+        let unit = CJK_UNITS.iter().find(|unit| rest.starts_with(**unit));
+        // A single character is only a number before a counter, so 一緒, 統一 and 十分 stay as
+        // they are; neither is a run starting with 万 (万一).
+        let single = end - start == 1;
+        let value = kanji_value(&chars[start..end]).filter(|_| {
+            (!single || unit.is_some() || percent_prefix)
+                && !matches!(chars[start], '万' | '萬' | '億' | '亿')
+        });
+        let Some(value) = value else {
+            output.extend(&chars[index..end]);
+            index = end;
+            continue;
+        };
+        output.push_str(&format_number(value));
+        index = end;
+        if percent_prefix {
+            output.push('%');
+        } else if let Some(unit @ ("パーセント" | "％")) = unit.copied() {
+            output.push('%');
+            index += unit.chars().count();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+
+    #[test]
+    fn turns_spoken_numbers_into_numerals() {
+        assert_eq!(
+            normalize(
+                "Sales grew twenty five percent, to three point five million dollars.",
+                "en"
+            ),
+            "Sales grew 25%, to 3.5 million dollars."
+        );
+        assert_eq!(
+            normalize(
+                "We hired one hundred and twelve people on March twenty first",
+                "en"
+            ),
+            "We hired 112 people on March 21"
+        );
+        assert_eq!(
+            normalize(
+                "one of the twenty-first century's forty thousand problems",
+                "en"
+            ),
+            "one of the 21st century's 40,000 problems"
+        );
+        assert_eq!(normalize("five, six, seven", "en"), "five, six, seven");
+        assert_eq!(normalize("two thousand twenty five", "en"), "2025");
+        assert_eq!(
+            normalize(
+                "Back in nineteen ninety, and again in twenty twenty five.",
+                "en"
+            ),
+            "Back in 1990, and again in 2025."
+        );
+
+        assert_eq!(
+            normalize(
+                "二〇二五年三月五日に三万五千円、二十五パーセント増えました",
+                "ja"
+            ),
+            "2025年3月5日に35,000円、25%増えました"
+        );
+        assert_eq!(
+            normalize("万一に備えて十分に一緒に統一しましょう", "ja"),
+            "万一に備えて十分に一緒に統一しましょう"
+        );
+        assert_eq!(
+            normalize("增长了百分之二十五，达到一百二十万元", "zh"),
+            "增长了25%，达到1,200,000元"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::events::{replay_ui_event, EventHub, UiEvent, SESSION_ENDED};
+use chrono::{DateTime, FixedOffset};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+const JOURNALS_DIR: &str = "journals";
+const MAX_REPLAY_GAP_MS: u64 = 5_000;
+
+static REPLAYING: AtomicBool = AtomicBool::new(false);
+
+// Writes every ui_event between session_started and session_ended to `journals/<session>.jsonl`.
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut journal: Option<File> = None;
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("event journal lagged, skipped {skipped} events");
+                    if let Some(file) = journal.as_mut() {
+                        let marker =
+                            serde_json::json!({ "event": "journal_gap", "skipped": skipped });
+                        let _ = writeln!(file, "{marker}");
+                    }
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed {
+                continue;
+            }
+            if event.event == "session_started" {
+                journal = event
+                    .payload
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .and_then(|id| match open_journal(&app, id) {
+                        Ok(file) => Some(file),
+                        Err(err) => {
+                            warn!("event journal unavailable: {err}");
+                            None
+                        }
+                    });
+            }
+            if let Some(file) = journal.as_mut() {
+                match serde_json::to_string(&event) {
+                    Ok(line) => {
+                        if let Err(err) = writeln!(file, "{line}") {
+                            warn!("event journal write failed: {err}");
+                        }
+                    }
+                    Err(err) => warn!("event journal serialize failed: {err}"),
+                }
+            }
+            if event.event == SESSION_ENDED {
+                journal = None;
+            }
+        }
+    });
+}
+
+fn open_journal(app: &AppHandle, session_id: &str) -> Result<File, String> {
+    let path = journal_path(app, session_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| format!("failed to open {}: {err}", path.display()))
+}
+
+fn journal_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(format!("invalid session id: {session_id}"));
+    }
+    let base = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    Ok(base.join(JOURNALS_DIR).join(format!("{session_id}.jsonl")))
+}
+
+fn parse_journal(content: &str) -> Vec<UiEvent> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<UiEvent>(line).ok())
+        .collect()
+}
+
+// Delay before each event, keeping the recorded spacing scaled by `speed`; long idle gaps are
+// shortened and a non-positive speed replays back to back.
+fn replay_delays(events: &[UiEvent], speed: f64) -> Vec<Duration> {
+    let mut previous: Option<DateTime<FixedOffset>> = None;
+    events
+        .iter()
+        .map(|event| {
+            let at = DateTime::parse_from_rfc3339(&event.timestamp).ok();
+            let gap_ms = match (previous, at) {
+                (Some(previous), Some(at)) => (at - previous).num_milliseconds().max(0) as u64,
+                _ => 0,
+            };
+            if at.is_some() {
+                previous = at;
+            }
+            if speed <= 0.0 {
+                return Duration::ZERO;
+            }
+            Duration::from_millis(gap_ms.min(MAX_REPLAY_GAP_MS)).div_f64(speed)
+        })
+        .collect()
+}
+
+// Developer tool: re-sends a recorded session in its original order to the output webview and the
+// broadcast channel. Replayed events are flagged so webhooks, MQTT and the journal ignore them.
+#[tauri::command]
+pub fn replay_event_journal(
+    app: AppHandle,
+    session_id: String,
+    speed: Option<f64>,
+) -> Result<usize, String> {
+    let path = journal_path(&app, session_id.trim())?;
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let events = parse_journal(&content);
+    if events.is_empty() {
+        return Err(format!("journal is empty: {}", path.display()));
+    }
+    if REPLAYING.swap(true, Ordering::SeqCst) {
+        return Err("a journal replay is already running".to_string());
+    }
+    let delays = replay_delays(&events, speed.unwrap_or(1.0));
+    let count = events.len();
+    tauri::async_runtime::spawn(async move {
+        for (event, delay) in events.into_iter().zip(delays) {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            replay_ui_event(&app, event);
+        }
+        REPLAYING.store(false, Ordering::SeqCst);
+    });
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_journal, replay_delays};
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_order_and_scales_gaps() {
+        let content = r#"{"event":"session_started","payload":{"id":"s1"},"timestamp":"2026-01-05T10:00:00+09:00"}
+{"event":"journal_gap","skipped":3}
+{"event":"segment_created","payload":{},"timestamp":"2026-01-05T10:00:01+09:00"}
+{"event":"segment_transcribed","payload":{},"timestamp":"2026-01-05T10:01:00+09:00"}"#;
+        let events = parse_journal(content);
+        let names = events
+            .iter()
+            .map(|event| event.event.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["session_started", "segment_created", "segment_transcribed"]
+        );
+        assert_eq!(
+            replay_delays(&events, 2.0),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_millis(2_500)
+            ]
+        );
+        assert!(replay_delays(&events, 0.0).iter().all(Duration::is_zero));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::events::emit_ui_event;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+pub const LIVE_DRAFT_UPDATE: &str = "live_draft_update";
+
+static STABILIZER: Lazy<Mutex<DraftStabilizer>> =
+    Lazy::new(|| Mutex::new(DraftStabilizer::default()));
+
+// `stable + tail == text`. Only the tail may still change, so the UI can restyle just the tail
+// and translation can wait for `stable_changed`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DraftUpdate {
+    pub revision: u64,
+    pub text: String,
+    pub stable: String,
+    pub tail: String,
+    pub stable_changed: bool,
+}
+
+// Text two consecutive drafts agree on is treated as settled (local agreement), cut back to a
+// word boundary so a half-recognized word never becomes stable.
+#[derive(Debug, Default)]
+pub struct DraftStabilizer {
+    previous: String,
+    stable: String,
+    revision: u64,
+}
+
+impl DraftStabilizer {
+    pub fn update(&mut self, raw: &str) -> DraftUpdate {
+        let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.revision += 1;
+        let agreed = agreed_prefix(&self.previous, &text);
+        // Stable text only grows while the draft keeps it; a sliding window or a new
+        // utterance that rewrites it starts over from the agreed part.
+        let stable_len = if text.starts_with(&self.stable) {
+            agreed.max(self.stable.len())
+        } else {
+            agreed
+        };
+        let stable_changed = text[..stable_len] != self.stable;
+        self.stable = text[..stable_len].to_string();
+        let update = DraftUpdate {
+            revision: self.revision,
+            stable: self.stable.clone(),
+            tail: text[stable_len..].to_string(),
+            text: text.clone(),
+            stable_changed,
+        };
+        self.previous = text;
+        update
+    }
+
+    pub fn reset(&mut self) {
+        self.previous.clear();
+        self.stable.clear();
+    }
+}
+
+// Byte length of the common prefix, moved back to the nearest word boundary.
+fn agreed_prefix(previous: &str, text: &str) -> usize {
+    let mut end = previous
+        .char_indices()
+        .zip(text.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((index, _), _)| index)
+        .unwrap_or_else(|| previous.len().min(text.len()));
+    while end > 0 && !is_word_boundary(previous, text, end) {
+        end = text[..end]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index);
+    }
+    end
+}
+
+// CJK text has no spaces, so any position after a non-ASCII character counts.
+fn is_word_boundary(previous: &str, text: &str, at: usize) -> bool {
+    let Some(before) = text[..at].chars().next_back() else {
+        return true;
+    };
+    if !before.is_ascii_alphanumeric() {
+        return true;
+    }
+    [previous, text].iter().all(|draft| {
+        !draft[at..]
+            .chars()
+            .next()
+            .is_some_and(|after| after.is_ascii_alphanumeric())
+    })
+}
+
+// Stabilizes and emits `live_draft_update`.
+pub fn publish(app: &AppHandle, text: &str) -> DraftUpdate {
+    let update = STABILIZER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .update(text);
+    emit_ui_event(app, LIVE_DRAFT_UPDATE, update.clone());
+    update
+}
+
+// A new session starts without the previous session's stable text.
+pub fn reset() {
+    if let Ok(mut stabilizer) = STABILIZER.lock() {
+        stabilizer.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DraftStabilizer;
+
+    #[test]
+    fn keeps_the_agreed_prefix_stable_up_to_a_word_boundary() {
+        let mut drafts = DraftStabilizer::default();
+        let first = drafts.update("we should ship");
+        assert_eq!(
+            (first.stable.as_str(), first.tail.as_str()),
+            ("", "we should ship")
+        );
+
+        let second = drafts.update("we should shift  the launch");
+        assert_eq!(second.stable, "we should ");
+        assert_eq!(second.tail, "shift the launch");
+        assert!(second.stable_changed);
+
+        let third = drafts.update("we should shift the launch date");
+        assert_eq!(third.stable, "we should shift the launch");
+        assert_eq!(third.tail, " date");
+
+        let repeated = drafts.update("we should shift the launch date");
+        assert_eq!(repeated.stable, repeated.text);
+        assert!(repeated.stable_changed);
+        assert!(
+            !drafts
+                .update("we should shift the launch date")
+                .stable_changed
+        );
+
+        // The window slid past the old text: nothing is agreed yet.
+        let slid = drafts.update("launch date to May");
+        assert_eq!(slid.stable, "");
+
+        let mut cjk = DraftStabilizer::default();
+        cjk.update("我们下周");
+        let update = cjk.update("我们下午开会");
+        assert_eq!(
+            (update.stable.as_str(), update.tail.as_str()),
+            ("我们下", "午开会")
+        );
+    }
+}
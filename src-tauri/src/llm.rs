@@ -0,0 +1,1196 @@
+use crate::app_config::AppConfig;
+use crate::defaults::{
+    DEFAULT_CLAUDE_API_VERSION, DEFAULT_CLAUDE_BASE_URL, DEFAULT_CLAUDE_MAX_TOKENS,
+    DEFAULT_CLAUDE_MODEL, DEFAULT_CLAUDE_TIMEOUT, DEFAULT_GEMINI_BASE_URL, DEFAULT_GEMINI_MODEL,
+    DEFAULT_GEMINI_TIMEOUT, DEFAULT_LOCAL_GPT_BASE_URL, DEFAULT_LOCAL_GPT_DIRECT_PATH,
+    DEFAULT_LOCAL_GPT_PROJECT_ID, DEFAULT_LOCAL_GPT_TIMEOUT, DEFAULT_OLLAMA_BASE_URL,
+    DEFAULT_OLLAMA_MODEL, DEFAULT_OLLAMA_TIMEOUT, DEFAULT_OPENAI_CHAT_BASE_URL,
+    DEFAULT_OPENAI_CHAT_MODEL, DEFAULT_OPENAI_CHAT_TIMEOUT,
+};
+use crate::http;
+use crate::redact::redact;
+use crate::stream::{EventDecoder, StreamFraming};
+use crate::usage::{TokenUsage, UsageState};
+use futures_util::future::BoxFuture;
+use futures_util::StreamExt;
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+const GENERATE_SYSTEM_PROMPT: &str = "Answer using provided context and cite sources as [n].";
+
+// One prompt round-trip. Chat APIs get `instructions` as the system message and `input` as the
+// user message; single-prompt APIs get both joined by a blank line.
+#[derive(Debug, Clone)]
+pub struct LlmPrompt {
+    pub instructions: String,
+    pub input: Option<String>,
+    pub temperature: f32,
+    // What the call is for, in the usage statistics.
+    pub feature: &'static str,
+    // Where the call is counted; set by `Metered`, unset calls go uncounted.
+    pub usage: Option<UsageState>,
+}
+
+impl LlmPrompt {
+    pub fn new(instructions: impl Into<String>) -> Self {
+        Self {
+            instructions: instructions.into(),
+            input: None,
+            temperature: 0.2,
+            feature: "other",
+            usage: None,
+        }
+    }
+
+    pub fn with_input(mut self, input: impl Into<String>) -> Self {
+        self.input = Some(input.into());
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn for_feature(mut self, feature: &'static str) -> Self {
+        self.feature = feature;
+        self
+    }
+
+    // Claude and Gemini need a user turn, so a prompt without input sends its instructions as one.
+    fn system_and_user(&self) -> (Option<&str>, &str) {
+        match self.input.as_deref() {
+            Some(input) => (Some(self.instructions.as_str()), input),
+            None => (None, self.instructions.as_str()),
+        }
+    }
+
+    fn joined(&self) -> String {
+        match self.input.as_deref() {
+            Some(input) => format!("{}\n\n{input}", self.instructions),
+            None => self.instructions.clone(),
+        }
+    }
+}
+
+// A text-completion backend. Callers hold `dyn LlmProvider`, so tests can swap in `MockLlm`.
+pub trait LlmProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn model(&self) -> &str;
+    fn endpoint(&self) -> &str;
+    // Whether `instructions` travel as a separate system message.
+    fn has_system_role(&self) -> bool {
+        false
+    }
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>>;
+    // Hands the reply to `on_chunk` piece by piece as it arrives and returns the whole text.
+    // Backends without a streaming API deliver it as one piece.
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let text = self.complete(prompt).await?;
+            on_chunk(&text);
+            Ok(text)
+        })
+    }
+}
+
+pub type ChunkSink<'a> = dyn FnMut(&str) + Send + 'a;
+
+impl<T: LlmProvider + ?Sized> LlmProvider for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn model(&self) -> &str {
+        (**self).model()
+    }
+
+    fn endpoint(&self) -> &str {
+        (**self).endpoint()
+    }
+
+    fn has_system_role(&self) -> bool {
+        (**self).has_system_role()
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        (**self).complete(prompt)
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        (**self).complete_streaming(prompt, on_chunk)
+    }
+}
+
+// Counts every prompt it passes on in `usage`.
+pub struct Metered {
+    llm: Box<dyn LlmProvider>,
+    usage: UsageState,
+}
+
+impl Metered {
+    fn prompt(&self, prompt: &LlmPrompt) -> LlmPrompt {
+        LlmPrompt {
+            usage: Some(self.usage.clone()),
+            ..prompt.clone()
+        }
+    }
+}
+
+impl LlmProvider for Metered {
+    fn name(&self) -> &'static str {
+        self.llm.name()
+    }
+
+    fn model(&self) -> &str {
+        self.llm.model()
+    }
+
+    fn endpoint(&self) -> &str {
+        self.llm.endpoint()
+    }
+
+    fn has_system_role(&self) -> bool {
+        self.llm.has_system_role()
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        let prompt = self.prompt(prompt);
+        Box::pin(async move { self.llm.complete(&prompt).await })
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        let prompt = self.prompt(prompt);
+        Box::pin(async move { self.llm.complete_streaming(&prompt, on_chunk).await })
+    }
+}
+
+// Without a ledger (the app is not set up yet) calls go uncounted.
+pub fn metered(llm: Box<dyn LlmProvider>, usage: Option<UsageState>) -> Box<dyn LlmProvider> {
+    match usage {
+        Some(usage) => Box::new(Metered { llm, usage }),
+        None => llm,
+    }
+}
+
+// `provider` is a normalized name, see `normalize_translate_provider`.
+pub fn provider_from_config(
+    provider: &str,
+    config: &AppConfig,
+) -> Result<Box<dyn LlmProvider>, String> {
+    match provider {
+        "openai" | "chatgpt" => Ok(Box::new(OpenAiProvider::from_config(config)?)),
+        "local-gpt" => Ok(Box::new(LocalGptProvider::from_config(config))),
+        "ollama" => Ok(Box::new(OllamaProvider::from_config(config)?)),
+        "claude" => Ok(Box::new(ClaudeProvider::from_config(config)?)),
+        "gemini" => Ok(Box::new(GeminiProvider::from_config(config)?)),
+        other => Err(format!("unsupported translate provider: {other}")),
+    }
+}
+
+// Free-form generation for summaries and RAG answers.
+pub async fn generate(
+    provider: &dyn LlmProvider,
+    prompt: &str,
+    feature: &'static str,
+) -> Result<String, String> {
+    let request = if provider.has_system_role() {
+        LlmPrompt::new(GENERATE_SYSTEM_PROMPT).with_input(prompt)
+    } else {
+        LlmPrompt::new(prompt)
+    };
+    provider.complete(&request.for_feature(feature)).await
+}
+
+// Counts the call in the prompt's ledger; replies that report no token counts are estimated from
+// the text.
+fn record_usage(
+    llm: &dyn LlmProvider,
+    prompt: &LlmPrompt,
+    reported: Option<TokenUsage>,
+    reply: &str,
+) {
+    let Some(usage) = &prompt.usage else {
+        return;
+    };
+    let estimated = reported.is_none();
+    let tokens = reported.unwrap_or_else(|| TokenUsage::estimate(&prompt.joined(), reply));
+    usage.record(llm.name(), llm.model(), prompt.feature, tokens, estimated);
+}
+
+pub struct OpenAiProvider {
+    api_key: String,
+    model: String,
+    endpoint: String,
+    timeout_secs: u64,
+}
+
+impl OpenAiProvider {
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let openai = &config.openai;
+        let api_key = openai.api_key.trim();
+        if api_key.is_empty() {
+            return Err("OpenAI apiKey is required".to_string());
+        }
+        let model = openai
+            .chat_model
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_MODEL.to_string());
+        let base_url = openai
+            .chat_base_url
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_BASE_URL.to_string());
+        Ok(Self {
+            api_key: api_key.to_string(),
+            model,
+            endpoint: base_url.trim_end_matches('/').to_string(),
+            timeout_secs: openai
+                .chat_timeout_secs
+                .unwrap_or(DEFAULT_OPENAI_CHAT_TIMEOUT),
+        })
+    }
+
+    fn post(&self, prompt: &LlmPrompt, stream: bool) -> Result<RequestBuilder, String> {
+        let mut input = vec![json!({
+            "role": "system",
+            "content": [{"type": "input_text", "text": prompt.instructions}]
+        })];
+        if let Some(text) = prompt.input.as_deref() {
+            input.push(json!({
+                "role": "user",
+                "content": [{"type": "input_text", "text": text}]
+            }));
+        }
+        let body = json!({
+          "model": self.model,
+          "input": input,
+          "temperature": prompt.temperature,
+          "stream": stream
+        });
+
+        let client = http::client(Some(self.timeout_secs))?;
+        Ok(client
+            .post(self.endpoint.as_str())
+            .bearer_auth(&self.api_key)
+            .json(&body))
+    }
+
+    async fn request(&self, prompt: &LlmPrompt) -> Result<String, String> {
+        let response = self
+            .post(prompt, false)?
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        let value: Value = response.json().await.map_err(|err| err.to_string())?;
+        if !status.is_success() {
+            return Err(redact(&value.to_string()).into_owned());
+        }
+
+        let text = extract_response_text(&value)
+            .ok_or_else(|| "OpenAI response missing text".to_string())?;
+        record_usage(self, prompt, TokenUsage::from_reply(&value), &text);
+        Ok(text)
+    }
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn has_system_role(&self) -> bool {
+        true
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(self.request(prompt))
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let request = self.post(prompt, true)?;
+            let (text, reported) =
+                read_stream(request, StreamFraming::Sse, openai_stream_delta, on_chunk).await?;
+            record_usage(self, prompt, reported, &text);
+            Ok(text)
+        })
+    }
+}
+
+// Text of a `response.output_text.delta` event from the responses API.
+fn openai_stream_delta(value: &Value) -> Option<String> {
+    if value.get("type").and_then(|t| t.as_str()) != Some("response.output_text.delta") {
+        return None;
+    }
+    value
+        .get("delta")
+        .and_then(|delta| delta.as_str())
+        .map(str::to_string)
+}
+
+pub struct OllamaProvider {
+    model: String,
+    endpoint: String,
+    timeout_secs: u64,
+}
+
+impl OllamaProvider {
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let ollama = config.ollama.clone().unwrap_or_default();
+        if ollama.enabled == Some(false) {
+            return Err("ollama disabled".to_string());
+        }
+        let model = ollama
+            .model
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string());
+        let base_url = ollama
+            .base_url
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OLLAMA_BASE_URL.to_string());
+        Ok(Self {
+            model,
+            endpoint: format!("{}/api/generate", base_url.trim_end_matches('/')),
+            timeout_secs: ollama.timeout_secs.unwrap_or(DEFAULT_OLLAMA_TIMEOUT),
+        })
+    }
+
+    fn post(&self, prompt: &LlmPrompt, stream: bool) -> Result<RequestBuilder, String> {
+        let body = json!({
+          "model": self.model,
+          "prompt": prompt.joined(),
+          "stream": stream
+        });
+        let client = http::client(Some(self.timeout_secs))?;
+        Ok(client.post(self.endpoint.as_str()).json(&body))
+    }
+
+    async fn request(&self, prompt: &LlmPrompt) -> Result<String, String> {
+        let response = self
+            .post(prompt, false)?
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        let value: Value = response.json().await.map_err(|err| err.to_string())?;
+        if !status.is_success() {
+            return Err(redact(&value.to_string()).into_owned());
+        }
+
+        let text = value
+            .get("response")
+            .and_then(|response| response.as_str())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| "ollama response missing text".to_string())?;
+        record_usage(self, prompt, TokenUsage::from_reply(&value), &text);
+        Ok(text)
+    }
+}
+
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(self.request(prompt))
+    }
+
+    // Ollama streams one JSON object per line.
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let request = self.post(prompt, true)?;
+            let (text, reported) =
+                read_stream(request, StreamFraming::Lines, ollama_stream_delta, on_chunk).await?;
+            record_usage(self, prompt, reported, &text);
+            Ok(text)
+        })
+    }
+}
+
+fn ollama_stream_delta(value: &Value) -> Option<String> {
+    value
+        .get("response")
+        .and_then(|response| response.as_str())
+        .map(str::to_string)
+}
+
+pub struct ClaudeProvider {
+    api_key: String,
+    model: String,
+    endpoint: String,
+    timeout_secs: u64,
+    max_tokens: u32,
+}
+
+impl ClaudeProvider {
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let claude = config.claude.clone().unwrap_or_default();
+        let api_key = claude
+            .api_key
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| "Claude apiKey is required".to_string())?;
+        let model = claude
+            .model
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CLAUDE_MODEL.to_string());
+        let base_url = claude
+            .base_url
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CLAUDE_BASE_URL.to_string());
+        Ok(Self {
+            api_key,
+            model,
+            endpoint: base_url.trim_end_matches('/').to_string(),
+            timeout_secs: claude.timeout_secs.unwrap_or(DEFAULT_CLAUDE_TIMEOUT),
+            max_tokens: claude.max_tokens.unwrap_or(DEFAULT_CLAUDE_MAX_TOKENS),
+        })
+    }
+
+    fn body(&self, prompt: &LlmPrompt, stream: bool) -> Value {
+        let (system, user) = prompt.system_and_user();
+        let mut body = json!({
+          "model": self.model,
+          "max_tokens": self.max_tokens,
+          "messages": [{"role": "user", "content": user}],
+          "temperature": prompt.temperature,
+          "stream": stream
+        });
+        if let Some(system) = system {
+            body["system"] = Value::from(system);
+        }
+        body
+    }
+
+    // Live translation sends the same request with `stream`, see `claude_stream_delta`.
+    pub fn post(&self, prompt: &LlmPrompt, stream: bool) -> Result<RequestBuilder, String> {
+        let client = http::client(Some(self.timeout_secs))?;
+        Ok(client
+            .post(self.endpoint.as_str())
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", DEFAULT_CLAUDE_API_VERSION)
+            .json(&self.body(prompt, stream)))
+    }
+
+    async fn request(&self, prompt: &LlmPrompt) -> Result<String, String> {
+        let response = self
+            .post(prompt, false)?
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        let value: Value = response.json().await.map_err(|err| err.to_string())?;
+        if !status.is_success() {
+            return Err(redact(&value.to_string()).into_owned());
+        }
+
+        let text = claude_reply_text(&value)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| "Claude response missing text".to_string())?;
+        record_usage(self, prompt, TokenUsage::from_reply(&value), &text);
+        Ok(text)
+    }
+}
+
+impl LlmProvider for ClaudeProvider {
+    fn name(&self) -> &'static str {
+        "claude"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn has_system_role(&self) -> bool {
+        true
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(self.request(prompt))
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let request = self.post(prompt, true)?;
+            let (text, reported) =
+                read_stream(request, StreamFraming::Sse, claude_stream_delta, on_chunk).await?;
+            record_usage(self, prompt, reported, &text);
+            Ok(text)
+        })
+    }
+}
+
+// Joins the text blocks of a messages API reply.
+fn claude_reply_text(value: &Value) -> Option<String> {
+    let blocks = value.get("content")?.as_array()?;
+    Some(
+        blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect(),
+    )
+}
+
+// Text of a `content_block_delta` stream event; other events carry none.
+pub fn claude_stream_delta(value: &Value) -> Option<String> {
+    if value.get("type").and_then(|t| t.as_str()) != Some("content_block_delta") {
+        return None;
+    }
+    value
+        .pointer("/delta/text")
+        .and_then(|text| text.as_str())
+        .map(str::to_string)
+}
+
+pub struct GeminiProvider {
+    api_key: String,
+    model: String,
+    // `{baseUrl}/models/{model}`; the method is appended per request.
+    endpoint: String,
+    timeout_secs: u64,
+}
+
+impl GeminiProvider {
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        let gemini = config.gemini.clone().unwrap_or_default();
+        let api_key = gemini
+            .api_key
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| "Gemini apiKey is required".to_string())?;
+        let model = gemini
+            .model
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_GEMINI_MODEL.to_string());
+        let base_url = gemini
+            .base_url
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_GEMINI_BASE_URL.to_string());
+        Ok(Self {
+            api_key,
+            endpoint: format!("{}/models/{model}", base_url.trim_end_matches('/')),
+            model,
+            timeout_secs: gemini.timeout_secs.unwrap_or(DEFAULT_GEMINI_TIMEOUT),
+        })
+    }
+
+    fn body(prompt: &LlmPrompt) -> Value {
+        let (system, user) = prompt.system_and_user();
+        let mut body = json!({
+          "contents": [{"role": "user", "parts": [{"text": user}]}],
+          "generationConfig": {"temperature": prompt.temperature}
+        });
+        if let Some(system) = system {
+            body["systemInstruction"] = json!({"parts": [{"text": system}]});
+        }
+        body
+    }
+
+    // Streaming uses server-sent events; every event is a partial reply, see `gemini_reply_text`.
+    pub fn post(&self, prompt: &LlmPrompt, stream: bool) -> Result<RequestBuilder, String> {
+        let url = if stream {
+            format!("{}:streamGenerateContent?alt=sse", self.endpoint)
+        } else {
+            format!("{}:generateContent", self.endpoint)
+        };
+        let client = http::client(Some(self.timeout_secs))?;
+        Ok(client
+            .post(url)
+            .header("x-goog-api-key", &self.api_key)
+            .json(&Self::body(prompt)))
+    }
+
+    async fn request(&self, prompt: &LlmPrompt) -> Result<String, String> {
+        let response = self
+            .post(prompt, false)?
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        let value: Value = response.json().await.map_err(|err| err.to_string())?;
+        if !status.is_success() {
+            return Err(redact(&value.to_string()).into_owned());
+        }
+
+        let text = gemini_reply_text(&value)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| "Gemini response missing text".to_string())?;
+        record_usage(self, prompt, TokenUsage::from_reply(&value), &text);
+        Ok(text)
+    }
+}
+
+impl LlmProvider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn has_system_role(&self) -> bool {
+        true
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(self.request(prompt))
+    }
+
+    fn complete_streaming<'a>(
+        &'a self,
+        prompt: &'a LlmPrompt,
+        on_chunk: &'a mut ChunkSink<'a>,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let request = self.post(prompt, true)?;
+            let (text, reported) =
+                read_stream(request, StreamFraming::Sse, gemini_reply_text, on_chunk).await?;
+            record_usage(self, prompt, reported, &text);
+            Ok(text)
+        })
+    }
+}
+
+// Joins the parts of the first candidate, for whole replies and stream events alike.
+pub fn gemini_reply_text(value: &Value) -> Option<String> {
+    let parts = value.pointer("/candidates/0/content/parts")?.as_array()?;
+    Some(
+        parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+            .collect(),
+    )
+}
+
+pub struct LocalGptProvider {
+    endpoint: String,
+    project_id: String,
+    timeout_secs: u64,
+}
+
+impl LocalGptProvider {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let local_gpt = config.local_gpt.clone().unwrap_or_default();
+        if local_gpt.enabled == Some(false) {
+            warn!(
+                "[local-gpt-direct] config localGpt.enabled=false, but proceeding because local-gpt provider is selected"
+            );
+        }
+        let base_url = local_gpt
+            .base_url
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_LOCAL_GPT_BASE_URL.to_string());
+        let project_id = local_gpt
+            .project_id
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| DEFAULT_LOCAL_GPT_PROJECT_ID.to_string());
+        Self {
+            endpoint: format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                DEFAULT_LOCAL_GPT_DIRECT_PATH.trim_start_matches('/')
+            ),
+            project_id,
+            timeout_secs: local_gpt.timeout_secs.unwrap_or(DEFAULT_LOCAL_GPT_TIMEOUT),
+        }
+    }
+
+    async fn request(&self, prompt: &LlmPrompt) -> Result<String, String> {
+        let prompt = prompt.joined();
+        info!(
+            "[local-gpt-direct] request project_id={} timeout_secs={} prompt_preview={}",
+            self.project_id,
+            self.timeout_secs,
+            compact_log_text(&prompt, 240)
+        );
+
+        let client = http::client(Some(self.timeout_secs))?;
+        let response = client
+            .post(self.endpoint.as_str())
+            .json(&json!({
+              "project_id": self.project_id.as_str(),
+              "project-id": self.project_id.as_str(),
+              "prompt": prompt
+            }))
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        let raw = response.text().await.map_err(|err| err.to_string())?;
+        let (value, parsed_json) = match serde_json::from_str::<Value>(&raw) {
+            Ok(value) => (value, true),
+            Err(_) => (json!({ "message": raw }), false),
+        };
+        if !parsed_json {
+            warn!(
+                "[local-gpt-direct] non-json response status={} raw_preview={}",
+                status.as_u16(),
+                compact_log_text(&raw, 300)
+            );
+        }
+        let message = value
+            .get("message")
+            .and_then(|field| field.as_str())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .unwrap_or_else(|| value.to_string());
+        let timed_out = value
+            .get("timed_out")
+            .and_then(|field| field.as_bool())
+            .unwrap_or(false);
+        let ok = value
+            .get("ok")
+            .and_then(|field| field.as_bool())
+            .unwrap_or(status.is_success());
+        let result = value
+            .get("result")
+            .and_then(|field| field.as_str())
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty());
+        let request_id = value
+            .get("request_id")
+            .and_then(|field| field.as_str())
+            .unwrap_or("");
+        let viewer_url = value
+            .get("viewer_url")
+            .and_then(|field| field.as_str())
+            .unwrap_or("");
+        let result_chars = result
+            .as_ref()
+            .map(|text| text.chars().count())
+            .unwrap_or(0usize);
+        let result_preview = result
+            .as_deref()
+            .map(|text| compact_log_text(text, 240))
+            .unwrap_or_default();
+        info!(
+            "[local-gpt-direct] response status={} ok={} timed_out={} request_id={} viewer_url={} message={} result_chars={} result_preview={}",
+            status.as_u16(),
+            ok,
+            timed_out,
+            request_id,
+            viewer_url,
+            compact_log_text(&message, 180),
+            result_chars,
+            result_preview
+        );
+
+        if ok {
+            return result.ok_or_else(|| "local-gpt response missing result".to_string());
+        }
+
+        if timed_out {
+            if let Some(partial) = result {
+                warn!(
+                    "local-gpt timed out, returning partial result chars={}",
+                    partial.chars().count()
+                );
+                return Ok(partial);
+            }
+        }
+
+        Err(redact(&message).into_owned())
+    }
+}
+
+impl LlmProvider for LocalGptProvider {
+    fn name(&self) -> &'static str {
+        "local-gpt"
+    }
+
+    fn model(&self) -> &str {
+        "-"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    // Local GPT reports no token counts.
+    fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let text = self.request(prompt).await?;
+            record_usage(self, prompt, None, &text);
+            Ok(text)
+        })
+    }
+}
+
+// Sends a streaming request and feeds the text that `delta` finds in each event to `on_chunk`.
+// Also returns the token usage the events reported, if any.
+async fn read_stream(
+    request: RequestBuilder,
+    framing: StreamFraming,
+    delta: fn(&Value) -> Option<String>,
+    on_chunk: &mut ChunkSink<'_>,
+) -> Result<(String, Option<TokenUsage>), String> {
+    let response = request.send().await.map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.map_err(|err| err.to_string())?;
+        return Err(redact(&body).into_owned());
+    }
+
+    let mut text = StreamText::new(framing, delta);
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|err| err.to_string())?;
+        text.push(&chunk, on_chunk);
+    }
+    text.finish(on_chunk)
+}
+
+// The text and usage of a streamed reply, body chunk by body chunk. Events that are not JSON
+// (OpenAI's `[DONE]`) or carry no text are skipped.
+struct StreamText {
+    events: EventDecoder,
+    delta: fn(&Value) -> Option<String>,
+    full: String,
+    reported: Option<TokenUsage>,
+}
+
+impl StreamText {
+    fn new(framing: StreamFraming, delta: fn(&Value) -> Option<String>) -> Self {
+        Self {
+            events: EventDecoder::new(framing),
+            delta,
+            full: String::new(),
+            reported: None,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8], on_chunk: &mut ChunkSink<'_>) {
+        self.events.push(chunk);
+        while let Some(data) = self.events.next_event() {
+            self.handle(&data, on_chunk);
+        }
+    }
+
+    fn finish(
+        mut self,
+        on_chunk: &mut ChunkSink<'_>,
+    ) -> Result<(String, Option<TokenUsage>), String> {
+        if let Some(data) = self.events.finish() {
+            self.handle(&data, on_chunk);
+        }
+        let full = self.full.trim().to_string();
+        if full.is_empty() {
+            return Err("streamed response missing text".to_string());
+        }
+        Ok((full, self.reported))
+    }
+
+    fn handle(&mut self, data: &str, on_chunk: &mut ChunkSink<'_>) {
+        let data = data.trim();
+        if data.is_empty() {
+            return;
+        }
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            debug!("[llm-stream] skipped event {}", compact_log_text(data, 120));
+            return;
+        };
+        if let Some(found) = TokenUsage::from_reply(&value) {
+            self.reported = Some(self.reported.map_or(found, |usage| usage.merge(found)));
+        }
+        if let Some(text) = (self.delta)(&value).filter(|text| !text.is_empty()) {
+            self.full.push_str(&text);
+            on_chunk(&text);
+        }
+    }
+}
+
+pub fn extract_response_text(value: &Value) -> Option<String> {
+    if let Some(text) = value.get("output_text").and_then(|field| field.as_str()) {
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            return Some(trimmed.to_string());
+        }
+    }
+    if let Some(output) = value.get("output").and_then(|field| field.as_array()) {
+        for item in output {
+            if let Some(content) = item.get("content").and_then(|field| field.as_array()) {
+                for part in content {
+                    if part.get("type").and_then(|t| t.as_str()) == Some("output_text") {
+                        if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
+                            let trimmed = text.trim();
+                            if !trimmed.is_empty() {
+                                return Some(trimmed.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn compact_log_text(text: &str, max_chars: usize) -> String {
+    let compact = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut output = compact.chars().take(max_chars).collect::<String>();
+    if compact.chars().count() > max_chars {
+        output.push_str("...");
+    }
+    output
+}
+
+// Canned replies for unit tests; records every prompt it was sent.
+#[cfg(test)]
+pub mod mock {
+    use super::{LlmPrompt, LlmProvider};
+    use futures_util::future::BoxFuture;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    pub struct MockLlm {
+        system_role: bool,
+        replies: Mutex<VecDeque<Result<String, String>>>,
+        prompts: Mutex<Vec<LlmPrompt>>,
+    }
+
+    impl MockLlm {
+        pub fn new(replies: &[&str]) -> Self {
+            Self {
+                system_role: false,
+                replies: Mutex::new(replies.iter().map(|reply| Ok(reply.to_string())).collect()),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+
+        pub fn with_system_role(mut self) -> Self {
+            self.system_role = true;
+            self
+        }
+
+        pub fn prompts(&self) -> Vec<LlmPrompt> {
+            self.prompts.lock().unwrap().clone()
+        }
+    }
+
+    impl LlmProvider for MockLlm {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn model(&self) -> &str {
+            "mock"
+        }
+
+        fn endpoint(&self) -> &str {
+            "-"
+        }
+
+        fn has_system_role(&self) -> bool {
+            self.system_role
+        }
+
+        fn complete<'a>(&'a self, prompt: &'a LlmPrompt) -> BoxFuture<'a, Result<String, String>> {
+            self.prompts.lock().unwrap().push(prompt.clone());
+            let reply = self
+                .replies
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Err("mock has no reply left".to_string()));
+            Box::pin(async move { reply })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockLlm;
+    use super::{
+        claude_reply_text, claude_stream_delta, extract_response_text, gemini_reply_text, generate,
+        metered, ollama_stream_delta, openai_stream_delta, GeminiProvider, LlmPrompt, StreamText,
+    };
+    use crate::runtime::block_on;
+    use crate::stream::StreamFraming;
+    use crate::usage::{TokenUsage, UsageState};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn generate_uses_a_system_message_only_where_supported() {
+        let chat = MockLlm::new(&["answer"]).with_system_role();
+        let plain = MockLlm::new(&["answer"]);
+        block_on(async {
+            assert_eq!(generate(&chat, "question", "rag").await.unwrap(), "answer");
+            assert_eq!(generate(&plain, "question", "rag").await.unwrap(), "answer");
+            assert!(generate(&plain, "again", "rag").await.is_err());
+        });
+
+        let chat_prompt = &chat.prompts()[0];
+        assert_eq!(chat_prompt.input.as_deref(), Some("question"));
+        assert_eq!(chat_prompt.feature, "rag");
+        let plain_prompt = &plain.prompts()[0];
+        assert_eq!(plain_prompt.instructions, "question");
+        assert!(plain_prompt.input.is_none());
+        assert!(plain_prompt.usage.is_none());
+
+        let mock = Arc::new(MockLlm::new(&["answer"]));
+        let llm = metered(Box::new(Arc::clone(&mock)), Some(UsageState::new()));
+        block_on(generate(llm.as_ref(), "question", "summary")).unwrap();
+        assert!(mock.prompts()[0].usage.is_some());
+
+        let value =
+            json!({ "output": [{ "content": [{ "type": "output_text", "text": " hi " }] }] });
+        assert_eq!(extract_response_text(&value).as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn reads_replies_and_stream_events_of_each_provider() {
+        let reply = json!({ "content": [
+            { "type": "text", "text": "Hello" },
+            { "type": "tool_use", "name": "x" },
+            { "type": "text", "text": " there" }
+        ] });
+        assert_eq!(claude_reply_text(&reply).as_deref(), Some("Hello there"));
+        let delta = json!({ "type": "content_block_delta", "delta": { "type": "text_delta", "text": "Hel" } });
+        assert_eq!(claude_stream_delta(&delta).as_deref(), Some("Hel"));
+        assert!(claude_stream_delta(&json!({ "type": "message_stop" })).is_none());
+        let delta = json!({ "type": "response.output_text.delta", "delta": "Hi" });
+        assert_eq!(openai_stream_delta(&delta).as_deref(), Some("Hi"));
+        assert!(openai_stream_delta(&json!({ "type": "response.completed" })).is_none());
+        let line = json!({ "response": "Ha", "done": false });
+        assert_eq!(ollama_stream_delta(&line).as_deref(), Some("Ha"));
+
+        let reply = json!({ "candidates": [{ "content": { "parts": [{ "text": "Bon" }, { "text": "jour" }] } }] });
+        assert_eq!(gemini_reply_text(&reply).as_deref(), Some("Bonjour"));
+        assert!(gemini_reply_text(&json!({ "promptFeedback": {} })).is_none());
+
+        let body = GeminiProvider::body(&LlmPrompt::new("Translate").with_input("hi"));
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "Translate");
+        assert_eq!(body["contents"][0]["parts"][0]["text"], "hi");
+        let body = GeminiProvider::body(&LlmPrompt::new("Say hi"));
+        assert!(body.get("systemInstruction").is_none());
+        assert_eq!(body["contents"][0]["parts"][0]["text"], "Say hi");
+    }
+
+    #[test]
+    fn decodes_streamed_replies_split_across_body_chunks() {
+        let decode = |framing, delta, body: &[u8], split: usize| {
+            let mut chunks = Vec::new();
+            let mut on_chunk = |chunk: &str| chunks.push(chunk.to_string());
+            let mut text = StreamText::new(framing, delta);
+            text.push(&body[..split], &mut on_chunk);
+            text.push(&body[split..], &mut on_chunk);
+            let result = text.finish(&mut on_chunk);
+            (result, chunks)
+        };
+
+        let openai = concat!(
+            "event: response.output_text.delta\n",
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"こんに\"}\n\n",
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"ちは\"}\n\n",
+            "data: {\"type\":\"response.completed\",\"response\":{\"usage\":",
+            "{\"input_tokens\":12,\"output_tokens\":3}}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        // Splits inside the first multi-byte character.
+        let (result, chunks) = decode(
+            StreamFraming::Sse,
+            openai_stream_delta,
+            openai.as_bytes(),
+            openai.find("こ").unwrap() + 1,
+        );
+        let usage = TokenUsage {
+            input_tokens: 12,
+            output_tokens: 3,
+        };
+        assert_eq!(result.unwrap(), ("こんにちは".to_string(), Some(usage)));
+        assert_eq!(chunks, ["こんに", "ちは"]);
+
+        let ollama = concat!(
+            "{\"response\":\"Hel\",\"done\":false}\n",
+            "{\"response\":\"lo\",\"done\":false}\n",
+            "{\"response\":\"\",\"done\":true,\"prompt_eval_count\":9,\"eval_count\":2}",
+        );
+        let (result, chunks) = decode(
+            StreamFraming::Lines,
+            ollama_stream_delta,
+            ollama.as_bytes(),
+            20,
+        );
+        let usage = TokenUsage {
+            input_tokens: 9,
+            output_tokens: 2,
+        };
+        assert_eq!(result.unwrap(), ("Hello".to_string(), Some(usage)));
+        assert_eq!(chunks, ["Hel", "lo"]);
+
+        let (result, chunks) = decode(
+            StreamFraming::Sse,
+            openai_stream_delta,
+            b"data: [DONE]\n\n",
+            3,
+        );
+        assert!(result.is_err());
+        assert!(chunks.is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::app_config::{load_config, LoggingConfig};
+use crate::defaults::{
+    DEFAULT_LOG_LEVEL, DEFAULT_LOG_MAX_FILES, DEFAULT_RECENT_LOG_LIMIT, RECENT_LOG_CAPACITY,
+};
+use crate::redact::{self, redact};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+const LOG_FILE_PREFIX: &str = "ai-shepherd";
+const APP_TARGET: &str = env!("CARGO_CRATE_NAME");
+const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+// JSON lines of the latest events, kept for the in-app log viewer.
+static RECENT: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_CAPACITY)));
+
+#[derive(Clone, Copy)]
+struct RecentLogs;
+
+impl Write for RecentLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        if let Ok(mut guard) = RECENT.lock() {
+            for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                if guard.len() == RECENT_LOG_CAPACITY {
+                    guard.pop_front();
+                }
+                guard.push_back(line.to_string());
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogs;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        *self
+    }
+}
+
+// Masks API keys and tokens in each formatted event before it reaches a sink.
+struct Redacted<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacted<M> {
+    type Writer = RedactedWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedWriter(self.0.make_writer())
+    }
+}
+
+struct RedactedWriter<W>(W);
+
+impl<W: Write> Write for RedactedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+// Human-readable lines go to stderr; JSON lines go to a daily rotating file and the in-memory buffer.
+// `RUST_LOG` takes precedence over the configured levels.
+pub fn init(app: &AppHandle) {
+    let app_config = load_config().ok();
+    if let Some(app_config) = &app_config {
+        redact::configure(app_config);
+    }
+    let config = app_config.and_then(|cfg| cfg.logging).unwrap_or_default();
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(filter_directives(&config)))
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+
+    let max_files = config.max_files.unwrap_or(DEFAULT_LOG_MAX_FILES).max(1);
+    let file = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| err.to_string())
+        .and_then(|base| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .filename_suffix("log")
+                .max_log_files(max_files)
+                .build(base.join("logs"))
+                .map_err(|err| err.to_string())
+        });
+    let (file, file_error) = match file {
+        Ok(file) => (Some(file), None),
+        Err(err) => (None, Some(err)),
+    };
+
+    let console = tracing_subscriber::fmt::layer().with_writer(Redacted(std::io::stderr));
+    let recent = tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_writer(Redacted(RecentLogs));
+    let file = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(Redacted(file))
+    });
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(console)
+        .with(recent)
+        .with(file)
+        .try_init()
+        .is_err()
+    {
+        return;
+    }
+    if let Some(err) = file_error {
+        tracing::warn!("log file disabled: {err}");
+    }
+}
+
+// `level` applies to the app itself and dependencies stay at warn; `modules` keys are app module
+// paths such as `audio::manager`.
+fn filter_directives(config: &LoggingConfig) -> String {
+    let level = normalize_level(config.level.as_deref()).unwrap_or(DEFAULT_LOG_LEVEL);
+    let mut directives = vec!["warn".to_string(), format!("{APP_TARGET}={level}")];
+    let mut modules = config
+        .modules
+        .iter()
+        .flatten()
+        .filter_map(|(module, level)| {
+            let module = module.trim().trim_start_matches("crate::");
+            let level = normalize_level(Some(level))?;
+            (!module.is_empty()).then(|| format!("{APP_TARGET}::{module}={level}"))
+        })
+        .collect::<Vec<_>>();
+    modules.sort();
+    directives.extend(modules);
+    directives.join(",")
+}
+
+pub fn normalize_level(level: Option<&str>) -> Option<&'static str> {
+    match level?.trim().to_lowercase().as_str() {
+        "trace" => Some("trace"),
+        "debug" => Some("debug"),
+        "info" => Some("info"),
+        "warn" | "warning" => Some("warn"),
+        "error" => Some("error"),
+        "off" => Some("off"),
+        _ => None,
+    }
+}
+
+fn level_rank(level: &str) -> usize {
+    let level = level.to_lowercase();
+    LEVELS.iter().position(|known| *known == level).unwrap_or(0)
+}
+
+#[tauri::command]
+pub fn get_recent_logs(limit: Option<usize>, level: Option<String>) -> Vec<Value> {
+    let limit = limit.unwrap_or(DEFAULT_RECENT_LOG_LIMIT);
+    let min_rank = normalize_level(level.as_deref()).map_or(0, level_rank);
+    let lines = RECENT
+        .lock()
+        .map(|guard| guard.iter().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut entries = lines
+        .iter()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|entry| {
+            entry
+                .get("level")
+                .and_then(Value::as_str)
+                .is_some_and(|value| level_rank(value) >= min_rank)
+        })
+        .take(limit)
+        .collect::<Vec<_>>();
+    entries.reverse();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_directives, normalize_level, APP_TARGET};
+    use crate::app_config::LoggingConfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn builds_per_module_directives() {
+        let config = LoggingConfig {
+            level: Some("Debug".to_string()),
+            modules: Some(HashMap::from([
+                ("audio::manager".to_string(), "trace".to_string()),
+                ("crate::rag".to_string(), "warning".to_string()),
+                ("remote".to_string(), "loud".to_string()),
+            ])),
+            max_files: None,
+            redact_patterns: None,
+        };
+        assert_eq!(
+            filter_directives(&config),
+            format!(
+                "warn,{APP_TARGET}=debug,{APP_TARGET}::audio::manager=trace,{APP_TARGET}::rag=warn"
+            )
+        );
+        assert_eq!(normalize_level(Some("bogus")), None);
+    }
+}
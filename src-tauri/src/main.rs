@@ -1,31 +1,123 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod acceleration;
 mod app_config;
 mod asr;
 mod audio;
+mod calendar;
+mod cancel;
+mod cli;
+mod config_commands;
+mod config_migration;
+mod consent;
+mod deepgram;
+mod defaults;
+mod events;
+mod http;
+mod interpreter;
+mod itn;
+mod journal;
+mod live_draft;
+mod llm;
+mod logging;
+mod mqtt;
+mod notifications;
+mod offline_asr;
+mod pii;
+mod punctuation;
 mod rag;
+mod rag_batch;
+mod realtime_asr;
+mod redact;
+mod remote;
+mod runtime;
+mod scheduler;
+mod session_archive;
+mod session_stats;
+mod settings_bundle;
+mod share;
+mod stream;
+mod study_export;
+mod summary;
+mod templates;
+mod timestamps;
 mod transcribe;
 mod translate;
+mod translation_cache;
+mod two_pass;
+mod updates;
+mod usage;
+mod webhooks;
+mod whisper_benchmark;
+mod whisper_inproc;
+mod whisper_models;
+mod whisper_pipe;
 mod whisper_server;
+mod word_filter;
 
-use app_config::{load_config, LocalGptConfig, OllamaConfig, TranslateConfig};
+use acceleration::get_acceleration_status;
+use app_config::load_config;
 use asr::AsrState;
-use audio::{CaptureManager, SegmentInfo};
+use audio::devices::{list_audio_devices, set_capture_device};
+use audio::playback::{PlaybackChunk, PlaybackState, DEFAULT_CHUNK_MS};
+use audio::stats::CaptureStats;
+use audio::store::DEFAULT_SEGMENT_PAGE_SIZE;
+use audio::waveform::{Waveform, DEFAULT_BUCKETS};
+use audio::{
+    ArmedStatus, CaptureManager, RediarizeReport, RetranscribeReport, SegmentInfo, SessionSummary,
+};
 use chrono::Local;
+use config_commands::{
+    describe_config, get_config, get_config_section, set_config, set_config_section,
+};
+use defaults::{
+    DEFAULT_LIVE_PROMPT, DEFAULT_OLLAMA_BASE_URL, DEFAULT_OLLAMA_MODEL, DEFAULT_OLLAMA_TIMEOUT,
+    DEFAULT_OPENAI_CHAT_BASE_URL, DEFAULT_OPENAI_CHAT_MODEL, DEFAULT_OPENAI_CHAT_TIMEOUT,
+    DEFAULT_TRANSLATE_PROVIDER, DEFAULT_TRANSLATE_TARGET_LANGUAGE,
+};
+use events::{emit_ui_event, EventHub};
 use futures_util::StreamExt;
+use interpreter::{get_interpreter_mode, set_interpreter_mode};
+use journal::replay_event_journal;
+use live_draft::DraftUpdate;
+use logging::get_recent_logs;
 use rag::{
-    rag_index_add_files, rag_index_remove_files, rag_index_sync_project, rag_pick_folder,
-    rag_project_create, rag_project_delete, rag_project_list, rag_search, RagState,
+    rag_index_add_files, rag_index_remove_files, rag_index_sync_project, rag_is_ready,
+    rag_pick_folder, rag_project_create, rag_project_delete, rag_project_list,
+    rag_project_set_filters, rag_search, ChunkHit, RagState,
 };
+use rag_batch::rag_ask_batch;
+use redact::redact;
+use remote::{get_remote_info, list_remote_actions, start_companion_pairing, RemoteState};
+use scheduler::get_scheduled_meeting;
 use serde::{Deserialize, Serialize};
+use session_archive::{
+    archive_session, list_session_archives, meeting_export_audio, restore_session,
+};
+use session_stats::get_session_stats;
+use settings_bundle::{export_settings_bundle, import_settings_bundle};
+use share::{preview_meeting_share, send_meeting_share};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Instant;
+use stream::{LineDecoder, SseDecoder};
+use study_export::export_study_subtitles;
+use summary::{get_session_summary, summarize_session};
 use tauri::webview::WebviewBuilder;
 use tauri::{
-    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, State, Webview, WebviewUrl,
+    AppHandle, LogicalPosition, LogicalSize, Manager, RunEvent, State, Webview, WebviewUrl,
     WebviewWindowBuilder, Window, WindowEvent,
 };
+use templates::{list_meeting_templates, start_session_with_template};
+use timestamps::{format_timestamps, now_rfc3339};
+use tracing::{debug, error, info, warn};
+use translation_cache::clear_translation_cache;
+use updates::check_for_updates;
+use usage::{get_usage_stats, TokenUsage, UsageState};
+use whisper_inproc::WhisperInprocManager;
+use whisper_models::{download_whisper_model, list_whisper_models, set_whisper_model};
+use whisper_pipe::WhisperPipeManager;
 use whisper_server::WhisperServerManager;
+use word_filter::{apply_to, WordFilter};
 
 const OUTPUT_LABEL: &str = "output";
 const OUTPUT_URL: &str = "blank.html";
@@ -33,18 +125,6 @@ const INTRO_URL: &str = "intro.html";
 const MIN_TOP_HEIGHT: f64 = 190.0;
 const MAX_TOP_HEIGHT: f64 = 10_000.0;
 const MIN_BOTTOM_HEIGHT: f64 = 100.0;
-const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
-const DEFAULT_OLLAMA_TIMEOUT: u64 = 600;
-const DEFAULT_OLLAMA_MODEL: &str = "gpt-oss:20b";
-const DEFAULT_OPENAI_CHAT_MODEL: &str = "gpt-4.1-mini";
-const DEFAULT_OPENAI_CHAT_BASE_URL: &str = "https://api.openai.com/v1/responses";
-const DEFAULT_OPENAI_CHAT_TIMEOUT: u64 = 120;
-const DEFAULT_LOCAL_GPT_BASE_URL: &str = "http://127.0.0.1:8787";
-const DEFAULT_LOCAL_GPT_TIMEOUT: u64 = 240;
-const DEFAULT_LOCAL_GPT_DIRECT_PATH: &str = "/local-gpt-sse/direct";
-const DEFAULT_LOCAL_GPT_PROJECT_ID: &str = "g-p-698c11cf2bc08191b07e28128883fcbb-testapi";
-const DEFAULT_LIVE_PROMPT: &str =
-    "Translate the following text to {target_language}. Output only the translated text.";
 
 #[derive(Debug, Deserialize)]
 struct LlmRequest {
@@ -61,9 +141,11 @@ struct RagAskRequest {
     project_ids: Vec<String>,
     top_k: Option<usize>,
     allow_out_of_context: Option<bool>,
+    // Runs the answer through the translation provider into the configured target language.
+    translate_answer: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct RagAnswerReference {
     index: usize,
     score: f32,
@@ -72,11 +154,15 @@ struct RagAnswerReference {
     snippet: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct RagAnswerResponse {
     provider: String,
     answer: String,
     references: Vec<RagAnswerReference>,
+    // Set when `answer` was translated; `original_answer` is the model's own wording.
+    answer_language: Option<String>,
+    original_answer: Option<String>,
+    translation_error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -202,12 +288,6 @@ fn to_boxed_error(message: String) -> Box<dyn std::error::Error> {
     Box::new(std::io::Error::new(std::io::ErrorKind::Other, message))
 }
 
-fn emit_output<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T) {
-    if let Some(webview) = app.get_webview(OUTPUT_LABEL) {
-        let _ = webview.emit(event, payload);
-    }
-}
-
 fn resolve_live_prompt_template(config: &app_config::AppConfig) -> String {
     config
         .translate
@@ -229,15 +309,7 @@ fn resolve_translate_settings(
     provider_override: Option<String>,
 ) -> Result<(String, String, app_config::AppConfig), String> {
     let config = load_config()?;
-    let translate_config = config.translate.clone().unwrap_or(TranslateConfig {
-        enabled: Some(true),
-        provider: Some("ollama".to_string()),
-        target_language: Some("zh".to_string()),
-        segment_batch_size: None,
-        segment_single_prompt: None,
-        segment_batch_prompt: None,
-        live_prompt: None,
-    });
+    let translate_config = config.translate.clone().unwrap_or_default();
 
     if translate_config.enabled == Some(false) {
         return Err("translation disabled".to_string());
@@ -246,11 +318,11 @@ fn resolve_translate_settings(
     let provider = provider_override
         .filter(|value| !value.trim().is_empty())
         .or(translate_config.provider)
-        .unwrap_or_else(|| "ollama".to_string());
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_PROVIDER.to_string());
     let provider = normalize_translate_provider(&provider);
     let target_language = translate_config
         .target_language
-        .unwrap_or_else(|| "zh".to_string());
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string());
 
     Ok((provider, target_language, config))
 }
@@ -287,11 +359,11 @@ async fn set_top_height(
 }
 
 #[tauri::command]
-async fn llm_generate(request: LlmRequest) -> Result<String, String> {
+async fn llm_generate(app: AppHandle, request: LlmRequest) -> Result<String, String> {
     let provider = request.provider.to_lowercase();
     match provider.as_str() {
-        "openai" => call_openai(request).await,
-        "ollama" => call_ollama(request).await,
+        "openai" => call_openai(&app, request).await,
+        "ollama" => call_ollama(&app, request).await,
         _ => Err(format!("unknown provider: {}", provider)),
     }
 }
@@ -302,6 +374,32 @@ async fn rag_ask_with_provider(
     rag_state: State<'_, Arc<RagState>>,
     provider_state: State<'_, TranslateProviderState>,
     request: RagAskRequest,
+) -> Result<RagAnswerResponse, String> {
+    let provider = provider_state
+        .provider
+        .lock()
+        .map(|value| normalize_translate_provider(&value))
+        .unwrap_or_else(|_| "ollama".to_string());
+    answer_rag_question(&app, rag_state.inner().clone(), provider, request).await
+}
+
+fn selected_translate_provider(app: &AppHandle) -> String {
+    app.try_state::<TranslateProviderState>()
+        .and_then(|state| {
+            state
+                .provider
+                .lock()
+                .ok()
+                .map(|value| normalize_translate_provider(&value))
+        })
+        .unwrap_or_else(|| "ollama".to_string())
+}
+
+async fn answer_rag_question(
+    app: &AppHandle,
+    state: Arc<RagState>,
+    provider: String,
+    request: RagAskRequest,
 ) -> Result<RagAnswerResponse, String> {
     let query = request.query.trim().to_string();
     if query.is_empty() {
@@ -312,13 +410,8 @@ async fn rag_ask_with_provider(
     }
     let top_k = request.top_k.unwrap_or(8).clamp(1, 20);
     let allow_out_of_context = request.allow_out_of_context.unwrap_or(false);
-    let provider = provider_state
-        .provider
-        .lock()
-        .map(|value| normalize_translate_provider(&value))
-        .unwrap_or_else(|_| "ollama".to_string());
+    let translate_answer = request.translate_answer.unwrap_or(false);
 
-    let state = rag_state.inner().clone();
     let app_handle = app.clone();
     let search_query = query.clone();
     let project_ids = request.project_ids;
@@ -330,6 +423,26 @@ async fn rag_ask_with_provider(
     .await
     .map_err(|err| err.to_string())??;
 
+    answer_with_hits(
+        &app,
+        provider,
+        &query,
+        &hits,
+        allow_out_of_context,
+        translate_answer,
+    )
+    .await
+}
+
+// Prompts the model with already retrieved chunks; `rag_ask_batch` shares hits across questions.
+async fn answer_with_hits(
+    app: &AppHandle,
+    provider: String,
+    query: &str,
+    hits: &[ChunkHit],
+    allow_out_of_context: bool,
+    translate_answer: bool,
+) -> Result<RagAnswerResponse, String> {
     let context = if hits.is_empty() {
         "No relevant context found in local project index.".to_string()
     } else {
@@ -368,7 +481,24 @@ async fn rag_ask_with_provider(
     };
 
     let config = load_config()?;
-    let answer = generate_with_selected_provider(&provider, &prompt, &config).await?;
+    let mut answer =
+        generate_with_selected_provider(app, &provider, &prompt, &config, "rag").await?;
+    let mut answer_language = None;
+    let mut original_answer = None;
+    let mut translation_error = None;
+    // A failed translation still returns the untranslated answer.
+    if translate_answer && !answer.trim().is_empty() {
+        match translate::translate_answer(app, &answer, Some(provider.clone())).await {
+            Ok(translated) => {
+                answer_language = Some(translate::target_language());
+                original_answer = Some(std::mem::replace(&mut answer, translated));
+            }
+            Err(err) => {
+                warn!("rag answer translation failed: {err}");
+                translation_error = Some(err);
+            }
+        }
+    }
     let references = hits
         .iter()
         .enumerate()
@@ -385,25 +515,29 @@ async fn rag_ask_with_provider(
         provider,
         answer,
         references,
+        answer_language,
+        original_answer,
+        translation_error,
     })
 }
 
 #[tauri::command]
 async fn translate_live(
     app: AppHandle,
+    capture: State<'_, CaptureManager>,
     text: String,
     provider: Option<String>,
     name: Option<String>,
     order: Option<u64>,
 ) -> Result<(), String> {
     let source = text.trim().to_string();
-    if source.is_empty() {
+    if source.is_empty() || capture.is_transcription_only() {
         return Ok(());
     }
 
     let (provider, target, config) = resolve_translate_settings(provider)?;
     let order = order.unwrap_or_else(|| Local::now().timestamp_millis().max(0) as u64);
-    eprintln!(
+    info!(
         "translate_live start provider={} text={}",
         provider,
         source.chars().take(60).collect::<String>()
@@ -411,9 +545,9 @@ async fn translate_live(
     let id = name
         .filter(|value| !value.trim().is_empty())
         .unwrap_or_else(|| format!("live-{}", Local::now().timestamp_millis()));
-    let created_at = Local::now().to_rfc3339();
+    let created_at = now_rfc3339();
 
-    emit_output(
+    emit_ui_event(
         &app,
         "live_translation_start",
         LiveTranslationStart {
@@ -426,36 +560,91 @@ async fn translate_live(
         },
     );
 
+    // Chunks are filtered one by one; the done event carries the whole text filtered again.
+    let word_filter = WordFilter::from_config();
     let started_at = Instant::now();
-    let result = if provider == "ollama" {
-        stream_translate_with_ollama(&app, &id, order, &source, &target, &config).await
+    // Every provider but Local GPT is streamed here; `translate_text` counts its own usage.
+    let streamed = matches!(
+        provider.as_str(),
+        "ollama" | "openai" | "chatgpt" | "claude" | "gemini"
+    );
+    let result: Result<LiveReply, String> = if provider == "ollama" {
+        stream_translate_with_ollama(
+            &app,
+            &id,
+            order,
+            &source,
+            &target,
+            &config,
+            word_filter.as_ref(),
+        )
+        .await
     } else if provider == "openai" || provider == "chatgpt" {
-        stream_translate_with_openai(&app, &id, order, &source, &target, &config).await
+        stream_translate_with_openai(
+            &app,
+            &id,
+            order,
+            &source,
+            &target,
+            &config,
+            word_filter.as_ref(),
+        )
+        .await
+    } else if provider == "claude" || provider == "gemini" {
+        match sse_translation_request(&provider, &config, &source, &target) {
+            Ok((request, delta)) => {
+                stream_translate_with_sse(
+                    &app,
+                    &id,
+                    order,
+                    &target,
+                    request,
+                    delta,
+                    word_filter.as_ref(),
+                )
+                .await
+            }
+            Err(err) => Err(err),
+        }
     } else {
         translate::translate_text(
+            &app,
             &source,
             Some(provider.clone()),
             translate::TranslateSource::Live,
+            None,
         )
         .await
+        .map(|translation| (translation, None))
     };
 
     match result {
-        Ok(translation) => {
-            emit_output(
+        Ok((translation, reported)) => {
+            if streamed {
+                record_live_usage(
+                    &app,
+                    &provider,
+                    &config,
+                    &source,
+                    &target,
+                    &translation,
+                    reported,
+                );
+            }
+            emit_ui_event(
                 &app,
                 "live_translation_done",
                 LiveTranslationDone {
                     id,
                     order,
-                    translation,
+                    translation: apply_to(word_filter.as_ref(), &translation, Some(&target)),
                     elapsed_ms: started_at.elapsed().as_millis() as u64,
                 },
             );
             Ok(())
         }
         Err(err) => {
-            emit_output(
+            emit_ui_event(
                 &app,
                 "live_translation_error",
                 LiveTranslationError {
@@ -476,13 +665,9 @@ async fn stream_translate_with_ollama(
     text: &str,
     target_language: &str,
     config: &app_config::AppConfig,
-) -> Result<String, String> {
-    let ollama = config.ollama.clone().unwrap_or_else(|| OllamaConfig {
-        enabled: Some(true),
-        model: Some(DEFAULT_OLLAMA_MODEL.to_string()),
-        base_url: Some(DEFAULT_OLLAMA_BASE_URL.to_string()),
-        timeout_secs: Some(DEFAULT_OLLAMA_TIMEOUT),
-    });
+    word_filter: Option<&WordFilter>,
+) -> Result<LiveReply, String> {
+    let ollama = config.ollama.clone().unwrap_or_default();
 
     if ollama.enabled == Some(false) {
         return Err("ollama disabled".to_string());
@@ -498,7 +683,7 @@ async fn stream_translate_with_ollama(
         .unwrap_or_else(|| DEFAULT_OLLAMA_BASE_URL.to_string());
     let timeout_secs = ollama.timeout_secs.unwrap_or(DEFAULT_OLLAMA_TIMEOUT);
     let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
-    eprintln!(
+    info!(
         "ollama stream request url={} model={} target={} chars={}",
         url,
         model,
@@ -520,10 +705,7 @@ async fn stream_translate_with_ollama(
       "stream": true
     });
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
+    let client = http::client(Some(timeout_secs))?;
     let response = client
         .post(url)
         .json(&body)
@@ -534,13 +716,14 @@ async fn stream_translate_with_ollama(
     let status = response.status();
     if !status.is_success() {
         let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
-        return Err(value.to_string());
+        return Err(redact(&value.to_string()).into_owned());
     }
 
     let mut stream = response.bytes_stream();
-    let mut buffer = String::new();
+    let mut lines = LineDecoder::new();
     let mut full = String::new();
     let mut raw = String::new();
+    let mut reported = None;
     let mut done = false;
 
     while let Some(chunk) = stream.next().await {
@@ -548,34 +731,32 @@ async fn stream_translate_with_ollama(
             Ok(value) => value,
             Err(err) => return Err(err.to_string()),
         };
-        let text = String::from_utf8_lossy(&chunk);
-        raw.push_str(&text);
-        buffer.push_str(&text);
-
-        loop {
-            let Some(pos) = buffer.find('\n') else { break };
-            let line = buffer[..pos].trim().to_string();
-            buffer = buffer[pos + 1..].to_string();
+        raw.push_str(&String::from_utf8_lossy(&chunk));
+        lines.push(&chunk);
+
+        while let Some(line) = lines.next_line() {
+            let line = line.trim();
             if line.is_empty() {
                 continue;
             }
-            let value: serde_json::Value = match serde_json::from_str(&line) {
+            let value: serde_json::Value = match serde_json::from_str(line) {
                 Ok(value) => value,
                 Err(err) => {
-                    eprintln!("ollama stream parse error: {err}");
+                    warn!("ollama stream parse error: {err}");
                     continue;
                 }
             };
+            merge_reported(&mut reported, &value);
             if let Some(response_text) = value.get("response").and_then(|v| v.as_str()) {
                 if !response_text.is_empty() {
                     full.push_str(response_text);
-                    emit_output(
+                    emit_ui_event(
                         app,
                         "live_translation_chunk",
                         LiveTranslationChunk {
                             id: id.to_string(),
                             order,
-                            chunk: response_text.to_string(),
+                            chunk: apply_to(word_filter, response_text, Some(target_language)),
                         },
                     );
                 }
@@ -592,19 +773,21 @@ async fn stream_translate_with_ollama(
     }
 
     if !done {
-        let line = buffer.trim();
+        let tail = lines.finish().unwrap_or_default();
+        let line = tail.trim();
         if !line.is_empty() {
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
+                merge_reported(&mut reported, &value);
                 if let Some(response_text) = value.get("response").and_then(|v| v.as_str()) {
                     if !response_text.is_empty() {
                         full.push_str(response_text);
-                        emit_output(
+                        emit_ui_event(
                             app,
                             "live_translation_chunk",
                             LiveTranslationChunk {
                                 id: id.to_string(),
                                 order,
-                                chunk: response_text.to_string(),
+                                chunk: apply_to(word_filter, response_text, Some(target_language)),
                             },
                         );
                     }
@@ -614,7 +797,7 @@ async fn stream_translate_with_ollama(
     }
 
     if full.trim().is_empty() && !raw.is_empty() {
-        eprintln!(
+        debug!(
             "ollama stream raw (first 1000 chars): {}",
             raw.chars().take(1000).collect::<String>()
         );
@@ -637,7 +820,7 @@ async fn stream_translate_with_ollama(
         }
     }
 
-    Ok(full.trim().to_string())
+    Ok((full.trim().to_string(), reported))
 }
 
 async fn stream_translate_with_openai(
@@ -647,7 +830,8 @@ async fn stream_translate_with_openai(
     text: &str,
     target_language: &str,
     config: &app_config::AppConfig,
-) -> Result<String, String> {
+    word_filter: Option<&WordFilter>,
+) -> Result<LiveReply, String> {
     let openai = &config.openai;
     let api_key = openai.api_key.trim();
     if api_key.is_empty() {
@@ -688,10 +872,7 @@ async fn stream_translate_with_openai(
       "stream": true
     });
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
+    let client = http::client(Some(timeout_secs))?;
     let response = client
         .post(base_url.trim_end_matches('/'))
         .bearer_auth(api_key)
@@ -703,12 +884,13 @@ async fn stream_translate_with_openai(
     let status = response.status();
     if !status.is_success() {
         let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
-        return Err(value.to_string());
+        return Err(redact(&value.to_string()).into_owned());
     }
 
     let mut stream = response.bytes_stream();
-    let mut buffer = String::new();
+    let mut events = SseDecoder::new();
     let mut full = String::new();
+    let mut reported = None;
     let mut done = false;
 
     while let Some(chunk) = stream.next().await {
@@ -716,20 +898,13 @@ async fn stream_translate_with_openai(
             Ok(value) => value,
             Err(err) => return Err(err.to_string()),
         };
-        let text = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&text);
+        events.push(&chunk);
 
-        loop {
-            let Some(pos) = buffer.find('\n') else { break };
-            let line = buffer[..pos].trim().to_string();
-            buffer = buffer[pos + 1..].to_string();
-            if line.is_empty() {
-                continue;
-            }
-            if !line.starts_with("data:") {
+        while let Some(data) = events.next_data() {
+            let payload = data.trim();
+            if payload.is_empty() {
                 continue;
             }
-            let payload = line.trim_start_matches("data:").trim();
             if payload == "[DONE]" {
                 done = true;
                 break;
@@ -737,10 +912,12 @@ async fn stream_translate_with_openai(
             let value: serde_json::Value = match serde_json::from_str(payload) {
                 Ok(value) => value,
                 Err(err) => {
-                    eprintln!("openai stream parse error: {err}");
+                    warn!("openai stream parse error: {err}");
                     continue;
                 }
             };
+            // `response.completed` carries the usage of the whole reply.
+            merge_reported(&mut reported, &value);
 
             if value
                 .get("type")
@@ -758,13 +935,13 @@ async fn stream_translate_with_openai(
             if let Some(chunk_text) = delta {
                 if !chunk_text.is_empty() {
                     full.push_str(chunk_text);
-                    emit_output(
+                    emit_ui_event(
                         app,
                         "live_translation_chunk",
                         LiveTranslationChunk {
                             id: id.to_string(),
                             order,
-                            chunk: chunk_text.to_string(),
+                            chunk: apply_to(word_filter, chunk_text, Some(target_language)),
                         },
                     );
                 }
@@ -780,10 +957,137 @@ async fn stream_translate_with_openai(
         }
     }
 
-    Ok(full.trim().to_string())
+    Ok((full.trim().to_string(), reported))
+}
+
+// A live translation and the token usage its stream reported, if any.
+type LiveReply = (String, Option<TokenUsage>);
+
+// Stream events report running totals, see `TokenUsage::merge`.
+fn merge_reported(reported: &mut Option<TokenUsage>, value: &serde_json::Value) {
+    if let Some(found) = TokenUsage::from_reply(value) {
+        *reported = Some(reported.map_or(found, |usage| usage.merge(found)));
+    }
+}
+
+// Streams that reported no usage are estimated from the prompt and the translation.
+fn record_live_usage(
+    app: &AppHandle,
+    provider: &str,
+    config: &app_config::AppConfig,
+    source: &str,
+    target_language: &str,
+    translation: &str,
+    reported: Option<TokenUsage>,
+) {
+    let Some(usage) = UsageState::of(app) else {
+        return;
+    };
+    let Ok(llm) = llm::provider_from_config(provider, config) else {
+        return;
+    };
+    let tokens = reported.unwrap_or_else(|| {
+        let template = resolve_live_prompt_template(config);
+        let mut prompt = render_prompt_template(&template, target_language, Some(source));
+        if !template.contains("{text}") {
+            prompt.push_str(source);
+        }
+        TokenUsage::estimate(&prompt, translation)
+    });
+    usage.record(
+        llm.name(),
+        llm.model(),
+        "translate_live",
+        tokens,
+        reported.is_none(),
+    );
+}
+
+// Pulls the text out of one streamed event; `None` for events that carry none.
+type StreamDelta = fn(&serde_json::Value) -> Option<String>;
+
+// The live prompt for Claude or Gemini, built like the OpenAI stream's.
+fn sse_translation_request(
+    provider: &str,
+    config: &app_config::AppConfig,
+    text: &str,
+    target_language: &str,
+) -> Result<(reqwest::RequestBuilder, StreamDelta), String> {
+    let prompt_template = resolve_live_prompt_template(config);
+    let rendered = render_prompt_template(&prompt_template, target_language, Some(text));
+    let mut prompt = llm::LlmPrompt::new(rendered);
+    if !prompt_template.contains("{text}") {
+        prompt = prompt.with_input(text);
+    }
+    if provider == "claude" {
+        let claude = llm::ClaudeProvider::from_config(config)?;
+        Ok((claude.post(&prompt, true)?, llm::claude_stream_delta))
+    } else {
+        let gemini = llm::GeminiProvider::from_config(config)?;
+        Ok((gemini.post(&prompt, true)?, llm::gemini_reply_text))
+    }
+}
+
+async fn stream_translate_with_sse(
+    app: &AppHandle,
+    id: &str,
+    order: u64,
+    target_language: &str,
+    request: reqwest::RequestBuilder,
+    delta: StreamDelta,
+    word_filter: Option<&WordFilter>,
+) -> Result<LiveReply, String> {
+    let response = request.send().await.map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.map_err(|err| err.to_string())?;
+        return Err(redact(&body).into_owned());
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut events = SseDecoder::new();
+    let mut full = String::new();
+    let mut reported = None;
+    let mut handle = |data: String| {
+        let value: serde_json::Value = match serde_json::from_str(data.trim()) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("sse stream parse error: {err}");
+                return;
+            }
+        };
+        // Claude reports input in `message_start` and output in `message_delta`; Gemini repeats
+        // `usageMetadata` in its events.
+        merge_reported(&mut reported, &value);
+        if let Some(chunk_text) = delta(&value).filter(|text| !text.is_empty()) {
+            full.push_str(&chunk_text);
+            emit_ui_event(
+                app,
+                "live_translation_chunk",
+                LiveTranslationChunk {
+                    id: id.to_string(),
+                    order,
+                    chunk: apply_to(word_filter, &chunk_text, Some(target_language)),
+                },
+            );
+        }
+    };
+
+    while let Some(chunk) = stream.next().await {
+        events.push(&chunk.map_err(|err| err.to_string())?);
+        while let Some(data) = events.next_data() {
+            handle(data);
+        }
+    }
+    // Gemini may close the stream without the blank line that ends its last event.
+    if let Some(data) = events.finish() {
+        handle(data);
+    }
+
+    Ok((full.trim().to_string(), reported))
 }
 
-async fn call_openai(request: LlmRequest) -> Result<String, String> {
+async fn call_openai(app: &AppHandle, request: LlmRequest) -> Result<String, String> {
     let base_url = request
         .base_url
         .filter(|value| !value.trim().is_empty())
@@ -800,7 +1104,7 @@ async fn call_openai(request: LlmRequest) -> Result<String, String> {
       "temperature": 0.2
     });
 
-    let client = reqwest::Client::new();
+    let client = http::client(None)?;
     let response = client
         .post(url)
         .bearer_auth(api_key)
@@ -812,20 +1116,29 @@ async fn call_openai(request: LlmRequest) -> Result<String, String> {
     let status = response.status();
     let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
     if !status.is_success() {
-        return Err(value.to_string());
+        return Err(redact(&value.to_string()).into_owned());
     }
 
-    value
+    let text = value
         .get("choices")
         .and_then(|choices| choices.get(0))
         .and_then(|choice| choice.get("message"))
         .and_then(|message| message.get("content"))
         .and_then(|content| content.as_str())
         .map(|text| text.to_string())
-        .ok_or_else(|| "OpenAI response missing content".to_string())
+        .ok_or_else(|| "OpenAI response missing content".to_string())?;
+    record_generate_usage(
+        app,
+        "openai",
+        &request.model,
+        &request.prompt,
+        &value,
+        &text,
+    );
+    Ok(text)
 }
 
-async fn call_ollama(request: LlmRequest) -> Result<String, String> {
+async fn call_ollama(app: &AppHandle, request: LlmRequest) -> Result<String, String> {
     let base_url = request
         .base_url
         .filter(|value| !value.trim().is_empty())
@@ -838,7 +1151,7 @@ async fn call_ollama(request: LlmRequest) -> Result<String, String> {
       "stream": false
     });
 
-    let client = reqwest::Client::new();
+    let client = http::client(None)?;
     let response = client
         .post(url)
         .json(&body)
@@ -849,14 +1162,40 @@ async fn call_ollama(request: LlmRequest) -> Result<String, String> {
     let status = response.status();
     let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
     if !status.is_success() {
-        return Err(value.to_string());
+        return Err(redact(&value.to_string()).into_owned());
     }
 
-    value
+    let text = value
         .get("response")
         .and_then(|response| response.as_str())
         .map(|text| text.to_string())
-        .ok_or_else(|| "Ollama response missing content".to_string())
+        .ok_or_else(|| "Ollama response missing content".to_string())?;
+    record_generate_usage(
+        app,
+        "ollama",
+        &request.model,
+        &request.prompt,
+        &value,
+        &text,
+    );
+    Ok(text)
+}
+
+// `llm_generate` calls the APIs directly, so it counts its own usage.
+fn record_generate_usage(
+    app: &AppHandle,
+    provider: &str,
+    model: &str,
+    prompt: &str,
+    reply: &serde_json::Value,
+    text: &str,
+) {
+    let Some(usage) = UsageState::of(app) else {
+        return;
+    };
+    let reported = TokenUsage::from_reply(reply);
+    let tokens = reported.unwrap_or_else(|| TokenUsage::estimate(prompt, text));
+    usage.record(provider, model, "llm_generate", tokens, reported.is_none());
 }
 
 fn compact_text(input: &str, max_chars: usize) -> String {
@@ -869,259 +1208,136 @@ fn compact_text(input: &str, max_chars: usize) -> String {
 }
 
 async fn generate_with_selected_provider(
+    app: &AppHandle,
     provider: &str,
     prompt: &str,
     config: &app_config::AppConfig,
+    feature: &'static str,
 ) -> Result<String, String> {
-    match provider {
-        "openai" => generate_with_openai(prompt, config).await,
-        "local-gpt" => generate_with_local_gpt(prompt, config).await,
-        _ => generate_with_ollama(prompt, config).await,
-    }
+    let llm = llm::metered(
+        llm::provider_from_config(provider, config)?,
+        UsageState::of(app),
+    );
+    llm::generate(llm.as_ref(), prompt, feature).await
 }
 
-async fn generate_with_openai(
-    prompt: &str,
-    config: &app_config::AppConfig,
-) -> Result<String, String> {
-    let openai = &config.openai;
-    let api_key = openai.api_key.trim();
-    if api_key.is_empty() {
-        return Err("OpenAI apiKey is required".to_string());
-    }
-    let model = openai
-        .chat_model
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_MODEL.to_string());
-    let base_url = openai
-        .chat_base_url
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_BASE_URL.to_string());
-    let timeout_secs = openai
-        .chat_timeout_secs
-        .unwrap_or(DEFAULT_OPENAI_CHAT_TIMEOUT);
-
-    let body = serde_json::json!({
-      "model": model,
-      "input": [
-        {
-          "role": "system",
-          "content": [{"type": "input_text", "text": "Answer using provided context and cite sources as [n]."}]
-        },
-        {
-          "role": "user",
-          "content": [{"type": "input_text", "text": prompt}]
-        }
-      ],
-      "temperature": 0.2
-    });
-
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-    let response = client
-        .post(base_url.trim_end_matches('/'))
-        .bearer_auth(api_key)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|err| err.to_string())?;
-
-    let status = response.status();
-    let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
-    if !status.is_success() {
-        return Err(value.to_string());
-    }
-
-    extract_openai_response_text(&value).ok_or_else(|| "OpenAI response missing text".to_string())
+#[tauri::command]
+async fn start_loopback_capture(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+) -> Result<(), String> {
+    state.start(app)
 }
 
-fn extract_openai_response_text(value: &serde_json::Value) -> Option<String> {
-    if let Some(text) = value.get("output_text").and_then(|field| field.as_str()) {
-        let trimmed = text.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
-        }
-    }
-    if let Some(output) = value.get("output").and_then(|field| field.as_array()) {
-        for item in output {
-            if let Some(content) = item.get("content").and_then(|field| field.as_array()) {
-                for part in content {
-                    if part.get("type").and_then(|t| t.as_str()) == Some("output_text") {
-                        if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                            let trimmed = text.trim();
-                            if !trimmed.is_empty() {
-                                return Some(trimmed.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    None
+#[tauri::command]
+async fn stop_loopback_capture(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    drop_translations: Option<bool>,
+) -> Result<(), String> {
+    state.stop(&app, drop_translations.unwrap_or(false))
 }
 
-async fn generate_with_local_gpt(
-    prompt: &str,
-    config: &app_config::AppConfig,
-) -> Result<String, String> {
-    let local_gpt = config.local_gpt.clone().unwrap_or_else(|| LocalGptConfig {
-        enabled: Some(true),
-        base_url: Some(DEFAULT_LOCAL_GPT_BASE_URL.to_string()),
-        timeout_secs: Some(DEFAULT_LOCAL_GPT_TIMEOUT),
-        project_id: None,
-    });
-
-    if local_gpt.enabled == Some(false) {
-        eprintln!(
-            "[local-gpt-direct] config localGpt.enabled=false, but proceeding because local-gpt provider is selected"
-        );
-    }
-
-    let base_url = local_gpt
-        .base_url
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_LOCAL_GPT_BASE_URL.to_string());
-    let timeout_secs = local_gpt.timeout_secs.unwrap_or(DEFAULT_LOCAL_GPT_TIMEOUT);
-    let project_id = local_gpt
-        .project_id
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| DEFAULT_LOCAL_GPT_PROJECT_ID.to_string());
-    let url = format!(
-        "{}/{}",
-        base_url.trim_end_matches('/'),
-        DEFAULT_LOCAL_GPT_DIRECT_PATH.trim_start_matches('/')
-    );
+#[tauri::command]
+fn pause_loopback_capture(app: AppHandle, state: State<'_, CaptureManager>) -> Result<(), String> {
+    state.pause(&app)
+}
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-    let response = client
-        .post(url)
-        .json(&serde_json::json!({
-          "project_id": project_id.as_str(),
-          "project-id": project_id.as_str(),
-          "prompt": prompt
-        }))
-        .send()
-        .await
-        .map_err(|err| err.to_string())?;
+#[tauri::command]
+fn resume_loopback_capture(app: AppHandle, state: State<'_, CaptureManager>) -> Result<(), String> {
+    state.resume(&app)
+}
 
-    let status = response.status();
-    let raw = response.text().await.map_err(|err| err.to_string())?;
-    let value: serde_json::Value =
-        serde_json::from_str(&raw).unwrap_or_else(|_| serde_json::json!({ "message": raw }));
-
-    let message = value
-        .get("message")
-        .and_then(|field| field.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty())
-        .unwrap_or_else(|| value.to_string());
-    let timed_out = value
-        .get("timed_out")
-        .and_then(|field| field.as_bool())
-        .unwrap_or(false);
-    let result = value
-        .get("result")
-        .and_then(|field| field.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty());
-
-    if status.is_success() && value.get("ok").and_then(|field| field.as_bool()) != Some(false) {
-        return result.ok_or_else(|| "local-gpt response missing result".to_string());
-    }
+#[tauri::command]
+fn is_capture_paused(state: State<'_, CaptureManager>) -> bool {
+    state.is_paused()
+}
 
-    if timed_out {
-        if let Some(partial) = result {
-            eprintln!(
-                "local-gpt rag prompt timed out, returning partial result chars={}",
-                partial.chars().count()
-            );
-            return Ok(partial);
-        }
-    }
+#[tauri::command]
+fn arm_capture(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    seconds: Option<u64>,
+) -> Result<ArmedStatus, String> {
+    state.arm(&app, seconds)
+}
 
-    Err(message)
+#[tauri::command]
+fn disarm_capture(app: AppHandle, state: State<'_, CaptureManager>) {
+    state.disarm(&app);
 }
 
-async fn generate_with_ollama(
-    prompt: &str,
-    config: &app_config::AppConfig,
-) -> Result<String, String> {
-    let ollama = config.ollama.clone().unwrap_or_else(|| OllamaConfig {
-        enabled: Some(true),
-        model: Some(DEFAULT_OLLAMA_MODEL.to_string()),
-        base_url: Some(DEFAULT_OLLAMA_BASE_URL.to_string()),
-        timeout_secs: Some(DEFAULT_OLLAMA_TIMEOUT),
-    });
+#[tauri::command]
+fn armed_capture_status(state: State<'_, CaptureManager>) -> ArmedStatus {
+    state.armed_status()
+}
 
-    if ollama.enabled == Some(false) {
-        return Err("ollama disabled".to_string());
-    }
-    let model = ollama
-        .model
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string());
-    let base_url = ollama
-        .base_url
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OLLAMA_BASE_URL.to_string());
-    let timeout_secs = ollama.timeout_secs.unwrap_or(DEFAULT_OLLAMA_TIMEOUT);
-    let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
+// Writes the buffered audio to segments, which can take a moment for a few minutes of audio.
+#[tauri::command]
+async fn flush_armed_capture(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    seconds: Option<u64>,
+) -> Result<usize, String> {
+    state.flush_armed(&app, seconds)
+}
 
-    let body = serde_json::json!({
-      "model": model,
-      "prompt": prompt,
-      "stream": false
-    });
+// Picks up the current default device after it changed under a running capture.
+#[tauri::command]
+async fn restart_loopback_capture(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+) -> Result<(), String> {
+    state.stop(&app, false)?;
+    state.start(app)
+}
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-    let response = client
-        .post(url)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|err| err.to_string())?;
+#[tauri::command]
+fn storage_stats(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+) -> Result<audio::retention::StorageStats, String> {
+    state.storage_stats(&app)
+}
 
-    let status = response.status();
-    let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
-    if !status.is_success() {
-        return Err(value.to_string());
-    }
+#[tauri::command]
+fn list_sessions(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+) -> Result<Vec<SessionSummary>, String> {
+    state.list_sessions(app)
+}
 
-    value
-        .get("response")
-        .and_then(|field| field.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty())
-        .ok_or_else(|| "Ollama response missing content".to_string())
+// Runs the speaker model over every segment, which takes a while for a long meeting.
+#[tauri::command]
+async fn rediarize_session(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    session_id: String,
+) -> Result<RediarizeReport, String> {
+    state.rediarize_session(&app, &session_id)
 }
 
+// Re-runs ASR over a whole session, or every kept segment without `session_id`, with the current
+// settings; progress goes to the output window.
 #[tauri::command]
-async fn start_loopback_capture(
+async fn retranscribe_session(
     app: AppHandle,
     state: State<'_, CaptureManager>,
-) -> Result<(), String> {
-    state.start(app)
+    session_id: Option<String>,
+) -> Result<RetranscribeReport, String> {
+    state
+        .retranscribe_session(&app, session_id.as_deref())
+        .await
 }
 
 #[tauri::command]
-async fn stop_loopback_capture(
+fn list_session_segments(
     app: AppHandle,
     state: State<'_, CaptureManager>,
-    drop_translations: Option<bool>,
-) -> Result<(), String> {
-    state.stop(&app, drop_translations.unwrap_or(false))
+    session_id: String,
+) -> Result<Vec<SegmentInfo>, String> {
+    state.list_session_segments(app, &session_id)
 }
 
 #[tauri::command]
@@ -1129,6 +1345,17 @@ fn is_translation_busy(state: State<'_, CaptureManager>) -> bool {
     state.is_translation_busy()
 }
 
+#[tauri::command]
+fn get_transcription_only(state: State<'_, CaptureManager>) -> bool {
+    state.is_transcription_only()
+}
+
+#[tauri::command]
+fn set_transcription_only(app: AppHandle, state: State<'_, CaptureManager>, enabled: bool) -> bool {
+    state.set_transcription_only(&app, enabled);
+    enabled
+}
+
 #[tauri::command]
 async fn list_segments(
     app: AppHandle,
@@ -1137,6 +1364,16 @@ async fn list_segments(
     state.list(app)
 }
 
+#[tauri::command]
+async fn list_segments_page(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    before: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SegmentInfo>, String> {
+    state.list_page(app, before, limit.unwrap_or(DEFAULT_SEGMENT_PAGE_SIZE))
+}
+
 #[tauri::command]
 async fn read_segment_bytes(
     app: AppHandle,
@@ -1146,11 +1383,71 @@ async fn read_segment_bytes(
     state.read_segment_bytes(app, name)
 }
 
+#[tauri::command]
+fn get_capture_stats(state: State<'_, CaptureManager>) -> CaptureStats {
+    state.capture_stats()
+}
+
+#[tauri::command]
+async fn segment_waveform(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    name: String,
+    buckets: Option<usize>,
+) -> Result<Waveform, String> {
+    state.segment_waveform(&app, &name, buckets.unwrap_or(DEFAULT_BUCKETS))
+}
+
+#[tauri::command]
+fn open_segment_playback(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    name: String,
+) -> Result<PlaybackState, String> {
+    state.open_playback(&app, &name)
+}
+
+// Next chunk from the current position; `done` marks the end of the segment.
+#[tauri::command]
+fn read_segment_playback(
+    state: State<'_, CaptureManager>,
+    max_ms: Option<u64>,
+) -> Result<PlaybackChunk, String> {
+    state.read_playback(max_ms.unwrap_or(DEFAULT_CHUNK_MS))
+}
+
+#[tauri::command]
+fn seek_segment_playback(
+    state: State<'_, CaptureManager>,
+    position_ms: u64,
+) -> Result<PlaybackState, String> {
+    state.seek_playback(position_ms)
+}
+
+#[tauri::command]
+fn segment_playback_state(state: State<'_, CaptureManager>) -> Option<PlaybackState> {
+    state.playback_state()
+}
+
+#[tauri::command]
+fn close_segment_playback(state: State<'_, CaptureManager>) {
+    state.close_playback();
+}
+
 #[tauri::command]
 async fn clear_segments(app: AppHandle, state: State<'_, CaptureManager>) -> Result<(), String> {
     state.clear(app)
 }
 
+#[tauri::command]
+async fn delete_segment(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    name: String,
+) -> Result<(), String> {
+    state.delete_segment(&app, &name)
+}
+
 #[tauri::command]
 async fn translate_segment(
     app: AppHandle,
@@ -1161,6 +1458,20 @@ async fn translate_segment(
     state.translate_segment(app, name, provider)
 }
 
+// Provider and model default to the current ASR settings; the translation is left as it was.
+#[tauri::command]
+async fn retranscribe_segment(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    name: String,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<SegmentInfo, String> {
+    state
+        .retranscribe_segment(&app, &name, provider, model)
+        .await
+}
+
 #[tauri::command]
 async fn open_external_window(app: AppHandle, label: String, url: String) -> Result<(), String> {
     let parsed_url = url::Url::parse(&url).map_err(|err| err.to_string())?;
@@ -1203,8 +1514,17 @@ fn get_asr_settings(state: State<'_, AsrState>) -> (String, bool, String) {
 }
 
 #[tauri::command]
-fn set_asr_provider(state: State<'_, AsrState>, provider: String) -> Result<String, String> {
-    Ok(state.set_provider(provider))
+fn set_asr_provider(
+    state: State<'_, AsrState>,
+    inproc: State<'_, WhisperInprocManager>,
+    provider: String,
+) -> Result<String, String> {
+    let provider = state.set_provider(provider);
+    // The in-process model holds its memory until another provider takes over.
+    if provider != "whisperinproc" {
+        inproc.unload();
+    }
+    Ok(provider)
 }
 
 #[tauri::command]
@@ -1217,6 +1537,51 @@ fn set_asr_language(state: State<'_, AsrState>, language: String) -> Result<Stri
     Ok(state.set_language(language))
 }
 
+#[derive(Debug, Clone, Serialize)]
+struct NextSessionAsr {
+    language: String,
+    // Model that loads when the next capture starts; `None` once it is already active.
+    pending_model: Option<String>,
+}
+
+// Running sessions keep the language and model they started with, so this only changes what the
+// next one gets. A model picked while idle is switched right away.
+#[tauri::command]
+fn set_next_session_asr(
+    app: AppHandle,
+    state: State<'_, AsrState>,
+    manager: State<'_, CaptureManager>,
+    language: Option<String>,
+    model: Option<String>,
+) -> Result<NextSessionAsr, String> {
+    let language = match language {
+        Some(language) => state.set_language(language),
+        None => state.language(),
+    };
+    if let Some(model) = model.map(|value| value.trim().to_string()) {
+        if manager.is_capturing() {
+            state.set_next_model(Some(model));
+        } else {
+            state.set_next_model(None);
+            set_whisper_model(app, model)?;
+        }
+    }
+    Ok(NextSessionAsr {
+        language,
+        pending_model: state.next_model(),
+    })
+}
+
+// Re-runs the first-run GPU/CPU benchmark and applies the fastest build.
+#[tauri::command]
+async fn run_whisper_benchmark(
+    app: AppHandle,
+) -> Result<whisper_benchmark::BenchmarkReport, String> {
+    tauri::async_runtime::spawn_blocking(move || whisper_benchmark::run_benchmark(&app))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
 #[tauri::command]
 fn get_translate_provider(state: State<'_, TranslateProviderState>) -> String {
     state
@@ -1240,23 +1605,46 @@ fn set_translate_provider(
     Ok(normalized)
 }
 
+#[tauri::command]
+fn get_target_language() -> String {
+    translate::target_language()
+}
+
+// Takes effect for the next live line and the next segment batch; finished segments keep theirs.
+#[tauri::command]
+fn set_target_language(language: String) -> Result<String, String> {
+    translate::set_target_language(&language)
+}
+
 #[tauri::command]
 fn log_live_line(index: u64, line: String) {
-    println!("[live {index}] {line}");
+    info!("[live {index}] {line}");
 }
 
 #[tauri::command]
-fn emit_live_draft(app: AppHandle, text: String) {
-    emit_output(&app, "live_draft_update", text);
+fn emit_live_draft(app: AppHandle, text: String) -> DraftUpdate {
+    live_draft::publish(&app, &text)
 }
 
 fn main() {
+    let cli_command = cli::parse_args(std::env::args().skip(1));
+    if !matches!(cli_command, Ok(None)) {
+        cli::attach_console();
+    }
+    let cli_command = match cli_command {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
     let asr_state = AsrState::new();
     let initial_translate_provider = load_config()
         .ok()
         .and_then(|cfg| cfg.translate.and_then(|translate| translate.provider))
-        .unwrap_or_else(|| "ollama".to_string());
-    tauri::Builder::default()
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_PROVIDER.to_string());
+    let builder = tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
         .manage(LayoutState {
             top_height: Mutex::new(None),
         })
@@ -1265,9 +1653,36 @@ fn main() {
         })
         .manage(CaptureManager::new())
         .manage(WhisperServerManager::new())
+        .manage(WhisperPipeManager::new())
+        .manage(WhisperInprocManager::new())
         .manage(asr_state)
         .manage(Arc::new(RagState::new()))
+        .manage(Arc::new(EventHub::new()))
+        .manage(RemoteState::new())
+        .manage(UsageState::new());
+    let context = tauri::generate_context!();
+    if let Some(command) = cli_command {
+        std::process::exit(cli::run(builder, context, command));
+    }
+
+    builder
         .setup(|app| {
+            logging::init(app.handle());
+            remote::start(app.handle());
+            webhooks::start(app.handle());
+            calendar::start(app.handle());
+            scheduler::start(app.handle());
+            mqtt::start(app.handle());
+            journal::start(app.handle());
+            notifications::start(app.handle());
+            templates::start(app.handle());
+            summary::start(app.handle());
+            rag::start(app.handle());
+            updates::start(app.handle());
+            translation_cache::start(app.handle());
+            audio::devices::start(app.handle());
+            audio::meeting_apps::start(app.handle());
+
             let asr_config = load_config()
                 .ok()
                 .and_then(|cfg| cfg.asr)
@@ -1275,9 +1690,36 @@ fn main() {
             if should_start_whisper_server(&asr_config) {
                 let app_handle = app.handle().clone();
                 std::thread::spawn(move || {
+                    let mut asr_config = asr_config;
+                    if whisper_benchmark::pending(&asr_config) {
+                        if let Err(err) = whisper_benchmark::run_benchmark(&app_handle) {
+                            error!("whisper benchmark failed: {err}");
+                        }
+                        if let Ok(config) = load_config() {
+                            asr_config = config.asr.unwrap_or_default();
+                        }
+                    }
                     if let Some(manager) = app_handle.try_state::<WhisperServerManager>() {
                         if let Err(err) = manager.ensure_started(&app_handle, &asr_config) {
-                            eprintln!("whisper-server start failed: {err}");
+                            error!("whisper-server start failed: {err}");
+                        }
+                    }
+                });
+            } else if should_start_whisper_pipe(&asr_config) {
+                let app_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Some(manager) = app_handle.try_state::<WhisperPipeManager>() {
+                        if let Err(err) = manager.warm_up(&app_handle, &asr_config) {
+                            error!("whisper-pipe start failed: {err}");
+                        }
+                    }
+                });
+            } else if should_start_whisper_inproc(&asr_config) {
+                let app_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Some(manager) = app_handle.try_state::<WhisperInprocManager>() {
+                        if let Err(err) = manager.warm_up(&app_handle, &asr_config) {
+                            error!("whisper-inproc model load failed: {err}");
                         }
                     }
                 });
@@ -1304,7 +1746,7 @@ fn main() {
                     let state = app_handle.state::<LayoutState>();
                     let override_top = read_top_override(&state);
                     if let Err(err) = apply_layout(&window, &output, override_top) {
-                        eprintln!("layout error: {err}");
+                        warn!("layout error: {err}");
                     }
                 }
             });
@@ -1323,32 +1765,110 @@ fn main() {
             content_navigate,
             set_top_height,
             start_loopback_capture,
+            start_session_with_template,
+            list_meeting_templates,
+            get_scheduled_meeting,
             stop_loopback_capture,
+            pause_loopback_capture,
+            resume_loopback_capture,
+            is_capture_paused,
+            arm_capture,
+            disarm_capture,
+            armed_capture_status,
+            flush_armed_capture,
+            restart_loopback_capture,
             is_translation_busy,
+            get_transcription_only,
+            check_for_updates,
+            format_timestamps,
+            set_transcription_only,
+            get_interpreter_mode,
+            set_interpreter_mode,
             list_segments,
+            list_segments_page,
             read_segment_bytes,
+            segment_waveform,
+            get_capture_stats,
+            open_segment_playback,
+            read_segment_playback,
+            seek_segment_playback,
+            segment_playback_state,
+            close_segment_playback,
             clear_segments,
+            delete_segment,
             translate_segment,
+            retranscribe_segment,
             get_asr_settings,
             set_asr_provider,
             set_asr_fallback,
             set_asr_language,
+            set_next_session_asr,
+            run_whisper_benchmark,
+            list_whisper_models,
+            download_whisper_model,
+            set_whisper_model,
             get_translate_provider,
             set_translate_provider,
+            get_target_language,
+            set_target_language,
+            clear_translation_cache,
+            get_usage_stats,
             log_live_line,
             emit_live_draft,
             rag_ask_with_provider,
+            rag_ask_batch,
             rag_index_add_files,
             rag_index_sync_project,
             rag_index_remove_files,
             rag_search,
+            rag_is_ready,
             rag_pick_folder,
             rag_project_list,
             rag_project_create,
-            rag_project_delete
+            rag_project_delete,
+            rag_project_set_filters,
+            export_settings_bundle,
+            import_settings_bundle,
+            get_config,
+            set_config,
+            get_config_section,
+            set_config_section,
+            describe_config,
+            get_remote_info,
+            list_remote_actions,
+            get_recent_logs,
+            get_acceleration_status,
+            replay_event_journal,
+            list_sessions,
+            rediarize_session,
+            retranscribe_session,
+            storage_stats,
+            list_session_segments,
+            summarize_session,
+            get_session_summary,
+            get_session_stats,
+            list_audio_devices,
+            set_capture_device,
+            archive_session,
+            meeting_export_audio,
+            restore_session,
+            list_session_archives,
+            preview_meeting_share,
+            send_meeting_share,
+            export_study_subtitles,
+            start_companion_pairing
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                audio::index::flush_all();
+                translation_cache::flush();
+                if let Some(manager) = app.try_state::<WhisperPipeManager>() {
+                    manager.stop();
+                }
+            }
+        });
 }
 
 fn should_start_whisper_server(config: &app_config::AsrConfig) -> bool {
@@ -1363,10 +1883,28 @@ fn should_start_whisper_server(config: &app_config::AsrConfig) -> bool {
     )
 }
 
+fn should_start_whisper_pipe(config: &app_config::AsrConfig) -> bool {
+    let provider = config.provider.clone().unwrap_or_default().to_lowercase();
+    matches!(
+        provider.as_str(),
+        "whisperpipe" | "whisper-pipe" | "whisper_pipe" | "pipe"
+    )
+}
+
+fn should_start_whisper_inproc(config: &app_config::AsrConfig) -> bool {
+    let provider = config.provider.clone().unwrap_or_default().to_lowercase();
+    matches!(
+        provider.as_str(),
+        "whisperinproc" | "whisper-inproc" | "whisper_inproc" | "inproc"
+    )
+}
+
 fn normalize_translate_provider(provider: &str) -> String {
     match provider.trim().to_lowercase().as_str() {
         "openai" | "chatgpt" => "openai".to_string(),
         "local-gpt" | "local_gpt" | "localgpt" => "local-gpt".to_string(),
+        "claude" | "anthropic" => "claude".to_string(),
+        "gemini" | "google" => "gemini".to_string(),
         _ => "ollama".to_string(),
     }
 }
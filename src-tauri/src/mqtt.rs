@@ -0,0 +1,115 @@
+use crate::app_config::{load_config, MqttConfig};
+use crate::defaults::{DEFAULT_MQTT_CLIENT_ID, DEFAULT_MQTT_PORT, DEFAULT_MQTT_TOPIC_PREFIX};
+use crate::events::{EventHub, ACTION_ITEMS_EXTRACTED, SUMMARY_READY};
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+const MQTT_EVENTS: [&str; 4] = [
+    "segment_transcribed",
+    "segment_translated",
+    SUMMARY_READY,
+    ACTION_ITEMS_EXTRACTED,
+];
+const MQTT_KEEP_ALIVE_SECS: u64 = 30;
+const MQTT_RECONNECT_DELAY_SECS: u64 = 5;
+const MQTT_CHANNEL_CAPACITY: usize = 64;
+
+pub fn start(app: &AppHandle) {
+    let config = load_config()
+        .ok()
+        .and_then(|cfg| cfg.mqtt)
+        .unwrap_or_default();
+    if config.enabled != Some(true) {
+        return;
+    }
+    let Some(host) = config.host.clone().filter(|value| !value.trim().is_empty()) else {
+        warn!("mqtt disabled: host is not configured");
+        return;
+    };
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+
+    let client_id = config
+        .client_id
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_MQTT_CLIENT_ID.to_string());
+    let mut options = MqttOptions::new(
+        client_id,
+        host.trim(),
+        config.port.unwrap_or(DEFAULT_MQTT_PORT),
+    );
+    options.set_keep_alive(Duration::from_secs(MQTT_KEEP_ALIVE_SECS));
+    if let Some(username) = config.username.clone().filter(|value| !value.is_empty()) {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, MQTT_CHANNEL_CAPACITY);
+
+    // The event loop owns the connection and reconnects on the next poll after an error.
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(err) = eventloop.poll().await {
+                warn!("mqtt connection error: {err}");
+                tokio::time::sleep(Duration::from_secs(MQTT_RECONNECT_DELAY_SECS)).await;
+            }
+        }
+    });
+
+    let qos = match config.qos.unwrap_or(0) {
+        2 => QoS::ExactlyOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::AtMostOnce,
+    };
+    let retain = config.retain.unwrap_or(false);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("mqtt publisher lagged, skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed || !MQTT_EVENTS.contains(&event.event.as_str()) {
+                continue;
+            }
+            let payload = match serde_json::to_vec(&event) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!("mqtt payload error: {err}");
+                    continue;
+                }
+            };
+            let topic = topic_for(&config, &event.event);
+            if let Err(err) = client.publish(topic, qos, retain, payload).await {
+                warn!("mqtt publish failed: {err}");
+            }
+        }
+    });
+}
+
+// `topics` maps an event name to a full topic; other events go to `<topicPrefix>/<event>`.
+fn topic_for(config: &MqttConfig, event: &str) -> String {
+    if let Some(topic) = config
+        .topics
+        .as_ref()
+        .and_then(|topics| topics.get(event))
+        .filter(|topic| !topic.trim().is_empty())
+    {
+        return topic.trim().to_string();
+    }
+    let prefix = config
+        .topic_prefix
+        .as_deref()
+        .map(|value| value.trim().trim_end_matches('/'))
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_MQTT_TOPIC_PREFIX);
+    format!("{prefix}/{event}")
+}
@@ -0,0 +1,272 @@
+use crate::app_config::load_config;
+use crate::defaults::{DEFAULT_NOTIFICATIONS_ENABLED, DEFAULT_NOTIFICATIONS_ONLY_WHEN_UNFOCUSED};
+use crate::events::{
+    EventHub, UiEvent, AUDIO_ALERT, AUDIO_DEVICE_CHANGED, CAPTURE_DEVICE_CHANGED, CAPTURE_FAILED,
+    RAG_SYNC_FINISHED, SCHEDULED_MEETING_STARTED, SESSION_ENDED, SUMMARY_READY, UPDATE_AVAILABLE,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+const MAX_BODY_CHARS: usize = 120;
+
+// Shows a desktop notification when a long-running task finishes, so the app can stay minimized.
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("notification dispatcher lagged, skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed {
+                continue;
+            }
+            let Some((title, body)) = describe(&event) else {
+                continue;
+            };
+            // Re-read config per event so edits apply without a restart.
+            let config = load_config()
+                .ok()
+                .and_then(|config| config.notifications)
+                .unwrap_or_default();
+            if !config.enabled.unwrap_or(DEFAULT_NOTIFICATIONS_ENABLED) {
+                continue;
+            }
+            let only_when_unfocused = config
+                .only_when_unfocused
+                .unwrap_or(DEFAULT_NOTIFICATIONS_ONLY_WHEN_UNFOCUSED);
+            if only_when_unfocused && main_window_focused(&app) {
+                continue;
+            }
+            if let Err(err) = app.notification().builder().title(title).body(body).show() {
+                warn!("[notify] {} failed: {err}", event.event);
+            }
+        }
+    });
+}
+
+fn main_window_focused(app: &AppHandle) -> bool {
+    app.get_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn describe(event: &UiEvent) -> Option<(String, String)> {
+    let payload = &event.payload;
+    match event.event.as_str() {
+        SUMMARY_READY => {
+            let title = match text_field(payload, "title") {
+                Some(title) => format!("纪要已生成：{title}"),
+                None => "会议纪要已生成".to_string(),
+            };
+            let summary = text_field(payload, "summary").unwrap_or_default();
+            Some((title, truncate(summary, MAX_BODY_CHARS)))
+        }
+        RAG_SYNC_FINISHED => {
+            let count = |field: &str| payload.get(field).and_then(Value::as_u64).unwrap_or(0);
+            let skipped = payload
+                .get("skipped_files")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            Some((
+                "索引同步完成".to_string(),
+                format!(
+                    "新增 {}，更新 {}，删除 {}，跳过 {} 个文件",
+                    count("indexed_files"),
+                    count("updated_files"),
+                    count("deleted_files"),
+                    skipped
+                ),
+            ))
+        }
+        UPDATE_AVAILABLE => {
+            let latest = text_field(payload, "latest_version")?;
+            let current = text_field(payload, "current_version").unwrap_or_default();
+            Some((
+                format!("有新版本 {latest}"),
+                format!("当前版本 {current}，请前往发布页下载"),
+            ))
+        }
+        AUDIO_DEVICE_CHANGED => {
+            if payload.get("restart_suggested").and_then(Value::as_bool) != Some(true) {
+                return None;
+            }
+            // Only a device picked by id is left behind; captures on the default one switch.
+            Some((
+                "音频设备已变化".to_string(),
+                "正在采集的音频设备已断开，可在输出窗口重新采集".to_string(),
+            ))
+        }
+        CAPTURE_DEVICE_CHANGED => {
+            let name = text_field(payload, "name")
+                .or_else(|| text_field(payload, "device_id"))
+                .unwrap_or("新的默认设备");
+            Some(("采集设备已切换".to_string(), format!("正在采集：{name}")))
+        }
+        // Only auto-stop; a manual stop needs no reminder.
+        SESSION_ENDED => {
+            if text_field(payload, "end_reason") != Some("silence") {
+                return None;
+            }
+            Some((
+                "采集已自动停止".to_string(),
+                "长时间未检测到语音，会话已结束".to_string(),
+            ))
+        }
+        SCHEDULED_MEETING_STARTED => {
+            let title = text_field(payload, "title").unwrap_or("定时会议");
+            let body = if payload.get("auto_started").and_then(Value::as_bool) == Some(true) {
+                "已自动开始采集"
+            } else {
+                "尚未开始采集，请记得点击开始"
+            };
+            Some((format!("会议开始：{title}"), body.to_string()))
+        }
+        // Only when raised; the all-clear needs no notification.
+        AUDIO_ALERT => {
+            if payload.get("active").and_then(Value::as_bool) != Some(true) {
+                return None;
+            }
+            match text_field(payload, "kind")? {
+                "clipping" => Some((
+                    "输入持续削波".to_string(),
+                    "音量过大导致失真，请调低音源或麦克风增益".to_string(),
+                )),
+                "dead_air" => {
+                    let minutes = payload.get("seconds").and_then(Value::as_u64).unwrap_or(0) / 60;
+                    Some((
+                        "长时间没有声音".to_string(),
+                        format!("已有 {minutes} 分钟没有采集到声音，请检查是否静音或选错了设备"),
+                    ))
+                }
+                _ => None,
+            }
+        }
+        CAPTURE_FAILED => Some((
+            "采集已停止".to_string(),
+            truncate(
+                text_field(payload, "error").unwrap_or_default(),
+                MAX_BODY_CHARS,
+            ),
+        )),
+        _ => None,
+    }
+}
+
+fn text_field<'a>(payload: &'a Value, field: &str) -> Option<&'a str> {
+    payload
+        .get(field)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated = text.chars().take(max_chars).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe;
+    use crate::events::{
+        UiEvent, AUDIO_ALERT, AUDIO_DEVICE_CHANGED, CAPTURE_DEVICE_CHANGED, RAG_SYNC_FINISHED,
+        SCHEDULED_MEETING_STARTED, SESSION_ENDED, SUMMARY_READY, UPDATE_AVAILABLE,
+    };
+    use serde_json::json;
+
+    fn event(name: &str, payload: serde_json::Value) -> UiEvent {
+        UiEvent {
+            event: name.to_string(),
+            payload,
+            timestamp: String::new(),
+            replayed: false,
+        }
+    }
+
+    #[test]
+    fn describes_finished_tasks_and_ignores_other_events() {
+        let (title, body) = describe(&event(
+            SUMMARY_READY,
+            json!({ "title": null, "summary": "x".repeat(200) }),
+        ))
+        .unwrap();
+        assert_eq!(title, "会议纪要已生成");
+        assert_eq!(body.chars().count(), 121);
+
+        let (_, body) = describe(&event(
+            RAG_SYNC_FINISHED,
+            json!({ "indexed_files": 3, "updated_files": 1, "deleted_files": 0, "skipped_files": [{}] }),
+        ))
+        .unwrap();
+        assert_eq!(body, "新增 3，更新 1，删除 0，跳过 1 个文件");
+
+        let (title, _) = describe(&event(
+            UPDATE_AVAILABLE,
+            json!({ "latest_version": "0.2.0", "current_version": "0.1.0" }),
+        ))
+        .unwrap();
+        assert_eq!(title, "有新版本 0.2.0");
+
+        let (_, body) = describe(&event(
+            AUDIO_DEVICE_CHANGED,
+            json!({ "kind": "unavailable", "restart_suggested": true }),
+        ))
+        .unwrap();
+        assert_eq!(body, "正在采集的音频设备已断开，可在输出窗口重新采集");
+        assert!(describe(&event(
+            AUDIO_DEVICE_CHANGED,
+            json!({ "kind": "default_changed", "restart_suggested": false }),
+        ))
+        .is_none());
+        let (_, body) = describe(&event(
+            CAPTURE_DEVICE_CHANGED,
+            json!({ "name": "Headphones", "reason": "default_changed" }),
+        ))
+        .unwrap();
+        assert_eq!(body, "正在采集：Headphones");
+
+        let (title, _) =
+            describe(&event(SESSION_ENDED, json!({ "end_reason": "silence" }))).unwrap();
+        assert_eq!(title, "采集已自动停止");
+        assert!(describe(&event(SESSION_ENDED, json!({ "end_reason": null }))).is_none());
+
+        let (title, body) = describe(&event(
+            SCHEDULED_MEETING_STARTED,
+            json!({ "title": "Weekly sync", "auto_started": true }),
+        ))
+        .unwrap();
+        assert_eq!(title, "会议开始：Weekly sync");
+        assert_eq!(body, "已自动开始采集");
+
+        let (title, _) = describe(&event(
+            AUDIO_ALERT,
+            json!({ "kind": "dead_air", "active": true, "seconds": 180 }),
+        ))
+        .unwrap();
+        assert_eq!(title, "长时间没有声音");
+        assert!(describe(&event(
+            AUDIO_ALERT,
+            json!({ "kind": "dead_air", "active": false, "seconds": 200 }),
+        ))
+        .is_none());
+
+        assert!(describe(&event("segment_ready", json!({}))).is_none());
+    }
+}
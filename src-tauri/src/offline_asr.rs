@@ -0,0 +1,155 @@
+// Last-resort transcription when whisper-server cannot start and there is no OpenAI fallback: a
+// small model on the CPU through the in-process backend, so machines without a GPU or network
+// still get rough transcripts.
+
+use crate::app_config::AsrConfig;
+use crate::defaults::{DEFAULT_OFFLINE_FALLBACK_ENABLED, DEFAULT_OFFLINE_FALLBACK_MODEL};
+use crate::events::{emit_ui_event, ASR_OFFLINE_FALLBACK};
+use crate::transcribe::transcribe_with_whisper_inproc;
+use crate::whisper_models::find_model;
+use crate::whisper_server::resolve_path_with_context;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+// Starting whisper-server again is only tried this often, since a failed start can take up to the
+// start timeout on every segment.
+const SERVER_RETRY_SECS: u64 = 60;
+
+// When whisper-server last failed to start; `Some` while segments go to the fallback model.
+static FAILED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+// Set once a missing fallback model was reported, so the UI offers the download once.
+static MISSING_REPORTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OfflineFallback {
+    pub model: String,
+    // Why whisper-server was not used, and why the fallback was not either when `downloaded` is
+    // false.
+    pub reason: String,
+    // `false` when the model is missing and the segment failed; `download_whisper_model` fetches it.
+    pub downloaded: bool,
+}
+
+// `None` while `asr.offlineFallback` is off.
+fn fallback_model(asr: &AsrConfig) -> Option<String> {
+    if !asr
+        .offline_fallback
+        .unwrap_or(DEFAULT_OFFLINE_FALLBACK_ENABLED)
+    {
+        return None;
+    }
+    Some(
+        asr.offline_fallback_model
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(DEFAULT_OFFLINE_FALLBACK_MODEL)
+            .to_string(),
+    )
+}
+
+// `reason` is returned as is when the fallback is off or the build has no `whisper-inproc` feature,
+// and with a note when the model is not downloaded.
+pub async fn transcribe_offline(
+    app: &AppHandle,
+    path: &Path,
+    config: &AsrConfig,
+    prompt_hint: Option<&str>,
+    reason: String,
+) -> Result<String, String> {
+    // The fallback model runs on the in-process backend, which this build leaves out.
+    if !cfg!(feature = "whisper-inproc") {
+        return Err(reason);
+    }
+    let Some(model) = fallback_model(config) else {
+        return Err(reason);
+    };
+    let Some(model_path) =
+        resolve_path_with_context(app, &model).or_else(|| find_model(app, &model))
+    else {
+        warn!("offline fallback model {model} is not downloaded");
+        let reason = format!("{reason} (offline model {model} not downloaded)");
+        if !MISSING_REPORTED.swap(true, Ordering::Relaxed) {
+            emit_ui_event(
+                app,
+                ASR_OFFLINE_FALLBACK,
+                OfflineFallback {
+                    model,
+                    reason: reason.clone(),
+                    downloaded: false,
+                },
+            );
+        }
+        return Err(reason);
+    };
+    MISSING_REPORTED.store(false, Ordering::Relaxed);
+    if record_failure() {
+        warn!("whisper-server unavailable, transcribing with offline model {model}: {reason}");
+        emit_ui_event(
+            app,
+            ASR_OFFLINE_FALLBACK,
+            OfflineFallback {
+                model: model.clone(),
+                reason,
+                downloaded: true,
+            },
+        );
+    }
+    let mut config = config.clone();
+    config.whisper_cpp_model_path = Some(model_path.to_string_lossy().to_string());
+    transcribe_with_whisper_inproc(app, path, &config, prompt_hint, false).await
+}
+
+// `true` the first time, so the switch is reported once.
+fn record_failure() -> bool {
+    let Ok(mut failed_at) = FAILED_AT.lock() else {
+        return false;
+    };
+    let first = failed_at.is_none();
+    // Segments sent here without a start attempt leave the retry clock alone.
+    if failed_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(SERVER_RETRY_SECS)) {
+        *failed_at = Some(Instant::now());
+    }
+    first
+}
+
+// Whether to skip starting whisper-server and go straight to the fallback model.
+pub fn retry_pending() -> bool {
+    FAILED_AT
+        .lock()
+        .ok()
+        .and_then(|failed_at| *failed_at)
+        .is_some_and(|at| at.elapsed() < Duration::from_secs(SERVER_RETRY_SECS))
+}
+
+// Called once whisper-server answers again.
+pub fn server_recovered() {
+    if let Ok(mut failed_at) = FAILED_AT.lock() {
+        if failed_at.take().is_some() {
+            info!("whisper-server is back, offline fallback off");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fallback_model;
+    use crate::app_config::AsrConfig;
+
+    #[test]
+    fn falls_back_to_the_tiny_model_unless_turned_off() {
+        let mut asr = AsrConfig::default();
+        assert_eq!(fallback_model(&asr).as_deref(), Some("tiny"));
+
+        asr.offline_fallback_model = Some(" base-q5_1 ".to_string());
+        assert_eq!(fallback_model(&asr).as_deref(), Some("base-q5_1"));
+
+        asr.offline_fallback = Some(false);
+        assert_eq!(fallback_model(&asr), None);
+    }
+}
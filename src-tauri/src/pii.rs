@@ -0,0 +1,205 @@
+use crate::app_config::{load_config, PiiConfig};
+use crate::defaults::{
+    DEFAULT_PII_EMAILS, DEFAULT_PII_ENABLED, DEFAULT_PII_LLM, DEFAULT_PII_PHONES,
+};
+use crate::llm::{metered, provider_from_config, LlmPrompt, LlmProvider};
+use crate::usage::UsageState;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tauri::AppHandle;
+use tracing::warn;
+
+const NAMES_PROMPT: &str = "List the names of people mentioned in the text below. \
+Reply with a JSON array of strings only, for example [\"Alice Chen\", \"田中\"], or [] when there are none.";
+
+static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap());
+// International, grouped (`090-1234-5678`, `(555) 123-4567`) and bare CN mobile numbers; dates
+// and times do not have the 3-4 digit groups this needs.
+static PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{2,4}\)[\s.-]?|\b\d{2,4}[\s.-])\d{3,4}[\s.-]?\d{3,4}\b|\b1[3-9]\d{9}\b",
+    )
+    .unwrap()
+});
+
+// Masks personal data in transcripts, translations and exports.
+pub struct PiiFilter {
+    emails: bool,
+    phones: bool,
+    names: Vec<String>,
+    patterns: Vec<Regex>,
+    llm: bool,
+    mask: Option<String>,
+}
+
+impl PiiFilter {
+    // `None` when PII redaction is off.
+    pub fn from_config() -> Option<Self> {
+        let config = load_config().ok()?.pii.unwrap_or_default();
+        if !config.enabled.unwrap_or(DEFAULT_PII_ENABLED) {
+            return None;
+        }
+        Some(Self::from_settings(&config))
+    }
+
+    pub(crate) fn from_settings(config: &PiiConfig) -> Self {
+        Self {
+            emails: config.emails.unwrap_or(DEFAULT_PII_EMAILS),
+            phones: config.phones.unwrap_or(DEFAULT_PII_PHONES),
+            names: config
+                .names
+                .iter()
+                .flatten()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            patterns: config
+                .patterns
+                .iter()
+                .flatten()
+                .filter_map(|pattern| Regex::new(pattern).ok())
+                .collect(),
+            llm: config.llm.unwrap_or(DEFAULT_PII_LLM),
+            mask: config.mask.clone().filter(|value| !value.is_empty()),
+        }
+    }
+
+    fn mask_for<'a>(&'a self, label: &'a str) -> &'a str {
+        self.mask.as_deref().unwrap_or(label)
+    }
+
+    pub fn mask(&self, text: &str) -> String {
+        self.mask_names(text, &self.names)
+    }
+
+    fn mask_names(&self, text: &str, names: &[String]) -> String {
+        let mut output = text.to_string();
+        if self.emails {
+            output = EMAIL
+                .replace_all(&output, self.mask_for("[email]"))
+                .into_owned();
+        }
+        if self.phones {
+            output = PHONE
+                .replace_all(&output, self.mask_for("[phone]"))
+                .into_owned();
+        }
+        let mut names = names.iter().collect::<Vec<_>>();
+        // Longest first, so "Alice Chen" is not left as "[name] Chen".
+        names.sort_by_key(|name| std::cmp::Reverse(name.chars().count()));
+        for name in names {
+            if let Some(pattern) = name_pattern(name) {
+                output = pattern
+                    .replace_all(&output, self.mask_for("[name]"))
+                    .into_owned();
+            }
+        }
+        for pattern in &self.patterns {
+            output = pattern
+                .replace_all(&output, self.mask_for("[redacted]"))
+                .into_owned();
+        }
+        output
+    }
+
+    // Asks the model for names not on the configured list; the regex pass still runs if it fails.
+    pub async fn mask_with_llm(&self, text: &str, llm: &dyn LlmProvider) -> String {
+        let prompt = LlmPrompt::new(NAMES_PROMPT)
+            .with_input(text)
+            .with_temperature(0.0)
+            .for_feature("pii");
+        let mut names = self.names.clone();
+        match llm.complete(&prompt).await {
+            Ok(reply) => names.extend(parse_names(&reply)),
+            Err(err) => warn!("[pii] name detection failed: {err}"),
+        }
+        self.mask_names(text, &names)
+    }
+}
+
+// Post-ASR stage; a no-op unless `pii.enabled`.
+pub async fn mask_transcript(app: &AppHandle, text: String) -> String {
+    let Some(filter) = PiiFilter::from_config() else {
+        return text;
+    };
+    if text.trim().is_empty() {
+        return text;
+    }
+    if filter.llm {
+        let provider = crate::selected_translate_provider(app);
+        let llm = load_config()
+            .and_then(|config| provider_from_config(&provider, &config))
+            .map(|llm| metered(llm, UsageState::of(app)));
+        match llm {
+            Ok(llm) => return filter.mask_with_llm(&text, llm.as_ref()).await,
+            Err(err) => warn!("[pii] name detection unavailable: {err}"),
+        }
+    }
+    filter.mask(&text)
+}
+
+// Regex and name-list pass for translations and exports.
+pub fn mask_text(text: &str) -> String {
+    match PiiFilter::from_config() {
+        Some(filter) => filter.mask(text),
+        None => text.to_string(),
+    }
+}
+
+fn name_pattern(name: &str) -> Option<Regex> {
+    let escaped = regex::escape(name);
+    // Word boundaries only make sense for alphabetic names; CJK names sit between other characters.
+    let pattern = if name.is_ascii() {
+        format!(r"(?i)\b{escaped}\b")
+    } else {
+        escaped
+    };
+    Regex::new(&pattern).ok()
+}
+
+fn parse_names(reply: &str) -> Vec<String> {
+    let (Some(start), Some(end)) = (reply.find('['), reply.rfind(']')) else {
+        return Vec::new();
+    };
+    if start >= end {
+        return Vec::new();
+    }
+    serde_json::from_str::<Vec<String>>(&reply[start..=end])
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        // Single characters would mask far more than the name.
+        .filter(|name| name.chars().count() >= 2)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PiiFilter;
+    use crate::app_config::PiiConfig;
+    use crate::llm::mock::MockLlm;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn masks_contacts_listed_names_and_model_detected_names() {
+        let filter = PiiFilter::from_settings(&PiiConfig {
+            enabled: Some(true),
+            names: Some(vec!["Alice".to_string(), "Alice Chen".to_string()]),
+            ..PiiConfig::default()
+        });
+        assert_eq!(
+            filter.mask("Mail alice.chen@example.com or call 090-1234-5678, Alice Chen said"),
+            "Mail [email] or call [phone], [name] said"
+        );
+        assert_eq!(
+            filter.mask("Meeting on 2025-01-20 at 10:00, Malice aside"),
+            "Meeting on 2025-01-20 at 10:00, Malice aside"
+        );
+        assert_eq!(filter.mask("手机 13812345678"), "手机 [phone]");
+
+        let llm = MockLlm::new(&[r#"Names: ["田中", "Bob"]"#]);
+        let masked = block_on(filter.mask_with_llm("田中さんと Bob と Alice", &llm));
+        assert_eq!(masked, "[name]さんと [name] と [name]");
+    }
+}
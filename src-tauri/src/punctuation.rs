@@ -0,0 +1,314 @@
+use crate::app_config::{load_config, PunctuationConfig};
+use crate::asr::AsrState;
+use crate::defaults::{DEFAULT_PUNCTUATION_ENABLED, DEFAULT_PUNCTUATION_MODE};
+use crate::llm::{metered, provider_from_config, LlmPrompt, LlmProvider};
+use crate::usage::UsageState;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const PUNCTUATION_PROMPT: &str = "Restore punctuation and capitalization in the transcript below. \
+Do not add, remove, reorder or translate any words, and keep the original language. \
+Reply with the corrected transcript only.";
+
+// English questions usually open with one of these.
+const QUESTION_WORDS: [&str; 16] = [
+    "what", "why", "how", "who", "where", "when", "which", "is", "are", "do", "does", "did", "can",
+    "could", "would", "should",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Off,
+    Rules,
+    // The selected translate provider; falls back to the rules when it fails or rewrites words.
+    Llm,
+}
+
+pub fn is_valid_mode(raw: &str) -> bool {
+    parse_mode(raw).is_some()
+}
+
+fn parse_mode(raw: &str) -> Option<Mode> {
+    match raw.trim().to_lowercase().as_str() {
+        "off" => Some(Mode::Off),
+        "rules" => Some(Mode::Rules),
+        "llm" => Some(Mode::Llm),
+        _ => None,
+    }
+}
+
+// whisper-server often returns Japanese and Chinese without any punctuation, and English in
+// lower case; this puts it back before the transcript is stored and translated.
+pub struct Punctuator {
+    mode: Mode,
+    languages: HashMap<String, Mode>,
+}
+
+impl Punctuator {
+    // `None` when punctuation restoration is off.
+    pub fn from_config() -> Option<Self> {
+        let config = load_config().ok()?.punctuation.unwrap_or_default();
+        if !config.enabled.unwrap_or(DEFAULT_PUNCTUATION_ENABLED) {
+            return None;
+        }
+        Some(Self::from_settings(&config))
+    }
+
+    pub(crate) fn from_settings(config: &PunctuationConfig) -> Self {
+        Self {
+            mode: config
+                .mode
+                .as_deref()
+                .and_then(parse_mode)
+                .or_else(|| parse_mode(DEFAULT_PUNCTUATION_MODE))
+                .unwrap_or(Mode::Rules),
+            languages: config
+                .languages
+                .iter()
+                .flatten()
+                .filter_map(|(language, mode)| {
+                    Some((language.trim().to_lowercase(), parse_mode(mode)?))
+                })
+                .collect(),
+        }
+    }
+
+    fn mode_for(&self, language: &str) -> Mode {
+        self.languages.get(language).copied().unwrap_or(self.mode)
+    }
+
+    pub async fn restore(
+        &self,
+        text: &str,
+        language: &str,
+        llm: impl FnOnce() -> Result<Box<dyn LlmProvider>, String>,
+    ) -> String {
+        match self.mode_for(language) {
+            Mode::Off => text.to_string(),
+            Mode::Rules => restore_with_rules(text, language),
+            Mode::Llm => match llm() {
+                Ok(llm) => restore_with_llm(text, language, llm.as_ref()).await,
+                Err(err) => {
+                    warn!("[punctuation] model unavailable: {err}");
+                    restore_with_rules(text, language)
+                }
+            },
+        }
+    }
+}
+
+// Post-ASR stage ahead of PII masking; a no-op unless `punctuation.enabled`. `language` is the
+// session's, or the current ASR language when `None`.
+pub async fn restore_punctuation(app: &AppHandle, text: String, language: Option<&str>) -> String {
+    let Some(punctuator) = Punctuator::from_config() else {
+        return text;
+    };
+    if text.trim().is_empty() {
+        return text;
+    }
+    let language = language
+        .map(str::to_string)
+        .or_else(|| app.try_state::<AsrState>().map(|state| state.language()))
+        .filter(|language| !language.is_empty() && language != "auto")
+        .unwrap_or_else(|| script_language(&text).to_string());
+    punctuator
+        .restore(&text, &language, || {
+            let provider = crate::selected_translate_provider(app);
+            load_config()
+                .and_then(|config| provider_from_config(&provider, &config))
+                .map(|llm| metered(llm, UsageState::of(app)))
+        })
+        .await
+}
+
+async fn restore_with_llm(text: &str, language: &str, llm: &dyn LlmProvider) -> String {
+    let prompt = LlmPrompt::new(PUNCTUATION_PROMPT)
+        .with_input(text)
+        .with_temperature(0.0)
+        .for_feature("punctuation");
+    match llm.complete(&prompt).await {
+        Ok(reply) if same_words(text, reply.trim()) => reply.trim().to_string(),
+        Ok(_) => {
+            warn!("[punctuation] model changed the wording, using rules");
+            restore_with_rules(text, language)
+        }
+        Err(err) => {
+            warn!("[punctuation] restore failed: {err}");
+            restore_with_rules(text, language)
+        }
+    }
+}
+
+// Compares letters and digits only, so added punctuation, spacing and casing are fine.
+fn same_words(original: &str, restored: &str) -> bool {
+    let letters = |text: &str| {
+        text.chars()
+            .filter(|ch| ch.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    !restored.is_empty() && letters(original) == letters(restored)
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}')
+}
+
+fn is_cjk_punctuation(ch: char) -> bool {
+    matches!(ch,
+        '\u{3000}'..='\u{303f}' | '\u{ff01}'..='\u{ff0f}' | '\u{ff1a}'..='\u{ff20}' | '\u{ff3b}'..='\u{ff40}' | '\u{ff5b}'..='\u{ff65}')
+}
+
+fn is_kana(ch: char) -> bool {
+    matches!(ch, '\u{3040}'..='\u{30ff}')
+}
+
+// For `auto`: kana means Japanese, other CJK text Chinese.
+pub(crate) fn script_language(text: &str) -> &'static str {
+    if text.chars().any(is_kana) {
+        "ja"
+    } else if text.chars().any(is_cjk) {
+        "zh"
+    } else {
+        "en"
+    }
+}
+
+fn restore_with_rules(text: &str, language: &str) -> String {
+    let text = text.trim();
+    if text.is_empty() {
+        return String::new();
+    }
+    if matches!(language, "ja" | "zh") && text.chars().any(is_cjk) {
+        restore_cjk(text, language)
+    } else {
+        restore_latin(text)
+    }
+}
+
+// whisper marks pauses between CJK words with spaces; those become commas, and the sentence
+// gets a closing mark.
+fn restore_cjk(text: &str, language: &str) -> String {
+    let comma = if language == "ja" { "、" } else { "，" };
+    let mut output = String::new();
+    for word in text.split_whitespace() {
+        if let (Some(before), Some(after)) = (output.chars().last(), word.chars().next()) {
+            // A space next to existing punctuation is just dropped.
+            if !(is_cjk_punctuation(before) || is_cjk_punctuation(after)) {
+                output.push_str(if is_cjk(before) && is_cjk(after) {
+                    comma
+                } else {
+                    " "
+                });
+            }
+        }
+        output.push_str(word);
+    }
+    let last = output.chars().last().unwrap_or_default();
+    if !(is_cjk_punctuation(last) || matches!(last, '.' | '!' | '?' | '…' | ')')) {
+        let question = match language {
+            "ja" => output.ends_with('か'),
+            _ => output.ends_with('吗') || output.ends_with('呢'),
+        };
+        output.push(if question { '？' } else { '。' });
+    }
+    output
+}
+
+// Capitalizes sentence starts and a lone `i`, and closes the last sentence.
+fn restore_latin(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 1);
+    let mut sentence_start = true;
+    for word in text.split_whitespace() {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        let mut chars = word.chars();
+        let lone_i = word == "i" || word.starts_with("i'");
+        match chars.next() {
+            Some(first) if sentence_start || lone_i => {
+                output.extend(first.to_uppercase());
+                output.push_str(chars.as_str());
+            }
+            _ => output.push_str(word),
+        }
+        sentence_start = word.ends_with(['.', '!', '?']);
+    }
+    let last = output.chars().last().unwrap_or_default();
+    if last.is_alphanumeric() {
+        let first = text
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        output.push(if QUESTION_WORDS.contains(&first.as_str()) {
+            '?'
+        } else {
+            '.'
+        });
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{restore_with_rules, Punctuator};
+    use crate::app_config::PunctuationConfig;
+    use crate::llm::mock::MockLlm;
+    use crate::llm::LlmProvider;
+    use crate::runtime::block_on;
+    use std::collections::HashMap;
+
+    #[test]
+    fn restores_punctuation_by_rules_or_model_per_language() {
+        assert_eq!(
+            restore_with_rules("今日は 会議を 始めます", "ja"),
+            "今日は、会議を、始めます。"
+        );
+        assert_eq!(restore_with_rules("准备好了吗", "zh"), "准备好了吗？");
+        assert_eq!(
+            restore_with_rules("下一个议题是 iPhone 15 的发布 请大家讨论", "zh"),
+            "下一个议题是 iPhone 15 的发布，请大家讨论。"
+        );
+        assert_eq!(
+            restore_with_rules("はい、 そうです。", "ja"),
+            "はい、そうです。"
+        );
+        assert_eq!(
+            restore_with_rules("ok i think so. let's move on", "en"),
+            "Ok I think so. Let's move on."
+        );
+        assert_eq!(
+            restore_with_rules("what time is it", "en"),
+            "What time is it?"
+        );
+
+        let punctuator = Punctuator::from_settings(&PunctuationConfig {
+            enabled: Some(true),
+            mode: Some("off".to_string()),
+            languages: Some(HashMap::from([("ja".to_string(), "llm".to_string())])),
+        });
+        let mock = |reply: &'static str| {
+            move || Ok(Box::new(MockLlm::new(&[reply])) as Box<dyn LlmProvider>)
+        };
+        assert_eq!(
+            block_on(punctuator.restore("hello there", "en", mock("unused"))),
+            "hello there"
+        );
+        assert_eq!(
+            block_on(punctuator.restore(
+                "それでは 始めましょう",
+                "ja",
+                mock("それでは、始めましょう。")
+            )),
+            "それでは、始めましょう。"
+        );
+        // A reply that rewrites the words is dropped in favour of the rules.
+        assert_eq!(
+            block_on(punctuator.restore("それでは 始めましょう", "ja", mock("では、始めます。"))),
+            "それでは、始めましょう。"
+        );
+    }
+}
@@ -1,4 +1,6 @@
+use crate::acceleration::ProviderSelection;
 use std::sync::Arc;
+use tracing::warn;
 
 pub trait Embedder: Send + Sync {
     fn embed_documents(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, String>;
@@ -13,10 +15,27 @@ pub struct FastEmbedder {
 
 impl FastEmbedder {
     pub fn new() -> Result<Self, String> {
+        let selection = ProviderSelection::for_model("embedding");
         let options =
             fastembed::TextInitOptions::new(fastembed::EmbeddingModel::MultilingualE5Small);
-        let mut model =
-            fastembed::TextEmbedding::try_new(options).map_err(|err| err.to_string())?;
+        let mut model = match fastembed::TextEmbedding::try_new(
+            options
+                .clone()
+                .with_execution_providers(selection.providers.clone()),
+        ) {
+            Ok(model) => {
+                selection.record(None);
+                model
+            }
+            Err(err) if selection.uses_gpu() => {
+                warn!("embedding model failed on gpu, retrying on cpu: {err}");
+                let model =
+                    fastembed::TextEmbedding::try_new(options).map_err(|err| err.to_string())?;
+                selection.record(Some(err.to_string()));
+                model
+            }
+            Err(err) => return Err(err.to_string()),
+        };
 
         // warm-up to discover dimension
         let test = model
@@ -1,4 +1,5 @@
 use once_cell::sync::Lazy;
+use regex::Regex;
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -110,6 +111,98 @@ pub fn extension_allowed(path: &Path) -> bool {
     ALLOWED_EXTENSIONS.contains(ext.as_str())
 }
 
+// Per-project include/exclude globs, matched against the `/`-separated path relative to the
+// project root. A pattern without `/` matches the file name at any depth, as in .gitignore.
+#[derive(Debug, Clone, Default)]
+pub struct PathPatterns {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl PathPatterns {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| pattern.trim())
+                .filter(|pattern| !pattern.is_empty())
+                .map(glob_to_regex)
+                .collect::<Result<Vec<_>, String>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    // Exclude wins; with include patterns set, a file must match one of them.
+    pub fn allows_file(&self, relative: &str) -> bool {
+        if self
+            .exclude
+            .iter()
+            .any(|pattern| pattern.is_match(relative))
+        {
+            return false;
+        }
+        self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.is_match(relative))
+    }
+
+    // Lets the walk skip a whole excluded tree such as `**/test_data/**`.
+    pub fn allows_dir(&self, relative: &str) -> bool {
+        let dir = format!("{}/", relative.trim_end_matches('/'));
+        !self.exclude.iter().any(|pattern| pattern.is_match(&dir))
+    }
+}
+
+pub fn is_valid_glob(pattern: &str) -> bool {
+    glob_to_regex(pattern.trim()).is_ok()
+}
+
+fn glob_to_regex(pattern: &str) -> Result<Regex, String> {
+    let normalized = pattern.replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./").trim_start_matches('/');
+    let mut regex = String::from("(?i)^");
+    if !normalized.contains('/') {
+        regex.push_str("(?:.*/)?");
+    }
+    let mut chars = normalized.chars().peekable();
+    let mut in_group = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '{' if !in_group => {
+                in_group = true;
+                regex.push_str("(?:");
+            }
+            '}' if in_group => {
+                in_group = false;
+                regex.push(')');
+            }
+            ',' if in_group => regex.push('|'),
+            other => regex.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    if in_group {
+        return Err(format!("unclosed '{{' in pattern: {pattern}"));
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|err| format!("invalid pattern {pattern}: {err}"))
+}
+
 pub fn is_minified_code(path: &Path, text: &str) -> bool {
     let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
         return false;
@@ -153,7 +246,7 @@ pub fn is_minified_code(path: &Path, text: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{extension_allowed, is_minified_code};
+    use super::{extension_allowed, is_minified_code, PathPatterns};
     use std::path::Path;
 
     #[test]
@@ -167,4 +260,21 @@ mod tests {
         let path = Path::new("bundle.min.js");
         assert!(is_minified_code(path, "var a=1;"));
     }
+
+    #[test]
+    fn project_patterns_exclude_then_include() {
+        let patterns = PathPatterns::new(
+            &["src/**".to_string(), "*.md".to_string()],
+            &["**/test_data/**".to_string(), "*.{lock,snap}".to_string()],
+        )
+        .unwrap();
+        assert!(patterns.allows_file("src/main.rs"));
+        assert!(patterns.allows_file("docs/guide/README.md"));
+        assert!(!patterns.allows_file("scripts/build.sh"));
+        assert!(!patterns.allows_file("src/test_data/sample.rs"));
+        assert!(!patterns.allows_file("src/ui/view.snap"));
+        assert!(!patterns.allows_dir("src/parser/test_data"));
+        assert!(patterns.allows_dir("src/parser"));
+        assert!(PathPatterns::new(&[], &["src/{a,b".to_string()]).is_err());
+    }
 }
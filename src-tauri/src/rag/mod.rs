@@ -9,16 +9,22 @@ mod store;
 mod types;
 
 pub use types::{
-    IndexAddRequest, IndexRemoveRequest, IndexReport, IndexSyncRequest, RagProject,
+    ChunkHit, IndexAddRequest, IndexRemoveRequest, IndexReport, IndexSyncRequest, RagProject,
     RagProjectCreateRequest, RagProjectDeleteReport, RagProjectDeleteRequest,
-    RagProjectListResponse, RagSearchRequest, RagSearchResponse,
+    RagProjectFiltersRequest, RagProjectListResponse, RagSearchRequest, RagSearchResponse,
 };
 
-use projects::{create_project, list_projects, remove_project};
+use crate::app_config::load_config;
+use crate::defaults::DEFAULT_RAG_WARM_UP;
+use crate::events::{emit_ui_event, RAG_SYNC_FINISHED};
+use projects::{create_project, list_projects, remove_project, set_project_filters};
+use serde::Serialize;
 use service::{delete_project_index, RagService};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, State};
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
 
 pub struct RagState {
     inner: Mutex<Option<RagService>>,
@@ -47,6 +53,65 @@ impl RagState {
             .ok_or_else(|| "rag init failed".to_string())?;
         f(service)
     }
+
+    pub fn is_ready(&self) -> bool {
+        self.inner
+            .lock()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false)
+    }
+
+    // Builds the service on a blocking thread; calls that arrive meanwhile wait on the same lock
+    // instead of loading the model twice.
+    fn warm_up(self: &Arc<Self>, app: &AppHandle) {
+        let state = Arc::clone(self);
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let started_at = Instant::now();
+            let result = state.with_service(&app, |_| Ok(()));
+            let elapsed_ms = started_at.elapsed().as_millis() as u64;
+            match &result {
+                Ok(()) => info!("[rag] embedding model ready in {elapsed_ms}ms"),
+                Err(err) => warn!("[rag] warm-up failed: {err}"),
+            }
+            emit_ui_event(
+                &app,
+                "rag_ready",
+                RagReadyPayload {
+                    ready: result.is_ok(),
+                    elapsed_ms,
+                    error: result.err(),
+                },
+            );
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RagReadyPayload {
+    ready: bool,
+    elapsed_ms: u64,
+    error: Option<String>,
+}
+
+// Skipped when no project exists yet, since nothing can be searched until one is indexed.
+pub fn start(app: &AppHandle) {
+    let enabled = load_config()
+        .ok()
+        .and_then(|cfg| cfg.rag)
+        .and_then(|rag| rag.warm_up)
+        .unwrap_or(DEFAULT_RAG_WARM_UP);
+    if !enabled || list_projects(app).is_empty() {
+        return;
+    }
+    if let Some(state) = app.try_state::<Arc<RagState>>() {
+        state.warm_up(app);
+    }
+}
+
+#[tauri::command]
+pub fn rag_is_ready(state: State<'_, Arc<RagState>>) -> bool {
+    state.is_ready()
 }
 
 #[tauri::command]
@@ -77,9 +142,11 @@ pub async fn rag_index_sync_project(
     let app = app.clone();
     tauri::async_runtime::spawn_blocking(move || {
         let root_dir = request.root_dir.map(PathBuf::from);
-        state.with_service(&app, |service| {
+        let report = state.with_service(&app, |service| {
             service.index_sync_project(&app, &request.project_id, root_dir)
-        })
+        })?;
+        emit_ui_event(&app, RAG_SYNC_FINISHED, report.clone());
+        Ok(report)
     })
     .await
     .map_err(|err| err.to_string())?
@@ -147,6 +214,15 @@ pub fn rag_project_create(
     create_project(&app, &request.project_name, &root)
 }
 
+// Takes effect on the next sync, which also drops files the new excludes filter out.
+#[tauri::command]
+pub fn rag_project_set_filters(
+    app: AppHandle,
+    request: RagProjectFiltersRequest,
+) -> Result<RagProject, String> {
+    set_project_filters(&app, &request.project_id, request.include, request.exclude)
+}
+
 #[tauri::command]
 pub async fn rag_project_delete(
     app: AppHandle,
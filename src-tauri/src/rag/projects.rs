@@ -1,3 +1,4 @@
+use crate::rag::file_filter::{is_valid_glob, PathPatterns};
 use crate::rag::paths::projects_path;
 use crate::rag::types::RagProject;
 use chrono::Utc;
@@ -14,6 +15,11 @@ pub struct ProjectEntry {
     pub project_name: Option<String>,
     pub root_dir: String,
     pub updated_at: String,
+    // Globs relative to `root_dir`, applied on top of the built-in filters.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -53,6 +59,55 @@ pub fn get_project_root<R: Runtime>(app: &AppHandle<R>, project_id: &str) -> Opt
         .map(|entry| PathBuf::from(&entry.root_dir))
 }
 
+// Patterns that no longer compile are ignored rather than failing the whole sync.
+pub fn get_project_patterns<R: Runtime>(app: &AppHandle<R>, project_id: &str) -> PathPatterns {
+    let index = load_projects(app);
+    let Some(entry) = index
+        .projects
+        .iter()
+        .find(|entry| entry.project_id == project_id)
+    else {
+        return PathPatterns::default();
+    };
+    PathPatterns::new(&entry.include, &entry.exclude).unwrap_or_default()
+}
+
+pub fn set_project_filters<R: Runtime>(
+    app: &AppHandle<R>,
+    project_id: &str,
+    include: Vec<String>,
+    exclude: Vec<String>,
+) -> Result<RagProject, String> {
+    let clean = |patterns: Vec<String>| {
+        patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<Vec<_>>()
+    };
+    let include = clean(include);
+    let exclude = clean(exclude);
+    if let Some(invalid) = include
+        .iter()
+        .chain(&exclude)
+        .find(|pattern| !is_valid_glob(pattern))
+    {
+        return Err(format!("invalid pattern: {invalid}"));
+    }
+    let mut index = load_projects(app);
+    let entry = index
+        .projects
+        .iter_mut()
+        .find(|entry| entry.project_id == project_id)
+        .ok_or_else(|| format!("project not found: {project_id}"))?;
+    entry.include = include;
+    entry.exclude = exclude;
+    entry.updated_at = Utc::now().to_rfc3339();
+    let project = to_project_dto(entry);
+    save_projects(app, &index)?;
+    Ok(project)
+}
+
 pub fn list_projects<R: Runtime>(app: &AppHandle<R>) -> Vec<RagProject> {
     let mut projects = load_projects(app)
         .projects
@@ -104,6 +159,8 @@ pub fn create_project<R: Runtime>(
         project_name: Some(final_name),
         root_dir: canonical_root,
         updated_at: now,
+        include: Vec::new(),
+        exclude: Vec::new(),
     };
     index.projects.push(entry.clone());
     save_projects(app, &index)?;
@@ -153,6 +210,8 @@ pub fn upsert_project_root<R: Runtime>(
             project_name: Some(root_name),
             root_dir,
             updated_at: Utc::now().to_rfc3339(),
+            include: Vec::new(),
+            exclude: Vec::new(),
         });
     }
     save_projects(app, &index)
@@ -181,6 +240,8 @@ fn to_project_dto(entry: &ProjectEntry) -> RagProject {
         ),
         root_dir: entry.root_dir.clone(),
         updated_at: entry.updated_at.clone(),
+        include: entry.include.clone(),
+        exclude: entry.exclude.clone(),
     }
 }
 
@@ -1,12 +1,17 @@
 use crate::rag::chunker::chunk_text;
 use crate::rag::embedder::{normalize_embeddings, Embedder, FastEmbedder};
-use crate::rag::file_filter::{extension_allowed, is_minified_code, should_skip_path};
+use crate::rag::file_filter::{
+    extension_allowed, is_minified_code, should_skip_path, PathPatterns,
+};
 use crate::rag::lancedb_store::LanceDbStore;
 use crate::rag::paths::lancedb_path;
-use crate::rag::projects::{get_project_root, upsert_project_root};
+use crate::rag::projects::{get_project_patterns, get_project_root, upsert_project_root};
 use crate::rag::store::{RagManifestStore, RagStore};
 use crate::rag::types::{ChunkHit, ChunkRecord, FileRecord, IndexReport, SkippedFile};
 use chrono::Utc;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -18,6 +23,10 @@ const DEFAULT_CHUNK_OVERLAP: usize = 150;
 const DEFAULT_MAX_FILE_SIZE: u64 = 1_048_576;
 const DEFAULT_EMBEDDING_DIMENSION: usize = 384;
 
+// Upper bound on threads reading and hashing files during a sync, so a large checkout does not
+// starve capture and transcription of CPU and disk.
+const SCAN_MAX_THREADS: usize = 8;
+
 const QUERY_PREFIX: &str = "query: ";
 const PASSAGE_PREFIX: &str = "passage: ";
 
@@ -77,10 +86,11 @@ impl RagService {
             report.root_dir = Some(root_dir.to_string_lossy().to_string());
             let _ = upsert_project_root(app, project_id, root_dir);
         }
+        let patterns = get_project_patterns(app, project_id);
 
         for path in file_paths {
             let Some(candidate) =
-                self.prepare_file_candidate(project_id, &path, root_dir.as_deref())?
+                self.prepare_file_candidate(project_id, &path, root_dir.as_deref(), &patterns)?
             else {
                 report.skipped_files.push(SkippedFile {
                     path: path.to_string_lossy().to_string(),
@@ -155,7 +165,10 @@ impl RagService {
         };
         report.root_dir = Some(root_dir.to_string_lossy().to_string());
 
-        let candidates = self.scan_project_files(project_id, &root_dir)?;
+        // Files that a newly added exclude pattern filters out drop out of `current` and are
+        // removed from the index below like deleted files.
+        let patterns = get_project_patterns(app, project_id);
+        let candidates = self.scan_project_files(project_id, &root_dir, &patterns)?;
         let mut current = HashMap::new();
         for candidate in candidates {
             current.insert(candidate.file_id.clone(), candidate);
@@ -317,28 +330,29 @@ impl RagService {
         &mut self,
         project_id: &str,
         root_dir: &Path,
+        patterns: &PathPatterns,
     ) -> Result<Vec<FileCandidate>, String> {
-        let mut candidates = Vec::new();
-        for entry in walkdir::WalkDir::new(root_dir)
-            .follow_links(false)
-            .into_iter()
-            .filter_entry(|entry| should_skip_path(entry.path()).is_none())
-        {
-            let entry = match entry {
-                Ok(entry) => entry,
-                Err(_) => continue,
-            };
-            if !entry.file_type().is_file() {
-                continue;
-            }
-            let path = entry.path();
-            let Some(candidate) = self.prepare_file_candidate(project_id, path, Some(root_dir))?
-            else {
-                continue;
-            };
-            candidates.push(candidate);
-        }
-        Ok(candidates)
+        let max_file_size = self.max_file_size;
+        let scan = || {
+            collect_project_paths(root_dir, patterns)
+                .par_iter()
+                .map(|path| {
+                    prepare_file_candidate(
+                        project_id,
+                        path,
+                        Some(root_dir),
+                        patterns,
+                        max_file_size,
+                    )
+                })
+                .collect::<Result<Vec<_>, String>>()
+        };
+        // Without the bounded pool the scan still runs, on rayon's global pool.
+        let candidates = match SCAN_POOL.as_ref() {
+            Some(pool) => pool.install(scan),
+            None => scan(),
+        }?;
+        Ok(candidates.into_iter().flatten().collect())
     }
 
     fn prepare_file_candidate(
@@ -346,44 +360,109 @@ impl RagService {
         project_id: &str,
         path: &Path,
         root_dir: Option<&Path>,
+        patterns: &PathPatterns,
     ) -> Result<Option<FileCandidate>, String> {
-        if should_skip_path(path).is_some() {
-            return Ok(None);
-        }
-        if !extension_allowed(path) {
-            return Ok(None);
-        }
-        let text = match read_text(path, self.max_file_size) {
-            Ok(text) => text,
-            Err(_) => return Ok(None),
-        };
-        if is_minified_code(path, &text) {
+        prepare_file_candidate(project_id, path, root_dir, patterns, self.max_file_size)
+    }
+}
+
+static SCAN_POOL: Lazy<Option<ThreadPool>> = Lazy::new(|| {
+    let threads = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .min(SCAN_MAX_THREADS);
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("rag-scan-{index}"))
+        .build()
+        .ok()
+});
+
+// Each top-level entry is walked on its own thread; results keep a stable, sorted order so
+// reports do not change between runs.
+fn collect_project_paths(root_dir: &Path, patterns: &PathPatterns) -> Vec<PathBuf> {
+    if should_skip_path(root_dir).is_some() {
+        return Vec::new();
+    }
+    let dir_allowed = |path: &Path| {
+        patterns.allows_dir(&normalize_relative_path(root_dir, path).unwrap_or_default())
+    };
+    let Ok(entries) = fs::read_dir(root_dir) else {
+        return Vec::new();
+    };
+    let mut top_level = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| !kind.is_symlink()))
+        .map(|entry| entry.path())
+        .filter(|path| should_skip_path(path).is_none())
+        .filter(|path| !path.is_dir() || dir_allowed(path))
+        .collect::<Vec<_>>();
+    top_level.sort();
+    top_level
+        .par_iter()
+        .flat_map_iter(|path| {
+            walkdir::WalkDir::new(path)
+                .follow_links(false)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|entry| {
+                    should_skip_path(entry.path()).is_none()
+                        && (!entry.file_type().is_dir() || dir_allowed(entry.path()))
+                })
+                .flatten()
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+        })
+        .collect()
+}
+
+fn prepare_file_candidate(
+    project_id: &str,
+    path: &Path,
+    root_dir: Option<&Path>,
+    patterns: &PathPatterns,
+    max_file_size: u64,
+) -> Result<Option<FileCandidate>, String> {
+    if should_skip_path(path).is_some() {
+        return Ok(None);
+    }
+    if !extension_allowed(path) {
+        return Ok(None);
+    }
+    let relative = if let Some(root_dir) = root_dir {
+        let relative = normalize_relative_path(root_dir, path)?;
+        if !patterns.allows_file(&relative) {
             return Ok(None);
         }
-        let relative = if let Some(root_dir) = root_dir {
-            normalize_relative_path(root_dir, path)?
-        } else {
-            normalize_filename_only(path)
-        };
-        let file_hash = hash_text(text.as_bytes());
-        let file_id = hash_text(&format!("{project_id}:{relative}"));
-        let metadata = fs::metadata(path).ok();
-        let mtime = metadata
-            .as_ref()
-            .and_then(|meta| meta.modified().ok())
-            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|time| time.as_secs() as i64);
-        let size = metadata.map(|meta| meta.len() as i64);
-
-        Ok(Some(FileCandidate {
-            file_id,
-            file_path: relative,
-            file_hash,
-            text,
-            mtime,
-            size,
-        }))
+        relative
+    } else {
+        normalize_filename_only(path)
+    };
+    let text = match read_text(path, max_file_size) {
+        Ok(text) => text,
+        Err(_) => return Ok(None),
+    };
+    if is_minified_code(path, &text) {
+        return Ok(None);
     }
+    let file_hash = hash_text(text.as_bytes());
+    let file_id = hash_text(&format!("{project_id}:{relative}"));
+    let metadata = fs::metadata(path).ok();
+    let mtime = metadata
+        .as_ref()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|time| time.as_secs() as i64);
+    let size = metadata.map(|meta| meta.len() as i64);
+
+    Ok(Some(FileCandidate {
+        file_id,
+        file_path: relative,
+        file_hash,
+        text,
+        mtime,
+        size,
+    }))
 }
 
 pub fn delete_project_index<R: Runtime>(
@@ -591,6 +670,30 @@ mod tests {
         hash_text(format!("{project_id}:{text}"))
     }
 
+    #[test]
+    fn collects_project_paths_in_sorted_order_across_threads() {
+        let root = temp_root("scan");
+        fs::create_dir_all(root.join("b/nested")).unwrap();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("b/nested/two.md"), "two").unwrap();
+        fs::write(root.join("b/one.md"), "one").unwrap();
+        fs::write(root.join("a/zero.txt"), "zero").unwrap();
+        fs::write(root.join("node_modules/pkg/index.js"), "skip").unwrap();
+        fs::write(root.join("readme.md"), "top").unwrap();
+
+        let relative = collect_project_paths(&root, &PathPatterns::default())
+            .iter()
+            .map(|path| normalize_relative_path(&root, path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relative,
+            vec!["a/zero.txt", "b/nested/two.md", "b/one.md", "readme.md"]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn index_add_and_search() {
         let _guard = TEST_LOCK.lock().unwrap();
@@ -0,0 +1,268 @@
+use crate::rag::{ChunkHit, RagState};
+use crate::{answer_with_hits, selected_translate_provider, RagAnswerResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+pub struct RagAskBatchRequest {
+    pub questions: Vec<String>,
+    pub project_ids: Vec<String>,
+    pub top_k: Option<usize>,
+    pub allow_out_of_context: Option<bool>,
+    pub translate_answer: Option<bool>,
+    // Also writes the Markdown report to this file.
+    pub export_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RagBatchItem {
+    pub question: String,
+    pub response: Option<RagAnswerResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RagBatchReport {
+    pub provider: String,
+    pub items: Vec<RagBatchItem>,
+    // Distinct chunks retrieved for the whole checklist, and how many of them served more than
+    // one question.
+    pub unique_chunks: usize,
+    pub shared_chunks: usize,
+    pub markdown: String,
+    pub exported_to: Option<String>,
+}
+
+// Retrieval results per distinct question. A checklist often repeats or rephrases items, so a
+// question is searched (and answered) once however often it appears.
+#[derive(Default)]
+struct ContextCache {
+    hits: HashMap<String, Result<Vec<ChunkHit>, String>>,
+    // Distinct questions each chunk was retrieved for.
+    chunk_uses: HashMap<String, usize>,
+}
+
+impl ContextCache {
+    fn key(question: &str) -> String {
+        question
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end_matches(['?', '？', '.', '。'])
+            .to_lowercase()
+    }
+
+    fn contains(&self, question: &str) -> bool {
+        self.hits.contains_key(&Self::key(question))
+    }
+
+    fn insert(&mut self, question: &str, hits: Result<Vec<ChunkHit>, String>) {
+        if let Ok(hits) = &hits {
+            for hit in hits {
+                *self.chunk_uses.entry(hit.chunk_id.clone()).or_default() += 1;
+            }
+        }
+        self.hits.insert(Self::key(question), hits);
+    }
+
+    fn get(&self, question: &str) -> Option<&Result<Vec<ChunkHit>, String>> {
+        self.hits.get(&Self::key(question))
+    }
+
+    fn shared_chunks(&self) -> usize {
+        self.chunk_uses.values().filter(|uses| **uses > 1).count()
+    }
+}
+
+#[tauri::command]
+pub async fn rag_ask_batch(
+    app: AppHandle,
+    state: State<'_, Arc<RagState>>,
+    request: RagAskBatchRequest,
+) -> Result<RagBatchReport, String> {
+    let questions = request
+        .questions
+        .iter()
+        .map(|question| question.trim().to_string())
+        .filter(|question| !question.is_empty())
+        .collect::<Vec<_>>();
+    if questions.is_empty() {
+        return Err("questions is empty".to_string());
+    }
+    if request.project_ids.is_empty() {
+        return Err("project_ids is empty".to_string());
+    }
+    let top_k = request.top_k.unwrap_or(8).clamp(1, 20);
+    let allow_out_of_context = request.allow_out_of_context.unwrap_or(false);
+    let translate_answer = request.translate_answer.unwrap_or(false);
+    let provider = selected_translate_provider(&app);
+    info!(
+        "[rag] batch of {} questions over {} projects",
+        questions.len(),
+        request.project_ids.len()
+    );
+
+    // All retrieval happens under one service lock; a failed search only fails its question.
+    let state = state.inner().clone();
+    let app_handle = app.clone();
+    let search_questions = questions.clone();
+    let project_ids = request.project_ids;
+    let cache = tauri::async_runtime::spawn_blocking(move || {
+        state.with_service(&app_handle, |service| {
+            let mut cache = ContextCache::default();
+            for question in &search_questions {
+                if !cache.contains(question) {
+                    let hits = service.search(question, project_ids.clone(), top_k);
+                    cache.insert(question, hits);
+                }
+            }
+            Ok(cache)
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+
+    let mut answers: HashMap<String, Result<RagAnswerResponse, String>> = HashMap::new();
+    let mut items = Vec::with_capacity(questions.len());
+    for question in questions {
+        let key = ContextCache::key(&question);
+        if !answers.contains_key(&key) {
+            let answer = match cache.get(&question) {
+                Some(Ok(hits)) => {
+                    answer_with_hits(
+                        &app,
+                        provider.clone(),
+                        &question,
+                        hits,
+                        allow_out_of_context,
+                        translate_answer,
+                    )
+                    .await
+                }
+                Some(Err(err)) => Err(err.clone()),
+                None => Err("question was not searched".to_string()),
+            };
+            answers.insert(key.clone(), answer);
+        }
+        let (response, error) = match &answers[&key] {
+            Ok(response) => (Some(response.clone()), None),
+            Err(err) => (None, Some(err.clone())),
+        };
+        items.push(RagBatchItem {
+            question,
+            response,
+            error,
+        });
+    }
+
+    let markdown = render_report(&items);
+    let exported_to = match request
+        .export_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        Some(path) => Some(export_report(Path::new(path), &markdown)?),
+        None => None,
+    };
+    Ok(RagBatchReport {
+        provider,
+        items,
+        unique_chunks: cache.chunk_uses.len(),
+        shared_chunks: cache.shared_chunks(),
+        markdown,
+        exported_to,
+    })
+}
+
+// `level` is the heading level of the question; references sit one level below.
+pub(crate) fn answer_markdown(
+    question: &str,
+    response: &RagAnswerResponse,
+    level: usize,
+) -> String {
+    let heading = "#".repeat(level);
+    let mut output = format!("{heading} {question}\n\n{}\n", response.answer.trim());
+    if !response.references.is_empty() {
+        output.push_str(&format!("\n{heading}# References\n\n"));
+        for reference in &response.references {
+            output.push_str(&format!(
+                "[{}] {} ({:.4})\n",
+                reference.index, reference.file_path, reference.score
+            ));
+        }
+    }
+    output
+}
+
+fn render_report(items: &[RagBatchItem]) -> String {
+    let mut output = String::from("# RAG Q&A report\n");
+    for (index, item) in items.iter().enumerate() {
+        let question = format!("{}. {}", index + 1, item.question);
+        output.push('\n');
+        match (&item.response, &item.error) {
+            (Some(response), _) => output.push_str(&answer_markdown(&question, response, 2)),
+            (None, error) => output.push_str(&format!(
+                "## {question}\n\n> error: {}\n",
+                error.as_deref().unwrap_or("no answer")
+            )),
+        }
+    }
+    output
+}
+
+fn export_report(path: &Path, markdown: &str) -> Result<String, String> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, markdown).map_err(|err| err.to_string())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContextCache;
+    use crate::rag::ChunkHit;
+
+    fn hit(chunk_id: &str) -> ChunkHit {
+        serde_json::from_value(serde_json::json!({
+            "project_id": "p",
+            "file_id": "f",
+            "file_path": "src/main.rs",
+            "chunk_id": chunk_id,
+            "chunk_index": 0,
+            "text": "fn main() {}",
+            "score": 0.5,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn shares_retrieval_between_repeated_questions() {
+        let mut cache = ContextCache::default();
+        cache.insert("How is auth handled?", Ok(vec![hit("a"), hit("b")]));
+        cache.insert("Where are secrets stored", Ok(vec![hit("b"), hit("c")]));
+        cache.insert("Which licenses apply?", Err("search failed".to_string()));
+
+        assert!(cache.contains("how  is auth handled"));
+        assert!(cache.contains("How is auth handled？"));
+        assert!(!cache.contains("How is logging handled?"));
+        assert_eq!(
+            cache
+                .get("where are secrets stored?")
+                .and_then(|hits| hits.as_ref().ok())
+                .map(Vec::len),
+            Some(2)
+        );
+        assert!(cache.get("Which licenses apply").unwrap().is_err());
+        assert_eq!(cache.chunk_uses.len(), 3);
+        assert_eq!(cache.shared_chunks(), 1);
+    }
+}
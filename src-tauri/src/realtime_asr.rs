@@ -0,0 +1,340 @@
+// Live captions from the OpenAI realtime transcription API. Capture audio is streamed over a
+// WebSocket as it arrives, instead of the rolling window being written to a WAV and uploaded on
+// every step.
+
+use crate::app_config::load_config;
+use crate::audio::resample::MonoResampler;
+use crate::defaults::{
+    DEFAULT_ASR_LIVE_PROVIDER, DEFAULT_OPENAI_REALTIME_URL, DEFAULT_REALTIME_TRANSCRIBE_MODEL,
+};
+use crate::events::{emit_output_event, emit_ui_event, REALTIME_TRANSCRIPT};
+use crate::live_draft;
+use crate::pii::mask_text;
+use crate::redact::redact;
+use crate::word_filter::filter_transcript;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+// pcm16 input to the realtime API is 24 kHz mono.
+const REALTIME_SAMPLE_RATE: u32 = 24_000;
+// Capture chunks waiting to be sent; a connection that falls this far behind loses audio rather
+// than stalling capture.
+const SEND_QUEUE_CHUNKS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RealtimeTranscript {
+    // One utterance as cut by the server's VAD.
+    pub item_id: String,
+    pub text: String,
+    // `false` while the utterance is still being transcribed.
+    pub is_final: bool,
+}
+
+struct Session {
+    url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+}
+
+pub struct RealtimeStream {
+    sender: mpsc::Sender<Vec<f32>>,
+    lagging: bool,
+}
+
+impl RealtimeStream {
+    // Connects in the background when `asr.liveProvider` is `openai-realtime`; `None` otherwise.
+    pub fn start(
+        app: &AppHandle,
+        sample_rate: u32,
+        channels: u16,
+        cancel: CancellationToken,
+    ) -> Option<Self> {
+        let config = load_config().ok()?;
+        let asr = config.asr.unwrap_or_default();
+        let provider = asr
+            .live_provider
+            .as_deref()
+            .unwrap_or(DEFAULT_ASR_LIVE_PROVIDER)
+            .trim()
+            .to_lowercase();
+        if provider != "openai-realtime" {
+            return None;
+        }
+        let api_key = config.openai.api_key.trim().to_string();
+        if api_key.is_empty() {
+            warn!("[realtime] OpenAI apiKey is required, using window transcription");
+            return None;
+        }
+        let resampler =
+            match MonoResampler::with_target(sample_rate, channels, REALTIME_SAMPLE_RATE) {
+                Ok(resampler) => resampler,
+                Err(err) => {
+                    warn!("[realtime] resampler unavailable: {err}");
+                    return None;
+                }
+            };
+        let session = Session {
+            url: asr
+                .realtime_url
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_OPENAI_REALTIME_URL.to_string()),
+            api_key,
+            model: asr
+                .realtime_model
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_REALTIME_TRANSCRIBE_MODEL.to_string()),
+            language: asr
+                .language
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty() && value != "auto"),
+        };
+        let (sender, receiver) = mpsc::channel(SEND_QUEUE_CHUNKS);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = run(&app, session, receiver, resampler, cancel).await {
+                warn!("[realtime] stream ended: {err}");
+            }
+        });
+        Some(Self {
+            sender,
+            lagging: false,
+        })
+    }
+
+    // `false` once the connection is gone, so the caller can go back to window transcription.
+    pub fn push(&mut self, pcm: &[f32]) -> bool {
+        match self.sender.try_send(pcm.to_vec()) {
+            Ok(()) => {
+                self.lagging = false;
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                if !self.lagging {
+                    warn!("[realtime] connection is behind, dropping audio");
+                }
+                self.lagging = true;
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+async fn run(
+    app: &AppHandle,
+    session: Session,
+    mut audio: mpsc::Receiver<Vec<f32>>,
+    mut resampler: MonoResampler,
+    cancel: CancellationToken,
+) -> Result<(), String> {
+    let mut request = session
+        .url
+        .as_str()
+        .into_client_request()
+        .map_err(|err| err.to_string())?;
+    let authorization = HeaderValue::from_str(&format!("Bearer {}", session.api_key))
+        .map_err(|err| err.to_string())?;
+    request.headers_mut().insert("Authorization", authorization);
+    request
+        .headers_mut()
+        .insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|err| redact(&err.to_string()).into_owned())?;
+    let (mut write, mut read) = socket.split();
+    write
+        .send(Message::text(session_update(&session).to_string()))
+        .await
+        .map_err(|err| err.to_string())?;
+    info!("[realtime] connected, model={}", session.model);
+
+    let mut utterances = Utterances::default();
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            chunk = audio.recv() => {
+                let Some(chunk) = chunk else {
+                    break;
+                };
+                let mono = resampler.process(&chunk)?;
+                if mono.is_empty() {
+                    continue;
+                }
+                write
+                    .send(Message::text(append_event(&mono).to_string()))
+                    .await
+                    .map_err(|err| err.to_string())?;
+            }
+            message = read.next() => {
+                let message = message
+                    .ok_or_else(|| "connection closed".to_string())?
+                    .map_err(|err| err.to_string())?;
+                if message.is_close() {
+                    return Err("connection closed by server".to_string());
+                }
+                let Some(event) = message
+                    .to_text()
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<Value>(text).ok())
+                else {
+                    continue;
+                };
+                if event.get("type").and_then(Value::as_str) == Some("error") {
+                    let message = event
+                        .pointer("/error/message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown error");
+                    warn!("[realtime] {}", redact(message));
+                    continue;
+                }
+                if let Some(transcript) = utterances.apply(&event) {
+                    publish(app, transcript);
+                }
+            }
+        }
+    }
+    let _ = write.send(Message::Close(None)).await;
+    Ok(())
+}
+
+// Live drafts get the regex pass only, like window transcripts; partials stay out of the event
+// hub and the journal.
+fn publish(app: &AppHandle, transcript: RealtimeTranscript) {
+    let transcript = RealtimeTranscript {
+        text: filter_transcript(app, &mask_text(transcript.text.trim())),
+        ..transcript
+    };
+    live_draft::publish(app, &transcript.text);
+    if transcript.is_final {
+        emit_ui_event(app, REALTIME_TRANSCRIPT, transcript);
+    } else {
+        emit_output_event(app, REALTIME_TRANSCRIPT, transcript);
+    }
+}
+
+fn session_update(session: &Session) -> Value {
+    let mut transcription = json!({ "model": session.model });
+    if let Some(language) = &session.language {
+        transcription["language"] = Value::from(language.as_str());
+    }
+    json!({
+        "type": "transcription_session.update",
+        "session": {
+            "input_audio_format": "pcm16",
+            "input_audio_transcription": transcription,
+            "turn_detection": { "type": "server_vad" },
+        },
+    })
+}
+
+fn append_event(mono: &[f32]) -> Value {
+    let mut bytes = Vec::with_capacity(mono.len() * 2);
+    for sample in mono {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    json!({ "type": "input_audio_buffer.append", "audio": BASE64.encode(bytes) })
+}
+
+// Text of the utterances still being transcribed, keyed by item.
+#[derive(Default)]
+struct Utterances {
+    partial: HashMap<String, String>,
+}
+
+impl Utterances {
+    // The transcript a server event changes, if any.
+    fn apply(&mut self, event: &Value) -> Option<RealtimeTranscript> {
+        let kind = event.get("type").and_then(Value::as_str)?;
+        let item_id = event.get("item_id").and_then(Value::as_str)?.to_string();
+        match kind {
+            "conversation.item.input_audio_transcription.delta" => {
+                let delta = event.get("delta").and_then(Value::as_str)?;
+                let text = self.partial.entry(item_id.clone()).or_default();
+                text.push_str(delta);
+                Some(RealtimeTranscript {
+                    item_id,
+                    text: text.clone(),
+                    is_final: false,
+                })
+            }
+            "conversation.item.input_audio_transcription.completed" => {
+                let partial = self.partial.remove(&item_id);
+                let text = event
+                    .get("transcript")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .or(partial)?;
+                Some(RealtimeTranscript {
+                    item_id,
+                    text,
+                    is_final: true,
+                })
+            }
+            "conversation.item.input_audio_transcription.failed" => {
+                self.partial.remove(&item_id);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_event, Utterances};
+    use serde_json::json;
+
+    #[test]
+    fn accumulates_deltas_until_the_utterance_completes() {
+        let mut utterances = Utterances::default();
+        let delta = |item: &str, text: &str| {
+            json!({
+                "type": "conversation.item.input_audio_transcription.delta",
+                "item_id": item,
+                "delta": text,
+            })
+        };
+        assert_eq!(
+            utterances.apply(&delta("a", "Good ")).unwrap().text,
+            "Good "
+        );
+        let partial = utterances.apply(&delta("a", "morning")).unwrap();
+        assert_eq!(
+            (partial.text.as_str(), partial.is_final),
+            ("Good morning", false)
+        );
+        assert!(utterances
+            .apply(&json!({ "type": "input_audio_buffer.speech_started", "item_id": "b" }))
+            .is_none());
+
+        let done = utterances
+            .apply(&json!({
+                "type": "conversation.item.input_audio_transcription.completed",
+                "item_id": "a",
+                "transcript": "Good morning, everyone.",
+            }))
+            .unwrap();
+        assert_eq!(
+            (done.item_id.as_str(), done.text.as_str(), done.is_final),
+            ("a", "Good morning, everyone.", true)
+        );
+        assert!(utterances.partial.is_empty());
+
+        // Two samples of pcm16, little-endian: 0x3fff and -0x7fff.
+        let append = append_event(&[0.5, -1.0]);
+        assert_eq!(append["audio"], "/z8BgA==");
+    }
+}
@@ -0,0 +1,189 @@
+use super::{ApiError, Authorized, RemoteState, ServerState};
+use crate::audio::CaptureManager;
+use crate::translate::set_target_language;
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::Manager;
+
+struct ActionSpec {
+    name: &'static str,
+    params: &'static [&'static str],
+    description: &'static str,
+}
+
+// Single-purpose triggers for Stream Deck buttons and AutoHotkey scripts; GET and POST both work.
+const ACTIONS: &[ActionSpec] = &[
+    ActionSpec {
+        name: "capture-start",
+        params: &[],
+        description: "Start capturing a new session",
+    },
+    ActionSpec {
+        name: "capture-stop",
+        params: &[],
+        description: "Stop the running capture",
+    },
+    ActionSpec {
+        name: "capture-toggle",
+        params: &[],
+        description: "Start capturing if idle, otherwise stop",
+    },
+    ActionSpec {
+        name: "bookmark",
+        params: &["note"],
+        description: "Bookmark the current moment of the running session",
+    },
+    ActionSpec {
+        name: "language",
+        params: &["value"],
+        description: "Switch the translation target language, e.g. value=en",
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAction {
+    pub name: String,
+    pub methods: Vec<String>,
+    pub path: String,
+    pub params: Vec<String>,
+    pub description: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ActionQuery {
+    note: Option<String>,
+    value: Option<String>,
+}
+
+pub(crate) fn routes() -> Router<ServerState> {
+    Router::new().route("/actions/{action}", get(run).post(run))
+}
+
+async fn run(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Path(action): Path<String>,
+    Query(query): Query<ActionQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let app = state.app.clone();
+    let response = match action.as_str() {
+        "capture-start" => {
+            start_capture(&state).await?;
+            json!({ "action": action, "capturing": true })
+        }
+        "capture-stop" => {
+            stop_capture(&state).await?;
+            json!({ "action": action, "capturing": false })
+        }
+        "capture-toggle" => {
+            let capturing = capture_manager(&state)?.is_capturing();
+            if capturing {
+                stop_capture(&state).await?;
+            } else {
+                start_capture(&state).await?;
+            }
+            json!({ "action": action, "capturing": !capturing })
+        }
+        "bookmark" => {
+            let bookmark = capture_manager(&state)?.add_bookmark(&app, query.note)?;
+            json!({ "action": action, "bookmark": bookmark })
+        }
+        "language" => {
+            let value = query.value.unwrap_or_default();
+            let language = set_target_language(&value)?;
+            json!({ "action": action, "targetLanguage": language })
+        }
+        _ => return Err(ApiError::not_found(format!("unknown action: {action}"))),
+    };
+    Ok(Json(response))
+}
+
+async fn start_capture(state: &ServerState) -> Result<(), ApiError> {
+    let app = state.app.clone();
+    // Starting opens the device and spawns the capture thread, so keep it off the async executor.
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app
+            .try_state::<CaptureManager>()
+            .ok_or_else(|| "capture manager unavailable".to_string())?;
+        manager.start(app.clone())
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(())
+}
+
+async fn stop_capture(state: &ServerState) -> Result<(), ApiError> {
+    let app = state.app.clone();
+    // Stopping joins the capture thread, so keep it off the async executor.
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app
+            .try_state::<CaptureManager>()
+            .ok_or_else(|| "capture manager unavailable".to_string())?;
+        manager.stop(&app, false)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(())
+}
+
+fn capture_manager(state: &ServerState) -> Result<tauri::State<'_, CaptureManager>, ApiError> {
+    state
+        .app
+        .try_state::<CaptureManager>()
+        .ok_or_else(|| ApiError::unavailable("capture manager unavailable"))
+}
+
+// `server` is the address and token of a running server with the API enabled.
+fn describe_actions(server: Option<(&str, &str)>) -> Vec<RemoteAction> {
+    ACTIONS
+        .iter()
+        .map(|spec| {
+            let path = format!("/actions/{}", spec.name);
+            RemoteAction {
+                name: spec.name.to_string(),
+                methods: vec!["GET".to_string(), "POST".to_string()],
+                url: server.map(|(address, token)| format!("http://{address}{path}?token={token}")),
+                path,
+                params: spec.params.iter().map(|param| param.to_string()).collect(),
+                description: spec.description.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn list_remote_actions(state: tauri::State<'_, RemoteState>) -> Vec<RemoteAction> {
+    let info = state.info().filter(|info| info.api_url.is_some());
+    describe_actions(
+        info.as_ref()
+            .map(|info| (info.address.as_str(), info.token.as_str())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe_actions;
+
+    #[test]
+    fn urls_only_when_server_is_available() {
+        let offline = describe_actions(None);
+        assert!(offline.iter().all(|action| action.url.is_none()));
+
+        let online = describe_actions(Some(("127.0.0.1:8765", "abc")));
+        let bookmark = online
+            .iter()
+            .find(|action| action.name == "bookmark")
+            .unwrap();
+        assert_eq!(bookmark.path, "/actions/bookmark");
+        assert_eq!(
+            bookmark.url.as_deref(),
+            Some("http://127.0.0.1:8765/actions/bookmark?token=abc")
+        );
+        assert_eq!(bookmark.params, vec!["note".to_string()]);
+    }
+}
@@ -0,0 +1,175 @@
+use super::{ApiError, Authorized, ServerState};
+use crate::audio::{CaptureManager, SegmentInfo, SessionSummary};
+use crate::rag::{rag_search, RagSearchRequest, RagSearchResponse, RagState};
+use axum::extract::{Path, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::Manager;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusResponse {
+    capturing: bool,
+    translation_busy: bool,
+    segment_count: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopRequest {
+    drop_translations: Option<bool>,
+}
+
+pub(crate) fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/sessions", get(sessions))
+        .route("/api/sessions/{id}/segments", get(session_segments))
+        .route("/api/segments", get(segments))
+        .route("/api/segments/{name}", get(segment))
+        .route("/api/segments/{name}/audio", get(segment_audio))
+        .route("/api/capture/start", post(capture_start))
+        .route("/api/capture/stop", post(capture_stop))
+        .route("/api/rag/search", post(rag_search_handler))
+        .route("/api/rag/ask", post(rag_ask_handler))
+}
+
+async fn status(
+    _: Authorized,
+    State(state): State<ServerState>,
+) -> Result<Json<StatusResponse>, ApiError> {
+    let manager = capture_manager(&state)?;
+    let segment_count = manager.segment_count(state.app.clone())?;
+    Ok(Json(StatusResponse {
+        capturing: manager.is_capturing(),
+        translation_busy: manager.is_translation_busy(),
+        segment_count,
+    }))
+}
+
+async fn sessions(
+    _: Authorized,
+    State(state): State<ServerState>,
+) -> Result<Json<Vec<SessionSummary>>, ApiError> {
+    let manager = capture_manager(&state)?;
+    Ok(Json(manager.list_sessions(state.app.clone())?))
+}
+
+async fn session_segments(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<SegmentInfo>>, ApiError> {
+    let manager = capture_manager(&state)?;
+    Ok(Json(manager.list_session_segments(state.app.clone(), &id)?))
+}
+
+async fn segments(
+    _: Authorized,
+    State(state): State<ServerState>,
+) -> Result<Json<Vec<SegmentInfo>>, ApiError> {
+    let manager = capture_manager(&state)?;
+    Ok(Json(manager.list(state.app.clone())?))
+}
+
+async fn segment(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<Json<SegmentInfo>, ApiError> {
+    let manager = capture_manager(&state)?;
+    manager
+        .find_segment(state.app.clone(), &name)?
+        .map(|segment| Json(Arc::unwrap_or_clone(segment)))
+        .ok_or_else(|| ApiError::not_found(format!("segment not found: {name}")))
+}
+
+async fn segment_audio(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let manager = capture_manager(&state)?;
+    let bytes = manager
+        .read_segment_bytes(state.app.clone(), name)
+        .map_err(ApiError::not_found)?;
+    Ok(([(CONTENT_TYPE, "audio/wav")], bytes))
+}
+
+async fn capture_start(
+    _: Authorized,
+    State(state): State<ServerState>,
+) -> Result<Json<bool>, ApiError> {
+    let app = state.app.clone();
+    // Starting opens the device and spawns the capture thread, so keep it off the async executor.
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app
+            .try_state::<CaptureManager>()
+            .ok_or_else(|| "capture manager unavailable".to_string())?;
+        manager.start(app.clone())
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(Json(true))
+}
+
+async fn capture_stop(
+    _: Authorized,
+    State(state): State<ServerState>,
+    request: Option<Json<StopRequest>>,
+) -> Result<Json<bool>, ApiError> {
+    let drop_translations = request
+        .and_then(|Json(request)| request.drop_translations)
+        .unwrap_or(false);
+    let app = state.app.clone();
+    // Stopping joins the capture thread, so keep it off the async executor.
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app
+            .try_state::<CaptureManager>()
+            .ok_or_else(|| "capture manager unavailable".to_string())?;
+        manager.stop(&app, drop_translations)
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(Json(true))
+}
+
+async fn rag_search_handler(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Json(request): Json<RagSearchRequest>,
+) -> Result<Json<RagSearchResponse>, ApiError> {
+    let rag_state = state
+        .app
+        .try_state::<Arc<RagState>>()
+        .ok_or_else(|| ApiError::unavailable("rag unavailable"))?;
+    let response = rag_search(state.app.clone(), rag_state, request).await?;
+    Ok(Json(response))
+}
+
+async fn rag_ask_handler(
+    _: Authorized,
+    State(state): State<ServerState>,
+    Json(request): Json<crate::RagAskRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let rag_state = state
+        .app
+        .try_state::<Arc<RagState>>()
+        .ok_or_else(|| ApiError::unavailable("rag unavailable"))?
+        .inner()
+        .clone();
+    let provider = crate::selected_translate_provider(&state.app);
+    let response = crate::answer_rag_question(&state.app, rag_state, provider, request).await?;
+    Ok(Json(response))
+}
+
+fn capture_manager(state: &ServerState) -> Result<tauri::State<'_, CaptureManager>, ApiError> {
+    state
+        .app
+        .try_state::<CaptureManager>()
+        .ok_or_else(|| ApiError::unavailable("capture manager unavailable"))
+}
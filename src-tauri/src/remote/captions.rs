@@ -0,0 +1,86 @@
+use super::companion::Viewer;
+use super::{ApiError, ServerState};
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+
+const CAPTIONS_PAGE: &str = include_str!("captions.html");
+const QR_MIN_SIZE: u32 = 240;
+
+pub(crate) fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/captions", get(page))
+        .route("/captions/qr.svg", get(qr))
+}
+
+async fn page(_: Viewer) -> Html<&'static str> {
+    Html(CAPTIONS_PAGE)
+}
+
+async fn qr(_: Viewer, State(state): State<ServerState>) -> Result<impl IntoResponse, ApiError> {
+    let url = state.share_url.as_deref().ok_or_else(|| {
+        ApiError::not_found(
+            "remote.bindAddress is loopback-only; bind 0.0.0.0 to share with phones",
+        )
+    })?;
+    Ok(([(CONTENT_TYPE, "image/svg+xml")], qr_svg(url)?))
+}
+
+pub fn qr_svg(data: &str) -> Result<String, String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|err| err.to_string())?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+        .quiet_zone(true)
+        .build())
+}
+
+pub fn share_url(address: SocketAddr, token: &str) -> Option<String> {
+    lan_base_url(address).map(|base| format!("{base}/captions?token={token}"))
+}
+
+// Phones can only reach the server when it listens beyond loopback.
+pub fn lan_base_url(address: SocketAddr) -> Option<String> {
+    let host = match address.ip() {
+        ip if ip.is_loopback() => return None,
+        ip if ip.is_unspecified() => lan_ip()?,
+        ip => ip,
+    };
+    Some(format!("http://{}", SocketAddr::new(host, address.port())))
+}
+
+// Connecting a UDP socket sends nothing but makes the OS pick the outbound interface.
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket
+        .local_addr()
+        .ok()
+        .map(|address| address.ip())
+        .filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{qr_svg, share_url};
+
+    #[test]
+    fn loopback_is_not_shareable() {
+        assert_eq!(share_url("127.0.0.1:8765".parse().unwrap(), "abc"), None);
+        assert_eq!(
+            share_url("192.168.1.20:8765".parse().unwrap(), "abc").as_deref(),
+            Some("http://192.168.1.20:8765/captions?token=abc")
+        );
+    }
+
+    #[test]
+    fn renders_svg_qr() {
+        let svg = qr_svg("http://192.168.1.20:8765/captions?token=abc").unwrap();
+        assert!(svg.contains("<svg"));
+    }
+}
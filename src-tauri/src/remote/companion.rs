@@ -0,0 +1,240 @@
+use super::{generate_token, request_token, tokens_match, ApiError, RemoteState, ServerState};
+use crate::audio::{CaptureManager, SegmentInfo};
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{Html, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+const COMPANION_PAGE: &str = include_str!("companion.html");
+const PAIRING_TTL: Duration = Duration::from_secs(300);
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+const RECENT_SEGMENTS: usize = 30;
+const COMPANION_EVENTS: [&str; 6] = [
+    "segment_transcribed",
+    "segment_translated",
+    "live_draft_update",
+    "segment_list_cleared",
+    "session_started",
+    "session_ended",
+];
+
+struct Pairing {
+    code: String,
+    expires_at: Instant,
+    attempts: u32,
+}
+
+// One pairing code is live at a time; redeeming it mints a read-only viewer token.
+pub(crate) struct Companion {
+    pairing: Mutex<Option<Pairing>>,
+    viewers: Mutex<HashSet<String>>,
+}
+
+impl Companion {
+    pub fn new() -> Self {
+        Self {
+            pairing: Mutex::new(None),
+            viewers: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn issue_code(&self) -> Result<String, String> {
+        let seed =
+            u64::from_str_radix(&generate_token()[..12], 16).map_err(|err| err.to_string())?;
+        let code = format!("{:06}", seed % 1_000_000);
+        let mut guard = self
+            .pairing
+            .lock()
+            .map_err(|_| "companion state poisoned".to_string())?;
+        *guard = Some(Pairing {
+            code: code.clone(),
+            expires_at: Instant::now() + PAIRING_TTL,
+            attempts: 0,
+        });
+        Ok(code)
+    }
+
+    fn redeem(&self, code: &str) -> Option<String> {
+        let mut guard = self.pairing.lock().ok()?;
+        let pairing = guard.as_mut()?;
+        if Instant::now() >= pairing.expires_at {
+            *guard = None;
+            return None;
+        }
+        if !tokens_match(code.trim(), &pairing.code) {
+            pairing.attempts += 1;
+            if pairing.attempts >= MAX_PAIRING_ATTEMPTS {
+                *guard = None;
+            }
+            return None;
+        }
+        *guard = None;
+        let token = generate_token();
+        self.viewers.lock().ok()?.insert(token.clone());
+        Some(token)
+    }
+
+    fn is_viewer(&self, token: &str) -> bool {
+        self.viewers
+            .lock()
+            .map(|guard| guard.iter().any(|viewer| tokens_match(token, viewer)))
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionPairing {
+    pub code: String,
+    pub url: String,
+    pub qr_svg: String,
+    pub expires_in_secs: u64,
+}
+
+#[tauri::command]
+pub fn start_companion_pairing(
+    state: tauri::State<'_, RemoteState>,
+) -> Result<CompanionPairing, String> {
+    let info = state
+        .info()
+        .ok_or_else(|| "remote server is not enabled".to_string())?;
+    let base = info.companion_url.ok_or_else(|| {
+        "remote.bindAddress is loopback-only; bind 0.0.0.0 to pair phones".to_string()
+    })?;
+    let code = state.companion().issue_code()?;
+    let url = format!("{base}?code={code}");
+    Ok(CompanionPairing {
+        qr_svg: super::captions::qr_svg(&url)?,
+        code,
+        url,
+        expires_in_secs: PAIRING_TTL.as_secs(),
+    })
+}
+
+// Accepts the full remote token, the share-link viewer token or a companion viewer token.
+pub(crate) struct Viewer {
+    // Whether the request carried the full remote token.
+    pub full: bool,
+}
+
+impl FromRequestParts<ServerState> for Viewer {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = request_token(parts);
+        let full = state.authorized(token.as_deref());
+        let allowed = full
+            || token.as_deref().is_some_and(|token| {
+                tokens_match(token, &state.viewer_token)
+                    || state
+                        .app
+                        .try_state::<RemoteState>()
+                        .is_some_and(|remote| remote.companion().is_viewer(token))
+            });
+        if allowed {
+            Ok(Viewer { full })
+        } else {
+            Err(ApiError::new(StatusCode::UNAUTHORIZED, "invalid token"))
+        }
+    }
+}
+
+pub(crate) fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/companion", get(page))
+        .route("/companion/pair", post(pair))
+        .route("/companion/recent", get(recent))
+        .route("/companion/ws", get(events))
+}
+
+async fn page() -> Html<&'static str> {
+    Html(COMPANION_PAGE)
+}
+
+#[derive(Deserialize)]
+struct PairRequest {
+    code: String,
+}
+
+#[derive(Serialize)]
+struct PairResponse {
+    token: String,
+}
+
+async fn pair(
+    State(state): State<ServerState>,
+    Json(request): Json<PairRequest>,
+) -> Result<Json<PairResponse>, ApiError> {
+    let remote = state
+        .app
+        .try_state::<RemoteState>()
+        .ok_or_else(|| ApiError::unavailable("remote state unavailable"))?;
+    let token = remote
+        .companion()
+        .redeem(&request.code)
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "invalid or expired code"))?;
+    Ok(Json(PairResponse { token }))
+}
+
+async fn recent(
+    _: Viewer,
+    State(state): State<ServerState>,
+) -> Result<Json<Vec<SegmentInfo>>, ApiError> {
+    let manager = state
+        .app
+        .try_state::<CaptureManager>()
+        .ok_or_else(|| ApiError::unavailable("capture manager unavailable"))?;
+    let segments = manager.recent_segments(state.app.clone(), RECENT_SEGMENTS)?;
+    Ok(Json(
+        segments.into_iter().map(Arc::unwrap_or_clone).collect(),
+    ))
+}
+
+async fn events(
+    _: Viewer,
+    State(state): State<ServerState>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    super::ws::upgrade_filtered(&state, upgrade, is_viewer_event)
+}
+
+pub(crate) fn is_viewer_event(event: &str) -> bool {
+    COMPANION_EVENTS.contains(&event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Companion;
+
+    #[test]
+    fn code_is_single_use() {
+        let companion = Companion::new();
+        let code = companion.issue_code().unwrap();
+        assert_eq!(code.len(), 6);
+        let token = companion.redeem(&code).unwrap();
+        assert!(companion.is_viewer(&token));
+        assert!(companion.redeem(&code).is_none());
+    }
+
+    #[test]
+    fn code_is_dropped_after_failed_attempts() {
+        let companion = Companion::new();
+        let code = companion.issue_code().unwrap();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        for _ in 0..super::MAX_PAIRING_ATTEMPTS {
+            assert!(companion.redeem(wrong).is_none());
+        }
+        assert!(companion.redeem(&code).is_none());
+    }
+}
@@ -0,0 +1,325 @@
+mod actions;
+mod api;
+mod captions;
+mod companion;
+mod obs;
+mod ws;
+
+use crate::app_config::{load_config, load_file_config, save_config};
+use crate::defaults::{DEFAULT_REMOTE_BIND_ADDRESS, DEFAULT_REMOTE_PORT};
+pub use actions::list_remote_actions;
+pub use companion::start_companion_pairing;
+
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteInfo {
+    pub enabled: bool,
+    pub address: String,
+    pub token: String,
+    pub ws_url: String,
+    pub obs_url: String,
+    pub captions_url: String,
+    pub share_url: Option<String>,
+    pub companion_url: Option<String>,
+    pub api_url: Option<String>,
+}
+
+pub struct RemoteState {
+    info: Mutex<Option<RemoteInfo>>,
+    companion: companion::Companion,
+}
+
+impl RemoteState {
+    pub fn new() -> Self {
+        Self {
+            info: Mutex::new(None),
+            companion: companion::Companion::new(),
+        }
+    }
+
+    fn set_info(&self, info: RemoteInfo) {
+        if let Ok(mut guard) = self.info.lock() {
+            *guard = Some(info);
+        }
+    }
+
+    fn info(&self) -> Option<RemoteInfo> {
+        self.info.lock().ok().and_then(|guard| guard.clone())
+    }
+
+    fn companion(&self) -> &companion::Companion {
+        &self.companion
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ServerState {
+    pub app: AppHandle,
+    pub token: String,
+    pub viewer_token: String,
+    pub share_url: Option<String>,
+}
+
+impl ServerState {
+    // Only the full token; viewer tokens never reach /api or /actions.
+    pub fn authorized(&self, token: Option<&str>) -> bool {
+        token.is_some_and(|value| tokens_match(value, &self.token))
+    }
+}
+
+// Read-only token for the captions page and its QR code, which end up on shared screens. Derived
+// from the remote token so shared links survive restarts without revealing the token itself.
+fn viewer_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"viewer:");
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())[..32].to_string()
+}
+
+// Compares digests, so neither the contents nor the length of the expected token leak through
+// the time the check takes.
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    let given = Sha256::digest(given.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    given
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+// Accepts the full token either as `Authorization: Bearer <token>` or as `?token=<token>`.
+pub(crate) struct Authorized;
+
+impl FromRequestParts<ServerState> for Authorized {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        if state.authorized(request_token(parts).as_deref()) {
+            Ok(Authorized)
+        } else {
+            Err(ApiError::new(StatusCode::UNAUTHORIZED, "invalid token"))
+        }
+    }
+}
+
+fn request_token(parts: &Parts) -> Option<String> {
+    let header = parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.trim().to_string());
+    header.or_else(|| {
+        parts.uri.query().and_then(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .find(|(key, _)| key == "token")
+                .map(|(_, value)| value.into_owned())
+        })
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, message)
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let config = load_config()
+        .ok()
+        .and_then(|cfg| cfg.remote)
+        .unwrap_or_default();
+    if config.enabled != Some(true) {
+        return;
+    }
+
+    let bind_address = config
+        .bind_address
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REMOTE_BIND_ADDRESS.to_string());
+    let port = config.port.unwrap_or(DEFAULT_REMOTE_PORT);
+    let address: SocketAddr = match format!("{bind_address}:{port}").parse() {
+        Ok(address) => address,
+        Err(err) => {
+            error!("remote server disabled: invalid address {bind_address}:{port}: {err}");
+            return;
+        }
+    };
+    let token = config
+        .token
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(persist_generated_token);
+
+    let api_enabled = config.api_enabled == Some(true);
+    let viewer_token = viewer_token(&token);
+    let share_url = captions::share_url(address, &viewer_token);
+    let lan_base_url = captions::lan_base_url(address);
+    let info = RemoteInfo {
+        enabled: true,
+        address: address.to_string(),
+        token: token.clone(),
+        ws_url: format!("ws://{address}/ws?token={token}"),
+        obs_url: format!("http://{address}/obs?token={viewer_token}"),
+        captions_url: format!("http://{address}/captions?token={viewer_token}"),
+        share_url: share_url.clone(),
+        companion_url: lan_base_url.map(|base| format!("{base}/companion")),
+        api_url: api_enabled.then(|| format!("http://{address}/api")),
+    };
+    if let Some(state) = app.try_state::<RemoteState>() {
+        state.set_info(info);
+    }
+
+    let state = ServerState {
+        app: app.clone(),
+        token,
+        viewer_token,
+        share_url,
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = serve(address, state, api_enabled).await {
+            error!("remote server stopped: {err}");
+        }
+    });
+}
+
+async fn serve(address: SocketAddr, state: ServerState, api_enabled: bool) -> Result<(), String> {
+    let mut router = Router::new()
+        .route("/ws", get(ws::handle_upgrade))
+        .merge(obs::routes())
+        .merge(captions::routes())
+        .merge(companion::routes());
+    if api_enabled {
+        router = router.merge(api::routes()).merge(actions::routes());
+    }
+    let router = router.with_state(state);
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .map_err(|err| format!("failed to bind {address}: {err}"))?;
+    info!("remote server listening on {address}");
+    axum::serve(listener, router)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn get_remote_info(state: tauri::State<'_, RemoteState>) -> RemoteInfo {
+    state.info().unwrap_or(RemoteInfo {
+        enabled: false,
+        address: String::new(),
+        token: String::new(),
+        ws_url: String::new(),
+        obs_url: String::new(),
+        captions_url: String::new(),
+        share_url: None,
+        companion_url: None,
+        api_url: None,
+    })
+}
+
+// Persist the token so overlay URLs keep working across restarts.
+fn persist_generated_token() -> String {
+    let token = generate_token();
+    match load_file_config() {
+        Ok(mut config) => {
+            let mut remote = config.remote.unwrap_or_default();
+            remote.token = Some(token.clone());
+            config.remote = Some(remote);
+            if let Err(err) = save_config(&config) {
+                warn!("remote token not saved: {err}");
+            }
+        }
+        Err(err) => warn!("remote token not saved: {err}"),
+    }
+    token
+}
+
+// 32 bytes from the OS random number generator, hex-encoded.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator");
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_token, tokens_match, viewer_token};
+
+    #[test]
+    fn matches_tokens_exactly() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc124", "abc123"));
+        assert!(!tokens_match("abc", "abc123"));
+        assert!(!tokens_match("", "abc123"));
+    }
+
+    #[test]
+    fn generates_random_hex_tokens() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|ch| ch.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn derives_a_stable_viewer_token_distinct_from_the_remote_token() {
+        let token = "0123456789abcdef0123456789abcdef";
+        let viewer = viewer_token(token);
+        assert_eq!(viewer, viewer_token(token));
+        assert_eq!(viewer.len(), token.len());
+        assert!(!tokens_match(&viewer, token));
+        assert_ne!(viewer, viewer_token("fedcba9876543210fedcba9876543210"));
+    }
+}
@@ -0,0 +1,39 @@
+use super::companion::Viewer;
+use super::{ApiError, ServerState};
+use crate::audio::{CaptureManager, SegmentInfo};
+use axum::extract::State;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::Arc;
+use tauri::Manager;
+
+const OBS_PAGE: &str = include_str!("obs.html");
+
+pub(crate) fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/obs", get(page))
+        .route("/captions/latest", get(latest))
+}
+
+// Read-only, so the overlay URL is built with the viewer token.
+async fn page(_: Viewer) -> Html<&'static str> {
+    Html(OBS_PAGE)
+}
+
+async fn latest(
+    _: Viewer,
+    State(state): State<ServerState>,
+) -> Result<Json<Option<SegmentInfo>>, ApiError> {
+    let manager = state
+        .app
+        .try_state::<CaptureManager>()
+        .ok_or_else(|| ApiError::unavailable("capture manager unavailable"))?;
+    let latest = manager.latest_segment(state.app.clone(), |segment| {
+        segment
+            .translation
+            .as_deref()
+            .is_some_and(|text| !text.trim().is_empty())
+    })?;
+    Ok(Json(latest.map(Arc::unwrap_or_clone)))
+}
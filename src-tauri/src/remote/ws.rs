@@ -0,0 +1,70 @@
+use super::companion::{is_viewer_event, Viewer};
+use super::ServerState;
+use crate::events::EventHub;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+pub(crate) async fn handle_upgrade(
+    viewer: Viewer,
+    State(state): State<ServerState>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    // Viewer tokens only see what the captions page shows.
+    if viewer.full {
+        upgrade_filtered(&state, upgrade, |_| true)
+    } else {
+        upgrade_filtered(&state, upgrade, is_viewer_event)
+    }
+}
+
+// `filter` receives the event name; only matching events reach the client.
+pub(crate) fn upgrade_filtered(
+    state: &ServerState,
+    upgrade: WebSocketUpgrade,
+    filter: fn(&str) -> bool,
+) -> Response {
+    let Some(hub) = state.app.try_state::<Arc<EventHub>>() else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    let hub = hub.inner().clone();
+    upgrade.on_upgrade(move |socket| forward_events(socket, hub, filter))
+}
+
+async fn forward_events(mut socket: WebSocket, hub: Arc<EventHub>, filter: fn(&str) -> bool) {
+    let mut events = hub.subscribe();
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                }
+            }
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("remote ws client lagged, skipped {skipped} events");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if !filter(&event.event) {
+                    continue;
+                }
+                let Ok(text) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use once_cell::sync::Lazy;
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+use tracing::warn;
+
+// The work is network-bound (whisper-server, OpenAI, LLM translation), so a couple of I/O threads
+// are enough; concurrency is already limited by the one-thread-per-stage workers.
+const PIPELINE_WORKER_THREADS: usize = 2;
+
+// Transcription, translation and window workers block on this runtime instead of Tauri's shared
+// one, so slow segments cannot starve command handlers and event delivery.
+static PIPELINE: Lazy<Option<Runtime>> = Lazy::new(|| {
+    Builder::new_multi_thread()
+        .worker_threads(PIPELINE_WORKER_THREADS)
+        .thread_name("pipeline-rt")
+        .enable_all()
+        .build()
+        .map_err(|err| warn!("pipeline runtime unavailable, using the shared runtime: {err}"))
+        .ok()
+});
+
+// Only call from the pipeline's own worker threads, never from inside an async task.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    match PIPELINE.as_ref() {
+        Some(runtime) => runtime.block_on(future),
+        None => tauri::async_runtime::block_on(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn runs_timers_and_spawned_tasks() {
+        let value = block_on(async {
+            let task = tokio::spawn(async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                std::thread::current().name().map(str::to_string)
+            });
+            task.await.ok().flatten()
+        });
+        assert_eq!(value.as_deref(), Some("pipeline-rt"));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::app_config::{load_config, ScheduleConfig, ScheduleEntry};
+use crate::audio::CaptureManager;
+use crate::calendar;
+use crate::defaults::{
+    DEFAULT_SCHEDULE_AUTO_START, DEFAULT_SCHEDULE_AUTO_STOP, DEFAULT_SCHEDULE_ENABLED,
+    DEFAULT_SCHEDULE_USE_CALENDAR,
+};
+use crate::events::{emit_ui_event, SCHEDULED_MEETING_ENDED, SCHEDULED_MEETING_STARTED};
+use crate::timestamps::rfc3339;
+use chrono::{Datelike, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::RwLock;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const SCHEDULE_POLL_SECS: u64 = 30;
+// The ICS is fetched again after this long; meetings are rarely added minutes before they start.
+const CALENDAR_REFRESH_SECS: u64 = 600;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Meeting {
+    title: String,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    source: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledMeeting {
+    pub title: String,
+    pub start: String,
+    pub end: String,
+    // `schedule` for an entry of `schedule.entries`, `calendar` for an ICS event.
+    pub source: String,
+    // Capture was started for this meeting rather than already running.
+    pub auto_started: bool,
+}
+
+// The meeting in progress, for a window opened after `scheduled_meeting_started` was sent.
+static CURRENT: Lazy<RwLock<Option<ScheduledMeeting>>> = Lazy::new(|| RwLock::new(None));
+
+#[tauri::command]
+pub fn get_scheduled_meeting() -> Option<ScheduledMeeting> {
+    CURRENT.read().ok().and_then(|current| current.clone())
+}
+
+// Checks the schedule and calendar every `SCHEDULE_POLL_SECS`, starting and stopping capture at
+// the edges of each meeting.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut calendar_cache: Option<(Instant, String)> = None;
+        let mut active: Option<Meeting> = None;
+        let mut auto_started = false;
+        loop {
+            let config = load_config()
+                .ok()
+                .and_then(|cfg| cfg.schedule)
+                .unwrap_or_default();
+            let current = if config.enabled.unwrap_or(DEFAULT_SCHEDULE_ENABLED) {
+                current_meeting(&config, &mut calendar_cache).await
+            } else {
+                None
+            };
+            if current != active {
+                if let Some(ended) = active.take() {
+                    finish_meeting(&app, &config, &ended, auto_started).await;
+                    auto_started = false;
+                }
+                if let Some(meeting) = current.clone() {
+                    auto_started = begin_meeting(&app, &config, &meeting).await;
+                }
+                active = current;
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(SCHEDULE_POLL_SECS)).await;
+        }
+    });
+}
+
+async fn current_meeting(
+    config: &ScheduleConfig,
+    calendar_cache: &mut Option<(Instant, String)>,
+) -> Option<Meeting> {
+    let now = Local::now().naive_local();
+    let entries = config.entries.as_deref().unwrap_or_default();
+    if let Some(found) = active_entry(entries, now) {
+        return Some(found);
+    }
+    if !config.use_calendar.unwrap_or(DEFAULT_SCHEDULE_USE_CALENDAR) {
+        return None;
+    }
+    let stale = calendar_cache
+        .as_ref()
+        .is_none_or(|(fetched, _)| fetched.elapsed().as_secs() >= CALENDAR_REFRESH_SECS);
+    if stale {
+        let calendar_config = load_config()
+            .ok()
+            .and_then(|cfg| cfg.calendar)
+            .unwrap_or_default();
+        match calendar::read_calendar(&calendar_config).await {
+            Ok(ics) => *calendar_cache = Some((Instant::now(), ics)),
+            Err(err) => warn!("[schedule] calendar fetch failed: {err}"),
+        }
+    }
+    let (_, ics) = calendar_cache.as_ref()?;
+    calendar::find_current_event(ics, now, Duration::zero()).map(|event| Meeting {
+        title: event.title,
+        start: event.start,
+        end: event.end,
+        source: "calendar",
+    })
+}
+
+async fn begin_meeting(app: &AppHandle, config: &ScheduleConfig, meeting: &Meeting) -> bool {
+    info!("[schedule] meeting started: {}", meeting.title);
+    let mut auto_started = false;
+    if config.auto_start.unwrap_or(DEFAULT_SCHEDULE_AUTO_START) {
+        let app = app.clone();
+        let started = tauri::async_runtime::spawn_blocking(move || {
+            let manager = app.state::<CaptureManager>();
+            if manager.is_capturing() {
+                return Ok(false);
+            }
+            manager.start(app.clone()).map(|_| true)
+        })
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|result| result);
+        match started {
+            Ok(started) => auto_started = started,
+            Err(err) => warn!("[schedule] capture start failed: {err}"),
+        }
+    }
+    let payload = describe(meeting, auto_started);
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(payload.clone());
+    }
+    emit_ui_event(app, SCHEDULED_MEETING_STARTED, payload);
+    auto_started
+}
+
+// Only a capture the scheduler started is stopped; one started by hand is left running.
+async fn finish_meeting(
+    app: &AppHandle,
+    config: &ScheduleConfig,
+    meeting: &Meeting,
+    auto_started: bool,
+) {
+    info!("[schedule] meeting ended: {}", meeting.title);
+    if auto_started && config.auto_stop.unwrap_or(DEFAULT_SCHEDULE_AUTO_STOP) {
+        let app = app.clone();
+        let stopped = tauri::async_runtime::spawn_blocking(move || {
+            app.state::<CaptureManager>()
+                .stop_with_reason(&app, false, "schedule")
+        })
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|result| result);
+        if let Err(err) = stopped {
+            warn!("[schedule] capture stop failed: {err}");
+        }
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = None;
+    }
+    emit_ui_event(
+        app,
+        SCHEDULED_MEETING_ENDED,
+        describe(meeting, auto_started),
+    );
+}
+
+fn describe(meeting: &Meeting, auto_started: bool) -> ScheduledMeeting {
+    let format = |at: NaiveDateTime| {
+        Local
+            .from_local_datetime(&at)
+            .earliest()
+            .map(|at| rfc3339(&at))
+            .unwrap_or_else(|| at.format("%Y-%m-%dT%H:%M:%S").to_string())
+    };
+    ScheduledMeeting {
+        title: meeting.title.clone(),
+        start: format(meeting.start),
+        end: format(meeting.end),
+        source: meeting.source.to_string(),
+        auto_started,
+    }
+}
+
+// Checked by `app_config` so a bad entry is rejected when the config is saved.
+pub fn validate_entry(entry: &ScheduleEntry) -> Result<(), String> {
+    parse_time(&entry.start)?;
+    parse_time(&entry.end)?;
+    for day in entry.days.iter().flatten() {
+        parse_weekday(day)?;
+    }
+    Ok(())
+}
+
+// An entry whose `end` is not after its `start` runs past midnight; `days` name the day it starts.
+fn active_entry(entries: &[ScheduleEntry], now: NaiveDateTime) -> Option<Meeting> {
+    for entry in entries {
+        let (Ok(start), Ok(end)) = (parse_time(&entry.start), parse_time(&entry.end)) else {
+            continue;
+        };
+        let days = entry
+            .days
+            .iter()
+            .flatten()
+            .filter_map(|day| parse_weekday(day).ok())
+            .collect::<Vec<_>>();
+        for day in [now.date() - Duration::days(1), now.date()] {
+            if !days.is_empty() && !days.contains(&day.weekday()) {
+                continue;
+            }
+            let opens = day.and_time(start);
+            let mut closes = day.and_time(end);
+            if closes <= opens {
+                closes += Duration::days(1);
+            }
+            if opens <= now && now < closes {
+                let title = entry
+                    .title
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or("定时会议");
+                return Some(Meeting {
+                    title: title.to_string(),
+                    start: opens,
+                    end: closes,
+                    source: "schedule",
+                });
+            }
+        }
+    }
+    None
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("schedule time must be HH:MM, got {value}"))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    match value.trim().to_lowercase().as_str() {
+        "mon" | "monday" => Ok(Weekday::Mon),
+        "tue" | "tuesday" => Ok(Weekday::Tue),
+        "wed" | "wednesday" => Ok(Weekday::Wed),
+        "thu" | "thursday" => Ok(Weekday::Thu),
+        "fri" | "friday" => Ok(Weekday::Fri),
+        "sat" | "saturday" => Ok(Weekday::Sat),
+        "sun" | "sunday" => Ok(Weekday::Sun),
+        _ => Err(format!("unknown schedule day: {value}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::active_entry;
+    use crate::app_config::ScheduleEntry;
+    use chrono::NaiveDateTime;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn entry(days: &[&str], start: &str, end: &str) -> ScheduleEntry {
+        ScheduleEntry {
+            title: Some("Standup".to_string()),
+            days: Some(days.iter().map(|day| day.to_string()).collect()),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_weekday_windows_and_overnight_entries() {
+        // 2025-01-06 is a Monday.
+        let entries = [entry(&["mon", "wed"], "09:30", "09:45")];
+        let meeting = active_entry(&entries, at("2025-01-06 09:30")).unwrap();
+        assert_eq!(meeting.title, "Standup");
+        assert_eq!(meeting.end, at("2025-01-06 09:45"));
+        assert!(active_entry(&entries, at("2025-01-06 09:45")).is_none());
+        assert!(active_entry(&entries, at("2025-01-07 09:35")).is_none());
+
+        // Starts Friday night, still running early Saturday.
+        let entries = [entry(&["fri"], "23:00", "01:00")];
+        let meeting = active_entry(&entries, at("2025-01-11 00:30")).unwrap();
+        assert_eq!(meeting.start, at("2025-01-10 23:00"));
+        assert!(active_entry(&entries, at("2025-01-11 23:30")).is_none());
+
+        // No days means every day.
+        let entries = [entry(&[], "14:00", "15:00")];
+        assert!(active_entry(&entries, at("2025-01-12 14:59")).is_some());
+    }
+}
@@ -0,0 +1,454 @@
+use crate::audio::manager::{ensure_segments_dir, SegmentInfo, SessionInfo};
+use crate::audio::recording::recording_path;
+use crate::audio::CaptureManager;
+use crate::summary::{load_digest, remove_digest, save_digest, SessionDigest};
+use chrono::DateTime;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const ARCHIVES_DIR: &str = "archives";
+const SESSION_ENTRY: &str = "session.json";
+const SEGMENTS_ENTRY: &str = "segments.json";
+const SUMMARY_ENTRY: &str = "summary.json";
+const AUDIO_PREFIX: &str = "segments/";
+const RECORDING_ENTRY: &str = "recording.wav";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionArchive {
+    pub session_id: String,
+    pub path: String,
+    pub bytes: u64,
+    pub segment_count: usize,
+    // Segments whose audio file was already gone; their transcripts are archived all the same.
+    pub missing_audio: usize,
+    pub originals_deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedSession {
+    #[serde(flatten)]
+    pub session: SessionInfo,
+    pub path: String,
+    pub bytes: u64,
+}
+
+struct ArchiveContents {
+    session: SessionInfo,
+    segments: Vec<SegmentInfo>,
+    digest: Option<SessionDigest>,
+}
+
+// Index at which an item stamped `at` keeps a list sorted by its rfc3339 stamps. Stamps that do
+// not parse are never treated as later, so such an item goes to the end.
+pub(crate) fn chronological_position(stamps: &[&str], at: &str) -> usize {
+    let Ok(at) = DateTime::parse_from_rfc3339(at) else {
+        return stamps.len();
+    };
+    stamps
+        .iter()
+        .position(|stamp| DateTime::parse_from_rfc3339(stamp).is_ok_and(|stamp| stamp > at))
+        .unwrap_or(stamps.len())
+}
+
+fn archives_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let base = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    Ok(base.join(ARCHIVES_DIR))
+}
+
+// Session ids become file names, so they are limited to what `begin_session` generates.
+fn check_session_id(session_id: &str) -> Result<(), String> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(format!("invalid session id: {session_id}"));
+    }
+    Ok(())
+}
+
+fn archive_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    check_session_id(session_id)?;
+    Ok(archives_dir(app)?.join(format!("{session_id}.zip")))
+}
+
+// Segment names come from the archive, so anything that could leave the segments directory is
+// rejected before a file is written.
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && Path::new(name).file_name().and_then(|value| value.to_str()) == Some(name)
+}
+
+#[tauri::command]
+pub fn archive_session(
+    app: AppHandle,
+    state: State<CaptureManager>,
+    session_id: String,
+    delete_originals: Option<bool>,
+) -> Result<SessionArchive, String> {
+    let summary = state
+        .list_sessions(app.clone())?
+        .into_iter()
+        .find(|item| item.session.id == session_id)
+        .ok_or_else(|| format!("session not found: {session_id}"))?;
+    if summary.active {
+        return Err(format!("session is still recording: {session_id}"));
+    }
+    let contents = ArchiveContents {
+        segments: state.list_session_segments(app.clone(), &session_id)?,
+        digest: load_digest(&app, &session_id)?,
+        session: summary.session,
+    };
+    let path = archive_path(&app, &session_id)?;
+    let missing_audio = write_archive(&path, &ensure_segments_dir(&app)?, &contents)?;
+    let bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    info!(
+        "[archive] {session_id}: {} segments, {bytes} bytes",
+        contents.segments.len()
+    );
+
+    let originals_deleted = delete_originals.unwrap_or(false);
+    if originals_deleted {
+        state.remove_session(&app, &session_id)?;
+        if let Err(err) = remove_digest(&app, &session_id) {
+            warn!("[archive] summary of {session_id} not removed: {err}");
+        }
+    }
+    Ok(SessionArchive {
+        session_id,
+        path: path.to_string_lossy().to_string(),
+        bytes,
+        segment_count: contents.segments.len(),
+        missing_audio,
+        originals_deleted,
+    })
+}
+
+// `archive` is a session id from the archive directory or the path of an archive file.
+#[tauri::command]
+pub fn restore_session(
+    app: AppHandle,
+    state: State<CaptureManager>,
+    archive: String,
+) -> Result<SessionInfo, String> {
+    let archive = archive.trim();
+    let path = if archive.ends_with(".zip") {
+        PathBuf::from(archive)
+    } else {
+        archive_path(&app, archive)?
+    };
+    if !path.is_file() {
+        return Err(format!("archive not found: {}", path.display()));
+    }
+    let contents = read_archive(&path)?;
+    let session_id = contents.session.id.clone();
+    check_session_id(&session_id)?;
+    let exists = state
+        .list_sessions(app.clone())?
+        .iter()
+        .any(|item| item.session.id == session_id);
+    if exists {
+        return Err(format!("session already exists: {session_id}"));
+    }
+    let segments_dir = ensure_segments_dir(&app)?;
+    let restored = extract_audio(&path, &segments_dir, &contents.segments)?;
+    if extract_recording(&path, &recording_path(&segments_dir, &session_id))? {
+        info!("[archive] restored the continuous recording of {session_id}");
+    }
+    info!(
+        "[archive] restoring {session_id}: {} segments, {restored} audio files",
+        contents.segments.len()
+    );
+    state.restore_session(&app, contents.session.clone(), contents.segments)?;
+    if let Some(digest) = &contents.digest {
+        save_digest(&app, digest)?;
+    }
+    Ok(contents.session)
+}
+
+#[tauri::command]
+pub fn list_session_archives(app: AppHandle) -> Result<Vec<ArchivedSession>, String> {
+    let dir = archives_dir(&app)?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut archives = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|value| value.to_str()) != Some("zip") {
+            continue;
+        }
+        // A damaged archive only hides itself from the list.
+        let session = File::open(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| ZipArchive::new(file).map_err(|err| err.to_string()))
+            .and_then(|mut zip| read_json::<SessionInfo, _>(&mut zip, SESSION_ENTRY));
+        match session {
+            Ok(session) => archives.push(ArchivedSession {
+                session,
+                bytes: fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+                path: path.to_string_lossy().to_string(),
+            }),
+            Err(err) => warn!("[archive] skipping {}: {err}", path.display()),
+        }
+    }
+    archives.sort_by(|a, b| a.session.started_at.cmp(&b.session.started_at));
+    Ok(archives)
+}
+
+// Copies the continuous recording of a finished session (`audio.json` `record_session`) to
+// `path`, or to a file picked in a save dialog. `None` when the dialog was cancelled.
+#[tauri::command]
+pub fn meeting_export_audio(
+    app: AppHandle,
+    state: State<CaptureManager>,
+    session_id: String,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    let summary = state
+        .list_sessions(app.clone())?
+        .into_iter()
+        .find(|item| item.session.id == session_id)
+        .ok_or_else(|| format!("session not found: {session_id}"))?;
+    if summary.active {
+        return Err(format!("session is still recording: {session_id}"));
+    }
+    let source = recording_path(&ensure_segments_dir(&app)?, &session_id);
+    if !source.is_file() {
+        return Err(format!("session has no recording: {session_id}"));
+    }
+    let Some(target) = path.map(PathBuf::from).or_else(|| {
+        rfd::FileDialog::new()
+            .set_file_name(format!("{session_id}.wav"))
+            .add_filter("WAV", &["wav"])
+            .save_file()
+    }) else {
+        return Ok(None);
+    };
+    let bytes = fs::copy(&source, &target)
+        .map_err(|err| format!("failed to write {}: {err}", target.display()))?;
+    info!("[archive] exported recording of {session_id}: {bytes} bytes");
+    Ok(Some(target.to_string_lossy().to_string()))
+}
+
+// Written next to the target and renamed, so an interrupted archive never replaces a good one.
+// Returns how many segments had no audio file.
+fn write_archive(
+    path: &Path,
+    segments_dir: &Path,
+    contents: &ArchiveContents,
+) -> Result<usize, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let tmp = path.with_extension("zip.tmp");
+    let written = write_entries(&tmp, segments_dir, contents);
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    let missing = written?;
+    fs::rename(&tmp, path).map_err(|err| err.to_string())?;
+    Ok(missing)
+}
+
+fn write_entries(
+    path: &Path,
+    segments_dir: &Path,
+    contents: &ArchiveContents,
+) -> Result<usize, String> {
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    write_json(&mut zip, SESSION_ENTRY, &contents.session, options)?;
+    write_json(&mut zip, SEGMENTS_ENTRY, &contents.segments, options)?;
+    if let Some(digest) = &contents.digest {
+        write_json(&mut zip, SUMMARY_ENTRY, digest, options)?;
+    }
+    let mut missing = 0;
+    for segment in &contents.segments {
+        let Ok(mut audio) = File::open(segments_dir.join(&segment.name)) else {
+            missing += 1;
+            continue;
+        };
+        zip.start_file(format!("{AUDIO_PREFIX}{}", segment.name), options)
+            .map_err(|err| err.to_string())?;
+        io::copy(&mut audio, &mut zip).map_err(|err| err.to_string())?;
+    }
+    if let Ok(mut recording) = File::open(recording_path(segments_dir, &contents.session.id)) {
+        zip.start_file(RECORDING_ENTRY, options)
+            .map_err(|err| err.to_string())?;
+        io::copy(&mut recording, &mut zip).map_err(|err| err.to_string())?;
+    }
+    zip.finish().map_err(|err| err.to_string())?;
+    Ok(missing)
+}
+
+fn write_json<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    value: &impl Serialize,
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value).map_err(|err| err.to_string())?;
+    zip.start_file(name, options)
+        .map_err(|err| err.to_string())?;
+    zip.write_all(&content).map_err(|err| err.to_string())
+}
+
+fn read_archive(path: &Path) -> Result<ArchiveContents, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let session = read_json(&mut zip, SESSION_ENTRY)?;
+    let segments: Vec<SegmentInfo> = read_json(&mut zip, SEGMENTS_ENTRY)?;
+    let has_summary = zip.file_names().any(|name| name == SUMMARY_ENTRY);
+    let digest = if has_summary {
+        Some(read_json(&mut zip, SUMMARY_ENTRY)?)
+    } else {
+        None
+    };
+    if let Some(segment) = segments
+        .iter()
+        .find(|segment| !is_plain_name(&segment.name))
+    {
+        return Err(format!("invalid segment name in archive: {}", segment.name));
+    }
+    Ok(ArchiveContents {
+        session,
+        segments,
+        digest,
+    })
+}
+
+fn read_json<T: DeserializeOwned, R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<T, String> {
+    let mut entry = zip.by_name(name).map_err(|err| format!("{name}: {err}"))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|err| format!("{name}: {err}"))?;
+    serde_json::from_str(&content).map_err(|err| format!("{name}: {err}"))
+}
+
+// Segments archived without audio keep their transcript only. Returns the files written.
+fn extract_audio(
+    path: &Path,
+    segments_dir: &Path,
+    segments: &[SegmentInfo],
+) -> Result<usize, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let mut restored = 0;
+    for segment in segments {
+        let Ok(mut entry) = zip.by_name(&format!("{AUDIO_PREFIX}{}", segment.name)) else {
+            continue;
+        };
+        let mut output =
+            File::create(segments_dir.join(&segment.name)).map_err(|err| err.to_string())?;
+        io::copy(&mut entry, &mut output).map_err(|err| err.to_string())?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+// Returns whether the archive had a continuous recording.
+fn extract_recording(path: &Path, target: &Path) -> Result<bool, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|err| err.to_string())?;
+    let Ok(mut entry) = zip.by_name(RECORDING_ENTRY) else {
+        return Ok(false);
+    };
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let mut output = File::create(target).map_err(|err| err.to_string())?;
+    io::copy(&mut entry, &mut output).map_err(|err| err.to_string())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chronological_position, extract_audio, extract_recording, is_plain_name, read_archive,
+        write_archive, ArchiveContents,
+    };
+    use crate::audio::manager::SegmentInfo;
+    use crate::audio::recording::recording_path;
+
+    #[test]
+    fn archives_and_restores_a_session() {
+        let dir = std::env::temp_dir().join(format!("ai_shepherd_archive_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (source, target) = (dir.join("source"), dir.join("target"));
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(source.join("seg_1.wav"), b"RIFF audio").unwrap();
+        let recording = recording_path(&source, "session_1");
+        std::fs::create_dir_all(recording.parent().unwrap()).unwrap();
+        std::fs::write(&recording, b"RIFF meeting").unwrap();
+
+        let segment = |name: &str| SegmentInfo {
+            name: name.to_string(),
+            session_id: Some("session_1".to_string()),
+            transcript: Some(format!("{name} transcript")),
+            ..Default::default()
+        };
+        let contents = ArchiveContents {
+            session: serde_json::from_value(serde_json::json!({
+                "id": "session_1",
+                "started_at": "2026-03-01T10:00:00+09:00",
+                "ended_at": "2026-03-01T11:00:00+09:00",
+            }))
+            .unwrap(),
+            segments: vec![segment("seg_1.wav"), segment("seg_2.wav")],
+            digest: None,
+        };
+        let path = dir.join("archives").join("session_1.zip");
+        assert_eq!(write_archive(&path, &source, &contents).unwrap(), 1);
+
+        let restored = read_archive(&path).unwrap();
+        assert_eq!(restored.session.id, "session_1");
+        assert_eq!(restored.segments.len(), 2);
+        assert_eq!(
+            restored.segments[1].transcript.as_deref(),
+            Some("seg_2.wav transcript")
+        );
+        assert!(restored.digest.is_none());
+        assert_eq!(
+            extract_audio(&path, &target, &restored.segments).unwrap(),
+            1
+        );
+        assert_eq!(
+            std::fs::read(target.join("seg_1.wav")).unwrap(),
+            b"RIFF audio"
+        );
+        let restored_recording = recording_path(&target, "session_1");
+        assert!(extract_recording(&path, &restored_recording).unwrap());
+        assert_eq!(std::fs::read(restored_recording).unwrap(), b"RIFF meeting");
+
+        assert!(is_plain_name("seg_1.wav"));
+        assert!(!is_plain_name("../sessions.json"));
+        assert!(!is_plain_name("..\\index.json"));
+        assert!(!is_plain_name(".."));
+
+        let stamps = ["2026-03-01T09:00:00+09:00", "2026-03-02T09:00:00+09:00"];
+        assert_eq!(
+            chronological_position(&stamps, "2026-03-01T10:00:00+09:00"),
+            1
+        );
+        assert_eq!(
+            chronological_position(&stamps, "2026-02-28T10:00:00+09:00"),
+            0
+        );
+        assert_eq!(chronological_position(&stamps, "not a date"), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
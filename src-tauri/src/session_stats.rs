@@ -0,0 +1,224 @@
+use crate::audio::manager::SessionInfo;
+use crate::audio::{CaptureManager, SegmentInfo};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SessionStats {
+    pub session_id: String,
+    pub title: Option<String>,
+    pub active: bool,
+    // Wall-clock time from start to end, or to now while the session runs.
+    pub duration_ms: u64,
+    // Audio kept as segments, i.e. time someone was speaking.
+    pub speech_ms: u64,
+    pub segment_count: usize,
+    // Transcript words keyed by ASR language; CJK characters count as one word each.
+    pub words_per_language: BTreeMap<String, u64>,
+    pub translated_words_per_language: BTreeMap<String, u64>,
+    pub avg_transcribe_ms: Option<u64>,
+    pub avg_translate_ms: Option<u64>,
+    // Keyed by provider.
+    pub transcribe_errors: BTreeMap<String, u64>,
+    pub translate_errors: BTreeMap<String, u64>,
+}
+
+// Without a session id: the running session, or the most recent one.
+#[tauri::command]
+pub fn get_session_stats(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    session_id: Option<String>,
+) -> Result<SessionStats, String> {
+    let mut sessions = state.list_sessions(app.clone())?;
+    let session = match session_id.as_deref().filter(|id| !id.trim().is_empty()) {
+        Some(id) => sessions
+            .into_iter()
+            .find(|item| item.session.id == id)
+            .ok_or_else(|| format!("session not found: {id}"))?,
+        None => {
+            let active = sessions.iter().rposition(|item| item.active);
+            let index = active
+                .or_else(|| sessions.len().checked_sub(1))
+                .ok_or_else(|| "no sessions yet".to_string())?;
+            sessions.swap_remove(index)
+        }
+    };
+    let segments = state.list_session_segments(app, &session.session.id)?;
+    Ok(compute_stats(&session.session, &segments, Local::now()))
+}
+
+pub(crate) fn compute_stats(
+    session: &SessionInfo,
+    segments: &[SegmentInfo],
+    now: DateTime<Local>,
+) -> SessionStats {
+    let mut stats = SessionStats {
+        session_id: session.id.clone(),
+        title: session.title.clone(),
+        active: session.ended_at.is_none(),
+        duration_ms: duration_ms(&session.started_at, session.ended_at.as_deref(), now),
+        segment_count: segments.len(),
+        ..SessionStats::default()
+    };
+    let mut transcribe_ms = Vec::new();
+    let mut translate_ms = Vec::new();
+    for segment in segments {
+        stats.speech_ms += segment.duration_ms;
+        if let Some(text) = segment.transcript.as_deref() {
+            let language = segment
+                .transcript_language
+                .clone()
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or_else(|| "unknown".to_string());
+            add_words(&mut stats.words_per_language, language, text);
+        }
+        if let (Some(text), Some(language)) = (
+            segment.translation.as_deref(),
+            segment.translation_language.clone(),
+        ) {
+            add_words(&mut stats.translated_words_per_language, language, text);
+        }
+        // Failed and dropped segments finish in 0 ms and would drag the average down.
+        if let Some(ms) = segment
+            .timings
+            .transcribe_ms
+            .or(segment.transcript_ms)
+            .filter(|ms| *ms > 0)
+        {
+            transcribe_ms.push(ms);
+        }
+        if let Some(ms) = segment
+            .timings
+            .translate_ms
+            .or(segment.translation_ms)
+            .filter(|ms| *ms > 0)
+        {
+            translate_ms.push(ms);
+        }
+        for failure in &segment.failures {
+            let errors = match failure.stage.as_str() {
+                "transcribe" => &mut stats.transcribe_errors,
+                _ => &mut stats.translate_errors,
+            };
+            *errors.entry(failure.provider.clone()).or_default() += 1;
+        }
+    }
+    stats.avg_transcribe_ms = average(&transcribe_ms);
+    stats.avg_translate_ms = average(&translate_ms);
+    stats
+}
+
+fn duration_ms(started_at: &str, ended_at: Option<&str>, now: DateTime<Local>) -> u64 {
+    let Ok(start) = DateTime::parse_from_rfc3339(started_at) else {
+        return 0;
+    };
+    let end = match ended_at.map(DateTime::parse_from_rfc3339) {
+        Some(Ok(end)) => end,
+        Some(Err(_)) => return 0,
+        None => now.fixed_offset(),
+    };
+    (end - start).num_milliseconds().max(0) as u64
+}
+
+fn add_words(totals: &mut BTreeMap<String, u64>, language: String, text: &str) {
+    let count = count_words(text);
+    if count > 0 {
+        *totals.entry(language).or_default() += count;
+    }
+}
+
+fn count_words(text: &str) -> u64 {
+    let mut count = 0;
+    let mut in_word = false;
+    for ch in text.chars() {
+        if is_cjk(ch) {
+            count += 1;
+            in_word = false;
+        } else if ch.is_alphanumeric() || (in_word && ch == '\'') {
+            if !in_word {
+                count += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+    count
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+fn average(values: &[u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<u64>() / values.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compute_stats;
+    use crate::audio::manager::{ProviderFailure, SessionInfo};
+    use crate::audio::SegmentInfo;
+    use chrono::{DateTime, Local};
+
+    #[test]
+    fn totals_speech_words_latency_and_errors() {
+        let session: SessionInfo = serde_json::from_value(serde_json::json!({
+            "id": "session_1",
+            "started_at": "2025-01-20T10:00:00.000+09:00",
+            "ended_at": "2025-01-20T10:30:00.000+09:00",
+        }))
+        .unwrap();
+        let segments = vec![
+            SegmentInfo {
+                duration_ms: 4_000,
+                transcript: Some("We can't ship on Friday".to_string()),
+                transcript_language: Some("en".to_string()),
+                transcript_ms: Some(800),
+                translation: Some("我们周五不能发布".to_string()),
+                translation_language: Some("zh".to_string()),
+                translation_ms: Some(1_500),
+                ..SegmentInfo::default()
+            },
+            SegmentInfo {
+                duration_ms: 2_000,
+                transcript: Some(String::new()),
+                transcript_ms: Some(0),
+                failures: vec![ProviderFailure {
+                    stage: "transcribe".to_string(),
+                    provider: "whisper".to_string(),
+                }],
+                ..SegmentInfo::default()
+            },
+            SegmentInfo {
+                duration_ms: 3_000,
+                transcript: Some("发布 delayed".to_string()),
+                transcript_language: Some("en".to_string()),
+                transcript_ms: Some(400),
+                ..SegmentInfo::default()
+            },
+        ];
+        let now = DateTime::parse_from_rfc3339("2025-01-20T12:00:00+09:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let stats = compute_stats(&session, &segments, now);
+        assert_eq!(stats.duration_ms, 30 * 60 * 1000);
+        assert_eq!(stats.speech_ms, 9_000);
+        assert_eq!(stats.segment_count, 3);
+        assert_eq!(stats.words_per_language.get("en"), Some(&8));
+        assert_eq!(stats.translated_words_per_language.get("zh"), Some(&8));
+        assert_eq!(stats.avg_transcribe_ms, Some(600));
+        assert_eq!(stats.avg_translate_ms, Some(1_500));
+        assert_eq!(stats.transcribe_errors.get("whisper"), Some(&1));
+        assert!(!stats.active);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::app_config::{load_config_value, save_config_value};
+use crate::audio::config::{self as audio_config, AudioConfig};
+use crate::timestamps::now_rfc3339;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const BUNDLE_VERSION: u32 = 1;
+const BUNDLE_FILE_NAME: &str = "ai-interview-settings.json";
+const SECRET_MARKERS: [&str; 4] = ["apikey", "token", "secret", "password"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub bundle_version: u32,
+    pub exported_at: String,
+    pub config: Value,
+    pub audio: Option<AudioConfig>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    pub config_path: String,
+    pub audio_path: Option<String>,
+    pub exported_at: String,
+}
+
+#[tauri::command]
+pub fn export_settings_bundle(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    let Some(path) = path.map(PathBuf::from).or_else(|| {
+        rfd::FileDialog::new()
+            .set_file_name(BUNDLE_FILE_NAME)
+            .add_filter("JSON", &["json"])
+            .save_file()
+    }) else {
+        return Ok(None);
+    };
+
+    let (_, mut config) = load_config_value()?;
+    strip_secrets(&mut config);
+    let bundle = SettingsBundle {
+        bundle_version: BUNDLE_VERSION,
+        exported_at: now_rfc3339(),
+        config,
+        audio: Some(audio_config::load_config(&app)),
+    };
+    let content = serde_json::to_string_pretty(&bundle).map_err(|err| err.to_string())?;
+    fs::write(&path, content)
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub fn import_settings_bundle(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<SettingsImportReport>, String> {
+    let Some(path) = path.map(PathBuf::from).or_else(|| {
+        rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+    }) else {
+        return Ok(None);
+    };
+
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let bundle: SettingsBundle = serde_json::from_str(&content)
+        .map_err(|err| format!("invalid settings bundle {}: {err}", path.display()))?;
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err(format!(
+            "settings bundle version {} is not supported",
+            bundle.bundle_version
+        ));
+    }
+
+    let mut config = bundle.config;
+    strip_secrets(&mut config);
+    // Secrets never travel in a bundle, so keep whatever this machine already has.
+    if let Ok((_, local)) = load_config_value() {
+        restore_secrets(&mut config, &local);
+    }
+    ensure_openai_key(&mut config);
+    let config_path = save_config_value(&config)?;
+
+    let audio_path = match bundle.audio {
+        Some(audio) => Some(
+            audio_config::save_config(&app, &audio)?
+                .to_string_lossy()
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    Ok(Some(SettingsImportReport {
+        config_path: config_path.to_string_lossy().to_string(),
+        audio_path,
+        exported_at: bundle.exported_at,
+    }))
+}
+
+pub fn is_secret_key(key: &str) -> bool {
+    let normalized = key
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    SECRET_MARKERS
+        .iter()
+        .any(|marker| normalized.ends_with(marker))
+}
+
+pub fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !is_secret_key(key));
+            for child in map.values_mut() {
+                strip_secrets(child);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                strip_secrets(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn restore_secrets(target: &mut Value, local: &Value) {
+    let (Value::Object(target), Value::Object(local)) = (target, local) else {
+        return;
+    };
+    for (key, local_value) in local {
+        if is_secret_key(key) {
+            target.insert(key.clone(), local_value.clone());
+        } else if let Some(child) = target.get_mut(key) {
+            restore_secrets(child, local_value);
+        }
+    }
+}
+
+fn ensure_openai_key(config: &mut Value) {
+    let Some(root) = config.as_object_mut() else {
+        return;
+    };
+    let openai = root
+        .entry("openai")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(fields) = openai {
+        fields
+            .entry("apiKey")
+            .or_insert_with(|| Value::String(String::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{restore_secrets, strip_secrets};
+    use serde_json::json;
+
+    #[test]
+    fn strips_nested_secrets() {
+        let mut config = json!({
+            "openai": { "apiKey": "sk-test", "model": "whisper-1" },
+            "translate": { "livePrompt": "keep me" }
+        });
+        strip_secrets(&mut config);
+        assert!(config["openai"].get("apiKey").is_none());
+        assert_eq!(config["openai"]["model"], "whisper-1");
+        assert_eq!(config["translate"]["livePrompt"], "keep me");
+    }
+
+    #[test]
+    fn restores_local_secrets_on_import() {
+        let mut imported = json!({ "openai": { "model": "gpt-4o-transcribe" } });
+        let local = json!({ "openai": { "apiKey": "sk-local", "model": "whisper-1" } });
+        restore_secrets(&mut imported, &local);
+        assert_eq!(imported["openai"]["apiKey"], "sk-local");
+        assert_eq!(imported["openai"]["model"], "gpt-4o-transcribe");
+    }
+}
@@ -0,0 +1,219 @@
+use crate::app_config::load_config;
+use crate::http;
+use crate::pii::mask_text;
+use crate::summary::{load_digest, SessionDigest};
+use crate::timestamps::TimeDisplay;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::AppHandle;
+
+const SHARE_TIMEOUT_SECS: u64 = 15;
+const DISCORD_MAX_CHARS: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShareTarget {
+    Slack,
+    Discord,
+}
+
+impl ShareTarget {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "slack" => Ok(Self::Slack),
+            "discord" => Ok(Self::Discord),
+            other => Err(format!("unsupported share target: {other}")),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Slack => "slack",
+            Self::Discord => "discord",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SharePreview {
+    pub target: String,
+    pub destination: String,
+    pub text: String,
+    pub payload: Value,
+}
+
+// Dry run: shows exactly what `send_meeting_share` would post, without sending.
+#[tauri::command]
+pub fn preview_meeting_share(
+    app: AppHandle,
+    session_id: String,
+    target: String,
+) -> Result<SharePreview, String> {
+    let target = ShareTarget::parse(&target)?;
+    let url = webhook_url(target)?;
+    let text = mask_text(&format_message(
+        target,
+        &require_digest(&app, &session_id)?,
+        &TimeDisplay::from_config(),
+    ));
+    Ok(SharePreview {
+        target: target.as_str().to_string(),
+        destination: mask_url(&url),
+        payload: payload_for(target, &text),
+        text,
+    })
+}
+
+// `text` lets the UI send the preview after the user edited it.
+#[tauri::command]
+pub async fn send_meeting_share(
+    app: AppHandle,
+    session_id: String,
+    target: String,
+    text: Option<String>,
+) -> Result<(), String> {
+    let target = ShareTarget::parse(&target)?;
+    let url = webhook_url(target)?;
+    let text = match text.filter(|value| !value.trim().is_empty()) {
+        Some(text) => text,
+        None => format_message(
+            target,
+            &require_digest(&app, &session_id)?,
+            &TimeDisplay::from_config(),
+        ),
+    };
+    // Edited text is masked too, in case names were typed back in.
+    let text = mask_text(&text);
+    let client = http::client(Some(SHARE_TIMEOUT_SECS))?;
+    let response = client
+        .post(&url)
+        .json(&payload_for(target, &text))
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "{} returned HTTP {status}: {body}",
+            target.as_str()
+        ));
+    }
+    Ok(())
+}
+
+fn require_digest(app: &AppHandle, session_id: &str) -> Result<SessionDigest, String> {
+    load_digest(app, session_id)?
+        .ok_or_else(|| format!("no summary for session {session_id}; summarize it first"))
+}
+
+fn webhook_url(target: ShareTarget) -> Result<String, String> {
+    let share = load_config()?.share.unwrap_or_default();
+    let url = match target {
+        ShareTarget::Slack => share.slack_webhook_url,
+        ShareTarget::Discord => share.discord_webhook_url,
+    };
+    url.map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("share.{}WebhookUrl is not configured", target.as_str()))
+}
+
+fn format_message(target: ShareTarget, digest: &SessionDigest, display: &TimeDisplay) -> String {
+    let (bold, bullet) = match target {
+        ShareTarget::Slack => ("*", "•"),
+        ShareTarget::Discord => ("**", "-"),
+    };
+    let title = digest
+        .title
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "Meeting summary".to_string());
+    let date = digest
+        .started_at
+        .as_deref()
+        .filter(|value| chrono::DateTime::parse_from_rfc3339(value).is_ok())
+        .map(|value| format!(" ({})", display.absolute().format(value)))
+        .unwrap_or_default();
+    let mut text = format!("{bold}{title}{bold}{date}\n\n{}", digest.summary.trim());
+    if !digest.action_items.is_empty() {
+        text.push_str(&format!("\n\n{bold}Action items{bold}"));
+        for item in &digest.action_items {
+            text.push_str(&format!("\n{bullet} {item}"));
+        }
+    }
+    text
+}
+
+fn payload_for(target: ShareTarget, text: &str) -> Value {
+    match target {
+        ShareTarget::Slack => json!({ "text": text }),
+        ShareTarget::Discord => {
+            let content = if text.chars().count() > DISCORD_MAX_CHARS {
+                let mut truncated = text.chars().take(DISCORD_MAX_CHARS - 1).collect::<String>();
+                truncated.push('…');
+                truncated
+            } else {
+                text.to_string()
+            };
+            json!({ "content": content })
+        }
+    }
+}
+
+// Webhook URLs embed their credential in the path, so only the host is shown.
+fn mask_url(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            parsed
+                .host_str()
+                .map(|host| format!("{}://{host}/…", parsed.scheme()))
+        })
+        .unwrap_or_else(|| "…".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_message, mask_url, payload_for, ShareTarget};
+    use crate::app_config::TimeConfig;
+    use crate::summary::SessionDigest;
+    use crate::timestamps::TimeDisplay;
+
+    fn display() -> TimeDisplay {
+        TimeDisplay::from_settings(&TimeConfig {
+            timezone: Some("original".to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn digest() -> SessionDigest {
+        SessionDigest {
+            session_id: "session_1".to_string(),
+            title: Some("Weekly Platform Sync".to_string()),
+            started_at: Some("2025-01-20T10:00:00+01:00".to_string()),
+            summary: "Agreed on Q3 scope.".to_string(),
+            action_items: vec!["Ana: draft plan".to_string()],
+            provider: "ollama".to_string(),
+            generated_at: "2025-01-20T11:00:00+01:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn formats_per_target() {
+        assert_eq!(
+            format_message(ShareTarget::Slack, &digest(), &display()),
+            "*Weekly Platform Sync* (2025-01-20 10:00)\n\nAgreed on Q3 scope.\n\n*Action items*\n• Ana: draft plan"
+        );
+        assert!(format_message(ShareTarget::Discord, &digest(), &display())
+            .starts_with("**Weekly Platform Sync**"));
+    }
+
+    #[test]
+    fn truncates_discord_and_masks_urls() {
+        let payload = payload_for(ShareTarget::Discord, &"x".repeat(2_500));
+        assert_eq!(payload["content"].as_str().unwrap().chars().count(), 2_000);
+        assert_eq!(
+            mask_url("https://hooks.slack.com/services/T000/B000/secret"),
+            "https://hooks.slack.com/…"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+// Incremental decoders for streamed HTTP bodies (NDJSON from Ollama, SSE from OpenAI-style APIs).
+// Consumed bytes are dropped once per chunk instead of re-copying the tail after every line, and
+// text is decoded per complete line so characters split across chunks stay intact.
+
+#[derive(Debug, Default)]
+pub struct LineDecoder {
+    buffer: Vec<u8>,
+    start: usize,
+}
+
+impl LineDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        if self.start > 0 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    // The next complete line without its line ending, or `None` until more bytes arrive.
+    pub fn next_line(&mut self) -> Option<String> {
+        let offset = self.buffer[self.start..]
+            .iter()
+            .position(|byte| *byte == b'\n')?;
+        let end = self.start + offset;
+        let line = decode_line(&self.buffer[self.start..end]);
+        self.start = end + 1;
+        Some(line)
+    }
+
+    // Whatever is left after the stream ended without a trailing newline.
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = &self.buffer[self.start..];
+        let line = (!rest.is_empty()).then(|| decode_line(rest));
+        self.buffer.clear();
+        self.start = 0;
+        line
+    }
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+// Yields the `data` of each server-sent event; multi-line data is joined with `\n` and other
+// fields (event, id, retry, comments) are ignored.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    lines: LineDecoder,
+    data: Option<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.lines.push(chunk);
+    }
+
+    pub fn next_data(&mut self) -> Option<String> {
+        while let Some(line) = self.lines.next_line() {
+            if let Some(data) = self.accept(&line) {
+                return Some(data);
+            }
+        }
+        None
+    }
+
+    // Dispatches an event whose terminating blank line never arrived.
+    pub fn finish(&mut self) -> Option<String> {
+        if let Some(line) = self.lines.finish() {
+            if let Some(data) = self.accept(&line) {
+                return Some(data);
+            }
+        }
+        self.data.take()
+    }
+
+    fn accept(&mut self, line: &str) -> Option<String> {
+        if line.is_empty() {
+            return self.data.take();
+        }
+        let value = line.strip_prefix("data:")?;
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match self.data.as_mut() {
+            Some(data) => {
+                data.push('\n');
+                data.push_str(value);
+            }
+            None => self.data = Some(value.to_string()),
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFraming {
+    Sse,
+    Lines,
+}
+
+// Yields one event payload per SSE `data` field or per line, whichever framing the body uses.
+#[derive(Debug)]
+pub struct EventDecoder {
+    framing: StreamFraming,
+    lines: LineDecoder,
+    events: SseDecoder,
+}
+
+impl EventDecoder {
+    pub fn new(framing: StreamFraming) -> Self {
+        Self {
+            framing,
+            lines: LineDecoder::new(),
+            events: SseDecoder::new(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        match self.framing {
+            StreamFraming::Sse => self.events.push(chunk),
+            StreamFraming::Lines => self.lines.push(chunk),
+        }
+    }
+
+    pub fn next_event(&mut self) -> Option<String> {
+        match self.framing {
+            StreamFraming::Sse => self.events.next_data(),
+            StreamFraming::Lines => self.lines.next_line(),
+        }
+    }
+
+    pub fn finish(&mut self) -> Option<String> {
+        match self.framing {
+            StreamFraming::Sse => self.events.finish(),
+            StreamFraming::Lines => self.lines.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventDecoder, LineDecoder, SseDecoder, StreamFraming};
+
+    #[test]
+    fn decodes_lines_and_events_split_across_chunks() {
+        let mut lines = LineDecoder::new();
+        let text = "{\"response\":\"你好\"}\r\n{\"done\":true}";
+        let bytes = text.as_bytes();
+        lines.push(&bytes[..15]);
+        assert_eq!(lines.next_line(), None);
+        lines.push(&bytes[15..]);
+        assert_eq!(
+            lines.next_line().as_deref(),
+            Some("{\"response\":\"你好\"}")
+        );
+        assert_eq!(lines.next_line(), None);
+        assert_eq!(lines.finish().as_deref(), Some("{\"done\":true}"));
+
+        let mut sse = SseDecoder::new();
+        sse.push(b": keep-alive\n\ndata: {\"a\":1}\n\nevent: x\ndata: line1\nda");
+        assert_eq!(sse.next_data().as_deref(), Some("{\"a\":1}"));
+        assert_eq!(sse.next_data(), None);
+        sse.push(b"ta: line2\n\ndata: [DONE]");
+        assert_eq!(sse.next_data().as_deref(), Some("line1\nline2"));
+        assert_eq!(sse.finish().as_deref(), Some("[DONE]"));
+    }
+
+    #[test]
+    fn event_decoder_follows_the_framing() {
+        let body = b"data: {\"a\":1}\n\ndata: {\"a\":2}\n\n";
+        let mut sse = EventDecoder::new(StreamFraming::Sse);
+        sse.push(&body[..20]);
+        assert_eq!(sse.next_event().as_deref(), Some("{\"a\":1}"));
+        assert_eq!(sse.next_event(), None);
+        sse.push(&body[20..]);
+        assert_eq!(sse.next_event().as_deref(), Some("{\"a\":2}"));
+        assert_eq!(sse.finish(), None);
+
+        let mut lines = EventDecoder::new(StreamFraming::Lines);
+        lines.push(b"{\"a\":1}\n{\"a\":");
+        assert_eq!(lines.next_event().as_deref(), Some("{\"a\":1}"));
+        lines.push(b"2}");
+        assert_eq!(lines.next_event(), None);
+        assert_eq!(lines.finish().as_deref(), Some("{\"a\":2}"));
+    }
+}
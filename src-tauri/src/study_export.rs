@@ -0,0 +1,434 @@
+use crate::app_config::load_config;
+use crate::audio::manager::{ensure_segments_dir, SessionInfo};
+use crate::audio::{CaptureManager, SegmentInfo};
+use crate::{generate_with_selected_provider, selected_translate_provider};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tracing::{info, warn};
+
+const READING_PROMPT: &str = "You add reading aids for language learners.\n\
+For each item in `items`, return `reading` for its `text`:\n\
+- script `pinyin`: the whole sentence in Hanyu Pinyin with tone marks, one space between words;\n\
+- script `furigana`: the sentence with every kanji word followed by its hiragana reading in \
+parentheses, e.g. 会議(かいぎ)を始(はじ)めます.\n\
+Return ONLY a JSON array. Each element must be {\"id\": string, \"reading\": string}.\n\
+Input:\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Script {
+    Pinyin,
+    Furigana,
+}
+
+impl Script {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pinyin => "pinyin",
+            Self::Furigana => "furigana",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StudyFormat {
+    Vtt,
+    Markdown,
+}
+
+impl StudyFormat {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("vtt").trim().to_lowercase().as_str() {
+            "vtt" | "webvtt" => Ok(Self::Vtt),
+            "md" | "markdown" => Ok(Self::Markdown),
+            other => Err(format!("unsupported study export format: {other}")),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Vtt => "vtt",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StudyExportRequest {
+    pub session_id: String,
+    // `vtt` (default) or `md`.
+    pub format: Option<String>,
+    // Pinyin/furigana readings from the selected LLM provider; on by default.
+    pub annotate: Option<bool>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudyExport {
+    pub format: String,
+    pub lines: usize,
+    pub annotated: usize,
+    pub content: String,
+    pub exported_to: Option<String>,
+}
+
+// One segment: the source line, its translation, optional readings and the clip it came from.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StudyLine {
+    start_ms: u64,
+    end_ms: u64,
+    source: String,
+    source_language: Option<String>,
+    source_reading: Option<String>,
+    translation: Option<String>,
+    translation_language: Option<String>,
+    translation_reading: Option<String>,
+    clip: String,
+}
+
+#[tauri::command]
+pub async fn export_study_subtitles(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    request: StudyExportRequest,
+) -> Result<StudyExport, String> {
+    let format = StudyFormat::parse(request.format.as_deref())?;
+    let session = state
+        .list_sessions(app.clone())?
+        .into_iter()
+        .find(|item| item.session.id == request.session_id)
+        .ok_or_else(|| format!("session not found: {}", request.session_id))?
+        .session;
+    let segments = state.list_session_segments(app.clone(), &session.id)?;
+    let clip_dir = ensure_segments_dir(&app)?;
+    let mut lines = study_lines(&session, &segments, &clip_dir);
+    if lines.is_empty() {
+        return Err("session has no transcript".to_string());
+    }
+
+    let annotated = if request.annotate.unwrap_or(true) {
+        // Readings are a study aid: without them the export is still useful.
+        match annotate(&app, &mut lines).await {
+            Ok(count) => count,
+            Err(err) => {
+                warn!("[study] reading annotation failed: {err}");
+                0
+            }
+        }
+    } else {
+        0
+    };
+
+    let title = session.title.as_deref().unwrap_or(&session.id);
+    let content = match format {
+        StudyFormat::Vtt => render_vtt(&lines),
+        StudyFormat::Markdown => render_markdown(title, &lines),
+    };
+    let exported_to = match request
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        Some(path) => Some(write_export(Path::new(path), &content)?),
+        None => None,
+    };
+    info!(
+        "[study] exported {} lines ({} annotated) of {} as {}",
+        lines.len(),
+        annotated,
+        session.id,
+        format.as_str()
+    );
+    Ok(StudyExport {
+        format: format.as_str().to_string(),
+        lines: lines.len(),
+        annotated,
+        content,
+        exported_to,
+    })
+}
+
+fn study_lines(session: &SessionInfo, segments: &[SegmentInfo], clip_dir: &Path) -> Vec<StudyLine> {
+    let session_start = DateTime::parse_from_rfc3339(&session.started_at).ok();
+    let mut elapsed_ms = 0;
+    let mut lines = Vec::new();
+    for segment in segments {
+        // Older segments have no timeline offset; they are written from their first sample, so
+        // `created_at` stands in for it.
+        let start_ms = segment.start_offset_ms.unwrap_or_else(|| {
+            session_start
+                .zip(DateTime::parse_from_rfc3339(&segment.created_at).ok())
+                .map(|(start, created)| (created - start).num_milliseconds().max(0) as u64)
+                .unwrap_or(elapsed_ms)
+        });
+        let end_ms = segment
+            .end_offset_ms
+            .unwrap_or(start_ms + segment.duration_ms);
+        elapsed_ms = end_ms;
+        let Some(source) = segment
+            .transcript
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+        else {
+            continue;
+        };
+        lines.push(StudyLine {
+            start_ms,
+            end_ms,
+            source: source.to_string(),
+            source_language: segment.transcript_language.clone(),
+            translation: segment
+                .translation
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            translation_language: segment.translation_language.clone(),
+            clip: clip_dir.join(&segment.name).to_string_lossy().to_string(),
+            ..StudyLine::default()
+        });
+    }
+    lines
+}
+
+// Chinese gets pinyin, Japanese gets furigana; text without kanji needs neither. `auto` or a
+// missing language falls back to looking for kana.
+fn reading_script(text: &str, language: Option<&str>) -> Option<Script> {
+    if !text.chars().any(is_han) {
+        return None;
+    }
+    let language = language.unwrap_or_default().to_lowercase();
+    if language.starts_with("ja") {
+        return Some(Script::Furigana);
+    }
+    if language.starts_with("zh") {
+        return Some(Script::Pinyin);
+    }
+    if text.chars().any(is_kana) {
+        Some(Script::Furigana)
+    } else {
+        Some(Script::Pinyin)
+    }
+}
+
+fn is_han(ch: char) -> bool {
+    matches!(ch as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF)
+}
+
+fn is_kana(ch: char) -> bool {
+    matches!(ch as u32, 0x3040..=0x30FF)
+}
+
+// One request for the whole session; returns how many readings were filled in.
+async fn annotate(app: &AppHandle, lines: &mut [StudyLine]) -> Result<usize, String> {
+    let mut items = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let texts = [
+            (
+                format!("s{index}"),
+                Some(line.source.as_str()),
+                &line.source_language,
+            ),
+            (
+                format!("t{index}"),
+                line.translation.as_deref(),
+                &line.translation_language,
+            ),
+        ];
+        for (id, text, language) in texts {
+            let Some(text) = text else {
+                continue;
+            };
+            if let Some(script) = reading_script(text, language.as_deref()) {
+                items.push(json!({ "id": id, "script": script.as_str(), "text": text }));
+            }
+        }
+    }
+    if items.is_empty() {
+        return Ok(0);
+    }
+    let payload =
+        serde_json::to_string(&json!({ "items": items })).map_err(|err| err.to_string())?;
+    let config = load_config()?;
+    let provider = selected_translate_provider(app);
+    let raw = generate_with_selected_provider(
+        app,
+        &provider,
+        &format!("{READING_PROMPT}{payload}"),
+        &config,
+        "study_export",
+    )
+    .await?;
+    let mut readings = parse_readings(&raw)?;
+    let mut count = 0;
+    for (index, line) in lines.iter_mut().enumerate() {
+        line.source_reading = readings.remove(&format!("s{index}"));
+        line.translation_reading = readings.remove(&format!("t{index}"));
+        count += usize::from(line.source_reading.is_some())
+            + usize::from(line.translation_reading.is_some());
+    }
+    Ok(count)
+}
+
+#[derive(Deserialize)]
+struct Reading {
+    id: String,
+    reading: String,
+}
+
+fn parse_readings(raw: &str) -> Result<HashMap<String, String>, String> {
+    let json = raw
+        .find('[')
+        .zip(raw.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &raw[start..=end])
+        .ok_or_else(|| "reading response is not a JSON array".to_string())?;
+    let readings: Vec<Reading> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    Ok(readings
+        .into_iter()
+        .filter(|item| !item.reading.trim().is_empty())
+        .map(|item| (item.id, item.reading.trim().to_string()))
+        .collect())
+}
+
+fn timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1_000 % 60,
+        ms % 1_000
+    )
+}
+
+// Each cue stacks source, reading and translation; the clip goes in a NOTE players ignore.
+fn render_vtt(lines: &[StudyLine]) -> String {
+    let mut output = String::from("WEBVTT\n");
+    for (index, line) in lines.iter().enumerate() {
+        output.push_str(&format!("\nNOTE clip: {}\n\n", line.clip));
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n",
+            index + 1,
+            timestamp(line.start_ms),
+            timestamp(line.end_ms),
+            line.source
+        ));
+        if let Some(reading) = &line.source_reading {
+            output.push_str(&format!("<i>{reading}</i>\n"));
+        }
+        if let Some(translation) = &line.translation {
+            output.push_str(&format!("{translation}\n"));
+        }
+        if let Some(reading) = &line.translation_reading {
+            output.push_str(&format!("<i>{reading}</i>\n"));
+        }
+    }
+    output
+}
+
+fn render_markdown(title: &str, lines: &[StudyLine]) -> String {
+    let mut output = format!("# {title}\n");
+    for line in lines {
+        output.push_str(&format!(
+            "\n**{}** [clip](<{}>)\n\n{}\n",
+            &timestamp(line.start_ms)[..8],
+            line.clip,
+            line.source
+        ));
+        if let Some(reading) = &line.source_reading {
+            output.push_str(&format!("*{reading}*\n"));
+        }
+        if let Some(translation) = &line.translation {
+            output.push_str(&format!("\n> {translation}\n"));
+        }
+        if let Some(reading) = &line.translation_reading {
+            output.push_str(&format!("> *{reading}*\n"));
+        }
+    }
+    output
+}
+
+fn write_export(path: &Path, content: &str) -> Result<String, String> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    fs::write(path, content).map_err(|err| err.to_string())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reading_script, render_vtt, study_lines, Script};
+    use crate::audio::manager::SessionInfo;
+    use crate::audio::SegmentInfo;
+    use std::path::Path;
+
+    #[test]
+    fn interleaves_lines_with_offsets_readings_and_clips() {
+        let session: SessionInfo = serde_json::from_value(serde_json::json!({
+            "id": "session_1",
+            "started_at": "2025-01-20T10:00:00.000+09:00",
+        }))
+        .unwrap();
+        let segments = vec![
+            SegmentInfo {
+                name: "seg_1.wav".to_string(),
+                created_at: "2025-01-20T10:00:05.250+09:00".to_string(),
+                duration_ms: 3_000,
+                transcript: Some("会議を始めます".to_string()),
+                transcript_language: Some("auto".to_string()),
+                translation: Some("Let's start the meeting".to_string()),
+                translation_language: Some("en".to_string()),
+                ..SegmentInfo::default()
+            },
+            SegmentInfo {
+                name: "seg_2.wav".to_string(),
+                created_at: "2025-01-20T10:01:10.000+09:00".to_string(),
+                duration_ms: 2_000,
+                transcript: Some("  ".to_string()),
+                ..SegmentInfo::default()
+            },
+            // The timeline offset wins over `created_at`.
+            SegmentInfo {
+                name: "seg_3.wav".to_string(),
+                created_at: "2025-01-20T10:01:20.000+09:00".to_string(),
+                duration_ms: 1_800,
+                transcript: Some("次の議題".to_string()),
+                start_offset_ms: Some(80_100),
+                end_offset_ms: Some(81_900),
+                ..SegmentInfo::default()
+            },
+        ];
+        let mut lines = study_lines(&session, &segments, Path::new("segments"));
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].start_ms, lines[0].end_ms), (5_250, 8_250));
+        assert_eq!((lines[1].start_ms, lines[1].end_ms), (80_100, 81_900));
+        assert_eq!(
+            reading_script(&lines[0].source, lines[0].source_language.as_deref()),
+            Some(Script::Furigana)
+        );
+        assert_eq!(reading_script("开始开会", None), Some(Script::Pinyin));
+        assert_eq!(reading_script("はじめます", Some("ja")), None);
+
+        lines[0].source_reading = Some("会議(かいぎ)を始(はじ)めます".to_string());
+        let vtt = render_vtt(&lines[..1]);
+        let clip = Path::new("segments").join("seg_1.wav");
+        assert_eq!(
+            vtt,
+            format!(
+                "WEBVTT\n\nNOTE clip: {}\n\n1\n00:00:05.250 --> 00:00:08.250\n会議を始めます\n\
+                 <i>会議(かいぎ)を始(はじ)めます</i>\nLet's start the meeting\n",
+                clip.display()
+            )
+        );
+    }
+}
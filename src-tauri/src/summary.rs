@@ -0,0 +1,311 @@
+use crate::app_config::{load_config, AutoStopConfig, MeetingTemplateConfig};
+use crate::audio::manager::SessionInfo;
+use crate::audio::CaptureManager;
+use crate::defaults::DEFAULT_AUTO_STOP_SUMMARIZE;
+use crate::events::{
+    emit_ui_event, EventHub, ACTION_ITEMS_EXTRACTED, SESSION_ENDED, SUMMARY_READY,
+};
+use crate::templates::find_template;
+use crate::timestamps::now_rfc3339;
+use crate::{generate_with_selected_provider, normalize_translate_provider};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+const SUMMARIES_DIR: &str = "summaries";
+const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+const SUMMARY_PROMPT: &str = "You summarize meeting transcripts.\n\
+Return ONLY a JSON object: {\"summary\": string, \"action_items\": [string]}.\n\
+`summary` is a short paragraph covering decisions and open questions.\n\
+Each action item names the owner when the transcript makes it clear.\n\
+Write in the language of the transcript.\n";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDigest {
+    pub session_id: String,
+    pub title: Option<String>,
+    pub started_at: Option<String>,
+    pub summary: String,
+    pub action_items: Vec<String>,
+    pub provider: String,
+    pub generated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryResponse {
+    summary: String,
+    #[serde(default)]
+    action_items: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn summarize_session(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    session_id: String,
+    provider: Option<String>,
+) -> Result<SessionDigest, String> {
+    let session = state
+        .list_sessions(app.clone())?
+        .into_iter()
+        .find(|item| item.session.id == session_id)
+        .ok_or_else(|| format!("session not found: {session_id}"))?
+        .session;
+    let transcript = state
+        .list_session_segments(app.clone(), &session_id)?
+        .into_iter()
+        .filter_map(|segment| {
+            let text = segment.transcript?.trim().to_string();
+            if text.is_empty() {
+                return None;
+            }
+            Some(match segment.speaker_id {
+                Some(speaker) => format!("[Speaker {speaker}] {text}"),
+                None => text,
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if transcript.is_empty() {
+        return Err("session has no transcript".to_string());
+    }
+    // Keep the tail: conclusions and action items cluster at the end of a meeting.
+    let skip = transcript
+        .chars()
+        .count()
+        .saturating_sub(MAX_TRANSCRIPT_CHARS);
+    let transcript = transcript.chars().skip(skip).collect::<String>();
+
+    let provider = provider
+        .map(|value| normalize_translate_provider(&value))
+        .unwrap_or_else(|| crate::selected_translate_provider(&app));
+    let template = session.template.as_deref().and_then(find_template);
+    let mut prompt = SUMMARY_PROMPT.to_string();
+    if let Some(structure) = template
+        .as_ref()
+        .and_then(|template| template.summary_prompt.as_deref())
+    {
+        prompt.push_str(structure.trim());
+        prompt.push('\n');
+    }
+    let action_items = template
+        .as_ref()
+        .and_then(|template| template.action_items)
+        .unwrap_or(true);
+    if !action_items {
+        prompt.push_str("Leave `action_items` empty.\n");
+    }
+    let config = load_config()?;
+    let raw = generate_with_selected_provider(
+        &app,
+        &provider,
+        &format!("{prompt}\nTranscript:\n{transcript}"),
+        &config,
+        "summary",
+    )
+    .await?;
+    let mut parsed = parse_summary_response(&raw);
+    if !action_items {
+        parsed.action_items.clear();
+    }
+
+    let digest = SessionDigest {
+        session_id: session.id,
+        title: session.title,
+        started_at: Some(session.started_at),
+        summary: parsed.summary,
+        action_items: parsed.action_items,
+        provider,
+        generated_at: now_rfc3339(),
+    };
+    save_digest(&app, &digest)?;
+    emit_ui_event(&app, SUMMARY_READY, digest.clone());
+    if !digest.action_items.is_empty() {
+        emit_ui_event(
+            &app,
+            ACTION_ITEMS_EXTRACTED,
+            serde_json::json!({
+                "session_id": digest.session_id,
+                "title": digest.title,
+                "action_items": digest.action_items,
+            }),
+        );
+    }
+    Ok(digest)
+}
+
+// Summarizes sessions whose template asks for it, and sessions ended by auto-stop.
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("summary dispatcher lagged, skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed || event.event != SESSION_ENDED {
+                continue;
+            }
+            let Ok(session) = serde_json::from_value::<SessionInfo>(event.payload.clone()) else {
+                continue;
+            };
+            let template = session.template.as_deref().and_then(find_template);
+            let auto_stop = load_config()
+                .ok()
+                .and_then(|config| config.auto_stop)
+                .unwrap_or_default();
+            if !should_auto_summarize(&session, template.as_ref(), &auto_stop) {
+                continue;
+            }
+            info!("[summary] summarizing {} after it ended", session.id);
+            let state = app.state::<CaptureManager>();
+            if let Err(err) = summarize_session(app.clone(), state, session.id.clone(), None).await
+            {
+                warn!(
+                    "[summary] automatic summary for {} failed: {err}",
+                    session.id
+                );
+            }
+        }
+    });
+}
+
+fn should_auto_summarize(
+    session: &SessionInfo,
+    template: Option<&MeetingTemplateConfig>,
+    auto_stop: &AutoStopConfig,
+) -> bool {
+    if template.and_then(|template| template.auto_summarize) == Some(true) {
+        return true;
+    }
+    session.end_reason.as_deref() == Some("silence")
+        && auto_stop.summarize.unwrap_or(DEFAULT_AUTO_STOP_SUMMARIZE)
+}
+
+#[tauri::command]
+pub fn get_session_summary(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<SessionDigest>, String> {
+    load_digest(&app, &session_id)
+}
+
+pub fn load_digest(app: &AppHandle, session_id: &str) -> Result<Option<SessionDigest>, String> {
+    let path = digest_path(app, session_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|err| err.to_string())
+}
+
+pub(crate) fn save_digest(app: &AppHandle, digest: &SessionDigest) -> Result<(), String> {
+    let path = digest_path(app, &digest.session_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(digest).map_err(|err| err.to_string())?;
+    fs::write(path, content).map_err(|err| err.to_string())
+}
+
+pub(crate) fn remove_digest(app: &AppHandle, session_id: &str) -> Result<(), String> {
+    let path = digest_path(app, session_id)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    fs::remove_file(path).map_err(|err| err.to_string())
+}
+
+fn digest_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(format!("invalid session id: {session_id}"));
+    }
+    let base = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    Ok(base.join(SUMMARIES_DIR).join(format!("{session_id}.json")))
+}
+
+// Models often wrap JSON in prose or code fences; fall back to the raw text as the summary.
+fn parse_summary_response(raw: &str) -> SummaryResponse {
+    let parsed = raw
+        .find('{')
+        .zip(raw.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<SummaryResponse>(&raw[start..=end]).ok());
+    let mut response = parsed.unwrap_or_else(|| SummaryResponse {
+        summary: raw.trim().to_string(),
+        action_items: Vec::new(),
+    });
+    response.summary = response.summary.trim().to_string();
+    response.action_items = response
+        .action_items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_summary_response, should_auto_summarize};
+    use crate::app_config::{AutoStopConfig, MeetingTemplateConfig};
+    use crate::audio::manager::SessionInfo;
+
+    #[test]
+    fn parses_fenced_json_and_falls_back_to_text() {
+        let parsed = parse_summary_response(
+            "```json\n{\"summary\": \"Agreed on Q3 scope.\", \"action_items\": [\"Ana: draft plan\", \" \"]}\n```",
+        );
+        assert_eq!(parsed.summary, "Agreed on Q3 scope.");
+        assert_eq!(parsed.action_items, vec!["Ana: draft plan".to_string()]);
+
+        let fallback = parse_summary_response("Plain summary.");
+        assert_eq!(fallback.summary, "Plain summary.");
+        assert!(fallback.action_items.is_empty());
+    }
+
+    #[test]
+    fn summarizes_template_sessions_and_auto_stopped_sessions() {
+        let mut session: SessionInfo = serde_json::from_value(serde_json::json!({
+            "id": "session_20250120_100000_000",
+            "started_at": "2025-01-20T10:00:00.000+09:00",
+            "ended_at": "2025-01-20T11:00:00.000+09:00",
+        }))
+        .unwrap();
+        let auto_stop = AutoStopConfig::default();
+        assert!(!should_auto_summarize(&session, None, &auto_stop));
+
+        let template = MeetingTemplateConfig {
+            id: "standup".to_string(),
+            auto_summarize: Some(true),
+            ..MeetingTemplateConfig::default()
+        };
+        assert!(should_auto_summarize(&session, Some(&template), &auto_stop));
+
+        session.end_reason = Some("silence".to_string());
+        assert!(should_auto_summarize(&session, None, &auto_stop));
+        let no_summary = AutoStopConfig {
+            summarize: Some(false),
+            ..AutoStopConfig::default()
+        };
+        assert!(!should_auto_summarize(&session, None, &no_summary));
+    }
+}
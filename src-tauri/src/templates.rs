@@ -0,0 +1,185 @@
+use crate::app_config::{load_file_config, AppConfig, MeetingTemplateConfig};
+use crate::audio::CaptureManager;
+use crate::events::{EventHub, SESSION_ENDED};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+// The template of the running session; its settings are layered over the loaded config.
+static ACTIVE: Lazy<RwLock<Option<MeetingTemplateConfig>>> = Lazy::new(|| RwLock::new(None));
+
+fn builtin_templates() -> Vec<MeetingTemplateConfig> {
+    vec![
+        MeetingTemplateConfig {
+            id: "standup".to_string(),
+            name: Some("每日站会".to_string()),
+            summary_prompt: Some(
+                "Structure the summary per person: done yesterday, planned today, blockers."
+                    .to_string(),
+            ),
+            action_items: Some(true),
+            auto_summarize: Some(true),
+            ..MeetingTemplateConfig::default()
+        },
+        MeetingTemplateConfig {
+            id: "customer-call".to_string(),
+            name: Some("客户沟通".to_string()),
+            summary_prompt: Some(
+                "Structure the summary as: customer needs, commitments we made, risks, next steps."
+                    .to_string(),
+            ),
+            action_items: Some(true),
+            auto_summarize: Some(true),
+            ..MeetingTemplateConfig::default()
+        },
+        MeetingTemplateConfig {
+            id: "interview".to_string(),
+            name: Some("面试".to_string()),
+            summary_prompt: Some(
+                "Structure the summary as: candidate background, strengths, concerns, \
+                 open questions for the next round."
+                    .to_string(),
+            ),
+            action_items: Some(false),
+            auto_summarize: Some(false),
+            ..MeetingTemplateConfig::default()
+        },
+    ]
+}
+
+// Built-in templates followed by `templates` from the config; a configured id replaces the
+// built-in one.
+pub fn all_templates(config: Option<&AppConfig>) -> Vec<MeetingTemplateConfig> {
+    let configured = config
+        .and_then(|config| config.templates.clone())
+        .unwrap_or_default();
+    let mut templates = builtin_templates()
+        .into_iter()
+        .filter(|builtin| !configured.iter().any(|item| item.id == builtin.id))
+        .collect::<Vec<_>>();
+    templates.extend(configured);
+    templates
+}
+
+pub fn find_template(id: &str) -> Option<MeetingTemplateConfig> {
+    let config = load_file_config().ok();
+    all_templates(config.as_ref())
+        .into_iter()
+        .find(|template| template.id == id.trim())
+}
+
+pub fn active_template() -> Option<MeetingTemplateConfig> {
+    ACTIVE.read().ok().and_then(|guard| guard.clone())
+}
+
+fn set_active(template: Option<MeetingTemplateConfig>) {
+    if let Ok(mut guard) = ACTIVE.write() {
+        *guard = template;
+    }
+}
+
+// Keeps a mid-session language switch from being masked by the template's target.
+pub fn override_target_language(language: &str) {
+    if let Ok(mut guard) = ACTIVE.write() {
+        if let Some(template) = guard.as_mut() {
+            template.target_language = Some(language.to_string());
+        }
+    }
+}
+
+// Called from `load_config`, so every pipeline stage sees the template without knowing about it.
+pub fn apply_active(config: &mut AppConfig) {
+    let Some(template) = active_template() else {
+        return;
+    };
+    let mut translate = config.translate.clone().unwrap_or_default();
+    if let Some(value) = template.target_language {
+        translate.target_language = Some(value);
+    }
+    if let Some(value) = template.live_prompt {
+        translate.live_prompt = Some(value);
+    }
+    if let Some(value) = template.segment_batch_prompt {
+        translate.segment_batch_prompt = Some(value);
+    }
+    config.translate = Some(translate);
+}
+
+#[tauri::command]
+pub fn list_meeting_templates() -> Vec<MeetingTemplateConfig> {
+    let config = load_file_config().ok();
+    all_templates(config.as_ref())
+}
+
+#[tauri::command]
+pub async fn start_session_with_template(
+    app: AppHandle,
+    state: State<'_, CaptureManager>,
+    template_id: String,
+) -> Result<(), String> {
+    let template = find_template(&template_id)
+        .ok_or_else(|| format!("unknown meeting template: {template_id}"))?;
+    info!("[template] starting session with {}", template.id);
+    set_active(Some(template));
+    let result = state.start(app);
+    if result.is_err() {
+        set_active(None);
+    }
+    result
+}
+
+// Clears the template when its session ends; `summary::start` runs its end-of-meeting actions.
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("template dispatcher lagged, skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed || event.event != SESSION_ENDED {
+                continue;
+            }
+            // A session started with another template in the meantime keeps it.
+            let template = event.payload.get("template").and_then(|v| v.as_str());
+            if active_template().is_some_and(|active| Some(active.id.as_str()) == template) {
+                set_active(None);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::all_templates;
+    use crate::app_config::AppConfig;
+
+    #[test]
+    fn configured_templates_replace_builtins_with_the_same_id() {
+        let config: AppConfig = serde_json::from_value(serde_json::json!({
+            "openai": { "apiKey": "" },
+            "templates": [
+                { "id": "interview", "name": "Tech interview", "targetLanguage": "en" },
+                { "id": "retro", "name": "Retro", "autoSummarize": true },
+            ],
+        }))
+        .unwrap();
+        let templates = all_templates(Some(&config));
+        let ids = templates
+            .iter()
+            .map(|template| template.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["standup", "customer-call", "interview", "retro"]);
+        assert_eq!(templates[2].target_language.as_deref(), Some("en"));
+        assert_eq!(templates[2].summary_prompt, None);
+    }
+}
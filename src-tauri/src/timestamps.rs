@@ -0,0 +1,231 @@
+use crate::app_config::{load_config, TimeConfig};
+use crate::defaults::{DEFAULT_TIME_LOCALE, DEFAULT_TIME_RELATIVE, DEFAULT_TIME_ZONE};
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat, Utc};
+
+// Stored timestamps are RFC3339 with millisecond precision and an explicit offset, so a session
+// copied to a machine in another timezone still converts to the right instant.
+pub fn rfc3339(at: &DateTime<Local>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, false)
+}
+
+pub fn now_rfc3339() -> String {
+    rfc3339(&Local::now())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Local,
+    Utc,
+    // The offset the timestamp was recorded with.
+    Original,
+    Fixed(FixedOffset),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Locale {
+    Zh,
+    Ja,
+    En,
+}
+
+impl Locale {
+    fn parse(raw: &str) -> Self {
+        let lower = raw.trim().to_lowercase();
+        if lower.starts_with("ja") {
+            Locale::Ja
+        } else if lower.starts_with("en") {
+            Locale::En
+        } else {
+            Locale::Zh
+        }
+    }
+
+    fn pattern(self) -> &'static str {
+        match self {
+            Locale::Zh => "%Y-%m-%d %H:%M",
+            Locale::Ja => "%Y/%m/%d %H:%M",
+            Locale::En => "%b %-d, %Y %H:%M",
+        }
+    }
+
+    // Relative wording for up to a week back; older or future times fall back to absolute dates.
+    fn relative(self, seconds: i64) -> Option<String> {
+        const MINUTE: i64 = 60;
+        const HOUR: i64 = 60 * MINUTE;
+        const DAY: i64 = 24 * HOUR;
+        let text = match seconds {
+            ..=-1 => return None,
+            0..MINUTE => match self {
+                Locale::Zh => "刚刚".to_string(),
+                Locale::Ja => "たった今".to_string(),
+                Locale::En => "just now".to_string(),
+            },
+            MINUTE..HOUR => {
+                let minutes = seconds / MINUTE;
+                match self {
+                    Locale::Zh => format!("{minutes} 分钟前"),
+                    Locale::Ja => format!("{minutes}分前"),
+                    Locale::En => format!("{minutes}m ago"),
+                }
+            }
+            HOUR..DAY => {
+                let hours = seconds / HOUR;
+                match self {
+                    Locale::Zh => format!("{hours} 小时前"),
+                    Locale::Ja => format!("{hours}時間前"),
+                    Locale::En => format!("{hours}h ago"),
+                }
+            }
+            _ if seconds < 7 * DAY => {
+                let days = seconds / DAY;
+                match self {
+                    Locale::Zh => format!("{days} 天前"),
+                    Locale::Ja => format!("{days}日前"),
+                    Locale::En => format!("{days}d ago"),
+                }
+            }
+            _ => return None,
+        };
+        Some(text)
+    }
+}
+
+// `None` for values that are neither a known zone name nor a `+09:00` style offset.
+fn parse_zone(raw: &str) -> Option<Zone> {
+    match raw.trim().to_lowercase().as_str() {
+        "" | "local" => Some(Zone::Local),
+        "utc" | "z" => Some(Zone::Utc),
+        "original" | "recorded" => Some(Zone::Original),
+        other => other.parse::<FixedOffset>().ok().map(Zone::Fixed),
+    }
+}
+
+pub fn is_valid_timezone(raw: &str) -> bool {
+    parse_zone(raw).is_some()
+}
+
+// How timestamps are rendered for people, in the UI and in exports.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeDisplay {
+    zone: Zone,
+    locale: Locale,
+    relative: bool,
+}
+
+impl TimeDisplay {
+    pub fn from_config() -> Self {
+        let config = load_config()
+            .ok()
+            .and_then(|config| config.time)
+            .unwrap_or_default();
+        Self::from_settings(&config)
+    }
+
+    pub(crate) fn from_settings(config: &TimeConfig) -> Self {
+        Self {
+            zone: config
+                .timezone
+                .as_deref()
+                .and_then(parse_zone)
+                .or_else(|| parse_zone(DEFAULT_TIME_ZONE))
+                .unwrap_or(Zone::Local),
+            locale: Locale::parse(config.locale.as_deref().unwrap_or(DEFAULT_TIME_LOCALE)),
+            relative: config.relative.unwrap_or(DEFAULT_TIME_RELATIVE),
+        }
+    }
+
+    // Exports are read later, so they always carry the full date.
+    pub fn absolute(self) -> Self {
+        Self {
+            relative: false,
+            ..self
+        }
+    }
+
+    pub fn with_relative(self, relative: bool) -> Self {
+        Self { relative, ..self }
+    }
+
+    // Unparseable input is returned unchanged.
+    pub fn format(&self, raw: &str) -> String {
+        self.format_at(raw, Utc::now())
+    }
+
+    fn format_at(&self, raw: &str, now: DateTime<Utc>) -> String {
+        let Ok(at) = DateTime::parse_from_rfc3339(raw.trim()) else {
+            return raw.to_string();
+        };
+        if self.relative {
+            let seconds = (now - at.with_timezone(&Utc)).num_seconds();
+            if let Some(text) = self.locale.relative(seconds) {
+                return text;
+            }
+        }
+        let pattern = self.locale.pattern();
+        match self.zone {
+            Zone::Local => at.with_timezone(&Local).format(pattern).to_string(),
+            Zone::Utc => format!("{} UTC", at.with_timezone(&Utc).format(pattern)),
+            Zone::Original => at.format(pattern).to_string(),
+            Zone::Fixed(offset) => at.with_timezone(&offset).format(pattern).to_string(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn format_timestamps(values: Vec<String>, relative: Option<bool>) -> Vec<String> {
+    let mut display = TimeDisplay::from_config();
+    if let Some(relative) = relative {
+        display = display.with_relative(relative);
+    }
+    values.iter().map(|value| display.format(value)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeDisplay;
+    use crate::app_config::TimeConfig;
+    use chrono::{DateTime, Utc};
+
+    fn display(timezone: &str, locale: &str, relative: bool) -> TimeDisplay {
+        TimeDisplay::from_settings(&TimeConfig {
+            timezone: Some(timezone.to_string()),
+            locale: Some(locale.to_string()),
+            relative: Some(relative),
+        })
+    }
+
+    #[test]
+    fn renders_in_the_configured_zone_locale_and_relative_mode() {
+        let recorded = "2025-01-20T10:00:00.000+01:00";
+        let now = DateTime::parse_from_rfc3339("2025-01-20T09:05:30Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            display("+09:00", "zh", false).format_at(recorded, now),
+            "2025-01-20 18:00"
+        );
+        assert_eq!(
+            display("original", "ja", false).format_at(recorded, now),
+            "2025/01/20 10:00"
+        );
+        assert_eq!(
+            display("utc", "en-US", false).format_at(recorded, now),
+            "Jan 20, 2025 09:00 UTC"
+        );
+        assert_eq!(
+            display("utc", "en", true).format_at(recorded, now),
+            "5m ago"
+        );
+        assert_eq!(
+            display("utc", "zh", true)
+                .absolute()
+                .format_at(recorded, now),
+            "2025-01-20 09:00 UTC"
+        );
+        assert_eq!(
+            display("local", "zh", false).format_at("yesterday", now),
+            "yesterday"
+        );
+    }
+}
@@ -1,62 +1,262 @@
 use crate::app_config::{load_config, AsrConfig, OpenAiConfig};
 use crate::asr::AsrState;
+use crate::deepgram::transcribe_with_deepgram;
+use crate::defaults::{
+    DEFAULT_ASR_TRANSLATE_TO_ENGLISH, DEFAULT_OPENAI_RESPONSE_FORMAT,
+    DEFAULT_OPENAI_TRANSCRIBE_BASE_URL, DEFAULT_OPENAI_TRANSCRIBE_MODEL,
+    DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT, DEFAULT_OPENAI_TRANSLATION_MODEL,
+    DEFAULT_WHISPER_SERVER_RESPONSE_FORMAT, DEFAULT_WHISPER_SERVER_TEMPERATURE,
+    DEFAULT_WHISPER_SERVER_URL,
+};
+use crate::http;
+use crate::offline_asr;
+use crate::redact::redact;
+use crate::whisper_inproc::WhisperInprocManager;
+use crate::whisper_pipe::WhisperPipeManager;
 use crate::whisper_server::WhisperServerManager;
 use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
+use tracing::warn;
 
-const DEFAULT_MODEL: &str = "whisper-1";
-const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
-const DEFAULT_TIMEOUT_SECS: u64 = 300;
-const DEFAULT_RESPONSE_FORMAT: &str = "json";
-const DEFAULT_WHISPER_SERVER_URL: &str = "http://127.0.0.1:8080/inference";
-const DEFAULT_WHISPER_SERVER_RESPONSE_FORMAT: &str = "text";
-const DEFAULT_WHISPER_SERVER_TEMPERATURE: &str = "0";
+// What a provider returned for one segment; only some providers time their words.
+#[derive(Debug, Clone, Default)]
+pub struct Transcription {
+    pub text: String,
+    pub words: Option<Vec<TranscriptWord>>,
+    // The provider translated the speech to English (`asr.translateToEnglish`).
+    pub english: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub word: String,
+    // Relative to the start of the segment.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    // From the provider's diarization, numbered from 1 within the segment.
+    pub speaker: Option<u32>,
+}
+
+impl Transcription {
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            words: None,
+            english: false,
+        }
+    }
+
+    fn translated(text: String, english: bool) -> Self {
+        Self {
+            english,
+            ..Self::plain(text)
+        }
+    }
+
+    // The speaker who talks longest in the segment.
+    pub fn speaker(&self) -> Option<u32> {
+        let mut talked: Vec<(u32, u64)> = Vec::new();
+        for word in self.words.iter().flatten() {
+            let Some(speaker) = word.speaker else {
+                continue;
+            };
+            let ms = word.end_ms.saturating_sub(word.start_ms);
+            match talked.iter_mut().find(|(known, _)| *known == speaker) {
+                Some((_, total)) => *total += ms,
+                None => talked.push((speaker, ms)),
+            }
+        }
+        talked
+            .into_iter()
+            .max_by_key(|(_, total)| *total)
+            .map(|(speaker, _)| speaker)
+    }
+
+    // Where the speaker changes, like the local switch detection's `speaker_switches_ms`.
+    pub fn speaker_switches_ms(&self) -> Vec<u64> {
+        let mut switches = Vec::new();
+        let mut previous = None;
+        for word in self.words.iter().flatten() {
+            let Some(speaker) = word.speaker else {
+                continue;
+            };
+            if previous.is_some_and(|previous| previous != speaker) {
+                switches.push(word.start_ms);
+            }
+            previous = Some(speaker);
+        }
+        switches
+    }
+}
 
 pub async fn transcribe_file(
     app: &AppHandle,
     path: &Path,
     whisper_prompt_hint: Option<&str>,
 ) -> Result<String, String> {
+    transcribe_detailed(app, path, whisper_prompt_hint, None, None, None)
+        .await
+        .map(|transcription| transcription.text)
+}
+
+// Like `transcribe_file`, keeping word timings from providers that return them. The provider,
+// model and language may be picked by the caller instead of the ASR settings; a chosen provider
+// never falls back to OpenAI. A local model other than the configured one runs on a one-off
+// whisper-pipe worker, since the server and the pool keep theirs loaded.
+pub async fn transcribe_detailed(
+    app: &AppHandle,
+    path: &Path,
+    whisper_prompt_hint: Option<&str>,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    language_override: Option<&str>,
+) -> Result<Transcription, String> {
     let config = load_config()?;
     let mut openai = config.openai.clone();
     let mut asr_config = config.asr.unwrap_or_default();
     let asr_state = app.state::<AsrState>();
-    let provider = asr_state.provider();
-    let fallback = asr_state.fallback_to_openai();
-    let language_override = asr_state.language();
-    if !language_override.trim().is_empty() {
-        asr_config.language = Some(language_override.clone());
-        openai.language = Some(language_override);
+    let provider = provider_override
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| asr_state.provider());
+    let fallback = provider_override.is_none() && asr_state.fallback_to_openai();
+    let language = language_override
+        .map(str::to_string)
+        .unwrap_or_else(|| asr_state.language());
+    if !language.trim().is_empty() {
+        asr_config.language = Some(language.clone());
+        openai.language = Some(language);
     }
+    let model_override = model_override
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if let Some(model) = model_override {
+        if provider == "openai" {
+            openai.model = Some(model.to_string());
+        } else if provider == "deepgram" {
+            asr_config.deepgram_model = Some(model.to_string());
+        } else {
+            asr_config.whisper_cpp_model_path = Some(model.to_string());
+        }
+    }
+    let dedicated = model_override.is_some();
+    let english = asr_config
+        .translate_to_english
+        .unwrap_or(DEFAULT_ASR_TRANSLATE_TO_ENGLISH);
+    let translated = |text| Transcription::translated(text, english);
 
     match provider.as_str() {
+        "whisperserver" if dedicated => {
+            return transcribe_with_whisper_pipe(app, path, &asr_config, whisper_prompt_hint, true)
+                .await
+                .map(Transcription::plain);
+        }
         "whisperserver" => {
-            let server_result =
-                transcribe_with_whisper_server(app, path, &asr_config, whisper_prompt_hint).await;
+            // Without OpenAI to fall back on, a server that cannot start hands over to the
+            // offline model.
+            let offline = !fallback || openai.api_key.trim().is_empty();
+            let url = if offline && offline_asr::retry_pending() {
+                Err("whisper-server failed to start recently".to_string())
+            } else {
+                whisper_server_url(app, &asr_config)
+            };
+            let server_result = match url {
+                Ok(url) => {
+                    post_to_whisper_server(&url, path, &asr_config, whisper_prompt_hint).await
+                }
+                Err(err) if offline => {
+                    return offline_asr::transcribe_offline(
+                        app,
+                        path,
+                        &asr_config,
+                        whisper_prompt_hint,
+                        err,
+                    )
+                    .await
+                    .map(translated);
+                }
+                Err(err) => Err(err),
+            };
             match server_result {
-                Ok(text) => return Ok(text),
+                Ok(text) => {
+                    offline_asr::server_recovered();
+                    return Ok(translated(text));
+                }
+                Err(err) => {
+                    if fallback {
+                        warn!("whisper-server failed, fallback to OpenAI: {err}");
+                    } else {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        "whisperpipe" => {
+            let pipe_result = transcribe_with_whisper_pipe(
+                app,
+                path,
+                &asr_config,
+                whisper_prompt_hint,
+                dedicated,
+            )
+            .await;
+            match pipe_result {
+                Ok(text) => return Ok(Transcription::plain(text)),
                 Err(err) => {
                     if fallback {
-                        eprintln!("whisper-server failed, fallback to OpenAI: {err}");
+                        warn!("whisper-pipe failed, fallback to OpenAI: {err}");
                     } else {
                         return Err(err);
                     }
                 }
             }
         }
+        "whisperinproc" => {
+            let inproc_result = transcribe_with_whisper_inproc(
+                app,
+                path,
+                &asr_config,
+                whisper_prompt_hint,
+                dedicated,
+            )
+            .await;
+            match inproc_result {
+                Ok(text) => return Ok(translated(text)),
+                Err(err) => {
+                    if fallback {
+                        warn!("whisper-inproc failed, fallback to OpenAI: {err}");
+                    } else {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        "deepgram" => match transcribe_with_deepgram(path, &asr_config).await {
+            Ok(transcription) => return Ok(transcription),
+            Err(err) => {
+                if fallback {
+                    warn!("deepgram failed, fallback to OpenAI: {err}");
+                } else {
+                    return Err(err);
+                }
+            }
+        },
         "openai" => {}
         other => {
             if fallback {
-                eprintln!("unknown ASR provider {other}, fallback to OpenAI");
+                warn!("unknown ASR provider {other}, fallback to OpenAI");
             } else {
                 return Err(format!("unsupported ASR provider: {other}"));
             }
         }
     }
 
-    transcribe_with_openai(path, &openai).await
+    transcribe_with_openai(path, &openai, english)
+        .await
+        .map(translated)
 }
 
 pub async fn transcribe_with_whisper_server(
@@ -65,22 +265,35 @@ pub async fn transcribe_with_whisper_server(
     config: &AsrConfig,
     prompt_hint: Option<&str>,
 ) -> Result<String, String> {
+    let url = whisper_server_url(app, config)?;
+    post_to_whisper_server(&url, path, config, prompt_hint).await
+}
+
+// The configured URL, or the managed server's, starting it if needed.
+fn whisper_server_url(app: &AppHandle, config: &AsrConfig) -> Result<String, String> {
     let manual_url = config
         .whisper_server_url
         .clone()
         .filter(|value| !value.trim().is_empty())
         .filter(|value| value.trim() != DEFAULT_WHISPER_SERVER_URL);
-    let url = if let Some(url) = manual_url {
-        url
-    } else {
-        let manager = app
-            .try_state::<WhisperServerManager>()
-            .ok_or_else(|| "whisper-server manager not available".to_string())?;
-        manager.ensure_started(app, config)?
-    };
+    if let Some(url) = manual_url {
+        return Ok(url);
+    }
+    let manager = app
+        .try_state::<WhisperServerManager>()
+        .ok_or_else(|| "whisper-server manager not available".to_string())?;
+    manager.ensure_started(app, config)
+}
+
+pub(crate) async fn post_to_whisper_server(
+    url: &str,
+    path: &Path,
+    config: &AsrConfig,
+    prompt_hint: Option<&str>,
+) -> Result<String, String> {
     let timeout_secs = config
         .whisper_server_timeout_secs
-        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        .unwrap_or(DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT);
 
     let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
     let file_name = path
@@ -110,20 +323,17 @@ pub async fn transcribe_with_whisper_server(
     {
         form = form.text("language", language);
     }
-    if let Some(prompt) = prompt_hint
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-    {
+    if config.translate_to_english == Some(true) {
+        form = form.text("translate", "true");
+    }
+    if let Some(prompt) = prompt_hint.map(str::trim).filter(|value| !value.is_empty()) {
         // Context is passed as a soft hint, not an instruction that forces correction.
         form = form
             .text("prompt", prompt.to_string())
             .text("initial_prompt", prompt.to_string());
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
+    let client = http::client(Some(timeout_secs))?;
 
     let response = client
         .post(url)
@@ -135,7 +345,7 @@ pub async fn transcribe_with_whisper_server(
     let status = response.status();
     let text = response.text().await.map_err(|err| err.to_string())?;
     if !status.is_success() {
-        return Err(text);
+        return Err(redact(&text).into_owned());
     }
     let trimmed = text.trim();
     if trimmed.is_empty() {
@@ -144,7 +354,92 @@ pub async fn transcribe_with_whisper_server(
     Ok(trimmed.to_string())
 }
 
-async fn transcribe_with_openai(path: &Path, openai: &OpenAiConfig) -> Result<String, String> {
+pub async fn transcribe_with_whisper_pipe(
+    app: &AppHandle,
+    path: &Path,
+    config: &AsrConfig,
+    prompt_hint: Option<&str>,
+    dedicated: bool,
+) -> Result<String, String> {
+    let timeout = Duration::from_secs(
+        config
+            .whisper_server_timeout_secs
+            .unwrap_or(DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT),
+    );
+    let checkout_app = app.clone();
+    let checkout_config = config.clone();
+    let worker = tauri::async_runtime::spawn_blocking(move || {
+        let manager = checkout_app
+            .try_state::<WhisperPipeManager>()
+            .ok_or_else(|| "whisper-pipe manager not available".to_string())?;
+        if dedicated {
+            manager.checkout_dedicated(&checkout_app, &checkout_config)
+        } else {
+            manager.checkout(&checkout_app, &checkout_config, timeout)
+        }
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+
+    let worker_id = worker.id();
+    let run_app = app.clone();
+    let path = path.to_path_buf();
+    let language = config
+        .language
+        .clone()
+        .filter(|value| !value.trim().is_empty());
+    let prompt = prompt_hint
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    let task = tauri::async_runtime::spawn_blocking(move || {
+        let manager = run_app.state::<WhisperPipeManager>();
+        manager.transcribe(worker, &path, language.as_deref(), prompt.as_deref())
+    });
+    match tokio::time::timeout(timeout, task).await {
+        Ok(joined) => joined.map_err(|err| err.to_string())?,
+        Err(_) => {
+            // Killing the worker unblocks the pending read; the pool respawns on demand.
+            if let Some(manager) = app.try_state::<WhisperPipeManager>() {
+                manager.kill(worker_id);
+            }
+            Err("whisper-pipe request timed out".to_string())
+        }
+    }
+}
+
+// A running whisper.cpp call cannot be interrupted, so no timeout applies here.
+pub async fn transcribe_with_whisper_inproc(
+    app: &AppHandle,
+    path: &Path,
+    config: &AsrConfig,
+    prompt_hint: Option<&str>,
+    dedicated: bool,
+) -> Result<String, String> {
+    let app = app.clone();
+    let path = path.to_path_buf();
+    let config = config.clone();
+    let prompt = prompt_hint
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app
+            .try_state::<WhisperInprocManager>()
+            .ok_or_else(|| "whisper-inproc manager not available".to_string())?;
+        manager.transcribe(&app, &config, &path, prompt.as_deref(), dedicated)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+// With `english` the speech goes to `/audio/translations`, which only takes whisper models and
+// always answers in English.
+async fn transcribe_with_openai(
+    path: &Path,
+    openai: &OpenAiConfig,
+    english: bool,
+) -> Result<String, String> {
     let api_key = openai.api_key.trim();
     if api_key.is_empty() {
         return Err("OpenAI apiKey is required".to_string());
@@ -154,19 +449,29 @@ async fn transcribe_with_openai(path: &Path, openai: &OpenAiConfig) -> Result<St
         .model
         .clone()
         .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        .unwrap_or_else(|| DEFAULT_OPENAI_TRANSCRIBE_MODEL.to_string());
+    let model = if english && !model.starts_with("whisper") {
+        DEFAULT_OPENAI_TRANSLATION_MODEL.to_string()
+    } else {
+        model
+    };
     let base_url = openai
         .base_url
         .clone()
         .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
-    let url = normalize_transcriptions_url(&base_url);
-    let timeout_secs = openai.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        .unwrap_or_else(|| DEFAULT_OPENAI_TRANSCRIBE_BASE_URL.to_string());
+    let mut url = normalize_transcriptions_url(&base_url);
+    if english {
+        url = translations_url(&url);
+    }
+    let timeout_secs = openai
+        .timeout_secs
+        .unwrap_or(DEFAULT_OPENAI_TRANSCRIBE_TIMEOUT);
     let response_format = openai
         .response_format
         .clone()
         .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_RESPONSE_FORMAT.to_string());
+        .unwrap_or_else(|| DEFAULT_OPENAI_RESPONSE_FORMAT.to_string());
 
     let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
     let file_name = path
@@ -186,15 +491,12 @@ async fn transcribe_with_openai(path: &Path, openai: &OpenAiConfig) -> Result<St
     if let Some(language) = openai
         .language
         .clone()
-        .filter(|value| !value.trim().is_empty())
+        .filter(|value| !value.trim().is_empty() && !english)
     {
         form = form.text("language", language);
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
+    let client = http::client(Some(timeout_secs))?;
 
     let response = client
         .post(url)
@@ -208,14 +510,14 @@ async fn transcribe_with_openai(path: &Path, openai: &OpenAiConfig) -> Result<St
     if response_format == "text" {
         let text = response.text().await.map_err(|err| err.to_string())?;
         if !status.is_success() {
-            return Err(text);
+            return Err(redact(&text).into_owned());
         }
         return Ok(text.trim().to_string());
     }
 
     let value: serde_json::Value = response.json().await.map_err(|err| err.to_string())?;
     if !status.is_success() {
-        return Err(value.to_string());
+        return Err(redact(&value.to_string()).into_owned());
     }
     let text = value
         .get("text")
@@ -228,6 +530,14 @@ async fn transcribe_with_openai(path: &Path, openai: &OpenAiConfig) -> Result<St
     Ok(text.to_string())
 }
 
+// Same base as the transcriptions endpoint.
+fn translations_url(transcriptions_url: &str) -> String {
+    match transcriptions_url.strip_suffix("/audio/transcriptions") {
+        Some(base) => format!("{base}/audio/translations"),
+        None => transcriptions_url.to_string(),
+    }
+}
+
 fn normalize_transcriptions_url(raw: &str) -> String {
     let trimmed = raw.trim().trim_end_matches('/');
     if trimmed.ends_with("/audio/transcriptions") {
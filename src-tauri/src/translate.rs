@@ -1,28 +1,18 @@
-use crate::app_config::{load_config, AppConfig, LocalGptConfig, TranslateConfig};
-use reqwest::Client;
+use crate::app_config::{load_config, load_file_config, save_config, AppConfig};
+use crate::defaults::{
+    DEFAULT_SEGMENT_BATCH_PROMPT, DEFAULT_SEGMENT_SINGLE_PROMPT, DEFAULT_TRANSLATE_PROVIDER,
+    DEFAULT_TRANSLATE_TARGET_LANGUAGE,
+};
+use crate::llm::{metered, provider_from_config, ChunkSink, LlmPrompt, LlmProvider};
+use crate::translation_cache::{self, CacheKey, CachedTranslation};
+use crate::usage::UsageState;
+use futures_util::future::BoxFuture;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
 use serde_json::json;
 use std::collections::HashMap;
-use std::time::Duration;
-
-const DEFAULT_OPENAI_CHAT_MODEL: &str = "gpt-4.1-mini";
-const DEFAULT_OPENAI_CHAT_BASE_URL: &str = "https://api.openai.com/v1/responses";
-const DEFAULT_OPENAI_CHAT_TIMEOUT: u64 = 120;
-const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
-const DEFAULT_OLLAMA_TIMEOUT: u64 = 600;
-const DEFAULT_LOCAL_GPT_BASE_URL: &str = "http://127.0.0.1:8787";
-const DEFAULT_LOCAL_GPT_TIMEOUT: u64 = 240;
-const DEFAULT_LOCAL_GPT_DIRECT_PATH: &str = "/local-gpt-sse/direct";
-const DEFAULT_LOCAL_GPT_PROJECT_ID: &str = "g-p-698c11cf2bc08191b07e28128883fcbb-testapi";
-const DEFAULT_SEGMENT_SINGLE_PROMPT: &str =
-    "Translate the following text to {target_language}. Output only the translated text.";
-const DEFAULT_SEGMENT_BATCH_PROMPT: &str = "You rewrite noisy ASR text and translate it.\n\
-For each item in `items`:\n\
-1) rewrite `text` into readable text in the same language as input and return as `cleaned_source`;\n\
-2) translate `cleaned_source` to {target_language} and return as `translation`.\n\
-Use `context` only as previous conversation context.\n\
-Return ONLY JSON array.\n\
-Each element must be {\"id\": string, \"cleaned_source\": string, \"translation\": string}.\n\
-Return exactly one element for every id in `items`.";
+use tauri::AppHandle;
+use tracing::info;
 
 #[derive(Debug, Clone)]
 pub struct BatchTranslationItem {
@@ -39,12 +29,18 @@ pub struct BatchTranslationResult {
 #[derive(Debug, Clone, Default)]
 pub struct BatchTranslationOptions {
     pub context_items: Vec<BatchTranslationItem>,
+    // Pins the target, so the caller knows which language the batch was translated into.
+    pub target_language: Option<String>,
+    // Translates item by item with the single prompt instead of the batch prompt, which also
+    // cleans up the transcript; faster but without `cleaned_source`.
+    pub skip_cleanup: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum TranslateSource {
     Segment,
     Live,
+    RagAnswer,
 }
 
 impl TranslateSource {
@@ -52,6 +48,16 @@ impl TranslateSource {
         match self {
             Self::Segment => "segment",
             Self::Live => "live",
+            Self::RagAnswer => "rag_answer",
+        }
+    }
+
+    // The feature name in the usage statistics.
+    fn usage_feature(self) -> &'static str {
+        match self {
+            Self::Segment => "translate_segment",
+            Self::Live => "translate_live",
+            Self::RagAnswer => "translate_rag_answer",
         }
     }
 }
@@ -66,7 +72,7 @@ fn log_translate_request(
     items: usize,
     chars: usize,
 ) {
-    eprintln!(
+    info!(
     "[translate-request] source={} provider={} mode={} model={} endpoint={} target={} items={} chars={}",
     source.as_str(),
     provider,
@@ -119,44 +125,299 @@ fn normalize_translate_provider(provider: &str) -> String {
     match provider.trim().to_lowercase().as_str() {
         "openai" | "chatgpt" => "openai".to_string(),
         "local-gpt" | "local_gpt" | "localgpt" => "local-gpt".to_string(),
+        "claude" | "anthropic" => "claude".to_string(),
+        "gemini" | "google" => "gemini".to_string(),
         _ => "ollama".to_string(),
     }
 }
 
-fn compact_log_text(text: &str, max_chars: usize) -> String {
-    let compact = text.split_whitespace().collect::<Vec<_>>().join(" ");
-    let mut output = compact.chars().take(max_chars).collect::<String>();
-    if compact.chars().count() > max_chars {
-        output.push_str("...");
+// Turns segments into prompts and model replies into translations; the backend is injected, so
+// this logic runs against `MockLlm` in tests.
+pub trait TranslationProvider: Send + Sync {
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        source: TranslateSource,
+    ) -> BoxFuture<'a, Result<String, String>>;
+
+    fn translate_batch<'a>(
+        &'a self,
+        items: &'a [BatchTranslationItem],
+        source: TranslateSource,
+        options: &'a BatchTranslationOptions,
+    ) -> BoxFuture<'a, Result<HashMap<String, BatchTranslationResult>, String>>;
+}
+
+pub struct LlmTranslator {
+    llm: Box<dyn LlmProvider>,
+    target_language: String,
+    single_prompt: String,
+    batch_prompt: String,
+}
+
+impl LlmTranslator {
+    pub fn new(
+        llm: Box<dyn LlmProvider>,
+        target_language: &str,
+        single_prompt: &str,
+        batch_prompt: &str,
+    ) -> Self {
+        Self {
+            llm,
+            target_language: target_language.to_string(),
+            single_prompt: single_prompt.to_string(),
+            batch_prompt: batch_prompt.to_string(),
+        }
+    }
+
+    // Calls are counted in `usage`.
+    pub fn from_config(
+        config: &AppConfig,
+        provider_override: Option<String>,
+        usage: Option<UsageState>,
+    ) -> Result<Self, String> {
+        let (provider, target_language) = resolve_translate_settings(config, provider_override)?;
+        let llm = metered(provider_from_config(&provider, config)?, usage);
+        Ok(Self::new(
+            llm,
+            &target_language,
+            &resolve_segment_prompt_template(config, SegmentPromptKind::Single),
+            &resolve_segment_prompt_template(config, SegmentPromptKind::Batch),
+        ))
+    }
+
+    // The model is part of the provider, so switching models does not serve stale translations.
+    fn cache_key(&self, text: &str, target_language: &str) -> CacheKey {
+        let provider = format!("{}:{}", self.llm.name(), self.llm.model());
+        CacheKey::new(text, &provider, target_language)
+    }
+
+    fn single_request(&self, text: &str, target_language: &str) -> LlmPrompt {
+        let prompt = render_prompt_template(&self.single_prompt, target_language, Some(text), None);
+        let request = LlmPrompt::new(prompt);
+        if self.single_prompt.contains("{text}") {
+            request
+        } else {
+            request.with_input(text)
+        }
+    }
+
+    async fn translate_single(
+        &self,
+        text: &str,
+        source: TranslateSource,
+        target_language: &str,
+    ) -> Result<String, String> {
+        let request = self
+            .single_request(text, target_language)
+            .for_feature(source.usage_feature());
+        self.log_request(source, "single", target_language, 1, text.chars().count());
+        self.llm.complete(&request).await
+    }
+
+    async fn translate_single_streaming(
+        &self,
+        text: &str,
+        source: TranslateSource,
+        target_language: &str,
+        on_chunk: &mut ChunkSink<'_>,
+    ) -> Result<String, String> {
+        let request = self
+            .single_request(text, target_language)
+            .for_feature(source.usage_feature());
+        self.log_request(source, "stream", target_language, 1, text.chars().count());
+        self.llm.complete_streaming(&request, on_chunk).await
+    }
+
+    async fn translate_many(
+        &self,
+        items: &[BatchTranslationItem],
+        source: TranslateSource,
+        options: &BatchTranslationOptions,
+    ) -> Result<HashMap<String, BatchTranslationResult>, String> {
+        let target_language = options
+            .target_language
+            .as_deref()
+            .unwrap_or(&self.target_language);
+        if options.skip_cleanup {
+            let mut results = HashMap::new();
+            for item in items {
+                let translation = self
+                    .translate_single(&item.text, source, target_language)
+                    .await?;
+                results.insert(
+                    item.id.clone(),
+                    BatchTranslationResult {
+                        translation: translation.trim().to_string(),
+                        cleaned_source: None,
+                    },
+                );
+            }
+            return Ok(results);
+        }
+        let payload = build_batch_payload(items, &options.context_items)?;
+        let prompt =
+            render_prompt_template(&self.batch_prompt, target_language, None, Some(&payload));
+        let mut request = LlmPrompt::new(prompt)
+            .with_temperature(0.1)
+            .for_feature(source.usage_feature());
+        if !self.batch_prompt.contains("{payload}") {
+            request = request.with_input(payload);
+        }
+        let batch_chars: usize = items.iter().map(|item| item.text.chars().count()).sum();
+        self.log_request(source, "batch", target_language, items.len(), batch_chars);
+        let text = self.llm.complete(&request).await?;
+        parse_batch_translation_json(&text)
+    }
+
+    fn log_request(
+        &self,
+        source: TranslateSource,
+        mode: &str,
+        target_language: &str,
+        items: usize,
+        chars: usize,
+    ) {
+        log_translate_request(
+            source,
+            self.llm.name(),
+            mode,
+            self.llm.endpoint(),
+            self.llm.model(),
+            target_language,
+            items,
+            chars,
+        );
+    }
+}
+
+impl TranslationProvider for LlmTranslator {
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        source: TranslateSource,
+    ) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(self.translate_single(text, source, &self.target_language))
+    }
+
+    fn translate_batch<'a>(
+        &'a self,
+        items: &'a [BatchTranslationItem],
+        source: TranslateSource,
+        options: &'a BatchTranslationOptions,
+    ) -> BoxFuture<'a, Result<HashMap<String, BatchTranslationResult>, String>> {
+        Box::pin(self.translate_many(items, source, options))
     }
-    output
 }
 
+// `target_language` overrides `translate.targetLanguage`, as the CLI's `--translate` does.
 pub async fn translate_text(
+    app: &AppHandle,
     text: &str,
     provider_override: Option<String>,
     source: TranslateSource,
+    target_language: Option<&str>,
 ) -> Result<String, String> {
     let config = load_config()?;
-    let (provider, target_language) = resolve_translate_settings(&config, provider_override)?;
+    let translator = LlmTranslator::from_config(&config, provider_override, UsageState::of(app))?;
+    let target_language = target_language.unwrap_or(&translator.target_language);
+    let key = translator.cache_key(text, target_language);
+    if let Some(cached) = translation_cache::lookup(&key) {
+        return Ok(cached.translation);
+    }
+    let translation = translator
+        .translate_single(text, source, target_language)
+        .await?;
+    if !translation.trim().is_empty() {
+        translation_cache::store(vec![(
+            key,
+            CachedTranslation {
+                translation: translation.clone(),
+                cleaned_source: None,
+            },
+        )]);
+    }
+    Ok(translation)
+}
 
-    match provider.as_str() {
-        "openai" | "chatgpt" => {
-            translate_with_openai(text, &target_language, &config, source).await
-        }
-        "local-gpt" => translate_with_local_gpt(text, &target_language, &config, source).await,
-        "ollama" => translate_with_ollama(text, &target_language, &config, source).await,
-        other => Err(format!("unsupported translate provider: {other}")),
+// `[3]` and `[1, 2]` source markers in a RAG answer.
+static CITATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\d+(?:\s*[,，]\s*\d+)*\]").expect("citation regex"));
+static CITATION_SLOT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*C(\d+)\s*\}\}").expect("citation slot regex"));
+
+// Translates a RAG answer into the target language without touching its citations.
+pub async fn translate_answer(
+    app: &AppHandle,
+    text: &str,
+    provider_override: Option<String>,
+) -> Result<String, String> {
+    let config = load_config()?;
+    let translator = LlmTranslator::from_config(&config, provider_override, UsageState::of(app))?;
+    translate_keeping_citations(&translator, text).await
+}
+
+async fn translate_keeping_citations(
+    translator: &dyn TranslationProvider,
+    text: &str,
+) -> Result<String, String> {
+    let (masked, citations) = mask_citations(text);
+    let translated = translator
+        .translate(&masked, TranslateSource::RagAnswer)
+        .await?;
+    Ok(restore_citations(translated.trim(), &citations))
+}
+
+// Models copy `{{C1}}` slots verbatim far more reliably than they keep `[1]` untranslated
+// (e.g. `【1】` in Chinese).
+fn mask_citations(text: &str) -> (String, Vec<String>) {
+    let mut citations = Vec::new();
+    let masked = CITATION
+        .replace_all(text, |caps: &Captures| {
+            citations.push(caps[0].to_string());
+            format!("{{{{C{}}}}}", citations.len())
+        })
+        .into_owned();
+    (masked, citations)
+}
+
+// A citation whose slot the model dropped is appended, so no reference is lost.
+fn restore_citations(text: &str, citations: &[String]) -> String {
+    let mut used = vec![false; citations.len()];
+    let restored = CITATION_SLOT
+        .replace_all(text, |caps: &Captures| {
+            let index = caps[1].parse::<usize>().ok().and_then(|n| n.checked_sub(1));
+            match index.filter(|index| *index < citations.len()) {
+                Some(index) => {
+                    used[index] = true;
+                    citations[index].clone()
+                }
+                None => String::new(),
+            }
+        })
+        .into_owned();
+    let missing = citations
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|(citation, _)| citation.as_str())
+        .collect::<String>();
+    if missing.is_empty() {
+        restored
+    } else {
+        format!("{} {missing}", restored.trim_end())
     }
 }
 
 #[allow(dead_code)]
 pub async fn translate_text_batch(
+    app: &AppHandle,
     items: &[BatchTranslationItem],
     provider_override: Option<String>,
     source: TranslateSource,
 ) -> Result<HashMap<String, String>, String> {
     let detailed = translate_text_batch_with_options(
+        app,
         items,
         provider_override,
         source,
@@ -177,6 +438,7 @@ pub async fn translate_text_batch(
 }
 
 pub async fn translate_text_batch_with_options(
+    app: &AppHandle,
     items: &[BatchTranslationItem],
     provider_override: Option<String>,
     source: TranslateSource,
@@ -187,396 +449,127 @@ pub async fn translate_text_batch_with_options(
     }
 
     let config = load_config()?;
-    let (provider, target_language) = resolve_translate_settings(&config, provider_override)?;
-
-    let translations = match provider.as_str() {
-        "openai" | "chatgpt" => {
-            translate_batch_with_openai(items, &target_language, &config, source, &options).await?
-        }
-        "local-gpt" => {
-            translate_batch_with_local_gpt(items, &target_language, &config, source, &options)
-                .await?
-        }
-        "ollama" => {
-            translate_batch_with_ollama(items, &target_language, &config, source, &options).await?
-        }
-        other => return Err(format!("unsupported translate provider: {other}")),
-    };
-
-    if translations.is_empty() {
-        return Err("batch translation response is empty".to_string());
-    }
-
-    Ok(translations)
-}
-
-async fn translate_with_openai(
-    text: &str,
-    target_language: &str,
-    config: &crate::app_config::AppConfig,
-    source: TranslateSource,
-) -> Result<String, String> {
-    let openai = &config.openai;
-    let api_key = openai.api_key.trim();
-    if api_key.is_empty() {
-        return Err("OpenAI apiKey is required".to_string());
-    }
-
-    let model = openai
-        .chat_model
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_MODEL.to_string());
-    let base_url = openai
-        .chat_base_url
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_BASE_URL.to_string());
-    let timeout_secs = openai
-        .chat_timeout_secs
-        .unwrap_or(DEFAULT_OPENAI_CHAT_TIMEOUT);
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Single);
-    let prompt_uses_text = prompt_template.contains("{text}");
-    let prompt = render_prompt_template(&prompt_template, target_language, Some(text), None);
-    let mut input = vec![json!({
-        "role": "system",
-        "content": [{"type": "input_text", "text": prompt}]
-    })];
-    if !prompt_uses_text {
-        input.push(json!({
-            "role": "user",
-            "content": [{"type": "input_text", "text": text}]
-        }));
-    }
-    let body = json!({
-      "model": model,
-      "input": input,
-      "temperature": 0.2
-    });
-    let endpoint = base_url.trim_end_matches('/').to_string();
-    log_translate_request(
-        source,
-        "openai",
-        "single",
-        endpoint.as_str(),
-        model.as_str(),
-        target_language,
-        1,
-        text.chars().count(),
-    );
-
-    let response = match client
-        .post(endpoint.as_str())
-        .bearer_auth(api_key)
-        .json(&body)
-        .send()
-        .await
-    {
-        Ok(response) => response,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let status = response.status();
-    let value: serde_json::Value = match response.json().await {
-        Ok(value) => value,
-        Err(err) => return Err(err.to_string()),
-    };
-    if !status.is_success() {
-        return Err(value.to_string());
-    }
-
-    extract_response_text(&value).ok_or_else(|| "OpenAI response missing text".to_string())
-}
-
-async fn translate_with_ollama(
-    text: &str,
-    target_language: &str,
-    config: &crate::app_config::AppConfig,
-    source: TranslateSource,
-) -> Result<String, String> {
-    let ollama = config
-        .ollama
+    let translator = LlmTranslator::from_config(&config, provider_override, UsageState::of(app))?;
+    let target_language = options
+        .target_language
         .clone()
-        .unwrap_or_else(|| crate::app_config::OllamaConfig {
-            enabled: Some(true),
-            model: Some("gpt-oss:20b".to_string()),
-            base_url: Some(DEFAULT_OLLAMA_BASE_URL.to_string()),
-            timeout_secs: Some(DEFAULT_OLLAMA_TIMEOUT),
-        });
+        .unwrap_or_else(|| translator.target_language.clone());
 
-    if ollama.enabled == Some(false) {
-        return Err("ollama disabled".to_string());
+    let mut translations = HashMap::new();
+    let mut misses = Vec::new();
+    for item in items {
+        match translation_cache::lookup(&translator.cache_key(&item.text, &target_language)) {
+            Some(cached) => {
+                translations.insert(
+                    item.id.clone(),
+                    BatchTranslationResult {
+                        translation: cached.translation,
+                        cleaned_source: cached.cleaned_source,
+                    },
+                );
+            }
+            None => misses.push(item.clone()),
+        }
     }
-
-    let model = ollama
-        .model
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| "gpt-oss:20b".to_string());
-    let base_url = ollama
-        .base_url
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OLLAMA_BASE_URL.to_string());
-    let timeout_secs = ollama.timeout_secs.unwrap_or(DEFAULT_OLLAMA_TIMEOUT);
-    let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
-
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Single);
-    let prompt_uses_text = prompt_template.contains("{text}");
-    let prompt = render_prompt_template(&prompt_template, target_language, Some(text), None);
-    let prompt = if prompt_uses_text {
-        prompt
-    } else {
-        format!("{prompt}\n\n{text}")
-    };
-    let body = json!({
-      "model": model,
-      "prompt": prompt,
-      "stream": false
-    });
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-
-    log_translate_request(
-        source,
-        "ollama",
-        "single",
-        url.as_str(),
-        model.as_str(),
-        target_language,
-        1,
-        text.chars().count(),
-    );
-
-    let response = match client.post(url.as_str()).json(&body).send().await {
-        Ok(response) => response,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let status = response.status();
-    let value: serde_json::Value = match response.json().await {
-        Ok(value) => value,
-        Err(err) => return Err(err.to_string()),
-    };
-    if !status.is_success() {
-        return Err(value.to_string());
+    if !translations.is_empty() {
+        info!(
+            "[translate-cache] hits={} misses={}",
+            translations.len(),
+            misses.len()
+        );
     }
 
-    value
-        .get("response")
-        .and_then(|response| response.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty())
-        .ok_or_else(|| "ollama response missing text".to_string())
-}
-
-fn resolve_local_gpt_settings(config: &AppConfig) -> Result<(String, String, u64), String> {
-    let local_gpt = config.local_gpt.clone().unwrap_or_else(|| LocalGptConfig {
-        enabled: Some(true),
-        base_url: Some(DEFAULT_LOCAL_GPT_BASE_URL.to_string()),
-        timeout_secs: Some(DEFAULT_LOCAL_GPT_TIMEOUT),
-        project_id: None,
-    });
-
-    if local_gpt.enabled == Some(false) {
-        eprintln!(
-            "[local-gpt-direct] config localGpt.enabled=false, but proceeding because local-gpt provider is selected"
+    if !misses.is_empty() {
+        let fresh = translator
+            .translate_batch(&misses, source, &options)
+            .await?;
+        let texts = misses
+            .iter()
+            .map(|item| (item.id.as_str(), item.text.as_str()))
+            .collect::<HashMap<_, _>>();
+        translation_cache::store(
+            fresh
+                .iter()
+                .filter(|(_, result)| !result.translation.trim().is_empty())
+                .filter_map(|(id, result)| {
+                    let text = texts.get(id.as_str())?;
+                    Some((
+                        translator.cache_key(text, &target_language),
+                        CachedTranslation {
+                            translation: result.translation.clone(),
+                            cleaned_source: result.cleaned_source.clone(),
+                        },
+                    ))
+                })
+                .collect(),
         );
+        translations.extend(fresh);
     }
 
-    let base_url = local_gpt
-        .base_url
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_LOCAL_GPT_BASE_URL.to_string());
-    let timeout_secs = local_gpt.timeout_secs.unwrap_or(DEFAULT_LOCAL_GPT_TIMEOUT);
-    let project_id = local_gpt
-        .project_id
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| DEFAULT_LOCAL_GPT_PROJECT_ID.to_string());
-    Ok((base_url, project_id, timeout_secs))
-}
+    if translations.is_empty() {
+        return Err("batch translation response is empty".to_string());
+    }
 
-fn local_gpt_direct_url(base_url: &str) -> String {
-    format!(
-        "{}/{}",
-        base_url.trim_end_matches('/'),
-        DEFAULT_LOCAL_GPT_DIRECT_PATH.trim_start_matches('/')
-    )
+    Ok(translations)
 }
 
-async fn request_local_gpt_direct(
-    prompt: &str,
-    target_language: &str,
-    mode: &str,
-    items: usize,
-    chars: usize,
-    config: &AppConfig,
+// Item by item with the single prompt, handing each reply to `on_chunk(id, chunk)` as it streams
+// in. Like `skip_cleanup` there is no context and no `cleaned_source`; cached items arrive whole.
+pub async fn translate_text_batch_streaming(
+    app: &AppHandle,
+    items: &[BatchTranslationItem],
+    provider_override: Option<String>,
     source: TranslateSource,
-) -> Result<String, String> {
-    let (base_url, project_id, timeout_secs) = resolve_local_gpt_settings(config)?;
-    let url = local_gpt_direct_url(&base_url);
-    let prompt_preview = compact_log_text(prompt, 240);
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
+    target_language: Option<String>,
+    on_chunk: &mut (dyn FnMut(&str, &str) + Send),
+) -> Result<HashMap<String, BatchTranslationResult>, String> {
+    let config = load_config()?;
+    let translator = LlmTranslator::from_config(&config, provider_override, UsageState::of(app))?;
+    let target_language = target_language.unwrap_or_else(|| translator.target_language.clone());
 
-    log_translate_request(
-        source,
-        "local-gpt",
-        mode,
-        url.as_str(),
-        "-",
-        target_language,
-        items,
-        chars,
-    );
-    eprintln!(
-        "[local-gpt-direct] request mode={} source={} project_id={} timeout_secs={} prompt_preview={}",
-        mode,
-        source.as_str(),
-        project_id,
-        timeout_secs,
-        prompt_preview
-    );
-
-    let response = client
-        .post(url.as_str())
-        .json(&json!({
-          "project_id": project_id.as_str(),
-          "project-id": project_id.as_str(),
-          "prompt": prompt
-        }))
-        .send()
-        .await
-        .map_err(|err| err.to_string())?;
-
-    let status = response.status();
-    let raw = response.text().await.map_err(|err| err.to_string())?;
-    let (value, parsed_json) = match serde_json::from_str::<serde_json::Value>(&raw) {
-        Ok(value) => (value, true),
-        Err(_) => (json!({ "message": raw }), false),
-    };
-    if !parsed_json {
-        eprintln!(
-            "[local-gpt-direct] non-json response status={} raw_preview={}",
-            status.as_u16(),
-            compact_log_text(&raw, 300)
+    let mut translations = HashMap::new();
+    for item in items {
+        let key = translator.cache_key(&item.text, &target_language);
+        let translation = match translation_cache::lookup(&key) {
+            Some(cached) => {
+                on_chunk(&item.id, &cached.translation);
+                cached.translation
+            }
+            None => {
+                let translation = translator
+                    .translate_single_streaming(
+                        &item.text,
+                        source,
+                        &target_language,
+                        &mut |chunk: &str| on_chunk(&item.id, chunk),
+                    )
+                    .await?;
+                translation_cache::store(vec![(
+                    key,
+                    CachedTranslation {
+                        translation: translation.clone(),
+                        cleaned_source: None,
+                    },
+                )]);
+                translation
+            }
+        };
+        translations.insert(
+            item.id.clone(),
+            BatchTranslationResult {
+                translation: translation.trim().to_string(),
+                cleaned_source: None,
+            },
         );
     }
-    let message = value
-        .get("message")
-        .and_then(|field| field.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty())
-        .unwrap_or_else(|| value.to_string());
-    let timed_out = value
-        .get("timed_out")
-        .and_then(|field| field.as_bool())
-        .unwrap_or(false);
-    let ok = value
-        .get("ok")
-        .and_then(|field| field.as_bool())
-        .unwrap_or(status.is_success());
-    let result = value
-        .get("result")
-        .and_then(|field| field.as_str())
-        .map(|text| text.trim().to_string())
-        .filter(|text| !text.is_empty());
-    let request_id = value
-        .get("request_id")
-        .and_then(|field| field.as_str())
-        .unwrap_or("");
-    let viewer_url = value
-        .get("viewer_url")
-        .and_then(|field| field.as_str())
-        .unwrap_or("");
-    let result_chars = result
-        .as_ref()
-        .map(|text| text.chars().count())
-        .unwrap_or(0usize);
-    let result_preview = result
-        .as_deref()
-        .map(|text| compact_log_text(text, 240))
-        .unwrap_or_default();
-    eprintln!(
-        "[local-gpt-direct] response status={} ok={} timed_out={} request_id={} viewer_url={} message={} result_chars={} result_preview={}",
-        status.as_u16(),
-        ok,
-        timed_out,
-        request_id,
-        viewer_url,
-        compact_log_text(&message, 180),
-        result_chars,
-        result_preview
-    );
-
-    if ok {
-        return result.ok_or_else(|| "local-gpt response missing result".to_string());
-    }
-
-    if timed_out {
-        if let Some(partial) = result {
-            eprintln!(
-                "local-gpt timed out, returning partial result chars={}",
-                partial.chars().count()
-            );
-            return Ok(partial);
-        }
-    }
-
-    Err(message)
-}
-
-async fn translate_with_local_gpt(
-    text: &str,
-    target_language: &str,
-    config: &crate::app_config::AppConfig,
-    source: TranslateSource,
-) -> Result<String, String> {
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Single);
-    let prompt_uses_text = prompt_template.contains("{text}");
-    let prompt = render_prompt_template(&prompt_template, target_language, Some(text), None);
-    let prompt = if prompt_uses_text {
-        prompt
-    } else {
-        format!("{prompt}\n\n{text}")
-    };
-
-    request_local_gpt_direct(
-        &prompt,
-        target_language,
-        "single",
-        1,
-        text.chars().count(),
-        config,
-        source,
-    )
-    .await
+    Ok(translations)
 }
 
 fn resolve_translate_settings(
     config: &AppConfig,
     provider_override: Option<String>,
 ) -> Result<(String, String), String> {
-    let translate_config = config.translate.clone().unwrap_or(TranslateConfig {
-        enabled: Some(true),
-        provider: Some("ollama".to_string()),
-        target_language: Some("zh".to_string()),
-        segment_batch_size: None,
-        segment_single_prompt: None,
-        segment_batch_prompt: None,
-        live_prompt: None,
-    });
+    let translate_config = config.translate.clone().unwrap_or_default();
 
     if translate_config.enabled == Some(false) {
         return Err("translation disabled".to_string());
@@ -585,226 +578,90 @@ fn resolve_translate_settings(
     let provider = provider_override
         .filter(|value| !value.trim().is_empty())
         .or(translate_config.provider)
-        .unwrap_or_else(|| "ollama".to_string());
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_PROVIDER.to_string());
     let provider = normalize_translate_provider(&provider);
 
     let target_language = translate_config
         .target_language
-        .unwrap_or_else(|| "zh".to_string());
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string());
 
     Ok((provider, target_language))
 }
 
-async fn translate_batch_with_openai(
-    items: &[BatchTranslationItem],
-    target_language: &str,
-    config: &AppConfig,
-    source: TranslateSource,
-    options: &BatchTranslationOptions,
-) -> Result<HashMap<String, BatchTranslationResult>, String> {
-    let openai = &config.openai;
-    let api_key = openai.api_key.trim();
-    if api_key.is_empty() {
-        return Err("OpenAI apiKey is required".to_string());
-    }
-
-    let model = openai
-        .chat_model
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_MODEL.to_string());
-    let base_url = openai
-        .chat_base_url
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OPENAI_CHAT_BASE_URL.to_string());
-    let timeout_secs = openai
-        .chat_timeout_secs
-        .unwrap_or(DEFAULT_OPENAI_CHAT_TIMEOUT);
-
-    let payload = build_batch_payload(items, &options.context_items)?;
-
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Batch);
-    let prompt_uses_payload = prompt_template.contains("{payload}");
-    let prompt = render_prompt_template(&prompt_template, target_language, None, Some(&payload));
-
-    let mut input = vec![json!({
-        "role": "system",
-        "content": [{"type": "input_text", "text": prompt}]
-    })];
-    if !prompt_uses_payload {
-        input.push(json!({
-            "role": "user",
-            "content": [{"type": "input_text", "text": payload}]
-        }));
-    }
-
-    let body = json!({
-      "model": model,
-      "input": input,
-      "temperature": 0.1
-    });
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-
-    let endpoint = base_url.trim_end_matches('/').to_string();
-    let batch_chars: usize = items.iter().map(|item| item.text.chars().count()).sum();
-    log_translate_request(
-        source,
-        "openai",
-        "batch",
-        endpoint.as_str(),
-        model.as_str(),
-        target_language,
-        items.len(),
-        batch_chars,
-    );
-
-    let response = match client
-        .post(endpoint.as_str())
-        .bearer_auth(api_key)
-        .json(&body)
-        .send()
-        .await
-    {
-        Ok(response) => response,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let status = response.status();
-    let value: serde_json::Value = match response.json().await {
-        Ok(value) => value,
-        Err(err) => return Err(err.to_string()),
-    };
-    if !status.is_success() {
-        return Err(value.to_string());
-    }
+pub fn target_language() -> String {
+    load_config()
+        .ok()
+        .and_then(|config| config.translate)
+        .and_then(|translate| translate.target_language)
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string())
+}
 
-    let text = extract_response_text(&value)
-        .ok_or_else(|| "OpenAI batch response missing text".to_string())?;
-    parse_batch_translation_json(&text)
+// `en`, `en-US`, `English` and the like.
+pub fn is_english(language: &str) -> bool {
+    let language = language.trim().to_lowercase();
+    language == "en" || language.starts_with("en-") || language == "english"
 }
 
-async fn translate_batch_with_ollama(
-    items: &[BatchTranslationItem],
-    target_language: &str,
-    config: &AppConfig,
-    source: TranslateSource,
-    options: &BatchTranslationOptions,
-) -> Result<HashMap<String, BatchTranslationResult>, String> {
-    let ollama = config
-        .ollama
-        .clone()
-        .unwrap_or_else(|| crate::app_config::OllamaConfig {
-            enabled: Some(true),
-            model: Some("gpt-oss:20b".to_string()),
-            base_url: Some(DEFAULT_OLLAMA_BASE_URL.to_string()),
-            timeout_secs: Some(DEFAULT_OLLAMA_TIMEOUT),
-        });
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpeakerTranslation {
+    Skip,
+    Target(String),
+}
 
-    if ollama.enabled == Some(false) {
-        return Err("ollama disabled".to_string());
+// `translate.speakers` for one segment; unlisted and undiarized segments get the session target.
+pub fn speaker_translation(config: &AppConfig, speaker_id: Option<u32>) -> SpeakerTranslation {
+    let translate = config.translate.clone().unwrap_or_default();
+    let preference = speaker_id.and_then(|speaker_id| {
+        translate
+            .speakers
+            .iter()
+            .flatten()
+            .find(|speaker| speaker.speaker_id == speaker_id)
+    });
+    if preference.and_then(|speaker| speaker.translate) == Some(false) {
+        return SpeakerTranslation::Skip;
     }
-
-    let model = ollama
-        .model
+    let target_language = preference
+        .and_then(|speaker| speaker.target_language.clone())
+        .or(translate.target_language)
         .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| "gpt-oss:20b".to_string());
-    let base_url = ollama
-        .base_url
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_OLLAMA_BASE_URL.to_string());
-    let timeout_secs = ollama.timeout_secs.unwrap_or(DEFAULT_OLLAMA_TIMEOUT);
-    let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
-
-    let payload = build_batch_payload(items, &options.context_items)?;
-
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Batch);
-    let prompt_uses_payload = prompt_template.contains("{payload}");
-    let prompt = render_prompt_template(&prompt_template, target_language, None, Some(&payload));
-    let prompt = if prompt_uses_payload {
-        prompt
-    } else {
-        format!("{prompt}\n\n{payload}")
-    };
-    let body = json!({
-      "model": model,
-      "prompt": prompt,
-      "stream": false
-    });
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|err| err.to_string())?;
-
-    let batch_chars: usize = items.iter().map(|item| item.text.chars().count()).sum();
-    log_translate_request(
-        source,
-        "ollama",
-        "batch",
-        url.as_str(),
-        model.as_str(),
-        target_language,
-        items.len(),
-        batch_chars,
-    );
-
-    let response = match client.post(url.as_str()).json(&body).send().await {
-        Ok(response) => response,
-        Err(err) => return Err(err.to_string()),
-    };
+        .unwrap_or_else(|| DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string());
+    SpeakerTranslation::Target(target_language)
+}
 
-    let status = response.status();
-    let value: serde_json::Value = match response.json().await {
-        Ok(value) => value,
-        Err(err) => return Err(err.to_string()),
-    };
-    if !status.is_success() {
-        return Err(value.to_string());
+// `translate.fallbackProviders` to retry a failed segment batch on, in order, leaving out the
+// provider that failed and repeats.
+pub fn fallback_providers(config: &AppConfig, failed: &str) -> Vec<String> {
+    let failed = normalize_translate_provider(failed);
+    let mut providers: Vec<String> = Vec::new();
+    for provider in config
+        .translate
+        .as_ref()
+        .and_then(|translate| translate.fallback_providers.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        let provider = normalize_translate_provider(provider);
+        if provider != failed && !providers.contains(&provider) {
+            providers.push(provider);
+        }
     }
-
-    let text = value
-        .get("response")
-        .and_then(|response| response.as_str())
-        .map(|raw| raw.trim().to_string())
-        .filter(|raw| !raw.is_empty())
-        .ok_or_else(|| "ollama batch response missing text".to_string())?;
-    parse_batch_translation_json(&text)
+    providers
 }
 
-async fn translate_batch_with_local_gpt(
-    items: &[BatchTranslationItem],
-    target_language: &str,
-    config: &AppConfig,
-    source: TranslateSource,
-    options: &BatchTranslationOptions,
-) -> Result<HashMap<String, BatchTranslationResult>, String> {
-    let payload = build_batch_payload(items, &options.context_items)?;
-    let prompt_template = resolve_segment_prompt_template(config, SegmentPromptKind::Batch);
-    let prompt_uses_payload = prompt_template.contains("{payload}");
-    let prompt = render_prompt_template(&prompt_template, target_language, None, Some(&payload));
-    let prompt = if prompt_uses_payload {
-        prompt
-    } else {
-        format!("{prompt}\n\n{payload}")
-    };
-
-    let batch_chars: usize = items.iter().map(|item| item.text.chars().count()).sum();
-    let text = request_local_gpt_direct(
-        &prompt,
-        target_language,
-        "batch",
-        items.len(),
-        batch_chars,
-        config,
-        source,
-    )
-    .await?;
-    parse_batch_translation_json(&text)
+// Persists the target language; every translate call re-reads the config, so it applies immediately.
+pub fn set_target_language(language: &str) -> Result<String, String> {
+    let language = language.trim();
+    if language.is_empty() {
+        return Err("target language is required".to_string());
+    }
+    let mut config = load_file_config()?;
+    let mut translate = config.translate.unwrap_or_default();
+    translate.target_language = Some(language.to_string());
+    config.translate = Some(translate);
+    save_config(&config)?;
+    crate::templates::override_target_language(language);
+    Ok(language.to_string())
 }
 
 fn build_batch_payload(
@@ -954,28 +811,181 @@ fn collect_batch_item(item: &serde_json::Value, map: &mut HashMap<String, BatchT
     }
 }
 
-fn extract_response_text(value: &serde_json::Value) -> Option<String> {
-    if let Some(text) = value.get("output_text").and_then(|field| field.as_str()) {
-        let trimmed = text.trim();
-        if !trimmed.is_empty() {
-            return Some(trimmed.to_string());
+#[cfg(test)]
+mod tests {
+    use super::{
+        fallback_providers, is_english, speaker_translation, translate_keeping_citations,
+        BatchTranslationItem, BatchTranslationOptions, LlmTranslator, SpeakerTranslation,
+        TranslateSource, TranslationProvider,
+    };
+    use crate::llm::mock::MockLlm;
+    use crate::runtime::block_on;
+    use std::sync::Arc;
+
+    fn item(id: &str, text: &str) -> BatchTranslationItem {
+        BatchTranslationItem {
+            id: id.to_string(),
+            text: text.to_string(),
         }
     }
-    if let Some(output) = value.get("output").and_then(|field| field.as_array()) {
-        for item in output {
-            if let Some(content) = item.get("content").and_then(|field| field.as_array()) {
-                for part in content {
-                    if part.get("type").and_then(|t| t.as_str()) == Some("output_text") {
-                        if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                            let trimmed = text.trim();
-                            if !trimmed.is_empty() {
-                                return Some(trimmed.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
+
+    #[test]
+    fn builds_prompts_and_parses_replies_through_the_provider() {
+        let llm = Arc::new(MockLlm::new(&[
+            "你好",
+            "```json\n{\"items\": [{\"id\": \"a\", \"translation\": \"早上好\", \"cleaned_source\": \"Good morning.\"}]}\n```",
+        ]));
+        let translator = LlmTranslator::new(
+            Box::new(llm.clone()),
+            "中文",
+            "Translate to {target_language}: {text}",
+            "Translate items to {target_language}.",
+        );
+        let options = BatchTranslationOptions {
+            context_items: vec![item("ctx", "earlier line")],
+            target_language: Some("日本語".to_string()),
+            skip_cleanup: false,
+        };
+        let items = [item("a", "good morning")];
+
+        let (single, batch) = block_on(async {
+            (
+                translator.translate("hello", TranslateSource::Live).await,
+                translator
+                    .translate_batch(&items, TranslateSource::Segment, &options)
+                    .await,
+            )
+        });
+        assert_eq!(single.unwrap(), "你好");
+        let batch = batch.unwrap();
+        assert_eq!(batch["a"].translation, "早上好");
+        assert_eq!(batch["a"].cleaned_source.as_deref(), Some("Good morning."));
+
+        let prompts = llm.prompts();
+        assert_eq!(prompts[0].instructions, "Translate to 中文: hello");
+        assert!(prompts[0].input.is_none());
+        assert_eq!(prompts[1].instructions, "Translate items to 日本語.");
+        let payload: serde_json::Value =
+            serde_json::from_str(prompts[1].input.as_deref().unwrap()).unwrap();
+        assert_eq!(payload["items"][0]["id"], "a");
+        assert_eq!(payload["context"][0]["text"], "earlier line");
+
+        let failing = LlmTranslator::new(Box::new(MockLlm::new(&["not json"])), "中文", "", "");
+        let result = block_on(failing.translate_batch(
+            &items,
+            TranslateSource::Segment,
+            &BatchTranslationOptions::default(),
+        ));
+        assert!(result.is_err());
+
+        let uncleaned = Arc::new(MockLlm::new(&[" おはよう "]));
+        let translator = LlmTranslator::new(
+            Box::new(uncleaned.clone()),
+            "中文",
+            "Translate to {target_language}: {text}",
+            "Translate items to {target_language}.",
+        );
+        let options = BatchTranslationOptions {
+            skip_cleanup: true,
+            ..options
+        };
+        let batch =
+            block_on(translator.translate_batch(&items, TranslateSource::Segment, &options))
+                .unwrap();
+        assert_eq!(batch["a"].translation, "おはよう");
+        assert!(batch["a"].cleaned_source.is_none());
+        assert_eq!(
+            uncleaned.prompts()[0].instructions,
+            "Translate to 日本語: good morning"
+        );
+    }
+
+    #[test]
+    fn keeps_rag_citations_through_translation() {
+        let llm = Arc::new(MockLlm::new(&[
+            "缓存在启动时加载 {{C1}}，失效由 watcher 处理{{ C2 }}。",
+            "缓存在启动时加载。",
+        ]));
+        let translator = LlmTranslator::new(Box::new(llm.clone()), "中文", "{text}", "");
+        let answer =
+            "The cache is loaded at startup [1], invalidation is done by the watcher [2, 4].";
+
+        let translated = block_on(translate_keeping_citations(&translator, answer)).unwrap();
+        assert_eq!(
+            translated,
+            "缓存在启动时加载 [1]，失效由 watcher 处理[2, 4]。"
+        );
+        assert_eq!(
+            llm.prompts()[0].instructions,
+            "The cache is loaded at startup {{C1}}, invalidation is done by the watcher {{C2}}."
+        );
+
+        // The model dropped the slots: the citations are appended instead of lost.
+        let dropped = block_on(translate_keeping_citations(&translator, answer)).unwrap();
+        assert_eq!(dropped, "缓存在启动时加载。 [1][2, 4]");
+    }
+
+    #[test]
+    fn resolves_per_speaker_preferences() {
+        let config = serde_json::from_value(serde_json::json!({
+            "openai": { "apiKey": "" },
+            "translate": {
+                "targetLanguage": "zh",
+                "speakers": [
+                    { "speakerId": 1, "targetLanguage": "en" },
+                    { "speakerId": 2, "translate": false },
+                ],
+            },
+        }))
+        .unwrap();
+        let target = |language: &str| SpeakerTranslation::Target(language.to_string());
+        assert_eq!(speaker_translation(&config, Some(1)), target("en"));
+        assert_eq!(
+            speaker_translation(&config, Some(2)),
+            SpeakerTranslation::Skip
+        );
+        assert_eq!(speaker_translation(&config, Some(3)), target("zh"));
+        assert_eq!(speaker_translation(&config, None), target("zh"));
+    }
+
+    #[test]
+    fn lists_fallback_providers_after_the_failed_one() {
+        let config = serde_json::from_value(serde_json::json!({
+            "openai": { "apiKey": "" },
+            "translate": {
+                "provider": "openai",
+                "fallbackProviders": ["ChatGPT", "ollama", "anthropic", "claude"],
+            },
+        }))
+        .unwrap();
+        assert_eq!(fallback_providers(&config, "openai"), ["ollama", "claude"]);
+        assert_eq!(fallback_providers(&config, "ollama"), ["openai", "claude"]);
+        // The failed provider may arrive under an alias, in any case.
+        assert_eq!(
+            fallback_providers(&config, " Anthropic "),
+            ["openai", "ollama"]
+        );
+        let repeated = serde_json::from_value(serde_json::json!({
+            "openai": { "apiKey": "" },
+            "translate": { "fallbackProviders": [" Gemini ", "google", "GEMINI", "local_gpt"] },
+        }))
+        .unwrap();
+        assert_eq!(
+            fallback_providers(&repeated, "openai"),
+            ["gemini", "local-gpt"]
+        );
+        assert_eq!(fallback_providers(&repeated, "gemini"), ["local-gpt"]);
+        let unset =
+            serde_json::from_value(serde_json::json!({ "openai": { "apiKey": "" } })).unwrap();
+        assert!(fallback_providers(&unset, "openai").is_empty());
+    }
+
+    #[test]
+    fn recognizes_english_targets() {
+        assert!(is_english("en"));
+        assert!(is_english(" en-US "));
+        assert!(is_english("English"));
+        assert!(!is_english("zh"));
+        assert!(!is_english("eng-lish"));
     }
-    None
 }
@@ -0,0 +1,373 @@
+// Short utterances ("はい", "OK") come back in nearly every meeting; their translations are kept
+// in a least-recently-used cache on disk so each one only reaches the model once per provider
+// and target language. Longer text is never cached, and deleting a segment drops its entries.
+
+use crate::app_config::load_config;
+use crate::defaults::{DEFAULT_TRANSLATION_CACHE_ENABLED, DEFAULT_TRANSLATION_CACHE_SIZE};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const CACHE_FILE: &str = "translation-cache.json";
+// Utterances longer than this rarely repeat and are not worth keeping on disk.
+const MAX_CACHED_CHARS: usize = 32;
+// Changes are written at most this often, by a timer thread rather than the translate path.
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+
+// `None` until `start`, or when the cache is disabled; lookups then always miss.
+static CACHE: Lazy<Mutex<Option<PersistentCache>>> = Lazy::new(|| Mutex::new(None));
+// Held while a snapshot is taken and written, so writes land in order.
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    text: String,
+    provider: String,
+    target: String,
+}
+
+impl CacheKey {
+    pub fn new(text: &str, provider: &str, target: &str) -> Self {
+        Self {
+            text: text.trim().to_string(),
+            provider: provider.to_string(),
+            target: target.trim().to_lowercase(),
+        }
+    }
+
+    fn cacheable(&self) -> bool {
+        !self.text.is_empty() && self.text.chars().count() <= MAX_CACHED_CHARS
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedTranslation {
+    pub translation: String,
+    pub cleaned_source: Option<String>,
+}
+
+// On-disk form, least recently used first.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheRecord {
+    text: String,
+    provider: String,
+    target: String,
+    translation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cleaned_source: Option<String>,
+}
+
+struct CacheEntry {
+    value: CachedTranslation,
+    // Tick of the last lookup or insert, the entry's key in `order`.
+    used: u64,
+}
+
+struct TranslationCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<CacheKey, CacheEntry>,
+    // Keys by last use, least recently used first.
+    order: BTreeMap<u64, CacheKey>,
+}
+
+impl TranslationCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<CachedTranslation> {
+        let entry = self.entries.get_mut(key)?;
+        self.tick += 1;
+        self.order.remove(&entry.used);
+        self.order.insert(self.tick, key.clone());
+        entry.used = self.tick;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, value: CachedTranslation) {
+        self.tick += 1;
+        let previous = self.entries.insert(
+            key.clone(),
+            CacheEntry {
+                value,
+                used: self.tick,
+            },
+        );
+        if let Some(previous) = previous {
+            self.order.remove(&previous.used);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    // Drops every entry for these source texts, whatever the provider and target.
+    fn forget(&mut self, texts: &HashSet<&str>) -> usize {
+        let before = self.entries.len();
+        self.entries
+            .retain(|key, _| !texts.contains(key.text.as_str()));
+        self.order
+            .retain(|_, key| !texts.contains(key.text.as_str()));
+        before - self.entries.len()
+    }
+
+    fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
+        self.entries.clear();
+        self.order.clear();
+        cleared
+    }
+
+    fn from_records(capacity: usize, records: Vec<CacheRecord>) -> Self {
+        let mut cache = Self::new(capacity);
+        for record in records {
+            let key = CacheKey::new(&record.text, &record.provider, &record.target);
+            // Files written before the length cap may still hold long text.
+            if !key.cacheable() {
+                continue;
+            }
+            cache.insert(
+                key,
+                CachedTranslation {
+                    translation: record.translation,
+                    cleaned_source: record.cleaned_source,
+                },
+            );
+        }
+        cache
+    }
+
+    fn records(&self) -> Vec<CacheRecord> {
+        self.order
+            .values()
+            .filter_map(|key| Some((key, self.entries.get(key)?)))
+            .map(|(key, entry)| CacheRecord {
+                text: key.text.clone(),
+                provider: key.provider.clone(),
+                target: key.target.clone(),
+                translation: entry.value.translation.clone(),
+                cleaned_source: entry.value.cleaned_source.clone(),
+            })
+            .collect()
+    }
+}
+
+struct PersistentCache {
+    cache: TranslationCache,
+    path: PathBuf,
+    save_scheduled: bool,
+}
+
+impl PersistentCache {
+    fn schedule_save(&mut self) {
+        if !std::mem::replace(&mut self.save_scheduled, true) {
+            thread::spawn(|| {
+                thread::sleep(SAVE_DELAY);
+                flush();
+            });
+        }
+    }
+}
+
+// Writes the cache now if a save is pending; called by the save timer and on app exit.
+pub fn flush() {
+    let Ok(_writing) = WRITE_LOCK.lock() else {
+        return;
+    };
+    let snapshot = CACHE.lock().ok().and_then(|mut guard| {
+        let persistent = guard.as_mut()?;
+        std::mem::replace(&mut persistent.save_scheduled, false)
+            .then(|| (persistent.path.clone(), persistent.cache.records()))
+    });
+    let Some((path, records)) = snapshot else {
+        return;
+    };
+    let result = serde_json::to_vec(&records)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| fs::write(&path, bytes).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        warn!("[translation-cache] save failed: {err}");
+    }
+}
+
+// Loads the cache from the app data dir; `translate.cacheEnabled` and `translate.cacheSize` are
+// read once here.
+pub fn start(app: &AppHandle) {
+    let translate = load_config()
+        .ok()
+        .and_then(|config| config.translate)
+        .unwrap_or_default();
+    if !translate
+        .cache_enabled
+        .unwrap_or(DEFAULT_TRANSLATION_CACHE_ENABLED)
+    {
+        return;
+    }
+    let capacity = translate
+        .cache_size
+        .unwrap_or(DEFAULT_TRANSLATION_CACHE_SIZE);
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(err) => {
+            warn!("[translation-cache] no app data dir: {err}");
+            return;
+        }
+    };
+    if let Err(err) = fs::create_dir_all(&dir) {
+        warn!("[translation-cache] create {} failed: {err}", dir.display());
+        return;
+    }
+    let path = dir.join(CACHE_FILE);
+    // A missing or unreadable file starts an empty cache.
+    let records = fs::read(&path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Vec<CacheRecord>>(&bytes).ok())
+        .unwrap_or_default();
+    let cache = TranslationCache::from_records(capacity, records);
+    info!(
+        "[translation-cache] loaded {} of at most {capacity} entries",
+        cache.entries.len()
+    );
+    if let Ok(mut guard) = CACHE.lock() {
+        *guard = Some(PersistentCache {
+            cache,
+            path,
+            save_scheduled: false,
+        });
+    }
+}
+
+pub fn lookup(key: &CacheKey) -> Option<CachedTranslation> {
+    if !key.cacheable() {
+        return None;
+    }
+    CACHE.lock().ok()?.as_mut()?.cache.get(key)
+}
+
+// Adds fresh translations of short texts; the file is written later by `flush`.
+pub fn store(entries: Vec<(CacheKey, CachedTranslation)>) {
+    let mut entries = entries
+        .into_iter()
+        .filter(|(key, _)| key.cacheable())
+        .peekable();
+    if entries.peek().is_none() {
+        return;
+    }
+    let Ok(mut guard) = CACHE.lock() else {
+        return;
+    };
+    let Some(persistent) = guard.as_mut() else {
+        return;
+    };
+    for (key, value) in entries {
+        persistent.cache.insert(key, value);
+    }
+    persistent.schedule_save();
+}
+
+// Drops the cached translations of deleted segments' transcripts.
+pub fn forget<'a>(texts: impl IntoIterator<Item = &'a str>) {
+    let texts = texts
+        .into_iter()
+        .map(str::trim)
+        .filter(|text| !text.is_empty() && text.chars().count() <= MAX_CACHED_CHARS)
+        .collect::<HashSet<_>>();
+    if texts.is_empty() {
+        return;
+    }
+    let Ok(mut guard) = CACHE.lock() else {
+        return;
+    };
+    let Some(persistent) = guard.as_mut() else {
+        return;
+    };
+    if persistent.cache.forget(&texts) > 0 {
+        persistent.schedule_save();
+    }
+}
+
+// Returns how many entries were dropped.
+#[tauri::command]
+pub fn clear_translation_cache() -> usize {
+    let Ok(mut guard) = CACHE.lock() else {
+        return 0;
+    };
+    let Some(persistent) = guard.as_mut() else {
+        return 0;
+    };
+    let cleared = persistent.cache.clear();
+    persistent.schedule_save();
+    cleared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheKey, CachedTranslation, TranslationCache};
+    use std::collections::HashSet;
+
+    #[test]
+    fn evicts_the_least_recently_used_entry_and_keeps_order_on_reload() {
+        let value = |text: &str| CachedTranslation {
+            translation: text.to_string(),
+            cleaned_source: None,
+        };
+        let mut cache = TranslationCache::new(2);
+        let hai = CacheKey::new(" はい ", "ollama", "ZH");
+        let ok = CacheKey::new("OK", "ollama", "zh");
+        cache.insert(hai.clone(), value("是"));
+        cache.insert(ok.clone(), value("好的"));
+        assert_eq!(
+            cache.get(&CacheKey::new("はい", "ollama", "zh")),
+            Some(value("是"))
+        );
+        assert!(cache.get(&CacheKey::new("はい", "openai", "zh")).is_none());
+
+        // `OK` is now the least recently used.
+        cache.insert(CacheKey::new("では", "ollama", "zh"), value("那么"));
+        assert!(cache.get(&ok).is_none());
+        assert!(cache.get(&hai).is_some());
+
+        let mut reloaded = TranslationCache::from_records(1, cache.records());
+        assert_eq!(reloaded.entries.len(), 1);
+        assert_eq!(reloaded.get(&hai), Some(value("是")));
+    }
+
+    #[test]
+    fn forgets_deleted_texts_and_skips_long_ones() {
+        let value = |text: &str| CachedTranslation {
+            translation: text.to_string(),
+            cleaned_source: None,
+        };
+        let mut cache = TranslationCache::new(10);
+        cache.insert(CacheKey::new("はい", "ollama", "zh"), value("是"));
+        cache.insert(CacheKey::new("はい", "openai", "en"), value("Yes"));
+        cache.insert(CacheKey::new("OK", "ollama", "zh"), value("好的"));
+        assert_eq!(cache.forget(&HashSet::from(["はい"])), 2);
+        assert_eq!(cache.records().len(), 1);
+        assert_eq!(cache.order.len(), 1);
+        assert!(cache.get(&CacheKey::new("OK", "ollama", "zh")).is_some());
+
+        assert!(CacheKey::new("それでは始めましょう", "ollama", "zh").cacheable());
+        let long = "来週の定例では予算の見直しと採用計画について詳しく話し合いましょう";
+        assert!(!CacheKey::new(long, "ollama", "zh").cacheable());
+        assert!(!CacheKey::new("  ", "ollama", "zh").cacheable());
+    }
+}
@@ -0,0 +1,164 @@
+// Two-pass transcription: segments are transcribed with the regular ASR settings first, so the
+// draft shows up right away, then re-run on a slower, more accurate model whose transcript
+// replaces it.
+
+use crate::app_config::{load_config, AsrConfig};
+use crate::asr::{normalize_provider, AsrState};
+use crate::defaults::{
+    DEFAULT_TWO_PASS_ENABLED, DEFAULT_TWO_PASS_MODEL, DEFAULT_TWO_PASS_RETRANSLATE,
+};
+use crate::runtime;
+use crate::transcribe::{transcribe_detailed, Transcription};
+use crate::whisper_models::find_model;
+use crate::whisper_pipe::{PipeWorker, WhisperPipeManager};
+use crate::whisper_server::resolve_path_with_context;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoPassSettings {
+    // `None` runs the model on a local whisper-pipe worker.
+    pub provider: Option<String>,
+    pub model: String,
+    pub retranslate: bool,
+}
+
+impl TwoPassSettings {
+    // `None` while two-pass transcription is off.
+    pub fn load() -> Option<Self> {
+        let asr = load_config().ok()?.asr.unwrap_or_default();
+        Self::from_asr(&asr)
+    }
+
+    fn from_asr(asr: &AsrConfig) -> Option<Self> {
+        if !asr.two_pass_enabled.unwrap_or(DEFAULT_TWO_PASS_ENABLED) {
+            return None;
+        }
+        let provider = asr
+            .two_pass_provider
+            .as_deref()
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty() && value != "local")
+            .map(|value| normalize_provider(&value));
+        let model = asr
+            .two_pass_model
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(DEFAULT_TWO_PASS_MODEL)
+            .to_string();
+        Some(Self {
+            provider,
+            model,
+            retranslate: asr
+                .two_pass_retranslate
+                .unwrap_or(DEFAULT_TWO_PASS_RETRANSLATE),
+        })
+    }
+}
+
+// Keeps the accurate model's worker between segments, so a large model is loaded once rather
+// than once per segment. `WhisperPipeManager::stop` kills it like any other worker; the next
+// segment then starts a new one.
+pub struct Refiner {
+    worker: Option<(PathBuf, PipeWorker)>,
+}
+
+impl Refiner {
+    pub fn new() -> Self {
+        Self { worker: None }
+    }
+
+    // Blocking; only call from the pipeline's own worker threads. `language` is the session's, or
+    // the current ASR language when `None`. Only providers honour `asr.translateToEnglish`; the
+    // local worker always transcribes in the spoken language.
+    pub fn transcribe(
+        &mut self,
+        app: &AppHandle,
+        settings: &TwoPassSettings,
+        path: &Path,
+        language: Option<&str>,
+    ) -> Result<Transcription, String> {
+        match settings.provider.as_deref() {
+            Some(provider) => runtime::block_on(transcribe_detailed(
+                app,
+                path,
+                None,
+                Some(provider),
+                Some(&settings.model),
+                language,
+            )),
+            None => self
+                .transcribe_local(app, &settings.model, path, language)
+                .map(Transcription::plain),
+        }
+    }
+
+    fn transcribe_local(
+        &mut self,
+        app: &AppHandle,
+        model: &str,
+        path: &Path,
+        language: Option<&str>,
+    ) -> Result<String, String> {
+        let model = resolve_path_with_context(app, model)
+            .or_else(|| find_model(app, model))
+            .ok_or_else(|| format!("two-pass model not found: {model}"))?;
+        let manager = app
+            .try_state::<WhisperPipeManager>()
+            .ok_or_else(|| "whisper-pipe manager not available".to_string())?;
+        let mut asr = load_config()?.asr.unwrap_or_default();
+        let language = language
+            .map(str::to_string)
+            .or_else(|| app.try_state::<AsrState>().map(|state| state.language()))
+            .filter(|value| !value.trim().is_empty())
+            .or(asr.language.clone());
+
+        if let Some((_, worker)) = self.worker.take_if(|(loaded, _)| *loaded != model) {
+            manager.checkin(worker);
+        }
+        if self.worker.is_none() {
+            asr.whisper_cpp_model_path = Some(model.to_string_lossy().to_string());
+            let worker = manager.checkout_dedicated(app, &asr)?;
+            info!("two-pass model loaded: {}", model.display());
+            self.worker = Some((model, worker));
+        }
+        let Some((_, worker)) = self.worker.as_mut() else {
+            return Err("two-pass worker not available".to_string());
+        };
+        let result = manager.transcribe_kept(worker, path, language.as_deref(), None);
+        if result.is_err() {
+            self.worker = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TwoPassSettings;
+    use crate::app_config::AsrConfig;
+
+    #[test]
+    fn reads_the_second_pass_from_the_asr_settings() {
+        let mut asr = AsrConfig::default();
+        assert_eq!(TwoPassSettings::from_asr(&asr), None);
+
+        asr.two_pass_enabled = Some(true);
+        asr.two_pass_provider = Some(" Local ".to_string());
+        asr.two_pass_model = Some(" ".to_string());
+        let local = TwoPassSettings::from_asr(&asr).unwrap();
+        assert_eq!(local.provider, None);
+        assert_eq!(local.model, "large-v3-turbo");
+        assert!(local.retranslate);
+
+        asr.two_pass_provider = Some("OpenAI".to_string());
+        asr.two_pass_model = Some("gpt-4o-transcribe".to_string());
+        asr.two_pass_retranslate = Some(false);
+        let cloud = TwoPassSettings::from_asr(&asr).unwrap();
+        assert_eq!(cloud.provider.as_deref(), Some("openai"));
+        assert_eq!(cloud.model, "gpt-4o-transcribe");
+        assert!(!cloud.retranslate);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::app_config::{load_config, UpdatesConfig};
+use crate::defaults::{DEFAULT_UPDATES_ENABLED, DEFAULT_UPDATE_CHECK_INTERVAL_HOURS};
+use crate::events::{emit_ui_event, UPDATE_AVAILABLE};
+use crate::http;
+use crate::timestamps::now_rfc3339;
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const UPDATE_FETCH_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub available: bool,
+    pub notes: Option<String>,
+    pub url: Option<String>,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Release {
+    version: String,
+    notes: Option<String>,
+    url: Option<String>,
+}
+
+// Version already announced, so periodic checks emit `update_available` once per release.
+static ANNOUNCED: Mutex<Option<String>> = Mutex::new(None);
+
+// Checks the release feed periodically; only notifies, installing stays a manual step.
+pub fn start(app: &AppHandle) {
+    let config = updates_config();
+    if !config.enabled.unwrap_or(DEFAULT_UPDATES_ENABLED) || feed_url(&config).is_none() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Re-read config per round so edits apply without a restart.
+            let config = updates_config();
+            if !config.enabled.unwrap_or(DEFAULT_UPDATES_ENABLED) {
+                break;
+            }
+            if let Err(err) = check(&app, &config).await {
+                warn!("[update] check failed: {err}");
+            }
+            let hours = config
+                .check_interval_hours
+                .unwrap_or(DEFAULT_UPDATE_CHECK_INTERVAL_HOURS)
+                .max(1);
+            tokio::time::sleep(Duration::from_secs(hours * 3600)).await;
+        }
+    });
+}
+
+// `None` when update checks are disabled or no feed is configured.
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let config = updates_config();
+    if !config.enabled.unwrap_or(DEFAULT_UPDATES_ENABLED) || feed_url(&config).is_none() {
+        return Ok(None);
+    }
+    check(&app, &config).await.map(Some)
+}
+
+async fn check(app: &AppHandle, config: &UpdatesConfig) -> Result<UpdateInfo, String> {
+    let url = feed_url(config).ok_or_else(|| "updates.feedUrl is not set".to_string())?;
+    let client = http::client(Some(UPDATE_FETCH_TIMEOUT_SECS))?;
+    let response = client
+        .get(url)
+        .header("Accept", "application/json")
+        // GitHub's API rejects requests without a user agent.
+        .header(
+            "User-Agent",
+            concat!("ai-shepherd/", env!("CARGO_PKG_VERSION")),
+        )
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let value: Value = response.json().await.map_err(|err| err.to_string())?;
+    let release = parse_release(&value).ok_or_else(|| "release feed has no version".to_string())?;
+
+    let current_version = env!("CARGO_PKG_VERSION").to_string();
+    let available = compare_versions(&release.version, &current_version) == Ordering::Greater;
+    let info = UpdateInfo {
+        current_version,
+        latest_version: release.version,
+        available,
+        notes: release.notes,
+        url: release.url,
+        checked_at: now_rfc3339(),
+    };
+    if info.available && mark_announced(&info.latest_version) {
+        info!(
+            "[update] {} available (current {})",
+            info.latest_version, info.current_version
+        );
+        emit_ui_event(app, UPDATE_AVAILABLE, info.clone());
+    }
+    Ok(info)
+}
+
+fn mark_announced(version: &str) -> bool {
+    let mut guard = match ANNOUNCED.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if guard.as_deref() == Some(version) {
+        return false;
+    }
+    *guard = Some(version.to_string());
+    true
+}
+
+fn updates_config() -> UpdatesConfig {
+    load_config()
+        .ok()
+        .and_then(|config| config.updates)
+        .unwrap_or_default()
+}
+
+fn feed_url(config: &UpdatesConfig) -> Option<&str> {
+    config
+        .feed_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+// Accepts a plain `{ "version", "notes", "url" }` document, a single GitHub release, or a GitHub
+// release list (newest first; drafts and pre-releases are skipped).
+fn parse_release(value: &Value) -> Option<Release> {
+    if let Some(items) = value.as_array() {
+        return items
+            .iter()
+            .filter(|item| !flag(item, "draft") && !flag(item, "prerelease"))
+            .find_map(parse_release);
+    }
+    let version = text(value, &["version", "tag_name"])?;
+    Some(Release {
+        version: version.trim_start_matches(['v', 'V']).to_string(),
+        notes: text(value, &["notes", "body"]).map(str::to_string),
+        url: text(value, &["url", "html_url"]).map(str::to_string),
+    })
+}
+
+fn flag(value: &Value, field: &str) -> bool {
+    value.get(field).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn text<'a>(value: &'a Value, fields: &[&str]) -> Option<&'a str> {
+    fields.iter().find_map(|field| {
+        value
+            .get(*field)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    })
+}
+
+// Dotted numeric comparison; a pre-release suffix (`1.2.0-beta`) sorts before the plain release.
+fn compare_versions(left: &str, right: &str) -> Ordering {
+    let split = |version: &str| {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (version, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>();
+        (parts, pre)
+    };
+    let (left_parts, left_pre) = split(left);
+    let (right_parts, right_pre) = split(right);
+    let len = left_parts.len().max(right_parts.len());
+    for index in 0..len {
+        let a = left_parts.get(index).copied().unwrap_or(0);
+        let b = right_parts.get(index).copied().unwrap_or(0);
+        match a.cmp(&b) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+    match (left_pre, right_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(&b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_versions, parse_release};
+    use serde_json::json;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn parses_feeds_and_orders_versions() {
+        assert_eq!(compare_versions("0.2.0", "0.1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v0.1", "0.1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.2.0-beta", "0.2.0"), Ordering::Less);
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Ordering::Greater);
+
+        let plain = parse_release(&json!({ "version": "0.3.0", "notes": "fixes" })).unwrap();
+        assert_eq!(plain.version, "0.3.0");
+        assert_eq!(plain.notes.as_deref(), Some("fixes"));
+
+        let github = parse_release(&json!([
+            { "tag_name": "v0.4.0-rc1", "prerelease": true },
+            { "tag_name": "v0.3.1", "body": "notes", "html_url": "https://example.com/r" }
+        ]))
+        .unwrap();
+        assert_eq!(github.version, "0.3.1");
+        assert_eq!(github.url.as_deref(), Some("https://example.com/r"));
+    }
+}
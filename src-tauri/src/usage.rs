@@ -0,0 +1,351 @@
+// Token counts of every LLM call in the current capture session (or since launch, before the
+// first one), per provider, model and feature, priced with `usage.prices` when asked for. Replies
+// that report no usage (Local GPT, Ollama's live stream before its last line) are estimated from
+// the text.
+
+use crate::app_config::{load_config, TokenPrice};
+use crate::defaults::DEFAULT_TOKEN_PRICES;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+// Where each provider reports its input and output counts: OpenAI responses and Claude (also
+// nested in OpenAI's `response.completed` and Claude's `message_start` stream events), OpenAI chat
+// completions, Gemini, and Ollama.
+const USAGE_FIELDS: &[(&str, &str)] = &[
+    ("/usage/input_tokens", "/usage/output_tokens"),
+    (
+        "/response/usage/input_tokens",
+        "/response/usage/output_tokens",
+    ),
+    (
+        "/message/usage/input_tokens",
+        "/message/usage/output_tokens",
+    ),
+    ("/usage/prompt_tokens", "/usage/completion_tokens"),
+    (
+        "/usageMetadata/promptTokenCount",
+        "/usageMetadata/candidatesTokenCount",
+    ),
+    ("/prompt_eval_count", "/eval_count"),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn from_reply(value: &Value) -> Option<Self> {
+        let count = |pointer: &str| value.pointer(pointer).and_then(Value::as_u64);
+        USAGE_FIELDS.iter().find_map(|(input, output)| {
+            let (input, output) = (count(input), count(output));
+            (input.is_some() || output.is_some()).then(|| Self {
+                input_tokens: input.unwrap_or_default(),
+                output_tokens: output.unwrap_or_default(),
+            })
+        })
+    }
+
+    // Stream events report running totals, and Claude splits input and output across events, so
+    // the largest count of each kind wins.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            input_tokens: self.input_tokens.max(other.input_tokens),
+            output_tokens: self.output_tokens.max(other.output_tokens),
+        }
+    }
+
+    pub fn estimate(prompt: &str, reply: &str) -> Self {
+        Self {
+            input_tokens: estimate_tokens(prompt),
+            output_tokens: estimate_tokens(reply),
+        }
+    }
+}
+
+// About four ASCII characters per token; CJK and other scripts take roughly one token each.
+pub fn estimate_tokens(text: &str) -> u64 {
+    let ascii = text.chars().filter(char::is_ascii).count() as u64;
+    let other = text.chars().count() as u64 - ascii;
+    ascii.div_ceil(4) + other
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UsageKey {
+    provider: String,
+    model: String,
+    feature: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counter {
+    requests: u64,
+    estimated_requests: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+// Managed by the app; clones share the counters, so LLM prompts can carry one along.
+#[derive(Debug, Clone, Default)]
+pub struct UsageState {
+    counters: Arc<Mutex<HashMap<UsageKey, Counter>>>,
+}
+
+impl UsageState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn of(app: &AppHandle) -> Option<Self> {
+        app.try_state::<UsageState>()
+            .map(|state| state.inner().clone())
+    }
+
+    // `feature` names the caller: `translate_segment`, `translate_live`, `summary`, `rag`, ...
+    pub fn record(
+        &self,
+        provider: &str,
+        model: &str,
+        feature: &str,
+        usage: TokenUsage,
+        estimated: bool,
+    ) {
+        let Ok(mut counters) = self.counters.lock() else {
+            return;
+        };
+        let counter = counters
+            .entry(UsageKey {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                feature: feature.to_string(),
+            })
+            .or_default();
+        counter.requests += 1;
+        counter.estimated_requests += u64::from(estimated);
+        counter.input_tokens += usage.input_tokens;
+        counter.output_tokens += usage.output_tokens;
+    }
+
+    // Called when a capture session starts, so the stats cover that session only.
+    pub fn reset(&self) {
+        if let Ok(mut counters) = self.counters.lock() {
+            counters.clear();
+        }
+    }
+
+    fn snapshot(&self) -> HashMap<UsageKey, Counter> {
+        self.counters
+            .lock()
+            .map(|counters| counters.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageTotals {
+    pub name: String,
+    pub requests: u64,
+    // Calls whose tokens were estimated rather than reported.
+    pub estimated_requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    // US dollars; models without a price count as free.
+    pub cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, counter: &Counter, cost_usd: f64) {
+        self.requests += counter.requests;
+        self.estimated_requests += counter.estimated_requests;
+        self.input_tokens += counter.input_tokens;
+        self.output_tokens += counter.output_tokens;
+        self.cost_usd += cost_usd;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageStats {
+    pub total: UsageTotals,
+    pub by_provider: Vec<UsageTotals>,
+    pub by_feature: Vec<UsageTotals>,
+}
+
+#[tauri::command]
+pub fn get_usage_stats(state: State<'_, UsageState>) -> UsageStats {
+    let prices = load_config()
+        .ok()
+        .and_then(|config| config.usage)
+        .and_then(|usage| usage.prices)
+        .unwrap_or_default();
+    summarize(&state.snapshot(), &prices)
+}
+
+fn summarize(
+    counters: &HashMap<UsageKey, Counter>,
+    prices: &HashMap<String, TokenPrice>,
+) -> UsageStats {
+    let mut total = UsageTotals {
+        name: "total".to_string(),
+        ..UsageTotals::default()
+    };
+    let mut by_provider = BTreeMap::<&str, UsageTotals>::new();
+    let mut by_feature = BTreeMap::<&str, UsageTotals>::new();
+    for (key, counter) in counters {
+        let cost_usd = price_for(prices, &key.provider, &key.model)
+            .map(|price| {
+                (counter.input_tokens as f64 * price.input_per_million
+                    + counter.output_tokens as f64 * price.output_per_million)
+                    / 1_000_000.0
+            })
+            .unwrap_or_default();
+        total.add(counter, cost_usd);
+        for (groups, name) in [
+            (&mut by_provider, key.provider.as_str()),
+            (&mut by_feature, key.feature.as_str()),
+        ] {
+            groups
+                .entry(name)
+                .or_insert_with(|| UsageTotals {
+                    name: name.to_string(),
+                    ..UsageTotals::default()
+                })
+                .add(counter, cost_usd);
+        }
+    }
+    UsageStats {
+        total,
+        by_provider: by_provider.into_values().collect(),
+        by_feature: by_feature.into_values().collect(),
+    }
+}
+
+fn price_for(
+    prices: &HashMap<String, TokenPrice>,
+    provider: &str,
+    model: &str,
+) -> Option<TokenPrice> {
+    let qualified = format!("{provider}:{model}");
+    prices
+        .get(&qualified)
+        .or_else(|| prices.get(provider))
+        .copied()
+        .or_else(|| {
+            DEFAULT_TOKEN_PRICES
+                .iter()
+                .find(|(key, _, _)| *key == qualified)
+                .map(|&(_, input_per_million, output_per_million)| TokenPrice {
+                    input_per_million,
+                    output_per_million,
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_tokens, summarize, Counter, TokenUsage, UsageKey, UsageState};
+    use crate::app_config::TokenPrice;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn reads_reported_usage_and_prices_it_per_provider_and_feature() {
+        let usage = |input_tokens, output_tokens| TokenUsage {
+            input_tokens,
+            output_tokens,
+        };
+        let openai = json!({ "usage": { "input_tokens": 12, "output_tokens": 3 } });
+        assert_eq!(TokenUsage::from_reply(&openai), Some(usage(12, 3)));
+        let ollama =
+            json!({ "response": "", "done": true, "prompt_eval_count": 40, "eval_count": 9 });
+        assert_eq!(TokenUsage::from_reply(&ollama), Some(usage(40, 9)));
+        let gemini =
+            json!({ "usageMetadata": { "promptTokenCount": 7, "candidatesTokenCount": 2 } });
+        assert_eq!(TokenUsage::from_reply(&gemini), Some(usage(7, 2)));
+        // Claude streams its input count first and the output count at the end.
+        let start = json!({ "type": "message_start", "message": { "usage": { "input_tokens": 25, "output_tokens": 1 } } });
+        let delta = json!({ "type": "message_delta", "usage": { "output_tokens": 15 } });
+        let streamed = TokenUsage::from_reply(&start)
+            .unwrap()
+            .merge(TokenUsage::from_reply(&delta).unwrap());
+        assert_eq!(streamed, usage(25, 15));
+        assert!(TokenUsage::from_reply(&json!({ "response": "hi" })).is_none());
+
+        assert_eq!(estimate_tokens("Good morning"), 3);
+        assert_eq!(estimate_tokens("おはよう"), 4);
+
+        let key = |provider: &str, model: &str, feature: &str| UsageKey {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            feature: feature.to_string(),
+        };
+        let counter = |input_tokens, output_tokens| Counter {
+            requests: 1,
+            estimated_requests: 0,
+            input_tokens,
+            output_tokens,
+        };
+        let counters = HashMap::from([
+            (
+                key("openai", "gpt-4.1-mini", "translate_segment"),
+                counter(1_000_000, 0),
+            ),
+            (key("openai", "gpt-4o", "summary"), counter(0, 1_000_000)),
+            (
+                key("ollama", "gpt-oss:20b", "translate_segment"),
+                counter(500, 100),
+            ),
+        ]);
+        let prices = HashMap::from([(
+            "openai:gpt-4o".to_string(),
+            TokenPrice {
+                input_per_million: 2.5,
+                output_per_million: 10.0,
+            },
+        )]);
+        let stats = summarize(&counters, &prices);
+        assert_eq!(stats.total.requests, 3);
+        assert!((stats.total.cost_usd - 10.4).abs() < 1e-9);
+        let names = |rows: &[super::UsageTotals]| {
+            rows.iter().map(|row| row.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&stats.by_provider), ["ollama", "openai"]);
+        assert_eq!(names(&stats.by_feature), ["summary", "translate_segment"]);
+        assert_eq!(stats.by_provider[0].cost_usd, 0.0);
+        assert!((stats.by_feature[1].cost_usd - 0.4).abs() < 1e-9);
+        assert_eq!(stats.by_feature[1].input_tokens, 1_000_500);
+    }
+
+    #[test]
+    fn shares_counters_between_clones_until_reset() {
+        let state = UsageState::new();
+        let prompt_copy = state.clone();
+        prompt_copy.record(
+            "openai",
+            "gpt-4o",
+            "summary",
+            TokenUsage::estimate("hi", "ok"),
+            true,
+        );
+        prompt_copy.record(
+            "openai",
+            "gpt-4o",
+            "summary",
+            TokenUsage {
+                input_tokens: 10,
+                output_tokens: 4,
+            },
+            false,
+        );
+        let stats = summarize(&state.snapshot(), &HashMap::new());
+        assert_eq!(stats.total.requests, 2);
+        assert_eq!(stats.total.estimated_requests, 1);
+        assert_eq!(stats.total.input_tokens, 11);
+
+        state.reset();
+        assert!(prompt_copy.snapshot().is_empty());
+    }
+}
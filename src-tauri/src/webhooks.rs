@@ -0,0 +1,134 @@
+use crate::app_config::{load_config, WebhookConfig};
+use crate::events::{EventHub, UiEvent, ACTION_ITEMS_EXTRACTED, SESSION_ENDED, SUMMARY_READY};
+use crate::http;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+const WEBHOOK_EVENTS: [&str; 3] = [SESSION_ENDED, SUMMARY_READY, ACTION_ITEMS_EXTRACTED];
+const WEBHOOK_TIMEOUT_SECS: u64 = 15;
+
+pub fn start(app: &AppHandle) {
+    let Some(hub) = app.try_state::<Arc<EventHub>>() else {
+        return;
+    };
+    let mut events = hub.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("webhook dispatcher lagged, skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if event.replayed || !is_webhook_event(&event.event) {
+                continue;
+            }
+            // Re-read config per event so edits apply without a restart.
+            let hooks = load_config()
+                .ok()
+                .and_then(|config| config.webhooks)
+                .unwrap_or_default();
+            let client = match http::client(Some(WEBHOOK_TIMEOUT_SECS)) {
+                Ok(client) => client,
+                Err(err) => {
+                    warn!("webhook client unavailable: {err}");
+                    continue;
+                }
+            };
+            for hook in hooks.iter().filter(|hook| wants_event(hook, &event.event)) {
+                if let Err(err) = deliver(&client, hook, &event).await {
+                    warn!("[webhook] {} {} failed: {err}", event.event, hook.url);
+                }
+            }
+        }
+    });
+}
+
+// Filters naming anything else would never match, so config validation rejects them.
+pub fn is_webhook_event(event: &str) -> bool {
+    WEBHOOK_EVENTS.contains(&event)
+}
+
+fn wants_event(hook: &WebhookConfig, event: &str) -> bool {
+    if hook.enabled == Some(false) || hook.url.trim().is_empty() {
+        return false;
+    }
+    match hook.events.as_ref() {
+        Some(filter) if !filter.is_empty() => filter.iter().any(|item| item == event),
+        _ => true,
+    }
+}
+
+async fn deliver(client: &Client, hook: &WebhookConfig, event: &UiEvent) -> Result<(), String> {
+    let body = serde_json::to_vec(event).map_err(|err| err.to_string())?;
+    let mut request = client
+        .post(hook.url.trim())
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", event.event.as_str());
+    if let Some(secret) = hook.secret.as_deref().filter(|value| !value.is_empty()) {
+        request = request.header("X-Webhook-Signature", sign(secret, &body)?);
+    }
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    Ok(())
+}
+
+fn sign(secret: &str, body: &[u8]) -> Result<String, String> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|err| err.to_string())?;
+    mac.update(body);
+    Ok(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_webhook_event, sign, wants_event};
+    use crate::app_config::WebhookConfig;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231 test case 2.
+        let signature = sign("Jefe", b"what do ya want for nothing?").unwrap();
+        assert_eq!(
+            signature,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn filters_events() {
+        let hook = WebhookConfig {
+            enabled: None,
+            url: "http://localhost/hook".to_string(),
+            secret: None,
+            events: Some(vec!["session_ended".to_string()]),
+        };
+        assert!(wants_event(&hook, "session_ended"));
+        assert!(!wants_event(&hook, "summary_ready"));
+    }
+
+    #[test]
+    fn accepts_only_emitted_events() {
+        assert!(is_webhook_event("session_ended"));
+        assert!(is_webhook_event("summary_ready"));
+        assert!(is_webhook_event("action_items_extracted"));
+        assert!(!is_webhook_event("summary_generated"));
+    }
+}
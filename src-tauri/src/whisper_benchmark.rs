@@ -0,0 +1,204 @@
+// First-run self-test: a 5-second sample through the GPU and CPU whisper-server builds at a few
+// thread counts. The fastest run becomes `asr.whisperServerDevice` and `asr.whisperServerThreads`,
+// so the core-count table is only a starting guess.
+
+use crate::app_config::{load_config, load_file_config, save_config, AsrConfig};
+use crate::events::{emit_ui_event, WHISPER_BENCHMARK_FINISHED};
+use crate::runtime;
+use crate::timestamps::now_rfc3339;
+use crate::transcribe::post_to_whisper_server;
+use crate::whisper_server::{
+    detect_physical_cores, recommend_threads, resolve_model_path, resolve_path_with_context,
+    resolve_server_exe, spawn_server, ServerDevice, WhisperServerManager,
+};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use serde::Serialize;
+use std::f32::consts::PI;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const SAMPLE_SECS: u32 = 5;
+const SAMPLE_RATE: u32 = 16_000;
+// Shipped with the app when available; otherwise a speech-like signal is generated.
+const SAMPLE_RESOURCE: &str = "resources/benchmark/sample-5s.wav";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkRun {
+    pub device: &'static str,
+    pub threads: usize,
+    // Inference time over audio length; below 1 is faster than real time.
+    pub real_time_factor: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub runs: Vec<BenchmarkRun>,
+    // What was written to the config; `None` when no build finished the sample.
+    pub device: Option<&'static str>,
+    pub threads: Option<usize>,
+}
+
+// Only whisper-server users without a thread count of their own, and only once.
+pub fn pending(asr: &AsrConfig) -> bool {
+    asr.whisper_server_benchmarked_at.is_none() && asr.whisper_server_threads.is_none()
+}
+
+// Blocking; takes a few server starts, so call it off the UI thread.
+pub fn run_benchmark(app: &AppHandle) -> Result<BenchmarkReport, String> {
+    let asr = load_config()?.asr.unwrap_or_default();
+    let model = resolve_model_path(app, &asr)
+        .ok_or_else(|| "whisper-server model path not found".to_string())?;
+    let sample = sample_path(app)?;
+    let physical_cores = detect_physical_cores();
+
+    let mut runs = Vec::new();
+    for device in [ServerDevice::Gpu, ServerDevice::Cpu] {
+        let Some(exe) = resolve_server_exe(app, device, &asr) else {
+            continue;
+        };
+        for threads in thread_candidates(device, physical_cores) {
+            let result = time_run(device, &exe, &model, threads, &sample, &asr);
+            if let Err(err) = &result {
+                warn!("[benchmark] {} -t {threads} failed: {err}", device.name());
+            }
+            runs.push(BenchmarkRun {
+                device: device.name(),
+                threads,
+                real_time_factor: result.as_ref().ok().copied(),
+                error: result.err(),
+            });
+        }
+    }
+    if runs.is_empty() {
+        return Err("no whisper-server build found".to_string());
+    }
+
+    let best = fastest(&runs);
+    let mut config = load_file_config()?;
+    let mut file_asr = config.asr.unwrap_or_default();
+    if let Some(best) = best {
+        info!(
+            "[benchmark] picked {} with -t {} (rtf {:.2})",
+            best.device,
+            best.threads,
+            best.real_time_factor.unwrap_or_default()
+        );
+        file_asr.whisper_server_device = Some(best.device.to_string());
+        file_asr.whisper_server_threads = Some(best.threads);
+    }
+    // Recorded even when every run failed, so a broken setup is not measured on each launch.
+    file_asr.whisper_server_benchmarked_at = Some(now_rfc3339());
+    config.asr = Some(file_asr);
+    save_config(&config)?;
+
+    if best.is_some() {
+        if let Some(manager) = app.try_state::<WhisperServerManager>() {
+            let asr = load_config()?.asr.unwrap_or_default();
+            manager.restart(app, &asr)?;
+        }
+    }
+    let report = BenchmarkReport {
+        device: best.map(|run| run.device),
+        threads: best.map(|run| run.threads),
+        runs,
+    };
+    emit_ui_event(app, WHISPER_BENCHMARK_FINISHED, report.clone());
+    Ok(report)
+}
+
+// Half the table's count, the table's count, and every physical core.
+fn thread_candidates(device: ServerDevice, physical_cores: usize) -> Vec<usize> {
+    let recommended = recommend_threads(device, physical_cores).min(physical_cores.max(1));
+    let mut candidates = vec![(recommended / 2).max(1), recommended, physical_cores.max(1)];
+    candidates.sort_unstable();
+    candidates.dedup();
+    candidates
+}
+
+fn fastest(runs: &[BenchmarkRun]) -> Option<&BenchmarkRun> {
+    runs.iter()
+        .filter_map(|run| Some((run, run.real_time_factor?)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(run, _)| run)
+}
+
+// The model loads before the port opens, so only inference is timed.
+fn time_run(
+    device: ServerDevice,
+    exe: &Path,
+    model: &Path,
+    threads: usize,
+    sample: &Path,
+    asr: &AsrConfig,
+) -> Result<f64, String> {
+    let mut handle = spawn_server(device, exe, model, threads)?;
+    let started_at = Instant::now();
+    let result = runtime::block_on(post_to_whisper_server(&handle.url, sample, asr, None));
+    let elapsed = started_at.elapsed().as_secs_f64();
+    let _ = handle.child.kill();
+    let _ = handle.child.wait();
+    result.map(|_| elapsed / SAMPLE_SECS as f64)
+}
+
+fn sample_path(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(path) = resolve_path_with_context(app, SAMPLE_RESOURCE) {
+        return Ok(path);
+    }
+    let path = std::env::temp_dir().join("whisper-benchmark-5s.wav");
+    write_sample(&path)?;
+    Ok(path)
+}
+
+// Voiced harmonics under a syllable-rate envelope, close enough to speech to keep the decoder
+// busy.
+fn write_sample(path: &Path) -> Result<(), String> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec).map_err(|err| err.to_string())?;
+    for index in 0..SAMPLE_RATE * SAMPLE_SECS {
+        let t = index as f32 / SAMPLE_RATE as f32;
+        let pitch = 140.0 + 20.0 * (2.0 * PI * 0.7 * t).sin();
+        let voice = (1..=8)
+            .map(|harmonic| (2.0 * PI * pitch * harmonic as f32 * t).sin() / harmonic as f32)
+            .sum::<f32>();
+        let envelope = (2.0 * PI * 4.0 * t).sin().max(0.0);
+        let sample = (voice * envelope * 0.25 * i16::MAX as f32) as i16;
+        writer.write_sample(sample).map_err(|err| err.to_string())?;
+    }
+    writer.finalize().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fastest, thread_candidates, BenchmarkRun};
+    use crate::whisper_server::ServerDevice;
+
+    #[test]
+    fn tries_a_few_thread_counts_and_keeps_the_fastest_run() {
+        assert_eq!(thread_candidates(ServerDevice::Gpu, 8), vec![2, 4, 8]);
+        assert_eq!(thread_candidates(ServerDevice::Cpu, 2), vec![1, 2]);
+        assert_eq!(thread_candidates(ServerDevice::Cpu, 1), vec![1]);
+
+        let run = |device, threads, real_time_factor| BenchmarkRun {
+            device,
+            threads,
+            real_time_factor,
+            error: None,
+        };
+        let runs = [
+            run("gpu", 4, None),
+            run("gpu", 8, Some(0.4)),
+            run("cpu", 8, Some(0.9)),
+        ];
+        let best = fastest(&runs).unwrap();
+        assert_eq!((best.device, best.threads), ("gpu", 8));
+        assert!(fastest(&runs[..1]).is_none());
+    }
+}
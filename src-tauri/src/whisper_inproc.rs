@@ -0,0 +1,260 @@
+// whisper.cpp linked into the app through whisper-rs: the model stays loaded in this process, and
+// segments go to it as samples instead of a multipart upload or a pipe frame. Only built with the
+// `whisper-inproc` cargo feature; without it the provider reports itself unsupported.
+
+use crate::app_config::AsrConfig;
+#[cfg(feature = "whisper-inproc")]
+use crate::audio::resample::{MonoResampler, ASR_SAMPLE_RATE};
+#[cfg(feature = "whisper-inproc")]
+use crate::audio::writer::f32_samples;
+#[cfg(feature = "whisper-inproc")]
+use crate::whisper_server::{detect_physical_cores, resolve_model_path};
+#[cfg(feature = "whisper-inproc")]
+use hound::WavReader;
+use std::path::Path;
+#[cfg(feature = "whisper-inproc")]
+use std::path::PathBuf;
+#[cfg(feature = "whisper-inproc")]
+use std::sync::{Mutex, MutexGuard};
+#[cfg(feature = "whisper-inproc")]
+use std::time::Instant;
+use tauri::AppHandle;
+#[cfg(feature = "whisper-inproc")]
+use tracing::info;
+#[cfg(feature = "whisper-inproc")]
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+#[cfg(not(feature = "whisper-inproc"))]
+pub struct WhisperInprocManager;
+
+#[cfg(not(feature = "whisper-inproc"))]
+impl WhisperInprocManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn warm_up(&self, _app: &AppHandle, _config: &AsrConfig) -> Result<(), String> {
+        Err(unsupported())
+    }
+
+    pub fn transcribe(
+        &self,
+        _app: &AppHandle,
+        _config: &AsrConfig,
+        _path: &Path,
+        _prompt: Option<&str>,
+        _dedicated: bool,
+    ) -> Result<String, String> {
+        Err(unsupported())
+    }
+
+    pub fn unload(&self) {}
+}
+
+#[cfg(not(feature = "whisper-inproc"))]
+fn unsupported() -> String {
+    "unsupported provider: whisper-inproc is not built in (cargo feature `whisper-inproc`)"
+        .to_string()
+}
+
+#[cfg(feature = "whisper-inproc")]
+struct LoadedModel {
+    path: PathBuf,
+    // Keeps the model it was created from alive.
+    state: WhisperState,
+}
+
+#[cfg(feature = "whisper-inproc")]
+pub struct WhisperInprocManager {
+    model: Mutex<Option<LoadedModel>>,
+    // The last model asked for by name, e.g. by two-pass transcription, kept next to the
+    // resident one.
+    dedicated: Mutex<Option<LoadedModel>>,
+}
+
+#[cfg(feature = "whisper-inproc")]
+impl WhisperInprocManager {
+    pub fn new() -> Self {
+        Self {
+            model: Mutex::new(None),
+            dedicated: Mutex::new(None),
+        }
+    }
+
+    // Loads the model ahead of the first segment so the load is not on the hot path.
+    pub fn warm_up(&self, app: &AppHandle, config: &AsrConfig) -> Result<(), String> {
+        let model = model_path(app, config)?;
+        let mut guard = lock(&self.model)?;
+        ensure_loaded(&mut guard, &model)?;
+        Ok(())
+    }
+
+    // Segments run one at a time, since a single run already uses every physical core. With
+    // `dedicated` the model goes to its own slot, so the resident one stays loaded as well.
+    pub fn transcribe(
+        &self,
+        app: &AppHandle,
+        config: &AsrConfig,
+        path: &Path,
+        prompt: Option<&str>,
+        dedicated: bool,
+    ) -> Result<String, String> {
+        let model = model_path(app, config)?;
+        let samples = read_samples(path)?;
+        let language = config
+            .language
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("auto");
+        let translate = config.translate_to_english == Some(true);
+        let mut guard = lock(if dedicated {
+            &self.dedicated
+        } else {
+            &self.model
+        })?;
+        let loaded = ensure_loaded(&mut guard, &model)?;
+        run(&mut loaded.state, &samples, language, translate, prompt)
+    }
+
+    // Frees the models, e.g. after switching to another provider.
+    pub fn unload(&self) {
+        for slot in [&self.model, &self.dedicated] {
+            if let Ok(mut guard) = slot.lock() {
+                *guard = None;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "whisper-inproc")]
+fn lock(slot: &Mutex<Option<LoadedModel>>) -> Result<MutexGuard<'_, Option<LoadedModel>>, String> {
+    slot.lock()
+        .map_err(|_| "whisper-inproc model poisoned".to_string())
+}
+
+#[cfg(feature = "whisper-inproc")]
+fn model_path(app: &AppHandle, config: &AsrConfig) -> Result<PathBuf, String> {
+    resolve_model_path(app, config).ok_or_else(|| "whisper-inproc model path not found".to_string())
+}
+
+// Replaces the model in `slot` when the configured path changed.
+#[cfg(feature = "whisper-inproc")]
+fn ensure_loaded<'a>(
+    slot: &'a mut Option<LoadedModel>,
+    model: &Path,
+) -> Result<&'a mut LoadedModel, String> {
+    if slot.as_ref().is_none_or(|loaded| loaded.path != model) {
+        // Free the old model before the new one takes its memory.
+        *slot = None;
+        *slot = Some(load(model)?);
+    }
+    slot.as_mut()
+        .ok_or_else(|| "whisper-inproc model not loaded".to_string())
+}
+
+#[cfg(feature = "whisper-inproc")]
+fn load(model: &Path) -> Result<LoadedModel, String> {
+    let started_at = Instant::now();
+    let model_str = model.to_str().ok_or_else(|| {
+        format!(
+            "whisper-inproc model path is not UTF-8: {}",
+            model.display()
+        )
+    })?;
+    let context = WhisperContext::new_with_params(model_str, WhisperContextParameters::default())
+        .map_err(|err| format!("failed to load whisper model: {err}"))?;
+    let state = context.create_state().map_err(|err| err.to_string())?;
+    info!(
+        "whisper-inproc model loaded: {} in {}ms",
+        model.display(),
+        started_at.elapsed().as_millis()
+    );
+    Ok(LoadedModel {
+        path: model.to_path_buf(),
+        state,
+    })
+}
+
+#[cfg(feature = "whisper-inproc")]
+fn run(
+    state: &mut WhisperState,
+    samples: &[f32],
+    language: &str,
+    translate: bool,
+    prompt: Option<&str>,
+) -> Result<String, String> {
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(detect_physical_cores() as i32);
+    params.set_language(Some(language));
+    params.set_translate(translate);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    if let Some(prompt) = prompt.map(str::trim).filter(|value| !value.is_empty()) {
+        // Context is passed as a soft hint, not an instruction that forces correction.
+        params.set_initial_prompt(prompt);
+    }
+    state.full(params, samples).map_err(|err| err.to_string())?;
+
+    let count = state.full_n_segments().map_err(|err| err.to_string())?;
+    let mut text = String::new();
+    for index in 0..count {
+        text.push_str(
+            &state
+                .full_get_segment_text(index)
+                .map_err(|err| err.to_string())?,
+        );
+    }
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err("whisper-inproc returned empty text".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+// The segment as whisper takes it: mono at `ASR_SAMPLE_RATE`, whatever format it was stored in.
+#[cfg(feature = "whisper-inproc")]
+fn read_samples(path: &Path) -> Result<Vec<f32>, String> {
+    let mut reader = WavReader::open(path).map_err(|err| err.to_string())?;
+    let spec = reader.spec();
+    let samples = f32_samples(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if spec.sample_rate == ASR_SAMPLE_RATE && spec.channels == 1 {
+        return Ok(samples);
+    }
+    let mut resampler = MonoResampler::new(spec.sample_rate, spec.channels)?;
+    let mut mono = resampler.process(&samples)?;
+    mono.extend(resampler.finish()?);
+    Ok(mono)
+}
+
+#[cfg(all(test, feature = "whisper-inproc"))]
+mod tests {
+    use super::read_samples;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+
+    #[test]
+    fn reads_capture_format_segments_as_16k_mono() {
+        let path = std::env::temp_dir().join(format!("whisper_inproc_{}.wav", std::process::id()));
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for _ in 0..48_000 {
+            writer.write_sample(0.25f32).unwrap();
+            writer.write_sample(0.25f32).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let samples = read_samples(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(samples.len(), 16_000);
+        assert!((samples[8_000] - 0.25).abs() < 0.01);
+    }
+}
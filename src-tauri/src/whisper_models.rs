@@ -0,0 +1,324 @@
+// ggml models for the local Whisper backends: the bundled ones, the ones downloaded into the app
+// data directory, and switching `asr.whisperCppModelPath` between them at runtime.
+
+use crate::app_config::{load_config, load_file_config, save_config};
+use crate::defaults::DEFAULT_WHISPER_MODEL_BASE_URL;
+use crate::events::{emit_output_event, emit_ui_event, WHISPER_MODEL_DOWNLOADED};
+use crate::http;
+use crate::redact::redact;
+use crate::whisper_inproc::WhisperInprocManager;
+use crate::whisper_pipe::WhisperPipeManager;
+use crate::whisper_server::{resolve_model_path, resolve_path_with_context, WhisperServerManager};
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{error, info};
+
+const MODELS_DIR: &str = "models";
+// Bundled models, looked up like `whisperCppModelPath`.
+const BUNDLED_DIRS: [&str; 2] = ["resources/models", "models"];
+const PROGRESS_INTERVAL_MS: u64 = 250;
+
+// Offered for download even before they exist locally.
+const CATALOG: [&str; 12] = [
+    "tiny",
+    "tiny.en",
+    "base",
+    "base.en",
+    "small",
+    "small.en",
+    "small-q5_1",
+    "medium",
+    "medium-q5_0",
+    "large-v3",
+    "large-v3-turbo",
+    "large-v3-turbo-q5_0",
+];
+
+static DOWNLOADING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperModel {
+    // `small-q5_1` for `ggml-small-q5_1.bin`.
+    pub name: String,
+    pub path: Option<String>,
+    pub bytes: Option<u64>,
+    // `managed` (downloaded by the app), `bundled`, or `available` for a catalog model not yet
+    // downloaded.
+    pub source: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgress {
+    name: String,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+}
+
+// Model names become file names and URL paths, so they are limited to what whisper.cpp publishes.
+fn check_model_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'))
+    {
+        return Err(format!("invalid model name: {name}"));
+    }
+    Ok(())
+}
+
+fn file_name(name: &str) -> String {
+    format!("ggml-{name}.bin")
+}
+
+fn model_name(path: &Path) -> Option<String> {
+    let file = path.file_name()?.to_str()?;
+    let name = file.strip_prefix("ggml-")?.strip_suffix(".bin")?;
+    check_model_name(name).ok()?;
+    Some(name.to_string())
+}
+
+fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let base = app.path().app_data_dir().map_err(|err| err.to_string())?;
+    Ok(base.join(MODELS_DIR))
+}
+
+fn scan(dir: &Path, source: &str, models: &mut BTreeMap<String, WhisperModel>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = model_name(&path) else {
+            continue;
+        };
+        // Downloads are scanned first, so they win over a bundled copy of the same model.
+        models.entry(name.clone()).or_insert_with(|| WhisperModel {
+            name,
+            bytes: entry.metadata().ok().map(|meta| meta.len()),
+            path: Some(path.to_string_lossy().to_string()),
+            source: source.to_string(),
+            active: false,
+        });
+    }
+}
+
+fn local_models(app: &AppHandle) -> Result<BTreeMap<String, WhisperModel>, String> {
+    let mut models = BTreeMap::new();
+    scan(&models_dir(app)?, "managed", &mut models);
+    for dir in BUNDLED_DIRS {
+        if let Some(dir) = resolve_path_with_context(app, dir) {
+            scan(&dir, "bundled", &mut models);
+        }
+    }
+    Ok(models)
+}
+
+// Path of a downloaded or bundled model, by name.
+pub(crate) fn find_model(app: &AppHandle, name: &str) -> Option<PathBuf> {
+    check_model_name(name).ok()?;
+    local_models(app)
+        .ok()?
+        .remove(name)
+        .and_then(|model| model.path)
+        .map(PathBuf::from)
+}
+
+#[tauri::command]
+pub fn list_whisper_models(app: AppHandle) -> Result<Vec<WhisperModel>, String> {
+    let mut models = local_models(&app)?;
+    for name in CATALOG {
+        models
+            .entry(name.to_string())
+            .or_insert_with(|| WhisperModel {
+                name: name.to_string(),
+                path: None,
+                bytes: None,
+                source: "available".to_string(),
+                active: false,
+            });
+    }
+    let asr = load_config()?.asr.unwrap_or_default();
+    let active = resolve_model_path(&app, &asr).and_then(|path| fs::canonicalize(path).ok());
+    for model in models.values_mut() {
+        model.active = active.is_some()
+            && model
+                .path
+                .as_ref()
+                .and_then(|path| fs::canonicalize(path).ok())
+                == active;
+    }
+    Ok(models.into_values().collect())
+}
+
+// Streams into a `.part` file that is renamed once complete, so an interrupted download never
+// shows up as a model. Progress goes to the output window only.
+#[tauri::command]
+pub async fn download_whisper_model(app: AppHandle, name: String) -> Result<WhisperModel, String> {
+    let name = name.trim().to_string();
+    check_model_name(&name)?;
+    {
+        let mut downloading = DOWNLOADING
+            .lock()
+            .map_err(|_| "model downloads poisoned".to_string())?;
+        if !downloading.insert(name.clone()) {
+            return Err(format!("{name} is already downloading"));
+        }
+    }
+    let result = download(&app, &name).await;
+    if let Ok(mut downloading) = DOWNLOADING.lock() {
+        downloading.remove(&name);
+    }
+    let model = result?;
+    emit_ui_event(&app, WHISPER_MODEL_DOWNLOADED, model.clone());
+    Ok(model)
+}
+
+async fn download(app: &AppHandle, name: &str) -> Result<WhisperModel, String> {
+    let dir = models_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    let target = dir.join(file_name(name));
+    let part = dir.join(format!("{}.part", file_name(name)));
+    let base_url = load_config()?
+        .asr
+        .and_then(|asr| asr.whisper_model_base_url)
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_WHISPER_MODEL_BASE_URL.to_string());
+    let url = format!("{}/{}", base_url.trim_end_matches('/'), file_name(name));
+    info!("downloading whisper model {name} from {}", redact(&url));
+
+    // Large models take minutes, so no overall timeout.
+    let client = http::client(None)?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|err| redact(&err.to_string()).into_owned())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("model download failed: {status}"));
+    }
+    let total_bytes = response.content_length();
+    let mut file = fs::File::create(&part).map_err(|err| err.to_string())?;
+    let mut stream = response.bytes_stream();
+    let mut downloaded_bytes = 0u64;
+    let mut last_progress = Instant::now();
+    let written: Result<(), String> = async {
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|err| err.to_string())?;
+            file.write_all(&chunk).map_err(|err| err.to_string())?;
+            downloaded_bytes += chunk.len() as u64;
+            if last_progress.elapsed() >= Duration::from_millis(PROGRESS_INTERVAL_MS) {
+                last_progress = Instant::now();
+                emit_progress(app, name, downloaded_bytes, total_bytes);
+            }
+        }
+        file.flush().map_err(|err| err.to_string())?;
+        if total_bytes.is_some_and(|total| total != downloaded_bytes) {
+            return Err("model download incomplete".to_string());
+        }
+        Ok(())
+    }
+    .await;
+    drop(file);
+    if let Err(err) = written {
+        let _ = fs::remove_file(&part);
+        return Err(err);
+    }
+    emit_progress(app, name, downloaded_bytes, total_bytes);
+    fs::rename(&part, &target).map_err(|err| err.to_string())?;
+    info!("whisper model {name} saved to {}", target.display());
+    Ok(WhisperModel {
+        name: name.to_string(),
+        path: Some(target.to_string_lossy().to_string()),
+        bytes: Some(downloaded_bytes),
+        source: "managed".to_string(),
+        active: false,
+    })
+}
+
+fn emit_progress(app: &AppHandle, name: &str, downloaded_bytes: u64, total_bytes: Option<u64>) {
+    emit_output_event(
+        app,
+        "whisper_model_download_progress",
+        DownloadProgress {
+            name: name.to_string(),
+            downloaded_bytes,
+            total_bytes,
+        },
+    );
+}
+
+// Saves the model as `asr.whisperCppModelPath`. Pipe workers and the in-process model pick it up
+// on the next segment; a running whisper-server is replaced right away.
+#[tauri::command]
+pub fn set_whisper_model(app: AppHandle, name: String) -> Result<WhisperModel, String> {
+    let name = name.trim().to_string();
+    check_model_name(&name)?;
+    let mut model = local_models(&app)?
+        .remove(&name)
+        .ok_or_else(|| format!("{name} is not downloaded"))?;
+    let path = model
+        .path
+        .clone()
+        .ok_or_else(|| format!("{name} is not downloaded"))?;
+
+    let mut config = load_file_config()?;
+    let mut asr = config.asr.unwrap_or_default();
+    asr.whisper_cpp_model_path = Some(path);
+    config.asr = Some(asr);
+    save_config(&config)?;
+    info!("whisper model switched to {name}");
+
+    if let Some(manager) = app.try_state::<WhisperPipeManager>() {
+        manager.stop();
+    }
+    if let Some(manager) = app.try_state::<WhisperInprocManager>() {
+        manager.unload();
+    }
+    let asr = load_config()?.asr.unwrap_or_default();
+    let app_handle = app.clone();
+    std::thread::spawn(move || {
+        if let Some(manager) = app_handle.try_state::<WhisperServerManager>() {
+            if let Err(err) = manager.restart(&app_handle, &asr) {
+                error!("whisper-server restart failed: {err}");
+            }
+        }
+    });
+    model.active = true;
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_model_name, model_name};
+    use std::path::Path;
+
+    #[test]
+    fn model_names_round_trip_through_file_names() {
+        assert_eq!(
+            model_name(Path::new("/data/models/ggml-small-q5_1.bin")).as_deref(),
+            Some("small-q5_1")
+        );
+        assert_eq!(
+            model_name(Path::new("ggml-large-v3-turbo.bin")).as_deref(),
+            Some("large-v3-turbo")
+        );
+        assert_eq!(model_name(Path::new("ggml-base.bin.part")), None);
+        assert_eq!(model_name(Path::new("pyannote_embedding.onnx")), None);
+
+        assert!(check_model_name("tiny.en").is_ok());
+        assert!(check_model_name("../secrets").is_err());
+        assert!(check_model_name("small/q5").is_err());
+        assert!(check_model_name("").is_err());
+    }
+}
@@ -0,0 +1,385 @@
+// Pool of long-lived whisper pipe workers, so the model is loaded once per process instead of
+// once per segment.
+//
+// Protocol (all integers little-endian): every message is a frame of a u32 byte length followed by
+// the payload. A request is two frames: a JSON header (`{"language": "ja", "prompt": "..."}`) and
+// the WAV bytes. The worker answers with one JSON frame, `{"text": "..."}` or `{"error": "..."}`.
+
+use crate::app_config::AsrConfig;
+use crate::defaults::DEFAULT_WHISPER_PIPE_WORKERS;
+use crate::whisper_server::{
+    detect_physical_cores, resolve_model_path, resolve_path_with_context, spawn_reader,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+// Responses larger than this mean the stream is out of sync rather than a long transcript.
+const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Serialize)]
+struct RequestHeader<'a> {
+    language: Option<&'a str>,
+    prompt: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    text: Option<String>,
+    error: Option<String>,
+}
+
+pub struct PipeWorker {
+    id: u64,
+    stdin: BufWriter<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl PipeWorker {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn request(
+        &mut self,
+        wav: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String, String> {
+        let header = serde_json::to_vec(&RequestHeader { language, prompt })
+            .map_err(|err| err.to_string())?;
+        write_frame(&mut self.stdin, &header).map_err(|err| err.to_string())?;
+        write_frame(&mut self.stdin, wav).map_err(|err| err.to_string())?;
+        self.stdin.flush().map_err(|err| err.to_string())?;
+        let payload = read_frame(&mut self.stdout).map_err(|err| err.to_string())?;
+        let response: Response = serde_json::from_slice(&payload).map_err(|err| err.to_string())?;
+        if let Some(error) = response.error.filter(|value| !value.trim().is_empty()) {
+            return Err(error);
+        }
+        let text = response.text.unwrap_or_default();
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Err("whisper-pipe returned empty text".to_string());
+        }
+        Ok(trimmed.to_string())
+    }
+}
+
+struct PoolState {
+    idle: Vec<PipeWorker>,
+    // Every live process, idle or checked out, so `stop` and timeouts can kill busy ones too.
+    children: HashMap<u64, Child>,
+    // One-off workers on another model; they do not count against the pool and exit on checkin.
+    dedicated: HashSet<u64>,
+    spawning: usize,
+    next_id: u64,
+}
+
+pub struct WhisperPipeManager {
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+impl WhisperPipeManager {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                children: HashMap::new(),
+                dedicated: HashSet::new(),
+                spawning: 0,
+                next_id: 0,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    // Starts one worker ahead of the first segment so the model load is not on the hot path.
+    pub fn warm_up(&self, app: &AppHandle, config: &AsrConfig) -> Result<(), String> {
+        let worker = self.checkout(app, config, Duration::from_secs(0))?;
+        self.checkin(worker);
+        Ok(())
+    }
+
+    // Runs one segment on a worker the caller keeps checked out, e.g. a dedicated one held loaded
+    // across segments. As with `transcribe`, a worker that fails is killed and must be dropped.
+    pub fn transcribe_kept(
+        &self,
+        worker: &mut PipeWorker,
+        path: &Path,
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String, String> {
+        let wav = std::fs::read(path).map_err(|err| err.to_string())?;
+        let result = worker.request(&wav, language, prompt);
+        if result.is_err() {
+            self.kill(worker.id);
+        }
+        result
+    }
+
+    // Takes an idle worker, spawning one while the pool is below its size, or waits up to
+    // `timeout` for one to be returned.
+    pub fn checkout(
+        &self,
+        app: &AppHandle,
+        config: &AsrConfig,
+        timeout: Duration,
+    ) -> Result<PipeWorker, String> {
+        let size = pool_size(config);
+        let deadline = Instant::now() + timeout;
+        let mut guard = self.lock();
+        loop {
+            while let Some(worker) = guard.idle.pop() {
+                if is_alive(&mut guard, worker.id) {
+                    return Ok(worker);
+                }
+                guard.children.remove(&worker.id);
+            }
+            if guard.children.len() - guard.dedicated.len() + guard.spawning < size {
+                guard.spawning += 1;
+                guard.next_id += 1;
+                let id = guard.next_id;
+                drop(guard);
+                let result = spawn_worker(app, config, id, size);
+                guard = self.lock();
+                guard.spawning -= 1;
+                return match result {
+                    Ok((worker, child)) => {
+                        guard.children.insert(id, child);
+                        Ok(worker)
+                    }
+                    Err(err) => {
+                        self.available.notify_one();
+                        Err(err)
+                    }
+                };
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err("whisper-pipe workers are all busy".to_string());
+            }
+            guard = match self.available.wait_timeout(guard, deadline - now) {
+                Ok((guard, _)) => guard,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+    }
+
+    // Spawns a worker for `config` outside the pool, e.g. to re-run one segment on a bigger model
+    // than the pool has loaded. It is killed when checked in.
+    pub fn checkout_dedicated(
+        &self,
+        app: &AppHandle,
+        config: &AsrConfig,
+    ) -> Result<PipeWorker, String> {
+        let id = {
+            let mut guard = self.lock();
+            guard.next_id += 1;
+            guard.next_id
+        };
+        let (worker, child) = spawn_worker(app, config, id, 1)?;
+        let mut guard = self.lock();
+        guard.children.insert(id, child);
+        guard.dedicated.insert(id);
+        Ok(worker)
+    }
+
+    pub fn checkin(&self, worker: PipeWorker) {
+        let mut guard = self.lock();
+        if guard.dedicated.remove(&worker.id) {
+            if let Some(mut child) = guard.children.remove(&worker.id) {
+                let _ = child.kill();
+            }
+        } else if guard.children.contains_key(&worker.id) {
+            guard.idle.push(worker);
+        }
+        self.available.notify_one();
+    }
+
+    // Blocking; a worker whose request fails at the I/O or protocol level is discarded because its
+    // stream position is unknown.
+    pub fn transcribe(
+        &self,
+        mut worker: PipeWorker,
+        path: &Path,
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String, String> {
+        let wav = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.checkin(worker);
+                return Err(err.to_string());
+            }
+        };
+        match worker.request(&wav, language, prompt) {
+            Ok(text) => {
+                self.checkin(worker);
+                Ok(text)
+            }
+            Err(err) => {
+                self.kill(worker.id);
+                Err(err)
+            }
+        }
+    }
+
+    // Kills a worker, e.g. one that did not answer in time; its pool slot is freed immediately.
+    pub fn kill(&self, id: u64) {
+        let mut guard = self.lock();
+        guard.idle.retain(|worker| worker.id != id);
+        guard.dedicated.remove(&id);
+        if let Some(mut child) = guard.children.remove(&id) {
+            let _ = child.kill();
+        }
+        self.available.notify_one();
+    }
+
+    pub fn stop(&self) {
+        let mut guard = self.lock();
+        guard.idle.clear();
+        guard.dedicated.clear();
+        for (_, mut child) in guard.children.drain() {
+            let _ = child.kill();
+        }
+        self.available.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Drop for WhisperPipeManager {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn is_alive(state: &mut PoolState, id: u64) -> bool {
+    state
+        .children
+        .get_mut(&id)
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+}
+
+fn pool_size(config: &AsrConfig) -> usize {
+    config
+        .whisper_pipe_workers
+        .unwrap_or(DEFAULT_WHISPER_PIPE_WORKERS)
+        .max(1)
+}
+
+fn spawn_worker(
+    app: &AppHandle,
+    config: &AsrConfig,
+    id: u64,
+    size: usize,
+) -> Result<(PipeWorker, Child), String> {
+    let exe = resolve_pipe_exe(app, config)
+        .ok_or_else(|| "whisper-pipe executable not found".to_string())?;
+    let model = resolve_model_path(app, config)
+        .ok_or_else(|| "whisper-pipe model path not found".to_string())?;
+    // Workers run concurrently, so they split the cores instead of each taking all of them.
+    let threads = (detect_physical_cores() / size).max(1);
+
+    let mut cmd = Command::new(&exe);
+    cmd.arg("-m")
+        .arg(&model)
+        .arg("-t")
+        .arg(threads.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = exe.parent() {
+        cmd.current_dir(dir);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("failed to spawn whisper-pipe: {err}"))?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| "whisper-pipe stdin unavailable".to_string())?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "whisper-pipe stdout unavailable".to_string())?;
+    if let Some(stderr) = child.stderr.take() {
+        spawn_reader(stderr, "whisper-pipe");
+    }
+    info!("whisper-pipe worker {id} started: threads={threads}");
+
+    let worker = PipeWorker {
+        id,
+        stdin: BufWriter::new(stdin),
+        stdout: BufReader::new(stdout),
+    };
+    Ok((worker, child))
+}
+
+fn resolve_pipe_exe(app: &AppHandle, config: &AsrConfig) -> Option<PathBuf> {
+    let raw = config
+        .whisper_pipe_path
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "whisper-pipe.exe".to_string());
+    let found = resolve_path_with_context(app, &raw);
+    if found.is_none() {
+        warn!("whisper-pipe executable not found: {raw}");
+    }
+    found
+}
+
+fn write_frame(writer: &mut impl Write, payload: &[u8]) -> std::io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "frame too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(payload)
+}
+
+fn read_frame(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("whisper-pipe frame too large: {len} bytes"),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_frame, write_frame};
+    use std::io::Cursor;
+
+    #[test]
+    fn frames_round_trip_and_reject_oversized_lengths() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, b"{\"text\":\"hi\"}").unwrap();
+        write_frame(&mut buffer, b"").unwrap();
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(read_frame(&mut reader).unwrap(), b"{\"text\":\"hi\"}");
+        assert!(read_frame(&mut reader).unwrap().is_empty());
+        assert!(read_frame(&mut reader).is_err());
+
+        let mut reader = Cursor::new(u32::MAX.to_le_bytes().to_vec());
+        assert!(read_frame(&mut reader).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use crate::app_config::AsrConfig;
-use std::io::{BufRead, BufReader, Read};
+use crate::events::{emit_ui_event, ASR_BACKEND_RESTARTED};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
@@ -7,15 +9,31 @@ use std::sync::Mutex;
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
 
 const DEFAULT_START_TIMEOUT_SECS: u64 = 30;
+const WATCHDOG_INTERVAL_SECS: u64 = 5;
+const HEALTH_TIMEOUT_SECS: u64 = 3;
+// A long inference can keep the server from answering for a while; only a run of missed pings
+// counts as hung.
+const MAX_MISSED_PINGS: u32 = 3;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum ServerDevice {
+pub(crate) enum ServerDevice {
     Gpu,
     Cpu,
 }
 
+impl ServerDevice {
+    // As written to `asr.whisperServerDevice`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ServerDevice::Gpu => "gpu",
+            ServerDevice::Cpu => "cpu",
+        }
+    }
+}
+
 enum DevicePreference {
     Auto,
     Gpu,
@@ -25,8 +43,38 @@ enum DevicePreference {
 struct ServerState {
     child: Option<Child>,
     url: Option<String>,
+    addr: Option<SocketAddr>,
     device: Option<ServerDevice>,
+    // Settings of the running server, reused when the watchdog restarts it.
+    config: Option<AsrConfig>,
     starting: bool,
+    watchdog: bool,
+}
+
+enum Health {
+    Healthy,
+    // The server was killed and has to be started again.
+    Failed(BackendFailure),
+    // Stopped on purpose; nothing left to watch.
+    Stopped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendFailure {
+    // The process is gone, e.g. it crashed.
+    Exited,
+    // The process is alive but stopped answering health pings.
+    Unresponsive,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendRestart {
+    pub backend: &'static str,
+    pub reason: BackendFailure,
+    // The new server, or `None` when it could not be started again.
+    pub url: Option<String>,
+    pub error: Option<String>,
 }
 
 pub struct WhisperServerManager {
@@ -39,8 +87,11 @@ impl WhisperServerManager {
             state: Mutex::new(ServerState {
                 child: None,
                 url: None,
+                addr: None,
                 device: None,
+                config: None,
                 starting: false,
+                watchdog: false,
             }),
         }
     }
@@ -55,6 +106,7 @@ impl WhisperServerManager {
             if let Ok(Some(_)) = child.try_wait() {
                 guard.child = None;
                 guard.url = None;
+                guard.addr = None;
                 guard.device = None;
             }
         }
@@ -85,10 +137,17 @@ impl WhisperServerManager {
                     ServerDevice::Gpu => "GPU",
                     ServerDevice::Cpu => "CPU",
                 };
-                eprintln!("whisper-server started ({device_label}) at {}", handle.url);
+                info!("whisper-server started ({device_label}) at {}", handle.url);
                 guard.url = Some(handle.url.clone());
+                guard.addr = Some(handle.addr);
                 guard.child = Some(handle.child);
                 guard.device = Some(handle.device);
+                guard.config = Some(config.clone());
+                if !guard.watchdog {
+                    guard.watchdog = true;
+                    let app = app.clone();
+                    thread::spawn(move || run_watchdog(app));
+                }
                 Ok(handle.url)
             }
             Err(err) => Err(err),
@@ -101,10 +160,130 @@ impl WhisperServerManager {
                 let _ = child.kill();
             }
             guard.url = None;
+            guard.addr = None;
             guard.device = None;
             guard.starting = false;
         }
     }
+
+    // Replaces a running server with one started from `config`, e.g. after the model changed.
+    // `None` when no server was running; the next segment starts one as usual.
+    pub fn restart(&self, app: &AppHandle, config: &AsrConfig) -> Result<Option<String>, String> {
+        let running = self
+            .state
+            .lock()
+            .map(|guard| guard.addr.is_some())
+            .unwrap_or(false);
+        self.stop();
+        if !running {
+            return Ok(None);
+        }
+        self.ensure_started(app, config).map(Some)
+    }
+
+    // Kills a dead or hung server so the next start replaces it.
+    fn check(&self, missed_pings: &mut u32) -> Health {
+        let (addr, exited) = {
+            let Ok(mut guard) = self.state.lock() else {
+                return Health::Stopped;
+            };
+            if guard.starting {
+                return Health::Healthy;
+            }
+            // Cleared under the same lock `ensure_started` checks, so a new server always gets a
+            // watchdog.
+            let Some(addr) = guard.addr else {
+                guard.watchdog = false;
+                return Health::Stopped;
+            };
+            let exited = guard
+                .child
+                .as_mut()
+                .is_none_or(|child| !matches!(child.try_wait(), Ok(None)));
+            (addr, exited)
+        };
+        let failure = if exited {
+            BackendFailure::Exited
+        } else if ping(addr, Duration::from_secs(HEALTH_TIMEOUT_SECS)) {
+            *missed_pings = 0;
+            return Health::Healthy;
+        } else {
+            *missed_pings += 1;
+            if *missed_pings < MAX_MISSED_PINGS {
+                return Health::Healthy;
+            }
+            BackendFailure::Unresponsive
+        };
+        *missed_pings = 0;
+        let Ok(mut guard) = self.state.lock() else {
+            return Health::Stopped;
+        };
+        // Stopped or replaced while the ping was in flight.
+        if guard.addr != Some(addr) {
+            return Health::Healthy;
+        }
+        if let Some(mut child) = guard.child.take() {
+            let _ = child.kill();
+        }
+        guard.url = None;
+        guard.addr = None;
+        guard.device = None;
+        Health::Failed(failure)
+    }
+}
+
+// Runs while a server is up. When a restart fails the next segment tries again, and the watchdog
+// exits unless that brings a server back.
+fn run_watchdog(app: AppHandle) {
+    let mut missed_pings = 0;
+    loop {
+        thread::sleep(Duration::from_secs(WATCHDOG_INTERVAL_SECS));
+        let Some(manager) = app.try_state::<WhisperServerManager>() else {
+            return;
+        };
+        let reason = match manager.check(&mut missed_pings) {
+            Health::Healthy => continue,
+            Health::Stopped => return,
+            Health::Failed(reason) => reason,
+        };
+        warn!("whisper-server {reason:?}, restarting");
+        let config = manager
+            .state
+            .lock()
+            .ok()
+            .and_then(|guard| guard.config.clone())
+            .unwrap_or_default();
+        let result = manager.ensure_started(&app, &config);
+        if let Err(err) = &result {
+            warn!("whisper-server restart failed: {err}");
+        }
+        emit_ui_event(
+            &app,
+            ASR_BACKEND_RESTARTED,
+            BackendRestart {
+                backend: "whisper-server",
+                reason,
+                url: result.as_ref().ok().cloned(),
+                error: result.err(),
+            },
+        );
+    }
+}
+
+// Any HTTP reply counts: builds without `/health` answer 404, which still proves the server is
+// serving requests.
+fn ping(addr: SocketAddr, timeout: Duration) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, timeout) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    let request = format!("GET /health HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut head = [0u8; 5];
+    stream.read_exact(&mut head).is_ok() && &head == b"HTTP/"
 }
 
 impl Drop for WhisperServerManager {
@@ -117,9 +296,10 @@ impl Drop for WhisperServerManager {
     }
 }
 
-struct ServerHandle {
-    child: Child,
-    url: String,
+pub(crate) struct ServerHandle {
+    pub(crate) child: Child,
+    pub(crate) url: String,
+    addr: SocketAddr,
     device: ServerDevice,
 }
 
@@ -168,31 +348,64 @@ fn start_server(app: &AppHandle, config: &AsrConfig) -> Result<ServerHandle, Str
         DevicePreference::Gpu => {
             let exe = resolve_server_exe(app, ServerDevice::Gpu, config)
                 .ok_or_else(|| "whisper-server gpu executable not found".to_string())?;
-            return spawn_server(ServerDevice::Gpu, &exe, &model);
+            return spawn_server(
+                ServerDevice::Gpu,
+                &exe,
+                &model,
+                server_threads(config, ServerDevice::Gpu),
+            );
         }
         DevicePreference::Cpu => {
             let exe = resolve_server_exe(app, ServerDevice::Cpu, config)
                 .ok_or_else(|| "whisper-server cpu executable not found".to_string())?;
-            return spawn_server(ServerDevice::Cpu, &exe, &model);
+            return spawn_server(
+                ServerDevice::Cpu,
+                &exe,
+                &model,
+                server_threads(config, ServerDevice::Cpu),
+            );
         }
         DevicePreference::Auto => {}
     }
 
     if let Some(exe) = resolve_server_exe(app, ServerDevice::Gpu, config) {
-        match spawn_server(ServerDevice::Gpu, &exe, &model) {
+        match spawn_server(
+            ServerDevice::Gpu,
+            &exe,
+            &model,
+            server_threads(config, ServerDevice::Gpu),
+        ) {
             Ok(handle) => return Ok(handle),
             Err(err) => {
-                eprintln!("whisper-server GPU failed: {err}");
+                warn!("whisper-server GPU failed: {err}");
             }
         }
     }
 
     let exe = resolve_server_exe(app, ServerDevice::Cpu, config)
         .ok_or_else(|| "whisper-server cpu executable not found".to_string())?;
-    spawn_server(ServerDevice::Cpu, &exe, &model)
+    spawn_server(
+        ServerDevice::Cpu,
+        &exe,
+        &model,
+        server_threads(config, ServerDevice::Cpu),
+    )
+}
+
+// `asr.whisperServerThreads`, written by the first-run benchmark, or the table below.
+fn server_threads(config: &AsrConfig, device: ServerDevice) -> usize {
+    config
+        .whisper_server_threads
+        .filter(|threads| *threads > 0)
+        .unwrap_or_else(|| recommend_threads(device, detect_physical_cores()))
 }
 
-fn spawn_server(device: ServerDevice, exe: &Path, model: &Path) -> Result<ServerHandle, String> {
+pub(crate) fn spawn_server(
+    device: ServerDevice,
+    exe: &Path,
+    model: &Path,
+    threads: usize,
+) -> Result<ServerHandle, String> {
     if !exe.exists() {
         return Err(format!("whisper-server not found: {}", exe.display()));
     }
@@ -203,12 +416,11 @@ fn spawn_server(device: ServerDevice, exe: &Path, model: &Path) -> Result<Server
     let port = pick_port()?;
     let url = format!("http://127.0.0.1:{port}/inference");
     let physical_cores = detect_physical_cores();
-    let threads = recommend_threads(device, physical_cores);
     let mode = match device {
         ServerDevice::Gpu => "GPU",
         ServerDevice::Cpu => "CPU",
     };
-    eprintln!(
+    info!(
     "whisper-server threads auto-config: mode={mode}, physical_cores={physical_cores}, -t={threads}"
   );
 
@@ -251,10 +463,15 @@ fn spawn_server(device: ServerDevice, exe: &Path, model: &Path) -> Result<Server
         Duration::from_secs(DEFAULT_START_TIMEOUT_SECS),
     )?;
 
-    Ok(ServerHandle { child, url, device })
+    Ok(ServerHandle {
+        child,
+        url,
+        addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+        device,
+    })
 }
 
-fn detect_physical_cores() -> usize {
+pub(crate) fn detect_physical_cores() -> usize {
     let physical = num_cpus::get_physical();
     if physical > 0 {
         return physical;
@@ -262,7 +479,7 @@ fn detect_physical_cores() -> usize {
     num_cpus::get().max(1)
 }
 
-fn recommend_threads(device: ServerDevice, physical_cores: usize) -> usize {
+pub(crate) fn recommend_threads(device: ServerDevice, physical_cores: usize) -> usize {
     match device {
         ServerDevice::Gpu => match physical_cores {
             0..=2 => 2,
@@ -288,7 +505,7 @@ fn recommend_threads(device: ServerDevice, physical_cores: usize) -> usize {
     }
 }
 
-fn spawn_reader<R: Read + Send + 'static>(reader: R, label: &'static str) {
+pub(crate) fn spawn_reader<R: Read + Send + 'static>(reader: R, label: &'static str) {
     thread::spawn(move || {
         let mut buf = BufReader::new(reader);
         let mut line = String::new();
@@ -300,7 +517,7 @@ fn spawn_reader<R: Read + Send + 'static>(reader: R, label: &'static str) {
             }
             let text = line.trim();
             if !text.is_empty() {
-                eprintln!("{label}: {text}");
+                debug!("{label}: {text}");
             }
         }
     });
@@ -330,7 +547,7 @@ fn pick_port() -> Result<u16, String> {
     Ok(port)
 }
 
-fn resolve_server_exe(
+pub(crate) fn resolve_server_exe(
     app: &AppHandle,
     device: ServerDevice,
     config: &AsrConfig,
@@ -437,7 +654,7 @@ fn resolve_server_path_override(
     resolve_path_with_context(app, &raw)
 }
 
-fn resolve_path_with_context(app: &AppHandle, raw: &str) -> Option<PathBuf> {
+pub(crate) fn resolve_path_with_context(app: &AppHandle, raw: &str) -> Option<PathBuf> {
     let raw = raw.trim();
     if raw.is_empty() {
         return None;
@@ -474,7 +691,7 @@ fn resolve_path_with_context(app: &AppHandle, raw: &str) -> Option<PathBuf> {
     candidates.into_iter().find(|path| path.exists())
 }
 
-fn resolve_model_path(app: &AppHandle, config: &AsrConfig) -> Option<PathBuf> {
+pub(crate) fn resolve_model_path(app: &AppHandle, config: &AsrConfig) -> Option<PathBuf> {
     let raw = config
         .whisper_cpp_model_path
         .clone()
@@ -535,3 +752,32 @@ fn resolve_model_path(app: &AppHandle, config: &AsrConfig) -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ping;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn any_http_reply_counts_as_alive() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 256];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        });
+        assert!(ping(addr, Duration::from_secs(1)));
+
+        // Accepts the connection but never answers, like a server stuck in a request.
+        let hung = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(!ping(
+            hung.local_addr().unwrap(),
+            Duration::from_millis(200)
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::app_config::{load_config, WordFilterConfig};
+use crate::asr::AsrState;
+use crate::defaults::{
+    DEFAULT_TRANSLATE_TARGET_LANGUAGE, DEFAULT_WORD_FILTER_ENABLED, DEFAULT_WORD_FILTER_MODE,
+    DEFAULT_WORD_FILTER_REPLACEMENT,
+};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    // Each character becomes `*`, so the caption keeps its length.
+    Mask,
+    // The word's entry in `replacements`, or the shared `replacement`.
+    Replace,
+}
+
+pub fn is_valid_mode(raw: &str) -> bool {
+    matches!(raw.trim().to_lowercase().as_str(), "mask" | "replace")
+}
+
+// Masks profanity and sensitive words in captions, e.g. while the overlay is screen-shared.
+pub struct WordFilter {
+    mode: Mode,
+    replacement: String,
+    replacements: HashMap<String, String>,
+    // One alternation per language key; `*` applies to every language.
+    lists: Vec<(String, Regex)>,
+}
+
+impl WordFilter {
+    // `None` when the filter is off or has no words.
+    pub fn from_config() -> Option<Self> {
+        let config = load_config().ok()?.word_filter.unwrap_or_default();
+        if !config.enabled.unwrap_or(DEFAULT_WORD_FILTER_ENABLED) {
+            return None;
+        }
+        let filter = Self::from_settings(&config);
+        (!filter.lists.is_empty()).then_some(filter)
+    }
+
+    pub(crate) fn from_settings(config: &WordFilterConfig) -> Self {
+        let mode = match config
+            .mode
+            .as_deref()
+            .unwrap_or(DEFAULT_WORD_FILTER_MODE)
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "replace" => Mode::Replace,
+            _ => Mode::Mask,
+        };
+        let lists = config
+            .words
+            .iter()
+            .flatten()
+            .filter_map(|(language, words)| {
+                Some((language.trim().to_lowercase(), word_pattern(words)?))
+            })
+            .collect();
+        Self {
+            mode,
+            replacement: config
+                .replacement
+                .clone()
+                .unwrap_or_else(|| DEFAULT_WORD_FILTER_REPLACEMENT.to_string()),
+            replacements: config
+                .replacements
+                .iter()
+                .flatten()
+                .map(|(word, replacement)| (word.trim().to_lowercase(), replacement.clone()))
+                .collect(),
+            lists,
+        }
+    }
+
+    // An unknown language (`None`, `auto`) gets every list.
+    pub fn apply(&self, text: &str, language: Option<&str>) -> String {
+        let language = language
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty() && value != "auto");
+        let mut output = text.to_string();
+        for (key, pattern) in &self.lists {
+            if !language_matches(key, language.as_deref()) {
+                continue;
+            }
+            output = pattern
+                .replace_all(&output, |captures: &Captures| {
+                    let word = &captures[0];
+                    match self.mode {
+                        Mode::Mask => "*".repeat(word.chars().count()),
+                        Mode::Replace => self
+                            .replacements
+                            .get(&word.to_lowercase())
+                            .unwrap_or(&self.replacement)
+                            .clone(),
+                    }
+                })
+                .into_owned();
+        }
+        output
+    }
+}
+
+// For streaming callers that load the filter once and apply it per chunk.
+pub fn apply_to(filter: Option<&WordFilter>, text: &str, language: Option<&str>) -> String {
+    match filter {
+        Some(filter) => filter.apply(text, language),
+        None => text.to_string(),
+    }
+}
+
+// Segment and window transcripts, in the ASR language.
+pub fn filter_transcript(app: &AppHandle, text: &str) -> String {
+    let Some(filter) = WordFilter::from_config() else {
+        return text.to_string();
+    };
+    let language = app.try_state::<AsrState>().map(|state| state.language());
+    filter.apply(text, language.as_deref())
+}
+
+// Translations, in the configured target language unless the caller knows it.
+pub fn filter_translation(text: &str, target_language: Option<&str>) -> String {
+    let Some(filter) = WordFilter::from_config() else {
+        return text.to_string();
+    };
+    let target_language = target_language.map(str::to_string).or_else(|| {
+        load_config()
+            .ok()
+            .and_then(|config| config.translate)
+            .and_then(|translate| translate.target_language)
+            .or_else(|| Some(DEFAULT_TRANSLATE_TARGET_LANGUAGE.to_string()))
+    });
+    filter.apply(text, target_language.as_deref())
+}
+
+fn language_matches(key: &str, language: Option<&str>) -> bool {
+    let Some(language) = language else {
+        return true;
+    };
+    key == "*"
+        || key == language
+        || language
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(['-', '_']))
+}
+
+fn word_pattern(words: &[String]) -> Option<Regex> {
+    let mut words = words
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+    // Longest first, so a phrase wins over a word it contains.
+    words.sort_by_key(|word| std::cmp::Reverse(word.chars().count()));
+    let alternatives = words
+        .iter()
+        .map(|word| {
+            let escaped = regex::escape(word);
+            // Word boundaries only for alphabetic words; CJK text has no spaces to anchor on.
+            if word.is_ascii() {
+                format!(r"\b{escaped}\b")
+            } else {
+                escaped
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!("(?i)(?:{alternatives})")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordFilter;
+    use crate::app_config::WordFilterConfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn masks_or_replaces_words_from_matching_language_lists() {
+        let words = HashMap::from([
+            (
+                "en".to_string(),
+                vec!["damn".to_string(), "hell".to_string()],
+            ),
+            ("zh".to_string(), vec!["该死".to_string()]),
+            ("*".to_string(), vec!["Project Falcon".to_string()]),
+        ]);
+        let mask = WordFilter::from_settings(&WordFilterConfig {
+            enabled: Some(true),
+            words: Some(words.clone()),
+            ..WordFilterConfig::default()
+        });
+        assert_eq!(
+            mask.apply("Damn, hello from hell about project falcon", Some("en-US")),
+            "****, hello from **** about **************"
+        );
+        assert_eq!(mask.apply("该死 damn", Some("zh")), "** damn");
+        assert_eq!(mask.apply("该死 damn", None), "** ****");
+
+        let replace = WordFilter::from_settings(&WordFilterConfig {
+            enabled: Some(true),
+            mode: Some("replace".to_string()),
+            words: Some(words),
+            replacements: Some(HashMap::from([(
+                "project falcon".to_string(),
+                "a new project".to_string(),
+            )])),
+            ..WordFilterConfig::default()
+        });
+        assert_eq!(
+            replace.apply("damn, Project Falcon slipped", Some("en")),
+            "***, a new project slipped"
+        );
+    }
+}
@@ -8,12 +8,23 @@ const PROJECT_MODAL_COLLAPSED_HEIGHT = 190;
 const urlInput = document.getElementById("urlInput");
 const loadBtn = document.getElementById("loadBtn");
 const introBtn = document.getElementById("introBtn");
+const updateBtn = document.getElementById("updateBtn");
 const asrProviderToggle = document.getElementById("asrProviderToggle");
 const translateProviderToggle = document.getElementById("translateProviderToggle");
+const translateTargetSelect = document.getElementById("translateTarget");
 const asrFallbackToggle = document.getElementById("asrFallbackToggle");
+const transcriptionOnlyToggle = document.getElementById("transcriptionOnlyToggle");
+const interpreterToggle = document.getElementById("interpreterToggle");
+const captureSource = document.getElementById("captureSource");
+const captureDevice = document.getElementById("captureDevice");
+const captureInputDevice = document.getElementById("captureInputDevice");
 const asrLanguageSelect = document.getElementById("asrLanguage");
+const meetingTemplateSelect = document.getElementById("meetingTemplate");
 const asrStart = document.getElementById("asrStart");
 const captureStatus = document.getElementById("captureStatus");
+const asrPause = document.getElementById("asrPause");
+const armCaptureBtn = document.getElementById("armCapture");
+const flushArmedBtn = document.getElementById("flushArmed");
 const clearSegmentsBtn = document.getElementById("clearSegments");
 const projectQuickSelect = document.getElementById("projectQuickSelect");
 const ragSearchBtn = document.getElementById("ragSearchBtn");
@@ -21,6 +32,7 @@ const splitter = document.getElementById("splitter");
 
 const projectSettingsBtn = document.getElementById("projectSettingsBtn");
 const currentProjectLabel = document.getElementById("currentProjectLabel");
+const usageCostLabel = document.getElementById("usageCost");
 const projectModal = document.getElementById("projectModal");
 const projectModalClose = document.getElementById("projectModalClose");
 const projectNewBtn = document.getElementById("projectNewBtn");
@@ -49,18 +61,51 @@ const stopCaptureAllBtn = document.getElementById("stopCaptureAll");
 const ragSearchModal = document.getElementById("ragSearchModal");
 const ragSearchPrompt = document.getElementById("ragSearchPrompt");
 const ragSearchAskBtn = document.getElementById("ragSearchAskBtn");
+const ragSearchBatchBtn = document.getElementById("ragSearchBatchBtn");
 const ragAllowOutOfContext = document.getElementById("ragAllowOutOfContext");
+const ragTranslateAnswer = document.getElementById("ragTranslateAnswer");
 const ragSearchOutput = document.getElementById("ragSearchOutput");
 const ragSearchProjectInfo = document.getElementById("ragSearchProjectInfo");
 const ragSearchCloseBtn = document.getElementById("ragSearchCloseBtn");
 
+const shareSummaryBtn = document.getElementById("shareSummaryBtn");
+const shareModal = document.getElementById("shareModal");
+const shareSessionInfo = document.getElementById("shareSessionInfo");
+const shareStats = document.getElementById("shareStats");
+const shareSummarizeBtn = document.getElementById("shareSummarizeBtn");
+const shareStudyBtn = document.getElementById("shareStudyBtn");
+const shareAudioBtn = document.getElementById("shareAudioBtn");
+const shareRediarizeBtn = document.getElementById("shareRediarizeBtn");
+const shareRetranscribeBtn = document.getElementById("shareRetranscribeBtn");
+const shareTarget = document.getElementById("shareTarget");
+const sharePreviewBtn = document.getElementById("sharePreviewBtn");
+const shareText = document.getElementById("shareText");
+const shareStatus = document.getElementById("shareStatus");
+const shareSendBtn = document.getElementById("shareSendBtn");
+const shareArchiveBtn = document.getElementById("shareArchiveBtn");
+const shareArchiveDelete = document.getElementById("shareArchiveDelete");
+const shareArchiveList = document.getElementById("shareArchiveList");
+const shareRestoreBtn = document.getElementById("shareRestoreBtn");
+const shareCloseBtn = document.getElementById("shareCloseBtn");
+
+const companionBtn = document.getElementById("companionBtn");
+const companionModal = document.getElementById("companionModal");
+const companionQr = document.getElementById("companionQr");
+const companionCode = document.getElementById("companionCode");
+const companionUrl = document.getElementById("companionUrl");
+const companionRefreshBtn = document.getElementById("companionRefreshBtn");
+const companionCloseBtn = document.getElementById("companionCloseBtn");
+
 let resizeState = null;
 let pendingResize = null;
 let resizeFrame = null;
 let isCapturing = false;
+let isPaused = false;
+let isArmed = false;
 let currentAsrProvider = "whisperserver";
 let currentTranslateProvider = "ollama";
-const TRANSLATE_PROVIDER_ORDER = ["ollama", "openai", "local-gpt"];
+const TRANSLATE_PROVIDER_ORDER = ["ollama", "openai", "local-gpt", "claude", "gemini"];
+const ASR_PROVIDER_ORDER = ["whisperserver", "whisperpipe", "whisperinproc", "deepgram", "openai"];
 let selectedProjectIds = [];
 let selectedProjectName = "";
 let projects = [];
@@ -78,6 +123,10 @@ let progressValue = 0;
 let ragSearchModalOpen = false;
 let ragSearchRunning = false;
 let stopCaptureChoiceResolver = null;
+let shareModalOpen = false;
+let shareBusy = false;
+let shareSessionId = "";
+let companionModalOpen = false;
 
 const normalizeUrl = (raw) => {
   if (!raw) return "";
@@ -107,8 +156,23 @@ const logError = (message) => {
 const updateAsrUi = () => {
   if (!asrProviderToggle) return;
   asrProviderToggle.dataset.provider = currentAsrProvider;
-  asrProviderToggle.textContent =
-    currentAsrProvider === "openai" ? "OpenAI" : "Whisper Server";
+  if (currentAsrProvider === "openai") {
+    asrProviderToggle.textContent = "OpenAI";
+    return;
+  }
+  if (currentAsrProvider === "whisperpipe") {
+    asrProviderToggle.textContent = "Whisper Pipe";
+    return;
+  }
+  if (currentAsrProvider === "whisperinproc") {
+    asrProviderToggle.textContent = "Whisper In-Process";
+    return;
+  }
+  if (currentAsrProvider === "deepgram") {
+    asrProviderToggle.textContent = "Deepgram";
+    return;
+  }
+  asrProviderToggle.textContent = "Whisper Server";
 };
 
 const updateTranslateProviderUi = () => {
@@ -122,17 +186,52 @@ const updateTranslateProviderUi = () => {
     translateProviderToggle.textContent = "Local GPT";
     return;
   }
+  if (currentTranslateProvider === "claude") {
+    translateProviderToggle.textContent = "Claude";
+    return;
+  }
+  if (currentTranslateProvider === "gemini") {
+    translateProviderToggle.textContent = "Gemini";
+    return;
+  }
   translateProviderToggle.textContent = "Ollama";
 };
 
-const updateCaptureUi = (active) => {
+const renderArmedUi = () => {
+  if (armCaptureBtn) {
+    armCaptureBtn.hidden = isCapturing;
+    armCaptureBtn.textContent = isArmed ? "取消待命" : "待命";
+  }
+  if (flushArmedBtn) {
+    flushArmedBtn.hidden = isCapturing || !isArmed;
+  }
+};
+
+// Starting a capture disarms and stopping it re-arms on the backend, so the state is read back.
+const refreshArmedUi = async () => {
+  try {
+    const status = await invoke("armed_capture_status");
+    isArmed = Boolean(status?.armed);
+  } catch (error) {
+    logError(`armed status error: ${error}`);
+  }
+  renderArmedUi();
+};
+
+const updateCaptureUi = (active, paused = false) => {
   isCapturing = active;
+  isPaused = active && paused;
   if (asrStart) {
     asrStart.textContent = active ? "Stop Capture" : "Start Capture";
   }
+  if (asrPause) {
+    asrPause.hidden = !active;
+    asrPause.textContent = isPaused ? "继续" : "暂停";
+  }
   if (captureStatus) {
-    captureStatus.textContent = active ? "Capturing..." : "Idle";
+    captureStatus.textContent = !active ? "Idle" : isPaused ? "Paused" : "Capturing...";
   }
+  void refreshArmedUi();
 };
 
 const updateCurrentProjectLabel = () => {
@@ -243,6 +342,7 @@ const runRagSearch = async () => {
   }
   const query = (ragSearchPrompt?.value || "").trim();
   const allowOutOfContext = !!ragAllowOutOfContext?.checked;
+  const translateAnswer = !!ragTranslateAnswer?.checked;
   if (!query) {
     appendRagOutput("请输入问题");
     return;
@@ -265,6 +365,7 @@ const runRagSearch = async () => {
         project_ids: [project.project_id],
         top_k: 8,
         allow_out_of_context: allowOutOfContext,
+        translate_answer: translateAnswer,
       },
     });
     const provider = String(response?.provider || currentTranslateProvider || "ollama");
@@ -272,8 +373,11 @@ const runRagSearch = async () => {
 
     appendRagOutput(`provider: ${provider}`);
     appendRagOutput("");
-    appendRagOutput("LLM answer:");
+    appendRagOutput(response?.answer_language ? `LLM answer (${response.answer_language}):` : "LLM answer:");
     appendRagOutput(answer || "(empty)");
+    if (response?.translation_error) {
+      appendRagOutput(`translation error: ${response.translation_error}`);
+    }
   } catch (error) {
     appendRagOutput(`error: ${error}`);
   } finally {
@@ -285,6 +389,368 @@ const runRagSearch = async () => {
   }
 };
 
+const runRagBatch = async () => {
+  if (ragSearchRunning) return;
+  const project = getSelectedProject();
+  if (!project) {
+    window.alert("请先选择项目");
+    return;
+  }
+  const questions = (ragSearchPrompt?.value || "")
+    .split(/[;；\n]/)
+    .map((item) => item.trim())
+    .filter(Boolean);
+  if (!questions.length) {
+    appendRagOutput("请输入问题，多个问题用分号分隔");
+    return;
+  }
+
+  ragSearchRunning = true;
+  [ragSearchAskBtn, ragSearchBatchBtn].forEach((button) => {
+    if (button) button.disabled = true;
+  });
+  if (ragSearchOutput) {
+    ragSearchOutput.textContent = "";
+  }
+  appendRagOutput(`批量提问中（${questions.length} 个问题）...`);
+
+  try {
+    const report = await invoke("rag_ask_batch", {
+      request: {
+        questions,
+        project_ids: [project.project_id],
+        top_k: 8,
+        allow_out_of_context: !!ragAllowOutOfContext?.checked,
+        translate_answer: !!ragTranslateAnswer?.checked,
+      },
+    });
+    if (ragSearchOutput) {
+      ragSearchOutput.textContent = "";
+    }
+    appendRagOutput(`provider: ${report.provider} | chunks=${report.unique_chunks} | shared=${report.shared_chunks}`);
+    appendRagOutput("");
+    appendRagOutput(report.markdown || "(empty)");
+  } catch (error) {
+    appendRagOutput(`error: ${error}`);
+  } finally {
+    ragSearchRunning = false;
+    [ragSearchAskBtn, ragSearchBatchBtn].forEach((button) => {
+      if (button) button.disabled = false;
+    });
+  }
+};
+
+const setShareStatus = (text) => {
+  if (shareStatus) {
+    shareStatus.textContent = text || "";
+  }
+};
+
+const setShareBusy = (busy) => {
+  shareBusy = busy;
+  [
+    shareSummarizeBtn,
+    sharePreviewBtn,
+    shareStudyBtn,
+    shareAudioBtn,
+    shareRediarizeBtn,
+    shareRetranscribeBtn,
+    shareSendBtn,
+    shareArchiveBtn,
+    shareRestoreBtn,
+  ].forEach((button) => {
+    if (button) button.disabled = busy;
+  });
+};
+
+const formatSeconds = (ms) => {
+  const seconds = Math.round((ms || 0) / 1000);
+  const minutes = Math.floor(seconds / 60);
+  return minutes ? `${minutes} 分 ${seconds % 60} 秒` : `${seconds} 秒`;
+};
+
+const formatCounts = (counts) => {
+  const entries = Object.entries(counts || {});
+  return entries.length ? entries.map(([key, count]) => `${key} ${count}`).join("，") : "无";
+};
+
+const renderSessionStats = (stats) => {
+  if (!shareStats) return;
+  shareStats.textContent = "";
+  if (!stats) {
+    shareStats.hidden = true;
+    return;
+  }
+  const latency = (ms) => (Number.isFinite(ms) ? `${(ms / 1000).toFixed(1)} 秒` : "-");
+  const rows = [
+    ["时长", formatSeconds(stats.duration_ms)],
+    ["讲话时间", formatSeconds(stats.speech_ms)],
+    ["片段数", String(stats.segment_count)],
+    ["转写字数", formatCounts(stats.words_per_language)],
+    ["译文字数", formatCounts(stats.translated_words_per_language)],
+    ["平均延迟", `转写 ${latency(stats.avg_transcribe_ms)}，翻译 ${latency(stats.avg_translate_ms)}`],
+    ["转写错误", formatCounts(stats.transcribe_errors)],
+    ["翻译错误", formatCounts(stats.translate_errors)],
+  ];
+  for (const [label, value] of rows) {
+    const dt = document.createElement("dt");
+    dt.textContent = label;
+    const dd = document.createElement("dd");
+    dd.textContent = value;
+    shareStats.append(dt, dd);
+  }
+  shareStats.hidden = false;
+};
+
+const openShareModal = async () => {
+  if (!shareModal) return;
+  void invoke("set_top_height", { height: PROJECT_MODAL_EXPANDED_HEIGHT }).catch((error) => {
+    logError(`expand top error: ${error}`);
+  });
+  shareModalOpen = true;
+  shareModal.classList.remove("hidden");
+  shareModal.setAttribute("aria-hidden", "false");
+  if (shareText) shareText.value = "";
+  if (shareSendBtn) shareSendBtn.disabled = true;
+  setShareStatus("");
+  renderSessionStats(null);
+  shareSessionId = "";
+  void loadShareArchives();
+  try {
+    const sessions = await invoke("list_sessions");
+    const finished = (sessions || []).filter((session) => !session.active);
+    const latest = finished[finished.length - 1];
+    if (!latest) {
+      if (shareSessionInfo) shareSessionInfo.textContent = "暂无已结束的会议";
+      return;
+    }
+    shareSessionId = latest.id;
+    if (shareSessionInfo) {
+      const [startedAt] = await invoke("format_timestamps", { values: [latest.started_at] });
+      shareSessionInfo.textContent = `会议：${latest.title || latest.id}（${startedAt}，${latest.segment_count} 段）`;
+    }
+    renderSessionStats(await invoke("get_session_stats", { sessionId: shareSessionId }));
+    const digest = await invoke("get_session_summary", { sessionId: shareSessionId });
+    setShareStatus(digest ? "已有总结，可直接预览" : "尚未生成总结");
+  } catch (error) {
+    setShareStatus(`加载会议失败：${error}`);
+  }
+};
+
+const closeShareModal = () => {
+  if (!shareModal || shareBusy) return;
+  shareModalOpen = false;
+  shareModal.classList.add("hidden");
+  shareModal.setAttribute("aria-hidden", "true");
+  void invoke("set_top_height", { height: PROJECT_MODAL_COLLAPSED_HEIGHT }).catch((error) => {
+    logError(`collapse top error: ${error}`);
+  });
+};
+
+const previewShare = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  try {
+    const preview = await invoke("preview_meeting_share", {
+      sessionId: shareSessionId,
+      target: shareTarget?.value || "slack",
+    });
+    if (shareText) shareText.value = preview.text || "";
+    setShareStatus(`预览（未发送），目标：${preview.destination}`);
+  } catch (error) {
+    setShareStatus(`预览失败：${error}`);
+  } finally {
+    setShareBusy(false);
+    if (shareSendBtn) shareSendBtn.disabled = !shareText?.value.trim();
+  }
+};
+
+const summarizeForShare = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  setShareStatus("正在生成总结...");
+  try {
+    await invoke("summarize_session", { sessionId: shareSessionId });
+  } catch (error) {
+    setShareStatus(`总结失败：${error}`);
+    setShareBusy(false);
+    return;
+  }
+  setShareBusy(false);
+  await previewShare();
+};
+
+const exportStudySubtitles = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  setShareStatus("正在生成学习字幕（含注音）...");
+  try {
+    const result = await invoke("export_study_subtitles", {
+      request: { session_id: shareSessionId, format: "vtt" },
+    });
+    if (shareText) shareText.value = result.content || "";
+    setShareStatus(`学习字幕：${result.lines} 行，注音 ${result.annotated} 处（未发送）`);
+  } catch (error) {
+    setShareStatus(`学习字幕生成失败：${error}`);
+  } finally {
+    setShareBusy(false);
+    if (shareSendBtn) shareSendBtn.disabled = true;
+  }
+};
+
+const exportMeetingAudio = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  try {
+    const path = await invoke("meeting_export_audio", { sessionId: shareSessionId });
+    if (path) setShareStatus(`已导出录音：${path}`);
+  } catch (error) {
+    setShareStatus(`导出录音失败：${error}`);
+  } finally {
+    setShareBusy(false);
+    if (shareSendBtn) shareSendBtn.disabled = !shareText?.value.trim();
+  }
+};
+
+const loadShareArchives = async () => {
+  if (!shareArchiveList) return;
+  try {
+    const archives = await invoke("list_session_archives");
+    shareArchiveList.replaceChildren(
+      ...(archives || []).map((archive) => {
+        const option = document.createElement("option");
+        option.value = archive.id;
+        option.textContent = archive.title || archive.id;
+        return option;
+      }),
+    );
+  } catch (error) {
+    logError(`list archives error: ${error}`);
+  }
+  if (shareRestoreBtn) shareRestoreBtn.disabled = !shareArchiveList.value;
+};
+
+const archiveShareSession = async () => {
+  if (!shareSessionId || shareBusy) return;
+  const deleteOriginals = Boolean(shareArchiveDelete?.checked);
+  if (deleteOriginals && !window.confirm("归档后将删除该会议的录音与转写，确认？")) return;
+  setShareBusy(true);
+  setShareStatus("正在归档...");
+  try {
+    const result = await invoke("archive_session", { sessionId: shareSessionId, deleteOriginals });
+    const size = (result.bytes / 1024 / 1024).toFixed(1);
+    const missing = result.missing_audio ? `，${result.missing_audio} 段缺少录音` : "";
+    setShareStatus(`已归档 ${result.segment_count} 段（${size} MB${missing}）：${result.path}`);
+    if (result.originals_deleted) shareSessionId = "";
+  } catch (error) {
+    setShareStatus(`归档失败：${error}`);
+  } finally {
+    setShareBusy(false);
+  }
+  await loadShareArchives();
+};
+
+const rediarizeShareSession = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  setShareStatus("正在重新识别说话人...");
+  try {
+    const report = await invoke("rediarize_session", { sessionId: shareSessionId });
+    setShareStatus(
+      `已识别 ${report.labeled}/${report.segment_count} 段，${report.speakers} 位说话人`,
+    );
+  } catch (error) {
+    setShareStatus(`重新识别失败：${error}`);
+  } finally {
+    setShareBusy(false);
+  }
+};
+
+const retranscribeShareSession = async () => {
+  if (!shareSessionId || shareBusy) return;
+  setShareBusy(true);
+  setShareStatus("正在重新转写...");
+  try {
+    const report = await invoke("retranscribe_session", { sessionId: shareSessionId });
+    const failed = report.failed ? `，${report.failed} 段失败` : "";
+    const missing = report.missing ? `，${report.missing} 段缺少录音` : "";
+    setShareStatus(`已重新转写 ${report.transcribed}/${report.segment_count} 段${failed}${missing}`);
+  } catch (error) {
+    setShareStatus(`重新转写失败：${error}`);
+  } finally {
+    setShareBusy(false);
+  }
+};
+
+const restoreShareArchive = async () => {
+  const archive = shareArchiveList?.value;
+  if (!archive || shareBusy) return;
+  setShareBusy(true);
+  try {
+    const session = await invoke("restore_session", { archive });
+    setShareStatus(`已恢复：${session.title || session.id}`);
+  } catch (error) {
+    setShareStatus(`恢复失败：${error}`);
+  } finally {
+    setShareBusy(false);
+  }
+};
+
+const sendShare = async () => {
+  const text = (shareText?.value || "").trim();
+  if (!shareSessionId || !text || shareBusy) return;
+  const target = shareTarget?.value || "slack";
+  if (!window.confirm(`确认发送到 ${target}？`)) return;
+  setShareBusy(true);
+  try {
+    await invoke("send_meeting_share", { sessionId: shareSessionId, target, text });
+    setShareStatus("已发送");
+  } catch (error) {
+    setShareStatus(`发送失败：${error}`);
+  } finally {
+    setShareBusy(false);
+  }
+};
+
+const refreshCompanionPairing = async () => {
+  if (companionQr) companionQr.innerHTML = "";
+  if (companionCode) companionCode.textContent = "";
+  if (companionUrl) companionUrl.textContent = "";
+  try {
+    const pairing = await invoke("start_companion_pairing");
+    // The SVG is generated locally by the backend QR encoder.
+    if (companionQr) companionQr.innerHTML = pairing.qrSvg;
+    if (companionCode) companionCode.textContent = pairing.code;
+    if (companionUrl) {
+      const minutes = Math.round(pairing.expiresInSecs / 60);
+      companionUrl.textContent = `${pairing.url}（${minutes} 分钟内有效）`;
+    }
+  } catch (error) {
+    if (companionUrl) companionUrl.textContent = `无法生成配对码：${error}`;
+  }
+};
+
+const openCompanionModal = () => {
+  if (!companionModal) return;
+  void invoke("set_top_height", { height: PROJECT_MODAL_EXPANDED_HEIGHT }).catch((error) => {
+    logError(`expand top error: ${error}`);
+  });
+  companionModalOpen = true;
+  companionModal.classList.remove("hidden");
+  companionModal.setAttribute("aria-hidden", "false");
+  void refreshCompanionPairing();
+};
+
+const closeCompanionModal = () => {
+  if (!companionModal) return;
+  companionModalOpen = false;
+  companionModal.classList.add("hidden");
+  companionModal.setAttribute("aria-hidden", "true");
+  void invoke("set_top_height", { height: PROJECT_MODAL_COLLAPSED_HEIGHT }).catch((error) => {
+    logError(`collapse top error: ${error}`);
+  });
+};
+
 const syncSelectedProject = () => {
   const selectedId = selectedProjectIds[0];
   if (!selectedId) {
@@ -554,6 +1020,18 @@ const renderProjectList = () => {
       void updateProject(project);
     });
 
+    const filterBtn = document.createElement("button");
+    filterBtn.type = "button";
+    filterBtn.textContent = "过滤";
+    filterBtn.title = [
+      `排除: ${(project.exclude || []).join(", ") || "无"}`,
+      `仅包含: ${(project.include || []).join(", ") || "全部"}`,
+    ].join("\n");
+    filterBtn.disabled = isBusy;
+    filterBtn.addEventListener("click", () => {
+      void editProjectFilters(project);
+    });
+
     const deleteBtn = document.createElement("button");
     deleteBtn.type = "button";
     deleteBtn.textContent = "删除";
@@ -564,6 +1042,7 @@ const renderProjectList = () => {
 
     actions.appendChild(showBtn);
     actions.appendChild(syncBtn);
+    actions.appendChild(filterBtn);
     actions.appendChild(deleteBtn);
 
     if (busyText) {
@@ -713,6 +1192,39 @@ const updateProject = async (project) => {
   }
 };
 
+const splitPatterns = (text) =>
+  text
+    .split(/[,，\n]/)
+    .map((item) => item.trim())
+    .filter(Boolean);
+
+const editProjectFilters = async (project) => {
+  if (!project || projectActionMap.has(project.project_id)) return;
+  const exclude = window.prompt(
+    "排除的路径（glob，逗号分隔，例如 **/test_data/**）",
+    (project.exclude || []).join(", ")
+  );
+  if (exclude === null) return;
+  const include = window.prompt(
+    "仅索引的路径（glob，逗号分隔，留空表示全部，例如 src/**）",
+    (project.include || []).join(", ")
+  );
+  if (include === null) return;
+  try {
+    await invoke("rag_project_set_filters", {
+      request: {
+        project_id: project.project_id,
+        include: splitPatterns(include),
+        exclude: splitPatterns(exclude),
+      },
+    });
+    await loadProjects();
+    window.alert("过滤规则已保存，点击“更新”后生效");
+  } catch (error) {
+    window.alert(`保存过滤规则失败：${error}`);
+  }
+};
+
 const deleteProject = async (project) => {
   if (!project || projectActionMap.has(project.project_id)) return;
   const confirmed = window.confirm(
@@ -773,6 +1285,188 @@ const loadTranslateProvider = async () => {
   }
 };
 
+// Keeps a configured language that is not in the list selectable instead of snapping to the first option.
+const showTranslateTarget = (language) => {
+  if (!translateTargetSelect || !language) return;
+  if (![...translateTargetSelect.options].some((option) => option.value === language)) {
+    translateTargetSelect.add(new Option(language, language));
+  }
+  translateTargetSelect.value = language;
+};
+
+const loadTranslateTarget = async () => {
+  if (!translateTargetSelect) return;
+  try {
+    showTranslateTarget(await invoke("get_target_language"));
+  } catch (error) {
+    logError(`translate target load error: ${error}`);
+  }
+};
+
+const loadMeetingTemplates = async () => {
+  if (!meetingTemplateSelect) return;
+  try {
+    const templates = await invoke("list_meeting_templates");
+    for (const template of templates || []) {
+      meetingTemplateSelect.add(new Option(template.name || template.id, template.id));
+    }
+  } catch (error) {
+    logError(`meeting templates load error: ${error}`);
+  }
+};
+
+const updateTranscriptionOnlyUi = (enabled) => {
+  if (transcriptionOnlyToggle) {
+    transcriptionOnlyToggle.checked = !!enabled;
+  }
+  if (translateProviderToggle) {
+    translateProviderToggle.disabled = !!enabled;
+  }
+};
+
+const loadTranscriptionOnly = async () => {
+  if (!transcriptionOnlyToggle) return;
+  try {
+    updateTranscriptionOnlyUi(await invoke("get_transcription_only"));
+  } catch (error) {
+    logError(`transcription only load error: ${error}`);
+  }
+};
+
+// The preset may swap the translate provider for a streaming one, so the toggle follows it.
+const updateInterpreterUi = (status) => {
+  if (interpreterToggle) {
+    interpreterToggle.checked = !!status?.enabled;
+    interpreterToggle.title = status?.enabled
+      ? `分段上限 ${status.max_segment_ms} ms${status.whisper_model_path ? "" : "，未找到小模型"}`
+      : "";
+  }
+  if (status?.translate_provider) {
+    currentTranslateProvider = status.translate_provider;
+    updateTranslateProviderUi();
+  }
+};
+
+const loadInterpreterMode = async () => {
+  if (!interpreterToggle) return;
+  try {
+    updateInterpreterUi(await invoke("get_interpreter_mode"));
+  } catch (error) {
+    logError(`interpreter mode load error: ${error}`);
+  }
+};
+
+let audioDevices = null;
+
+const fillDeviceSelect = (select, devices, selectedId, hidden) => {
+  const defaultOption = document.createElement("option");
+  defaultOption.value = "";
+  defaultOption.textContent = "系统默认";
+  select.replaceChildren(
+    defaultOption,
+    ...(devices || []).map((device) => {
+      const option = document.createElement("option");
+      option.value = device.id;
+      option.textContent = `${device.name || device.id}${device.is_default ? "（默认）" : ""}`;
+      return option;
+    }),
+  );
+  select.value = selectedId || "";
+  select.hidden = hidden;
+  select.title = audioDevices.capturing ? "更改将在下次开始录音时生效" : "";
+};
+
+// Output devices are shown for loopback, microphones for the microphone, both in dual mode.
+const renderCaptureDevices = () => {
+  if (!captureSource || !captureDevice || !captureInputDevice || !audioDevices) return;
+  const source = captureSource.value;
+  fillDeviceSelect(
+    captureDevice,
+    audioDevices.outputs,
+    audioDevices.output_device_id,
+    source === "microphone",
+  );
+  fillDeviceSelect(
+    captureInputDevice,
+    audioDevices.inputs,
+    audioDevices.input_device_id,
+    source === "loopback",
+  );
+};
+
+const applyAudioDevices = (devices) => {
+  audioDevices = devices;
+  if (captureSource) captureSource.value = devices?.source || "loopback";
+  renderCaptureDevices();
+};
+
+const loadAudioDevices = async () => {
+  if (!captureSource) return;
+  try {
+    applyAudioDevices(await invoke("list_audio_devices"));
+  } catch (error) {
+    logError(`audio devices load error: ${error}`);
+  }
+};
+
+const saveCaptureDevice = async () => {
+  if (!audioDevices) return;
+  const selection = {
+    source: captureSource?.value || "loopback",
+    output_device_id: captureDevice?.value || null,
+    input_device_id: captureInputDevice?.value || null,
+  };
+  try {
+    applyAudioDevices(await invoke("set_capture_device", { selection }));
+  } catch (error) {
+    logError(`capture device error: ${error}`);
+    await loadAudioDevices();
+  }
+};
+
+let latestUpdate = null;
+
+const loadUpdateStatus = async () => {
+  if (!updateBtn) return;
+  try {
+    const info = await invoke("check_for_updates");
+    latestUpdate = info?.available ? info : null;
+  } catch (error) {
+    latestUpdate = null;
+    logError(`update check error: ${error}`);
+  }
+  updateBtn.hidden = !latestUpdate;
+  if (latestUpdate) {
+    updateBtn.textContent = `有新版本 ${latestUpdate.latest_version}`;
+  }
+};
+
+const USAGE_REFRESH_MS = 10_000;
+
+// Token usage and estimated cost of LLM calls in the current session (the backend resets it when
+// one starts); the tooltip breaks it down by provider.
+const loadUsageStats = async () => {
+  if (!usageCostLabel) return;
+  let stats;
+  try {
+    stats = await invoke("get_usage_stats");
+  } catch (error) {
+    logError(`usage stats error: ${error}`);
+    return;
+  }
+  const total = stats?.total;
+  usageCostLabel.hidden = !total?.requests;
+  if (!total?.requests) return;
+  const tokens = total.input_tokens + total.output_tokens;
+  usageCostLabel.textContent = `本次费用 $${total.cost_usd.toFixed(4)}（${tokens} tokens）`;
+  usageCostLabel.title = (stats.by_provider || [])
+    .map(
+      (row) =>
+        `${row.name}: ${row.requests} 次，输入 ${row.input_tokens}，输出 ${row.output_tokens}，$${row.cost_usd.toFixed(4)}`
+    )
+    .join("\n");
+};
+
 const scheduleResize = (height) => {
   pendingResize = height;
   if (resizeFrame) return;
@@ -814,7 +1508,14 @@ const chooseStopCaptureMode = () =>
 
 const startCapture = async () => {
   if (isCapturing) return;
-  await invoke("start_loopback_capture");
+  const templateId = meetingTemplateSelect?.value;
+  if (templateId) {
+    await invoke("start_session_with_template", { templateId });
+    // The template may bring its own target language.
+    await loadTranslateTarget();
+  } else {
+    await invoke("start_loopback_capture");
+  }
   updateCaptureUi(true);
 };
 
@@ -884,6 +1585,42 @@ asrStart?.addEventListener("click", async () => {
   }
 });
 
+// Pausing keeps the session and the transcription/translation workers running.
+asrPause?.addEventListener("click", async () => {
+  if (!isCapturing) return;
+  try {
+    await invoke(isPaused ? "resume_loopback_capture" : "pause_loopback_capture");
+    updateCaptureUi(true, !isPaused);
+  } catch (error) {
+    logError(`pause error: ${error}`);
+  }
+});
+
+// Armed keeps the last minutes of audio in memory, to be saved as a session after the fact.
+armCaptureBtn?.addEventListener("click", async () => {
+  try {
+    await invoke(isArmed ? "disarm_capture" : "arm_capture");
+  } catch (error) {
+    logError(`arm error: ${error}`);
+  }
+  await refreshArmedUi();
+});
+
+flushArmedBtn?.addEventListener("click", async () => {
+  if (!isArmed) return;
+  flushArmedBtn.disabled = true;
+  try {
+    const count = await invoke("flush_armed_capture");
+    if (captureStatus) {
+      captureStatus.textContent = `已保存 ${count} 段`;
+    }
+  } catch (error) {
+    logError(`flush error: ${error}`);
+  } finally {
+    flushArmedBtn.disabled = false;
+  }
+});
+
 clearSegmentsBtn?.addEventListener("click", async () => {
   try {
     await invoke("clear_segments");
@@ -901,8 +1638,25 @@ introBtn?.addEventListener("click", async () => {
   }
 });
 
+updateBtn?.addEventListener("click", () => {
+  if (!latestUpdate) return;
+  const lines = [
+    `当前版本：${latestUpdate.current_version}`,
+    `最新版本：${latestUpdate.latest_version}`,
+  ];
+  if (latestUpdate.notes) {
+    lines.push("", latestUpdate.notes);
+  }
+  if (latestUpdate.url) {
+    lines.push("", `下载：${latestUpdate.url}`);
+  }
+  window.alert(lines.join("\n"));
+});
+
 asrProviderToggle?.addEventListener("click", async () => {
-  const next = currentAsrProvider === "whisperserver" ? "openai" : "whisperserver";
+  const currentIndex = ASR_PROVIDER_ORDER.indexOf(currentAsrProvider);
+  const next =
+    ASR_PROVIDER_ORDER[(currentIndex >= 0 ? currentIndex + 1 : 0) % ASR_PROVIDER_ORDER.length];
   try {
     const updated = await invoke("set_asr_provider", { provider: next });
     currentAsrProvider = updated || next;
@@ -927,6 +1681,51 @@ translateProviderToggle?.addEventListener("click", async () => {
   }
 });
 
+translateTargetSelect?.addEventListener("change", async () => {
+  try {
+    showTranslateTarget(
+      await invoke("set_target_language", { language: translateTargetSelect.value })
+    );
+  } catch (error) {
+    logError(`translate target error: ${error}`);
+  }
+});
+
+transcriptionOnlyToggle?.addEventListener("change", async () => {
+  try {
+    const enabled = await invoke("set_transcription_only", {
+      enabled: transcriptionOnlyToggle.checked,
+    });
+    updateTranscriptionOnlyUi(enabled);
+  } catch (error) {
+    logError(`transcription only error: ${error}`);
+  }
+});
+
+captureSource?.addEventListener("change", () => {
+  renderCaptureDevices();
+  void saveCaptureDevice();
+});
+
+captureDevice?.addEventListener("change", () => {
+  void saveCaptureDevice();
+});
+
+captureInputDevice?.addEventListener("change", () => {
+  void saveCaptureDevice();
+});
+
+interpreterToggle?.addEventListener("change", async () => {
+  try {
+    updateInterpreterUi(
+      await invoke("set_interpreter_mode", { enabled: interpreterToggle.checked })
+    );
+  } catch (error) {
+    interpreterToggle.checked = !interpreterToggle.checked;
+    logError(`interpreter mode error: ${error}`);
+  }
+});
+
 asrFallbackToggle?.addEventListener("change", async () => {
   try {
     await invoke("set_asr_fallback", { fallback: asrFallbackToggle.checked });
@@ -937,9 +1736,10 @@ asrFallbackToggle?.addEventListener("change", async () => {
 
 asrLanguageSelect?.addEventListener("change", async () => {
   try {
-    const updated = await invoke("set_asr_language", { language: asrLanguageSelect.value });
-    if (updated) {
-      asrLanguageSelect.value = updated;
+    // A running meeting keeps its language; the choice applies from the next one.
+    const updated = await invoke("set_next_session_asr", { language: asrLanguageSelect.value });
+    if (updated?.language) {
+      asrLanguageSelect.value = updated.language;
     }
   } catch (error) {
     logError(`asr language error: ${error}`);
@@ -968,6 +1768,10 @@ ragSearchAskBtn?.addEventListener("click", () => {
   void runRagSearch();
 });
 
+ragSearchBatchBtn?.addEventListener("click", () => {
+  void runRagBatch();
+});
+
 ragSearchPrompt?.addEventListener("keydown", (event) => {
   if (event.key === "Enter") {
     event.preventDefault();
@@ -979,6 +1783,70 @@ ragSearchCloseBtn?.addEventListener("click", () => {
   closeRagSearchModal();
 });
 
+shareSummaryBtn?.addEventListener("click", () => {
+  void openShareModal();
+});
+
+shareStudyBtn?.addEventListener("click", () => {
+  void exportStudySubtitles();
+});
+
+shareAudioBtn?.addEventListener("click", () => {
+  void exportMeetingAudio();
+});
+
+shareRediarizeBtn?.addEventListener("click", () => {
+  void rediarizeShareSession();
+});
+
+shareRetranscribeBtn?.addEventListener("click", () => {
+  void retranscribeShareSession();
+});
+
+shareSummarizeBtn?.addEventListener("click", () => {
+  void summarizeForShare();
+});
+
+sharePreviewBtn?.addEventListener("click", () => {
+  void previewShare();
+});
+
+shareTarget?.addEventListener("change", () => {
+  void previewShare();
+});
+
+shareSendBtn?.addEventListener("click", () => {
+  void sendShare();
+});
+
+shareArchiveBtn?.addEventListener("click", () => {
+  void archiveShareSession();
+});
+
+shareRestoreBtn?.addEventListener("click", () => {
+  void restoreShareArchive();
+});
+
+shareArchiveList?.addEventListener("change", () => {
+  if (shareRestoreBtn) shareRestoreBtn.disabled = !shareArchiveList.value || shareBusy;
+});
+
+shareCloseBtn?.addEventListener("click", () => {
+  closeShareModal();
+});
+
+companionBtn?.addEventListener("click", () => {
+  openCompanionModal();
+});
+
+companionRefreshBtn?.addEventListener("click", () => {
+  void refreshCompanionPairing();
+});
+
+companionCloseBtn?.addEventListener("click", () => {
+  closeCompanionModal();
+});
+
 projectSettingsBtn?.addEventListener("click", () => {
   void openProjectModal();
 });
@@ -996,6 +1864,14 @@ window.addEventListener("keydown", (event) => {
     closeRagSearchModal();
     return;
   }
+  if (event.key === "Escape" && shareModalOpen && !shareBusy) {
+    closeShareModal();
+    return;
+  }
+  if (event.key === "Escape" && companionModalOpen) {
+    closeCompanionModal();
+    return;
+  }
   const progressVisible = projectProgressModal && !projectProgressModal.classList.contains("hidden");
   if (event.key === "Escape" && progressVisible) {
     event.preventDefault();
@@ -1039,6 +1915,15 @@ if (savedProjectId) {
 updateCurrentProjectLabel();
 loadAsrSettings();
 loadTranslateProvider();
+loadTranslateTarget();
+loadMeetingTemplates();
+loadTranscriptionOnly();
+loadInterpreterMode();
+loadAudioDevices();
+void refreshArmedUi();
+loadUpdateStatus();
+void loadUsageStats();
+setInterval(() => void loadUsageStats(), USAGE_REFRESH_MS);
 void loadProjects();
 renderProjectDraft();
 
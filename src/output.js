@@ -16,6 +16,13 @@ const liveFinalEl = document.getElementById("liveFinal");
 const livePartialEl = document.getElementById("livePartial");
 const liveMetaEl = document.getElementById("liveMeta");
 const liveSpeakerEl = document.getElementById("liveSpeaker");
+const recIndicatorEl = document.getElementById("recIndicator");
+const pausedIndicatorEl = document.getElementById("pausedIndicator");
+const deviceBannerEl = document.getElementById("deviceBanner");
+const deviceBannerTextEl = document.getElementById("deviceBannerText");
+const deviceRestartBtn = document.getElementById("deviceRestartBtn");
+const deviceDismissBtn = document.getElementById("deviceDismissBtn");
+const levelMeterEl = document.getElementById("levelMeter");
 
 const MAIN_SPLIT_STORAGE_KEY = "segment_board_main_split_ratio";
 const QUESTION_SPLIT_STORAGE_KEY = "segment_board_question_split_ratio";
@@ -27,12 +34,23 @@ const DEFAULT_QUESTION_SPLIT_RATIO = 0.5;
 const MIN_QUESTION_SPLIT_RATIO = 0.2;
 const MAX_QUESTION_SPLIT_RATIO = 0.8;
 const SPLIT_BAR_PIXEL_WIDTH = 12;
+// Which side of a dual-source recording a segment came from.
+const TRACK_LABELS = { me: "Me", them: "Them" };
+const LEVEL_LABELS = { microphone: "Mic", loopback: "System" };
+// Meter range in dBFS; anything quieter reads as empty.
+const LEVEL_MIN_DB = -60;
 
 const segmentMap = new Map();
 const rowTranslationRequested = new Set();
 const translationInvokeQueue = [];
 const translationInvokeQueued = new Set();
 const TRANSLATION_INVOKE_INTERVAL_MS = 80;
+// Playback fetches the segment a chunk at a time, keeping this much scheduled ahead.
+const PLAYBACK_CHUNK_MS = 1000;
+const PLAYBACK_LEAD_SECONDS = 1.5;
+const PLAYBACK_TICK_MS = 200;
+const WAVEFORM_WIDTH = 160;
+const WAVEFORM_HEIGHT = 18;
 const QUESTION_TEST_TEXT = "测试问题：你最想优化这个功能的哪一部分？";
 
 let translateEnabled = false;
@@ -40,6 +58,7 @@ let questionsEnabled = false;
 let autoScrollEnabled = false;
 let draggingSplit = null;
 let translationInvokeRunning = false;
+let playback = null;
 let liveStreamOrder = Number.NEGATIVE_INFINITY;
 let liveStreamId = "";
 let liveStreamText = "";
@@ -102,6 +121,40 @@ const setLivePartial = (text) => {
   }
 };
 
+let liveDraftRevision = 0;
+
+// Settled text stays put; only the still-changing tail is restyled, so the caption stops flickering.
+const applyLiveDraft = (payload) => {
+  if (!livePartialEl) return;
+  const revision = Number(payload?.revision);
+  if (Number.isFinite(revision)) {
+    if (revision <= liveDraftRevision) return;
+    liveDraftRevision = revision;
+  }
+  const stable = payload?.stable || "";
+  const tail = payload?.tail || "";
+  if (!stable && !tail) {
+    setLivePartial("");
+    return;
+  }
+  let stableEl = livePartialEl.querySelector(".draft-stable");
+  let tailEl = livePartialEl.querySelector(".draft-tail");
+  if (!stableEl || !tailEl) {
+    livePartialEl.textContent = "";
+    stableEl = document.createElement("span");
+    stableEl.className = "draft-stable";
+    tailEl = document.createElement("span");
+    tailEl.className = "draft-tail";
+    livePartialEl.append(stableEl, tailEl);
+  }
+  if (stableEl.textContent !== stable) {
+    stableEl.textContent = stable;
+  }
+  tailEl.textContent = tail;
+  livePartialEl.dataset.state = "ready";
+  setHeaderPrompt("");
+};
+
 const setLiveFinal = (text, state = "ready") => {
   if (!liveFinalEl) return;
   liveFinalEl.textContent = text || "";
@@ -301,7 +354,7 @@ const updateStatus = () => {
 const getTranslateProvider = async () => {
   try {
     const provider = await invoke("get_translate_provider");
-    if (provider === "openai" || provider === "ollama" || provider === "local-gpt") {
+    if (["openai", "ollama", "local-gpt", "claude", "gemini"].includes(provider)) {
       return provider;
     }
   } catch (_) {
@@ -310,11 +363,45 @@ const getTranslateProvider = async () => {
   return "ollama";
 };
 
+// Whisper gives no word timings, so each speaker switch is placed proportionally in the text and
+// moved to the nearest word or sentence break.
+const SWITCH_BREAK = /[\s,.!?;:，。！？；：、]/;
+const SWITCH_SNAP_CHARS = 12;
+
+const splitAtSwitches = (text, switches, durationMs) => {
+  if (!Array.isArray(switches) || !switches.length || !durationMs) return text;
+  const parts = [];
+  let start = 0;
+  switches.forEach((offsetMs) => {
+    const target = Math.round((offsetMs / durationMs) * text.length);
+    let cut = -1;
+    for (let distance = 0; distance <= SWITCH_SNAP_CHARS && cut < 0; distance += 1) {
+      [target + distance, target - distance].forEach((index) => {
+        if (cut < 0 && index > start && index < text.length && SWITCH_BREAK.test(text[index])) {
+          cut = index + 1;
+        }
+      });
+    }
+    if (cut < 0) return;
+    parts.push(text.slice(start, cut).trim());
+    start = cut;
+  });
+  parts.push(text.slice(start).trim());
+  return parts.filter(Boolean).join("\n");
+};
+
 const renderRowTranscript = (entry) => {
-  const transcript = normalizeText(entry.info.transcript);
+  const transcript = splitAtSwitches(
+    normalizeText(entry.info.transcript),
+    entry.info.speaker_switches_ms,
+    entry.info.duration_ms
+  );
   if (transcript) {
-    entry.transcriptEl.textContent = transcript;
-    entry.transcriptEl.dataset.state = "ready";
+    const track = TRACK_LABELS[entry.info.track];
+    entry.transcriptEl.textContent = track ? `${track}: ${transcript}` : transcript;
+    // Two-pass drafts are replaced once the accurate model has run.
+    entry.transcriptEl.dataset.state = entry.info.draft ? "draft" : "ready";
+    entry.transcriptEl.title = entry.info.draft ? "Draft, refining..." : "";
   } else {
     entry.transcriptEl.textContent = "Transcribing...";
     entry.transcriptEl.dataset.state = "pending";
@@ -336,6 +423,9 @@ const renderRowTranslation = (entry) => {
   }
 
   const cleaned = normalizeText(translation);
+  // Differs from the selected provider when `translate.fallbackProviders` took over.
+  const provider = entry.info.translation_provider;
+  entry.translationEl.title = cleaned && provider ? `Translated by ${provider}` : "";
   if (cleaned) {
     entry.translationEl.textContent = cleaned;
     entry.translationEl.dataset.state = "ready";
@@ -350,10 +440,205 @@ const renderRowQuestion = (entry) => {
   entry.questionEl.dataset.state = "ready";
 };
 
+const formatPlaybackTime = (ms) => {
+  const seconds = Math.max(0, Math.floor((ms || 0) / 1000));
+  return `${Math.floor(seconds / 60)}:${String(seconds % 60).padStart(2, "0")}`;
+};
+
+const playbackPosition = (session) => {
+  if (session.startedAt === null) return session.originMs;
+  const elapsedMs = (session.context.currentTime - session.startedAt) * 1000;
+  return clamp(session.originMs + elapsedMs, 0, session.durationMs);
+};
+
+const renderPlaybackControls = (entry) => {
+  const session = playback?.entry === entry ? playback : null;
+  entry.playBtn.textContent = session ? "■" : "▶";
+  entry.playBtn.title = session ? "Stop" : "Play segment";
+  entry.seekEl.hidden = !session;
+  if (!session) {
+    entry.playTimeEl.textContent = formatPlaybackTime(entry.info.duration_ms);
+    return;
+  }
+  const positionMs = playbackPosition(session);
+  entry.seekEl.max = String(session.durationMs);
+  if (!session.seeking) {
+    entry.seekEl.value = String(Math.round(positionMs));
+  }
+  entry.playTimeEl.textContent =
+    `${formatPlaybackTime(positionMs)} / ${formatPlaybackTime(session.durationMs)}`;
+};
+
+const drawWaveform = (canvas, waveform) => {
+  const context = canvas.getContext("2d");
+  if (!context) return;
+  const scale = window.devicePixelRatio || 1;
+  canvas.width = WAVEFORM_WIDTH * scale;
+  canvas.height = WAVEFORM_HEIGHT * scale;
+  context.scale(scale, scale);
+  context.clearRect(0, 0, WAVEFORM_WIDTH, WAVEFORM_HEIGHT);
+  const count = waveform.peaks.length;
+  if (!count) return;
+  const barWidth = WAVEFORM_WIDTH / count;
+  const middle = WAVEFORM_HEIGHT / 2;
+  const styles = window.getComputedStyle(canvas);
+  [
+    [waveform.peaks, styles.getPropertyValue("--muted") || "#6f645a"],
+    [waveform.rms, styles.getPropertyValue("--accent") || "#da5a34"],
+  ].forEach(([values, color]) => {
+    context.fillStyle = color.trim();
+    values.forEach((value, index) => {
+      const half = Math.max(0.5, Math.min(1, value) * middle);
+      context.fillRect(index * barWidth, middle - half, Math.max(1, barWidth - 0.2), half * 2);
+    });
+  });
+};
+
+const loadWaveform = async (entry) => {
+  if (entry.waveformRequested || !entry.info.name) return;
+  entry.waveformRequested = true;
+  try {
+    const waveform = await invoke("segment_waveform", { name: entry.info.name });
+    drawWaveform(entry.waveformEl, waveform);
+  } catch (error) {
+    console.warn("segment_waveform error", error);
+  }
+};
+
+// Waveforms are fetched once a row scrolls into view, not for the whole history at load.
+const waveformObserver =
+  "IntersectionObserver" in window
+    ? new IntersectionObserver((items) => {
+        items.forEach((item) => {
+          if (!item.isIntersecting) return;
+          waveformObserver.unobserve(item.target);
+          const entry = segmentMap.get(item.target.dataset.name);
+          if (entry) void loadWaveform(entry);
+        });
+      })
+    : null;
+
+const stopPlayback = () => {
+  const session = playback;
+  if (!session) return;
+  playback = null;
+  session.stopped = true;
+  clearInterval(session.timer);
+  session.sources.forEach((source) => {
+    try {
+      source.stop();
+    } catch (_) {
+      // Already ended.
+    }
+  });
+  void session.context.close();
+  renderPlaybackControls(session.entry);
+  invoke("close_segment_playback").catch(() => {});
+};
+
+const scheduleChunk = (session, chunk) => {
+  const channels = Math.max(1, chunk.channels);
+  const frames = Math.floor(chunk.samples.length / channels);
+  if (!frames) return;
+  const buffer = session.context.createBuffer(channels, frames, chunk.sample_rate);
+  for (let channel = 0; channel < channels; channel += 1) {
+    const data = buffer.getChannelData(channel);
+    for (let frame = 0; frame < frames; frame += 1) {
+      data[frame] = chunk.samples[frame * channels + channel];
+    }
+  }
+  const source = session.context.createBufferSource();
+  source.buffer = buffer;
+  source.connect(session.context.destination);
+  const at = Math.max(session.nextAt, session.context.currentTime);
+  if (session.startedAt === null) {
+    session.startedAt = at;
+  }
+  source.start(at);
+  session.nextAt = at + buffer.duration;
+  session.sources.push(source);
+  source.onended = () => {
+    session.sources = session.sources.filter((item) => item !== source);
+    if (session.done && !session.sources.length && playback === session) {
+      stopPlayback();
+    }
+  };
+};
+
+const pumpPlayback = async (session) => {
+  while (!session.stopped && !session.done) {
+    if (session.nextAt - session.context.currentTime > PLAYBACK_LEAD_SECONDS) {
+      await sleep(PLAYBACK_TICK_MS);
+      continue;
+    }
+    let chunk;
+    try {
+      chunk = await invoke("read_segment_playback", { maxMs: PLAYBACK_CHUNK_MS });
+    } catch (error) {
+      console.warn("read_segment_playback error", error);
+      if (playback === session) stopPlayback();
+      return;
+    }
+    if (session.stopped) return;
+    session.done = chunk.done;
+    scheduleChunk(session, chunk);
+  }
+  if (session.done && !session.sources.length && playback === session) {
+    stopPlayback();
+  }
+};
+
+const startPlayback = async (entry, positionMs = 0) => {
+  stopPlayback();
+  let state;
+  try {
+    state = await invoke("open_segment_playback", { name: entry.info.name });
+    if (positionMs > 0) {
+      state = await invoke("seek_segment_playback", { positionMs: Math.round(positionMs) });
+    }
+  } catch (error) {
+    console.warn("open_segment_playback error", error);
+    return;
+  }
+  const context = new AudioContext();
+  const session = {
+    entry,
+    context,
+    durationMs: state.duration_ms,
+    originMs: state.position_ms,
+    startedAt: null,
+    nextAt: context.currentTime,
+    sources: [],
+    stopped: false,
+    done: false,
+    seeking: false,
+    timer: 0,
+  };
+  playback = session;
+  session.timer = setInterval(() => renderPlaybackControls(entry), PLAYBACK_TICK_MS);
+  renderPlaybackControls(entry);
+  void pumpPlayback(session);
+};
+
+// Position on the meeting timeline, counted from when capture started.
+const renderRowOffset = (entry) => {
+  const offsetMs = entry.info.start_offset_ms;
+  const known = typeof offsetMs === "number";
+  entry.offsetEl.hidden = !known;
+  if (!known) return;
+  entry.offsetEl.textContent = `+${formatPlaybackTime(offsetMs)}`;
+  entry.offsetEl.title =
+    typeof entry.info.end_offset_ms === "number"
+      ? `Meeting time ${formatPlaybackTime(offsetMs)} – ${formatPlaybackTime(entry.info.end_offset_ms)}`
+      : "Meeting time";
+};
+
 const renderRow = (entry) => {
+  renderRowOffset(entry);
   renderRowTranscript(entry);
   renderRowTranslation(entry);
   renderRowQuestion(entry);
+  renderPlaybackControls(entry);
 };
 
 const clearQueuedRowTranslations = () => {
@@ -461,9 +746,53 @@ const createRow = (info) => {
   const left = document.createElement("div");
   left.className = "cell transcript-cell";
 
+  const playLine = document.createElement("div");
+  playLine.className = "meta-line segment-playback";
+
+  const offsetEl = document.createElement("span");
+  offsetEl.className = "segment-offset";
+
+  const playBtn = document.createElement("button");
+  playBtn.type = "button";
+  playBtn.className = "segment-play";
+
+  const waveformEl = document.createElement("canvas");
+  waveformEl.className = "segment-waveform";
+  waveformEl.title = "Play from here";
+
+  const seekEl = document.createElement("input");
+  seekEl.type = "range";
+  seekEl.className = "segment-seek";
+  seekEl.min = "0";
+  seekEl.step = "100";
+
+  const playTimeEl = document.createElement("span");
+  playTimeEl.className = "segment-playtime";
+
+  const retranscribeBtn = document.createElement("button");
+  retranscribeBtn.type = "button";
+  retranscribeBtn.className = "segment-retranscribe";
+  retranscribeBtn.textContent = "↻";
+  retranscribeBtn.title = "Re-transcribe";
+
+  playLine.appendChild(offsetEl);
+  playLine.appendChild(playBtn);
+  playLine.appendChild(waveformEl);
+  playLine.appendChild(seekEl);
+  playLine.appendChild(playTimeEl);
+  playLine.appendChild(retranscribeBtn);
+
+  const deleteBtn = document.createElement("button");
+  deleteBtn.type = "button";
+  deleteBtn.className = "segment-delete";
+  deleteBtn.textContent = "✕";
+  deleteBtn.title = "Delete segment";
+  playLine.appendChild(deleteBtn);
+
   const transcriptEl = document.createElement("div");
   transcriptEl.className = "entry-text segment-transcript";
 
+  left.appendChild(playLine);
   left.appendChild(transcriptEl);
 
   const dividerMain = document.createElement("div");
@@ -499,14 +828,51 @@ const createRow = (info) => {
     transcriptEl,
     translationEl,
     questionEl,
+    playBtn,
+    waveformEl,
+    waveformRequested: false,
+    seekEl,
+    playTimeEl,
+    offsetEl,
     info: {
       name: info.name,
       transcript: info.transcript,
       translation: info.translation,
+      track: info.track,
+      duration_ms: info.duration_ms,
+      start_offset_ms: info.start_offset_ms,
+      end_offset_ms: info.end_offset_ms,
+      speaker_switches_ms: info.speaker_switches_ms,
+      draft: info.draft,
       order: parseOrder(info),
     },
   };
 
+  playBtn.addEventListener("click", () => {
+    if (playback?.entry === entry) {
+      stopPlayback();
+    } else {
+      void startPlayback(entry);
+    }
+  });
+  waveformEl.addEventListener("click", (event) => {
+    const bounds = waveformEl.getBoundingClientRect();
+    const ratio = bounds.width ? (event.clientX - bounds.left) / bounds.width : 0;
+    void startPlayback(entry, clamp(ratio, 0, 1) * (entry.info.duration_ms || 0));
+  });
+  seekEl.addEventListener("input", () => {
+    if (playback?.entry === entry) playback.seeking = true;
+  });
+  seekEl.addEventListener("change", () => {
+    void startPlayback(entry, Number(seekEl.value) || 0);
+  });
+  retranscribeBtn.addEventListener("click", () => {
+    void retranscribeRow(entry, retranscribeBtn);
+  });
+  deleteBtn.addEventListener("click", () => {
+    void deleteRow(entry);
+  });
+
   row.addEventListener("mouseenter", () => {
     row.classList.add("hover-linked");
   });
@@ -515,6 +881,11 @@ const createRow = (info) => {
   });
 
   renderRow(entry);
+  if (waveformObserver) {
+    waveformObserver.observe(row);
+  } else {
+    void loadWaveform(entry);
+  }
   return entry;
 };
 
@@ -576,6 +947,55 @@ const addSegment = (info, { scrollToBottom = true } = {}) => {
   updateStatus();
 };
 
+// Uses the current ASR settings; the old translation stays until the row is translated again.
+const retranscribeRow = async (entry, button) => {
+  const name = entry?.info?.name;
+  if (!name || button.disabled) return;
+  button.disabled = true;
+  button.title = "Re-transcribing...";
+  try {
+    const info = await invoke("retranscribe_segment", { name });
+    updateSegment(info);
+    button.title = "Re-transcribe";
+  } catch (error) {
+    console.warn("retranscribe_segment error", error);
+    button.title = `Re-transcribe failed: ${error}`;
+  } finally {
+    button.disabled = false;
+  }
+};
+
+const deleteRow = async (entry) => {
+  const name = entry?.info?.name;
+  if (!name || !window.confirm("Delete this segment and its audio?")) return;
+  try {
+    await invoke("delete_segment", { name });
+  } catch (error) {
+    console.warn("delete_segment error", error);
+  }
+};
+
+// The row goes when the backend confirms with "segment_deleted".
+const removeSegmentRow = (name) => {
+  const entry = segmentMap.get(name);
+  if (!entry) return;
+  if (playback?.entry === entry) {
+    stopPlayback();
+  }
+  if (waveformObserver) {
+    waveformObserver.unobserve(entry.row);
+  }
+  segmentMap.delete(name);
+  rowTranslationRequested.delete(name);
+  translationInvokeQueued.delete(name);
+  const queued = translationInvokeQueue.indexOf(name);
+  if (queued >= 0) {
+    translationInvokeQueue.splice(queued, 1);
+  }
+  entry.row.remove();
+  updateStatus();
+};
+
 const updateSegment = (info) => {
   if (!info || !info.name) return;
   const entry = segmentMap.get(info.name);
@@ -612,13 +1032,18 @@ const updateBoardUi = () => {
   }
 };
 
-const clearSegmentsUi = () => {
+const removeSegmentRows = () => {
+  stopPlayback();
   clearQueuedRowTranslations();
   segmentMap.clear();
   rowTranslationRequested.clear();
   if (listEl) {
     listEl.querySelectorAll(".segment-row").forEach((node) => node.remove());
   }
+};
+
+const clearSegmentsUi = () => {
+  removeSegmentRows();
   resetLiveState();
   updateStatus();
 };
@@ -652,10 +1077,8 @@ const loadSegments = async () => {
   }
 };
 
+// The caption text itself arrives as `live_draft_update`.
 const applyWindowTranscript = (payload) => {
-  const cleaned = normalizeText(payload?.text || "");
-  setLivePartial(cleaned);
-
   if (liveMetaEl) {
     const latency = Number.isFinite(payload?.elapsed_ms)
       ? `${(payload.elapsed_ms / 1000).toFixed(1)}s`
@@ -672,6 +1095,13 @@ const applyWindowTranscript = (payload) => {
   }
 };
 
+// Realtime captions also arrive as `live_draft_update`; this only labels the stream.
+const applyRealtimeTranscript = (payload) => {
+  if (liveMetaEl) {
+    liveMetaEl.textContent = payload?.is_final ? "Realtime | final" : "Realtime";
+  }
+};
+
 const handleLiveTranslationStart = (payload) => {
   const order = Number(payload?.order);
   if (!Number.isFinite(order)) return;
@@ -805,6 +1235,285 @@ autoScrollToggle?.addEventListener("change", () => {
   }
 });
 
+// What the banner currently shows, for events that take it down again when their condition ends.
+let deviceBannerTag = null;
+
+// Tags the banner offering a missing whisper model, whose button downloads it instead.
+const MODEL_DOWNLOAD_TAG = "download:";
+
+// The button restarts capture, which also starts it when nothing is running.
+const showDeviceBanner = (text, canRestart, actionLabel = "Restart capture") => {
+  if (!deviceBannerEl) return;
+  deviceBannerTextEl.textContent = text;
+  deviceRestartBtn.textContent = actionLabel;
+  deviceRestartBtn.hidden = !canRestart;
+  deviceBannerEl.hidden = false;
+  deviceBannerTag = null;
+};
+
+const hideDeviceBanner = () => {
+  if (deviceBannerEl) {
+    deviceBannerEl.hidden = true;
+  }
+};
+
+const showModelDownloadBanner = (text, model) => {
+  showDeviceBanner(text, true, `Download ${model}`);
+  deviceBannerTag = `${MODEL_DOWNLOAD_TAG}${model}`;
+};
+
+deviceRestartBtn?.addEventListener("click", async () => {
+  deviceRestartBtn.disabled = true;
+  const model = deviceBannerTag?.startsWith(MODEL_DOWNLOAD_TAG)
+    ? deviceBannerTag.slice(MODEL_DOWNLOAD_TAG.length)
+    : null;
+  try {
+    if (model) {
+      await invoke("download_whisper_model", { name: model });
+    } else {
+      await invoke("restart_loopback_capture");
+    }
+    hideDeviceBanner();
+  } catch (error) {
+    if (model) {
+      showModelDownloadBanner(`Downloading whisper model ${model} failed: ${error}`, model);
+    } else {
+      showDeviceBanner(`Restart failed: ${error}`, true);
+    }
+  } finally {
+    deviceRestartBtn.disabled = false;
+  }
+});
+
+deviceDismissBtn?.addEventListener("click", hideDeviceBanner);
+
+listen("audio_device_changed", (event) => {
+  const change = event?.payload;
+  if (!change?.restart_suggested) return;
+  showDeviceBanner("The capture device was disconnected.", true);
+});
+
+// Capture on the default device follows it without a restart.
+listen("capture_device_changed", (event) => {
+  const change = event?.payload;
+  const name = change?.name || change?.device_id || "the new default device";
+  const verb = change?.reason === "device_lost" ? "Device lost; capture moved" : "Capture moved";
+  showDeviceBanner(`${verb} to ${name}.`, false);
+});
+
+listen("capture_failed", (event) => {
+  const error = event?.payload?.error || "unknown error";
+  showDeviceBanner(`Capture stopped: ${error}`, true);
+});
+
+listen("worker_restarted", (event) => {
+  const restart = event?.payload;
+  if (!restart) return;
+  const cause = restart.reason === "stuck" ? "was stuck" : "crashed";
+  const requeued = restart.requeued?.length ? `, retrying ${restart.requeued.join(", ")}` : "";
+  showDeviceBanner(`The ${restart.worker} worker ${cause} and was restarted${requeued}.`, false);
+});
+
+listen("transcription_backlog", (event) => {
+  const backlog = event?.payload;
+  if (!backlog) return;
+  const text = backlog.behind
+    ? `Transcription is ${backlog.depth} segments behind; live preview paused until it catches up.`
+    : "Transcription caught up; live preview resumed.";
+  showDeviceBanner(text, false);
+});
+
+listen("asr_backend_restarted", (event) => {
+  const restart = event?.payload;
+  if (!restart) return;
+  const cause = restart.reason === "unresponsive" ? "stopped responding" : "exited";
+  const next = restart.url ? "was restarted" : `could not be restarted: ${restart.error || "unknown error"}`;
+  showDeviceBanner(`The ${restart.backend} ${cause} and ${next}.`, false);
+});
+
+listen("asr_offline_fallback", (event) => {
+  const fallback = event?.payload;
+  if (!fallback) return;
+  if (fallback.downloaded === false) {
+    showModelDownloadBanner(
+      `whisper-server is unavailable and the offline ${fallback.model} model is not downloaded, so segments are not transcribed.`,
+      fallback.model,
+    );
+    return;
+  }
+  showDeviceBanner(
+    `whisper-server is unavailable; transcribing offline with the ${fallback.model} model (lower accuracy).`,
+    false,
+  );
+});
+
+listen("whisper_benchmark_finished", (event) => {
+  const report = event?.payload;
+  if (!report) return;
+  const text = report.device
+    ? `Whisper benchmark: using the ${report.device.toUpperCase()} build with -t ${report.threads}.`
+    : "Whisper benchmark: no whisper-server build finished the sample.";
+  showDeviceBanner(text, false);
+});
+
+listen("whisper_model_download_progress", (event) => {
+  const progress = event?.payload;
+  if (!progress) return;
+  const mb = (bytes) => `${(bytes / 1048576).toFixed(0)} MB`;
+  const done = progress.total_bytes
+    ? `${Math.floor((progress.downloaded_bytes / progress.total_bytes) * 100)}%`
+    : mb(progress.downloaded_bytes);
+  showDeviceBanner(`Downloading whisper model ${progress.name}: ${done}`, false);
+});
+
+listen("whisper_model_downloaded", (event) => {
+  const model = event?.payload;
+  if (!model) return;
+  showDeviceBanner(`Whisper model ${model.name} downloaded.`, false);
+});
+
+listen("session_retranscribe_progress", (event) => {
+  const progress = event?.payload;
+  if (!progress) return;
+  showDeviceBanner(`Re-transcribing segments: ${progress.done}/${progress.total}`, false);
+});
+
+listen("session_retranscribed", (event) => {
+  const report = event?.payload;
+  if (!report) return;
+  const failed = report.failed ? `, ${report.failed} failed` : "";
+  showDeviceBanner(`Re-transcribed ${report.transcribed} segments${failed}.`, false);
+});
+
+listen("scheduled_meeting_started", (event) => {
+  const meeting = event?.payload;
+  if (!meeting) return;
+  const next = meeting.auto_started ? "capture started" : "press Start to record";
+  showDeviceBanner(
+    `Scheduled meeting "${meeting.title}" has begun; ${next}.`,
+    !meeting.auto_started,
+    "Start capture",
+  );
+});
+
+listen("meeting_app_detected", (event) => {
+  const detected = event?.payload;
+  if (!detected || detected.capturing) return;
+  if (detected.auto_started) {
+    showDeviceBanner(`${detected.app} call detected; capture started.`, false);
+    return;
+  }
+  showDeviceBanner(`${detected.app} call detected. Record it?`, true, "Start capture");
+  deviceBannerTag = `meeting:${detected.app}`;
+});
+
+const hideTaggedBanner = (tag) => {
+  if (deviceBannerTag === tag) {
+    hideDeviceBanner();
+    deviceBannerTag = null;
+  }
+};
+
+listen("meeting_app_ended", (event) => {
+  hideTaggedBanner(`meeting:${event?.payload?.app}`);
+});
+
+listen("audio_alert", (event) => {
+  const alert = event?.payload;
+  if (!alert) return;
+  const tag = `alert:${alert.kind}`;
+  if (!alert.active) {
+    hideTaggedBanner(tag);
+    return;
+  }
+  if (alert.kind === "clipping") {
+    showDeviceBanner("The input is clipping; turn the source or microphone gain down.", false);
+  } else {
+    const minutes = Math.floor((alert.seconds || 0) / 60);
+    showDeviceBanner(
+      `No sound for ${minutes} min. Is the source muted or the wrong device selected?`,
+      true,
+    );
+  }
+  deviceBannerTag = tag;
+});
+
+const levelRows = new Map();
+
+const levelPercent = (db) =>
+  Math.max(0, Math.min(100, ((db - LEVEL_MIN_DB) / -LEVEL_MIN_DB) * 100));
+
+const renderLevel = (level) => {
+  let row = levelRows.get(level.source);
+  if (!row) {
+    const el = document.createElement("div");
+    el.className = "level-row";
+    const label = document.createElement("span");
+    label.textContent = LEVEL_LABELS[level.source] || level.source;
+    const track = document.createElement("div");
+    track.className = "level-track";
+    const fill = document.createElement("div");
+    fill.className = "level-fill";
+    const peak = document.createElement("div");
+    peak.className = "level-peak";
+    track.append(fill, peak);
+    el.append(label, track);
+    levelMeterEl.append(el);
+    row = { fill, peak, track };
+    levelRows.set(level.source, row);
+  }
+  row.fill.style.width = `${levelPercent(level.rms_db)}%`;
+  row.peak.style.left = `${levelPercent(level.peak_db)}%`;
+  row.track.title = `RMS ${level.rms_db.toFixed(1)} dBFS, peak ${level.peak_db.toFixed(1)} dBFS`;
+};
+
+const resetLevelMeter = () => {
+  if (!levelMeterEl) return;
+  levelRows.clear();
+  levelMeterEl.replaceChildren();
+  levelMeterEl.hidden = true;
+};
+
+// Sent about ten times a second while capturing, only to this window.
+listen("audio_level", (event) => {
+  const levels = event?.payload?.levels;
+  if (!levelMeterEl || !Array.isArray(levels)) return;
+  levelMeterEl.hidden = false;
+  levels.forEach(renderLevel);
+});
+
+// Nothing is recorded while paused; the pipeline keeps finishing earlier segments.
+listen("capture_paused_changed", (event) => {
+  const paused = !!event?.payload;
+  if (pausedIndicatorEl) {
+    pausedIndicatorEl.hidden = !paused;
+  }
+  if (paused) {
+    resetLevelMeter();
+  }
+});
+
+listen("session_started", (event) => {
+  hideDeviceBanner();
+  resetLevelMeter();
+  if (pausedIndicatorEl) {
+    pausedIndicatorEl.hidden = true;
+  }
+  if (recIndicatorEl) {
+    recIndicatorEl.hidden = !event?.payload?.consent?.indicator;
+  }
+});
+
+listen("session_ended", () => {
+  resetLevelMeter();
+  if (pausedIndicatorEl) {
+    pausedIndicatorEl.hidden = true;
+  }
+  if (recIndicatorEl) {
+    recIndicatorEl.hidden = true;
+  }
+});
+
 listen("segment_created", (event) => {
   if (event?.payload) {
     addSegment(event.payload, { scrollToBottom: true });
@@ -830,16 +1539,45 @@ listen("segment_translated", (event) => {
   }
 });
 
+// With `translate.streamSegments` each event carries the translation so far, until
+// `segment_translated` replaces it with the final text. `reset` comes before a fallback provider
+// starts over.
+listen("segment_translation_chunk", (event) => {
+  const payload = event?.payload;
+  if (!payload?.name || !translateEnabled) return;
+  const entry = segmentMap.get(payload.name);
+  if (!entry || hasTranslationText(entry.info.translation)) return;
+  entry.translationEl.textContent = payload.text || "";
+  entry.translationEl.dataset.state = payload.reset ? "pending" : "ready";
+});
+
 listen("segment_speakered", (event) => {
   if (event?.payload) {
     updateSegment(event.payload);
   }
 });
 
+listen("segment_deleted", (event) => {
+  if (event?.payload?.name) {
+    removeSegmentRow(event.payload.name);
+  }
+});
+
 listen("segment_list_cleared", () => {
   clearSegmentsUi();
 });
 
+// Archiving or restoring a session changes the history anywhere in the list, not just at the end.
+listen("session_removed", () => {
+  removeSegmentRows();
+  void loadSegments();
+});
+
+listen("session_restored", () => {
+  removeSegmentRows();
+  void loadSegments();
+});
+
 listen("segment_translation_canceled", () => {
   clearQueuedRowTranslations();
   rowTranslationRequested.clear();
@@ -851,6 +1589,18 @@ listen("window_transcribed", (event) => {
   }
 });
 
+listen("realtime_transcript", (event) => {
+  if (event?.payload) {
+    applyRealtimeTranscript(event.payload);
+  }
+});
+
+listen("live_draft_update", (event) => {
+  if (event?.payload) {
+    applyLiveDraft(event.payload);
+  }
+});
+
 listen("live_translation_start", (event) => {
   if (event?.payload) {
     handleLiveTranslationStart(event.payload);